@@ -27,6 +27,16 @@ pub struct AgentRunConfig {
     pub session_id: String,
     /// User ID for memory/context
     pub user_id: String,
+    /// Persona the turn runs as; scopes the memory tools
+    pub persona_id: String,
+    /// Channel whose tool policy applies (e.g. `web`)
+    pub channel: String,
+    /// Session tool profile overriding the channel's (e.g. `readonly`)
+    pub tool_profile: Option<String>,
+    /// Suggestion for a mistyped slash command (see
+    /// [`crate::skills::SlashCommandAction::Suggest`]); when set, it is the
+    /// reply and the agent does not run
+    pub slash_suggestion: Option<String>,
     /// Optional channel to emit tool events to a WebSocket client
     /// Pass `None` for headless/non-WebSocket callers
     pub notify: Option<tokio::sync::mpsc::Sender<AgentNotifyEvent>>,
@@ -35,6 +45,15 @@ pub struct AgentRunConfig {
     pub synapse_override: Option<Arc<synapse_client::SynapseClient>>,
 }
 
+impl AgentRunConfig {
+    /// Parsed session tool profile, if one is set and known
+    fn session_profile(&self) -> Option<crate::tools::SessionToolProfile> {
+        self.tool_profile
+            .as_deref()
+            .and_then(crate::tools::SessionToolProfile::parse)
+    }
+}
+
 /// In-progress tool call being assembled from streaming events
 #[derive(Default, Clone)]
 struct PendingToolCall {
@@ -43,10 +62,12 @@ struct PendingToolCall {
     arguments: String,
 }
 
-/// Tool lifecycle events emitted to WebSocket clients during agent execution
+/// Streaming and tool lifecycle events emitted to WebSocket clients during agent execution
 /// Kept in this module to avoid circular dependency with `api::websocket`
 #[derive(Debug, Clone)]
 pub enum AgentNotifyEvent {
+    /// Incremental assistant text as it streams from Synapse
+    Delta { text: String },
     /// Tool invocation started
     ToolStart { tool_id: String, name: String },
     /// Tool invocation completed
@@ -58,6 +79,11 @@ pub enum AgentNotifyEvent {
         output: String,
         is_error: bool,
     },
+    /// Token usage for the whole turn, sent once it finishes
+    Usage {
+        input_tokens: u32,
+        output_tokens: u32,
+    },
 }
 
 /// Extract a short display label from tool arguments JSON
@@ -86,6 +112,17 @@ fn summarize_invocation(name: &str, args: &str) -> String {
 ///
 /// Returns an error if the LLM call fails or no Synapse client is configured.
 pub async fn run_agent_turn(state: &ApiState, config: AgentRunConfig) -> crate::Result<String> {
+    // Unknown slash command close to an existing one: suggest instead of guessing
+    if let Some(suggestion) = config.slash_suggestion {
+        state
+            .session_repo
+            .add_message(&config.session_id, MessageRole::User, &config.prompt)?;
+        state
+            .session_repo
+            .add_message(&config.session_id, MessageRole::Assistant, &suggestion)?;
+        return Ok(suggestion);
+    }
+
     let Some(synapse) = config
         .synapse_override
         .clone()
@@ -96,8 +133,21 @@ pub async fn run_agent_turn(state: &ApiState, config: AgentRunConfig) -> crate::
         ));
     };
 
+    // Daily usage cap: reply with the notice instead of running the agent
+    if let crate::usage::UsageDecision::LimitReached(notice) =
+        state.usage_cap.check(&config.user_id)
+    {
+        state
+            .session_repo
+            .add_message(&config.session_id, MessageRole::User, &config.prompt)?;
+        state
+            .session_repo
+            .add_message(&config.session_id, MessageRole::Assistant, &notice)?;
+        return Ok(notice);
+    }
+
     let memory_tools = Arc::new(crate::tools::BuiltinMemoryTools::new(
-        state.memory_repo.clone(),
+        state.memory_repo.for_persona(&config.persona_id),
         state.embedder.clone(),
         config.user_id.clone(),
     ));
@@ -110,7 +160,9 @@ pub async fn run_agent_turn(state: &ApiState, config: AgentRunConfig) -> crate::
             state.plugin_manager.clone(),
         )
         .with_memory_tools(Arc::clone(&memory_tools))
-        .with_exec_tool(Arc::clone(&exec_tool));
+        .with_exec_tool(Arc::clone(&exec_tool))
+        .with_policy(Arc::clone(&state.tool_policy), config.channel.clone())
+        .with_session_profile(config.session_profile());
         executor.list_tools().await.ok()
     };
 
@@ -142,10 +194,15 @@ pub async fn run_agent_turn(state: &ApiState, config: AgentRunConfig) -> crate::
             tool_choice: None,
         };
 
-        let mut stream = synapse
-            .chat_completion_stream(&request)
-            .await
-            .map_err(|e| crate::Error::Agent(e.to_string()))?;
+        // Fail fast while Synapse is down; errors mid-stream count as failures
+        let permit = state.synapse_breaker.acquire()?;
+        let mut stream = match synapse.chat_completion_stream(&request).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                permit.failure();
+                return Err(crate::Error::Agent(e.to_string()));
+            }
+        };
 
         let mut turn_text = String::new();
         let mut pending_tool_calls: Vec<PendingToolCall> = Vec::new();
@@ -155,6 +212,9 @@ pub async fn run_agent_turn(state: &ApiState, config: AgentRunConfig) -> crate::
             match event {
                 Ok(ChatEvent::ContentDelta(text)) => {
                     turn_text.push_str(&text);
+                    if let Some(ref n) = config.notify {
+                        let _ = n.send(AgentNotifyEvent::Delta { text }).await;
+                    }
                 }
                 Ok(ChatEvent::ToolCallStart { index, id, name }) => {
                     let idx = index as usize;
@@ -183,13 +243,16 @@ pub async fn run_agent_turn(state: &ApiState, config: AgentRunConfig) -> crate::
                     break;
                 }
                 Ok(ChatEvent::Error(e)) => {
+                    permit.failure();
                     return Err(crate::Error::Agent(e));
                 }
                 Err(e) => {
+                    permit.failure();
                     return Err(crate::Error::Agent(e.to_string()));
                 }
             }
         }
+        permit.success();
 
         full_response.push_str(&turn_text);
 
@@ -230,7 +293,12 @@ pub async fn run_agent_turn(state: &ApiState, config: AgentRunConfig) -> crate::
                     state.plugin_manager.clone(),
                 )
                 .with_memory_tools(Arc::clone(&memory_tools))
-                .with_exec_tool(Arc::clone(&exec_tool)),
+                .with_exec_tool(Arc::clone(&exec_tool))
+                .with_fetch_cache(Arc::clone(&state.fetch_cache))
+                .with_output_config(state.tool_output.clone())
+                .with_policy(Arc::clone(&state.tool_policy), config.channel.clone())
+                .with_session_profile(config.session_profile())
+                .with_audit_context(&config.session_id, &config.user_id),
             );
 
             // Headless: skip interactive tools, run the rest
@@ -352,6 +420,23 @@ pub async fn run_agent_turn(state: &ApiState, config: AgentRunConfig) -> crate::
         });
     }
 
+    state.usage_cap.record(
+        &config.user_id,
+        crate::usage::turn_tokens(
+            u64::from(total_input_tokens) + u64::from(total_output_tokens),
+            &config.prompt,
+            &full_response,
+        ),
+    );
+    if let Some(ref n) = config.notify {
+        let _ = n
+            .send(AgentNotifyEvent::Usage {
+                input_tokens: total_input_tokens,
+                output_tokens: total_output_tokens,
+            })
+            .await;
+    }
+
     // Store user message and assistant response
     state
         .session_repo
@@ -377,6 +462,10 @@ mod tests {
             max_iterations: 10,
             session_id: "sess_1".to_string(),
             user_id: "user_1".to_string(),
+            persona_id: "orin".to_string(),
+            channel: "web".to_string(),
+            tool_profile: None,
+            slash_suggestion: None,
             notify: None,
             synapse_override: None,
         };
@@ -11,8 +11,12 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 
-use super::{ApiState, auth::require_api_key};
-use crate::db::{SessionRepo, TelegramGroupConfig, UserRepo};
+use super::ApiState;
+use super::auth::{RequireScope, require_api_key, require_scope};
+use crate::RelayStatus;
+use crate::db::{
+    SessionRepo, SyncStateRepo, TelegramGroupConfig, UserApiKey, UserApiKeyRepo, UserRepo,
+};
 
 // --- Request/Response types ---
 
@@ -42,9 +46,17 @@ pub struct SessionResponse {
     pub channel: String,
     pub channel_id: String,
     pub persona_id: String,
+    /// Tool profile overriding the channel's (`null` = channel default)
+    pub tool_profile: Option<String>,
     pub created_at: String,
 }
 
+#[derive(Deserialize)]
+pub struct SetToolProfileRequest {
+    /// Profile name (`readonly`, `minimal`, `messaging`, `full`, ...); `null` clears
+    pub profile: Option<String>,
+}
+
 #[derive(Serialize)]
 pub struct MessageResponse {
     pub id: String,
@@ -53,6 +65,88 @@ pub struct MessageResponse {
     pub created_at: String,
 }
 
+#[derive(Serialize)]
+pub struct MaintenanceResponse {
+    pub enabled: bool,
+    /// Notice sent to users (`null` = silent)
+    pub message: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SetMaintenanceRequest {
+    pub enabled: bool,
+    /// Replaces the notice when present; an empty string means stay silent
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct UsageResponse {
+    pub user_id: String,
+    /// Tokens used today (UTC)
+    pub tokens_today: u64,
+    /// Whether a per-user override replaces the global caps
+    pub has_override: bool,
+    /// Override daily token cap (`null` with an override = unlimited)
+    pub daily_tokens: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct SetUsageLimitRequest {
+    /// Daily token cap for this user (`null` = unlimited)
+    #[serde(default)]
+    pub daily_tokens: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateApiKeyRequest {
+    /// Label to tell keys apart
+    pub name: String,
+    /// Granted scopes (default: `chat`); include `admin` for admin endpoints
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct ApiKeyResponse {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+impl From<UserApiKey> for ApiKeyResponse {
+    fn from(key: UserApiKey) -> Self {
+        Self {
+            id: key.id,
+            user_id: key.user_id,
+            name: key.name,
+            scopes: key.scopes,
+            created_at: key.created_at.to_rfc3339(),
+            last_used_at: key.last_used_at.map(|t| t.to_rfc3339()),
+            revoked_at: key.revoked_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CreateApiKeyResponse {
+    #[serde(flatten)]
+    pub key: ApiKeyResponse,
+    /// The raw key; shown only once
+    pub api_key: String,
+}
+
+#[derive(Serialize)]
+pub struct ReloadPersonaResponse {
+    pub id: String,
+    pub name: String,
+    pub knowledge_chunks: usize,
+}
+
 #[derive(Serialize)]
 pub struct ErrorResponse {
     pub error: ErrorDetail,
@@ -62,6 +156,8 @@ pub struct ErrorResponse {
 pub struct ErrorDetail {
     pub code: String,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 fn error_response(code: &str, message: &str) -> Json<ErrorResponse> {
@@ -69,6 +165,7 @@ fn error_response(code: &str, message: &str) -> Json<ErrorResponse> {
         error: ErrorDetail {
             code: code.to_string(),
             message: message.to_string(),
+            request_id: crate::request_id::current(),
         },
     })
 }
@@ -240,6 +337,7 @@ async fn list_sessions(
                 channel: s.channel,
                 channel_id: s.channel_id,
                 persona_id: s.persona_id,
+                tool_profile: s.tool_profile,
                 created_at: s.created_at.to_rfc3339(),
             })
             .collect(),
@@ -273,6 +371,96 @@ async fn get_session_messages(
     ))
 }
 
+/// Set or clear a session's tool profile override
+///
+/// The override takes precedence over the channel and persona tool profiles.
+async fn set_session_tool_profile(
+    State(state): State<Arc<ApiState>>,
+    Path(session_id): Path<String>,
+    Json(req): Json<SetToolProfileRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let profile = match req.profile.as_deref() {
+        Some(name) => Some(
+            crate::tools::SessionToolProfile::parse(name).ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    error_response("invalid_profile", &format!("Unknown tool profile: {name}")),
+                )
+            })?,
+        ),
+        None => None,
+    };
+
+    let updated = SessionRepo::new(state.db.clone())
+        .set_tool_profile(
+            &session_id,
+            profile.as_ref().map(crate::tools::SessionToolProfile::name),
+        )
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_response("db_error", &e.to_string()),
+            )
+        })?;
+
+    if updated {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            error_response("not_found", "Session not found"),
+        ))
+    }
+}
+
+// --- API key handlers ---
+
+/// Mint an API key for a user
+///
+/// The raw key is only returned here; the gateway stores a hash.
+async fn create_api_key(
+    State(state): State<Arc<ApiState>>,
+    Path(user_id): Path<String>,
+    Json(req): Json<CreateApiKeyRequest>,
+) -> crate::Result<(StatusCode, Json<CreateApiKeyResponse>)> {
+    if UserRepo::new(state.db.clone()).find(&user_id)?.is_none() {
+        return Err(crate::Error::NotFound(format!("user {user_id}")));
+    }
+
+    let (key, api_key) =
+        UserApiKeyRepo::new(state.db.clone()).create(&user_id, &req.name, &req.scopes)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateApiKeyResponse {
+            key: key.into(),
+            api_key,
+        }),
+    ))
+}
+
+/// List a user's API keys (without secrets)
+async fn list_api_keys(
+    State(state): State<Arc<ApiState>>,
+    Path(user_id): Path<String>,
+) -> crate::Result<Json<Vec<ApiKeyResponse>>> {
+    let keys = UserApiKeyRepo::new(state.db.clone()).list(&user_id)?;
+
+    Ok(Json(keys.into_iter().map(Into::into).collect()))
+}
+
+/// Revoke an API key
+async fn revoke_api_key(
+    State(state): State<Arc<ApiState>>,
+    Path(key_id): Path<String>,
+) -> crate::Result<StatusCode> {
+    if UserApiKeyRepo::new(state.db.clone()).revoke(&key_id)? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(crate::Error::NotFound(format!("API key {key_id}")))
+    }
+}
+
 // --- Telegram group config handlers ---
 
 /// List Telegram group configurations
@@ -330,6 +518,217 @@ async fn delete_telegram_group(
     }
 }
 
+// --- Maintenance mode handlers ---
+
+fn maintenance_status(state: &ApiState) -> MaintenanceResponse {
+    MaintenanceResponse {
+        enabled: state.maintenance.is_enabled(),
+        message: state.maintenance.message(),
+    }
+}
+
+/// Get maintenance mode status
+async fn get_maintenance(State(state): State<Arc<ApiState>>) -> Json<MaintenanceResponse> {
+    Json(maintenance_status(&state))
+}
+
+/// Toggle maintenance mode
+async fn set_maintenance(
+    State(state): State<Arc<ApiState>>,
+    Json(req): Json<SetMaintenanceRequest>,
+) -> Json<MaintenanceResponse> {
+    if let Some(message) = req.message {
+        state.maintenance.set_message(Some(message));
+    }
+    state.maintenance.set_enabled(req.enabled);
+
+    Json(maintenance_status(&state))
+}
+
+// --- Relay handlers ---
+
+/// Get the status of each configured relay
+///
+/// Empty when relays are disabled. Statuses follow the relay monitor, so a
+/// relay that died reports `connected: false` until it is restarted.
+async fn get_relay_status(State(state): State<Arc<ApiState>>) -> Json<Vec<RelayStatus>> {
+    Json(
+        state
+            .relay_status
+            .as_ref()
+            .map(|status| status.borrow().clone())
+            .unwrap_or_default(),
+    )
+}
+
+// --- Persona handlers ---
+
+/// Reload the active persona without a restart
+///
+/// Re-fetches the persona through the usual load priority (env dir, Manifold,
+/// cache, embedded), re-resolves its knowledge packs, and swaps both in. New
+/// turns pick up the change; turns already running finish with the old one.
+async fn reload_persona(
+    State(state): State<Arc<ApiState>>,
+) -> crate::Result<Json<ReloadPersonaResponse>> {
+    let persona_id = state.active_persona.read().await.id.clone();
+
+    let persona =
+        tokio::task::spawn_blocking(move || crate::Config::load_persona_with_priority(&persona_id))
+            .await
+            .map_err(|e| crate::Error::Config(format!("persona reload task failed: {e}")))??;
+
+    let knowledge = crate::knowledge::load_persona_knowledge(
+        &persona,
+        &state.manifold_url,
+        state.knowledge_cache_dir.clone(),
+    )
+    .await;
+    let knowledge_chunks = knowledge.len();
+
+    {
+        let mut active = state.active_persona.write().await;
+        let mut persona_knowledge = state.persona_knowledge.write().await;
+        active.id = persona.id().to_string();
+        active.system_prompt = persona.system_prompt().map(String::from);
+        *persona_knowledge = Arc::new(knowledge);
+    }
+
+    tracing::info!(
+        persona_id = persona.id(),
+        knowledge_chunks,
+        "persona reloaded"
+    );
+    Ok(Json(ReloadPersonaResponse {
+        id: persona.id().to_string(),
+        name: persona.name().to_string(),
+        knowledge_chunks,
+    }))
+}
+
+// --- Memory sync handlers ---
+
+/// List memory sync conflicts awaiting manual resolution
+async fn list_sync_conflicts(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<Vec<crate::sync::MergeConflict>>, (StatusCode, Json<ErrorResponse>)> {
+    SyncStateRepo::new(state.db.clone())
+        .conflicts()
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_response("db_error", &e.to_string()),
+            )
+        })
+}
+
+/// Resolve a memory's sync conflicts, keeping its local values
+///
+/// Edit the memory first to take the remote values instead; either way the
+/// result is pushed to other devices on the next sync.
+async fn resolve_sync_conflicts(
+    State(state): State<Arc<ApiState>>,
+    Path(memory_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let resolved = SyncStateRepo::new(state.db.clone())
+        .resolve_conflicts(&memory_id)
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_response("db_error", &e.to_string()),
+            )
+        })?;
+
+    if resolved > 0 {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            error_response("not_found", "No sync conflicts for this memory"),
+        ))
+    }
+}
+
+// --- Usage cap handlers ---
+
+fn usage_status(
+    state: &ApiState,
+    user_id: String,
+) -> Result<UsageResponse, (StatusCode, Json<ErrorResponse>)> {
+    let db_error = |e: crate::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            error_response("db_error", &e.to_string()),
+        )
+    };
+    let tokens_today = state.usage_cap.tokens_today(&user_id).map_err(db_error)?;
+    let limit = state
+        .usage_cap
+        .repo()
+        .get_limit(&user_id)
+        .map_err(db_error)?;
+
+    Ok(UsageResponse {
+        user_id,
+        tokens_today,
+        has_override: limit.is_some(),
+        daily_tokens: limit.and_then(|l| l.daily_tokens),
+    })
+}
+
+/// Get a user's usage today and cap override
+async fn get_usage(
+    State(state): State<Arc<ApiState>>,
+    Path(user_id): Path<String>,
+) -> Result<Json<UsageResponse>, (StatusCode, Json<ErrorResponse>)> {
+    usage_status(&state, user_id).map(Json)
+}
+
+/// Set a per-user daily cap override
+async fn set_usage_limit(
+    State(state): State<Arc<ApiState>>,
+    Path(user_id): Path<String>,
+    Json(req): Json<SetUsageLimitRequest>,
+) -> Result<Json<UsageResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let limit = crate::db::UsageLimit {
+        daily_tokens: req.daily_tokens,
+    };
+    state
+        .usage_cap
+        .set_override(&user_id, Some(limit))
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_response("db_error", &e.to_string()),
+            )
+        })?;
+
+    usage_status(&state, user_id).map(Json)
+}
+
+/// Remove a per-user override (the global caps apply again)
+async fn delete_usage_limit(
+    State(state): State<Arc<ApiState>>,
+    Path(user_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let deleted = state.usage_cap.repo().delete_limit(&user_id).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            error_response("db_error", &e.to_string()),
+        )
+    })?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            error_response("not_found", "Usage limit override not found"),
+        ))
+    }
+}
+
 /// Build admin router with auth middleware
 pub fn router(state: Arc<ApiState>) -> Router {
     Router::new()
@@ -338,11 +737,34 @@ pub fn router(state: Arc<ApiState>) -> Router {
         .route("/users/{id}", get(get_user))
         .route("/users/{id}/life-json", put(set_life_json))
         .route("/users/{id}", delete(delete_user))
+        .route(
+            "/users/{id}/api-keys",
+            post(create_api_key).get(list_api_keys),
+        )
+        .route("/api-keys/{key_id}", delete(revoke_api_key))
         .route("/sessions", get(list_sessions))
         .route("/sessions/{id}/messages", get(get_session_messages))
+        .route("/sessions/{id}/tool-profile", put(set_session_tool_profile))
         .route("/telegram/groups", get(list_telegram_groups))
         .route("/telegram/groups/{chat_id}", put(upsert_telegram_group))
         .route("/telegram/groups/{chat_id}", delete(delete_telegram_group))
+        .route("/maintenance", get(get_maintenance).put(set_maintenance))
+        .route("/relay", get(get_relay_status))
+        .route("/persona/reload", post(reload_persona))
+        .route("/sync/conflicts", get(list_sync_conflicts))
+        .route(
+            "/sync/conflicts/{memory_id}",
+            delete(resolve_sync_conflicts),
+        )
+        .route("/users/{id}/usage", get(get_usage))
+        .route(
+            "/users/{id}/usage-limit",
+            put(set_usage_limit).delete(delete_usage_limit),
+        )
+        .layer(middleware::from_fn_with_state(
+            RequireScope("admin"),
+            require_scope,
+        ))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             require_api_key,
@@ -1,11 +1,19 @@
 //! Authentication middleware
 //!
-//! Supports two authentication methods:
+//! Supports four authentication methods:
 //! 1. API key (simple Bearer token matching `BEACON_API_KEY`)
-//! 2. Gatekeeper JWT (validated via JWKS endpoint)
+//! 2. Per-user API keys (`bk_...`, when `BEACON_AUTH_MODE=api_key_per_user`)
+//! 3. Device session tokens (from `POST /api/pair/session`)
+//! 4. Gatekeeper JWT (validated via JWKS endpoint)
 //!
-//! In development mode (no API key configured, no JWKS), all requests pass through
+//! In development mode (no API key configured, no JWKS), all requests pass through.
+//! Presented keys are compared in constant time and never logged.
+//!
+//! In cloud mode, JWT callers are also authorized per route: a [`ScopePolicy`]
+//! maps `/api` and `/ws` path prefixes to the scope they require, and
+//! unmapped routes are denied. Routers can add their own [`RequireScope`] guard.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::{
@@ -16,15 +24,41 @@ use axum::{
 };
 
 use super::ApiState;
+use crate::db::api_key::API_KEY_PREFIX;
+use crate::security::TrustLevel;
+use crate::security::device::is_session_token;
+use crate::security::hmac::constant_time_eq;
+
+/// Scope value marking a route as requiring no authentication
+pub const PUBLIC_SCOPE: &str = "public";
 
 /// Authenticated user identity extracted from the request
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct AuthIdentity {
-    /// User ID (from JWT `sub` claim or "api-key" for API key auth)
+    /// User ID (from JWT `sub` claim, the key's owner for per-user keys, the
+    /// device's user (or the device ID) for device sessions, or "api-key" for
+    /// the shared API key)
     pub user_id: String,
     /// Authentication method used
     pub method: AuthMethod,
+    /// Scopes granted by the JWT or per-user key (empty for other methods)
+    pub scopes: Vec<String>,
+}
+
+impl AuthIdentity {
+    /// Whether this identity may access routes requiring `scope`
+    ///
+    /// API key and anonymous (development) callers are unrestricted.
+    #[must_use]
+    pub fn has_scope(&self, scope: &str) -> bool {
+        match self.method {
+            AuthMethod::ApiKey | AuthMethod::Anonymous => true,
+            AuthMethod::Jwt | AuthMethod::UserApiKey | AuthMethod::DeviceSession => {
+                self.scopes.iter().any(|s| s == scope)
+            }
+        }
+    }
 }
 
 /// How the user was authenticated
@@ -33,6 +67,10 @@ pub struct AuthIdentity {
 pub enum AuthMethod {
     /// Matched the configured `BEACON_API_KEY`
     ApiKey,
+    /// Matched a per-user API key
+    UserApiKey,
+    /// Matched a paired device's session token
+    DeviceSession,
     /// Validated via Gatekeeper JWT
     Jwt,
     /// No auth configured (development mode)
@@ -47,14 +85,299 @@ fn extract_bearer(req: &Request) -> Option<&str> {
         .and_then(|v| v.strip_prefix("Bearer "))
 }
 
+/// Extract a token from the Authorization header or `?token=` query
+///
+/// Browsers cannot set headers on WebSocket upgrades, so `/ws` clients pass
+/// the JWT as a query parameter.
+fn extract_token(req: &Request) -> Option<String> {
+    if let Some(token) = extract_bearer(req) {
+        return Some(token.to_string());
+    }
+    url::form_urlencoded::parse(req.uri().query()?.as_bytes())
+        .find(|(key, value)| key == "token" && !value.is_empty())
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Route-to-scope mapping enforced in cloud mode
+///
+/// Patterns are path prefixes matched segment by segment, where `*` matches
+/// any single segment. The most specific matching pattern wins.
+#[derive(Debug, Clone)]
+pub struct ScopePolicy {
+    routes: Vec<(String, String)>,
+}
+
+impl Default for ScopePolicy {
+    fn default() -> Self {
+        let routes = [
+            ("/api/admin", "admin"),
+            ("/api/browser", "admin"),
+            ("/api/canvas", "chat"),
+            ("/api/chat", "chat"),
+            ("/api/devices", "admin"),
+            ("/api/knowledge", "admin"),
+            ("/api/memories", "chat"),
+            ("/api/nodes", "admin"),
+            ("/api/pair", "chat"),
+            ("/api/pair/confirm", "admin"),
+            ("/api/pair/pending", "admin"),
+            ("/api/pair/request", "admin"),
+            ("/api/persona", "chat"),
+            ("/api/personas", "chat"),
+            ("/api/personas/*/activate", "admin"),
+            ("/api/personas/marketplace", "admin"),
+            ("/api/plugins", "admin"),
+            ("/api/providers", "chat"),
+            ("/api/skills", "admin"),
+            ("/api/skills/commands", "chat"),
+            ("/api/skills/install", "admin"),
+            ("/api/status", "chat"),
+            ("/api/voice", "chat"),
+            // Webhooks authenticate with their own signatures
+            ("/api/webhooks", PUBLIC_SCOPE),
+            ("/ws/canvas", "chat"),
+            ("/ws/chat", "chat"),
+            ("/ws/node", "admin"),
+        ];
+        Self {
+            routes: routes
+                .into_iter()
+                .map(|(p, s)| (p.to_string(), s.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl ScopePolicy {
+    /// Default policy with configured entries added or replaced
+    #[must_use]
+    pub fn with_overrides(overrides: &HashMap<String, String>) -> Self {
+        let mut policy = Self::default();
+        for (pattern, scope) in overrides {
+            let pattern = format!("/{}", pattern.trim().trim_matches('/'));
+            let scope = scope.trim().to_string();
+            match policy.routes.iter_mut().find(|(p, _)| *p == pattern) {
+                Some(entry) => entry.1 = scope,
+                None => policy.routes.push((pattern, scope)),
+            }
+        }
+        policy
+    }
+
+    /// Whether the policy applies to a path (API and WebSocket routes only)
+    #[must_use]
+    pub fn governs(path: &str) -> bool {
+        path == "/api" || path.starts_with("/api/") || path == "/ws" || path.starts_with("/ws/")
+    }
+
+    /// Scope required for a path, or `None` if no pattern matches
+    #[must_use]
+    pub fn required_scope(&self, path: &str) -> Option<&str> {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        self.routes
+            .iter()
+            .filter_map(|(pattern, scope)| {
+                let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+                let matches = pattern.len() <= segments.len()
+                    && pattern
+                        .iter()
+                        .zip(&segments)
+                        .all(|(p, s)| *p == "*" || p == s);
+                let literal = pattern.iter().filter(|p| **p != "*").count();
+                matches.then_some(((pattern.len(), literal), scope.as_str()))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map(|(_, scope)| scope)
+    }
+}
+
+/// Resolve the caller's identity from an API key or Gatekeeper JWT
+///
+/// Returns `Ok(None)` when no credentials were supplied.
+async fn authenticate(
+    state: &ApiState,
+    token: Option<&str>,
+) -> Result<Option<AuthIdentity>, StatusCode> {
+    let Some(token) = token else {
+        return Ok(None);
+    };
+
+    if state
+        .api_key
+        .as_deref()
+        .is_some_and(|key| constant_time_eq(key.as_bytes(), token.as_bytes()))
+    {
+        return Ok(Some(AuthIdentity {
+            user_id: "api-key".to_string(),
+            method: AuthMethod::ApiKey,
+            scopes: Vec::new(),
+        }));
+    }
+
+    if let Some(ref keys) = state.user_api_keys
+        && token.starts_with(API_KEY_PREFIX)
+    {
+        return match keys.authenticate(token) {
+            Ok(Some(key)) => {
+                tracing::debug!(user_id = %key.user_id, key_id = %key.id, "authenticated via user API key");
+                Ok(Some(AuthIdentity {
+                    user_id: key.user_id,
+                    method: AuthMethod::UserApiKey,
+                    scopes: key.scopes,
+                }))
+            }
+            Ok(None) => Err(StatusCode::UNAUTHORIZED),
+            Err(e) => {
+                tracing::error!(error = %e, "user API key lookup failed");
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        };
+    }
+
+    if let Some(ref pairing) = state.device_pairing
+        && is_session_token(token)
+    {
+        return match pairing.device_manager.validate_session(token) {
+            Ok(Some(device)) if !device.is_approved() => {
+                tracing::warn!(device_id = %device.id, "session of unapproved device rejected");
+                Err(StatusCode::UNAUTHORIZED)
+            }
+            Ok(Some(device)) => {
+                tracing::debug!(device_id = %device.id, "authenticated via device session");
+                let mut scopes = vec!["chat".to_string()];
+                if device.trust_level == TrustLevel::Admin {
+                    scopes.push("admin".to_string());
+                }
+                Ok(Some(AuthIdentity {
+                    user_id: device.user_id.unwrap_or(device.id),
+                    method: AuthMethod::DeviceSession,
+                    scopes,
+                }))
+            }
+            Ok(None) => Err(StatusCode::UNAUTHORIZED),
+            Err(e) => {
+                tracing::error!(error = %e, "device session lookup failed");
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        };
+    }
+
+    let Some(ref jwt_cache) = state.jwt_cache else {
+        return Ok(None);
+    };
+    match jwt_cache.validate(token).await {
+        Ok(claims) => {
+            tracing::debug!(user_id = %claims.sub, "authenticated via Gatekeeper JWT");
+            Ok(Some(AuthIdentity {
+                scopes: claims.scopes(),
+                user_id: claims.sub,
+                method: AuthMethod::Jwt,
+            }))
+        }
+        Err(e) => {
+            tracing::debug!(error = %e, "JWT validation failed");
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    }
+}
+
+/// Middleware enforcing the [`ScopePolicy`] on API and WebSocket routes
+///
+/// Only active in cloud mode. Routes with no matching pattern are denied;
+/// authorized callers get an `AuthIdentity` in request extensions.
+pub async fn enforce_scope_policy(
+    State((state, policy)): State<(Arc<ApiState>, Arc<ScopePolicy>)>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let path = req.uri().path();
+    if !state.cloud_mode || !ScopePolicy::governs(path) {
+        return Ok(next.run(req).await);
+    }
+
+    let Some(scope) = policy.required_scope(path) else {
+        tracing::warn!(path, "no scope mapped for route, denying");
+        return Err(StatusCode::FORBIDDEN);
+    };
+    if scope == PUBLIC_SCOPE {
+        return Ok(next.run(req).await);
+    }
+
+    let token = extract_token(&req);
+    let Some(identity) = authenticate(&state, token.as_deref()).await? else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+    if !identity.has_scope(scope) {
+        tracing::warn!(path, scope, user_id = %identity.user_id, "missing required scope");
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    req.extensions_mut().insert(identity);
+    Ok(next.run(req).await)
+}
+
+/// Route guard requiring a scope on the caller's identity
+///
+/// Layer with `middleware::from_fn_with_state(RequireScope("admin"), require_scope)`.
+/// Requests without an `AuthIdentity` pass through: outside cloud mode the
+/// router's own auth middleware applies instead.
+#[derive(Debug, Clone, Copy)]
+pub struct RequireScope(pub &'static str);
+
+/// Reject requests whose identity lacks the [`RequireScope`] scope with 403
+pub async fn require_scope(
+    State(RequireScope(scope)): State<RequireScope>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if let Some(identity) = req.extensions().get::<AuthIdentity>()
+        && !identity.has_scope(scope)
+    {
+        tracing::warn!(scope, user_id = %identity.user_id, "missing required scope");
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(next.run(req).await)
+}
+
 /// Middleware to verify API key (admin endpoints)
+///
+/// Per-user keys and device sessions are accepted only when they carry the
+/// `admin` scope.
 pub async fn require_api_key(
     State(state): State<Arc<ApiState>>,
-    req: Request,
+    mut req: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    // JWT callers were already authorized by the cloud-mode scope policy
+    if req
+        .extensions()
+        .get::<AuthIdentity>()
+        .is_some_and(|identity| identity.method == AuthMethod::Jwt)
+    {
+        return Ok(next.run(req).await);
+    }
+
+    if (state.user_api_keys.is_some() || state.device_pairing.is_some())
+        && let Some(identity) = authenticate(&state, extract_bearer(&req)).await?
+        && matches!(
+            identity.method,
+            AuthMethod::UserApiKey | AuthMethod::DeviceSession
+        )
+    {
+        if !identity.has_scope("admin") {
+            tracing::warn!(user_id = %identity.user_id, method = ?identity.method, "credential lacks admin scope");
+            return Err(StatusCode::FORBIDDEN);
+        }
+        req.extensions_mut().insert(identity);
+        return Ok(next.run(req).await);
+    }
+
     // If no API key configured, allow all requests (development mode)
     let Some(expected_key) = &state.api_key else {
+        if state.user_api_keys.is_some() {
+            tracing::debug!("no valid user API key provided");
+            return Err(StatusCode::UNAUTHORIZED);
+        }
         tracing::warn!("API key not configured - allowing unauthenticated access");
         return Ok(next.run(req).await);
     };
@@ -62,7 +385,14 @@ pub async fn require_api_key(
     let provided_key = extract_bearer(&req);
 
     match provided_key {
-        Some(key) if key == expected_key => Ok(next.run(req).await),
+        Some(key) if constant_time_eq(key.as_bytes(), expected_key.as_bytes()) => {
+            req.extensions_mut().insert(AuthIdentity {
+                user_id: "api-key".to_string(),
+                method: AuthMethod::ApiKey,
+                scopes: Vec::new(),
+            });
+            Ok(next.run(req).await)
+        }
         Some(_) => {
             tracing::warn!("invalid API key provided");
             Err(StatusCode::UNAUTHORIZED)
@@ -74,55 +404,36 @@ pub async fn require_api_key(
     }
 }
 
-/// Middleware that accepts either API key or Gatekeeper JWT
+/// Middleware that accepts the API key, a per-user key, or a Gatekeeper JWT
 ///
-/// On success, inserts `AuthIdentity` into request extensions.
-/// In development mode (no auth configured), passes through as anonymous.
-#[allow(dead_code)]
+/// Guards the chat HTTP, SSE and WebSocket routes when `require_chat_auth` is
+/// set; the token may come from the Authorization header or the `?token=`
+/// query. On success, inserts
+/// `AuthIdentity` into request extensions. In development mode (no auth
+/// configured), passes through as anonymous.
 pub async fn require_auth(
     State(state): State<Arc<ApiState>>,
     mut req: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    let token = extract_bearer(&req);
-
-    // Try API key first
-    if let Some(expected_key) = &state.api_key
-        && let Some(key) = token
-        && key == expected_key
-    {
-        req.extensions_mut().insert(AuthIdentity {
-            user_id: "api-key".to_string(),
-            method: AuthMethod::ApiKey,
-        });
+    // Already authorized by the cloud-mode scope policy
+    if req.extensions().get::<AuthIdentity>().is_some() {
         return Ok(next.run(req).await);
     }
 
-    // Try JWT validation via HIDRA Gatekeeper
-    if let Some(ref jwt_cache) = state.jwt_cache
-        && let Some(token) = token
-    {
-        match jwt_cache.validate(token).await {
-            Ok(claims) => {
-                tracing::debug!(user_id = %claims.sub, "authenticated via Gatekeeper JWT");
-                req.extensions_mut().insert(AuthIdentity {
-                    user_id: claims.sub,
-                    method: AuthMethod::Jwt,
-                });
-                return Ok(next.run(req).await);
-            }
-            Err(e) => {
-                tracing::debug!(error = %e, "JWT validation failed");
-                return Err(StatusCode::UNAUTHORIZED);
-            }
-        }
+    // Try API key first, then JWT validation via HIDRA Gatekeeper
+    let token = extract_token(&req);
+    if let Some(identity) = authenticate(&state, token.as_deref()).await? {
+        req.extensions_mut().insert(identity);
+        return Ok(next.run(req).await);
     }
 
-    // Development mode: no API key and no JWKS configured
-    if state.api_key.is_none() && state.jwt_cache.is_none() {
+    // Development mode: no API key, user keys, or JWKS configured
+    if state.api_key.is_none() && state.user_api_keys.is_none() && state.jwt_cache.is_none() {
         req.extensions_mut().insert(AuthIdentity {
             user_id: "anonymous".to_string(),
             method: AuthMethod::Anonymous,
+            scopes: Vec::new(),
         });
         return Ok(next.run(req).await);
     }
@@ -159,13 +470,77 @@ mod tests {
         let api_key = AuthIdentity {
             user_id: "api-key".to_string(),
             method: AuthMethod::ApiKey,
+            scopes: Vec::new(),
         };
         assert_eq!(api_key.method, AuthMethod::ApiKey);
+        assert!(api_key.has_scope("admin"));
 
         let jwt = AuthIdentity {
             user_id: "user-123".to_string(),
             method: AuthMethod::Jwt,
+            scopes: vec!["chat".to_string()],
         };
         assert_eq!(jwt.method, AuthMethod::Jwt);
+        assert!(jwt.has_scope("chat"));
+        assert!(!jwt.has_scope("admin"));
+    }
+
+    #[test]
+    fn extracts_query_token() {
+        let req = Request::builder()
+            .uri("/ws/chat/web-1?foo=bar&token=abc.def")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(extract_token(&req).as_deref(), Some("abc.def"));
+
+        // Query values are percent-decoded
+        let req = Request::builder()
+            .uri("/ws/chat/web-1?token=bk_a%2Fb%3D%3D")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(extract_token(&req).as_deref(), Some("bk_a/b=="));
+
+        let req = Request::builder()
+            .uri("/ws/chat/web-1?token=")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(extract_token(&req), None);
+    }
+
+    #[test]
+    fn claims_merge_scope_and_permissions() {
+        let claims: super::super::jwt::GatekeeperClaims = serde_json::from_value(
+            serde_json::json!({"sub": "u", "exp": 0, "scope": "chat admin", "permissions": ["admin", "billing"]}),
+        )
+        .unwrap();
+        assert_eq!(claims.scopes(), vec!["chat", "admin", "billing"]);
+    }
+
+    #[test]
+    fn scope_policy_picks_most_specific_route() {
+        let policy = ScopePolicy::default();
+        assert_eq!(policy.required_scope("/api/admin/users"), Some("admin"));
+        assert_eq!(policy.required_scope("/ws/chat/web-1"), Some("chat"));
+        assert_eq!(policy.required_scope("/api/skills/install"), Some("admin"));
+        assert_eq!(policy.required_scope("/api/skills/commands"), Some("chat"));
+        assert_eq!(policy.required_scope("/api/personas"), Some("chat"));
+        assert_eq!(
+            policy.required_scope("/api/personas/orin/activate"),
+            Some("admin")
+        );
+        assert_eq!(policy.required_scope("/api/unknown"), None);
+        assert!(!ScopePolicy::governs("/health"));
+    }
+
+    #[test]
+    fn scope_policy_overrides() {
+        let overrides = HashMap::from([
+            ("/api/skills/".to_string(), "chat".to_string()),
+            ("api/extra".to_string(), "ops".to_string()),
+        ]);
+        let policy = ScopePolicy::with_overrides(&overrides);
+        assert_eq!(policy.required_scope("/api/skills/install"), Some("admin"));
+        assert_eq!(policy.required_scope("/api/skills/foo"), Some("chat"));
+        assert_eq!(policy.required_scope("/api/extra/thing"), Some("ops"));
     }
 }
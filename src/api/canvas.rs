@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use axum::{
     Router,
-    extract::{State, WebSocketUpgrade, ws::Message},
+    extract::{Query, State, WebSocketUpgrade, ws::Message},
     response::IntoResponse,
     routing::get,
 };
@@ -43,18 +43,31 @@ pub enum CanvasWsOutgoing {
     Connected,
 }
 
+/// Query parameters for the canvas WebSocket
+#[derive(Debug, Default, Deserialize)]
+pub struct CanvasWsQuery {
+    /// Replay existing elements as individual `push` commands instead of a
+    /// single `snapshot`, for clients that don't understand `snapshot`
+    #[serde(default)]
+    pub legacy: bool,
+}
+
 /// Build canvas WebSocket router
 pub fn router(canvas: SharedCanvas) -> Router {
     Router::new().route("/", get(ws_upgrade)).with_state(canvas)
 }
 
 /// Handle WebSocket upgrade request
-async fn ws_upgrade(State(canvas): State<SharedCanvas>, ws: WebSocketUpgrade) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, canvas))
+async fn ws_upgrade(
+    State(canvas): State<SharedCanvas>,
+    Query(query): Query<CanvasWsQuery>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_socket(socket, canvas, query.legacy))
 }
 
 /// Handle WebSocket connection
-async fn handle_socket(socket: axum::extract::ws::WebSocket, canvas: SharedCanvas) {
+async fn handle_socket(socket: axum::extract::ws::WebSocket, canvas: SharedCanvas, legacy: bool) {
     let (mut sender, mut receiver) = socket.split();
 
     // Send connected message
@@ -67,12 +80,21 @@ async fn handle_socket(socket: axum::extract::ws::WebSocket, canvas: SharedCanva
 
     tracing::info!("Canvas WebSocket connected");
 
-    // Subscribe to canvas updates
-    let mut rx = {
+    // Subscribe to canvas updates and replay the current state first
+    let (elements, mut rx) = {
         let canvas_guard = canvas.lock().await;
-        canvas_guard.subscribe()
+        canvas_guard.subscribe_with_snapshot()
     };
 
+    for cmd in CanvasCommand::replay(elements, legacy) {
+        let msg = CanvasWsOutgoing::Command(cmd);
+        if let Ok(text) = serde_json::to_string(&msg)
+            && sender.send(Message::Text(text.into())).await.is_err()
+        {
+            return;
+        }
+    }
+
     // Spawn task to forward canvas updates to WebSocket
     let mut broadcast_task = tokio::spawn(async move {
         while let Ok(cmd) = rx.recv().await {
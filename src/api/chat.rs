@@ -0,0 +1,524 @@
+//! HTTP chat endpoint with idempotent retries
+//!
+//! `POST /api/chat` runs the same turn as the WebSocket chat handler and
+//! returns the full reply. Clients on flaky networks can send an
+//! `Idempotency-Key` header: repeated keys from the same user get the stored
+//! response instead of a second LLM call. Reusing a key with a different
+//! request body is rejected with 422. Keys live for
+//! `BEACON_IDEMPOTENCY_TTL_SECS` (default 24h).
+//!
+//! `GET`/`POST /api/chat/stream` runs the same turn but streams the reply as
+//! Server-Sent Events for web clients that don't use the WebSocket.
+
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post},
+};
+use futures::Stream;
+use mini_moka::sync::Cache;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::{OnceCell, mpsc};
+use tokio_util::task::AbortOnDropHandle;
+
+use super::ApiState;
+use super::auth::{AuthIdentity, AuthMethod};
+use super::feedback::FeedbackManager;
+use super::websocket::{WsOutgoing, handle_chat_message};
+
+/// Header carrying the client-chosen idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// How long completed responses are kept for replay
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Longest accepted idempotency key
+const MAX_KEY_LEN: usize = 255;
+
+#[derive(Deserialize)]
+pub struct ChatRequest {
+    #[serde(alias = "content")]
+    pub message: String,
+    pub session_id: String,
+    /// Override the active persona for this message
+    #[serde(default)]
+    pub persona_id: Option<String>,
+    /// Override the model for this message
+    #[serde(default)]
+    pub model_override: Option<String>,
+}
+
+impl ChatRequest {
+    /// Hash of the request body, stored with its idempotency key
+    fn body_hash(&self) -> String {
+        let body = serde_json::to_vec(&(
+            &self.message,
+            &self.session_id,
+            &self.persona_id,
+            &self.model_override,
+        ))
+        .unwrap_or_default();
+        hex::encode(Sha256::digest(&body))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatResponse {
+    pub message_id: String,
+    pub content: String,
+}
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    pub error: ErrorDetail,
+}
+
+#[derive(Serialize)]
+pub struct ErrorDetail {
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+fn error_response(code: &str, message: &str) -> Json<ErrorResponse> {
+    Json(ErrorResponse {
+        error: ErrorDetail {
+            code: code.to_string(),
+            message: message.to_string(),
+            request_id: crate::request_id::current(),
+        },
+    })
+}
+
+type ChatError = (StatusCode, Json<ErrorResponse>);
+
+/// The request a key was first used with and its response
+struct IdempotencySlot {
+    body_hash: String,
+    response: OnceCell<ChatResponse>,
+}
+
+/// Responses keyed by (user, idempotency key)
+///
+/// Each slot holds a `OnceCell`, so a retry that arrives while the first
+/// request is still running waits for its result. Failed turns leave the
+/// slot empty and the next retry runs again.
+#[derive(Clone)]
+pub struct IdempotencyCache {
+    entries: Cache<(String, String), Arc<IdempotencySlot>>,
+    insert_lock: Arc<Mutex<()>>,
+}
+
+impl std::fmt::Debug for IdempotencyCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotencyCache")
+            .field("entries", &self.entries.entry_count())
+            .finish()
+    }
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_IDEMPOTENCY_TTL)
+    }
+}
+
+impl IdempotencyCache {
+    /// Create a cache keeping responses for `ttl`
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Cache::builder()
+                .max_capacity(10_000)
+                .time_to_live(ttl)
+                .build(),
+            insert_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Slot for a user's key, created empty on first use
+    ///
+    /// Returns `None` when the key was first used with a different body.
+    fn slot(&self, user: &str, key: &str, body_hash: &str) -> Option<Arc<IdempotencySlot>> {
+        let cache_key = (user.to_string(), key.to_string());
+        let _guard = self
+            .insert_lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(slot) = self.entries.get(&cache_key) {
+            return (slot.body_hash == body_hash).then_some(slot);
+        }
+        let slot = Arc::new(IdempotencySlot {
+            body_hash: body_hash.to_string(),
+            response: OnceCell::new(),
+        });
+        self.entries.insert(cache_key, Arc::clone(&slot));
+        Some(slot)
+    }
+}
+
+/// Send a chat message and return the full reply
+async fn send_message(
+    State(state): State<Arc<ApiState>>,
+    identity: Option<Extension<AuthIdentity>>,
+    headers: HeaderMap,
+    Json(req): Json<ChatRequest>,
+) -> Result<Json<ChatResponse>, ChatError> {
+    let gatekeeper_user_id = caller_user_id(identity);
+
+    let key = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|v| v.to_str().map(str::trim))
+        .transpose()
+        .map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                error_response("invalid_idempotency_key", "Idempotency-Key must be ASCII"),
+            )
+        })?
+        .filter(|k| !k.is_empty());
+
+    let Some(key) = key else {
+        return run_turn(&state, &req, gatekeeper_user_id.as_deref())
+            .await
+            .map(Json);
+    };
+    if key.len() > MAX_KEY_LEN {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            error_response(
+                "invalid_idempotency_key",
+                &format!("Idempotency-Key must be at most {MAX_KEY_LEN} characters"),
+            ),
+        ));
+    }
+
+    // Unauthenticated callers are scoped by session
+    let scope = gatekeeper_user_id
+        .clone()
+        .unwrap_or_else(|| format!("session:{}", req.session_id));
+    let Some(slot) = state.idempotency.slot(&scope, key, &req.body_hash()) else {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            error_response(
+                "idempotency_key_reused",
+                "Idempotency-Key was already used with a different request body",
+            ),
+        ));
+    };
+    let replayed = slot.response.initialized();
+
+    let response = slot
+        .response
+        .get_or_try_init(|| run_turn(&state, &req, gatekeeper_user_id.as_deref()))
+        .await?;
+    if replayed {
+        tracing::debug!(key, "replaying idempotent chat response");
+    }
+
+    Ok(Json(response.clone()))
+}
+
+/// Run one chat turn through the WebSocket handler, collecting its output
+async fn run_turn(
+    state: &Arc<ApiState>,
+    req: &ChatRequest,
+    gatekeeper_user_id: Option<&str>,
+) -> Result<ChatResponse, ChatError> {
+    let (tx, mut rx) = mpsc::channel::<WsOutgoing>(32);
+    let feedback = Arc::new(FeedbackManager::new());
+
+    let turn = handle_chat_message(
+        &req.message,
+        req.persona_id.clone(),
+        req.model_override.clone(),
+        state,
+        &req.session_id,
+        tx,
+        gatekeeper_user_id,
+        &feedback,
+    );
+    let collect = async {
+        let mut content = String::new();
+        while let Some(msg) = rx.recv().await {
+            match msg {
+                WsOutgoing::ChatChunk { content: chunk } => content.push_str(&chunk),
+                WsOutgoing::ChatComplete { message_id } => {
+                    return Ok(ChatResponse {
+                        message_id,
+                        content,
+                    });
+                }
+                WsOutgoing::Error { code, message } => {
+                    let status = match code.as_str() {
+                        "agent_error" => StatusCode::BAD_GATEWAY,
+                        "unavailable" => StatusCode::SERVICE_UNAVAILABLE,
+                        _ => StatusCode::INTERNAL_SERVER_ERROR,
+                    };
+                    return Err((status, error_response(&code, &message)));
+                }
+                _ => {}
+            }
+        }
+        Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            error_response("internal_error", "chat turn ended without a reply"),
+        ))
+    };
+
+    let (turn_result, response) = tokio::join!(turn, collect);
+    turn_result.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            error_response("internal_error", &e.to_string()),
+        )
+    })?;
+    response
+}
+
+/// Stream a chat reply over SSE, with the request in the query string
+async fn stream_message_get(
+    State(state): State<Arc<ApiState>>,
+    identity: Option<Extension<AuthIdentity>>,
+    Query(req): Query<ChatRequest>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    stream_turn(state, caller_user_id(identity), req)
+}
+
+/// Stream a chat reply over SSE, with the request in the body
+async fn stream_message_post(
+    State(state): State<Arc<ApiState>>,
+    identity: Option<Extension<AuthIdentity>>,
+    Json(req): Json<ChatRequest>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    stream_turn(state, caller_user_id(identity), req)
+}
+
+/// User ID of a caller authenticated as a specific user (JWT, per-user key,
+/// or device session)
+pub(super) fn caller_user_id(identity: Option<Extension<AuthIdentity>>) -> Option<String> {
+    identity
+        .filter(|Extension(identity)| {
+            matches!(
+                identity.method,
+                AuthMethod::Jwt | AuthMethod::UserApiKey | AuthMethod::DeviceSession
+            )
+        })
+        .map(|Extension(identity)| identity.user_id)
+}
+
+/// Progress of an SSE turn, threaded through the event stream
+struct SseTurn {
+    rx: mpsc::Receiver<WsOutgoing>,
+    /// Aborts the turn, and with it the upstream generation, when the client
+    /// disconnects and the stream is dropped
+    _turn: AbortOnDropHandle<()>,
+    session_id: String,
+    usage: Option<(u32, u32)>,
+    finished: bool,
+}
+
+/// Run one chat turn in the background and stream its output as SSE events
+///
+/// Deltas are sent as `data: {"delta": ...}` events. The stream ends with a
+/// `done` event carrying the session id, message id, and token usage, or an
+/// `error` event.
+fn stream_turn(
+    state: Arc<ApiState>,
+    gatekeeper_user_id: Option<String>,
+    req: ChatRequest,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::channel::<WsOutgoing>(32);
+    let session_id = req.session_id.clone();
+
+    let request_id = crate::request_id::current_or_new();
+    let turn = tokio::spawn(crate::request_id::scope(request_id, async move {
+        let feedback = Arc::new(FeedbackManager::new());
+        if let Err(e) = handle_chat_message(
+            &req.message,
+            req.persona_id,
+            req.model_override,
+            &state,
+            &req.session_id,
+            tx.clone(),
+            gatekeeper_user_id.as_deref(),
+            &feedback,
+        )
+        .await
+        {
+            let _ = tx
+                .send(WsOutgoing::Error {
+                    code: "internal_error".to_string(),
+                    message: e.to_string(),
+                })
+                .await;
+        }
+    }));
+
+    let turn = SseTurn {
+        rx,
+        _turn: AbortOnDropHandle::new(turn),
+        session_id,
+        usage: None,
+        finished: false,
+    };
+    let events = futures::stream::unfold(turn, |mut turn| async move {
+        let event = next_sse_event(&mut turn).await?;
+        Some((Ok(event), turn))
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Translate the turn's output into the next SSE event, or None once finished
+async fn next_sse_event(turn: &mut SseTurn) -> Option<Event> {
+    if turn.finished {
+        return None;
+    }
+    loop {
+        let Some(msg) = turn.rx.recv().await else {
+            turn.finished = true;
+            return Some(error_event(
+                "internal_error",
+                "chat turn ended without a reply",
+            ));
+        };
+        match msg {
+            WsOutgoing::ChatChunk { content } => return Some(delta_event(&content)),
+            WsOutgoing::Usage {
+                input_tokens,
+                output_tokens,
+                ..
+            } => turn.usage = Some((input_tokens, output_tokens)),
+            WsOutgoing::ChatComplete { message_id } => {
+                turn.finished = true;
+                let usage = turn.usage.map(|(input_tokens, output_tokens)| {
+                    serde_json::json!({
+                        "input_tokens": input_tokens,
+                        "output_tokens": output_tokens,
+                    })
+                });
+                let done = serde_json::json!({
+                    "session_id": turn.session_id,
+                    "message_id": message_id,
+                    "usage": usage,
+                });
+                return Some(Event::default().event("done").data(done.to_string()));
+            }
+            WsOutgoing::Error { code, message } => {
+                turn.finished = true;
+                return Some(error_event(&code, &message));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn delta_event(delta: &str) -> Event {
+    Event::default().data(serde_json::json!({ "delta": delta }).to_string())
+}
+
+fn error_event(code: &str, message: &str) -> Event {
+    let body = serde_json::json!({ "code": code, "message": message });
+    Event::default().event("error").data(body.to_string())
+}
+
+/// Build the chat router
+///
+/// Credentials are only required outside cloud mode when
+/// `require_chat_auth` is set; cloud mode enforces the scope policy instead.
+pub fn router(state: Arc<ApiState>) -> Router {
+    let router = Router::new()
+        .route("/", post(send_message))
+        .route("/stream", get(stream_message_get).post(stream_message_post));
+    let router = if state.require_chat_auth {
+        router.layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            super::auth::require_auth,
+        ))
+    } else {
+        router
+    };
+    router.with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn slots_are_scoped_per_user() {
+        let cache = IdempotencyCache::default();
+        let first = cache.slot("alice", "k1", "h").unwrap();
+        first
+            .response
+            .set(ChatResponse {
+                message_id: "m1".to_string(),
+                content: "hi".to_string(),
+            })
+            .unwrap();
+
+        let again = cache.slot("alice", "k1", "h").unwrap();
+        assert_eq!(again.response.get().unwrap().message_id, "m1");
+
+        // Same key from another user is independent
+        assert!(!cache.slot("bob", "k1", "h").unwrap().response.initialized());
+        assert!(
+            !cache
+                .slot("alice", "k2", "h")
+                .unwrap()
+                .response
+                .initialized()
+        );
+    }
+
+    #[test]
+    fn key_reused_with_another_body_is_refused() {
+        let cache = IdempotencyCache::default();
+        let request = |message: &str| ChatRequest {
+            message: message.to_string(),
+            session_id: "s1".to_string(),
+            persona_id: None,
+            model_override: None,
+        };
+        let (hello, bye) = (request("hello").body_hash(), request("bye").body_hash());
+        assert_eq!(hello, request("hello").body_hash());
+
+        assert!(cache.slot("alice", "k", &hello).is_some());
+        assert!(cache.slot("alice", "k", &bye).is_none());
+        assert!(cache.slot("alice", "k", &hello).is_some());
+    }
+
+    #[tokio::test]
+    async fn concurrent_retries_share_one_run() {
+        let cache = IdempotencyCache::default();
+        let runs = std::sync::atomic::AtomicUsize::new(0);
+        let run = || async {
+            runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok::<_, ()>(ChatResponse {
+                message_id: "m1".to_string(),
+                content: "hi".to_string(),
+            })
+        };
+
+        let (a, b) = (
+            cache.slot("alice", "k", "h").unwrap(),
+            cache.slot("alice", "k", "h").unwrap(),
+        );
+        let (ra, rb) = tokio::join!(
+            a.response.get_or_try_init(run),
+            b.response.get_or_try_init(run)
+        );
+        assert_eq!(ra.unwrap().message_id, rb.unwrap().message_id);
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+}
@@ -1,16 +1,20 @@
 //! Health check endpoints
 
+use std::net::IpAddr;
 use std::sync::Arc;
 
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     routing::{get, post},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::ApiState;
+use crate::db::PoolStats;
+use crate::readiness::ReadinessComponent;
+use crate::synapse::BreakerState;
 use crate::{Config, Persona};
 
 /// Health check response
@@ -24,7 +28,16 @@ pub struct HealthResponse {
 #[derive(Serialize)]
 pub struct ReadinessResponse {
     pub status: &'static str,
+    /// Agent replies are paused while true; the API keeps serving
+    pub maintenance: bool,
     pub checks: ReadinessChecks,
+    /// Connection pool usage, for alerting on saturation
+    pub database_pool: PoolStats,
+    /// Synapse circuit breaker state
+    pub synapse_circuit: BreakerState,
+    /// Address the gateway is advertised at over mDNS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mdns_address: Option<IpAddr>,
 }
 
 /// Individual readiness checks
@@ -32,6 +45,8 @@ pub struct ReadinessResponse {
 pub struct ReadinessChecks {
     pub database: CheckResult,
     pub agent: CheckResult,
+    pub channel: CheckResult,
+    pub billing: CheckResult,
 }
 
 /// Result of a single health check
@@ -57,6 +72,13 @@ impl CheckResult {
         }
     }
 
+    fn ok_with(message: impl Into<String>) -> Self {
+        Self {
+            status: "ok",
+            message: Some(message.into()),
+        }
+    }
+
     fn unavailable() -> Self {
         Self {
             status: "unavailable",
@@ -75,11 +97,22 @@ async fn health() -> Json<HealthResponse> {
 
 /// Readiness probe - is the service ready to accept traffic?
 async fn ready(State(state): State<Arc<ApiState>>) -> (StatusCode, Json<ReadinessResponse>) {
+    // Sample before the database check borrows a connection
+    let database_pool = crate::db::pool_stats(&state.db);
     let db_check = check_database(&state);
     let agent_check = check_agent(&state);
+    let channel_check = check_channel(&state);
+    let billing_check = check_billing(&state);
+    let mdns_address = match &state.mdns {
+        Some(mdns) => mdns.advertised_address().await,
+        None => None,
+    };
 
-    let all_ok = db_check.status == "ok"
-        && (agent_check.status == "ok" || agent_check.status == "unavailable");
+    let readiness = &state.readiness;
+    let all_ok = readiness.is_satisfied(ReadinessComponent::Database, db_check.status)
+        && readiness.is_satisfied(ReadinessComponent::Agent, agent_check.status)
+        && readiness.is_satisfied(ReadinessComponent::Channel, channel_check.status)
+        && readiness.is_satisfied(ReadinessComponent::Billing, billing_check.status);
 
     let status = if all_ok { "ok" } else { "degraded" };
     let http_status = if all_ok {
@@ -92,10 +125,16 @@ async fn ready(State(state): State<Arc<ApiState>>) -> (StatusCode, Json<Readines
         http_status,
         Json(ReadinessResponse {
             status,
+            maintenance: state.maintenance.is_enabled(),
             checks: ReadinessChecks {
                 database: db_check,
                 agent: agent_check,
+                channel: channel_check,
+                billing: billing_check,
             },
+            database_pool,
+            synapse_circuit: state.synapse_breaker.state(),
+            mdns_address,
         }),
     )
 }
@@ -114,10 +153,42 @@ fn check_database(state: &ApiState) -> CheckResult {
     }
 }
 
-/// Check agent availability
+/// Check agent availability, noting an open Synapse circuit
+///
+/// An open circuit does not fail readiness: taking the gateway out of
+/// rotation would stop the traffic that probes Synapse recovery, and users
+/// still get a reply saying the agent is unavailable.
 fn check_agent(state: &ApiState) -> CheckResult {
-    match &state.synapse {
-        Some(_) => CheckResult::ok(),
+    if state.synapse.is_none() {
+        return CheckResult::unavailable();
+    }
+    match state.synapse_breaker.state() {
+        BreakerState::Open => CheckResult::ok_with(format!(
+            "circuit open, probing again in {}s",
+            state
+                .synapse_breaker
+                .retry_in()
+                .unwrap_or_default()
+                .as_secs()
+        )),
+        BreakerState::Closed | BreakerState::HalfOpen => CheckResult::ok(),
+    }
+}
+
+/// Check that the primary channel connected at startup
+fn check_channel(state: &ApiState) -> CheckResult {
+    match state.readiness.primary_channel() {
+        Some((_, true)) => CheckResult::ok(),
+        Some((name, false)) => CheckResult::fail(format!("{name} failed to connect")),
+        None => CheckResult::unavailable(),
+    }
+}
+
+/// Check that billing initialized (when `AETHER_URL` is set)
+fn check_billing(state: &ApiState) -> CheckResult {
+    match state.readiness.billing() {
+        Some(true) => CheckResult::ok(),
+        Some(false) => CheckResult::fail("initialization failed"),
         None => CheckResult::unavailable(),
     }
 }
@@ -135,6 +206,7 @@ pub struct StatusResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<ModelStatus>,
     pub voice_available: bool,
+    pub maintenance: bool,
 }
 
 #[derive(Serialize)]
@@ -156,13 +228,54 @@ pub struct PersonaInfo {
     pub accent_color: Option<String>,
 }
 
+/// Where an available persona was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PersonaOrigin {
+    /// Compiled into the binary
+    Embedded,
+    /// Present in the local persona cache directory
+    Cached,
+    /// Published on Manifold but not available locally
+    Manifold,
+}
+
+impl PersonaOrigin {
+    /// Short label for CLI output
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Embedded => "embedded",
+            Self::Cached => "cached",
+            Self::Manifold => "manifold",
+        }
+    }
+}
+
+/// A persona that can be activated, with its origin
+#[derive(Serialize)]
+pub struct AvailablePersona {
+    #[serde(flatten)]
+    pub info: PersonaInfo,
+    pub source: PersonaOrigin,
+    pub active: bool,
+}
+
 /// Response for listing all personas
 #[derive(Serialize)]
 pub struct PersonaListResponse {
-    pub personas: Vec<PersonaInfo>,
+    pub personas: Vec<AvailablePersona>,
     pub active_id: String,
 }
 
+/// Query params for listing personas
+#[derive(Debug, Default, Deserialize)]
+pub struct PersonaListQuery {
+    /// Also list personas published on Manifold
+    #[serde(default)]
+    pub manifold: bool,
+}
+
 /// Get system status including current model
 async fn status(State(state): State<Arc<ApiState>>) -> Json<StatusResponse> {
     let model = state.model_info.as_ref().map(|m| ModelStatus {
@@ -175,6 +288,7 @@ async fn status(State(state): State<Arc<ApiState>>) -> Json<StatusResponse> {
         persona_id: state.persona_id.clone(),
         model,
         voice_available: state.synapse.is_some(),
+        maintenance: state.maintenance.is_enabled(),
     })
 }
 
@@ -195,26 +309,77 @@ async fn get_persona(State(state): State<Arc<ApiState>>) -> Json<PersonaInfo> {
 }
 
 /// List all available personas
-async fn list_personas(State(state): State<Arc<ApiState>>) -> Json<PersonaListResponse> {
-    let mut personas = load_all_personas(&state.persona_cache_dir);
+async fn list_personas(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<PersonaListQuery>,
+) -> Json<PersonaListResponse> {
+    let active_id = state.active_persona.read().await.id.clone();
+    let mut personas = available_personas(&state.persona_cache_dir, &active_id);
+
+    if query.manifold {
+        let client = crate::skills::ManifoldClient::new(&state.manifold_url);
+        match client.list_personas("community").await {
+            Ok(remote) => merge_manifold_personas(&mut personas, &remote, &active_id),
+            Err(e) => tracing::warn!(error = %e, "failed to list Manifold personas"),
+        }
+    }
+
+    Json(PersonaListResponse {
+        personas,
+        active_id,
+    })
+}
+
+/// List personas available locally: cached first, then embedded ones not in the cache
+///
+/// Sorted by name.
+#[must_use]
+pub fn available_personas(cache_dir: &std::path::Path, active_id: &str) -> Vec<AvailablePersona> {
+    let mut personas: Vec<AvailablePersona> = load_all_personas(cache_dir)
+        .into_iter()
+        .map(|info| AvailablePersona {
+            active: info.id == active_id,
+            info,
+            source: PersonaOrigin::Cached,
+        })
+        .collect();
 
     // Merge in embedded personas not already in cache
     let cached_ids: std::collections::HashSet<String> =
-        personas.iter().map(|p| p.id.clone()).collect();
+        personas.iter().map(|p| p.info.id.clone()).collect();
     for (_, json) in Config::embedded_personas() {
         if let Ok(persona) = serde_json::from_str::<Persona>(json)
             && !cached_ids.contains(&persona.identity.id)
         {
-            personas.push(persona_to_info(&persona));
+            personas.push(AvailablePersona {
+                active: persona.identity.id == active_id,
+                info: persona_to_info(&persona),
+                source: PersonaOrigin::Embedded,
+            });
         }
     }
 
-    personas.sort_by(|a, b| a.name.cmp(&b.name));
+    personas.sort_by(|a, b| a.info.name.cmp(&b.info.name));
+    personas
+}
 
-    Json(PersonaListResponse {
-        personas,
-        active_id: state.persona_id.clone(),
-    })
+/// Append Manifold personas that are not already available locally
+pub fn merge_manifold_personas(
+    personas: &mut Vec<AvailablePersona>,
+    remote: &[Persona],
+    active_id: &str,
+) {
+    let known: std::collections::HashSet<String> =
+        personas.iter().map(|p| p.info.id.clone()).collect();
+    for persona in remote {
+        if !known.contains(&persona.identity.id) {
+            personas.push(AvailablePersona {
+                active: persona.identity.id == active_id,
+                info: persona_to_info(persona),
+                source: PersonaOrigin::Manifold,
+            });
+        }
+    }
 }
 
 /// Activate a persona (switch to it)
@@ -355,7 +520,10 @@ pub fn ready_router(state: Arc<ApiState>) -> Router {
         .route("/api/personas", get(list_personas))
         .route(
             "/api/personas/{persona_id}/activate",
-            post(activate_persona),
+            post(activate_persona).route_layer(axum::middleware::from_fn_with_state(
+                super::auth::RequireScope("admin"),
+                super::auth::require_scope,
+            )),
         )
         .route("/api/pair/gateway", get(get_gateway_info))
         .with_state(state)
@@ -13,6 +13,32 @@ pub struct GatekeeperClaims {
     pub sub: String,
     pub exp: u64,
     pub iss: Option<String>,
+    /// Space-delimited OAuth scopes
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// Permission list (alternative to `scope`)
+    #[serde(default)]
+    pub permissions: Option<Vec<String>>,
+}
+
+impl GatekeeperClaims {
+    /// Granted scopes from both the `scope` and `permissions` claims
+    #[must_use]
+    pub fn scopes(&self) -> Vec<String> {
+        let mut scopes: Vec<String> = self
+            .scope
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+        for permission in self.permissions.iter().flatten() {
+            if !scopes.contains(permission) {
+                scopes.push(permission.clone());
+            }
+        }
+        scopes
+    }
 }
 
 /// Cached JWKS for validating JWTs
@@ -1,5 +1,6 @@
 //! Knowledge pack API endpoints
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::{
@@ -12,7 +13,10 @@ use axum::{
 use serde::{Deserialize, Serialize};
 
 use super::{ApiState, auth::require_api_key};
-use crate::knowledge::{KnowledgePackResolver, select_knowledge};
+use crate::db::KnowledgePackRepo;
+use crate::knowledge::{
+    KnowledgePackResolver, cosine_similarity, select_knowledge, select_knowledge_with_embeddings,
+};
 use crate::persona::{KnowledgeChunk, KnowledgePackRef};
 use crate::skills::ManifoldClient;
 
@@ -67,6 +71,36 @@ pub struct ChunkPreviewQuery {
     pub max_tokens: Option<usize>,
 }
 
+/// Query parameters for scored selection preview
+#[derive(Deserialize)]
+pub struct SelectionPreviewQuery {
+    pub q: String,
+    /// Number of top-scoring chunks to return (default 5)
+    pub k: Option<usize>,
+}
+
+/// A knowledge chunk with its similarity to the query
+#[derive(Serialize)]
+pub struct ScoredChunkResponse {
+    #[serde(flatten)]
+    pub chunk: ChunkResponse,
+    /// Cosine similarity between the query and chunk embeddings
+    pub score: f32,
+    /// Whether selection would inject this chunk into context
+    pub selected: bool,
+}
+
+/// Response for scored selection preview
+#[derive(Serialize)]
+pub struct SelectionPreviewResponse {
+    /// Top `k` chunks by score
+    pub chunks: Vec<ScoredChunkResponse>,
+    /// Chunks selection would inject (may include some outside the top `k`)
+    pub selected: usize,
+    /// Chunks loaded for the persona
+    pub total: usize,
+}
+
 /// A single knowledge chunk in API responses
 #[derive(Serialize)]
 pub struct ChunkResponse {
@@ -93,6 +127,8 @@ pub struct ErrorResponse {
 pub struct ErrorDetail {
     pub code: String,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 fn error_response(code: &str, message: &str) -> Json<ErrorResponse> {
@@ -100,6 +136,7 @@ fn error_response(code: &str, message: &str) -> Json<ErrorResponse> {
         error: ErrorDetail {
             code: code.to_string(),
             message: message.to_string(),
+            request_id: crate::request_id::current(),
         },
     })
 }
@@ -230,7 +267,8 @@ async fn preview_chunks(
     Query(query): Query<ChunkPreviewQuery>,
 ) -> Json<ChunkPreviewResponse> {
     let max_tokens = query.max_tokens.unwrap_or(state.max_context_tokens);
-    let selected = select_knowledge(&state.persona_knowledge, &query.message, max_tokens);
+    let knowledge = state.persona_knowledge.read().await.clone();
+    let selected = select_knowledge(&knowledge, &query.message, max_tokens);
 
     let chunks: Vec<ChunkResponse> = selected.into_iter().map(chunk_to_response).collect();
     let total = chunks.len();
@@ -238,6 +276,98 @@ async fn preview_chunks(
     Json(ChunkPreviewResponse { chunks, total })
 }
 
+/// Score loaded knowledge against a query and show what selection would pick
+///
+/// Chunks use the vectors stored in `knowledge_vec` for their installed pack;
+/// only chunks without one are embedded on the fly, so every chunk gets a
+/// score. Selection runs with the same token budget as the chat path.
+async fn preview_selection(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<SelectionPreviewQuery>,
+) -> Result<Json<SelectionPreviewResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let Some(embedder) = state.embedder.as_ref() else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            error_response(
+                "embedder_unavailable",
+                "No embedder configured; set OPENAI_API_KEY or BEACON_EMBEDDING_BACKEND=local to enable scoring",
+            ),
+        ));
+    };
+    if query.q.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            error_response("invalid_query", "Query parameter `q` must not be empty"),
+        ));
+    }
+
+    let embedding_error = |e: crate::Error| {
+        (
+            StatusCode::BAD_GATEWAY,
+            error_response("embedding_error", &e.to_string()),
+        )
+    };
+    let mut knowledge: Vec<KnowledgeChunk> = state.persona_knowledge.read().await.to_vec();
+    let query_embedding = embedder
+        .embed(&query.q)
+        .await
+        .map_err(|e| embedding_error(e.into()))?;
+
+    let stored = KnowledgePackRepo::new(state.db.clone())
+        .chunk_embeddings()
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "failed to load stored knowledge embeddings");
+            HashMap::new()
+        });
+    for chunk in &mut knowledge {
+        if chunk.embedding.is_none() {
+            chunk.embedding = stored.get(&chunk.content).cloned();
+        }
+    }
+
+    let missing: Vec<usize> = (0..knowledge.len())
+        .filter(|&i| knowledge[i].embedding.is_none())
+        .collect();
+    if !missing.is_empty() {
+        let contents: Vec<&str> = missing
+            .iter()
+            .map(|&i| knowledge[i].content.as_str())
+            .collect();
+        let embeddings = embedder
+            .embed_batch(&contents)
+            .await
+            .map_err(|e| embedding_error(e.into()))?;
+        for (i, embedding) in missing.into_iter().zip(embeddings) {
+            knowledge[i].embedding = Some(embedding);
+        }
+    }
+
+    let selected = select_knowledge_with_embeddings(
+        &knowledge,
+        &query.q,
+        Some(&query_embedding),
+        state.max_context_tokens / 4,
+    );
+
+    let mut scored: Vec<ScoredChunkResponse> = knowledge
+        .iter()
+        .filter_map(|chunk| Some((chunk, chunk.embedding.as_ref()?)))
+        .map(|(chunk, embedding)| ScoredChunkResponse {
+            chunk: chunk_to_response(chunk),
+            score: cosine_similarity(&query_embedding, embedding),
+            selected: selected.iter().any(|s| std::ptr::eq(*s, chunk)),
+        })
+        .collect();
+    scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scored.truncate(query.k.unwrap_or(5));
+
+    Ok(Json(SelectionPreviewResponse {
+        chunks: scored,
+        selected: selected.len(),
+        total: knowledge.len(),
+    }))
+}
+
 // --- Cache helpers ---
 
 /// Read all cached knowledge packs from the cache directory
@@ -343,6 +473,7 @@ pub fn router(state: Arc<ApiState>) -> Router {
         .route("/install", post(install_pack))
         .route("/packs/{name}", delete(remove_pack))
         .route("/chunks", get(preview_chunks))
+        .route("/preview", get(preview_selection))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             require_api_key,
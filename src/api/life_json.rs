@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 
 use super::{ApiState, auth::require_api_key};
 use crate::context::life_json_sync;
-use crate::db::{Memory, MemoryCategory};
+use crate::db::{Memory, MemoryCategory, Message};
 
 // --- Request/Response types ---
 
@@ -66,6 +66,8 @@ pub struct ErrorResponse {
 pub struct ErrorDetail {
     pub code: String,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 fn error_response(code: &str, message: &str) -> Json<ErrorResponse> {
@@ -73,6 +75,7 @@ fn error_response(code: &str, message: &str) -> Json<ErrorResponse> {
         error: ErrorDetail {
             code: code.to_string(),
             message: message.to_string(),
+            request_id: crate::request_id::current(),
         },
     })
 }
@@ -189,6 +192,45 @@ pub struct MemorySearchQuery {
     pub limit: Option<usize>,
 }
 
+/// Query parameters for the message history search endpoint
+#[derive(Deserialize)]
+pub struct MessageSearchQuery {
+    pub user_id: String,
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+/// Stored message DTO for history search results
+#[derive(Serialize)]
+pub struct MessageDto {
+    pub id: String,
+    pub session_id: String,
+    pub role: String,
+    pub content: String,
+    pub created_at: String,
+    pub thread_id: Option<String>,
+}
+
+impl From<&Message> for MessageDto {
+    fn from(m: &Message) -> Self {
+        Self {
+            id: m.id.clone(),
+            session_id: m.session_id.clone(),
+            role: m.role.as_str().to_string(),
+            content: m.content.clone(),
+            created_at: m.created_at.to_rfc3339(),
+            thread_id: m.thread_id.clone(),
+        }
+    }
+}
+
+/// Response for the message history search endpoint
+#[derive(Serialize)]
+pub struct MessageSearchResponse {
+    pub messages: Vec<MessageDto>,
+    pub count: usize,
+}
+
 /// Request body for creating a memory via the CRUD endpoint
 #[derive(Deserialize)]
 pub struct CreateMemoryRequest {
@@ -213,7 +255,10 @@ async fn export_memories(
     State(state): State<Arc<ApiState>>,
     Query(query): Query<ExportQuery>,
 ) -> Result<Json<ExportResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let persona_id = query.persona_id.unwrap_or_else(|| state.persona_id.clone());
+    let persona_id = match query.persona_id {
+        Some(id) => id,
+        None => state.active_persona.read().await.id.clone(),
+    };
 
     let result = life_json_sync::export_memories(
         &state.memory_repo,
@@ -284,7 +329,8 @@ async fn crud_list_memories(
         .as_deref()
         .and_then(MemoryCategory::from_str_value);
     let memories = state
-        .memory_repo
+        .active_memory_repo()
+        .await
         .list(&query.user_id, category)
         .map_err(|e| {
             (
@@ -311,7 +357,8 @@ async fn search_memories(
 ) -> Result<Json<ListResponse>, (StatusCode, Json<ErrorResponse>)> {
     let limit = query.limit.unwrap_or(10);
     let memories = state
-        .memory_repo
+        .active_memory_repo()
+        .await
         .search(&query.user_id, &query.q)
         .map_err(|e| {
             (
@@ -328,6 +375,30 @@ async fn search_memories(
     }))
 }
 
+/// Search a user's conversation history across sessions
+async fn search_messages(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<MessageSearchQuery>,
+) -> Result<Json<MessageSearchResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let limit = query.limit.unwrap_or(20);
+    let messages = state
+        .session_repo
+        .search_messages(&query.user_id, &query.q, limit)
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_response("db_error", &e.to_string()),
+            )
+        })?;
+
+    let dtos: Vec<MessageDto> = messages.iter().map(MessageDto::from).collect();
+    let count = dtos.len();
+    Ok(Json(MessageSearchResponse {
+        messages: dtos,
+        count,
+    }))
+}
+
 /// Create a new memory (CRUD endpoint)
 async fn create_memory(
     State(state): State<Arc<ApiState>>,
@@ -344,7 +415,7 @@ async fn create_memory(
         memory = memory.with_tag(tag);
     }
 
-    state.memory_repo.add(&memory).map_err(|e| {
+    state.active_memory_repo().await.add(&memory).map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             error_response("db_error", &e.to_string()),
@@ -385,6 +456,7 @@ pub fn router(state: Arc<ApiState>) -> Router {
         // Memory management CRUD
         .route("/", get(crud_list_memories).post(create_memory))
         .route("/search", get(search_memories))
+        .route("/search-messages", get(search_messages))
         .route("/{id}", delete(delete_memory))
         .layer(middleware::from_fn_with_state(
             state.clone(),
@@ -6,6 +6,7 @@ pub use feedback::{FeedbackAnswer, FeedbackManager};
 mod auth;
 pub mod browser;
 pub mod canvas;
+pub mod chat;
 pub mod health;
 pub mod jwt;
 pub mod knowledge;
@@ -16,6 +17,7 @@ pub mod personas;
 pub mod plugins;
 pub mod providers;
 pub mod rate_limit;
+pub mod request_id;
 pub mod skills;
 pub mod voice;
 pub mod webhooks;
@@ -36,8 +38,10 @@ use tower_http::trace::TraceLayer;
 use crate::Result;
 use crate::attachments::AttachmentProcessor;
 use crate::canvas::Canvas;
-use crate::channels::{TeamsChannel, TelegramAccountRegistry, TelegramChannel};
-use crate::context::ContextConfig;
+use crate::channels::{
+    Channel, OutboundChannels, TeamsChannel, TelegramAccountRegistry, TelegramChannel,
+};
+use crate::context::{ContextConfig, MemorySelection, Tokenizer};
 use crate::db::{
     DbPool, Embedder, Indexer, MemoryRepo, SessionRepo, SkillRepo, TelegramGroupConfigRepo,
     UserRepo,
@@ -55,12 +59,18 @@ pub struct ModelInfo {
 }
 
 /// Dynamic persona state that can be updated at runtime
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ActivePersona {
     pub id: String,
     pub system_prompt: Option<String>,
 }
 
+/// Persona knowledge swapped wholesale on reload
+///
+/// Readers take the inner `Arc` for the duration of a turn, so a reload
+/// never changes the chunks a running turn sees.
+pub type SharedKnowledge = Arc<RwLock<Arc<Vec<crate::persona::KnowledgeChunk>>>>;
+
 /// Shared sender registry for active WebSocket connections
 pub type WsSenders = Arc<RwLock<HashMap<String, mpsc::Sender<websocket::WsOutgoing>>>>;
 
@@ -105,10 +115,12 @@ pub struct ApiState {
     pub key_provisioner: Option<Arc<crate::providers::KeyProvisioner>>,
     pub local_key_store: Option<crate::providers::LocalKeyStore>,
     pub jwt_cache: Option<Arc<jwt::JwksCache>>,
-    pub persona_knowledge: Vec<crate::persona::KnowledgeChunk>,
+    pub persona_knowledge: SharedKnowledge,
     pub max_context_tokens: usize,
     pub knowledge_cache_dir: PathBuf,
     pub cloud_mode: bool,
+    /// Require credentials on the chat routes outside cloud mode
+    pub require_chat_auth: bool,
     pub rate_limiter: Option<rate_limit::SharedLimiter>,
     /// Active WebSocket senders keyed by user ID, for proactive `ws_push` delivery
     pub ws_senders: Option<WsSenders>,
@@ -120,12 +132,18 @@ pub struct ApiState {
     pub skills_config: crate::config::SkillsConfig,
     /// Agent-level skill filter
     pub skill_filter: crate::skills::SkillFilter,
+    /// Fetches remote skill bodies on first use
+    pub remote_skills: crate::skills::RemoteSkillLoader,
     /// Whether voice input/output is enabled (for config-based eligibility)
     pub voice_enabled: bool,
     /// Hook manager for pre/post message processing
     pub hook_manager: Option<Arc<HookManager>>,
     /// DM security pairing manager
     pub pairing_manager: Option<Arc<PairingManager>>,
+    /// Per-user API keys (set when `AuthMode::ApiKeyPerUser` is active)
+    pub user_api_keys: Option<crate::db::UserApiKeyRepo>,
+    /// Device pairing and administration (mounted at /api/pair and /api/devices)
+    pub device_pairing: Option<Arc<pairing::PairingState>>,
     /// Attachment processor for vision/audio analysis
     pub attachment_processor: Option<Arc<AttachmentProcessor>>,
     /// Telegram update dedup cache
@@ -146,43 +164,120 @@ pub struct ApiState {
     pub reranker: Option<Arc<dyn agent_core::knowledge::Reranker>>,
     /// Direct MCP server manager
     pub mcp_manager: Option<Arc<crate::mcp::McpServerManager>>,
+    /// Send-only channel adapters for proactive delivery
+    pub outbound_channels: OutboundChannels,
+    /// Shared secret for validating Vortex callbacks
+    pub vortex_webhook_secret: Option<String>,
+    /// Accept unsigned Vortex callbacks when no secret is configured
+    pub vortex_allow_unsigned: bool,
+    /// Inbound side of the generic webhook channel
+    pub generic_webhook: Option<crate::channels::WebhookInbound>,
+    /// Tool result truncation/summarization
+    pub tool_output: crate::tools::ToolOutputConfig,
+    /// Maintenance toggle shared with channel handlers
+    pub maintenance: Arc<crate::maintenance::MaintenanceMode>,
+    /// Readiness requirements and recorded startup outcomes
+    pub readiness: Arc<crate::readiness::Readiness>,
+    /// Live relay statuses published by the relay monitor, when relays are enabled
+    pub relay_status: Option<tokio::sync::watch::Receiver<Vec<crate::relay::RelayStatus>>>,
+    /// Circuit breaker shared by everything calling Synapse
+    pub synapse_breaker: Arc<crate::synapse::CircuitBreaker>,
+    /// `WebFetch` result cache shared by every conversation
+    pub fetch_cache: Arc<crate::tools::WebFetchCache>,
+    /// mDNS advertiser, when the gateway advertises itself
+    pub mdns: Option<Arc<crate::discovery::MdnsAdvertiser>>,
+    /// Local per-user daily usage caps
+    pub usage_cap: Arc<crate::usage::UsageCap>,
+    /// Stored chat responses for `Idempotency-Key` replay
+    pub idempotency: chat::IdempotencyCache,
 }
 
 impl ApiState {
     /// Build the system prompt with current enabled skills loaded from the database
     ///
-    /// Uses budget-aware inclusion with per-user scoping.
+    /// Uses budget-aware inclusion with per-user and per-channel scoping.
     /// Falls back to the static `system_prompt` when no skills are installed.
     #[must_use]
-    pub fn system_prompt_with_skills(&self, user_id: Option<&str>) -> String {
+    pub fn system_prompt_with_skills(
+        &self,
+        user_id: Option<&str>,
+        channel: Option<&str>,
+    ) -> String {
+        self.prompt_preview(user_id, channel).prompt
+    }
+
+    /// Memory repository scoped to the currently active persona
+    pub async fn active_memory_repo(&self) -> MemoryRepo {
+        let persona_id = self.active_persona.read().await.id.clone();
+        self.memory_repo.for_persona(persona_id)
+    }
+
+    /// Assemble the system prompt and report which skills made it in
+    #[must_use]
+    pub fn prompt_preview(&self, user_id: Option<&str>, channel: Option<&str>) -> PromptPreview {
         let all_skills = self
             .skill_repo
-            .list_enabled_for_user(user_id)
+            .list_enabled_for_user(user_id, channel)
             .unwrap_or_default();
 
         // Apply agent-level skill filter
-        let skills: Vec<crate::skills::InstalledSkill> = all_skills
+        let (mut skills, filtered): (Vec<_>, Vec<_>) = all_skills
+            .into_iter()
+            .partition(|s| self.skill_filter.allows(&s.skill.metadata.name));
+
+        // Point remote skills at their cache
+        for skill in &mut skills {
+            self.remote_skills.localize(skill);
+        }
+        let dropped_by_filter = filtered
             .into_iter()
-            .filter(|s| self.skill_filter.allows(&s.skill.metadata.name))
+            .map(|s| s.skill.metadata.name)
             .collect();
 
         if skills.is_empty() {
-            return self.system_prompt.clone();
+            return PromptPreview {
+                prompt: self.system_prompt.clone(),
+                included: vec![],
+                dropped_by_budget: vec![],
+                dropped_by_filter,
+            };
         }
         let budget = crate::prompt::PromptBudget {
             max_skills: self.skills_config.max_skills_in_prompt,
             max_chars: self.skills_config.max_skills_prompt_chars,
             voice_enabled: self.voice_enabled,
         };
-        crate::prompt::build_system_prompt_with_budget(
-            &self.persona_name,
-            self.persona_system_prompt.as_deref().unwrap_or_default(),
-            &skills,
-            &budget,
-        )
+        let selection = crate::prompt::select_skills(&skills, &budget);
+
+        PromptPreview {
+            prompt: crate::prompt::build_prompt_from_selection(
+                &self.persona_name,
+                self.persona_system_prompt.as_deref().unwrap_or_default(),
+                &selection,
+            ),
+            included: selection.included_names(),
+            dropped_by_budget: selection
+                .over_budget
+                .iter()
+                .map(|s| s.skill.metadata.name.clone())
+                .collect(),
+            dropped_by_filter,
+        }
     }
 }
 
+/// System prompt as it would be sent, with skill inclusion details
+#[derive(Debug, serde::Serialize)]
+pub struct PromptPreview {
+    pub prompt: String,
+    /// Skills included in the prompt, in prompt order
+    pub included: Vec<String>,
+    /// Eligible skills left out by the prompt budget
+    pub dropped_by_budget: Vec<String>,
+    /// Skills excluded by the agent-level skill filter
+    pub dropped_by_filter: Vec<String>,
+}
+
 /// Configuration for building an API server
 pub struct ApiServerBuilder {
     db: DbPool,
@@ -211,23 +306,46 @@ pub struct ApiServerBuilder {
     local_key_store: Option<crate::providers::LocalKeyStore>,
     jwt_cache: Option<Arc<jwt::JwksCache>>,
     persona_knowledge: Vec<crate::persona::KnowledgeChunk>,
+    active_persona: Option<Arc<RwLock<ActivePersona>>>,
+    shared_knowledge: Option<SharedKnowledge>,
     max_context_tokens: usize,
     knowledge_cache_dir: Option<PathBuf>,
     plugin_manager: Option<plugins::SharedPluginManager>,
+    node_registry: Option<nodes::SharedNodeRegistry>,
     cloud_mode: bool,
+    require_chat_auth: bool,
     billing_state: Option<crate::billing::BillingState>,
     skills_config: crate::config::SkillsConfig,
     voice_enabled: bool,
     hook_manager: Option<Arc<HookManager>>,
     pairing_manager: Option<Arc<PairingManager>>,
+    device_pairing: Option<Arc<pairing::PairingState>>,
+    user_api_keys: Option<crate::db::UserApiKeyRepo>,
     attachment_processor: Option<Arc<AttachmentProcessor>>,
     telegram_config: Option<crate::config::TelegramConfig>,
+    telegram_dedup_path: Option<PathBuf>,
     cron_tools: Option<Arc<crate::tools::BuiltinCronTools>>,
     session_compactor: Option<Arc<crate::context::SessionCompactor>>,
     telegram_registry: Option<TelegramAccountRegistry>,
     condenser: Option<Arc<dyn agent_core::knowledge::QueryCondenser>>,
     reranker: Option<Arc<dyn agent_core::knowledge::Reranker>>,
     mcp_manager: Option<Arc<crate::mcp::McpServerManager>>,
+    outbound_channels: OutboundChannels,
+    vortex_webhook_secret: Option<String>,
+    vortex_allow_unsigned: bool,
+    generic_webhook: Option<crate::channels::WebhookInbound>,
+    tool_output: crate::tools::ToolOutputConfig,
+    maintenance: Arc<crate::maintenance::MaintenanceMode>,
+    memory_scope: crate::db::MemoryScope,
+    memory_ttl: crate::db::MemoryTtl,
+    readiness: Option<Arc<crate::readiness::Readiness>>,
+    relay_status: Option<tokio::sync::watch::Receiver<Vec<crate::relay::RelayStatus>>>,
+    synapse_breaker: Arc<crate::synapse::CircuitBreaker>,
+    fetch_cache: Arc<crate::tools::WebFetchCache>,
+    mdns: Option<Arc<crate::discovery::MdnsAdvertiser>>,
+    usage_cap: Option<Arc<crate::usage::UsageCap>>,
+    scope_policy: auth::ScopePolicy,
+    idempotency_ttl: std::time::Duration,
 }
 
 impl ApiServerBuilder {
@@ -269,23 +387,46 @@ impl ApiServerBuilder {
             local_key_store: None,
             jwt_cache: None,
             persona_knowledge: Vec::new(),
+            active_persona: None,
+            shared_knowledge: None,
             max_context_tokens: 8000,
             knowledge_cache_dir: None,
             plugin_manager: None,
+            node_registry: None,
             cloud_mode: false,
+            require_chat_auth: false,
             billing_state: None,
             skills_config: crate::config::SkillsConfig::default(),
             voice_enabled: false,
             hook_manager: None,
             pairing_manager: None,
+            device_pairing: None,
+            user_api_keys: None,
             attachment_processor: None,
             telegram_config: None,
+            telegram_dedup_path: None,
             cron_tools: None,
             session_compactor: None,
             telegram_registry: None,
             condenser: None,
             reranker: None,
             mcp_manager: None,
+            outbound_channels: OutboundChannels::default(),
+            vortex_webhook_secret: None,
+            vortex_allow_unsigned: false,
+            generic_webhook: None,
+            tool_output: crate::tools::ToolOutputConfig::default(),
+            maintenance: Arc::new(crate::maintenance::MaintenanceMode::default()),
+            memory_scope: crate::db::MemoryScope::default(),
+            memory_ttl: crate::db::MemoryTtl::default(),
+            readiness: None,
+            relay_status: None,
+            synapse_breaker: Arc::default(),
+            fetch_cache: Arc::default(),
+            mdns: None,
+            usage_cap: None,
+            scope_policy: auth::ScopePolicy::default(),
+            idempotency_ttl: chat::DEFAULT_IDEMPOTENCY_TTL,
         }
     }
 
@@ -405,6 +546,23 @@ impl ApiServerBuilder {
         self
     }
 
+    /// Share the active persona with other components (e.g. channel handlers)
+    ///
+    /// Persona switches and reloads through the API then apply to them too.
+    #[must_use]
+    pub fn active_persona(mut self, persona: Arc<RwLock<ActivePersona>>) -> Self {
+        self.active_persona = Some(persona);
+        self
+    }
+
+    /// Share persona knowledge with other components, replacing
+    /// [`Self::persona_knowledge`]
+    #[must_use]
+    pub fn shared_knowledge(mut self, knowledge: SharedKnowledge) -> Self {
+        self.shared_knowledge = Some(knowledge);
+        self
+    }
+
     /// Set maximum context tokens from persona config
     #[must_use]
     pub const fn max_context_tokens(mut self, tokens: usize) -> Self {
@@ -426,6 +584,27 @@ impl ApiServerBuilder {
         self
     }
 
+    /// Require credentials on the chat routes outside cloud mode
+    #[must_use]
+    pub const fn require_chat_auth(mut self, required: bool) -> Self {
+        self.require_chat_auth = required;
+        self
+    }
+
+    /// Set how long chat responses are kept for `Idempotency-Key` replay (default: 24h)
+    #[must_use]
+    pub const fn idempotency_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.idempotency_ttl = ttl;
+        self
+    }
+
+    /// Override the scopes required per route prefix in cloud mode
+    #[must_use]
+    pub fn route_scopes(mut self, overrides: &HashMap<String, String>) -> Self {
+        self.scope_policy = auth::ScopePolicy::with_overrides(overrides);
+        self
+    }
+
     /// Set a pre-built plugin manager (shared with daemon)
     #[must_use]
     pub fn plugin_manager(mut self, pm: plugins::SharedPluginManager) -> Self {
@@ -433,6 +612,13 @@ impl ApiServerBuilder {
         self
     }
 
+    /// Set a pre-built node registry (shared with daemon)
+    #[must_use]
+    pub fn node_registry(mut self, registry: nodes::SharedNodeRegistry) -> Self {
+        self.node_registry = Some(registry);
+        self
+    }
+
     /// Override the billing state (used in tests to inject a pre-configured state)
     #[must_use]
     pub fn billing_state(mut self, state: crate::billing::BillingState) -> Self {
@@ -461,6 +647,20 @@ impl ApiServerBuilder {
         self
     }
 
+    /// Set the device pairing state, enabling the device pairing endpoints
+    #[must_use]
+    pub fn device_pairing(mut self, state: Arc<pairing::PairingState>) -> Self {
+        self.device_pairing = Some(state);
+        self
+    }
+
+    /// Enable per-user API key authentication
+    #[must_use]
+    pub fn user_api_keys(mut self, repo: crate::db::UserApiKeyRepo) -> Self {
+        self.user_api_keys = Some(repo);
+        self
+    }
+
     /// Set the attachment processor for vision/audio analysis
     #[must_use]
     pub fn attachment_processor(mut self, processor: Arc<AttachmentProcessor>) -> Self {
@@ -475,6 +675,13 @@ impl ApiServerBuilder {
         self
     }
 
+    /// Persist the Telegram webhook dedup cache at `path` across restarts
+    #[must_use]
+    pub fn telegram_dedup_path(mut self, path: PathBuf) -> Self {
+        self.telegram_dedup_path = Some(path);
+        self
+    }
+
     /// Set built-in cron tools for scheduling via Vortex
     #[must_use]
     pub fn cron_tools(mut self, tools: Arc<crate::tools::BuiltinCronTools>) -> Self {
@@ -517,23 +724,145 @@ impl ApiServerBuilder {
         self
     }
 
+    /// Register a send-only channel adapter for proactive delivery
+    #[must_use]
+    pub fn outbound_channel(mut self, channel: Arc<dyn Channel>) -> Self {
+        self.outbound_channels.insert(channel);
+        self
+    }
+
+    /// Resolve proactive deliveries through channels provided by extensions
+    #[must_use]
+    pub fn extension_channels(mut self, channels: crate::extensions::ExtensionChannels) -> Self {
+        self.outbound_channels.set_extensions(channels);
+        self
+    }
+
+    /// Set the shared secret for validating Vortex callbacks
+    #[must_use]
+    pub fn vortex_webhook_secret(mut self, secret: Option<String>) -> Self {
+        self.vortex_webhook_secret = secret;
+        self
+    }
+
+    /// Accept unsigned Vortex callbacks when no secret is configured
+    #[must_use]
+    pub const fn vortex_allow_unsigned(mut self, allow: bool) -> Self {
+        self.vortex_allow_unsigned = allow;
+        self
+    }
+
+    /// Set the inbound handle for `POST /api/webhooks/generic`
+    #[must_use]
+    pub fn generic_webhook(mut self, inbound: crate::channels::WebhookInbound) -> Self {
+        self.generic_webhook = Some(inbound);
+        self
+    }
+
+    /// Set how tool results are truncated or summarized
+    #[must_use]
+    pub fn tool_output(mut self, config: crate::tools::ToolOutputConfig) -> Self {
+        self.tool_output = config;
+        self
+    }
+
+    /// Set the shared maintenance toggle
+    #[must_use]
+    pub fn maintenance(mut self, maintenance: Arc<crate::maintenance::MaintenanceMode>) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
+    /// Set how memories are scoped across users and personas
+    #[must_use]
+    pub const fn memory_scope(mut self, scope: crate::db::MemoryScope) -> Self {
+        self.memory_scope = scope;
+        self
+    }
+
+    /// Set per-category memory lifetimes
+    #[must_use]
+    pub const fn memory_ttl(mut self, ttl: crate::db::MemoryTtl) -> Self {
+        self.memory_ttl = ttl;
+        self
+    }
+
+    /// Set the shared readiness tracker (defaults follow cloud mode)
+    #[must_use]
+    pub fn readiness(mut self, readiness: Arc<crate::readiness::Readiness>) -> Self {
+        self.readiness = Some(readiness);
+        self
+    }
+
+    /// Report relay statuses from the relay monitor
+    /// (see [`crate::relay::RelayManager::subscribe`])
+    #[must_use]
+    pub fn relay_status(
+        mut self,
+        status: tokio::sync::watch::Receiver<Vec<crate::relay::RelayStatus>>,
+    ) -> Self {
+        self.relay_status = Some(status);
+        self
+    }
+
+    /// Set the shared Synapse circuit breaker
+    #[must_use]
+    pub fn synapse_breaker(mut self, breaker: Arc<crate::synapse::CircuitBreaker>) -> Self {
+        self.synapse_breaker = breaker;
+        self
+    }
+
+    /// Set the shared `WebFetch` result cache
+    #[must_use]
+    pub fn fetch_cache(mut self, cache: Arc<crate::tools::WebFetchCache>) -> Self {
+        self.fetch_cache = cache;
+        self
+    }
+
+    /// Set the mDNS advertiser whose address `/ready` reports
+    #[must_use]
+    pub fn mdns(mut self, advertiser: Arc<crate::discovery::MdnsAdvertiser>) -> Self {
+        self.mdns = Some(advertiser);
+        self
+    }
+
+    /// Set the shared daily usage caps (default: no caps)
+    #[must_use]
+    pub fn usage_cap(mut self, usage_cap: Arc<crate::usage::UsageCap>) -> Self {
+        self.usage_cap = Some(usage_cap);
+        self
+    }
+
     /// Build the API server
     #[must_use]
     #[allow(clippy::too_many_lines)]
     pub fn build(self) -> ApiServer {
         let session_repo = SessionRepo::new(self.db.clone());
         let user_repo = UserRepo::new(self.db.clone());
-        let memory_repo = MemoryRepo::new(self.db.clone());
+        let memory_repo = MemoryRepo::new(self.db.clone())
+            .with_scope(self.memory_scope, &self.persona_id)
+            .with_ttl(self.memory_ttl);
         let skill_repo = SkillRepo::new(self.db.clone());
         let telegram_group_repo = TelegramGroupConfigRepo::new(self.db.clone());
 
-        // Create embedder and indexer if OPENAI_API_KEY is set
+        // Create embedder from the configured backend; the indexer also needs
+        // OPENAI_API_KEY for fact extraction
         let openai_key = std::env::var("OPENAI_API_KEY").ok();
 
-        let embedder = openai_key
-            .as_deref()
-            .and_then(|key| Embedder::new(key.to_string()).ok())
-            .map(Arc::new);
+        let embedder = match Embedder::from_env() {
+            Ok(embedder) => embedder.map(Arc::new),
+            Err(e) => {
+                tracing::warn!(error = %e, "embedding backend unavailable");
+                None
+            }
+        };
+        if let Some(embedder) = &embedder {
+            tracing::info!(
+                backend = embedder.backend_name(),
+                dimension = embedder.dimension(),
+                "embeddings enabled"
+            );
+        }
 
         let indexer = embedder
             .as_ref()
@@ -561,21 +890,25 @@ impl ApiServerBuilder {
 
         let browser = browser::default_browser();
         let canvas = Arc::new(Mutex::new(Canvas::new()));
-        let node_registry = Arc::new(Mutex::new(NodeRegistry::new()));
+        let node_registry = self
+            .node_registry
+            .unwrap_or_else(|| Arc::new(Mutex::new(NodeRegistry::new())));
         let plugin_manager = self.plugin_manager.unwrap_or_else(|| {
             let mut pm = crate::plugins::PluginManager::new();
             let dirs = crate::plugins::default_plugin_dirs();
-            let loaded = pm.load_all(&dirs);
+            let loaded = pm.load_all(&dirs).loaded;
             if !loaded.is_empty() {
                 tracing::info!(count = loaded.len(), plugins = ?loaded, "loaded plugins");
             }
             Arc::new(Mutex::new(pm))
         });
 
-        let active_persona = Arc::new(RwLock::new(ActivePersona {
-            id: self.persona_id.clone(),
-            system_prompt: self.persona_system_prompt.clone(),
-        }));
+        let active_persona = self.active_persona.unwrap_or_else(|| {
+            Arc::new(RwLock::new(ActivePersona {
+                id: self.persona_id.clone(),
+                system_prompt: self.persona_system_prompt.clone(),
+            }))
+        });
 
         let rate_limiter = if self.cloud_mode {
             Some(rate_limit::create_limiter(120))
@@ -583,11 +916,34 @@ impl ApiServerBuilder {
             None
         };
 
+        let usage_cap = self.usage_cap.unwrap_or_else(|| {
+            Arc::new(crate::usage::UsageCap::new(
+                self.db.clone(),
+                crate::usage::UsageCapConfig::default(),
+            ))
+        });
+
+        let readiness = self.readiness.unwrap_or_else(|| {
+            Arc::new(crate::readiness::Readiness::new(
+                &crate::readiness::ReadinessConfig::default(),
+                self.cloud_mode,
+            ))
+        });
+
         let billing_state = if self.cloud_mode {
-            crate::billing::BillingState::from_env().unwrap_or_else(|e| {
-                tracing::error!(error = %e, "billing initialization failed, running without billing");
-                None
-            })
+            match crate::billing::BillingState::from_env() {
+                Ok(state) => {
+                    if state.is_some() {
+                        readiness.record_billing(true);
+                    }
+                    state
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "billing initialization failed, running without billing");
+                    readiness.record_billing(false);
+                    None
+                }
+            }
         } else {
             None
         };
@@ -639,25 +995,32 @@ impl ApiServerBuilder {
             key_provisioner: self.key_provisioner,
             local_key_store: self.local_key_store,
             jwt_cache: self.jwt_cache,
-            persona_knowledge: self.persona_knowledge,
+            persona_knowledge: self
+                .shared_knowledge
+                .unwrap_or_else(|| Arc::new(RwLock::new(Arc::new(self.persona_knowledge)))),
             max_context_tokens: self.max_context_tokens,
             knowledge_cache_dir: self
                 .knowledge_cache_dir
                 .unwrap_or_else(|| PathBuf::from(".cache/omni/knowledge")),
             cloud_mode: self.cloud_mode,
+            require_chat_auth: self.require_chat_auth,
             rate_limiter,
             ws_senders: Some(Arc::new(RwLock::new(HashMap::new()))),
             billing_state,
             usage_recorder,
             skill_filter: self.skills_config.skill_filter.clone(),
+            remote_skills: crate::skills::RemoteSkillLoader::for_config(&self.skills_config),
             voice_enabled: self.voice_enabled,
             skills_config: self.skills_config,
             hook_manager: self.hook_manager,
             pairing_manager: self.pairing_manager,
+            device_pairing: self.device_pairing,
+            user_api_keys: self.user_api_keys,
             attachment_processor: self.attachment_processor,
-            telegram_dedup: Arc::new(std::sync::Mutex::new(
-                crate::channels::UpdateDedup::default(),
-            )),
+            telegram_dedup: Arc::new(std::sync::Mutex::new(self.telegram_dedup_path.map_or_else(
+                crate::channels::UpdateDedup::default,
+                crate::channels::UpdateDedup::persistent,
+            ))),
             telegram_config: self.telegram_config,
             telegram_group_repo,
             cron_tools: self.cron_tools,
@@ -666,12 +1029,26 @@ impl ApiServerBuilder {
             condenser,
             reranker: self.reranker,
             mcp_manager: self.mcp_manager,
+            outbound_channels: self.outbound_channels,
+            vortex_webhook_secret: self.vortex_webhook_secret,
+            vortex_allow_unsigned: self.vortex_allow_unsigned,
+            generic_webhook: self.generic_webhook,
+            tool_output: self.tool_output,
+            maintenance: self.maintenance,
+            readiness,
+            relay_status: self.relay_status,
+            synapse_breaker: self.synapse_breaker,
+            fetch_cache: self.fetch_cache,
+            mdns: self.mdns,
+            usage_cap,
+            idempotency: chat::IdempotencyCache::new(self.idempotency_ttl),
         });
 
         ApiServer {
             state,
             port: self.port,
             static_dir: self.static_dir,
+            scope_policy: Arc::new(self.scope_policy),
         }
     }
 }
@@ -681,6 +1058,7 @@ pub struct ApiServer {
     state: Arc<ApiState>,
     port: u16,
     static_dir: Option<PathBuf>,
+    scope_policy: Arc<auth::ScopePolicy>,
 }
 
 impl ApiServer {
@@ -689,12 +1067,15 @@ impl ApiServer {
     pub fn context_config(
         persona_id: &str,
         persona_system_prompt: Option<String>,
+        model: &str,
     ) -> ContextConfig {
         ContextConfig {
             max_messages: 20,
             max_tokens: 4000,
+            tokenizer: Tokenizer::for_model(model),
             persona_id: persona_id.to_string(),
             max_memories: 10,
+            memory_selection: MemorySelection::Relevant,
             persona_system_prompt,
         }
     }
@@ -707,6 +1088,7 @@ impl ApiServer {
                 "/api/canvas",
                 canvas::api::router(self.state.canvas.clone()),
             )
+            .nest("/api/chat", chat::router(self.state.clone()))
             .nest("/api/providers", providers::router(self.state.clone()))
             .nest("/api/knowledge", knowledge::router(self.state.clone()))
             .nest("/api/memories", life_json::router(self.state.clone()))
@@ -720,17 +1102,33 @@ impl ApiServer {
             .nest("/api/browser", browser::router(self.state.browser.clone()))
             .nest(
                 "/api/nodes",
-                nodes::router(self.state.node_registry.clone()),
+                nodes::router(self.state.node_registry.clone(), self.state.clone()),
             )
             .nest(
                 "/api/plugins",
                 plugins::router(self.state.plugin_manager.clone()),
             )
             .nest("/ws", websocket::router(self.state.clone()))
-            .nest("/ws", nodes::ws_router(self.state.node_registry.clone()))
+            .nest(
+                "/ws",
+                nodes::ws_router(self.state.node_registry.clone(), self.state.clone()),
+            )
             .nest("/ws/canvas", canvas::router(self.state.canvas.clone()))
             .merge(health::router())
-            .merge(health::ready_router(self.state.clone()));
+            .merge(health::ready_router(self.state.clone()))
+            .merge(personas::local_router(self.state.clone()));
+
+        if let Some(pairing) = self.state.device_pairing.clone() {
+            router = router
+                .nest(
+                    "/api/pair",
+                    pairing::router(Arc::clone(&pairing), self.state.clone()),
+                )
+                .nest(
+                    "/api/devices",
+                    pairing::devices_router(pairing, self.state.clone()),
+                );
+        }
 
         // Serve static files if configured
         if let Some(static_dir) = &self.static_dir {
@@ -758,6 +1156,12 @@ impl ApiServer {
             router
         };
 
+        // Route scope authorization (cloud mode only)
+        let router = router.layer(axum::middleware::from_fn_with_state(
+            (self.state.clone(), Arc::clone(&self.scope_policy)),
+            auth::enforce_scope_policy,
+        ));
+
         // Rate limiting (cloud mode only)
         let router = router.layer(axum::middleware::from_fn_with_state(
             self.state.clone(),
@@ -770,7 +1174,11 @@ impl ApiServer {
             .allow_methods(Any)
             .allow_headers(Any);
 
-        router.layer(cors).layer(TraceLayer::new_for_http())
+        // Request ids wrap tracing so every span of a request carries the id
+        router
+            .layer(cors)
+            .layer(TraceLayer::new_for_http())
+            .layer(axum::middleware::from_fn(request_id::request_id_middleware))
     }
 
     /// Run the API server
@@ -795,6 +1203,19 @@ impl ApiServer {
 
         tracing::info!(port = self.port, "API server listening");
 
+        // Retry queued scheduled deliveries once their channel is reachable again
+        let state = Arc::clone(&self.state);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(webhooks::vortex::OUTBOX_RETRY_INTERVAL);
+            loop {
+                interval.tick().await;
+                let delivered = webhooks::vortex::retry_outbox(&state).await;
+                if delivered > 0 {
+                    tracing::info!(delivered, "outbox messages delivered");
+                }
+            }
+        });
+
         axum::serve(listener, self.router())
             .await
             .map_err(|e| crate::Error::Config(format!("API server error: {e}")))?;
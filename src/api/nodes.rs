@@ -2,8 +2,10 @@
 //!
 //! WebSocket endpoint for node connections and REST endpoints
 //! for listing nodes and invoking commands
+//!
+//! Both routers require the API key (or an admin-scoped credential): nodes
+//! run commands on the devices they control.
 
-use std::collections::HashSet;
 use std::sync::Arc;
 
 use axum::{
@@ -13,18 +15,22 @@ use axum::{
         ws::{Message, WebSocket},
     },
     http::StatusCode,
+    middleware,
     response::IntoResponse,
     routing::{get, post},
 };
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use tokio::sync::Mutex;
+use tokio::sync::mpsc;
 
-use crate::nodes::policy::is_command_allowed;
-use crate::nodes::{InvokeResult, NodeRegistration, NodeRegistry, NodeSession};
+use super::ApiState;
+use super::auth::{RequireScope, require_api_key, require_scope};
 
-/// Shared node registry state
-pub type SharedNodeRegistry = Arc<Mutex<NodeRegistry>>;
+pub use crate::nodes::SharedNodeRegistry;
+use crate::nodes::{
+    InvokeError, InvokeRequest, InvokeResult, KnownNode, NodeDispatch, NodeRegistration,
+    NodeRegistry, NodeSession, NodeStatus,
+};
 
 /// Query parameters for node WebSocket connection
 #[derive(Debug, Deserialize)]
@@ -35,7 +41,6 @@ struct NodeWsQuery {
 /// Outgoing message from gateway to node
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
-#[allow(dead_code)]
 enum GatewayToNode {
     /// Registration accepted
     Registered { node_id: String },
@@ -45,6 +50,8 @@ enum GatewayToNode {
         command: String,
         params: serde_json::Value,
     },
+    /// Abort an invocation the gateway stopped waiting for
+    Cancel { correlation_id: String },
     /// Error message
     Error { code: String, message: String },
 }
@@ -94,6 +101,14 @@ impl From<&NodeSession> for NodeResponse {
     }
 }
 
+/// REST response for listing known device registrations
+#[derive(Serialize)]
+pub struct RegistrationResponse {
+    #[serde(flatten)]
+    pub node: KnownNode,
+    pub status: NodeStatus,
+}
+
 /// REST request for invoking a command
 #[derive(Deserialize)]
 pub struct InvokeBody {
@@ -108,29 +123,86 @@ const fn default_timeout_ms() -> u64 {
     30_000
 }
 
+/// REST request for invoking a command on any capable node
+#[derive(Deserialize)]
+pub struct InvokeAnyBody {
+    /// Capability the node must declare (e.g. `shell`)
+    pub capability: String,
+    /// Restrict to nodes on this platform (e.g. `linux`)
+    pub platform: Option<String>,
+    #[serde(flatten)]
+    pub invoke: InvokeBody,
+}
+
 /// REST response for invoke result
 #[derive(Serialize)]
 pub struct InvokeResponse {
+    /// Node that handled the invocation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
     pub ok: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
 }
 
-/// Build node routes
-pub fn router(registry: SharedNodeRegistry) -> Router {
+impl From<InvokeResult> for InvokeResponse {
+    fn from(result: InvokeResult) -> Self {
+        Self {
+            node_id: None,
+            ok: result.ok,
+            payload: result.payload,
+            error: result.error,
+            timed_out: result.timed_out,
+        }
+    }
+}
+
+impl From<NodeDispatch> for GatewayToNode {
+    fn from(dispatch: NodeDispatch) -> Self {
+        match dispatch {
+            NodeDispatch::Invoke {
+                correlation_id,
+                command,
+                params,
+            } => Self::Invoke {
+                correlation_id,
+                command,
+                params,
+            },
+            NodeDispatch::Cancel { correlation_id } => Self::Cancel { correlation_id },
+        }
+    }
+}
+
+/// Build node routes (admin-authenticated)
+pub fn router(registry: SharedNodeRegistry, api_state: Arc<ApiState>) -> Router {
     Router::new()
         .route("/", get(list_nodes))
+        .route("/registrations", get(list_registrations))
+        .route("/invoke-any", post(invoke_any))
         .route("/{node_id}", get(get_node))
         .route("/{node_id}/invoke", post(invoke_node))
+        .layer(middleware::from_fn_with_state(
+            RequireScope("admin"),
+            require_scope,
+        ))
+        .layer(middleware::from_fn_with_state(api_state, require_api_key))
         .with_state(registry)
 }
 
-/// Build node WebSocket router
-pub fn ws_router(registry: SharedNodeRegistry) -> Router {
+/// Build node WebSocket router (admin-authenticated)
+pub fn ws_router(registry: SharedNodeRegistry, api_state: Arc<ApiState>) -> Router {
     Router::new()
         .route("/node", get(ws_upgrade))
+        .layer(middleware::from_fn_with_state(
+            RequireScope("admin"),
+            require_scope,
+        ))
+        .layer(middleware::from_fn_with_state(api_state, require_api_key))
         .with_state(registry)
 }
 
@@ -151,6 +223,7 @@ async fn handle_node_socket(
     device_id: Option<String>,
 ) {
     let (mut sender, mut receiver) = socket.split();
+    let (outbox_tx, mut outbox_rx) = mpsc::unbounded_channel::<NodeDispatch>();
     let mut node_id: Option<String> = None;
 
     tracing::info!(device_id = ?device_id, "node WebSocket connected, awaiting registration");
@@ -174,7 +247,12 @@ async fn handle_node_socket(
 
         match incoming {
             NodeToGateway::Register(registration) => {
-                let id = registry.lock().await.register(registration);
+                let id = {
+                    let mut reg = registry.lock().await;
+                    let id = reg.register(registration);
+                    reg.attach(&id, outbox_tx.clone());
+                    id
+                };
                 node_id = Some(id.clone());
 
                 let ack = GatewayToNode::Registered {
@@ -207,8 +285,23 @@ async fn handle_node_socket(
         return;
     };
 
-    // Handle ongoing messages (invoke responses)
-    while let Some(Ok(msg)) = receiver.next().await {
+    // Forward dispatches to the node and handle its messages (invoke responses)
+    loop {
+        let msg = tokio::select! {
+            Some(dispatch) = outbox_rx.recv() => {
+                let Ok(json) = serde_json::to_string(&GatewayToNode::from(dispatch)) else {
+                    continue;
+                };
+                if sender.send(Message::Text(json.into())).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+            msg = receiver.next() => match msg {
+                Some(Ok(msg)) => msg,
+                _ => break,
+            },
+        };
         match msg {
             Message::Text(text) => {
                 let Ok(incoming) = serde_json::from_str::<NodeToGateway>(&text) else {
@@ -222,16 +315,22 @@ async fn handle_node_socket(
                         payload,
                         error,
                     } => {
-                        let result = InvokeResult { ok, payload, error };
+                        let result = InvokeResult {
+                            ok,
+                            payload,
+                            error,
+                            timed_out: false,
+                        };
                         let mut reg = registry.lock().await;
-                        if !reg.handle_response(&correlation_id, result) {
+                        if !reg.handle_response(&node_id, &correlation_id, result) {
                             tracing::warn!(
+                                node_id = %node_id,
                                 correlation_id = %correlation_id,
-                                "no pending invocation for correlation ID"
+                                "no pending invocation for correlation ID on this node"
                             );
                         }
                     }
-                    NodeToGateway::Ping => {}
+                    NodeToGateway::Ping => registry.lock().await.touch(&node_id),
                     NodeToGateway::Register(_) => {
                         tracing::warn!(node_id = %node_id, "duplicate registration ignored");
                     }
@@ -259,6 +358,24 @@ async fn list_nodes(State(registry): State<SharedNodeRegistry>) -> Json<Vec<Node
     Json(nodes)
 }
 
+/// List every known device, including stale ones awaiting reconnection
+async fn list_registrations(
+    State(registry): State<SharedNodeRegistry>,
+) -> Json<Vec<RegistrationResponse>> {
+    let mut registrations: Vec<RegistrationResponse> = registry
+        .lock()
+        .await
+        .registrations()
+        .into_iter()
+        .map(|(node, status)| RegistrationResponse {
+            node: node.clone(),
+            status,
+        })
+        .collect();
+    registrations.sort_by(|a, b| b.node.last_seen.cmp(&a.node.last_seen));
+    Json(registrations)
+}
+
 /// Get a specific node
 async fn get_node(
     State(registry): State<SharedNodeRegistry>,
@@ -276,62 +393,72 @@ async fn invoke_node(
     Path(node_id): Path<String>,
     Json(body): Json<InvokeBody>,
 ) -> Result<Json<InvokeResponse>, (StatusCode, Json<InvokeResponse>)> {
-    let err = |code: StatusCode, msg: &str| {
-        (
-            code,
-            Json(InvokeResponse {
-                ok: false,
-                payload: None,
-                error: Some(msg.to_string()),
-            }),
+    let request = InvokeRequest {
+        node_id,
+        command: body.command,
+        params: body.params,
+        timeout_ms: body.timeout_ms,
+        idempotency_key: body.idempotency_key,
+    };
+    run_invoke(&registry, request).await
+}
+
+/// Invoke a command on any node with a capability, chosen round-robin
+///
+/// Any capable device may be picked, so this sits behind the same admin auth
+/// as direct invocation.
+async fn invoke_any(
+    State(registry): State<SharedNodeRegistry>,
+    Json(body): Json<InvokeAnyBody>,
+) -> Result<Json<InvokeResponse>, (StatusCode, Json<InvokeResponse>)> {
+    let node_id = registry
+        .lock()
+        .await
+        .pick_by_capability(
+            &body.capability,
+            body.platform.as_deref(),
+            &body.invoke.command,
         )
+        .map_err(invoke_error)?;
+
+    let request = InvokeRequest {
+        node_id,
+        command: body.invoke.command,
+        params: body.invoke.params,
+        timeout_ms: body.invoke.timeout_ms,
+        idempotency_key: body.invoke.idempotency_key,
     };
+    run_invoke(&registry, request).await
+}
 
-    // Check command policy
-    let (platform, declared_commands) = {
-        let reg = registry.lock().await;
-        let node = reg.get(&node_id).ok_or_else(|| {
-            err(
-                StatusCode::NOT_FOUND,
-                &format!("node '{node_id}' not found"),
-            )
-        })?;
-        let result = (node.platform.clone(), node.commands.clone());
-        drop(reg);
-        result
+/// Dispatch an invocation and map its outcome to a response
+async fn run_invoke(
+    registry: &SharedNodeRegistry,
+    request: InvokeRequest,
+) -> Result<Json<InvokeResponse>, (StatusCode, Json<InvokeResponse>)> {
+    let node_id = request.node_id.clone();
+    let respond = |result: InvokeResult| {
+        Json(InvokeResponse {
+            node_id: Some(node_id.clone()),
+            ..InvokeResponse::from(result)
+        })
     };
 
-    let deny_list = HashSet::new();
-    if !is_command_allowed(&platform, &declared_commands, &deny_list, &body.command) {
-        return Err(err(
-            StatusCode::FORBIDDEN,
-            &format!(
-                "command '{}' not allowed for platform '{platform}'",
-                body.command
-            ),
-        ));
+    match NodeRegistry::invoke(registry, request).await {
+        Ok(result) if result.timed_out => Err((StatusCode::GATEWAY_TIMEOUT, respond(result))),
+        Ok(result) => Ok(respond(result)),
+        Err(e) => Err(invoke_error(e)),
     }
+}
 
-    // Prepare the invocation
-    let (_correlation_id, rx) = {
-        let mut reg = registry.lock().await;
-        reg.prepare_invoke(&node_id)
-            .map_err(|e| err(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))?
+fn invoke_error(e: InvokeError) -> (StatusCode, Json<InvokeResponse>) {
+    let status = match e {
+        InvokeError::NotFound(_) | InvokeError::NoCapableNode(_) => StatusCode::NOT_FOUND,
+        InvokeError::NotAllowed { .. } => StatusCode::FORBIDDEN,
+        InvokeError::Disconnected(_) => StatusCode::BAD_GATEWAY,
     };
-
-    // TODO: send invoke message to node via WebSocket sender
-    // For now, we wait for the response with a timeout
-    let timeout = tokio::time::Duration::from_millis(body.timeout_ms);
-    match tokio::time::timeout(timeout, rx).await {
-        Ok(Ok(result)) => Ok(Json(InvokeResponse {
-            ok: result.ok,
-            payload: result.payload,
-            error: result.error,
-        })),
-        Ok(Err(_)) => Err(err(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "invocation channel closed unexpectedly",
-        )),
-        Err(_) => Err(err(StatusCode::GATEWAY_TIMEOUT, "invocation timed out")),
-    }
+    (
+        status,
+        Json(InvokeResponse::from(InvokeResult::failed(e.to_string()))),
+    )
 }
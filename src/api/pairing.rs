@@ -1,24 +1,41 @@
 //! Device pairing API endpoints
 //!
-//! Provides endpoints for pairing new devices with the gateway:
+//! Provides endpoints for pairing new devices with the gateway. Issuing and
+//! confirming codes is an admin action (API key / admin scope required); the
+//! confirming admin approves the pairing and becomes the device's owner:
 //! - POST /api/pair/request - Generate a pairing code
 //! - POST /api/pair/confirm - Complete pairing with code + device public key
 //! - GET /api/pair/pending - List pending pairing requests
-//! - DELETE /api/devices/{id} - Revoke a paired device
+//!
+//! Public, for paired devices:
+//! - POST /api/pair/challenge - Get a nonce to sign
+//! - POST /api/pair/session - Exchange a signed challenge for a device session
+//!   token, accepted as a Bearer token by the chat and admin routes once the
+//!   pairing is approved
+//!
+//! Device administration (API key / admin scope required):
+//! - GET /api/devices - List paired devices (`?user_id=` for one user's)
+//! - POST /api/devices/{id}/approve - Approve a device paired without approval
+//! - POST /api/devices/{id}/revoke - Revoke a device and end its sessions
+//! - DELETE /api/devices/{id} - Remove a paired device
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::{
-    Json, Router,
-    extract::{Path, State},
+    Extension, Json, Router,
+    extract::{Path, Query, State},
     http::StatusCode,
+    middleware,
     response::IntoResponse,
     routing::{delete, get, post},
 };
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
+use super::ApiState;
+use super::auth::{AuthIdentity, RequireScope, require_api_key, require_scope};
+use super::chat::caller_user_id;
 use crate::security::auth::{AuthChallenge, PairingRequest};
 use crate::security::{DeviceIdentity, DeviceManager, PairedDevice, TrustLevel};
 
@@ -66,23 +83,42 @@ impl PairingState {
 }
 
 /// Build pairing router
-pub fn router(state: Arc<PairingState>) -> Router {
-    Router::new()
+///
+/// Code issuance and confirmation are admin-authenticated; the challenge and
+/// session routes stay public for paired devices.
+pub fn router(state: Arc<PairingState>, api_state: Arc<ApiState>) -> Router {
+    let admin = Router::new()
         .route("/request", post(request_pairing))
         .route("/confirm", post(confirm_pairing))
         .route("/pending", get(list_pending))
+        .layer(middleware::from_fn_with_state(
+            RequireScope("admin"),
+            require_scope,
+        ))
+        .layer(middleware::from_fn_with_state(api_state, require_api_key));
+
+    Router::new()
+        .merge(admin)
         .route("/challenge", post(create_challenge))
+        .route("/session", post(create_session))
         .route("/gateway", get(get_gateway_info))
         .with_state(state)
 }
 
-/// Build devices router
-pub fn devices_router(state: Arc<PairingState>) -> Router {
+/// Build devices router (admin-authenticated)
+pub fn devices_router(state: Arc<PairingState>, api_state: Arc<ApiState>) -> Router {
     Router::new()
         .route("/", get(list_devices))
         .route("/{device_id}", get(get_device))
-        .route("/{device_id}", delete(revoke_device))
+        .route("/{device_id}", delete(remove_device))
+        .route("/{device_id}/approve", post(approve_device))
+        .route("/{device_id}/revoke", post(revoke_device))
         .route("/{device_id}/trust", post(update_trust))
+        .layer(middleware::from_fn_with_state(
+            RequireScope("admin"),
+            require_scope,
+        ))
+        .layer(middleware::from_fn_with_state(api_state, require_api_key))
         .with_state(state)
 }
 
@@ -152,6 +188,12 @@ pub struct PairedDeviceInfo {
     pub trust_level: String,
     pub paired_at: String,
     pub last_seen: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
 }
 
 impl From<PairedDevice> for PairedDeviceInfo {
@@ -163,6 +205,9 @@ impl From<PairedDevice> for PairedDeviceInfo {
             trust_level: d.trust_level.to_string(),
             paired_at: d.paired_at.to_rfc3339(),
             last_seen: d.last_seen.to_rfc3339(),
+            user_id: d.user_id,
+            revoked_at: d.revoked_at.map(|t| t.to_rfc3339()),
+            approved_by: d.approved_by,
         }
     }
 }
@@ -180,6 +225,36 @@ pub struct ChallengeResponse {
     pub expires_in: i64,
 }
 
+/// Request body for exchanging a signed challenge for a session
+#[derive(Debug, Deserialize)]
+pub struct CreateSessionBody {
+    /// Challenge ID from POST /challenge
+    pub challenge_id: String,
+
+    /// Paired device ID
+    pub device_id: String,
+
+    /// Device's signature over the challenge nonce (base64)
+    pub signature: String,
+}
+
+/// Response for a new device session
+#[derive(Debug, Serialize)]
+pub struct CreateSessionResponse {
+    /// Bearer token for the session
+    pub token: String,
+
+    /// Seconds until the session expires
+    pub expires_in: i64,
+}
+
+/// Query for listing devices
+#[derive(Debug, Deserialize)]
+pub struct ListDevicesQuery {
+    /// Only list devices belonging to this user
+    pub user_id: Option<String>,
+}
+
 /// Gateway info response
 #[derive(Debug, Serialize)]
 pub struct GatewayInfoResponse {
@@ -232,11 +307,26 @@ async fn request_pairing(
     (StatusCode::CREATED, Json(response))
 }
 
+/// Name recorded as the approver of an admin action
+fn approver(identity: Option<&Extension<AuthIdentity>>) -> String {
+    identity.map_or_else(
+        || "anonymous".to_string(),
+        |Extension(identity)| identity.user_id.clone(),
+    )
+}
+
 /// Confirm pairing with code and device public key
+///
+/// The authenticated admin approves the pairing and, when they act as a
+/// specific user, owns the device.
 async fn confirm_pairing(
     State(state): State<Arc<PairingState>>,
+    identity: Option<Extension<AuthIdentity>>,
     Json(body): Json<ConfirmPairingBody>,
 ) -> impl IntoResponse {
+    let approved_by = approver(identity.as_ref());
+    let owner = caller_user_id(identity);
+
     // Find matching pending request by code
     let request = {
         let requests = state.pending_requests.read().await;
@@ -276,6 +366,21 @@ async fn confirm_pairing(
         }
     };
 
+    if let Err(e) = state.device_manager.approve(&device.id, &approved_by) {
+        tracing::error!(error = %e, device_id = %device.id, "failed to record pairing approval");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    if let Some(ref user_id) = owner
+        && let Err(e) = state.device_manager.assign_user(&device.id, user_id)
+    {
+        tracing::warn!(error = %e, device_id = %device.id, "failed to assign device to user");
+    }
+    let device = PairedDevice {
+        user_id: owner,
+        approved_by: Some(approved_by),
+        ..device
+    };
+
     // Remove the used pairing request
     {
         let mut requests = state.pending_requests.write().await;
@@ -334,6 +439,54 @@ async fn create_challenge(State(state): State<Arc<PairingState>>) -> impl IntoRe
     (StatusCode::CREATED, Json(response))
 }
 
+/// Exchange a signed challenge for a device session
+async fn create_session(
+    State(state): State<Arc<PairingState>>,
+    Json(body): Json<CreateSessionBody>,
+) -> impl IntoResponse {
+    // Challenges are single-use
+    let challenge = state.challenges.write().await.remove(&body.challenge_id);
+    let Some(challenge) = challenge.filter(|c| !c.is_expired()) else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "invalid or expired challenge"})),
+        )
+            .into_response();
+    };
+
+    let verified = state.device_manager.verify_signature(
+        &body.device_id,
+        &challenge.payload(),
+        &body.signature,
+    );
+    if !matches!(verified, Ok(true)) {
+        if let Err(e) = verified {
+            tracing::warn!(device_id = %body.device_id, error = %e, "device authentication failed");
+        }
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "signature verification failed"})),
+        )
+            .into_response();
+    }
+
+    match state.device_manager.create_session(&body.device_id) {
+        Ok((token, expires_at)) => (
+            StatusCode::CREATED,
+            Json(CreateSessionResponse {
+                token,
+                expires_in: (expires_at - chrono::Utc::now()).num_seconds(),
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
 /// Get gateway info
 async fn get_gateway_info(State(state): State<Arc<PairingState>>) -> impl IntoResponse {
     Json(GatewayInfoResponse {
@@ -344,9 +497,16 @@ async fn get_gateway_info(State(state): State<Arc<PairingState>>) -> impl IntoRe
     })
 }
 
-/// List all paired devices
-async fn list_devices(State(state): State<Arc<PairingState>>) -> impl IntoResponse {
-    match state.device_manager.list() {
+/// List paired devices, optionally only one user's
+async fn list_devices(
+    State(state): State<Arc<PairingState>>,
+    Query(query): Query<ListDevicesQuery>,
+) -> impl IntoResponse {
+    let devices = match query.user_id {
+        Some(ref user_id) => state.device_manager.list_devices(user_id),
+        None => state.device_manager.list(),
+    };
+    match devices {
         Ok(devices) => {
             let infos: Vec<PairedDeviceInfo> = devices.into_iter().map(Into::into).collect();
             Ok(Json(infos))
@@ -367,14 +527,42 @@ async fn get_device(
     }
 }
 
-/// Revoke a paired device
+/// Approve a device that was paired without admin approval
+async fn approve_device(
+    State(state): State<Arc<PairingState>>,
+    identity: Option<Extension<AuthIdentity>>,
+    Path(device_id): Path<String>,
+) -> impl IntoResponse {
+    match state
+        .device_manager
+        .approve(&device_id, &approver(identity.as_ref()))
+    {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Revoke a paired device, keeping its record
 async fn revoke_device(
     State(state): State<Arc<PairingState>>,
     Path(device_id): Path<String>,
+) -> impl IntoResponse {
+    match state.device_manager.revoke(&device_id) {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Remove a paired device
+async fn remove_device(
+    State(state): State<Arc<PairingState>>,
+    Path(device_id): Path<String>,
 ) -> impl IntoResponse {
     match state.device_manager.remove(&device_id) {
         Ok(true) => {
-            tracing::info!(device_id = %device_id, "device revoked");
+            tracing::info!(device_id = %device_id, "device removed");
             StatusCode::NO_CONTENT
         }
         Ok(false) => StatusCode::NOT_FOUND,
@@ -398,6 +586,7 @@ async fn update_trust(
             tracing::info!(device_id = %device_id, trust_level = %trust_level, "trust level updated");
             StatusCode::OK
         }
+        Err(crate::Error::Auth(_)) => StatusCode::CONFLICT,
         Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
@@ -405,6 +594,7 @@ async fn update_trust(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::auth::AuthMethod;
     use crate::db::init_memory;
 
     fn setup() -> Arc<PairingState> {
@@ -434,4 +624,100 @@ mod tests {
         state.cleanup_expired().await;
         assert_eq!(state.pending_requests.read().await.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_confirm_takes_owner_from_approver() {
+        let state = setup();
+        let request = PairingRequest::generate();
+        let code = request.code.clone();
+        state
+            .pending_requests
+            .write()
+            .await
+            .insert(request.id.clone(), request);
+
+        let device = DeviceIdentity::generate("tablet");
+        let identity = AuthIdentity {
+            user_id: "alice".to_string(),
+            method: AuthMethod::UserApiKey,
+            scopes: vec!["admin".to_string()],
+        };
+        let status = confirm_pairing(
+            State(Arc::clone(&state)),
+            Some(Extension(identity)),
+            Json(ConfirmPairingBody {
+                code,
+                public_key: device.public_key.clone(),
+                device_id: device.device_id.clone(),
+                device_name: "tablet".to_string(),
+                platform: None,
+            }),
+        )
+        .await
+        .into_response()
+        .status();
+        assert_eq!(status, StatusCode::OK);
+
+        let paired = state
+            .device_manager
+            .get(&device.device_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(paired.user_id.as_deref(), Some("alice"));
+        assert_eq!(paired.approved_by.as_deref(), Some("alice"));
+        assert!(state.pending_requests.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_revoked_device_cannot_open_session() {
+        let state = setup();
+        let device = DeviceIdentity::generate("tablet");
+        state
+            .device_manager
+            .register(
+                &device.device_id,
+                &device.public_key,
+                "tablet",
+                None,
+                TrustLevel::Paired,
+            )
+            .unwrap();
+
+        let sign_in = |state: Arc<PairingState>, device: DeviceIdentity| async move {
+            let challenge = AuthChallenge::generate();
+            let signature = device.sign(&challenge.payload()).unwrap();
+            state
+                .challenges
+                .write()
+                .await
+                .insert("ch".to_string(), challenge);
+            create_session(
+                State(state),
+                Json(CreateSessionBody {
+                    challenge_id: "ch".to_string(),
+                    device_id: device.device_id.clone(),
+                    signature,
+                }),
+            )
+            .await
+            .into_response()
+            .status()
+        };
+
+        assert_eq!(
+            sign_in(Arc::clone(&state), device.clone()).await,
+            StatusCode::CREATED
+        );
+
+        let status = revoke_device(State(Arc::clone(&state)), Path(device.device_id.clone()))
+            .await
+            .into_response()
+            .status();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        assert_eq!(
+            sign_in(Arc::clone(&state), device).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
 }
@@ -1,4 +1,4 @@
-//! Personas API endpoints for marketplace integration
+//! Personas API endpoints for marketplace integration and local authoring
 
 use std::sync::Arc;
 
@@ -6,12 +6,15 @@ use axum::{
     Json, Router,
     extract::{Path, Query, State},
     http::StatusCode,
-    routing::{get, post},
+    middleware,
+    routing::{get, post, put},
 };
 use serde::{Deserialize, Serialize};
 
 use super::ApiState;
+use super::auth::{RequireScope, require_api_key, require_scope};
 use crate::Persona;
+use crate::config::local_persona_marker;
 use crate::db::PersonaRepo;
 use crate::skills::ManifoldClient;
 
@@ -25,6 +28,28 @@ pub fn router(state: Arc<ApiState>) -> Router {
         .with_state(state)
 }
 
+/// Build the router for locally authored personas
+///
+/// Writes need the admin key; listing is open like `GET /api/personas`.
+pub fn local_router(state: Arc<ApiState>) -> Router {
+    let writes = Router::new()
+        .route("/api/personas", post(create_local))
+        .route("/api/personas/{persona_id}", put(save_local))
+        .route_layer(middleware::from_fn_with_state(
+            RequireScope("admin"),
+            require_scope,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_api_key,
+        ));
+
+    Router::new()
+        .route("/api/personas/local", get(list_local))
+        .merge(writes)
+        .with_state(state)
+}
+
 /// Persona info for API responses
 #[derive(Debug, Serialize)]
 pub struct PersonaResponse {
@@ -134,6 +159,7 @@ async fn search(
             Json(ApiError {
                 code: "manifold_error".to_string(),
                 message: e.to_string(),
+                details: Vec::new(),
             }),
         )
     })?;
@@ -173,6 +199,7 @@ async fn get_persona(
             Json(ApiError {
                 code: "database_error".to_string(),
                 message: e.to_string(),
+                details: Vec::new(),
             }),
         )
     })?;
@@ -191,6 +218,7 @@ async fn get_persona(
             Json(ApiError {
                 code: "not_found".to_string(),
                 message: format!("persona not found: {persona_id}"),
+                details: Vec::new(),
             }),
         )),
     }
@@ -213,6 +241,7 @@ async fn install(
                 Json(ApiError {
                     code: "manifold_error".to_string(),
                     message: e.to_string(),
+                    details: Vec::new(),
                 }),
             )
         })?;
@@ -227,6 +256,7 @@ async fn install(
                 Json(ApiError {
                     code: "database_error".to_string(),
                     message: e.to_string(),
+                    details: Vec::new(),
                 }),
             )
         })?;
@@ -253,6 +283,7 @@ async fn uninstall(
             Json(ApiError {
                 code: "database_error".to_string(),
                 message: e.to_string(),
+                details: Vec::new(),
             }),
         )
     })?;
@@ -265,13 +296,134 @@ async fn uninstall(
             Json(ApiError {
                 code: "not_found".to_string(),
                 message: format!("persona not found: {persona_id}"),
+                details: Vec::new(),
             }),
         ))
     }
 }
 
+/// List personas authored locally
+async fn list_local(State(state): State<Arc<ApiState>>) -> Json<PersonaListResponse> {
+    let mut personas: Vec<PersonaResponse> = std::fs::read_dir(&state.persona_cache_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "local") {
+                return None;
+            }
+            let content = std::fs::read_to_string(path.with_extension("json")).ok()?;
+            let persona: Persona = serde_json::from_str(&content).ok()?;
+            Some(PersonaResponse::from(&persona))
+        })
+        .collect();
+    personas.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let total = personas.len();
+    Json(PersonaListResponse { personas, total })
+}
+
+/// Author a new local persona
+async fn create_local(
+    State(state): State<Arc<ApiState>>,
+    Json(persona): Json<Persona>,
+) -> Result<(StatusCode, Json<PersonaResponse>), (StatusCode, Json<ApiError>)> {
+    check_persona(&persona)?;
+
+    let path = state
+        .persona_cache_dir
+        .join(format!("{}.json", persona.id()));
+    if path.exists() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ApiError {
+                code: "already_exists".to_string(),
+                message: format!(
+                    "persona already exists: {} (use PUT to replace it)",
+                    persona.id()
+                ),
+                details: Vec::new(),
+            }),
+        ));
+    }
+
+    write_local(&state, &persona)?;
+    Ok((StatusCode::CREATED, Json(PersonaResponse::from(&persona))))
+}
+
+/// Create or replace a local persona
+///
+/// Replacing a cached Manifold persona turns it into a local one.
+async fn save_local(
+    State(state): State<Arc<ApiState>>,
+    Path(persona_id): Path<String>,
+    Json(persona): Json<Persona>,
+) -> Result<Json<PersonaResponse>, (StatusCode, Json<ApiError>)> {
+    if persona.id() != persona_id {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiError {
+                code: "id_mismatch".to_string(),
+                message: format!(
+                    "body identity.id {} does not match path {persona_id}",
+                    persona.id()
+                ),
+                details: Vec::new(),
+            }),
+        ));
+    }
+    check_persona(&persona)?;
+
+    write_local(&state, &persona)?;
+    Ok(Json(PersonaResponse::from(&persona)))
+}
+
+/// Reject personas that fail validation, listing every problem
+fn check_persona(persona: &Persona) -> Result<(), (StatusCode, Json<ApiError>)> {
+    persona.validate().map_err(|errors| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiError {
+                code: "invalid_persona".to_string(),
+                message: format!("persona has {} problem(s)", errors.len()),
+                details: errors.iter().map(ToString::to_string).collect(),
+            }),
+        )
+    })
+}
+
+/// Write the persona JSON and its local marker to the persona cache dir
+fn write_local(state: &ApiState, persona: &Persona) -> Result<(), (StatusCode, Json<ApiError>)> {
+    let io_error = |e: std::io::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError {
+                code: "io_error".to_string(),
+                message: e.to_string(),
+                details: Vec::new(),
+            }),
+        )
+    };
+
+    let dir = &state.persona_cache_dir;
+    let json = serde_json::to_string_pretty(persona).map_err(|e| io_error(e.into()))?;
+    let path = dir.join(format!("{}.json", persona.id()));
+    let tmp = path.with_extension("json.tmp");
+
+    // Mark first so a concurrent Manifold fetch can't overwrite the new file
+    std::fs::write(local_persona_marker(dir, persona.id()), b"").map_err(io_error)?;
+    std::fs::write(&tmp, json).map_err(io_error)?;
+    std::fs::rename(&tmp, &path).map_err(io_error)?;
+
+    tracing::info!(persona_id = persona.id(), path = %path.display(), "saved local persona");
+    Ok(())
+}
+
 #[derive(Debug, Serialize)]
 struct ApiError {
     code: String,
     message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    details: Vec<String>,
 }
@@ -0,0 +1,23 @@
+//! `X-Request-Id` propagation for HTTP requests
+
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+
+use crate::request_id::{self, REQUEST_ID_HEADER};
+
+/// Run the request under its `X-Request-Id` and echo the id on the response
+///
+/// A missing or malformed header gets a freshly generated id.
+pub async fn request_id_middleware(req: Request, next: Next) -> Response {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(request_id::sanitize)
+        .map_or_else(request_id::generate, ToString::to_string);
+
+    let mut response = request_id::scope(request_id.clone(), next.run(req)).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
@@ -12,9 +12,11 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 
-use super::{ApiState, auth::require_api_key};
+use super::ApiState;
+use super::auth::{RequireScope, require_api_key, require_scope};
 use crate::skills::{
-    ManifoldClient, Skill, SkillPriority, SkillSnapshot, SkillSource, SnapshotEntry,
+    ManifoldClient, MissingRequirement, Skill, SkillPriority, SkillSnapshot, SkillSource,
+    SnapshotEntry,
 };
 
 // --- Request/Response types ---
@@ -121,11 +123,34 @@ fn default_namespace() -> String {
     "community".to_string()
 }
 
+#[derive(Deserialize)]
+pub struct PromptPreviewQuery {
+    pub user_id: Option<String>,
+    pub channel: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct SetEnabledRequest {
     pub enabled: bool,
 }
 
+/// Scoped enable/disable; omit `enabled` to clear the override
+#[derive(Deserialize)]
+pub struct SetScopeRequest {
+    pub user_id: Option<String>,
+    pub channel: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct SkillScopeResponse {
+    pub skill_id: String,
+    pub user_id: Option<String>,
+    pub channel: Option<String>,
+    /// Override now in effect (`None` = cleared)
+    pub enabled: Option<bool>,
+}
+
 #[derive(Deserialize)]
 pub struct SetPriorityRequest {
     pub priority: SkillPriority,
@@ -163,6 +188,16 @@ pub struct SkillStatusEntry {
     pub missing: SkillRequirements,
 }
 
+/// Requirement check for an enabled skill
+#[derive(Serialize)]
+pub struct SkillDiagnostic {
+    pub id: String,
+    pub name: String,
+    /// Whether the skill is included in the system prompt
+    pub satisfied: bool,
+    pub missing: Vec<MissingRequirement>,
+}
+
 /// Skill requirements lists
 #[derive(Serialize)]
 pub struct SkillRequirements {
@@ -187,6 +222,8 @@ pub struct ErrorResponse {
 pub struct ErrorDetail {
     pub code: String,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 fn error_response(code: &str, message: &str) -> Json<ErrorResponse> {
@@ -194,6 +231,7 @@ fn error_response(code: &str, message: &str) -> Json<ErrorResponse> {
         error: ErrorDetail {
             code: code.to_string(),
             message: message.to_string(),
+            request_id: crate::request_id::current(),
         },
     })
 }
@@ -677,6 +715,98 @@ async fn skill_status(
     }))
 }
 
+/// Check every enabled skill's requirements against the running host
+async fn skill_diagnostics(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<Vec<SkillDiagnostic>>, (StatusCode, Json<ErrorResponse>)> {
+    let skills = state.skill_repo.list_enabled().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            error_response("db_error", &e.to_string()),
+        )
+    })?;
+
+    let diagnostics = skills
+        .iter()
+        .map(|s| {
+            let missing = s.missing_requirements(state.voice_enabled);
+            SkillDiagnostic {
+                id: s.skill.id.clone(),
+                name: s.skill.metadata.name.clone(),
+                satisfied: missing.is_empty(),
+                missing,
+            }
+        })
+        .collect();
+
+    Ok(Json(diagnostics))
+}
+
+/// Enable or disable a skill for a user and/or channel
+///
+/// Precedence is user+channel, then user, then channel, then the global flag.
+async fn set_scope(
+    State(state): State<Arc<ApiState>>,
+    Path(skill_id): Path<String>,
+    Json(req): Json<SetScopeRequest>,
+) -> Result<Json<SkillScopeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let user_id = req.user_id.as_deref().filter(|s| !s.is_empty());
+    let channel = req.channel.as_deref().filter(|s| !s.is_empty());
+
+    let result = match req.enabled {
+        Some(enabled) => state
+            .skill_repo
+            .set_scope(&skill_id, user_id, channel, enabled),
+        None if user_id.is_none() && channel.is_none() => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                error_response(
+                    "invalid_scope",
+                    "The global enabled flag cannot be cleared; set `enabled` instead",
+                ),
+            ));
+        }
+        None => state
+            .skill_repo
+            .get(&skill_id)
+            .and_then(|skill| match skill {
+                Some(_) => state
+                    .skill_repo
+                    .clear_scope(&skill_id, user_id, channel)
+                    .map(|_| true),
+                None => Ok(false),
+            }),
+    };
+
+    let found = result.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            error_response("db_error", &e.to_string()),
+        )
+    })?;
+    if !found {
+        return Err((
+            StatusCode::NOT_FOUND,
+            error_response("not_found", "Skill not found"),
+        ));
+    }
+
+    Ok(Json(SkillScopeResponse {
+        skill_id,
+        user_id: user_id.map(ToString::to_string),
+        channel: channel.map(ToString::to_string),
+        enabled: req.enabled,
+    }))
+}
+
+/// Dry-run the system prompt for a user
+async fn prompt_preview(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<PromptPreviewQuery>,
+) -> Json<super::PromptPreview> {
+    Json(state.prompt_preview(query.user_id.as_deref(), query.channel.as_deref()))
+}
+
 /// List available slash commands
 async fn list_commands(
     State(state): State<Arc<ApiState>>,
@@ -749,14 +879,15 @@ async fn install_deps(
         )
     })?;
 
-    let result = crate::skills::install::execute_install(spec, prefs)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                error_response("install_error", &e.to_string()),
-            )
-        })?;
+    let result =
+        crate::skills::install::execute_install(spec, &state.skills_config, &state.skill_repo)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    error_response("install_error", &e.to_string()),
+                )
+            })?;
 
     Ok(Json(InstallDepsResponse { skill_id, result }))
 }
@@ -887,9 +1018,23 @@ pub fn router(state: Arc<ApiState>) -> Router {
         .route("/", get(list_installed))
         .route("/commands", get(list_commands))
         .route("/status", get(skill_status))
+        .route("/diagnostics", get(skill_diagnostics))
+        .route("/prompt-preview", get(prompt_preview))
         .route("/search", get(search_skills))
-        .route("/install", post(install_skill))
-        .route("/install/local", post(install_local))
+        .route(
+            "/install",
+            post(install_skill).route_layer(middleware::from_fn_with_state(
+                RequireScope("admin"),
+                require_scope,
+            )),
+        )
+        .route(
+            "/install/local",
+            post(install_local).route_layer(middleware::from_fn_with_state(
+                RequireScope("admin"),
+                require_scope,
+            )),
+        )
         .route("/snapshot", get(get_snapshot).post(import_snapshot))
         .route(
             "/{skill_id}",
@@ -897,6 +1042,7 @@ pub fn router(state: Arc<ApiState>) -> Router {
         )
         .route("/{skill_id}/enabled", patch(set_enabled))
         .route("/{skill_id}/priority", patch(set_priority))
+        .route("/{skill_id}/scope", post(set_scope))
         .route("/{skill_id}/install-deps", post(install_deps))
         .layer(middleware::from_fn_with_state(
             state.clone(),
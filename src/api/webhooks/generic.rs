@@ -0,0 +1,84 @@
+//! Generic webhook handler
+//!
+//! Accepts messages for the webhook channel and hands them to its channel
+//! handler; replies are delivered to the configured outgoing URL.
+
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::http::{HeaderMap, StatusCode};
+use axum::{Json, extract::State};
+use serde::Serialize;
+
+use crate::api::ApiState;
+use crate::channels::{InboundPayload, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+/// Generic webhook response
+#[derive(Serialize)]
+pub struct GenericWebhookResponse {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl GenericWebhookResponse {
+    fn error(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<Self>) {
+        (
+            status,
+            Json(Self {
+                ok: false,
+                error: Some(message.into()),
+            }),
+        )
+    }
+}
+
+/// Handle a message posted to the generic webhook
+///
+/// The request must carry an `X-Beacon-Timestamp` header (unix seconds) and
+/// an `X-Beacon-Signature` header with the hex HMAC-SHA256 of
+/// `<timestamp>.<body>` (optionally prefixed with `sha256=`). The endpoint is
+/// only enabled when `WEBHOOK_SECRET` is set. Accepted messages are processed
+/// asynchronously.
+pub async fn handle_message(
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<GenericWebhookResponse>) {
+    let Some(inbound) = &state.generic_webhook else {
+        return GenericWebhookResponse::error(
+            StatusCode::NOT_FOUND,
+            "webhook channel is not configured",
+        );
+    };
+
+    let signature = headers.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok());
+    let timestamp = headers.get(TIMESTAMP_HEADER).and_then(|v| v.to_str().ok());
+    if !inbound.verify(signature, timestamp, &body) {
+        tracing::warn!("generic webhook signature mismatch or stale timestamp");
+        return GenericWebhookResponse::error(StatusCode::FORBIDDEN, "invalid signature");
+    }
+
+    let payload: InboundPayload = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return GenericWebhookResponse::error(
+                StatusCode::BAD_REQUEST,
+                format!("invalid message body: {e}"),
+            );
+        }
+    };
+
+    if let Err(e) = inbound.deliver(payload.into_incoming()).await {
+        tracing::error!(error = %e, "generic webhook delivery failed");
+        return GenericWebhookResponse::error(StatusCode::SERVICE_UNAVAILABLE, e.to_string());
+    }
+
+    (
+        StatusCode::ACCEPTED,
+        Json(GenericWebhookResponse {
+            ok: true,
+            error: None,
+        }),
+    )
+}
@@ -122,6 +122,7 @@ pub async fn handle_event(
     let context_config = crate::api::ApiServer::context_config(
         &state.persona_id,
         state.persona_system_prompt.clone(),
+        &state.llm_model,
     );
     let context_builder = ContextBuilder::new(context_config);
     let built_context = context_builder.build_with_memory(
@@ -130,7 +131,7 @@ pub async fn handle_event(
         user.life_json_path.as_deref(),
         &state.session_repo,
         &state.user_repo,
-        Some((&state.memory_repo, text)),
+        Some((&state.memory_repo.for_persona(&session.persona_id), text)),
     );
 
     if let Ok(ctx) = &built_context {
@@ -151,7 +152,9 @@ pub async fn handle_event(
     let request = synapse_client::ChatRequest {
         model: state.llm_model.clone(),
         messages: vec![
-            synapse_client::Message::system(&state.system_prompt_with_skills(None)),
+            synapse_client::Message::system(
+                &state.system_prompt_with_skills(Some(&user.id), Some("google_chat")),
+            ),
             synapse_client::Message::user(&augmented_prompt),
         ],
         stream: false,
@@ -6,6 +6,7 @@ use axum::{Router, routing::post};
 
 use super::ApiState;
 
+pub mod generic;
 pub mod google_chat;
 pub mod teams;
 pub mod telegram;
@@ -22,5 +23,6 @@ pub fn router(state: Arc<ApiState>) -> Router {
         .route("/teams", post(teams::handle_activity))
         .route("/google-chat", post(google_chat::handle_event))
         .route("/vortex", post(vortex::handle_vortex_callback))
+        .route("/generic", post(generic::handle_message))
         .with_state(state)
 }
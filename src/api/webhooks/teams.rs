@@ -115,6 +115,7 @@ pub async fn handle_activity(
     let context_config = crate::api::ApiServer::context_config(
         &state.persona_id,
         state.persona_system_prompt.clone(),
+        &state.llm_model,
     );
     let context_builder = ContextBuilder::new(context_config);
     let built_context = context_builder.build_with_memory(
@@ -123,7 +124,7 @@ pub async fn handle_activity(
         user.life_json_path.as_deref(),
         &state.session_repo,
         &state.user_repo,
-        Some((&state.memory_repo, text)),
+        Some((&state.memory_repo.for_persona(&session.persona_id), text)),
     );
 
     if let Ok(ctx) = &built_context {
@@ -144,7 +145,9 @@ pub async fn handle_activity(
     let request = synapse_client::ChatRequest {
         model: state.llm_model.clone(),
         messages: vec![
-            synapse_client::Message::system(&state.system_prompt_with_skills(None)),
+            synapse_client::Message::system(
+                &state.system_prompt_with_skills(Some(&user.id), Some("teams")),
+            ),
             synapse_client::Message::user(&augmented_prompt),
         ],
         stream: false,
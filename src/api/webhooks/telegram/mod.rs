@@ -101,6 +101,7 @@ async fn handle_update_for_account(
             );
             return (StatusCode::OK, Json(WebhookResponse { ok: true }));
         }
+        dedup.save();
     }
 
     // For per-account requests, verify the account exists in the registry
@@ -139,7 +140,8 @@ async fn handle_update_for_account(
                 let _ = tg.answer_callback_query(&callback_id, None).await;
             }
 
-            tokio::spawn(async move {
+            let request_id = crate::request_id::current_or_new();
+            tokio::spawn(crate::request_id::scope(request_id, async move {
                 if let Err(e) = process::process_telegram_message(
                     state, cb_message, text, has_media, account_id,
                 )
@@ -147,7 +149,7 @@ async fn handle_update_for_account(
                 {
                     tracing::error!(error = %e, "Telegram callback query processing failed");
                 }
-            });
+            }));
 
             return (StatusCode::OK, Json(WebhookResponse { ok: true }));
         }
@@ -276,14 +278,16 @@ async fn handle_update_for_account(
         return (StatusCode::OK, Json(WebhookResponse { ok: true }));
     }
 
-    // Spawn processing in background so we return 200 immediately
-    tokio::spawn(async move {
+    // Spawn processing in background so we return 200 immediately, keeping
+    // the webhook's request id for the whole turn
+    let request_id = crate::request_id::current_or_new();
+    tokio::spawn(crate::request_id::scope(request_id, async move {
         if let Err(e) =
             process::process_telegram_message(state, message, text, has_media, account_id).await
         {
             tracing::error!(error = %e, "Telegram message processing failed");
         }
-    });
+    }));
 
     (StatusCode::OK, Json(WebhookResponse { ok: true }))
 }
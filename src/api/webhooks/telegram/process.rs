@@ -7,8 +7,8 @@ use futures::StreamExt as _;
 use super::media::extract_media_file_refs;
 use super::types::TelegramMessage;
 use crate::api::ApiState;
-use crate::channels::{Attachment, Channel, IncomingMessage};
-use crate::context::{ContextBuilder, ContextConfig};
+use crate::channels::{Attachment, Channel, IncomingMessage, TypingKeepalive};
+use crate::context::{ContextBuilder, ContextConfig, MemorySelection, Tokenizer};
 use crate::db::MessageRole;
 use crate::hooks::{HookAction, HookEvent};
 
@@ -37,6 +37,7 @@ fn telegram_to_incoming(message: &TelegramMessage, content: &str) -> IncomingMes
         attachments: vec![],
         thread_id: message.message_thread_id.map(|id| id.to_string()),
         callback_data: None,
+        request_id: crate::request_id::current_or_new(),
     }
 }
 
@@ -166,32 +167,46 @@ pub async fn process_telegram_message(
                     // Check for pairing code verification
                     let trimmed = msg.content.trim().to_uppercase();
                     if trimmed.len() == 6 && trimmed.chars().all(|c| c.is_ascii_alphanumeric()) {
-                        if matches!(
-                            pm.verify_pairing(&msg.sender_id, "telegram", &trimmed),
-                            Ok(true)
-                        ) {
-                            let _ = telegram
-                                .send_message(
-                                    message.chat.id,
-                                    "Pairing successful! You can now send messages.",
-                                    None,
-                                )
-                                .await;
-                            // Fall through to process the message
-                        } else {
-                            return Ok(());
+                        match pm.redeem_code(&msg.sender_id, "telegram", &trimmed) {
+                            Ok(()) => {
+                                let _ = telegram
+                                    .send_message(
+                                        message.chat.id,
+                                        "Pairing successful! You can now send messages.",
+                                        None,
+                                    )
+                                    .await;
+                                // Fall through to process the message
+                            }
+                            Err(crate::security::PairingError::Storage(e)) => {
+                                tracing::error!(error = %e, "pairing verification failed");
+                                return Ok(());
+                            }
+                            Err(e @ crate::security::PairingError::Locked { .. }) => {
+                                let _ = telegram
+                                    .send_message(message.chat.id, e.notice(), None)
+                                    .await;
+                                return Ok(());
+                            }
+                            Err(e) => {
+                                let _ = telegram
+                                    .send_message(
+                                        message.chat.id,
+                                        &format!(
+                                            "{} Send any message to get a new code.",
+                                            e.notice()
+                                        ),
+                                        None,
+                                    )
+                                    .await;
+                                return Ok(());
+                            }
                         }
                     } else if let Ok(Some(code)) =
                         pm.generate_pairing_code(&msg.sender_id, "telegram")
                     {
                         let _ = telegram
-                            .send_message(
-                                message.chat.id,
-                                &format!(
-                                    "Please enter the pairing code to start messaging.\n\nYour code: {code}\n\n(This code expires in 10 minutes)"
-                                ),
-                                None,
-                            )
+                            .send_message(message.chat.id, &pm.code_prompt(&code), None)
                             .await;
                         return Ok(());
                     }
@@ -200,6 +215,18 @@ pub async fn process_telegram_message(
         }
     }
 
+    // Maintenance mode: answer with the notice (or stay silent) and skip the agent
+    match state.maintenance.action() {
+        crate::maintenance::MaintenanceAction::Proceed => {}
+        crate::maintenance::MaintenanceAction::Silent => return Ok(()),
+        crate::maintenance::MaintenanceAction::Reply(notice) => {
+            let _ = telegram
+                .send_message(message.chat.id, &notice, Some(message.message_id))
+                .await;
+            return Ok(());
+        }
+    }
+
     // Hook: message:received
     if let Some(ref hm) = state.hook_manager {
         let hook_event = HookEvent::new(HookAction::MessageReceived, "telegram", &msg);
@@ -220,6 +247,32 @@ pub async fn process_telegram_message(
         }
     }
 
+    // Reminder slash commands are handled directly, without the agent
+    if let Some(command) = crate::tools::ReminderCommand::parse(&msg.content) {
+        let reply = match state.cron_tools {
+            Some(ref ct) => {
+                let origin =
+                    crate::tools::ReminderOrigin::new(&msg.sender_id, "telegram", &msg.channel_id)
+                        .with_thread(msg.thread_id.clone());
+                ct.run_command(command, &origin).await
+            }
+            None => "Reminders are not available (scheduling is not configured).".to_string(),
+        };
+        let _ = telegram
+            .send_message(message.chat.id, &reply, Some(message.message_id))
+            .await;
+        return Ok(());
+    }
+
+    // Daily usage cap: answer with the notice and skip the agent until reset
+    if let crate::usage::UsageDecision::LimitReached(notice) = state.usage_cap.check(&msg.sender_id)
+    {
+        let _ = telegram
+            .send_message(message.chat.id, &notice, Some(message.message_id))
+            .await;
+        return Ok(());
+    }
+
     // Find or create user and session
     let user = state.user_repo.find_or_create(&msg.sender_id)?;
 
@@ -228,11 +281,13 @@ pub async fn process_telegram_message(
         Some(aid) if aid != "default" => format!("{aid}:{}", msg.channel_id),
         _ => msg.channel_id.clone(),
     };
-    let session = state.session_repo.find_or_create(
+    // Forum topics get their own session and history
+    let session = state.session_repo.find_or_create_threaded(
         &user.id,
         "telegram",
         &session_channel_id,
         &state.persona_id,
+        msg.thread_id.as_deref(),
     )?;
 
     // Publish beacon.conversation.started for new sessions
@@ -266,29 +321,17 @@ pub async fn process_telegram_message(
         tracing::warn!(error = %e, "failed to store user message");
     }
 
-    // Run session compaction if needed (non-fatal on failure)
-    if let Some(ref compactor) = state.session_compactor
-        && let Ok(count) = state.session_repo.message_count(&session.id)
-        && compactor.needs_compaction(count)
-        && let Err(e) = compactor
-            .compact(
-                &session.id,
-                &state.session_repo,
-                &state.memory_repo,
-                state.indexer.as_deref(),
-                &user.id,
-            )
-            .await
-    {
-        tracing::warn!(error = %e, "session compaction failed, proceeding normally");
-    }
+    // Memories follow the persona this session runs as
+    let memory_repo = state.memory_repo.for_persona(&session.persona_id);
 
     // Build context with thread support
     let context_config = ContextConfig {
         max_messages: 20,
         max_tokens: 4000,
+        tokenizer: Tokenizer::for_model(&state.llm_model),
         persona_id: state.persona_id.clone(),
         max_memories: 10,
+        memory_selection: MemorySelection::Relevant,
         persona_system_prompt: state.persona_system_prompt.clone(),
     };
     let context_builder = ContextBuilder::new(context_config);
@@ -298,10 +341,25 @@ pub async fn process_telegram_message(
         user.life_json_path.as_deref(),
         &state.session_repo,
         &state.user_repo,
-        Some((&state.memory_repo, msg.content.as_str())),
+        Some((&memory_repo, msg.content.as_str())),
         thread_id,
     );
 
+    // Compact long sessions in the background; this turn keeps the history
+    // it just loaded and later turns see the summary
+    if let Some(ref compactor) = state.session_compactor {
+        compactor.compact_in_background(
+            &session.id,
+            state.session_repo.clone(),
+            memory_repo.clone(),
+            state
+                .indexer
+                .as_ref()
+                .map(|indexer| Arc::new(indexer.for_persona(&session.persona_id))),
+            &user.id,
+        );
+    }
+
     if let Ok(ctx) = &built_context {
         tracing::debug!(
             session = %session.id,
@@ -381,8 +439,13 @@ pub async fn process_telegram_message(
     let content_with_attachments = if msg.attachments.is_empty() {
         content.clone()
     } else if let Some(ref ap) = state.attachment_processor {
+        let user_language = state
+            .user_repo
+            .get_context_value(&user.id, crate::media::language::USER_LANGUAGE_KEY)
+            .ok()
+            .flatten();
         let attachment_text = ap
-            .process_attachments(&msg.attachments)
+            .process_attachments_for(&msg.attachments, user_language.as_deref())
             .await
             .unwrap_or_default();
         if attachment_text.is_empty() {
@@ -395,12 +458,13 @@ pub async fn process_telegram_message(
     };
 
     // Inject knowledge based on user message
+    let persona_knowledge = state.persona_knowledge.read().await.clone();
     if let Ok(ref mut ctx) = built_context
-        && !state.persona_knowledge.is_empty()
+        && !persona_knowledge.is_empty()
     {
         let max_knowledge_tokens = state.max_context_tokens / 4;
         let selected = crate::knowledge::select_knowledge(
-            &state.persona_knowledge,
+            &persona_knowledge,
             &content_with_attachments,
             max_knowledge_tokens,
         );
@@ -451,35 +515,56 @@ pub async fn process_telegram_message(
         return Ok(());
     }
 
-    // Start streaming message placeholder
-    let streaming_msg_id = telegram
-        .send_streaming_start(
-            &msg.channel_id,
-            "\u{2026}",
-            Some(&msg.id),
-            msg.thread_id.as_deref(),
-        )
-        .await
-        .ok();
+    // Start streaming message placeholder (or defer until the first text)
+    let streaming = telegram.streaming();
+    let mut defer_stream_start = !streaming.placeholder;
+    let mut streaming_msg_id = if streaming.placeholder {
+        telegram
+            .send_streaming_start(
+                &msg.channel_id,
+                "\u{2026}",
+                Some(&msg.id),
+                msg.thread_id.as_deref(),
+            )
+            .await
+            .ok()
+    } else {
+        None
+    };
 
-    let response = {
+    // Refresh the typing indicator while the agent runs, until text streams
+    let mut reported_tokens: u64 = 0;
+    let typing = TypingKeepalive::new(streaming.typing_interval());
+    let response = typing.run(telegram, &msg.channel_id, async {
         // Fetch available tools from Synapse MCP and plugins
         let exec_tool = Arc::new(crate::tools::BuiltinExecTool::default());
+        let reminder_origin =
+            crate::tools::ReminderOrigin::new(&msg.sender_id, "telegram", &msg.channel_id)
+                .with_thread(msg.thread_id.clone());
         let tools = {
             let mut executor = crate::tools::executor::ToolExecutor::new(
                 Arc::clone(synapse),
                 state.plugin_manager.clone(),
             )
-            .with_exec_tool(Arc::clone(&exec_tool));
+            .with_exec_tool(Arc::clone(&exec_tool))
+            .with_policy(Arc::clone(&state.tool_policy), "telegram")
+            .with_session_profile(
+                session
+                    .tool_profile
+                    .as_deref()
+                    .and_then(crate::tools::SessionToolProfile::parse),
+            );
             if let Some(ref ct) = state.cron_tools {
-                executor = executor.with_cron_tools(Arc::clone(ct));
+                executor = executor
+                    .with_cron_tools(Arc::clone(ct))
+                    .with_reminder_origin(reminder_origin.clone());
             }
             executor.list_tools().await.ok()
         };
 
         // Multi-turn tool loop with streaming support
         let mut messages = vec![
-            synapse_client::Message::system(&state.system_prompt_with_skills(Some(&user.id))),
+            synapse_client::Message::system(&state.system_prompt_with_skills(Some(&user.id), Some("telegram"))),
             synapse_client::Message::user(&augmented_prompt),
         ];
         let mut final_response = String::new();
@@ -487,9 +572,21 @@ pub async fn process_telegram_message(
             Arc::clone(synapse),
             state.plugin_manager.clone(),
         )
-        .with_exec_tool(exec_tool);
+        .with_exec_tool(exec_tool)
+        .with_fetch_cache(Arc::clone(&state.fetch_cache))
+        .with_output_config(state.tool_output.clone())
+        .with_policy(Arc::clone(&state.tool_policy), "telegram")
+        .with_session_profile(
+            session
+                .tool_profile
+                .as_deref()
+                .and_then(crate::tools::SessionToolProfile::parse),
+        )
+        .with_audit_context(&session.id, &msg.sender_id);
         if let Some(ref ct) = state.cron_tools {
-            executor = executor.with_cron_tools(Arc::clone(ct));
+            executor = executor
+                .with_cron_tools(Arc::clone(ct))
+                .with_reminder_origin(reminder_origin);
         }
         let mut loop_detector = crate::tools::LoopDetector::default();
 
@@ -506,8 +603,18 @@ pub async fn process_telegram_message(
                 tool_choice: None,
             };
 
+            let permit = match state.synapse_breaker.acquire() {
+                Ok(permit) => permit,
+                Err(e) => {
+                    tracing::warn!(error = %e, "skipping synapse call");
+                    final_response = crate::synapse::UNAVAILABLE_REPLY.to_string();
+                    break;
+                }
+            };
+
             match synapse.chat_completion_stream(&request).await {
                 Ok(mut stream) => {
+                    let mut stream_failed = false;
                     let mut turn_text = String::new();
                     let mut pending_tool_calls: Vec<PendingToolCall> = Vec::new();
                     let mut finish_reason: Option<String> = None;
@@ -515,9 +622,21 @@ pub async fn process_telegram_message(
                     while let Some(event) = stream.next().await {
                         match event {
                             Ok(synapse_client::ChatEvent::ContentDelta(text)) => {
+                                typing.stop();
                                 turn_text.push_str(&text);
                                 // Stream update to Telegram (rate limiter handles throttling)
-                                if let Some(ref mid) = streaming_msg_id {
+                                if defer_stream_start {
+                                    defer_stream_start = false;
+                                    streaming_msg_id = telegram
+                                        .send_streaming_start(
+                                            &msg.channel_id,
+                                            &turn_text,
+                                            Some(&msg.id),
+                                            msg.thread_id.as_deref(),
+                                        )
+                                        .await
+                                        .ok();
+                                } else if let Some(ref mid) = streaming_msg_id {
                                     let _ = telegram
                                         .send_streaming_update(&msg.channel_id, mid, &turn_text)
                                         .await;
@@ -538,21 +657,34 @@ pub async fn process_telegram_message(
                                 }
                             }
                             Ok(synapse_client::ChatEvent::Done {
-                                finish_reason: fr, ..
+                                finish_reason: fr,
+                                usage,
                             }) => {
                                 finish_reason = fr;
+                                if let Some(u) = usage {
+                                    reported_tokens += u64::from(u.prompt_tokens)
+                                        + u64::from(u.completion_tokens);
+                                }
                                 break;
                             }
                             Ok(synapse_client::ChatEvent::Error(e)) => {
                                 tracing::error!(error = %e, "streaming error");
+                                stream_failed = true;
                                 break;
                             }
                             Err(e) => {
                                 tracing::error!(error = %e, "stream event error");
+                                stream_failed = true;
                                 break;
                             }
                         }
                     }
+                    // A stream that breaks off is a failed call
+                    if stream_failed {
+                        permit.failure();
+                    } else {
+                        permit.success();
+                    }
 
                     if !turn_text.is_empty() {
                         final_response.push_str(&turn_text);
@@ -589,6 +721,7 @@ pub async fn process_telegram_message(
 
                         let mut should_break = false;
                         for tc in &tool_calls {
+                            let started = std::time::Instant::now();
                             let result = executor
                                 .execute(&tc.function.name, &tc.function.arguments)
                                 .await
@@ -631,6 +764,8 @@ pub async fn process_telegram_message(
                                 &tc.function.name,
                                 tool_success,
                                 &msg.sender_id,
+                                u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+                                &tc.function.arguments,
                             ));
                         }
 
@@ -643,6 +778,7 @@ pub async fn process_telegram_message(
                     break;
                 }
                 Err(e) => {
+                    permit.failure();
                     tracing::error!(error = %e, "synapse stream error");
                     final_response =
                         "Sorry, I encountered an error processing your message.".to_string();
@@ -659,7 +795,13 @@ pub async fn process_telegram_message(
         }
 
         final_response
-    };
+    })
+    .await;
+
+    state.usage_cap.record(
+        &user.id,
+        crate::usage::turn_tokens(reported_tokens, &augmented_prompt, &response),
+    );
 
     // Hook: message:after_agent
     let response = if let Some(ref hm) = state.hook_manager {
@@ -672,6 +814,34 @@ pub async fn process_telegram_message(
         response
     };
 
+    // Hook: response:generated - final text before send, can rewrite or suppress it
+    let response = if let Some(ref hm) = state.hook_manager {
+        let hook_event = HookEvent::new(HookAction::ResponseGenerated, "telegram", &msg)
+            .with_session(&session.id)
+            .with_response(&response);
+        let hook_result = hm.trigger(&hook_event).await;
+        if hook_result.skip_processing {
+            tracing::debug!("hook suppressed Telegram response");
+            return Ok(());
+        }
+        match hook_result.modified_response {
+            Some(rewritten) if rewritten != response => {
+                // Streaming already delivered the original text; replace it in place
+                if let Some(ref mid) = streaming_msg_id
+                    && let Err(e) = telegram
+                        .edit_message(&msg.channel_id, mid, &rewritten)
+                        .await
+                {
+                    tracing::warn!(error = %e, "failed to apply rewritten response");
+                }
+                rewritten
+            }
+            _ => response,
+        }
+    } else {
+        response
+    };
+
     // Store assistant response with thread context
     if let Err(e) = state.session_repo.add_message_with_thread(
         &session.id,
@@ -1,14 +1,20 @@
 //! Vortex webhook handler for scheduled job callbacks
 
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::{Json, extract::State, http::StatusCode};
+use axum::body::Bytes;
+use axum::http::{HeaderMap, StatusCode};
+use axum::{Json, extract::State};
 use serde::{Deserialize, Serialize};
 
 use crate::agent::{AgentRunConfig, run_agent_turn};
 use crate::api::ApiState;
+use crate::channels::{Channel, OutgoingMessage, verify_signed};
 use crate::context::ContextBuilder;
-use crate::db::MessageRole;
+use crate::db::{MessageRole, OutboxRepo};
+use crate::security::hmac;
+use crate::tools::ReminderPayload;
 
 /// Vortex callback payload
 #[derive(Debug, Deserialize)]
@@ -43,6 +49,9 @@ pub struct AgentRunPayload {
     /// Optional persona override (defaults to `state.persona_id`)
     #[serde(default)]
     pub persona_id: Option<String>,
+    /// Optional thread to deliver the result into
+    #[serde(default)]
+    pub thread_id: Option<String>,
 }
 
 /// Channel to deliver agent results to
@@ -70,11 +79,75 @@ impl std::str::FromStr for DeliveryChannel {
     }
 }
 
+/// Where a scheduled message should be delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeliveryRoute<'a> {
+    /// Channel name (e.g. `telegram`, `discord`, `ws_push`)
+    pub channel: &'a str,
+    /// Channel-specific conversation ID
+    pub channel_id: &'a str,
+    /// Thread within the conversation, if any
+    pub thread_id: Option<&'a str>,
+    /// User the message is for, used for the `ws_push` and outbox fallbacks
+    pub user_id: &'a str,
+}
+
+/// How a scheduled message was delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    /// Sent through the matching channel adapter
+    Channel,
+    /// Pushed to the user's live WebSocket connection
+    WsPush,
+    /// Queued in the outbox until the user reconnects
+    Outbox,
+}
+
 /// Handle Vortex webhook callback
+///
+/// The request must carry either `X-Vortex-Signature` and
+/// `X-Vortex-Timestamp` headers (hex HMAC-SHA256 of `<timestamp>.<body>`,
+/// optionally prefixed with `sha256=`, within the webhook timestamp skew) or
+/// an `X-Vortex-Secret` header matching the secret. Without a configured
+/// secret, callbacks are rejected unless unsigned callbacks are explicitly
+/// allowed.
 pub async fn handle_vortex_callback(
     State(state): State<Arc<ApiState>>,
-    Json(callback): Json<VortexCallback>,
+    headers: HeaderMap,
+    body: Bytes,
 ) -> (StatusCode, Json<VortexResponse>) {
+    let authorized = match state.vortex_webhook_secret {
+        Some(ref secret) => verify_callback(secret, &headers, &body),
+        None if state.vortex_allow_unsigned => true,
+        None => {
+            tracing::warn!("Vortex callback rejected: no webhook secret configured");
+            false
+        }
+    };
+    if !authorized {
+        tracing::warn!("Vortex callback rejected");
+        return (
+            StatusCode::FORBIDDEN,
+            Json(VortexResponse {
+                ok: false,
+                error: Some("invalid callback signature".to_string()),
+            }),
+        );
+    }
+
+    let callback: VortexCallback = match serde_json::from_slice(&body) {
+        Ok(callback) => callback,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(VortexResponse {
+                    ok: false,
+                    error: Some(format!("invalid callback body: {e}")),
+                }),
+            );
+        }
+    };
+
     tracing::info!(
         schedule_id = %callback.schedule_id,
         action = %callback.action,
@@ -114,54 +187,45 @@ pub async fn handle_vortex_callback(
 }
 
 /// Handle remind action
+///
+/// Delivers the reminder to the channel and thread it was created from. If
+/// that channel is unavailable (e.g. since disabled), falls back to `ws_push`
+/// and then the outbox rather than failing the callback.
 async fn handle_remind(state: &ApiState, callback: &VortexCallback) -> crate::Result<()> {
-    let user_id = callback
-        .payload
-        .get("user_id")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| crate::Error::Config("missing user_id in remind payload".to_string()))?;
-
-    let message = callback
-        .payload
-        .get("message")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| crate::Error::Config("missing message in remind payload".to_string()))?;
-
-    let channel = callback
-        .payload
-        .get("channel")
-        .and_then(|v| v.as_str())
-        .unwrap_or("telegram");
-
-    let channel_id = callback.payload.get("channel_id").and_then(|v| v.as_str());
+    let payload: ReminderPayload = serde_json::from_value(callback.payload.clone())
+        .map_err(|e| crate::Error::Config(format!("invalid remind payload: {e}")))?;
 
     tracing::info!(
-        user_id = %user_id,
-        channel = %channel,
-        message = %message,
+        user_id = %payload.user_id,
+        channel = %payload.channel,
         "processing remind action"
     );
 
-    // Route to appropriate channel
-    match channel {
-        "telegram" => {
-            let Some(telegram) = &state.telegram else {
-                return Err(crate::Error::Channel("Telegram not configured".to_string()));
-            };
+    let outcome = deliver(state, reminder_route(&payload), &payload.message).await?;
+    tracing::info!(
+        schedule_id = %callback.schedule_id,
+        ?outcome,
+        "reminder delivered"
+    );
 
-            let chat_id = channel_id
-                .ok_or_else(|| crate::Error::Config("missing channel_id for Telegram".to_string()))?
-                .parse::<i64>()
-                .map_err(|e| crate::Error::Config(format!("invalid Telegram chat_id: {e}")))?;
+    Ok(())
+}
 
-            telegram.send_message(chat_id, message, None).await?;
-        }
-        _ => {
-            tracing::warn!(channel = %channel, "unsupported channel for remind");
-        }
-    }
+/// Resolve the delivery route stored with a reminder
+fn reminder_route(payload: &ReminderPayload) -> DeliveryRoute<'_> {
+    // Web clients are addressed by user ID
+    let channel_id = if payload.channel == "ws_push" && payload.channel_id.is_empty() {
+        payload.user_id.as_str()
+    } else {
+        payload.channel_id.as_str()
+    };
 
-    Ok(())
+    DeliveryRoute {
+        channel: &payload.channel,
+        channel_id,
+        thread_id: payload.thread_id.as_deref(),
+        user_id: &payload.user_id,
+    }
 }
 
 /// Handle `check_in` action
@@ -180,6 +244,7 @@ async fn handle_check_in(state: &ApiState, callback: &VortexCallback) -> crate::
         .unwrap_or("telegram");
 
     let channel_id = callback.payload.get("channel_id").and_then(|v| v.as_str());
+    let thread_id = callback.payload.get("thread_id").and_then(|v| v.as_str());
 
     let prompt = callback
         .payload
@@ -200,17 +265,19 @@ async fn handle_check_in(state: &ApiState, callback: &VortexCallback) -> crate::
     let check_in_message = if let Some(synapse) = &state.synapse {
         // Find or create session for this channel
         let channel_id_str = channel_id.unwrap_or(user_id);
-        let session = state.session_repo.find_or_create(
+        let session = state.session_repo.find_or_create_threaded(
             &user.id,
             channel,
             channel_id_str,
             &state.persona_id,
+            thread_id,
         )?;
 
         // Build context with memory
         let context_config = crate::api::ApiServer::context_config(
             &state.persona_id,
             state.persona_system_prompt.clone(),
+            &state.llm_model,
         );
         let context_builder = ContextBuilder::new(context_config);
         let built_context = context_builder.build_with_memory(
@@ -219,7 +286,7 @@ async fn handle_check_in(state: &ApiState, callback: &VortexCallback) -> crate::
             user.life_json_path.as_deref(),
             &state.session_repo,
             &state.user_repo,
-            Some((&state.memory_repo, prompt)),
+            Some((&state.memory_repo.for_persona(&session.persona_id), prompt)),
         );
 
         let augmented_prompt = built_context
@@ -229,7 +296,9 @@ async fn handle_check_in(state: &ApiState, callback: &VortexCallback) -> crate::
         let request = synapse_client::ChatRequest {
             model: state.llm_model.clone(),
             messages: vec![
-                synapse_client::Message::system(&state.system_prompt_with_skills(None)),
+                synapse_client::Message::system(
+                    &state.system_prompt_with_skills(Some(&user.id), Some(channel)),
+                ),
                 synapse_client::Message::user(&augmented_prompt),
             ],
             stream: false,
@@ -267,26 +336,14 @@ async fn handle_check_in(state: &ApiState, callback: &VortexCallback) -> crate::
         prompt.to_string()
     };
 
-    // Send via appropriate channel
-    match channel {
-        "telegram" => {
-            let Some(telegram) = &state.telegram else {
-                return Err(crate::Error::Channel("Telegram not configured".to_string()));
-            };
-
-            let chat_id = channel_id
-                .ok_or_else(|| crate::Error::Config("missing channel_id for Telegram".to_string()))?
-                .parse::<i64>()
-                .map_err(|e| crate::Error::Config(format!("invalid Telegram chat_id: {e}")))?;
-
-            telegram
-                .send_message(chat_id, &check_in_message, None)
-                .await?;
-        }
-        _ => {
-            tracing::warn!(channel = %channel, "unsupported channel for check_in");
-        }
-    }
+    // Send via the originating channel
+    let route = DeliveryRoute {
+        channel,
+        channel_id: channel_id.unwrap_or(user_id),
+        thread_id,
+        user_id,
+    };
+    deliver(state, route, &check_in_message).await?;
 
     Ok(())
 }
@@ -310,16 +367,20 @@ async fn handle_agent_run(state: &ApiState, callback: &VortexCallback) -> crate:
     let system_prompt = state.persona_system_prompt.clone().unwrap_or_default();
 
     // Find or create session for this channel
-    let session = state.session_repo.find_or_create(
+    let session = state.session_repo.find_or_create_threaded(
         &user.id,
         &payload.channel,
         &payload.channel_id,
         persona_id,
+        payload.thread_id.as_deref(),
     )?;
 
     // Build context with memory
-    let context_config =
-        crate::api::ApiServer::context_config(persona_id, state.persona_system_prompt.clone());
+    let context_config = crate::api::ApiServer::context_config(
+        persona_id,
+        state.persona_system_prompt.clone(),
+        &state.llm_model,
+    );
     let context_builder = ContextBuilder::new(context_config);
     let built_context = context_builder.build_with_memory(
         &session.id,
@@ -327,7 +388,7 @@ async fn handle_agent_run(state: &ApiState, callback: &VortexCallback) -> crate:
         user.life_json_path.as_deref(),
         &state.session_repo,
         &state.user_repo,
-        Some((&state.memory_repo, &payload.prompt)),
+        Some((&state.memory_repo.for_persona(persona_id), &payload.prompt)),
     );
 
     let augmented_prompt = built_context.as_ref().map_or_else(
@@ -345,60 +406,220 @@ async fn handle_agent_run(state: &ApiState, callback: &VortexCallback) -> crate:
         max_iterations,
         session_id: session.id.clone(),
         user_id: user.id.clone(),
+        persona_id: persona_id.to_string(),
+        channel: payload.channel.clone(),
+        tool_profile: session.tool_profile.clone(),
+        slash_suggestion: None,
         notify: None,
         synapse_override: None,
     };
 
     let result = run_agent_turn(state, agent_config).await?;
 
-    // Deliver result to the specified channel
-    let delivery_channel: DeliveryChannel = payload.channel.parse()?;
-    deliver_agent_result(state, delivery_channel, &payload.channel_id, &result).await
+    // Deliver result to the specified channel (rejecting unknown channels)
+    payload.channel.parse::<DeliveryChannel>()?;
+    let route = DeliveryRoute {
+        channel: &payload.channel,
+        channel_id: &payload.channel_id,
+        thread_id: payload.thread_id.as_deref(),
+        user_id: &payload.user_id,
+    };
+    deliver(state, route, &result).await?;
+
+    Ok(())
 }
 
-/// Deliver the agent result to a channel
-async fn deliver_agent_result(
+/// Deliver a scheduled message along `route`
+///
+/// Tries the matching channel adapter first, then the user's live WebSocket
+/// connection, and finally queues the message in the outbox.
+///
+/// # Errors
+///
+/// Returns error only if every fallback fails, including the outbox write
+pub async fn deliver(
     state: &ApiState,
-    channel: DeliveryChannel,
-    channel_id: &str,
+    route: DeliveryRoute<'_>,
     message: &str,
-) -> crate::Result<()> {
-    match channel {
-        DeliveryChannel::Telegram => {
-            let Some(telegram) = &state.telegram else {
-                return Err(crate::Error::Channel("Telegram not configured".to_string()));
-            };
-            let chat_id = channel_id
-                .parse::<i64>()
-                .map_err(|e| crate::Error::Config(format!("invalid Telegram chat_id: {e}")))?;
-            telegram.send_message(chat_id, message, None).await?;
+) -> crate::Result<DeliveryOutcome> {
+    if route.channel == "ws_push" {
+        if push_ws(state, route.channel_id, message).await {
+            return Ok(DeliveryOutcome::WsPush);
         }
-        DeliveryChannel::Discord => {
-            tracing::warn!(channel_id, "Discord delivery not yet wired into ApiState");
+    } else {
+        let outgoing = outgoing_message(route.channel_id, route.thread_id, message);
+        match send_via(state, route.channel, outgoing).await {
+            Some(Ok(())) => return Ok(DeliveryOutcome::Channel),
+            Some(Err(e)) => {
+                tracing::warn!(channel = route.channel, error = %e, "scheduled delivery failed");
+            }
+            None => {
+                tracing::warn!(
+                    channel = route.channel,
+                    "channel unavailable for scheduled delivery"
+                );
+            }
         }
-        DeliveryChannel::Slack => {
-            tracing::warn!(channel_id, "Slack delivery not yet wired into ApiState");
+    }
+
+    // Fall back to the user's web session, then the outbox
+    if route.channel != "ws_push" && push_ws(state, route.user_id, message).await {
+        return Ok(DeliveryOutcome::WsPush);
+    }
+
+    OutboxRepo::new(state.db.clone()).enqueue(
+        route.user_id,
+        route.channel,
+        route.channel_id,
+        route.thread_id,
+        message,
+    )?;
+    Ok(DeliveryOutcome::Outbox)
+}
+
+/// How often queued deliveries are retried through their original channel
+pub const OUTBOX_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum outbox rows retried per pass
+const OUTBOX_RETRY_BATCH: usize = 100;
+
+/// How long to wait before retrying rows whose channel has no adapter or
+/// whose user has no live connection
+const OUTBOX_UNAVAILABLE_DELAY: Duration = Duration::from_secs(300);
+
+/// Result of one outbox delivery attempt
+enum RetryOutcome {
+    Sent,
+    /// The adapter rejected the message; counts towards dead-lettering
+    Failed,
+    /// Nothing to deliver through yet; retried later without counting
+    Unavailable,
+}
+
+/// Retry queued deliveries through the channel they were meant for
+///
+/// Returns how many messages were delivered. Rows are claimed first so a
+/// reconnecting WebSocket cannot flush the same message concurrently. Send
+/// failures back off and are dead-lettered after
+/// [`crate::db::outbox::MAX_DELIVERY_ATTEMPTS`]; rows whose channel is still
+/// unavailable stay queued for a later pass or the user's next connection.
+pub async fn retry_outbox(state: &ApiState) -> usize {
+    let repo = OutboxRepo::new(state.db.clone());
+    let claimed = match repo.claim_due(OUTBOX_RETRY_BATCH) {
+        Ok(claimed) => claimed,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to load outbox");
+            return 0;
         }
-        DeliveryChannel::WsPush => {
-            if let Some(senders) = &state.ws_senders {
-                let map = senders.read().await;
-                if let Some(tx) = map.get(channel_id) {
-                    let msg = crate::api::websocket::WsOutgoing::ChatChunk {
-                        content: message.to_string(),
-                    };
-                    if tx.send(msg).await.is_err() {
-                        tracing::warn!(user_id = channel_id, "ws_push: client disconnected");
-                    }
-                } else {
-                    tracing::warn!(user_id = channel_id, "ws_push: no active connection");
-                }
+    };
+
+    let mut delivered = 0;
+    for entry in claimed {
+        let outcome = if entry.channel == "ws_push" {
+            if push_ws(state, &entry.channel_id, &entry.content).await {
+                RetryOutcome::Sent
             } else {
-                tracing::warn!("ws_push: ws_senders not configured in ApiState");
+                RetryOutcome::Unavailable
+            }
+        } else {
+            let outgoing = outgoing_message(
+                &entry.channel_id,
+                entry.thread_id.as_deref(),
+                &entry.content,
+            );
+            match send_via(state, &entry.channel, outgoing).await {
+                Some(Ok(())) => RetryOutcome::Sent,
+                Some(Err(e)) => {
+                    tracing::debug!(channel = %entry.channel, error = %e, "outbox retry failed");
+                    RetryOutcome::Failed
+                }
+                None => RetryOutcome::Unavailable,
             }
+        };
+
+        let result = match outcome {
+            RetryOutcome::Sent => repo.mark_delivered(&entry.id).map(|()| delivered += 1),
+            RetryOutcome::Failed => repo.record_failure(&entry).map(|dead| {
+                if dead {
+                    tracing::warn!(
+                        id = %entry.id,
+                        channel = %entry.channel,
+                        "outbox message dead-lettered after repeated failures"
+                    );
+                }
+            }),
+            RetryOutcome::Unavailable => repo.release(&entry.id, OUTBOX_UNAVAILABLE_DELAY),
+        };
+        if let Err(e) = result {
+            tracing::warn!(error = %e, id = %entry.id, "failed to update outbox message");
         }
     }
+    delivered
+}
 
-    Ok(())
+/// Build a plain outbound message for a scheduled delivery
+fn outgoing_message(channel_id: &str, thread_id: Option<&str>, content: &str) -> OutgoingMessage {
+    OutgoingMessage {
+        channel_id: channel_id.to_string(),
+        content: content.to_string(),
+        reply_to: None,
+        thread_id: thread_id.map(String::from),
+        keyboard: None,
+        media: vec![],
+        edit_target: None,
+        voice_note: false,
+        attachments: vec![],
+    }
+}
+
+/// Send through the adapter for a channel name, or `None` if there is none
+///
+/// Extension channels are looked up at send time, so a hot-reloaded
+/// extension's replacement adapter is used.
+async fn send_via(
+    state: &ApiState,
+    channel: &str,
+    message: OutgoingMessage,
+) -> Option<crate::Result<()>> {
+    if let Some(adapter) = state.outbound_channels.get(channel) {
+        return Some(adapter.send(message).await);
+    }
+    match (channel, &state.telegram) {
+        ("telegram", Some(telegram)) => Some(telegram.send(message).await),
+        _ => None,
+    }
+}
+
+/// Push a message to a live WebSocket connection, returning whether it was sent
+async fn push_ws(state: &ApiState, key: &str, message: &str) -> bool {
+    let Some(senders) = &state.ws_senders else {
+        return false;
+    };
+    let Some(tx) = senders.read().await.get(key).cloned() else {
+        return false;
+    };
+
+    let msg = crate::api::websocket::WsOutgoing::ChatChunk {
+        content: message.to_string(),
+    };
+    if tx.send(msg).await.is_err() {
+        tracing::warn!(key, "ws_push: client disconnected");
+        return false;
+    }
+    true
+}
+
+/// Validate a callback against the configured webhook secret
+fn verify_callback(secret: &str, headers: &HeaderMap, body: &[u8]) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    if let Some(signature) = header("x-vortex-signature") {
+        return verify_signed(secret, Some(signature), header("x-vortex-timestamp"), body);
+    }
+
+    headers
+        .get("x-vortex-secret")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|provided| hmac::constant_time_eq(provided.as_bytes(), secret.as_bytes()))
 }
 
 #[cfg(test)]
@@ -446,4 +667,82 @@ mod tests {
         );
         assert!("unknown".parse::<DeliveryChannel>().is_err());
     }
+
+    #[test]
+    fn reminder_created_in_channel_is_routed_back_to_it() {
+        // Create: the slash command / tool stores the origin in the payload
+        let origin = crate::tools::ReminderOrigin::new("u1", "discord", "chan-42")
+            .with_thread(Some("thread-7".to_string()));
+        let stored = serde_json::to_value(origin.payload("Stand up")).unwrap();
+
+        // Callback: Vortex echoes the payload back
+        let callback: VortexCallback = serde_json::from_value(serde_json::json!({
+            "schedule_id": "sched_1",
+            "action": "remind",
+            "payload": stored,
+            "fired_at": "2024-01-08T09:00:00Z"
+        }))
+        .unwrap();
+        let payload: ReminderPayload = serde_json::from_value(callback.payload).unwrap();
+
+        // Deliver: routed to the originating channel, conversation, and thread
+        assert_eq!(
+            reminder_route(&payload),
+            DeliveryRoute {
+                channel: "discord",
+                channel_id: "chan-42",
+                thread_id: Some("thread-7"),
+                user_id: "u1",
+            }
+        );
+        assert_eq!(payload.message, "Stand up");
+    }
+
+    #[test]
+    fn ws_push_reminder_routes_to_user() {
+        let origin = crate::tools::ReminderOrigin::new("u1", "ws_push", "");
+        let payload = origin.payload("hi");
+        let route = reminder_route(&payload);
+        assert_eq!(route.channel_id, "u1");
+    }
+
+    #[test]
+    fn verifies_signature_and_shared_secret() {
+        let body = br#"{"schedule_id":"s"}"#;
+
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            .to_string();
+        let mut signed = ts.clone().into_bytes();
+        signed.push(b'.');
+        signed.extend_from_slice(body);
+
+        let mut headers = HeaderMap::new();
+        let sig = hmac::sign("s3cret", &signed);
+        headers.insert("x-vortex-signature", sig.parse().unwrap());
+        // A signature without its timestamp is rejected
+        assert!(!verify_callback("s3cret", &headers, body));
+        headers.insert("x-vortex-timestamp", ts.parse().unwrap());
+        assert!(verify_callback("s3cret", &headers, body));
+        assert!(!verify_callback("other", &headers, body));
+
+        // Stale timestamps fall outside the replay window
+        let mut headers = HeaderMap::new();
+        let stale = "1000";
+        let sig = hmac::sign(
+            "s3cret",
+            &[stale.as_bytes(), b".", body.as_slice()].concat(),
+        );
+        headers.insert("x-vortex-signature", sig.parse().unwrap());
+        headers.insert("x-vortex-timestamp", stale.parse().unwrap());
+        assert!(!verify_callback("s3cret", &headers, body));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-vortex-secret", "s3cret".parse().unwrap());
+        assert!(verify_callback("s3cret", &headers, body));
+
+        assert!(!verify_callback("s3cret", &HeaderMap::new(), body));
+    }
 }
@@ -6,7 +6,7 @@ use std::time::Duration;
 use axum::{
     Router,
     extract::{
-        Path, Query, State, WebSocketUpgrade,
+        Extension, Path, Query, State, WebSocketUpgrade,
         ws::{Message, WebSocket},
     },
     response::IntoResponse,
@@ -20,6 +20,7 @@ use tokio::sync::mpsc;
 use std::fmt::Write as _;
 
 use super::ApiState;
+use super::auth::AuthIdentity;
 use crate::agent::{AgentNotifyEvent, AgentRunConfig, run_agent_turn};
 use crate::api::feedback::{FeedbackAnswer, FeedbackManager};
 use crate::context::ContextBuilder;
@@ -37,6 +38,7 @@ struct WsQuery {
 pub enum WsIncoming {
     /// Send a chat message
     Chat {
+        #[serde(alias = "message")]
         content: String,
         /// Override the active persona for this message
         #[serde(default)]
@@ -65,6 +67,14 @@ pub enum WsOutgoing {
     ChatChunk { content: String },
     /// Chat response complete
     ChatComplete { message_id: String },
+    /// Response stream for the session has ended
+    Done { session_id: String },
+    /// Token usage for the completed turn
+    Usage {
+        session_id: String,
+        input_tokens: u32,
+        output_tokens: u32,
+    },
     /// Tool invocation started — emitted immediately on dispatch
     ToolStart { tool_id: String, name: String },
     /// Tool invocation finished
@@ -113,21 +123,59 @@ pub enum WsOutgoing {
 }
 
 /// Build WebSocket router
+///
+/// Credentials are only required outside cloud mode when
+/// `require_chat_auth` is set; cloud mode enforces the scope policy instead.
 pub fn router(state: Arc<ApiState>) -> Router {
-    Router::new()
-        .route("/chat/{session_id}", get(ws_upgrade))
-        .with_state(state)
+    let router = Router::new().route(
+        "/chat/{session_id}",
+        get(ws_upgrade).route_layer(axum::middleware::from_fn_with_state(
+            super::auth::RequireScope("chat"),
+            super::auth::require_scope,
+        )),
+    );
+    let router = if state.require_chat_auth {
+        router.layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            super::auth::require_auth,
+        ))
+    } else {
+        router
+    };
+    router.with_state(state)
 }
 
 /// Handle WebSocket upgrade request
 async fn ws_upgrade(
     State(state): State<Arc<ApiState>>,
     Path(session_id): Path<String>,
+    identity: Option<Extension<AuthIdentity>>,
     query: Query<WsQuery>,
     ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
-    let token = query.0.token;
-    ws.on_upgrade(move |socket| handle_socket(socket, state, session_id, token))
+    let user_id = match super::chat::caller_user_id(identity) {
+        Some(user_id) => Some(user_id),
+        None => jwt_user_id(&state, query.0.token.as_deref()).await,
+    };
+    ws.on_upgrade(move |socket| handle_socket(socket, state, session_id, user_id))
+}
+
+/// Resolve the Gatekeeper user of a `?token=` JWT when no auth layer ran
+///
+/// Invalid tokens fall back to the session identity rather than refusing the
+/// upgrade; cloud mode rejects unauthenticated sockets afterwards.
+async fn jwt_user_id(state: &ApiState, token: Option<&str>) -> Option<String> {
+    let jwt_cache = state.jwt_cache.as_ref()?;
+    match jwt_cache.validate(token?).await {
+        Ok(claims) => {
+            tracing::info!(user_id = %claims.sub, "authenticated via Gatekeeper JWT");
+            Some(claims.sub)
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "JWT validation failed, using session identity");
+            None
+        }
+    }
 }
 
 /// Handle WebSocket connection
@@ -136,7 +184,7 @@ async fn handle_socket(
     socket: WebSocket,
     state: Arc<ApiState>,
     session_id: String,
-    token: Option<String>,
+    gatekeeper_user_id: Option<String>,
 ) {
     let (mut sender, mut receiver) = socket.split();
 
@@ -154,33 +202,10 @@ async fn handle_socket(
 
     tracing::info!(session_id = %session_id, "WebSocket connected");
 
-    // Validate JWT if provided (resolve Gatekeeper user ID)
-    let gatekeeper_user_id = if let Some(ref jwt_cache) = state.jwt_cache {
-        if let Some(ref token) = token {
-            match jwt_cache.validate(token).await {
-                Ok(claims) => {
-                    tracing::info!(user_id = %claims.sub, "authenticated via Gatekeeper JWT");
-                    Some(claims.sub)
-                }
-                Err(e) => {
-                    tracing::warn!(error = %e, "JWT validation failed, using session identity");
-                    None
-                }
-            }
-        } else {
-            tracing::debug!(session_id = %session_id, "no JWT token provided in WebSocket query");
-            None
-        }
-    } else {
-        tracing::debug!(session_id = %session_id, "no jwt_cache configured, skipping JWT validation");
-        None
-    };
-
-    // Cloud mode: require valid JWT
+    // Cloud mode: require a user identity (JWT or per-user key)
     if state.cloud_mode && gatekeeper_user_id.is_none() {
         tracing::warn!(
             session_id = %session_id,
-            token_provided = token.is_some(),
             jwt_cache_configured = state.jwt_cache.is_some(),
             "cloud mode: rejecting unauthenticated WebSocket"
         );
@@ -221,6 +246,9 @@ async fn handle_socket(
         }
     });
 
+    // Deliver scheduled messages that arrived while the user was offline
+    flush_outbox(&state, &ws_push_key, &tx).await;
+
     // Clone state for deregistration after tasks complete
     let state_for_cleanup = Arc::clone(&state);
 
@@ -291,6 +319,42 @@ async fn handle_socket(
     tracing::info!(session_id = %session_id, "WebSocket disconnected");
 }
 
+/// Send pending outbox messages to a newly connected client
+///
+/// Rows are claimed first so the outbox retry loop cannot deliver the same
+/// message concurrently; anything not sent is released again.
+async fn flush_outbox(state: &ApiState, user_id: &str, tx: &mpsc::Sender<WsOutgoing>) {
+    let repo = crate::db::OutboxRepo::new(state.db.clone());
+    let claimed = match repo.claim_for_user(user_id) {
+        Ok(claimed) => claimed,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to load outbox");
+            return;
+        }
+    };
+
+    let mut messages = claimed.into_iter();
+    for message in messages.by_ref() {
+        let msg = WsOutgoing::ChatChunk {
+            content: message.content.clone(),
+        };
+        if tx.send(msg).await.is_err() {
+            if let Err(e) = repo.release(&message.id, Duration::ZERO) {
+                tracing::warn!(error = %e, id = %message.id, "failed to release outbox message");
+            }
+            break;
+        }
+        if let Err(e) = repo.mark_delivered(&message.id) {
+            tracing::warn!(error = %e, id = %message.id, "failed to mark outbox message delivered");
+        }
+    }
+    for message in messages {
+        if let Err(e) = repo.release(&message.id, Duration::ZERO) {
+            tracing::warn!(error = %e, id = %message.id, "failed to release outbox message");
+        }
+    }
+}
+
 /// Handle a single incoming message
 async fn handle_message(
     text: &str,
@@ -343,7 +407,7 @@ async fn handle_message(
 
 /// Handle a chat message and stream the response
 #[allow(clippy::too_many_lines, clippy::too_many_arguments)]
-async fn handle_chat_message(
+pub(super) async fn handle_chat_message(
     content: &str,
     persona_id_override: Option<String>,
     msg_model_override: Option<String>,
@@ -418,6 +482,9 @@ async fn handle_chat_message(
         .find_or_create(&user_id, "web", session_id, &active_persona_id)
         .map_err(|e| crate::Error::Database(e.to_string()))?;
 
+    // Memories follow the persona resolved for this turn, not the startup one
+    let memory_repo = state.memory_repo.for_persona(&active_persona_id);
+
     tracing::info!(
         active_persona_id = %active_persona_id,
         has_persona_prompt = active_system_prompt.is_some(),
@@ -426,8 +493,11 @@ async fn handle_chat_message(
     );
 
     // Build context config with active persona
-    let context_config =
-        crate::api::ApiServer::context_config(&active_persona_id, active_system_prompt);
+    let context_config = crate::api::ApiServer::context_config(
+        &active_persona_id,
+        active_system_prompt,
+        msg_model_override.as_deref().unwrap_or(&state.llm_model),
+    );
     let context_builder = ContextBuilder::new(context_config);
 
     // Embed user message for semantic memory retrieval when embedder is available
@@ -448,13 +518,14 @@ async fn handle_chat_message(
         user.life_json_path.as_deref(),
         &state.session_repo,
         &state.user_repo,
-        Some(&state.memory_repo),
+        Some(&memory_repo),
         query_embedding.as_deref(),
     );
 
     // Inject knowledge based on conversation context + embeddings
+    let persona_knowledge = state.persona_knowledge.read().await.clone();
     if let Ok(ref mut ctx) = built_context
-        && !state.persona_knowledge.is_empty()
+        && !persona_knowledge.is_empty()
     {
         // Build multi-turn retrieval query from recent session history
         let prior_texts: Vec<String> = state
@@ -480,7 +551,7 @@ async fn handle_chat_message(
         let max_knowledge_tokens = state.max_context_tokens / 4;
         let selected = if let Some(ref reranker) = state.reranker {
             agent_core::knowledge::select_knowledge_reranked(
-                &state.persona_knowledge,
+                &persona_knowledge,
                 &retrieval_query,
                 query_embedding.as_deref(),
                 reranker.as_ref(),
@@ -489,7 +560,7 @@ async fn handle_chat_message(
             .await
         } else {
             crate::knowledge::select_knowledge_with_embeddings(
-                &state.persona_knowledge,
+                &persona_knowledge,
                 &retrieval_query,
                 query_embedding.as_deref(),
                 max_knowledge_tokens,
@@ -505,6 +576,21 @@ async fn handle_chat_message(
         .as_ref()
         .map_or_else(|_| content.to_string(), |ctx| ctx.format_prompt(content));
 
+    // Compact long sessions in the background; this turn keeps the history
+    // already in the prompt and later turns see the summary
+    if let Some(ref compactor) = state.session_compactor {
+        compactor.compact_in_background(
+            &session.id,
+            state.session_repo.clone(),
+            memory_repo.clone(),
+            state
+                .indexer
+                .as_ref()
+                .map(|indexer| Arc::new(indexer.for_persona(&active_persona_id))),
+            &user_id,
+        );
+    }
+
     // Resolve Synapse client for this user (BYOK or default)
     let (synapse_to_use, model_override) = if let (Some(gk_user_id), Some(resolver)) =
         (&gatekeeper_user_id, &state.key_resolver)
@@ -579,9 +665,15 @@ async fn handle_chat_message(
     );
 
     // Resolve slash command if present
-    let slash_action =
-        crate::skills::resolve_slash_command(content, &state.skill_repo, gatekeeper_user_id)
-            .unwrap_or(None);
+    let slash_action = crate::skills::resolve_slash_command(
+        content,
+        &state.skill_repo,
+        gatekeeper_user_id,
+        Some("web"),
+        &state.remote_skills,
+    )
+    .await
+    .unwrap_or(None);
 
     // Handle tool dispatch: execute tool directly and return result without LLM
     if let Some(crate::skills::SlashCommandAction::DispatchTool {
@@ -594,7 +686,15 @@ async fn handle_chat_message(
             Arc::clone(&synapse),
             state.plugin_manager.clone(),
         )
-        .with_exec_tool(Arc::new(crate::tools::BuiltinExecTool::default()));
+        .with_exec_tool(Arc::new(crate::tools::BuiltinExecTool::default()))
+        .with_fetch_cache(Arc::clone(&state.fetch_cache))
+        .with_policy(Arc::clone(&state.tool_policy), "web")
+        .with_session_profile(
+            session
+                .tool_profile
+                .as_deref()
+                .and_then(crate::tools::SessionToolProfile::parse),
+        );
         let result = executor
             .execute(tool_name, arguments)
             .await
@@ -614,6 +714,14 @@ async fn handle_chat_message(
         return Ok(());
     }
 
+    // Unknown command close to an existing one: the runner replies with this
+    let slash_suggestion = match &slash_action {
+        Some(crate::skills::SlashCommandAction::Suggest { candidates }) => {
+            Some(crate::skills::format_suggestion(candidates))
+        }
+        _ => None,
+    };
+
     // Extract content and slash skill for prompt injection path
     let injected_remaining: Option<String>;
     let slash_skill;
@@ -634,7 +742,7 @@ async fn handle_chat_message(
     let mut system_prompt = if active_persona_id == crate::NO_PERSONA_ID {
         String::new()
     } else {
-        state.system_prompt_with_skills(gatekeeper_user_id)
+        state.system_prompt_with_skills(gatekeeper_user_id, Some("web"))
     };
 
     // Append invoked slash skill to system prompt
@@ -646,12 +754,20 @@ async fn handle_chat_message(
         );
     }
 
-    // Bridge: forward AgentNotifyEvent → WsOutgoing::ToolStart/ToolResult to client
+    // Bridge: forward AgentNotifyEvent → WsOutgoing::ChatChunk/ToolStart/ToolResult/Usage to
+    // client. Shares `tx` with ws_push, so proactive messages interleave with the stream.
+    // Resolves to whether any response text was streamed
     let (notify_tx, mut notify_rx) = mpsc::channel::<AgentNotifyEvent>(32);
     let tx_notify = tx.clone();
-    let notify_bridge = tokio::spawn(async move {
+    let stream_session_id = session_id.to_string();
+    let mut notify_bridge = tokio::spawn(async move {
+        let mut streamed = false;
         while let Some(event) = notify_rx.recv().await {
             let ws_msg = match event {
+                AgentNotifyEvent::Delta { text } => {
+                    streamed = true;
+                    WsOutgoing::ChatChunk { content: text }
+                }
                 AgentNotifyEvent::ToolStart { tool_id, name } => {
                     WsOutgoing::ToolStart { tool_id, name }
                 }
@@ -668,9 +784,18 @@ async fn handle_chat_message(
                     output,
                     is_error,
                 },
+                AgentNotifyEvent::Usage {
+                    input_tokens,
+                    output_tokens,
+                } => WsOutgoing::Usage {
+                    session_id: stream_session_id.clone(),
+                    input_tokens,
+                    output_tokens,
+                },
             };
             let _ = tx_notify.send(ws_msg).await;
         }
+        streamed
     });
 
     let agent_config = AgentRunConfig {
@@ -681,6 +806,10 @@ async fn handle_chat_message(
         max_iterations: 10,
         session_id: session.id.clone(),
         user_id: user_id.clone(),
+        persona_id: active_persona_id.clone(),
+        channel: "web".to_string(),
+        tool_profile: session.tool_profile.clone(),
+        slash_suggestion,
         notify: Some(notify_tx),
         synapse_override: Some(synapse),
     };
@@ -711,7 +840,22 @@ async fn handle_chat_message(
 
     let agent_result = run_agent_turn(state, agent_config).await;
     heartbeat.abort();
-    notify_bridge.abort();
+
+    // The notify sender was dropped with the config; drain remaining chunks
+    // so `Done` is never sent ahead of them
+    let streamed = match tokio::time::timeout(Duration::from_secs(5), &mut notify_bridge).await {
+        Ok(result) => result.unwrap_or(false),
+        // A stalled bridge may already have forwarded part of the response
+        Err(_) => {
+            notify_bridge.abort();
+            true
+        }
+    };
+    tx.send(WsOutgoing::Done {
+        session_id: session_id.to_string(),
+    })
+    .await
+    .map_err(|_| crate::Error::Config("channel closed".to_string()))?;
 
     let full_response = match agent_result {
         Ok(text) => text,
@@ -721,9 +865,16 @@ async fn handle_chat_message(
                 error = %e,
                 "agent turn failed"
             );
-            let error = WsOutgoing::Error {
-                code: "agent_error".to_string(),
-                message: e.to_string(),
+            let error = if matches!(e, crate::Error::Unavailable(_)) {
+                WsOutgoing::Error {
+                    code: e.code().to_string(),
+                    message: crate::synapse::UNAVAILABLE_REPLY.to_string(),
+                }
+            } else {
+                WsOutgoing::Error {
+                    code: "agent_error".to_string(),
+                    message: e.to_string(),
+                }
             };
             tx.send(error)
                 .await
@@ -732,8 +883,9 @@ async fn handle_chat_message(
         }
     };
 
-    // Stream the full response as a single chunk so WS clients receive it
-    if !full_response.is_empty() {
+    // Replies that never reached the stream (e.g. slash command suggestions)
+    // are sent whole
+    if !streamed && !full_response.is_empty() {
         tx.send(WsOutgoing::ChatChunk {
             content: full_response.clone(),
         })
@@ -762,7 +914,11 @@ async fn handle_chat_message(
         .map_err(|_| crate::Error::Config("channel closed".to_string()))?;
 
     // Post-turn background fact extraction — fire-and-forget, never blocks the response
-    if let Some(indexer) = state.indexer.clone() {
+    if let Some(indexer) = state
+        .indexer
+        .as_ref()
+        .map(|indexer| indexer.for_persona(&active_persona_id))
+    {
         let uid = user_id.clone();
         let user_msg = content.to_string();
         let assistant_msg = full_response.clone();
@@ -928,6 +1084,22 @@ mod tests {
         assert!(json.contains("\"tool_id\":\"abc\""));
     }
 
+    #[test]
+    fn done_serializes() {
+        let done = WsOutgoing::Done {
+            session_id: "web-1".to_string(),
+        };
+        let json = serde_json::to_string(&done).unwrap();
+        assert!(json.contains("\"type\":\"done\""));
+    }
+
+    #[test]
+    fn chat_accepts_message_field() {
+        let json = r#"{"type":"chat","message":"hi"}"#;
+        let msg: WsIncoming = serde_json::from_str(json).unwrap();
+        assert!(matches!(msg, WsIncoming::Chat { content, .. } if content == "hi"));
+    }
+
     #[test]
     fn ask_user_serializes() {
         let msg = WsOutgoing::AskUser {
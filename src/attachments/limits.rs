@@ -0,0 +1,140 @@
+//! Download limits for attachments fetched by URL
+//!
+//! Attachment URLs come from chat platforms (often signed CDN links) but are
+//! ultimately user-controlled, so downloads are capped in size and time and
+//! must declare a content type that fits the attachment kind.
+
+use std::time::Duration;
+
+use crate::channels::AttachmentKind;
+
+/// Default cap for image downloads
+pub const DEFAULT_MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Default cap for audio downloads
+pub const DEFAULT_MAX_AUDIO_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Default cap for video downloads
+pub const DEFAULT_MAX_VIDEO_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Default cap for other file downloads
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Default time allowed for a whole download
+pub const DEFAULT_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Size and time limits for attachment downloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttachmentLimits {
+    pub max_image_bytes: u64,
+    pub max_audio_bytes: u64,
+    pub max_video_bytes: u64,
+    pub max_file_bytes: u64,
+    /// Time allowed for a whole download, including the body
+    pub timeout: Duration,
+}
+
+impl Default for AttachmentLimits {
+    fn default() -> Self {
+        Self {
+            max_image_bytes: DEFAULT_MAX_IMAGE_BYTES,
+            max_audio_bytes: DEFAULT_MAX_AUDIO_BYTES,
+            max_video_bytes: DEFAULT_MAX_VIDEO_BYTES,
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            timeout: DEFAULT_DOWNLOAD_TIMEOUT,
+        }
+    }
+}
+
+impl AttachmentLimits {
+    /// Limits from `BEACON_ATTACHMENT_MAX_{IMAGE,AUDIO,VIDEO,FILE}_MB` and
+    /// `BEACON_ATTACHMENT_TIMEOUT_SECS`
+    #[must_use]
+    pub fn from_env() -> Self {
+        let mb = |var: &str, default: u64| {
+            std::env::var(var)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map_or(default, |mb| mb.saturating_mul(1024 * 1024))
+        };
+        let default = Self::default();
+        Self {
+            max_image_bytes: mb("BEACON_ATTACHMENT_MAX_IMAGE_MB", default.max_image_bytes),
+            max_audio_bytes: mb("BEACON_ATTACHMENT_MAX_AUDIO_MB", default.max_audio_bytes),
+            max_video_bytes: mb("BEACON_ATTACHMENT_MAX_VIDEO_MB", default.max_video_bytes),
+            max_file_bytes: mb("BEACON_ATTACHMENT_MAX_FILE_MB", default.max_file_bytes),
+            timeout: std::env::var("BEACON_ATTACHMENT_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map_or(default.timeout, Duration::from_secs),
+        }
+    }
+
+    /// Largest download accepted for an attachment kind
+    #[must_use]
+    pub const fn max_bytes(&self, kind: AttachmentKind) -> u64 {
+        match kind {
+            AttachmentKind::Image => self.max_image_bytes,
+            AttachmentKind::Audio => self.max_audio_bytes,
+            AttachmentKind::Video => self.max_video_bytes,
+            AttachmentKind::File => self.max_file_bytes,
+        }
+    }
+}
+
+/// Whether a served content type is acceptable for an attachment kind
+///
+/// Generic binary types are accepted because many CDNs serve every file as
+/// `application/octet-stream`; anything else must match the kind, which
+/// rejects HTML error pages from expired signed URLs.
+#[must_use]
+pub fn content_type_allowed(kind: AttachmentKind, content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if essence.is_empty() || essence == "application/octet-stream" {
+        return true;
+    }
+
+    match kind {
+        AttachmentKind::Image => essence.starts_with("image/"),
+        AttachmentKind::Audio => {
+            essence.starts_with("audio/") || essence == "application/ogg" || essence == "video/ogg"
+        }
+        AttachmentKind::Video => essence.starts_with("video/"),
+        AttachmentKind::File => essence != "text/html",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn images_are_capped_below_files() {
+        let limits = AttachmentLimits::default();
+        assert!(limits.max_bytes(AttachmentKind::Image) < limits.max_bytes(AttachmentKind::File));
+    }
+
+    #[test]
+    fn content_types_match_kind() {
+        assert!(content_type_allowed(AttachmentKind::Image, "image/png"));
+        assert!(content_type_allowed(
+            AttachmentKind::Audio,
+            "audio/ogg; codecs=opus"
+        ));
+        assert!(content_type_allowed(
+            AttachmentKind::Image,
+            "application/octet-stream"
+        ));
+        assert!(!content_type_allowed(
+            AttachmentKind::Image,
+            "text/html; charset=utf-8"
+        ));
+        assert!(!content_type_allowed(AttachmentKind::Video, "image/gif"));
+        assert!(!content_type_allowed(AttachmentKind::File, "text/html"));
+    }
+}
@@ -2,27 +2,52 @@
 //!
 //! Processes images, audio, and other attachments to augment message context
 
+mod limits;
+mod video;
 mod vision;
 
 use std::sync::Arc;
 
 use synapse_client::SynapseClient;
+use tokio::sync::Semaphore;
 
 use crate::Result;
 use crate::channels::{Attachment, AttachmentKind};
+use crate::media::language::resolve_language;
+use crate::media::providers::WhisperProvider;
+use crate::media::{MediaAnalysis, MediaCache, MediaProcessor};
 
+pub use limits::{AttachmentLimits, content_type_allowed};
+pub use video::{DEFAULT_KEYFRAMES, keyframes_from_env};
 pub use vision::VisionClient;
 
+/// Default number of attachments processed concurrently
+pub const DEFAULT_MAX_CONCURRENT_ATTACHMENTS: usize = 4;
+
 /// Processes attachments and returns text descriptions/transcriptions
 pub struct AttachmentProcessor {
     /// Vision client for image analysis
     vision: Option<Arc<VisionClient>>,
+    /// Media provider chain tried when the vision client is absent or fails
+    media: Option<Arc<MediaProcessor>>,
     /// Synapse client for audio transcription
     synapse: Option<Arc<SynapseClient>>,
     /// STT model identifier for Synapse transcription
     stt_model: String,
     /// HTTP client for downloading attachments
     client: reqwest::Client,
+    /// Cache of image descriptions keyed by content hash
+    cache: MediaCache,
+    /// Default STT language hint (BCP 47); `None` auto-detects
+    stt_language: Option<String>,
+    /// Whisper provider used when a language hint applies
+    whisper: Option<Arc<WhisperProvider>>,
+    /// Keyframes described per video (0 disables frame analysis)
+    video_keyframes: usize,
+    /// Caps concurrent attachment processing across all messages
+    concurrency: Arc<Semaphore>,
+    /// Size and time limits for downloads
+    limits: AttachmentLimits,
 }
 
 impl AttachmentProcessor {
@@ -35,45 +60,131 @@ impl AttachmentProcessor {
     ) -> Self {
         Self {
             vision,
+            media: None,
             synapse,
             stt_model,
             client: reqwest::Client::new(),
+            cache: MediaCache::default(),
+            stt_language: None,
+            whisper: None,
+            video_keyframes: DEFAULT_KEYFRAMES,
+            concurrency: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_ATTACHMENTS)),
+            limits: AttachmentLimits::default(),
         }
     }
 
+    /// Set the media provider chain used as an image description fallback
+    ///
+    /// A processor without any configured provider is ignored.
+    #[must_use]
+    pub fn with_media(mut self, media: MediaProcessor) -> Self {
+        self.media = (!media.provider_names().is_empty()).then(|| Arc::new(media));
+        self
+    }
+
+    /// Set the default STT language hint (persona or configuration)
+    #[must_use]
+    pub fn with_stt_language(mut self, language: Option<String>) -> Self {
+        self.stt_language = language;
+        self
+    }
+
+    /// Set the Whisper provider used for language-hinted transcription
+    ///
+    /// Synapse transcription takes no language parameter, so audio is sent to
+    /// Whisper directly whenever a hint resolves.
+    #[must_use]
+    pub fn with_whisper(mut self, whisper: Arc<WhisperProvider>) -> Self {
+        self.whisper = Some(whisper);
+        self
+    }
+
+    /// Set how many keyframes are described per video
+    ///
+    /// More frames give better coverage of long videos at the cost of one
+    /// vision request each. Zero skips frame analysis entirely.
+    #[must_use]
+    pub const fn with_video_keyframes(mut self, count: usize) -> Self {
+        self.video_keyframes = count;
+        self
+    }
+
+    /// Set how many attachments may be processed at once (minimum 1)
+    ///
+    /// The limit is shared by every message handled by this processor so
+    /// bursts don't overwhelm the vision and STT backends.
+    #[must_use]
+    pub fn with_max_concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = Arc::new(Semaphore::new(limit.max(1)));
+        self
+    }
+
+    /// Set the download size and time limits
+    #[must_use]
+    pub const fn with_limits(mut self, limits: AttachmentLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set the image description cache
+    #[must_use]
+    pub fn with_cache(mut self, cache: MediaCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
     /// Process all attachments and return augmented text
     ///
     /// # Errors
     ///
     /// Returns error if attachment processing fails
     pub async fn process_attachments(&self, attachments: &[Attachment]) -> Result<String> {
+        self.process_attachments_for(attachments, None).await
+    }
+
+    /// Process all attachments with a per-user STT language hint
+    ///
+    /// The user's hint takes precedence over the default one; with neither,
+    /// transcription auto-detects the language.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if attachment processing fails
+    pub async fn process_attachments_for(
+        &self,
+        attachments: &[Attachment],
+        user_language: Option<&str>,
+    ) -> Result<String> {
         if attachments.is_empty() {
             return Ok(String::new());
         }
 
-        let mut parts = Vec::new();
+        let language = resolve_language(&[user_language, self.stt_language.as_deref()]);
 
-        for attachment in attachments {
-            let description = self.process_single(attachment).await;
-            parts.push(description);
-        }
+        // Failures yield a fallback string per attachment, so one bad
+        // attachment never aborts the batch; join_all preserves input order
+        let parts = futures::future::join_all(attachments.iter().map(|attachment| async {
+            let _permit = self.concurrency.acquire().await;
+            self.process_single(attachment, language.as_deref()).await
+        }))
+        .await;
 
         Ok(parts.join("\n"))
     }
 
     /// Process a single attachment
-    async fn process_single(&self, attachment: &Attachment) -> String {
+    async fn process_single(&self, attachment: &Attachment, language: Option<&str>) -> String {
         match attachment.kind {
             AttachmentKind::Image => self.process_image(attachment).await,
-            AttachmentKind::Audio => self.process_audio(attachment).await,
-            AttachmentKind::Video => self.process_video(attachment),
+            AttachmentKind::Audio => self.process_audio(attachment, language).await,
+            AttachmentKind::Video => self.process_video(attachment).await,
             AttachmentKind::File => self.process_file(attachment),
         }
     }
 
     /// Process an image attachment using vision
     async fn process_image(&self, attachment: &Attachment) -> String {
-        let Some(vision) = &self.vision else {
+        if !self.can_describe_images() {
             return format!(
                 "[Image: {}]",
                 attachment.filename.as_deref().unwrap_or("image")
@@ -92,12 +203,34 @@ impl AttachmentProcessor {
             }
         };
 
+        // Reuse a previous description of identical bytes
+        if let Some(description) = self
+            .cache
+            .get(&image_data, &attachment.mime_type)
+            .and_then(|analysis| analysis.description)
+        {
+            tracing::debug!("image description cache hit");
+            return format!(
+                "[Image: {}]\n{}",
+                attachment.filename.as_deref().unwrap_or("image"),
+                description
+            );
+        }
+
         // Analyze with vision
-        match vision
+        match self
             .describe_image(&image_data, &attachment.mime_type)
             .await
         {
             Ok(description) => {
+                self.cache.insert(
+                    &image_data,
+                    &attachment.mime_type,
+                    MediaAnalysis {
+                        description: Some(description.clone()),
+                        ..MediaAnalysis::default()
+                    },
+                );
                 format!(
                     "[Image: {}]\n{}",
                     attachment.filename.as_deref().unwrap_or("image"),
@@ -114,14 +247,15 @@ impl AttachmentProcessor {
         }
     }
 
-    /// Process an audio attachment using Synapse STT
-    async fn process_audio(&self, attachment: &Attachment) -> String {
-        let Some(synapse) = &self.synapse else {
+    /// Process an audio attachment using Synapse STT (or Whisper with a language hint)
+    async fn process_audio(&self, attachment: &Attachment, language: Option<&str>) -> String {
+        let whisper = self.whisper.as_ref().filter(|_| language.is_some());
+        if whisper.is_none() && self.synapse.is_none() {
             return format!(
                 "[Audio: {}]",
                 attachment.filename.as_deref().unwrap_or("audio")
             );
-        };
+        }
 
         // Get audio data
         let audio_data = match self.get_attachment_data(attachment).await {
@@ -147,16 +281,25 @@ impl AttachmentProcessor {
             }
         };
 
-        let filename = attachment.filename.as_deref().unwrap_or("audio.wav");
-        match synapse
-            .transcribe(wav_data.into(), filename, &self.stt_model)
-            .await
-        {
-            Ok(result) => {
+        let transcript = if let Some(whisper) = whisper {
+            whisper.transcribe(&wav_data, "audio/wav", language).await
+        } else if let Some(synapse) = &self.synapse {
+            let filename = attachment.filename.as_deref().unwrap_or("audio.wav");
+            synapse
+                .transcribe(wav_data.into(), filename, &self.stt_model)
+                .await
+                .map(|result| result.text)
+                .map_err(|e| crate::Error::Stt(e.to_string()))
+        } else {
+            Err(crate::Error::Stt("no transcription provider".to_string()))
+        };
+
+        match transcript {
+            Ok(text) => {
                 format!(
                     "[Audio transcription: {}]\n\"{}\"",
                     attachment.filename.as_deref().unwrap_or("audio"),
-                    result.text
+                    text
                 )
             }
             Err(e) => {
@@ -169,13 +312,99 @@ impl AttachmentProcessor {
         }
     }
 
-    /// Process a video attachment (metadata only for now)
-    #[allow(clippy::unused_self)]
-    fn process_video(&self, attachment: &Attachment) -> String {
-        format!(
-            "[Video: {}]",
-            attachment.filename.as_deref().unwrap_or("video")
-        )
+    /// Process a video attachment by describing evenly spaced keyframes
+    ///
+    /// Falls back to a metadata stub when vision is unavailable, `ffmpeg` is
+    /// not on PATH, or frames cannot be extracted.
+    async fn process_video(&self, attachment: &Attachment) -> String {
+        let filename = attachment.filename.as_deref().unwrap_or("video");
+        let stub = format!("[Video: {filename}]");
+
+        if !self.can_describe_images() || self.video_keyframes == 0 {
+            return stub;
+        }
+        let Some(ffmpeg) = video::ffmpeg_path() else {
+            tracing::debug!("ffmpeg not found, skipping video keyframe analysis");
+            return stub;
+        };
+
+        // Get video data
+        let video_data = match self.get_attachment_data(attachment).await {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to download video");
+                return format!("[Video: {filename} (could not download)]");
+            }
+        };
+
+        let frames =
+            match video::extract_keyframes(&ffmpeg, &video_data, self.video_keyframes).await {
+                Ok(frames) => frames,
+                Err(e) => {
+                    tracing::warn!(error = %e, "video keyframe extraction failed");
+                    return stub;
+                }
+            };
+
+        let mut descriptions = Vec::with_capacity(frames.len());
+        for frame in &frames {
+            match self.describe_image(&frame.data, "image/jpeg").await {
+                Ok(description) => descriptions.push(format!(
+                    "[{}] {}",
+                    video::format_timestamp(frame.timestamp),
+                    description
+                )),
+                Err(e) => tracing::warn!(
+                    error = %e,
+                    timestamp = frame.timestamp,
+                    "keyframe analysis failed"
+                ),
+            }
+        }
+
+        if descriptions.is_empty() {
+            return format!("[Video: {filename} (analysis failed)]");
+        }
+
+        format!("[Video: {filename}]\n{}", descriptions.join("\n"))
+    }
+
+    /// Whether any image description backend is configured
+    const fn can_describe_images(&self) -> bool {
+        self.vision.is_some() || self.media.is_some()
+    }
+
+    /// Describe an image with the vision client, then the media provider chain
+    async fn describe_image(&self, data: &[u8], mime_type: &str) -> Result<String> {
+        let mut last_error = None;
+
+        if let Some(vision) = &self.vision {
+            match vision.describe_image(data, mime_type).await {
+                Ok(description) => return Ok(description),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        if let Some(media) = &self.media {
+            if let Some(vision_error) = &last_error {
+                tracing::debug!(error = %vision_error, "vision failed, trying media providers");
+            }
+            match media.process(data, mime_type).await {
+                Ok(MediaAnalysis {
+                    description: Some(description),
+                    ..
+                }) => return Ok(description),
+                Ok(_) => {
+                    last_error = Some(crate::Error::Media(
+                        "media provider returned no description".to_string(),
+                    ));
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| crate::Error::Media("no image description backend".to_string())))
     }
 
     /// Process a generic file attachment
@@ -190,8 +419,13 @@ impl AttachmentProcessor {
 
     /// Get attachment data from URL or inline data
     async fn get_attachment_data(&self, attachment: &Attachment) -> Result<Vec<u8>> {
+        let max_bytes = self.limits.max_bytes(attachment.kind);
+
         // If we have inline data, use it
         if let Some(data) = &attachment.data {
+            if data.len() as u64 > max_bytes {
+                return Err(too_large(attachment.kind, max_bytes));
+            }
             return Ok(data.clone());
         }
 
@@ -201,7 +435,22 @@ impl AttachmentProcessor {
             .as_ref()
             .ok_or_else(|| crate::Error::Attachment("No URL or data for attachment".to_string()))?;
 
-        let response = self
+        tokio::time::timeout(
+            self.limits.timeout,
+            self.download(url, attachment.kind, max_bytes),
+        )
+        .await
+        .map_err(|_| {
+            crate::Error::Attachment(format!(
+                "Download timed out after {}s",
+                self.limits.timeout.as_secs()
+            ))
+        })?
+    }
+
+    /// Download an attachment body, aborting once it exceeds `max_bytes`
+    async fn download(&self, url: &str, kind: AttachmentKind, max_bytes: u64) -> Result<Vec<u8>> {
+        let mut response = self
             .client
             .get(url)
             .send()
@@ -215,26 +464,67 @@ impl AttachmentProcessor {
             )));
         }
 
-        let bytes = response
-            .bytes()
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        if !content_type_allowed(kind, content_type) {
+            return Err(crate::Error::Attachment(format!(
+                "Download rejected: unexpected content type {content_type} for {kind:?} attachment"
+            )));
+        }
+
+        // Reject up front when the server announces the size
+        if response.content_length().is_some_and(|len| len > max_bytes) {
+            return Err(too_large(kind, max_bytes));
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
             .await
-            .map_err(|e| crate::Error::Attachment(format!("Read failed: {e}")))?;
+            .map_err(|e| crate::Error::Attachment(format!("Read failed: {e}")))?
+        {
+            if (data.len() + chunk.len()) as u64 > max_bytes {
+                return Err(too_large(kind, max_bytes));
+            }
+            data.extend_from_slice(&chunk);
+        }
 
-        Ok(bytes.to_vec())
+        Ok(data)
     }
 }
 
+/// Error for an attachment over its size limit
+fn too_large(kind: AttachmentKind, max_bytes: u64) -> crate::Error {
+    crate::Error::AttachmentTooLarge(format!(
+        "{kind:?} attachment exceeds limit of {max_bytes} bytes"
+    ))
+}
+
+/// Opus always decodes at 48kHz
+#[cfg(feature = "opus")]
+const OPUS_SAMPLE_RATE: u32 = 48_000;
+
+/// Largest Opus frame in samples (120ms at 48kHz)
+#[cfg(feature = "opus")]
+const OPUS_MAX_FRAME: usize = 5760;
+
 /// Convert audio to WAV format for STT
 fn convert_to_wav(data: &[u8], mime_type: &str) -> Result<Vec<u8>> {
-    match mime_type {
+    // Ignore parameters such as `audio/ogg; codecs=opus`
+    let essence = mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    match essence.as_str() {
         "audio/wav" | "audio/wave" | "audio/x-wav" => Ok(data.to_vec()),
         "audio/mpeg" | "audio/mp3" => convert_mp3_to_wav(data),
-        "audio/ogg" | "audio/opus" => {
-            // OGG/Opus conversion would require additional dependencies
-            Err(crate::Error::Attachment(
-                "OGG/Opus conversion not yet supported".to_string(),
-            ))
-        }
+        "audio/ogg" | "audio/opus" => convert_opus_to_wav(data),
         _ => {
             // Try to use as-is and let Whisper handle it
             Ok(data.to_vec())
@@ -285,6 +575,73 @@ fn convert_mp3_to_wav(mp3_data: &[u8]) -> Result<Vec<u8>> {
     samples_to_wav(&resampled, 16000)
 }
 
+/// Convert OGG/Opus (e.g. Telegram and WhatsApp voice notes) to WAV using libopus
+#[cfg(feature = "opus")]
+fn convert_opus_to_wav(ogg_data: &[u8]) -> Result<Vec<u8>> {
+    use crate::voice::samples_to_wav;
+
+    let mut reader = ogg::PacketReader::new(std::io::Cursor::new(ogg_data));
+    let mut decoder: Option<opus::Decoder> = None;
+    let mut pre_skip = 0;
+    let mut samples: Vec<f32> = Vec::new();
+    let mut frame = vec![0.0_f32; OPUS_MAX_FRAME];
+
+    while let Some(packet) = reader
+        .read_packet()
+        .map_err(|e| crate::Error::Attachment(format!("OGG read error: {e}")))?
+    {
+        let data = &packet.data;
+
+        if data.starts_with(b"OpusHead") {
+            if data.len() < 19 {
+                return Err(crate::Error::Attachment(
+                    "Truncated OpusHead header".to_string(),
+                ));
+            }
+            // Samples of encoder delay to discard from the start
+            pre_skip = usize::from(u16::from_le_bytes([data[10], data[11]]));
+            // libopus downmixes stereo streams when decoding to mono
+            decoder = Some(
+                opus::Decoder::new(OPUS_SAMPLE_RATE, opus::Channels::Mono)
+                    .map_err(|e| crate::Error::Attachment(format!("Opus init failed: {e}")))?,
+            );
+            continue;
+        }
+        if data.starts_with(b"OpusTags") {
+            continue;
+        }
+
+        let Some(decoder) = decoder.as_mut() else {
+            return Err(crate::Error::Attachment(
+                "OGG stream is not Opus-encoded".to_string(),
+            ));
+        };
+        let decoded = decoder
+            .decode_float(data, &mut frame, false)
+            .map_err(|e| crate::Error::Attachment(format!("Opus decode error: {e}")))?;
+        samples.extend_from_slice(&frame[..decoded]);
+    }
+
+    if decoder.is_none() {
+        return Err(crate::Error::Attachment(
+            "OGG stream is not Opus-encoded".to_string(),
+        ));
+    }
+
+    samples.drain(..pre_skip.min(samples.len()));
+    let resampled = resample_audio(&samples, OPUS_SAMPLE_RATE, 16000)?;
+
+    samples_to_wav(&resampled, 16000)
+}
+
+/// OGG/Opus decoding needs libopus, which is only linked with the `opus` feature
+#[cfg(not(feature = "opus"))]
+fn convert_opus_to_wav(_ogg_data: &[u8]) -> Result<Vec<u8>> {
+    Err(crate::Error::Attachment(
+        "OGG/Opus decoding requires the `opus` feature".to_string(),
+    ))
+}
+
 /// Resample audio using rubato
 #[allow(clippy::cast_possible_truncation)]
 fn resample_audio(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>> {
@@ -319,3 +676,150 @@ fn resample_audio(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f
     // Convert back to f32
     Ok(output.iter().map(|&s| s as f32).collect())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn process_attachments_preserves_order() {
+        let processor =
+            AttachmentProcessor::new(None, None, "whisper-1".to_string()).with_max_concurrency(1);
+        let attachments = [
+            Attachment::from_data(vec![1], "image/png".to_string(), Some("a.png".to_string())),
+            Attachment::from_data(vec![2], "application/pdf".to_string(), None),
+            Attachment::from_data(vec![3], "audio/mpeg".to_string(), Some("c.mp3".to_string())),
+        ];
+
+        let text = processor.process_attachments(&attachments).await.unwrap();
+
+        assert_eq!(
+            text,
+            "[Image: a.png]\n[File: file (application/pdf)]\n[Audio: c.mp3]"
+        );
+    }
+
+    struct StubVision;
+
+    #[async_trait::async_trait]
+    impl crate::media::MediaProvider for StubVision {
+        fn supports(&self, mime_type: &str) -> bool {
+            mime_type.starts_with("image/")
+        }
+
+        async fn process(&self, _data: &[u8], _mime_type: &str) -> Result<MediaAnalysis> {
+            Ok(MediaAnalysis {
+                description: Some("a red square".to_string()),
+                ..MediaAnalysis::default()
+            })
+        }
+
+        fn name(&self) -> &'static str {
+            "stub-vision"
+        }
+    }
+
+    #[tokio::test]
+    async fn images_fall_back_to_media_providers() {
+        let mut media = MediaProcessor::new(crate::media::MediaConfig::default());
+        media.add_provider(Box::new(StubVision));
+        let processor =
+            AttachmentProcessor::new(None, None, "whisper-1".to_string()).with_media(media);
+        let attachments = [Attachment::from_data(
+            vec![1],
+            "image/png".to_string(),
+            Some("a.png".to_string()),
+        )];
+
+        let text = processor.process_attachments(&attachments).await.unwrap();
+
+        assert_eq!(text, "[Image: a.png]\na red square");
+    }
+
+    async fn serve(body: Vec<u8>, content_type: &'static str) -> String {
+        use axum::Router;
+        use axum::http::header;
+        use axum::routing::get;
+
+        let app = Router::new().route(
+            "/file",
+            get(move || async move { ([(header::CONTENT_TYPE, content_type)], body) }),
+        );
+        format!("{}/file", crate::test_support::serve(app).await)
+    }
+
+    fn small_images() -> AttachmentLimits {
+        AttachmentLimits {
+            max_image_bytes: 16,
+            ..AttachmentLimits::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn download_enforces_size_limit() {
+        let processor = AttachmentProcessor::new(None, None, "whisper-1".to_string())
+            .with_limits(small_images());
+
+        let url = serve(vec![0; 8], "image/png").await;
+        let attachment = Attachment::from_url(url, "image/png".to_string(), None);
+        assert_eq!(
+            processor
+                .get_attachment_data(&attachment)
+                .await
+                .unwrap()
+                .len(),
+            8
+        );
+
+        let url = serve(vec![0; 64], "image/png").await;
+        let attachment = Attachment::from_url(url, "image/png".to_string(), None);
+        let err = processor
+            .get_attachment_data(&attachment)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("too large"), "{err}");
+
+        // Files have their own, larger limit
+        let url = serve(vec![0; 64], "application/pdf").await;
+        let attachment = Attachment::from_url(url, "application/pdf".to_string(), None);
+        assert!(processor.get_attachment_data(&attachment).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn download_rejects_mismatched_content_type() {
+        let processor = AttachmentProcessor::new(None, None, "whisper-1".to_string());
+
+        let url = serve(b"<html>expired</html>".to_vec(), "text/html").await;
+        let attachment = Attachment::from_url(url, "image/png".to_string(), None);
+        let err = processor
+            .get_attachment_data(&attachment)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("content type"), "{err}");
+    }
+
+    #[cfg(feature = "opus")]
+    #[test]
+    fn convert_to_wav_decodes_ogg_opus() {
+        let ogg = include_bytes!("../../tests/fixtures/silence.opus.ogg");
+        let wav = convert_to_wav(ogg, "audio/ogg; codecs=opus").unwrap();
+
+        let reader = hound::WavReader::new(std::io::Cursor::new(wav)).unwrap();
+        let spec = reader.spec();
+        assert_eq!(spec.sample_rate, 16000);
+        assert_eq!(spec.channels, 1);
+        // One second of silence at 48kHz, minus pre-skip and resampler tail
+        let len = reader.len();
+        assert!((14_000..=16_000).contains(&len), "unexpected length {len}");
+        assert!(
+            reader
+                .into_samples::<i16>()
+                .all(|s| s.unwrap().unsigned_abs() < 16)
+        );
+    }
+
+    #[test]
+    fn convert_to_wav_rejects_non_opus_ogg() {
+        assert!(convert_to_wav(b"not an ogg stream", "audio/ogg").is_err());
+    }
+}
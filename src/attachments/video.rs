@@ -0,0 +1,173 @@
+//! Video keyframe extraction via `ffmpeg`
+//!
+//! Frames are sampled at evenly spaced timestamps so they can be described
+//! individually by the vision client.
+
+use std::path::Path;
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+use crate::{Error, Result};
+
+/// Default number of keyframes extracted per video
+pub const DEFAULT_KEYFRAMES: usize = 3;
+
+/// A single extracted frame
+#[derive(Debug, Clone)]
+pub struct Keyframe {
+    /// Position in the video, in seconds
+    pub timestamp: f64,
+    /// JPEG-encoded frame
+    pub data: Vec<u8>,
+}
+
+/// Number of keyframes from `BEACON_VIDEO_KEYFRAMES` (default: 3, 0 disables)
+#[must_use]
+pub fn keyframes_from_env() -> usize {
+    std::env::var("BEACON_VIDEO_KEYFRAMES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_KEYFRAMES)
+}
+
+/// Locate `ffmpeg` on PATH
+#[must_use]
+pub fn ffmpeg_path() -> Option<std::path::PathBuf> {
+    which::which("ffmpeg").ok()
+}
+
+/// Extract `count` evenly spaced keyframes from a video
+///
+/// # Errors
+///
+/// Returns error if the video cannot be written to disk, its duration cannot
+/// be determined, or `ffmpeg` fails to decode a frame
+pub async fn extract_keyframes(ffmpeg: &Path, video: &[u8], count: usize) -> Result<Vec<Keyframe>> {
+    // ffmpeg needs a seekable input for containers like MP4
+    let input = tempfile::NamedTempFile::new()
+        .map_err(|e| Error::Attachment(format!("Failed to create temp file: {e}")))?;
+    tokio::fs::write(input.path(), video)
+        .await
+        .map_err(|e| Error::Attachment(format!("Failed to write video: {e}")))?;
+
+    let duration = probe_duration(ffmpeg, input.path()).await?;
+
+    let mut frames = Vec::with_capacity(count);
+    for timestamp in keyframe_timestamps(duration, count) {
+        let data = extract_frame(ffmpeg, input.path(), timestamp).await?;
+        frames.push(Keyframe { timestamp, data });
+    }
+
+    Ok(frames)
+}
+
+/// Read the video duration from ffmpeg's stream info
+async fn probe_duration(ffmpeg: &Path, input: &Path) -> Result<f64> {
+    // Without an output ffmpeg exits with an error after printing stream info
+    let output = Command::new(ffmpeg)
+        .arg("-hide_banner")
+        .arg("-i")
+        .arg(input)
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| Error::Attachment(format!("Failed to run ffmpeg: {e}")))?;
+
+    parse_duration(&String::from_utf8_lossy(&output.stderr))
+        .ok_or_else(|| Error::Attachment("Could not determine video duration".to_string()))
+}
+
+/// Decode the frame at `timestamp` as a JPEG
+async fn extract_frame(ffmpeg: &Path, input: &Path, timestamp: f64) -> Result<Vec<u8>> {
+    let output = Command::new(ffmpeg)
+        .args(["-hide_banner", "-loglevel", "error", "-ss"])
+        .arg(format!("{timestamp:.3}"))
+        .arg("-i")
+        .arg(input)
+        .args([
+            "-frames:v",
+            "1",
+            "-f",
+            "image2pipe",
+            "-vcodec",
+            "mjpeg",
+            "-",
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| Error::Attachment(format!("Failed to run ffmpeg: {e}")))?;
+
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(Error::Attachment(format!(
+            "ffmpeg frame extraction failed at {timestamp:.1}s: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Parse `Duration: HH:MM:SS.ss` from ffmpeg's stderr
+fn parse_duration(stderr: &str) -> Option<f64> {
+    let rest = stderr.split("Duration:").nth(1)?;
+    let value = rest.split(',').next()?.trim();
+
+    let mut parts = value.split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+
+    Some(hours.mul_add(3600.0, minutes * 60.0) + seconds).filter(|d| *d > 0.0)
+}
+
+/// Evenly spaced timestamps, each centered in its segment of the video
+#[allow(clippy::cast_precision_loss)]
+fn keyframe_timestamps(duration: f64, count: usize) -> Vec<f64> {
+    let segment = duration / count as f64;
+    (0..count)
+        .map(|i| segment.mul_add(i as f64, segment / 2.0))
+        .collect()
+}
+
+/// Format seconds as `M:SS` (or `H:MM:SS` for long videos)
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{minutes}:{secs:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_reads_ffmpeg_stream_info() {
+        let stderr = "Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'in.mp4':\n  \
+            Duration: 00:01:02.50, start: 0.000000, bitrate: 1205 kb/s\n";
+        assert_eq!(parse_duration(stderr), Some(62.5));
+        assert_eq!(parse_duration("  Duration: N/A, bitrate: N/A"), None);
+        assert_eq!(parse_duration("no info"), None);
+    }
+
+    #[test]
+    fn keyframe_timestamps_are_evenly_spaced() {
+        assert_eq!(keyframe_timestamps(30.0, 3), vec![5.0, 15.0, 25.0]);
+        assert_eq!(keyframe_timestamps(10.0, 1), vec![5.0]);
+        assert!(keyframe_timestamps(10.0, 0).is_empty());
+    }
+
+    #[test]
+    fn format_timestamp_uses_minutes_and_hours() {
+        assert_eq!(format_timestamp(5.4), "0:05");
+        assert_eq!(format_timestamp(125.0), "2:05");
+        assert_eq!(format_timestamp(3725.0), "1:02:05");
+    }
+}
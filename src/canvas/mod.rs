@@ -23,6 +23,28 @@ pub enum CanvasCommand {
     Clear,
     /// Update specific element by ID
     Update { id: String, content: CanvasContent },
+    /// Full canvas state, sent once when a client subscribes
+    Snapshot { elements: Vec<CanvasElement> },
+}
+
+impl CanvasCommand {
+    /// Commands that bring a new subscriber up to date with `elements`
+    ///
+    /// Produces a single `Snapshot`, or one `Push` per element for clients
+    /// that predate the `Snapshot` command.
+    #[must_use]
+    pub fn replay(elements: Vec<CanvasElement>, as_pushes: bool) -> Vec<Self> {
+        if as_pushes {
+            elements
+                .into_iter()
+                .map(|element| Self::Push {
+                    content: element.content,
+                })
+                .collect()
+        } else {
+            vec![Self::Snapshot { elements }]
+        }
+    }
 }
 
 /// Canvas content types
@@ -44,6 +66,21 @@ pub enum CanvasContent {
     },
     /// Chart/visualization (JSON spec, e.g. Vega-Lite)
     Chart { spec: serde_json::Value },
+    /// Mermaid diagram definition (flowcharts, sequence diagrams, etc.)
+    Mermaid { definition: String },
+}
+
+impl CanvasContent {
+    /// Image content as a channel attachment (for [`crate::channels::Channel::send_media`])
+    ///
+    /// Returns `None` for non-image content or unsupported image sources.
+    #[must_use]
+    pub fn to_attachment(&self) -> Option<crate::channels::Attachment> {
+        match self {
+            Self::Image { src, .. } => crate::channels::Attachment::image(src, None),
+            _ => None,
+        }
+    }
 }
 
 /// Canvas element with ID for updates
@@ -91,6 +128,17 @@ impl Canvas {
         self.tx.subscribe()
     }
 
+    /// Subscribe to canvas updates along with the current elements
+    ///
+    /// Both are taken together so no update is missed or seen twice between
+    /// the snapshot and the live stream.
+    #[must_use]
+    pub fn subscribe_with_snapshot(
+        &self,
+    ) -> (Vec<CanvasElement>, broadcast::Receiver<CanvasCommand>) {
+        (self.elements.clone(), self.tx.subscribe())
+    }
+
     /// Push content to canvas
     ///
     /// Returns the ID of the newly created element
@@ -107,6 +155,24 @@ impl Canvas {
         id
     }
 
+    /// Insert or replace content under a caller-supplied ID
+    ///
+    /// Broadcasts an `Update` if the element already existed, otherwise a
+    /// `Push`. Returns true if an existing element was replaced.
+    pub fn push_with_id(&mut self, id: String, content: CanvasContent) -> bool {
+        if self.update(&id, content.clone()) {
+            return true;
+        }
+
+        self.elements.push(CanvasElement {
+            id,
+            content: content.clone(),
+        });
+        let _ = self.tx.send(CanvasCommand::Push { content });
+
+        false
+    }
+
     /// Update an existing element by ID
     ///
     /// Returns true if the element was found and updated
@@ -189,6 +255,18 @@ impl CanvasTools {
         Ok(canvas.push(content))
     }
 
+    /// Insert or replace content under a stable ID (agent tool)
+    ///
+    /// Returns true if an existing element was replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the lock cannot be acquired
+    pub async fn push_with_id(&self, id: String, content: CanvasContent) -> Result<bool> {
+        let mut canvas = self.canvas.lock().await;
+        Ok(canvas.push_with_id(id, content))
+    }
+
     /// Update existing element (agent tool)
     ///
     /// # Errors
@@ -226,6 +304,49 @@ impl CanvasTools {
 mod tests {
     use super::*;
 
+    #[test]
+    fn image_content_converts_to_attachment() {
+        let inline = CanvasContent::Image {
+            src: "data:image/png;base64,iVBORw0K".to_string(),
+            alt: None,
+        };
+        let attachment = inline.to_attachment().unwrap();
+        assert_eq!(attachment.mime_type, "image/png");
+        assert!(attachment.data.is_some());
+
+        let remote = CanvasContent::Image {
+            src: "https://example.com/chart.webp?v=2".to_string(),
+            alt: Some("chart".to_string()),
+        };
+        let attachment = remote.to_attachment().unwrap();
+        assert_eq!(attachment.mime_type, "image/webp");
+        assert_eq!(
+            attachment.url.as_deref(),
+            Some("https://example.com/chart.webp?v=2")
+        );
+
+        let text = CanvasContent::Markdown {
+            text: "hi".to_string(),
+        };
+        assert!(text.to_attachment().is_none());
+    }
+
+    #[test]
+    fn mermaid_content_serde_roundtrip() {
+        let content = CanvasContent::Mermaid {
+            definition: "graph TD\n  A --> B".to_string(),
+        };
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(json["type"], "mermaid");
+        assert_eq!(json["definition"], "graph TD\n  A --> B");
+
+        let parsed: CanvasContent = serde_json::from_value(json).unwrap();
+        let CanvasContent::Mermaid { definition } = parsed else {
+            panic!("expected mermaid content");
+        };
+        assert_eq!(definition, "graph TD\n  A --> B");
+    }
+
     #[test]
     fn canvas_push_and_clear() {
         let mut canvas = Canvas::new();
@@ -265,6 +386,69 @@ mod tests {
         }
     }
 
+    #[test]
+    fn canvas_push_with_id_inserts_then_replaces() {
+        let mut canvas = Canvas::new();
+        let mut rx = canvas.subscribe();
+
+        let replaced = canvas.push_with_id(
+            "price-chart".to_string(),
+            CanvasContent::Markdown {
+                text: "v1".to_string(),
+            },
+        );
+        assert!(!replaced);
+        assert!(matches!(rx.try_recv().unwrap(), CanvasCommand::Push { .. }));
+
+        let replaced = canvas.push_with_id(
+            "price-chart".to_string(),
+            CanvasContent::Markdown {
+                text: "v2".to_string(),
+            },
+        );
+        assert!(replaced);
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            CanvasCommand::Update { ref id, .. } if id == "price-chart"
+        ));
+
+        assert_eq!(canvas.elements().len(), 1);
+        let Some(CanvasElement {
+            content: CanvasContent::Markdown { text },
+            ..
+        }) = canvas.get("price-chart")
+        else {
+            panic!("expected markdown content");
+        };
+        assert_eq!(text, "v2");
+    }
+
+    #[test]
+    fn canvas_replay_as_snapshot_or_pushes() {
+        let mut canvas = Canvas::new();
+        canvas.push(CanvasContent::Markdown {
+            text: "a".to_string(),
+        });
+        canvas.push(CanvasContent::Markdown {
+            text: "b".to_string(),
+        });
+        let (elements, _rx) = canvas.subscribe_with_snapshot();
+
+        let snapshot = CanvasCommand::replay(elements.clone(), false);
+        assert!(matches!(
+            snapshot.as_slice(),
+            [CanvasCommand::Snapshot { elements }] if elements.len() == 2
+        ));
+
+        let pushes = CanvasCommand::replay(elements, true);
+        assert_eq!(pushes.len(), 2);
+        assert!(
+            pushes
+                .iter()
+                .all(|cmd| matches!(cmd, CanvasCommand::Push { .. }))
+        );
+    }
+
     #[test]
     fn canvas_remove_element() {
         let mut canvas = Canvas::new();
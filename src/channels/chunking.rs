@@ -1,16 +1,18 @@
-//! Text chunking for Telegram's message size limit
+//! Text chunking for per-message size limits
 //!
-//! Telegram enforces a 4096-character cap per message. This module splits
-//! long text into smaller chunks while trying to preserve logical boundaries
-//! (paragraphs, sentences) and keeping fenced code blocks intact.
+//! Channels cap message length (Discord 2000, Slack 4000, `WhatsApp` and
+//! Telegram 4096 characters). This module splits long text into smaller
+//! chunks while trying to preserve logical boundaries (paragraphs, sentences)
+//! and keeping fenced code blocks intact. Code blocks too large for one chunk
+//! are split by line, with the fence closed and reopened in the same language.
+//!
+//! Limits are measured in bytes, which never undercounts characters.
 
 /// Default chunk size limit (leaves margin from Telegram's 4096 hard cap)
-#[allow(dead_code)]
-const DEFAULT_LIMIT: usize = 4000;
+pub const DEFAULT_LIMIT: usize = 4000;
 
 /// Strategy for splitting text that exceeds the chunk limit
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(dead_code)]
 pub enum ChunkStrategy {
     /// Split on paragraph boundaries (double newlines), fall back to sentence,
     /// then hard split
@@ -29,14 +31,13 @@ pub enum ChunkStrategy {
 ///
 /// # Examples
 ///
-/// ```ignore
-/// use beacon_gateway::channels::telegram::chunking::{chunk_text, ChunkStrategy};
+/// ```
+/// use beacon_gateway::channels::chunking::{ChunkStrategy, chunk_text};
 ///
 /// let chunks = chunk_text("short", 0, ChunkStrategy::Paragraph);
 /// assert_eq!(chunks, vec!["short"]);
 /// ```
 #[must_use]
-#[allow(dead_code)]
 pub fn chunk_text(text: &str, limit: usize, strategy: ChunkStrategy) -> Vec<String> {
     let limit = if limit == 0 { DEFAULT_LIMIT } else { limit };
 
@@ -62,7 +63,6 @@ pub fn chunk_text(text: &str, limit: usize, strategy: ChunkStrategy) -> Vec<Stri
 /// 3. Accumulate segments into chunks up to the limit.
 /// 4. If a single segment still exceeds the limit, fall back to sentence
 ///    splitting, then hard splitting.
-#[allow(dead_code)]
 fn chunk_paragraph(text: &str, limit: usize) -> Vec<String> {
     let segments = split_preserving_code_blocks(text, "\n\n");
     assemble_chunks(&segments, limit, ChunkStrategy::Sentence)
@@ -72,7 +72,6 @@ fn chunk_paragraph(text: &str, limit: usize) -> Vec<String> {
 ///
 /// Splits on `. `, `! `, `? ` boundaries. If a single segment still exceeds
 /// the limit, falls back to hard splitting.
-#[allow(dead_code)]
 fn chunk_sentence(text: &str, limit: usize) -> Vec<String> {
     let segments = split_on_sentences(text);
     assemble_chunks(&segments, limit, ChunkStrategy::HardSplit)
@@ -82,7 +81,6 @@ fn chunk_sentence(text: &str, limit: usize) -> Vec<String> {
 ///
 /// Splits at exact `limit`-sized boundaries. Tries to break on the last
 /// newline before the limit; if none exists, breaks at `limit` directly.
-#[allow(dead_code)]
 fn chunk_hard(text: &str, limit: usize) -> Vec<String> {
     let mut chunks = Vec::new();
     let mut remaining = text;
@@ -115,7 +113,6 @@ fn chunk_hard(text: &str, limit: usize) -> Vec<String> {
 /// Code blocks are never broken across segments; the delimiter is ignored
 /// inside fenced regions.
 #[must_use]
-#[allow(dead_code)]
 fn split_preserving_code_blocks<'a>(text: &'a str, delimiter: &str) -> Vec<&'a str> {
     let mut segments = Vec::new();
     let mut pos = 0;
@@ -210,7 +207,6 @@ fn split_preserving_code_blocks<'a>(text: &'a str, delimiter: &str) -> Vec<&'a s
 ///
 /// The punctuation stays attached to the preceding segment.
 #[must_use]
-#[allow(dead_code)]
 fn split_on_sentences(text: &str) -> Vec<&str> {
     let mut segments = Vec::new();
     let mut start = 0;
@@ -249,7 +245,6 @@ fn split_on_sentences(text: &str) -> Vec<&str> {
 ///
 /// When a single segment exceeds the limit, it is recursively split using the
 /// `fallback` strategy.
-#[allow(dead_code)]
 fn assemble_chunks(segments: &[&str], limit: usize, fallback: ChunkStrategy) -> Vec<String> {
     let mut chunks: Vec<String> = Vec::new();
     let mut current = String::new();
@@ -274,16 +269,14 @@ fn assemble_chunks(segments: &[&str], limit: usize, fallback: ChunkStrategy) ->
             current.push_str(trimmed);
         } else if current.is_empty() {
             // Single segment exceeds limit — split with fallback strategy
-            let sub = chunk_text(trimmed, limit, fallback);
-            chunks.extend(sub);
+            chunks.extend(split_oversized(trimmed, limit, fallback));
         } else {
             // Flush current chunk, start a new one with this segment
             chunks.push(std::mem::take(&mut current));
             if trimmed.len() <= limit {
                 current.push_str(trimmed);
             } else {
-                let sub = chunk_text(trimmed, limit, fallback);
-                chunks.extend(sub);
+                chunks.extend(split_oversized(trimmed, limit, fallback));
             }
         }
     }
@@ -295,11 +288,90 @@ fn assemble_chunks(segments: &[&str], limit: usize, fallback: ChunkStrategy) ->
     chunks
 }
 
+/// Split a segment larger than `limit`, keeping code fences balanced
+fn split_oversized(segment: &str, limit: usize, fallback: ChunkStrategy) -> Vec<String> {
+    if segment.starts_with("```") {
+        chunk_code_block(segment, limit)
+    } else {
+        chunk_text(segment, limit, fallback)
+    }
+}
+
+/// Split a fenced code block by lines
+///
+/// Each chunk is a complete block: the fence is closed at the end of a chunk
+/// and reopened with the same language at the start of the next. Lines longer
+/// than a chunk are hard split without trimming, preserving indentation.
+fn chunk_code_block(block: &str, limit: usize) -> Vec<String> {
+    let (opening, rest) = block.split_once('\n').unwrap_or((block, ""));
+    let opening = opening.trim_end();
+    let body = rest.trim_end();
+    let body = body
+        .strip_suffix("```")
+        .unwrap_or(body)
+        .trim_end_matches('\n');
+
+    // "```lang\n" before and "\n```" after each chunk's body
+    let overhead = opening.len() + 1 + 4;
+    if overhead >= limit {
+        return chunk_hard(block, limit);
+    }
+    let budget = limit - overhead;
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in body.lines() {
+        for piece in split_at_char_boundaries(line, budget) {
+            let needed = if current.is_empty() {
+                piece.len()
+            } else {
+                current.len() + 1 + piece.len()
+            };
+            if needed > budget && !current.is_empty() {
+                chunks.push(format!("{opening}\n{current}\n```"));
+                current.clear();
+            }
+            if !current.is_empty() {
+                current.push('\n');
+            }
+            current.push_str(piece);
+        }
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(format!("{opening}\n{current}\n```"));
+    }
+
+    chunks
+}
+
+/// Split `text` into pieces of at most `limit` bytes on character boundaries
+fn split_at_char_boundaries(text: &str, limit: usize) -> Vec<&str> {
+    if text.is_empty() {
+        return vec![text];
+    }
+
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut split = limit.min(rest.len());
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+        // A character wider than the limit still has to go somewhere
+        if split == 0 {
+            split = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        pieces.push(&rest[..split]);
+        rest = &rest[split..];
+    }
+
+    pieces
+}
+
 /// Find the best byte offset to split at within `limit`.
 ///
 /// Prefers the last newline before the limit; falls back to `limit` itself.
 #[must_use]
-#[allow(dead_code)]
 fn find_split_point(text: &str, limit: usize) -> usize {
     let search_range = &text[..limit];
 
@@ -452,6 +524,41 @@ mod tests {
         assert!(result.iter().all(|c| !c.is_empty()));
     }
 
+    #[test]
+    fn oversized_code_block_reopens_fence_with_language() {
+        let body: Vec<String> = (0..20).map(|i| format!("    let x{i} = {i};")).collect();
+        let text = format!("Intro.\n\n```rust\n{}\n```\n\nOutro.", body.join("\n"));
+        let result = chunk_text(&text, 120, ChunkStrategy::Paragraph);
+
+        assert!(result.iter().all(|c| c.len() <= 120));
+        let code_chunks: Vec<&String> = result.iter().filter(|c| c.contains("let x")).collect();
+        assert!(code_chunks.len() > 1, "block was not split: {result:?}");
+        for chunk in &code_chunks {
+            assert!(
+                chunk.starts_with("```rust\n"),
+                "fence not reopened: {chunk:?}"
+            );
+            assert!(chunk.ends_with("\n```"), "fence not closed: {chunk:?}");
+            // Indentation survives the split
+            assert!(chunk.contains("\n    let x"));
+        }
+
+        // Every line appears exactly once, in order
+        let lines: Vec<&str> = code_chunks
+            .iter()
+            .flat_map(|c| c.lines().filter(|l| l.contains("let x")))
+            .collect();
+        assert_eq!(lines, body.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn discord_sized_chunks_fit_limit() {
+        let text = "word ".repeat(1000);
+        let result = chunk_text(&text, 2000, ChunkStrategy::Paragraph);
+        assert!(result.len() >= 3);
+        assert!(result.iter().all(|c| c.len() <= 2000));
+    }
+
     // ---- Non-empty chunks guarantee ----
 
     #[test]
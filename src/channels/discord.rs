@@ -5,14 +5,24 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use serenity::Client;
 use serenity::all::{
-    ChannelId, Context, CreateEmbed, CreateMessage, EventHandler, GatewayIntents, Message,
-    MessageId, ReactionType, Ready,
+    ChannelId, Context, CreateAttachment, CreateEmbed, CreateMessage, EditMessage, EventHandler,
+    GatewayIntents, GetMessages, Message, MessageId, ReactionType, Ready,
 };
 use tokio::sync::{Mutex, mpsc};
 
-use super::{Attachment, Channel, ChannelCapability, IncomingMessage, OutgoingMessage};
+use super::chunking::{ChunkStrategy, chunk_text};
+use super::{
+    Attachment, Channel, ChannelCapability, IncomingMessage, OutgoingMessage, StreamingConfig,
+    StreamingEditCoalescer,
+};
 use crate::{Error, Result};
 
+/// Discord's per-message content limit
+const DISCORD_MESSAGE_LIMIT: usize = 2000;
+
+/// Maximum messages returned per history request
+const DISCORD_HISTORY_PAGE: usize = 100;
+
 /// Discord channel adapter
 pub struct DiscordChannel {
     token: String,
@@ -20,7 +30,9 @@ pub struct DiscordChannel {
     client: Option<Client>,
     message_tx: Option<mpsc::Sender<IncomingMessage>>,
     http: Option<Arc<serenity::http::Http>>,
+    bot_user_id: Option<String>,
     connected: bool,
+    edits: StreamingEditCoalescer,
 }
 
 impl DiscordChannel {
@@ -37,7 +49,9 @@ impl DiscordChannel {
             client: None,
             message_tx: None,
             http: None,
+            bot_user_id: None,
             connected: false,
+            edits: default_edits(),
         }
     }
 
@@ -52,10 +66,97 @@ impl DiscordChannel {
             client: None,
             message_tx: Some(tx),
             http: None,
+            bot_user_id: None,
             connected: false,
+            edits: default_edits(),
         };
         (channel, rx)
     }
+
+    /// Create a send-only adapter that talks to the REST API without a gateway session
+    ///
+    /// Useful for proactive delivery from outside the receive loop.
+    #[must_use]
+    pub fn send_only(token: String) -> Self {
+        let http = Arc::new(serenity::http::Http::new(&token));
+        Self {
+            token,
+            client: None,
+            message_tx: None,
+            http: Some(http),
+            connected: true,
+            edits: default_edits(),
+        }
+    }
+
+    /// Apply the streaming edit interval
+    #[must_use]
+    pub fn with_streaming(mut self, config: StreamingConfig) -> Self {
+        self.edits = StreamingEditCoalescer::from_config(&config);
+        self
+    }
+
+    /// Send one message that fits within the size limit
+    async fn send_chunk(&self, message: &OutgoingMessage) -> Result<()> {
+        let http = self
+            .http
+            .as_ref()
+            .ok_or_else(|| Error::Channel("Discord not connected".to_string()))?;
+
+        let channel_id: u64 = message
+            .channel_id
+            .parse()
+            .map_err(|_| Error::Channel("Invalid channel ID".to_string()))?;
+
+        let channel = ChannelId::new(channel_id);
+
+        // Build message with optional embed for code blocks
+        let builder = if message.has_code_blocks() {
+            // Extract code blocks and create embeds
+            let code_blocks = message.extract_code_blocks();
+            let mut builder = CreateMessage::new();
+
+            // Add non-code content as regular message
+            let plain_content = remove_code_blocks(&message.content);
+            if !plain_content.trim().is_empty() {
+                builder = builder.content(plain_content.trim());
+            }
+
+            // Add code blocks as embeds (Discord has better rendering)
+            for (lang, code) in code_blocks {
+                let title = if lang.is_empty() {
+                    "Code".to_string()
+                } else {
+                    lang.clone()
+                };
+
+                // Discord embeds have a 4096 char limit for description
+                let truncated = if code.len() > 4000 {
+                    format!("{}...\n(truncated)", &code[..4000])
+                } else {
+                    code
+                };
+
+                let embed = CreateEmbed::new()
+                    .title(title)
+                    .description(format!("```{lang}\n{truncated}\n```"))
+                    .color(0x002B_2D31); // Discord dark theme color
+
+                builder = builder.add_embed(embed);
+            }
+
+            builder
+        } else {
+            CreateMessage::new().content(&message.content)
+        };
+
+        channel
+            .send_message(http, builder)
+            .await
+            .map_err(|e| Error::Channel(format!("Discord send error: {e}")))?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -65,7 +166,11 @@ impl Channel for DiscordChannel {
     }
 
     fn capabilities(&self) -> &'static [ChannelCapability] {
-        &[ChannelCapability::Reactions]
+        &[
+            ChannelCapability::Reactions,
+            ChannelCapability::MediaSend,
+            ChannelCapability::Streaming,
+        ]
     }
 
     async fn connect(&mut self) -> Result<()> {
@@ -85,6 +190,13 @@ impl Channel for DiscordChannel {
             .map_err(|e| Error::Channel(format!("Discord client error: {e}")))?;
 
         self.http = Some(client.http.clone());
+        self.bot_user_id = client
+            .http
+            .get_current_user()
+            .await
+            .map(|user| user.id.to_string())
+            .map_err(|e| tracing::warn!(error = %e, "failed to fetch Discord bot user"))
+            .ok();
 
         // Spawn the client in a background task
         let mut client_runner = client;
@@ -108,6 +220,29 @@ impl Channel for DiscordChannel {
     }
 
     async fn send(&self, message: OutgoingMessage) -> Result<()> {
+        let chunks = chunk_text(
+            &message.content,
+            DISCORD_MESSAGE_LIMIT,
+            ChunkStrategy::Paragraph,
+        );
+        let count = chunks.len();
+        for content in chunks {
+            self.send_chunk(&OutgoingMessage {
+                content,
+                ..message.clone()
+            })
+            .await?;
+        }
+
+        tracing::debug!(channel = %message.channel_id, chunks = count, "Discord message sent");
+        Ok(())
+    }
+
+    async fn send_media(&self, message: OutgoingMessage) -> Result<()> {
+        if message.attachments.is_empty() {
+            return self.send(message).await;
+        }
+
         let http = self
             .http
             .as_ref()
@@ -118,54 +253,54 @@ impl Channel for DiscordChannel {
             .parse()
             .map_err(|_| Error::Channel("Invalid channel ID".to_string()))?;
 
-        let channel = ChannelId::new(channel_id);
-
-        // Build message with optional embed for code blocks
-        let builder = if message.has_code_blocks() {
-            // Extract code blocks and create embeds
-            let code_blocks = message.extract_code_blocks();
-            let mut builder = CreateMessage::new();
-
-            // Add non-code content as regular message
-            let plain_content = remove_code_blocks(&message.content);
-            if !plain_content.trim().is_empty() {
-                builder = builder.content(plain_content.trim());
-            }
-
-            // Add code blocks as embeds (Discord has better rendering)
-            for (lang, code) in code_blocks {
-                let title = if lang.is_empty() {
-                    "Code".to_string()
-                } else {
-                    lang.clone()
-                };
-
-                // Discord embeds have a 4096 char limit for description
-                let truncated = if code.len() > 4000 {
-                    format!("{}...\n(truncated)", &code[..4000])
-                } else {
-                    code
-                };
-
-                let embed = CreateEmbed::new()
-                    .title(title)
-                    .description(format!("```{lang}\n{truncated}\n```"))
-                    .color(0x002B_2D31); // Discord dark theme color
-
-                builder = builder.add_embed(embed);
-            }
+        // Long captions go out as text first; the files ride with the last chunk
+        let mut chunks = chunk_text(
+            &message.content,
+            DISCORD_MESSAGE_LIMIT,
+            ChunkStrategy::Paragraph,
+        );
+        let caption = chunks.pop();
+        for content in chunks {
+            self.send_chunk(&OutgoingMessage {
+                content,
+                ..message.clone()
+            })
+            .await?;
+        }
 
-            builder
-        } else {
-            CreateMessage::new().content(&message.content)
-        };
+        let mut builder = CreateMessage::new();
+        if let Some(caption) = &caption {
+            builder = builder.content(caption);
+        }
+        for attachment in &message.attachments {
+            let file = match (&attachment.data, &attachment.url) {
+                (Some(data), _) => CreateAttachment::bytes(
+                    data.clone(),
+                    attachment
+                        .filename
+                        .clone()
+                        .unwrap_or_else(|| "attachment".to_string()),
+                ),
+                (None, Some(url)) => CreateAttachment::url(http, url)
+                    .await
+                    .map_err(|e| Error::Channel(format!("Discord attachment error: {e}")))?,
+                (None, None) => {
+                    return Err(Error::Channel("attachment has no data or URL".to_string()));
+                }
+            };
+            builder = builder.add_file(file);
+        }
 
-        channel
+        ChannelId::new(channel_id)
             .send_message(http, builder)
             .await
             .map_err(|e| Error::Channel(format!("Discord send error: {e}")))?;
 
-        tracing::debug!(channel_id = %message.channel_id, "Discord message sent");
+        tracing::debug!(
+            channel_id = %message.channel_id,
+            attachments = message.attachments.len(),
+            "Discord media sent"
+        );
         Ok(())
     }
 
@@ -173,6 +308,10 @@ impl Channel for DiscordChannel {
         self.connected
     }
 
+    fn bot_user_id(&self) -> Option<&str> {
+        self.bot_user_id.as_deref()
+    }
+
     async fn send_typing(&self, channel_id: &str) -> Result<()> {
         let http = self
             .http
@@ -241,6 +380,193 @@ impl Channel for DiscordChannel {
         tracing::debug!(channel_id, message_id, emoji, "Discord reaction removed");
         Ok(())
     }
+
+    async fn send_streaming_start(
+        &self,
+        channel_id: &str,
+        initial_text: &str,
+        reply_to: Option<&str>,
+        _thread_id: Option<&str>,
+    ) -> Result<String> {
+        let http = self
+            .http
+            .as_ref()
+            .ok_or_else(|| Error::Channel("Discord not connected".to_string()))?;
+
+        let id: u64 = channel_id
+            .parse()
+            .map_err(|_| Error::Channel("Invalid channel ID".to_string()))?;
+        let channel = ChannelId::new(id);
+
+        // Threads are channels on Discord, so only the reply needs setting
+        let mut builder = CreateMessage::new().content(first_chunk(initial_text));
+        if let Some(reply) = reply_to.and_then(|id| id.parse().ok()) {
+            builder = builder.reference_message((channel, MessageId::new(reply)));
+        }
+
+        let sent = channel
+            .send_message(http, builder)
+            .await
+            .map_err(|e| Error::Channel(format!("Discord send error: {e}")))?;
+
+        let message_id = sent.id.to_string();
+        self.edits.start(&message_id, initial_text);
+        Ok(message_id)
+    }
+
+    async fn send_streaming_update(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<()> {
+        match self.edits.update(message_id, text) {
+            Some(text) => {
+                self.edit_message(channel_id, message_id, &first_chunk(&text))
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn send_streaming_end(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        final_text: &str,
+    ) -> Result<()> {
+        // Always edit on final (bypass the coalescer) to ensure final text lands
+        self.edits.finish(message_id);
+
+        let mut chunks =
+            chunk_text(final_text, DISCORD_MESSAGE_LIMIT, ChunkStrategy::Paragraph).into_iter();
+        let Some(first) = chunks.next() else {
+            return Ok(());
+        };
+        self.edit_message(channel_id, message_id, &first).await?;
+
+        // Overflow goes out as follow-up messages
+        for content in chunks {
+            self.send_chunk(&OutgoingMessage::text(channel_id.to_string(), content))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn edit_message(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        new_content: &str,
+    ) -> Result<()> {
+        let http = self
+            .http
+            .as_ref()
+            .ok_or_else(|| Error::Channel("Discord not connected".to_string()))?;
+
+        let chan_id: u64 = channel_id
+            .parse()
+            .map_err(|_| Error::Channel("Invalid channel ID".to_string()))?;
+
+        let msg_id: u64 = message_id
+            .parse()
+            .map_err(|_| Error::Channel("Invalid message ID".to_string()))?;
+
+        ChannelId::new(chan_id)
+            .edit_message(
+                http,
+                MessageId::new(msg_id),
+                EditMessage::new().content(new_content),
+            )
+            .await
+            .map_err(|e| Error::Channel(format!("Discord edit error: {e}")))?;
+
+        Ok(())
+    }
+
+    async fn fetch_history(&self, channel_id: &str, limit: usize) -> Result<Vec<IncomingMessage>> {
+        let http = self
+            .http
+            .as_ref()
+            .ok_or_else(|| Error::Channel("Discord not connected".to_string()))?;
+
+        let id: u64 = channel_id
+            .parse()
+            .map_err(|_| Error::Channel("Invalid channel ID".to_string()))?;
+        let channel = ChannelId::new(id);
+
+        // Pages come back newest first; walk backwards from the latest message
+        let mut messages: Vec<Message> = Vec::new();
+        let mut before: Option<MessageId> = None;
+        while messages.len() < limit {
+            #[allow(clippy::cast_possible_truncation)]
+            let page_size = (limit - messages.len()).min(DISCORD_HISTORY_PAGE) as u8;
+            let mut request = GetMessages::new().limit(page_size);
+            if let Some(before) = before {
+                request = request.before(before);
+            }
+
+            let page = channel
+                .messages(http, request)
+                .await
+                .map_err(|e| Error::Channel(format!("Discord history error: {e}")))?;
+            let exhausted = page.len() < usize::from(page_size);
+            before = page.last().map(|m| m.id);
+            messages.extend(page);
+            if exhausted || before.is_none() {
+                break;
+            }
+        }
+
+        tracing::debug!(
+            channel_id,
+            count = messages.len(),
+            "Discord history fetched"
+        );
+        Ok(messages.iter().rev().map(incoming_from_message).collect())
+    }
+}
+
+/// Edit coalescer with Discord's default streaming interval
+fn default_edits() -> StreamingEditCoalescer {
+    StreamingEditCoalescer::from_config(&StreamingConfig::for_channel("discord"))
+}
+
+/// Leading part of a streaming message that fits in one Discord message
+fn first_chunk(text: &str) -> String {
+    chunk_text(text, DISCORD_MESSAGE_LIMIT, ChunkStrategy::Paragraph)
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+}
+
+/// Convert a Discord message into an `IncomingMessage`
+fn incoming_from_message(msg: &Message) -> IncomingMessage {
+    let attachments = msg
+        .attachments
+        .iter()
+        .map(|att| {
+            let mime_type = att
+                .content_type
+                .clone()
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+            Attachment::from_url(att.url.clone(), mime_type, Some(att.filename.clone()))
+        })
+        .collect();
+
+    IncomingMessage {
+        id: msg.id.to_string(),
+        channel_id: msg.channel_id.to_string(),
+        sender_id: msg.author.id.to_string(),
+        sender_name: msg.author.name.clone(),
+        content: msg.content.clone(),
+        is_dm: msg.guild_id.is_none(),
+        reply_to: msg.referenced_message.as_ref().map(|r| r.id.to_string()),
+        attachments,
+        thread_id: None,
+        callback_data: None,
+        request_id: crate::request_id::current_or_new(),
+    }
 }
 
 /// Parse an emoji string into a Discord `ReactionType`
@@ -293,34 +619,7 @@ impl EventHandler for DiscordHandler {
             return;
         }
 
-        // Extract reply-to if this is a reply
-        let reply_to = msg.referenced_message.as_ref().map(|r| r.id.to_string());
-
-        // Extract attachments from Discord message
-        let attachments = msg
-            .attachments
-            .iter()
-            .map(|att| {
-                let mime_type = att
-                    .content_type
-                    .clone()
-                    .unwrap_or_else(|| "application/octet-stream".to_string());
-                Attachment::from_url(att.url.clone(), mime_type, Some(att.filename.clone()))
-            })
-            .collect();
-
-        let incoming = IncomingMessage {
-            id: msg.id.to_string(),
-            channel_id: msg.channel_id.to_string(),
-            sender_id: msg.author.id.to_string(),
-            sender_name: msg.author.name.clone(),
-            content: msg.content.clone(),
-            is_dm,
-            reply_to,
-            attachments,
-            thread_id: None,
-            callback_data: None,
-        };
+        let incoming = incoming_from_message(&msg);
 
         if let Some(tx) = self.message_tx.lock().await.as_ref()
             && let Err(e) = tx.send(incoming).await
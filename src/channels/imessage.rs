@@ -372,6 +372,7 @@ impl IMessageChannel {
                                 attachments,
                                 thread_id: None,
                                 callback_data: None,
+                                request_id: crate::request_id::current_or_new(),
                             };
                             let _ = tx.blocking_send(incoming);
                         }
@@ -0,0 +1,593 @@
+//! IRC channel adapter over a raw client connection
+//!
+//! Speaks the client protocol directly (RFC 1459/2812) over TCP, optionally
+//! wrapped in TLS. The connection runs in a background task that answers
+//! pings, joins the configured channels and reconnects with backoff when the
+//! server drops the link.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use super::{Channel, IncomingMessage, OutgoingMessage};
+use crate::{Error, Result};
+
+/// Maximum IRC line length in bytes, including the trailing CRLF
+const MAX_LINE_BYTES: usize = 512;
+
+/// Bytes reserved for the `:nick!user@host ` prefix the server adds when
+/// relaying our messages (user and host are not known client-side)
+const USERHOST_RESERVE: usize = 64;
+
+/// Minimum delay between outgoing lines, to stay under server flood limits
+const SEND_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Initial reconnect delay, doubled after each failed attempt
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Upper bound for the reconnect delay
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(300);
+
+/// IRC connection settings
+#[derive(Debug, Clone)]
+pub struct IrcConfig {
+    /// Server hostname
+    pub server: String,
+    /// Server port
+    pub port: u16,
+    /// Connect with TLS
+    pub tls: bool,
+    /// Nickname
+    pub nick: String,
+    /// Server password (sent as `PASS`)
+    pub password: Option<String>,
+    /// Channels to join (e.g. `#beacon`)
+    pub channels: Vec<String>,
+}
+
+/// Byte stream to the server (plain TCP or TLS)
+trait IrcStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> IrcStream for T {}
+
+/// IRC channel adapter
+pub struct IrcChannel {
+    config: IrcConfig,
+    message_tx: Option<mpsc::Sender<IncomingMessage>>,
+    /// Lines queued for the connection task (set on connect)
+    outgoing: Option<mpsc::UnboundedSender<String>>,
+    /// Whether the connection task is currently registered with the server
+    connected: Arc<AtomicBool>,
+    task: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl IrcChannel {
+    /// Create a new IRC channel adapter
+    #[must_use]
+    pub fn new(config: IrcConfig) -> Self {
+        Self {
+            config,
+            message_tx: None,
+            outgoing: None,
+            connected: Arc::new(AtomicBool::new(false)),
+            task: None,
+        }
+    }
+
+    /// Create with a message receiver
+    ///
+    /// Returns the channel and a receiver for incoming messages
+    #[must_use]
+    pub fn with_receiver(config: IrcConfig) -> (Self, mpsc::Receiver<IncomingMessage>) {
+        let (tx, rx) = mpsc::channel(100);
+        let mut channel = Self::new(config);
+        channel.message_tx = Some(tx);
+        (channel, rx)
+    }
+
+    /// Queue a raw protocol line
+    fn queue(&self, line: String) -> Result<()> {
+        self.outgoing
+            .as_ref()
+            .ok_or_else(|| Error::Channel("IRC not connected".to_string()))?
+            .send(line)
+            .map_err(|_| Error::Channel("IRC connection task stopped".to_string()))
+    }
+}
+
+/// Open a TCP (and optionally TLS) connection to the server
+async fn open_stream(config: &IrcConfig) -> Result<Box<dyn IrcStream>> {
+    let tcp = TcpStream::connect((config.server.as_str(), config.port))
+        .await
+        .map_err(|e| Error::Channel(format!("IRC connect to {} failed: {e}", config.server)))?;
+
+    if !config.tls {
+        return Ok(Box::new(tcp));
+    }
+
+    let mut roots = tokio_rustls::rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let tls_config = tokio_rustls::rustls::ClientConfig::builder_with_provider(Arc::new(
+        tokio_rustls::rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| Error::Channel(format!("IRC TLS setup failed: {e}")))?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+
+    let server_name = tokio_rustls::rustls::pki_types::ServerName::try_from(config.server.clone())
+        .map_err(|e| Error::Channel(format!("Invalid IRC server name: {e}")))?;
+    let stream = tokio_rustls::TlsConnector::from(Arc::new(tls_config))
+        .connect(server_name, tcp)
+        .await
+        .map_err(|e| Error::Channel(format!("IRC TLS handshake failed: {e}")))?;
+
+    Ok(Box::new(stream))
+}
+
+/// Why a session ended
+enum SessionEnd {
+    /// The connection dropped or errored; reconnect
+    Disconnected,
+    /// The adapter was dropped; stop for good
+    Shutdown,
+}
+
+/// Drive one connection until it drops
+///
+/// Registration lines go out immediately; queued outgoing lines are throttled
+/// by [`SEND_INTERVAL`].
+async fn run_session(
+    stream: Box<dyn IrcStream>,
+    config: &IrcConfig,
+    outgoing: &mut mpsc::UnboundedReceiver<String>,
+    message_tx: Option<&mpsc::Sender<IncomingMessage>>,
+    connected: &AtomicBool,
+) -> SessionEnd {
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+
+    let mut nick = config.nick.clone();
+    let mut registration = Vec::new();
+    if let Some(password) = &config.password {
+        registration.push(format!("PASS {password}"));
+    }
+    // Ask for IRCv3 account tags so senders can be identified by account;
+    // servers without capability negotiation ignore this
+    registration.push("CAP REQ :account-tag".to_string());
+    registration.push(format!("NICK {nick}"));
+    registration.push(format!("USER {nick} 0 * :Beacon"));
+    for line in registration {
+        if write_line(&mut writer, &line).await.is_err() {
+            return SessionEnd::Disconnected;
+        }
+    }
+
+    let mut buf = Vec::new();
+    let mut next_send = Instant::now();
+    loop {
+        let throttled = Instant::now() < next_send;
+        tokio::select! {
+            read = reader.read_until(b'\n', &mut buf) => {
+                match read {
+                    Ok(0) | Err(_) => return SessionEnd::Disconnected,
+                    Ok(_) => {}
+                }
+                // Servers and clients may use legacy encodings
+                let line = String::from_utf8_lossy(&buf).trim_end().to_string();
+                buf.clear();
+
+                let Some(message) = IrcMessage::parse(&line) else {
+                    continue;
+                };
+                let reply = match message.command {
+                    "PING" => Some(format!("PONG :{}", message.params.first().unwrap_or(&""))),
+                    // Capability ACK/NAK: finish negotiation either way
+                    "CAP" if matches!(message.params.get(1), Some(&("ACK" | "NAK"))) => {
+                        Some("CAP END".to_string())
+                    }
+                    // RPL_WELCOME: registration complete
+                    "001" => {
+                        connected.store(true, Ordering::Relaxed);
+                        tracing::info!(server = %config.server, nick = %nick, "IRC registered");
+                        (!config.channels.is_empty())
+                            .then(|| format!("JOIN {}", config.channels.join(",")))
+                    }
+                    // ERR_NICKNAMEINUSE: retry with a suffix
+                    "433" => {
+                        nick.push('_');
+                        Some(format!("NICK {nick}"))
+                    }
+                    "PRIVMSG" => {
+                        if let (Some(tx), Some(incoming)) =
+                            (message_tx, message.to_incoming(&nick))
+                            && let Err(e) = tx.send(incoming).await
+                        {
+                            tracing::warn!(error = %e, "Failed to forward IRC message");
+                        }
+                        None
+                    }
+                    "ERROR" => {
+                        tracing::warn!(reason = ?message.params.last(), "IRC server closed link");
+                        return SessionEnd::Disconnected;
+                    }
+                    _ => None,
+                };
+                if let Some(reply) = reply
+                    && write_line(&mut writer, &reply).await.is_err()
+                {
+                    return SessionEnd::Disconnected;
+                }
+            }
+            line = outgoing.recv(), if !throttled => {
+                let Some(line) = line else {
+                    let _ = write_line(&mut writer, "QUIT :Beacon shutting down").await;
+                    return SessionEnd::Shutdown;
+                };
+                if write_line(&mut writer, &line).await.is_err() {
+                    return SessionEnd::Disconnected;
+                }
+                next_send = Instant::now() + SEND_INTERVAL;
+            }
+            () = tokio::time::sleep_until(next_send), if throttled => {}
+        }
+    }
+}
+
+/// Write one protocol line with CRLF
+///
+/// CR, LF and NUL are stripped so no value can smuggle in a second command.
+async fn write_line<W: AsyncWrite + Unpin>(writer: &mut W, line: &str) -> std::io::Result<()> {
+    writer.write_all(sanitize_line(line).as_bytes()).await?;
+    writer.write_all(b"\r\n").await?;
+    writer.flush().await
+}
+
+/// Keep a connection alive, reconnecting with exponential backoff
+async fn run_connection(
+    first: Box<dyn IrcStream>,
+    config: IrcConfig,
+    mut outgoing: mpsc::UnboundedReceiver<String>,
+    message_tx: Option<mpsc::Sender<IncomingMessage>>,
+    connected: Arc<AtomicBool>,
+) {
+    let mut stream = Some(first);
+    let mut delay = RECONNECT_DELAY;
+
+    loop {
+        if let Some(s) = stream.take() {
+            let end = run_session(s, &config, &mut outgoing, message_tx.as_ref(), &connected).await;
+            connected.store(false, Ordering::Relaxed);
+            if matches!(end, SessionEnd::Shutdown) {
+                return;
+            }
+            tracing::warn!(server = %config.server, "IRC connection lost, reconnecting");
+            delay = RECONNECT_DELAY;
+        }
+
+        tokio::time::sleep(delay).await;
+        match open_stream(&config).await {
+            Ok(s) => stream = Some(s),
+            Err(e) => {
+                tracing::warn!(error = %e, retry_secs = delay.as_secs(), "IRC reconnect failed");
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Channel for IrcChannel {
+    fn name(&self) -> &'static str {
+        "irc"
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        if self.config.nick.is_empty() {
+            return Err(Error::Channel("IRC nick required".to_string()));
+        }
+
+        let stream = open_stream(&self.config).await?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.outgoing = Some(tx);
+        self.task = Some(tokio::spawn(run_connection(
+            stream,
+            self.config.clone(),
+            rx,
+            self.message_tx.clone(),
+            Arc::clone(&self.connected),
+        )));
+
+        tracing::info!(
+            server = %self.config.server,
+            port = self.config.port,
+            tls = self.config.tls,
+            "IRC channel connected"
+        );
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        // Dropping the sender makes the session send QUIT and stop
+        self.outgoing = None;
+        if let Some(task) = self.task.take() {
+            let _ = tokio::time::timeout(Duration::from_secs(5), task).await;
+        }
+        self.connected.store(false, Ordering::Relaxed);
+        tracing::info!("IRC channel disconnected");
+        Ok(())
+    }
+
+    async fn send(&self, message: OutgoingMessage) -> Result<()> {
+        let target = &message.channel_id;
+        let max_bytes = max_text_bytes(target, &self.config.nick);
+
+        for line in message
+            .content
+            .split(['\r', '\n'])
+            .filter(|l| !l.trim().is_empty())
+        {
+            for chunk in split_message(line, max_bytes) {
+                self.queue(format!("PRIVMSG {target} :{chunk}"))?;
+            }
+        }
+
+        tracing::debug!(target = %target, "IRC message queued");
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+}
+
+/// Remove characters that end or truncate an IRC line
+fn sanitize_line(line: &str) -> String {
+    line.replace(['\r', '\n', '\0'], "")
+}
+
+/// Room for message text in a `PRIVMSG` to `target` once the server adds
+/// our prefix and the line its CRLF
+fn max_text_bytes(target: &str, nick: &str) -> usize {
+    // ":nick!user@host PRIVMSG target :text\r\n"
+    let overhead = 1 + nick.len() + 1 + USERHOST_RESERVE + " PRIVMSG ".len() + target.len() + 2 + 2;
+    MAX_LINE_BYTES.saturating_sub(overhead).max(1)
+}
+
+/// Split a line into chunks of at most `max_bytes`, on character boundaries
+/// and preferring spaces
+fn split_message(text: &str, max_bytes: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text.trim();
+
+    while !rest.is_empty() {
+        if rest.len() <= max_bytes {
+            chunks.push(rest.to_string());
+            break;
+        }
+
+        let mut limit = max_bytes;
+        while !rest.is_char_boundary(limit) {
+            limit -= 1;
+        }
+        // A single character wider than the budget still has to go out
+        if limit == 0 {
+            limit = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+
+        let split = rest[..limit].rfind(' ').filter(|&i| i > 0).unwrap_or(limit);
+        chunks.push(rest[..split].trim_end().to_string());
+        rest = rest[split..].trim_start();
+    }
+
+    chunks
+}
+
+/// A parsed protocol line
+#[derive(Debug, PartialEq, Eq)]
+struct IrcMessage<'a> {
+    /// Raw IRCv3 message tags, without the leading `@`
+    tags: Option<&'a str>,
+    /// Source (`nick!user@host` or server name)
+    prefix: Option<&'a str>,
+    command: &'a str,
+    params: Vec<&'a str>,
+}
+
+impl<'a> IrcMessage<'a> {
+    /// Parse a line, keeping any IRCv3 message tags
+    fn parse(line: &'a str) -> Option<Self> {
+        let mut rest = line.trim_end_matches(['\r', '\n']);
+        let tags = if let Some(stripped) = rest.strip_prefix('@') {
+            let (tags, remainder) = stripped.split_once(' ')?;
+            rest = remainder.trim_start();
+            Some(tags)
+        } else {
+            None
+        };
+
+        let prefix = if let Some(stripped) = rest.strip_prefix(':') {
+            let (prefix, remainder) = stripped.split_once(' ')?;
+            rest = remainder.trim_start();
+            Some(prefix)
+        } else {
+            None
+        };
+
+        let (command, mut rest) = rest.split_once(' ').unwrap_or((rest, ""));
+        if command.is_empty() {
+            return None;
+        }
+
+        let mut params = Vec::new();
+        loop {
+            rest = rest.trim_start_matches(' ');
+            if rest.is_empty() {
+                break;
+            }
+            if let Some(trailing) = rest.strip_prefix(':') {
+                params.push(trailing);
+                break;
+            }
+            let (param, remainder) = rest.split_once(' ').unwrap_or((rest, ""));
+            params.push(param);
+            rest = remainder;
+        }
+
+        Some(Self {
+            tags,
+            prefix,
+            command,
+            params,
+        })
+    }
+
+    /// Value of a message tag, if present and non-empty
+    fn tag(&self, key: &str) -> Option<&'a str> {
+        self.tags?.split(';').find_map(|tag| {
+            let (k, v) = tag.split_once('=').unwrap_or((tag, ""));
+            (k == key && !v.is_empty()).then_some(v)
+        })
+    }
+
+    /// Map a `PRIVMSG` to an incoming message
+    ///
+    /// Messages addressed to `nick` are DMs and use the sender as channel ID.
+    /// The sender ID is the IRCv3 `account` tag when the server provides one,
+    /// otherwise the full `nick!user@host`, since a bare nick can be taken by
+    /// anyone. CTCP requests (e.g. `VERSION`, `ACTION`) are ignored.
+    fn to_incoming(&self, nick: &str) -> Option<IncomingMessage> {
+        let prefix = self.prefix?;
+        let sender = prefix.split('!').next()?;
+        let sender_id = self
+            .tag("account")
+            .filter(|account| *account != "*")
+            .map_or_else(
+                || prefix.to_string(),
+                |account| format!("account:{account}"),
+            );
+        let (target, text) = match self.params.as_slice() {
+            [target, text] => (*target, *text),
+            _ => return None,
+        };
+        if text.starts_with('\u{1}') || text.trim().is_empty() {
+            return None;
+        }
+
+        let is_dm = target.eq_ignore_ascii_case(nick);
+        Some(IncomingMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            channel_id: if is_dm { sender } else { target }.to_string(),
+            sender_id,
+            sender_name: sender.to_string(),
+            content: text.to_string(),
+            is_dm,
+            reply_to: None,
+            attachments: Vec::new(),
+            thread_id: None,
+            callback_data: None,
+            request_id: crate::request_id::current_or_new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_handles_prefix_tags_and_trailing() {
+        let msg =
+            IrcMessage::parse("@time=2024-01-01T00:00:00Z :alice!a@host PRIVMSG #beacon :hi there")
+                .unwrap();
+        assert_eq!(msg.tags, Some("time=2024-01-01T00:00:00Z"));
+        assert_eq!(msg.tag("time"), Some("2024-01-01T00:00:00Z"));
+        assert_eq!(msg.prefix, Some("alice!a@host"));
+        assert_eq!(msg.command, "PRIVMSG");
+        assert_eq!(msg.params, vec!["#beacon", "hi there"]);
+
+        let ping = IrcMessage::parse("PING :irc.example.net\r\n").unwrap();
+        assert_eq!(ping.prefix, None);
+        assert_eq!(ping.params, vec!["irc.example.net"]);
+
+        assert!(IrcMessage::parse("").is_none());
+    }
+
+    #[test]
+    fn privmsg_to_nick_is_dm() {
+        let dm = IrcMessage::parse(":alice!a@host PRIVMSG Beacon :hello")
+            .unwrap()
+            .to_incoming("beacon")
+            .unwrap();
+        assert!(dm.is_dm);
+        assert_eq!(dm.channel_id, "alice");
+        assert_eq!(dm.sender_id, "alice!a@host");
+        assert_eq!(dm.sender_name, "alice");
+
+        let chan = IrcMessage::parse(":alice!a@host PRIVMSG #beacon :hello")
+            .unwrap()
+            .to_incoming("beacon")
+            .unwrap();
+        assert!(!chan.is_dm);
+        assert_eq!(chan.channel_id, "#beacon");
+
+        let ctcp = IrcMessage::parse(":alice!a@host PRIVMSG beacon :\u{1}VERSION\u{1}").unwrap();
+        assert!(ctcp.to_incoming("beacon").is_none());
+    }
+
+    #[test]
+    fn account_tag_identifies_sender() {
+        let msg = IrcMessage::parse("@account=alice_acct;time=x :alice!a@host PRIVMSG #b :hi")
+            .unwrap()
+            .to_incoming("beacon")
+            .unwrap();
+        assert_eq!(msg.sender_id, "account:alice_acct");
+
+        // `*` means logged out
+        let msg = IrcMessage::parse("@account=* :alice!a@host PRIVMSG #b :hi")
+            .unwrap()
+            .to_incoming("beacon")
+            .unwrap();
+        assert_eq!(msg.sender_id, "alice!a@host");
+    }
+
+    #[test]
+    fn sanitize_strips_line_breaks_and_nul() {
+        assert_eq!(
+            sanitize_line("PRIVMSG #a :hi\r\nQUIT :bye\0"),
+            "PRIVMSG #a :hiQUIT :bye"
+        );
+    }
+
+    #[test]
+    fn split_message_respects_byte_budget() {
+        assert_eq!(split_message("hello world", 100), vec!["hello world"]);
+        assert_eq!(split_message("hello world", 8), vec!["hello", "world"]);
+
+        // Multi-byte characters are never cut in half
+        let chunks = split_message("ééééé", 5);
+        assert_eq!(chunks, vec!["éé", "éé", "é"]);
+        assert!(chunks.iter().all(|c| c.len() <= 5));
+    }
+
+    #[test]
+    fn lines_fit_in_512_bytes() {
+        let nick = "beacon";
+        let target = "#channel";
+        let max = max_text_bytes(target, nick);
+        let text = "x".repeat(2000);
+        for chunk in split_message(&text, max) {
+            let relayed = format!(
+                ":{nick}!{}@host PRIVMSG {target} :{chunk}\r\n",
+                "u".repeat(USERHOST_RESERVE - 6)
+            );
+            assert!(relayed.len() <= MAX_LINE_BYTES);
+        }
+    }
+}
@@ -0,0 +1,720 @@
+//! Mastodon channel adapter using the Mastodon REST API
+//!
+//! Polls the notifications endpoint for mentions and replies with statuses
+//! threaded via `in_reply_to_id`. Works with any Mastodon-compatible
+//! `ActivityPub` server (Mastodon, GoToSocial, Akkoma, etc.)
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use super::{Attachment, Channel, ChannelCapability, IncomingMessage, OutgoingMessage};
+use crate::{Error, Result};
+
+/// Interval between notification polls
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Status length used when the instance does not report its limit
+const DEFAULT_MAX_CHARACTERS: usize = 500;
+
+/// Media attachments allowed per status
+const MAX_MEDIA_PER_STATUS: usize = 4;
+
+/// Mastodon channel adapter
+pub struct MastodonChannel {
+    instance_url: String,
+    access_token: String,
+    client: reqwest::Client,
+    message_tx: Option<mpsc::Sender<IncomingMessage>>,
+    connected: bool,
+    /// Authenticated account ID (set on connect)
+    account_id: Option<String>,
+    /// Authenticated account username (set on connect)
+    username: Option<String>,
+    /// Maximum characters per status (set on connect)
+    max_characters: usize,
+    /// Stops the poll loop (replaced on each connect)
+    shutdown: CancellationToken,
+}
+
+/// Account returned by the API
+#[derive(Debug, Clone, Deserialize)]
+struct Account {
+    id: String,
+    /// `user` for local accounts, `user@domain` for remote ones
+    acct: String,
+    username: String,
+    #[serde(default)]
+    display_name: String,
+}
+
+/// Notification returned by `/api/v1/notifications`
+#[derive(Debug, Deserialize)]
+struct Notification {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    account: Account,
+    status: Option<Status>,
+}
+
+/// Status returned by the API
+#[derive(Debug, Deserialize)]
+struct Status {
+    id: String,
+    /// HTML content
+    content: String,
+    visibility: String,
+    in_reply_to_id: Option<String>,
+    account: Account,
+    #[serde(default)]
+    media_attachments: Vec<MediaAttachmentInfo>,
+}
+
+/// Media attached to a status
+#[derive(Debug, Deserialize)]
+struct MediaAttachmentInfo {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    url: Option<String>,
+}
+
+/// Instance metadata from `/api/v2/instance`
+#[derive(Debug, Deserialize)]
+struct Instance {
+    configuration: Option<InstanceConfiguration>,
+}
+
+/// Instance configuration limits
+#[derive(Debug, Deserialize)]
+struct InstanceConfiguration {
+    statuses: Option<StatusLimits>,
+}
+
+/// Status limits
+#[derive(Debug, Deserialize)]
+struct StatusLimits {
+    max_characters: Option<usize>,
+}
+
+/// Status creation request
+#[derive(Debug, Serialize)]
+struct StatusRequest<'a> {
+    status: &'a str,
+    visibility: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    in_reply_to_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    media_ids: Option<&'a [String]>,
+}
+
+/// Created status (only the ID is needed to continue a thread)
+#[derive(Debug, Deserialize)]
+struct CreatedStatus {
+    id: String,
+}
+
+/// Where and how a reply should be posted
+struct ReplyTarget {
+    /// Account mentioned at the start of each status
+    acct: String,
+    /// Visibility of the reply
+    visibility: String,
+    /// Status being replied to
+    in_reply_to_id: Option<String>,
+}
+
+impl MastodonChannel {
+    /// Create a new Mastodon channel adapter
+    ///
+    /// # Arguments
+    ///
+    /// * `instance_url` - Instance base URL (e.g., "<https://mastodon.social>")
+    /// * `access_token` - Access token with `read` and `write` scopes
+    #[must_use]
+    pub fn new(instance_url: &str, access_token: String) -> Self {
+        Self {
+            instance_url: instance_url.trim_end_matches('/').to_string(),
+            access_token,
+            client: reqwest::Client::new(),
+            message_tx: None,
+            connected: false,
+            account_id: None,
+            username: None,
+            max_characters: DEFAULT_MAX_CHARACTERS,
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Create with a message receiver
+    ///
+    /// Returns the channel and a receiver for incoming messages
+    #[must_use]
+    pub fn with_receiver(
+        instance_url: &str,
+        access_token: String,
+    ) -> (Self, mpsc::Receiver<IncomingMessage>) {
+        let (tx, rx) = mpsc::channel(100);
+        let mut channel = Self::new(instance_url, access_token);
+        channel.message_tx = Some(tx);
+        (channel, rx)
+    }
+
+    /// Build API endpoint URL
+    fn api_url(&self, path: &str) -> String {
+        format!("{}/api{path}", self.instance_url)
+    }
+
+    /// Send a GET request and parse the JSON response
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let response = self
+            .client
+            .get(self.api_url(path))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Mastodon request failed: {e}")))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Channel(format!(
+                "Mastodon API error: {status} - {body}"
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Channel(format!("Mastodon parse error: {e}")))
+    }
+
+    /// Newest mention notification ID, so polling skips history
+    async fn latest_notification_id(&self) -> Result<Option<String>> {
+        let notifications: Vec<Notification> = self
+            .get_json("/v1/notifications?types[]=mention&limit=1")
+            .await?;
+        Ok(notifications.into_iter().next().map(|n| n.id))
+    }
+
+    /// Poll for new mentions in the background
+    fn spawn_poll_loop(&self, since_id: Option<String>) {
+        let instance_url = self.instance_url.clone();
+        let access_token = self.access_token.clone();
+        let client = self.client.clone();
+        let message_tx = self.message_tx.clone();
+        let account_id = self.account_id.clone();
+        let username = self.username.clone().unwrap_or_default();
+        let shutdown = self.shutdown.clone();
+
+        tokio::spawn(async move {
+            let mut since_id = since_id;
+
+            loop {
+                tokio::select! {
+                    () = shutdown.cancelled() => break,
+                    () = tokio::time::sleep(POLL_INTERVAL) => {}
+                }
+
+                let mut url =
+                    format!("{instance_url}/api/v1/notifications?types[]=mention&limit=40");
+                if let Some(id) = &since_id {
+                    url.push_str("&since_id=");
+                    url.push_str(id);
+                }
+
+                let response = match client.get(&url).bearer_auth(&access_token).send().await {
+                    Ok(r) if r.status().is_success() => r,
+                    Ok(r) => {
+                        tracing::warn!(status = %r.status(), "Mastodon poll error, retrying");
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Mastodon poll request failed, retrying");
+                        continue;
+                    }
+                };
+
+                let notifications: Vec<Notification> = match response.json().await {
+                    Ok(n) => n,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Mastodon poll parse error, retrying");
+                        continue;
+                    }
+                };
+
+                // Notifications arrive newest first
+                if let Some(newest) = notifications.first() {
+                    since_id = Some(newest.id.clone());
+                }
+
+                for notification in notifications.into_iter().rev() {
+                    if notification.kind != "mention"
+                        || account_id.as_deref() == Some(notification.account.id.as_str())
+                    {
+                        continue;
+                    }
+                    let Some(status) = notification.status else {
+                        continue;
+                    };
+
+                    let incoming = incoming_from_status(status, &username);
+                    if let Some(tx) = &message_tx
+                        && let Err(e) = tx.send(incoming).await
+                    {
+                        tracing::warn!(error = %e, "Failed to forward Mastodon message");
+                    }
+                }
+            }
+        });
+    }
+
+    /// Resolve mention and visibility for a reply
+    ///
+    /// Replies keep the visibility of the status they answer, except that public
+    /// mentions are answered unlisted to stay off the public timelines. Messages
+    /// without a status to reply to are sent as direct messages.
+    async fn reply_target(&self, message: &OutgoingMessage) -> Result<ReplyTarget> {
+        let Some(status_id) = message.reply_to.as_deref() else {
+            return Ok(ReplyTarget {
+                acct: message.channel_id.clone(),
+                visibility: "direct".to_string(),
+                in_reply_to_id: None,
+            });
+        };
+
+        let status: Status = self.get_json(&format!("/v1/statuses/{status_id}")).await?;
+        let visibility = match status.visibility.as_str() {
+            "public" => "unlisted".to_string(),
+            other => other.to_string(),
+        };
+        Ok(ReplyTarget {
+            acct: status.account.acct,
+            visibility,
+            in_reply_to_id: Some(status.id),
+        })
+    }
+
+    /// Post `content` as a thread of statuses, attaching media to the first
+    async fn post_thread(
+        &self,
+        target: ReplyTarget,
+        content: &str,
+        media_ids: &[String],
+    ) -> Result<()> {
+        let mention = format!("@{} ", target.acct);
+        let budget = self
+            .max_characters
+            .saturating_sub(mention.chars().count())
+            .max(1);
+
+        let mut chunks = split_status(content, budget);
+        if chunks.is_empty() {
+            chunks.push(String::new());
+        }
+
+        let mut in_reply_to_id = target.in_reply_to_id;
+        for (i, chunk) in chunks.iter().enumerate() {
+            let text = format!("{mention}{chunk}");
+            let request = StatusRequest {
+                status: text.trim_end(),
+                visibility: &target.visibility,
+                in_reply_to_id: in_reply_to_id.as_deref(),
+                media_ids: (i == 0 && !media_ids.is_empty()).then_some(media_ids),
+            };
+
+            let response = self
+                .client
+                .post(self.api_url("/v1/statuses"))
+                .bearer_auth(&self.access_token)
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| Error::Channel(format!("Mastodon send failed: {e}")))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(Error::Channel(format!(
+                    "Mastodon send failed: {status} - {body}"
+                )));
+            }
+
+            let created: CreatedStatus = response
+                .json()
+                .await
+                .map_err(|e| Error::Channel(format!("Mastodon parse error: {e}")))?;
+            in_reply_to_id = Some(created.id);
+        }
+
+        tracing::debug!(
+            acct = %target.acct,
+            statuses = chunks.len(),
+            "Mastodon status sent"
+        );
+        Ok(())
+    }
+
+    /// Upload an attachment and return its media ID
+    async fn upload_media(&self, attachment: &Attachment) -> Result<String> {
+        let data = match (&attachment.data, &attachment.url) {
+            (Some(data), _) => data.clone(),
+            (None, Some(url)) => self
+                .client
+                .get(url)
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .map_err(|e| Error::Channel(format!("Mastodon attachment fetch failed: {e}")))?
+                .bytes()
+                .await
+                .map_err(|e| Error::Channel(format!("Mastodon attachment fetch failed: {e}")))?
+                .to_vec(),
+            (None, None) => {
+                return Err(Error::Channel("attachment has no data or URL".to_string()));
+            }
+        };
+
+        let part = reqwest::multipart::Part::bytes(data)
+            .file_name(
+                attachment
+                    .filename
+                    .clone()
+                    .unwrap_or_else(|| "attachment".to_string()),
+            )
+            .mime_str(&attachment.mime_type)
+            .map_err(|e| Error::Channel(format!("Invalid attachment MIME type: {e}")))?;
+        let form = reqwest::multipart::Form::new().part("file", part);
+
+        let response = self
+            .client
+            .post(self.api_url("/v2/media"))
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Mastodon upload failed: {e}")))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Channel(format!(
+                "Mastodon upload failed: {status} - {body}"
+            )));
+        }
+
+        let mut media: MediaAttachmentInfo = response
+            .json()
+            .await
+            .map_err(|e| Error::Channel(format!("Mastodon parse error: {e}")))?;
+
+        // Large files are processed asynchronously; statuses reject media
+        // until a URL is available
+        for _ in 0..10 {
+            if media.url.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            media = self.get_json(&format!("/v1/media/{}", media.id)).await?;
+        }
+
+        Ok(media.id)
+    }
+
+    /// Favourite or unfavourite a status
+    async fn set_favourite(&self, status_id: &str, favourite: bool) -> Result<()> {
+        let action = if favourite {
+            "favourite"
+        } else {
+            "unfavourite"
+        };
+        let response = self
+            .client
+            .post(self.api_url(&format!("/v1/statuses/{status_id}/{action}")))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Mastodon {action} failed: {e}")))?;
+
+        if !response.status().is_success() {
+            tracing::debug!(status = %response.status(), action, "Mastodon reaction failed");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Channel for MastodonChannel {
+    fn name(&self) -> &'static str {
+        "mastodon"
+    }
+
+    fn capabilities(&self) -> &'static [ChannelCapability] {
+        &[ChannelCapability::MediaSend, ChannelCapability::Reactions]
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        let account: Account = self
+            .get_json("/v1/accounts/verify_credentials")
+            .await
+            .map_err(|e| Error::Channel(format!("Mastodon auth failed: {e}")))?;
+
+        // Older servers lack v2 instance metadata; fall back to the default
+        if let Ok(instance) = self.get_json::<Instance>("/v2/instance").await
+            && let Some(max) = instance
+                .configuration
+                .and_then(|c| c.statuses)
+                .and_then(|s| s.max_characters)
+        {
+            self.max_characters = max;
+        }
+
+        tracing::info!(
+            acct = %account.acct,
+            instance = %self.instance_url,
+            max_characters = self.max_characters,
+            "Mastodon authenticated"
+        );
+        self.account_id = Some(account.id);
+        self.username = Some(account.username);
+
+        let since_id = self.latest_notification_id().await?;
+        self.shutdown.cancel();
+        self.shutdown = CancellationToken::new();
+        self.spawn_poll_loop(since_id);
+
+        self.connected = true;
+        tracing::info!("Mastodon channel connected");
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.shutdown.cancel();
+        self.connected = false;
+        tracing::info!("Mastodon channel disconnected");
+        Ok(())
+    }
+
+    async fn send(&self, message: OutgoingMessage) -> Result<()> {
+        let target = self.reply_target(&message).await?;
+        self.post_thread(target, &message.content, &[]).await
+    }
+
+    async fn send_media(&self, message: OutgoingMessage) -> Result<()> {
+        if message.attachments.is_empty() {
+            return self.send(message).await;
+        }
+
+        if message.attachments.len() > MAX_MEDIA_PER_STATUS {
+            tracing::warn!(
+                count = message.attachments.len(),
+                max = MAX_MEDIA_PER_STATUS,
+                "too many attachments for one Mastodon status, dropping extras"
+            );
+        }
+
+        let mut media_ids = Vec::new();
+        for attachment in message.attachments.iter().take(MAX_MEDIA_PER_STATUS) {
+            media_ids.push(self.upload_media(attachment).await?);
+        }
+
+        let target = self.reply_target(&message).await?;
+        self.post_thread(target, &message.content, &media_ids).await
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    async fn add_reaction(&self, _channel_id: &str, message_id: &str, _emoji: &str) -> Result<()> {
+        // Mastodon has no emoji reactions; a favourite is the closest equivalent
+        self.set_favourite(message_id, true).await
+    }
+
+    async fn remove_reaction(
+        &self,
+        _channel_id: &str,
+        message_id: &str,
+        _emoji: &str,
+    ) -> Result<()> {
+        self.set_favourite(message_id, false).await
+    }
+}
+
+/// Map a mentioning status to an incoming message
+///
+/// The channel ID is the author's `acct`, so replies without a status to
+/// thread onto can still mention them.
+fn incoming_from_status(status: Status, own_username: &str) -> IncomingMessage {
+    let content = strip_leading_mention(&html_to_text(&status.content), own_username);
+
+    let attachments = status
+        .media_attachments
+        .into_iter()
+        .filter_map(|media| {
+            let url = media.url?;
+            let mime_type = match media.kind.as_str() {
+                "image" => Attachment::image(&url, None)
+                    .map_or_else(|| "image/jpeg".to_string(), |a| a.mime_type),
+                "video" | "gifv" => "video/mp4".to_string(),
+                "audio" => "audio/mpeg".to_string(),
+                _ => "application/octet-stream".to_string(),
+            };
+            Some(Attachment::from_url(url, mime_type, None))
+        })
+        .collect();
+
+    let sender_name = if status.account.display_name.trim().is_empty() {
+        status.account.acct.clone()
+    } else {
+        status.account.display_name.clone()
+    };
+
+    IncomingMessage {
+        id: status.id,
+        channel_id: status.account.acct.clone(),
+        sender_id: status.account.acct,
+        sender_name,
+        content,
+        is_dm: status.visibility == "direct",
+        reply_to: status.in_reply_to_id,
+        attachments,
+        thread_id: None,
+        callback_data: None,
+        request_id: crate::request_id::current_or_new(),
+    }
+}
+
+/// Convert status HTML to plain text, keeping paragraph and line breaks
+fn html_to_text(html: &str) -> String {
+    let html = html
+        .replace("<br>", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("</p><p>", "\n\n");
+    let fragment = scraper::Html::parse_fragment(&html);
+    fragment
+        .root_element()
+        .text()
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Remove mentions of the bot from the start of a status
+fn strip_leading_mention(text: &str, username: &str) -> String {
+    let mut rest = text.trim_start();
+    while let Some(token) = rest.split_whitespace().next() {
+        let handle = token.trim_start_matches('@');
+        let is_self = token.starts_with('@')
+            && !username.is_empty()
+            && handle
+                .split('@')
+                .next()
+                .is_some_and(|name| name.eq_ignore_ascii_case(username));
+        if !is_self {
+            break;
+        }
+        rest = rest[token.len()..].trim_start();
+    }
+    rest.to_string()
+}
+
+/// Split text into statuses of at most `max_chars`, preferring line and
+/// word boundaries
+fn split_status(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = text.trim();
+
+    while !rest.is_empty() {
+        if rest.chars().count() <= max_chars {
+            chunks.push(rest.to_string());
+            break;
+        }
+
+        let limit = rest
+            .char_indices()
+            .nth(max_chars)
+            .map_or(rest.len(), |(i, _)| i);
+        let head = &rest[..limit];
+        let split = head
+            .rfind('\n')
+            .or_else(|| head.rfind(' '))
+            .filter(|&i| i > 0)
+            .unwrap_or(limit);
+
+        chunks.push(rest[..split].trim_end().to_string());
+        rest = rest[split..].trim_start();
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_to_text_keeps_breaks_and_drops_markup() {
+        let html = r#"<p><span class="h-card"><a href="https://example.social/@beacon">@<span>beacon</span></a></span> hi &amp; hello</p><p>second<br>line</p>"#;
+        assert_eq!(html_to_text(html), "@beacon hi & hello\n\nsecond\nline");
+    }
+
+    #[test]
+    fn strip_leading_mention_removes_only_self() {
+        assert_eq!(
+            strip_leading_mention("@beacon @Beacon@example.social what time is it?", "beacon"),
+            "what time is it?"
+        );
+        assert_eq!(
+            strip_leading_mention("@alice can you ask @beacon", "beacon"),
+            "@alice can you ask @beacon"
+        );
+    }
+
+    #[test]
+    fn split_status_prefers_word_boundaries() {
+        assert_eq!(split_status("short", 500), vec!["short"]);
+        assert_eq!(
+            split_status("one two three four", 9),
+            vec!["one two", "three", "four"]
+        );
+        assert_eq!(split_status("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert!(split_status("   ", 10).is_empty());
+    }
+
+    #[test]
+    fn incoming_from_status_maps_direct_mentions() {
+        let status: Status = serde_json::from_value(serde_json::json!({
+            "id": "110",
+            "content": "<p>@beacon remind me</p>",
+            "visibility": "direct",
+            "in_reply_to_id": "100",
+            "account": {
+                "id": "7",
+                "acct": "alice@example.social",
+                "username": "alice",
+                "display_name": "Alice"
+            },
+            "media_attachments": [
+                { "id": "1", "type": "image", "url": "https://files.example/a.png" },
+                { "id": "2", "type": "gifv", "url": "https://files.example/b.mp4" }
+            ]
+        }))
+        .unwrap();
+
+        let msg = incoming_from_status(status, "beacon");
+        assert_eq!(msg.id, "110");
+        assert_eq!(msg.channel_id, "alice@example.social");
+        assert_eq!(msg.sender_name, "Alice");
+        assert_eq!(msg.content, "remind me");
+        assert!(msg.is_dm);
+        assert_eq!(msg.reply_to.as_deref(), Some("100"));
+        assert_eq!(msg.attachments[0].mime_type, "image/png");
+        assert_eq!(msg.attachments[1].mime_type, "video/mp4");
+    }
+}
@@ -409,6 +409,7 @@ impl MatrixChannel {
                                     attachments,
                                     thread_id: None,
                                     callback_data: None,
+                                    request_id: crate::request_id::current_or_new(),
                                 };
 
                                 if let Some(tx) = &message_tx
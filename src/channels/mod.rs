@@ -2,29 +2,46 @@
 //!
 //! Each channel implements the `Channel` trait to provide unified messaging.
 
+pub mod chunking;
 mod discord;
 mod google_chat;
 mod imessage;
+mod irc;
+mod mastodon;
 mod matrix;
+pub mod rate_limit;
 mod signal;
 mod slack;
+pub mod streaming;
 mod teams;
 mod telegram;
+mod webhook;
 mod whatsapp;
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use async_trait::async_trait;
 
 pub use discord::DiscordChannel;
 pub use google_chat::{GoogleChatChannel, GoogleChatEvent};
 pub use imessage::{IMessageChannel, IMessageChat, IMessageMessage};
+pub use irc::{IrcChannel, IrcConfig};
+pub use mastodon::MastodonChannel;
 pub use matrix::MatrixChannel;
+pub use rate_limit::{RateLimitPolicy, RateLimitedChannel, RateLimiter};
 pub use signal::{SignalChannel, SignalMessage};
 pub use slack::{SlackChannel, SlackEvent};
+pub use streaming::{StreamingConfig, StreamingEditCoalescer, StreamingSettings, TypingKeepalive};
 pub use teams::{TeamsActivity, TeamsChannel};
 pub use telegram::{
     BotCommand, MediaFileRef, TelegramAccount, TelegramAccountRegistry, TelegramChannel,
     TelegramRateLimiter, UpdateDedup, extract_update_media_refs, should_skip_group_message,
 };
+pub use webhook::{
+    InboundPayload, SIGNATURE_HEADER, TIMESTAMP_HEADER, WebhookChannel, WebhookInbound,
+    verify_signed,
+};
 pub use whatsapp::{WhatsAppChannel, WhatsAppWebhook};
 
 use crate::Result;
@@ -108,6 +125,39 @@ impl Attachment {
             filename,
         }
     }
+
+    /// Create an image attachment from an `http(s)` URL or a base64 `data:` URL
+    ///
+    /// Returns `None` for other sources or malformed data URLs.
+    #[must_use]
+    pub fn image(src: &str, filename: Option<String>) -> Option<Self> {
+        use base64::Engine as _;
+
+        if let Some(rest) = src.strip_prefix("data:") {
+            let (mime_type, data) = rest.split_once(";base64,")?;
+            let data = base64::engine::general_purpose::STANDARD
+                .decode(data.trim())
+                .ok()?;
+            return Some(Self::from_data(data, mime_type.to_string(), filename));
+        }
+
+        if !(src.starts_with("https://") || src.starts_with("http://")) {
+            return None;
+        }
+        let path = src.split(['?', '#']).next().unwrap_or(src).to_lowercase();
+        let mime_type = match path.rsplit('.').next() {
+            Some("png") => "image/png",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            Some("svg") => "image/svg+xml",
+            _ => "image/jpeg",
+        };
+        Some(Self::from_url(
+            src.to_string(),
+            mime_type.to_string(),
+            filename,
+        ))
+    }
 }
 
 impl AttachmentKind {
@@ -159,6 +209,9 @@ pub struct IncomingMessage {
 
     /// Inline keyboard callback data (when user clicks a button)
     pub callback_data: Option<String>,
+
+    /// Correlation id assigned at ingress (see [`crate::request_id`])
+    pub request_id: String,
 }
 
 /// Interactive inline keyboard
@@ -228,6 +281,9 @@ pub struct OutgoingMessage {
 
     /// Send as voice note (TTS output)
     pub voice_note: bool,
+
+    /// Images and files to upload via [`Channel::send_media`]
+    pub attachments: Vec<Attachment>,
 }
 
 impl OutgoingMessage {
@@ -243,6 +299,7 @@ impl OutgoingMessage {
             media: Vec::new(),
             edit_target: None,
             voice_note: false,
+            attachments: Vec::new(),
         }
     }
 
@@ -258,9 +315,36 @@ impl OutgoingMessage {
             media: Vec::new(),
             edit_target: None,
             voice_note: false,
+            attachments: Vec::new(),
         }
     }
 
+    /// Attach images or files (sent via [`Channel::send_media`])
+    #[must_use]
+    pub fn with_attachments(mut self, attachments: Vec<Attachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+
+    /// Content with attachment URLs appended, for channels without media upload
+    ///
+    /// Inline-only attachments have nothing to link to and are omitted.
+    #[must_use]
+    pub fn content_with_attachment_links(&self) -> String {
+        let links: Vec<&str> = self
+            .attachments
+            .iter()
+            .filter_map(|a| a.url.as_deref())
+            .collect();
+        if links.is_empty() {
+            return self.content.clone();
+        }
+        if self.content.trim().is_empty() {
+            return links.join("\n");
+        }
+        format!("{}\n\n{}", self.content, links.join("\n"))
+    }
+
     /// Check if content contains code blocks
     #[must_use]
     pub fn has_code_blocks(&self) -> bool {
@@ -317,7 +401,10 @@ impl OutgoingMessage {
 #[async_trait]
 pub trait Channel: Send + Sync {
     /// Get the channel name
-    fn name(&self) -> &'static str;
+    ///
+    /// Built-in adapters return a constant; extension channels return their
+    /// manifest-provided name.
+    fn name(&self) -> &str;
 
     /// Declare which capabilities this channel supports
     fn capabilities(&self) -> &'static [ChannelCapability] {
@@ -333,9 +420,38 @@ pub trait Channel: Send + Sync {
     /// Send a message
     async fn send(&self, message: OutgoingMessage) -> Result<()>;
 
+    /// Send a message with its attachments
+    ///
+    /// Default implementation posts attachment URLs as text for channels
+    /// without media upload; inline-only attachments are dropped.
+    async fn send_media(&self, mut message: OutgoingMessage) -> Result<()> {
+        let dropped = message
+            .attachments
+            .iter()
+            .filter(|a| a.url.is_none())
+            .count();
+        if dropped > 0 {
+            tracing::warn!(
+                channel = self.name(),
+                dropped,
+                "channel cannot upload media, dropping inline attachments"
+            );
+        }
+        message.content = message.content_with_attachment_links();
+        message.attachments.clear();
+        self.send(message).await
+    }
+
     /// Check if connected
     fn is_connected(&self) -> bool;
 
+    /// Platform user ID the bot posts as, once connected
+    ///
+    /// History backfill uses it to tell the bot's own messages from users'.
+    fn bot_user_id(&self) -> Option<&str> {
+        None
+    }
+
     /// Send typing indicator to show the bot is processing
     ///
     /// Default implementation is a no-op for channels that don't support typing
@@ -407,6 +523,23 @@ pub trait Channel: Send + Sync {
     async fn delete_message(&self, _channel_id: &str, _message_id: &str) -> Result<()> {
         Ok(())
     }
+
+    /// Fetch up to `limit` recent messages from a conversation, oldest first
+    ///
+    /// Used to backfill context for sessions with no local history. Adapters
+    /// page through the platform API internally. Default implementation errors
+    /// for platforms without a history API; the Telegram Bot API has none, so
+    /// Telegram sessions are not backfilled.
+    async fn fetch_history(
+        &self,
+        _channel_id: &str,
+        _limit: usize,
+    ) -> Result<Vec<IncomingMessage>> {
+        Err(crate::Error::Channel(format!(
+            "{} does not support fetching history",
+            self.name()
+        )))
+    }
 }
 
 /// Channel registry - manages multiple channel adapters
@@ -428,6 +561,12 @@ impl ChannelRegistry {
         self.channels.push(channel);
     }
 
+    /// Register a channel adapter whose outbound calls are rate limited
+    pub fn register_with_policy(&mut self, channel: Box<dyn Channel>, policy: RateLimitPolicy) {
+        self.channels
+            .push(Box::new(RateLimitedChannel::new(channel, policy)));
+    }
+
     /// Connect all registered channels
     ///
     /// # Errors
@@ -456,3 +595,55 @@ impl Default for ChannelRegistry {
         Self::new()
     }
 }
+
+/// Send-only channel adapters keyed by channel name
+///
+/// Used for proactive delivery (e.g. scheduled reminders) from places that
+/// don't own the channel's receive loop, such as API webhook handlers.
+/// Channels provided by extensions are read from the registry's shared map
+/// on each lookup, so reloads are picked up.
+#[derive(Clone, Default)]
+pub struct OutboundChannels {
+    channels: HashMap<String, Arc<dyn Channel>>,
+    extensions: Option<crate::extensions::ExtensionChannels>,
+}
+
+impl OutboundChannels {
+    /// Register an adapter under its channel name, replacing any existing one
+    pub fn insert(&mut self, channel: Arc<dyn Channel>) {
+        self.channels.insert(channel.name().to_string(), channel);
+    }
+
+    /// Fall back to channels provided by loaded extensions
+    pub fn set_extensions(&mut self, channels: crate::extensions::ExtensionChannels) {
+        self.extensions = Some(channels);
+    }
+
+    /// Get the adapter for a channel name
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Channel>> {
+        if let Some(channel) = self.channels.get(name) {
+            return Some(Arc::clone(channel));
+        }
+        self.extensions
+            .as_ref()?
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .values()
+            .flatten()
+            .find(|channel| channel.name() == name)
+            .cloned()
+    }
+
+    /// Whether no adapters are registered
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+            && self.extensions.as_ref().is_none_or(|ext| {
+                ext.read()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .values()
+                    .all(Vec::is_empty)
+            })
+    }
+}
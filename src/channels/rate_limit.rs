@@ -0,0 +1,431 @@
+//! Outbound rate limiting for channel adapters
+//!
+//! Platforms enforce per-channel rate limits and answer bursts (e.g. streaming
+//! edits) with 429s. [`RateLimiter`] is a token bucket keyed by `channel_id`:
+//! calls over the limit wait their turn instead of failing, up to a maximum
+//! queue depth. [`IntervalLimiter`] is a non-blocking throttle for skipping
+//! intermediate edits outright.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use super::{Channel, ChannelCapability, OutgoingMessage, StreamingConfig};
+use crate::{Error, Result};
+
+/// Default number of calls allowed to wait per `channel_id`
+pub const DEFAULT_MAX_QUEUE: usize = 32;
+
+/// Buckets tracked before idle ones are pruned
+const MAX_TRACKED_BUCKETS: usize = 1024;
+
+/// Token-bucket limits for one channel adapter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitPolicy {
+    /// Calls allowed back-to-back before limiting kicks in
+    pub burst: u32,
+    /// Sustained calls per second per `channel_id`
+    pub per_second: f64,
+    /// Calls allowed to wait per `channel_id`; further calls fail
+    pub max_queue: usize,
+}
+
+impl RateLimitPolicy {
+    /// Create a policy with the default queue depth
+    #[must_use]
+    pub const fn new(per_second: f64, burst: u32) -> Self {
+        Self {
+            burst,
+            per_second,
+            max_queue: DEFAULT_MAX_QUEUE,
+        }
+    }
+
+    /// Set the maximum number of waiting calls per `channel_id`
+    #[must_use]
+    pub const fn with_max_queue(mut self, max_queue: usize) -> Self {
+        self.max_queue = max_queue;
+        self
+    }
+
+    /// Discord: 5 messages per 5 seconds per channel
+    #[must_use]
+    pub const fn discord() -> Self {
+        Self::new(1.0, 5)
+    }
+
+    /// Slack: about one message per second per channel, short bursts tolerated
+    #[must_use]
+    pub const fn slack() -> Self {
+        Self::new(1.0, 3)
+    }
+
+    /// Default policy for a channel name, if it has known platform limits
+    #[must_use]
+    pub fn for_channel(name: &str) -> Option<Self> {
+        match name {
+            "discord" => Some(Self::discord()),
+            "slack" => Some(Self::slack()),
+            _ => None,
+        }
+    }
+}
+
+/// Token bucket for one `channel_id`
+#[derive(Debug)]
+struct Bucket {
+    /// Available tokens; negative when calls are queued
+    tokens: f64,
+    updated: Instant,
+    queued: usize,
+}
+
+/// Token-bucket limiter keyed by `channel_id`
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    policy: RateLimitPolicy,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl RateLimiter {
+    /// Create a limiter enforcing `policy`
+    #[must_use]
+    pub fn new(policy: RateLimitPolicy) -> Self {
+        Self {
+            policy,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Limits being enforced
+    #[must_use]
+    pub const fn policy(&self) -> RateLimitPolicy {
+        self.policy
+    }
+
+    /// Wait until a call to `channel_id` is allowed
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `max_queue` calls are already waiting for `channel_id`
+    pub async fn acquire(&self, channel_id: &str) -> Result<()> {
+        let wait = self.reserve(channel_id, Instant::now())?;
+        if wait.is_zero() {
+            return Ok(());
+        }
+
+        // Release the queue slot even if the caller is cancelled mid-wait
+        let _slot = QueueSlot {
+            limiter: self,
+            channel_id,
+        };
+        tracing::debug!(
+            channel_id,
+            wait_ms = wait.as_millis(),
+            "outbound rate limit reached, queueing"
+        );
+        tokio::time::sleep(wait).await;
+        Ok(())
+    }
+
+    /// Take a token at `now`, returning how long the caller must wait for it
+    fn reserve(&self, channel_id: &str, now: Instant) -> Result<Duration> {
+        let burst = f64::from(self.policy.burst.max(1));
+        let rate = self.policy.per_second.max(f64::EPSILON);
+
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            buckets.retain(|_, b| {
+                b.queued > 0
+                    || b.tokens + now.duration_since(b.updated).as_secs_f64() * rate < burst
+            });
+        }
+        let bucket = buckets.entry(channel_id.to_string()).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+            queued: 0,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = elapsed.mul_add(rate, bucket.tokens).min(burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(Duration::ZERO);
+        }
+        if bucket.queued >= self.policy.max_queue {
+            return Err(Error::RateLimited(format!(
+                "outbound queue full for {channel_id}"
+            )));
+        }
+
+        // Borrow against future refill; the deficit is the wait
+        bucket.tokens -= 1.0;
+        bucket.queued += 1;
+        Ok(Duration::from_secs_f64(-bucket.tokens / rate))
+    }
+
+    fn release(&self, channel_id: &str) {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(bucket) = buckets.get_mut(channel_id) {
+            bucket.queued = bucket.queued.saturating_sub(1);
+        }
+    }
+}
+
+/// A waiting call's place in a bucket's queue
+struct QueueSlot<'a> {
+    limiter: &'a RateLimiter,
+    channel_id: &'a str,
+}
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.limiter.release(self.channel_id);
+    }
+}
+
+/// Channel adapter whose outbound calls pass through a [`RateLimiter`]
+///
+/// Sends, edits (including streaming updates), and reactions are limited per
+/// `channel_id`; typing indicators and deletions pass straight through.
+pub struct RateLimitedChannel {
+    inner: Box<dyn Channel>,
+    limiter: RateLimiter,
+}
+
+impl RateLimitedChannel {
+    /// Wrap `inner` with `policy`
+    #[must_use]
+    pub fn new(inner: Box<dyn Channel>, policy: RateLimitPolicy) -> Self {
+        Self {
+            inner,
+            limiter: RateLimiter::new(policy),
+        }
+    }
+
+    /// The limiter applied to outbound calls
+    #[must_use]
+    pub const fn limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+}
+
+#[async_trait]
+impl Channel for RateLimitedChannel {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> &'static [ChannelCapability] {
+        self.inner.capabilities()
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        self.inner.connect().await
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.inner.disconnect().await
+    }
+
+    async fn send(&self, message: OutgoingMessage) -> Result<()> {
+        self.limiter.acquire(&message.channel_id).await?;
+        self.inner.send(message).await
+    }
+
+    async fn send_media(&self, message: OutgoingMessage) -> Result<()> {
+        self.limiter.acquire(&message.channel_id).await?;
+        self.inner.send_media(message).await
+    }
+
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+
+    async fn send_typing(&self, channel_id: &str) -> Result<()> {
+        self.inner.send_typing(channel_id).await
+    }
+
+    async fn add_reaction(&self, channel_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        self.limiter.acquire(channel_id).await?;
+        self.inner.add_reaction(channel_id, message_id, emoji).await
+    }
+
+    async fn remove_reaction(&self, channel_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        self.inner
+            .remove_reaction(channel_id, message_id, emoji)
+            .await
+    }
+
+    async fn send_streaming_start(
+        &self,
+        channel_id: &str,
+        initial_text: &str,
+        reply_to: Option<&str>,
+        thread_id: Option<&str>,
+    ) -> Result<String> {
+        self.limiter.acquire(channel_id).await?;
+        self.inner
+            .send_streaming_start(channel_id, initial_text, reply_to, thread_id)
+            .await
+    }
+
+    async fn send_streaming_update(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<()> {
+        self.limiter.acquire(channel_id).await?;
+        self.inner
+            .send_streaming_update(channel_id, message_id, text)
+            .await
+    }
+
+    async fn send_streaming_end(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        final_text: &str,
+    ) -> Result<()> {
+        self.limiter.acquire(channel_id).await?;
+        self.inner
+            .send_streaming_end(channel_id, message_id, final_text)
+            .await
+    }
+
+    async fn edit_message(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        new_content: &str,
+    ) -> Result<()> {
+        self.limiter.acquire(channel_id).await?;
+        self.inner
+            .edit_message(channel_id, message_id, new_content)
+            .await
+    }
+
+    async fn delete_message(&self, channel_id: &str, message_id: &str) -> Result<()> {
+        self.inner.delete_message(channel_id, message_id).await
+    }
+}
+
+/// Non-blocking minimum-interval throttle keyed by `channel_id`
+///
+/// Used to drop intermediate streaming edits rather than queue them.
+#[derive(Debug, Clone)]
+pub struct IntervalLimiter {
+    /// Minimum interval between calls per chat
+    interval: Duration,
+    /// Last call timestamp per chat
+    last_call: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl IntervalLimiter {
+    /// Create a limiter with the given minimum interval between calls per chat
+    #[must_use]
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_call: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Create a limiter using a channel's configured streaming edit interval
+    #[must_use]
+    pub fn from_config(config: &StreamingConfig) -> Self {
+        Self::new(config.edit_interval())
+    }
+
+    /// Minimum interval between calls per chat
+    #[must_use]
+    pub const fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Check if a call is allowed for the given chat. Returns true if allowed
+    #[must_use]
+    pub fn check(&self, chat_id: &str) -> bool {
+        let mut map = self
+            .last_call
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = Instant::now();
+
+        if let Some(last) = map.get(chat_id)
+            && now.duration_since(*last) < self.interval
+        {
+            return false;
+        }
+
+        map.insert(chat_id.to_string(), now);
+        true
+    }
+
+    /// Record a 429 response — push the effective interval forward for this chat
+    pub fn backoff(&self, chat_id: &str) {
+        let mut map = self
+            .last_call
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let future = Instant::now() + self.interval;
+        map.insert(chat_id.to_string(), future);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst_then_queues() {
+        let limiter = RateLimiter::new(RateLimitPolicy::new(2.0, 2));
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve("c1", now).unwrap(), Duration::ZERO);
+        assert_eq!(limiter.reserve("c1", now).unwrap(), Duration::ZERO);
+        // Third and fourth calls wait for successive refills
+        assert_eq!(
+            limiter.reserve("c1", now).unwrap(),
+            Duration::from_millis(500)
+        );
+        assert_eq!(limiter.reserve("c1", now).unwrap(), Duration::from_secs(1));
+        // Other channel IDs have their own bucket
+        assert_eq!(limiter.reserve("c2", now).unwrap(), Duration::ZERO);
+    }
+
+    #[test]
+    fn bucket_refills_over_time() {
+        let limiter = RateLimiter::new(RateLimitPolicy::new(1.0, 1));
+        let now = Instant::now();
+
+        assert_eq!(limiter.reserve("c1", now).unwrap(), Duration::ZERO);
+        let later = now + Duration::from_secs(1);
+        assert_eq!(limiter.reserve("c1", later).unwrap(), Duration::ZERO);
+    }
+
+    #[test]
+    fn full_queue_is_rejected() {
+        let limiter = RateLimiter::new(RateLimitPolicy::new(1.0, 1).with_max_queue(1));
+        let now = Instant::now();
+
+        limiter.reserve("c1", now).unwrap();
+        assert!(limiter.reserve("c1", now).unwrap() > Duration::ZERO);
+        assert!(limiter.reserve("c1", now).is_err());
+
+        // A finished wait frees its slot
+        limiter.release("c1");
+        assert!(limiter.reserve("c1", now).is_ok());
+    }
+}
@@ -186,6 +186,7 @@ impl SignalChannel {
             attachments,
             thread_id: None,
             callback_data: None,
+            request_id: crate::request_id::current_or_new(),
         };
 
         if let Some(tx) = &self.message_tx {
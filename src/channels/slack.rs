@@ -7,11 +7,21 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
-use super::{Attachment, Channel, ChannelCapability, IncomingMessage, OutgoingMessage};
+use super::chunking::{ChunkStrategy, chunk_text};
+use super::{
+    Attachment, AttachmentKind, Channel, ChannelCapability, IncomingMessage, OutgoingMessage,
+    StreamingConfig, StreamingEditCoalescer,
+};
 use crate::{Error, Result};
 
 const SLACK_API_URL: &str = "https://slack.com/api";
 
+/// Slack's recommended per-message text limit
+const SLACK_MESSAGE_LIMIT: usize = 4000;
+
+/// Maximum messages requested per `conversations.history` page
+const SLACK_HISTORY_PAGE: usize = 200;
+
 /// Slack channel adapter
 pub struct SlackChannel {
     bot_token: String,
@@ -19,6 +29,9 @@ pub struct SlackChannel {
     message_tx: Option<mpsc::Sender<IncomingMessage>>,
     bot_user_id: Option<String>,
     connected: bool,
+    edits: StreamingEditCoalescer,
+    /// Thread of each streaming message, so overflow lands in the same thread
+    stream_threads: std::sync::Mutex<std::collections::HashMap<String, String>>,
 }
 
 /// Slack API response wrapper
@@ -38,6 +51,30 @@ struct AuthTestResponse {
     bot_id: Option<String>,
 }
 
+/// Conversation history page
+#[derive(Debug, Deserialize)]
+struct HistoryResponse {
+    #[serde(default)]
+    messages: Vec<HistoryMessage>,
+    response_metadata: Option<ResponseMetadata>,
+}
+
+/// Message returned by `conversations.history`
+#[derive(Debug, Deserialize)]
+struct HistoryMessage {
+    ts: String,
+    user: Option<String>,
+    text: Option<String>,
+    thread_ts: Option<String>,
+    files: Option<Vec<SlackFile>>,
+}
+
+/// Pagination cursor metadata
+#[derive(Debug, Deserialize)]
+struct ResponseMetadata {
+    next_cursor: Option<String>,
+}
+
 /// Chat post message request (simple text)
 #[derive(Debug, Serialize)]
 struct PostMessageRequest<'a> {
@@ -47,6 +84,20 @@ struct PostMessageRequest<'a> {
     thread_ts: Option<&'a str>,
 }
 
+/// Chat post message response (only the posted message's timestamp is used)
+#[derive(Debug, Deserialize)]
+struct PostMessageResponse {
+    ts: String,
+}
+
+/// Chat update request
+#[derive(Debug, Serialize)]
+struct UpdateMessageRequest<'a> {
+    channel: &'a str,
+    ts: &'a str,
+    text: &'a str,
+}
+
 /// Chat post message request with blocks
 #[derive(Debug, Serialize)]
 struct PostMessageWithBlocksRequest<'a> {
@@ -65,6 +116,8 @@ enum SlackBlock {
     Section { text: SlackText },
     #[serde(rename = "divider")]
     Divider {},
+    #[serde(rename = "image")]
+    Image { image_url: String, alt_text: String },
 }
 
 /// Slack Block Kit text object
@@ -75,6 +128,31 @@ struct SlackText {
     text: String,
 }
 
+/// External upload URL response
+#[derive(Debug, Deserialize)]
+struct UploadUrlResponse {
+    upload_url: String,
+    file_id: String,
+}
+
+/// Complete external upload request
+#[derive(Debug, Serialize)]
+struct CompleteUploadRequest<'a> {
+    files: Vec<UploadedFile<'a>>,
+    channel_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread_ts: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    initial_comment: Option<&'a str>,
+}
+
+/// File reference in a completed upload
+#[derive(Debug, Serialize)]
+struct UploadedFile<'a> {
+    id: &'a str,
+    title: &'a str,
+}
+
 /// Reaction add/remove request
 #[derive(Debug, Serialize)]
 struct ReactionRequest<'a> {
@@ -97,6 +175,8 @@ impl SlackChannel {
             message_tx: None,
             bot_user_id: None,
             connected: false,
+            edits: StreamingEditCoalescer::from_config(&StreamingConfig::for_channel("slack")),
+            stream_threads: std::sync::Mutex::default(),
         }
     }
 
@@ -112,10 +192,19 @@ impl SlackChannel {
             message_tx: Some(tx),
             bot_user_id: None,
             connected: false,
+            edits: StreamingEditCoalescer::from_config(&StreamingConfig::for_channel("slack")),
+            stream_threads: std::sync::Mutex::default(),
         };
         (channel, rx)
     }
 
+    /// Apply the streaming edit interval
+    #[must_use]
+    pub fn with_streaming(mut self, config: StreamingConfig) -> Self {
+        self.edits = StreamingEditCoalescer::from_config(&config);
+        self
+    }
+
     /// Process an incoming Slack event (from Events API webhook)
     ///
     /// Call this from your webhook handler when receiving events
@@ -137,27 +226,7 @@ impl SlackChannel {
                 return Ok(());
             }
 
-            // Parse file attachments
-            let attachments = msg
-                .files
-                .as_ref()
-                .map(|files| {
-                    files
-                        .iter()
-                        .filter_map(|f| {
-                            let mime_type = f
-                                .mimetype
-                                .clone()
-                                .unwrap_or_else(|| "application/octet-stream".to_string());
-                            let url = f
-                                .url_private_download
-                                .clone()
-                                .or_else(|| f.url_private.clone())?;
-                            Some(Attachment::from_url(url, mime_type, f.name.clone()))
-                        })
-                        .collect()
-                })
-                .unwrap_or_default();
+            let attachments = file_attachments(msg.files.as_deref());
 
             let incoming = IncomingMessage {
                 id: msg.ts.clone().unwrap_or_default(),
@@ -168,8 +237,9 @@ impl SlackChannel {
                 is_dm: msg.channel_type.as_deref() == Some("im"),
                 reply_to: msg.thread_ts.clone(),
                 attachments,
-                thread_id: None,
+                thread_id: msg.thread_ts.clone(),
                 callback_data: None,
+                request_id: crate::request_id::current_or_new(),
             };
 
             if let Some(tx) = &self.message_tx {
@@ -181,63 +251,93 @@ impl SlackChannel {
 
         Ok(())
     }
-}
 
-#[async_trait]
-impl Channel for SlackChannel {
-    fn name(&self) -> &'static str {
-        "slack"
-    }
-
-    fn capabilities(&self) -> &'static [ChannelCapability] {
-        &[ChannelCapability::Reactions]
-    }
-
-    async fn connect(&mut self) -> Result<()> {
-        // Test authentication
-        let response = self
+    /// Upload inline attachment data via Slack's external upload flow
+    async fn upload_file(
+        &self,
+        channel_id: &str,
+        thread_ts: Option<&str>,
+        attachment: &Attachment,
+        initial_comment: Option<&str>,
+    ) -> Result<()> {
+        let data = attachment.data.clone().unwrap_or_default();
+        let filename = attachment
+            .filename
+            .clone()
+            .unwrap_or_else(|| "attachment".to_string());
+
+        let result: SlackResponse<UploadUrlResponse> = self
             .client
-            .post(format!("{SLACK_API_URL}/auth.test"))
+            .post(format!("{SLACK_API_URL}/files.getUploadURLExternal"))
             .bearer_auth(&self.bot_token)
+            .form(&[
+                ("filename", filename.clone()),
+                ("length", data.len().to_string()),
+            ])
             .send()
             .await
-            .map_err(|e| Error::Channel(format!("Slack request failed: {e}")))?;
-
-        let auth: SlackResponse<AuthTestResponse> = response
+            .map_err(|e| Error::Channel(format!("Slack upload request failed: {e}")))?
             .json()
             .await
-            .map_err(|e| Error::Channel(format!("Slack parse error: {e}")))?;
+            .map_err(|e| Error::Channel(format!("Slack upload parse error: {e}")))?;
+        let upload = match (result.ok, result.data) {
+            (true, Some(upload)) => upload,
+            _ => {
+                return Err(Error::Channel(format!(
+                    "Slack upload failed: {}",
+                    result.error.unwrap_or_default()
+                )));
+            }
+        };
 
-        if !auth.ok {
+        let response = self
+            .client
+            .post(&upload.upload_url)
+            .header(reqwest::header::CONTENT_TYPE, &attachment.mime_type)
+            .body(data)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack upload failed: {e}")))?;
+        if !response.status().is_success() {
             return Err(Error::Channel(format!(
-                "Slack auth failed: {}",
-                auth.error.unwrap_or_default()
+                "Slack upload failed: {}",
+                response.status()
             )));
         }
 
-        if let Some(data) = auth.data {
-            self.bot_user_id = Some(data.user_id.clone());
-            tracing::info!(
-                user_id = %data.user_id,
-                team = %data.team,
-                bot_id = ?data.bot_id,
-                "Slack authenticated"
-            );
+        let request = CompleteUploadRequest {
+            files: vec![UploadedFile {
+                id: &upload.file_id,
+                title: &filename,
+            }],
+            channel_id,
+            thread_ts,
+            initial_comment,
+        };
+        let result: SlackResponse<serde_json::Value> = self
+            .client
+            .post(format!("{SLACK_API_URL}/files.completeUploadExternal"))
+            .bearer_auth(&self.bot_token)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack upload request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack upload parse error: {e}")))?;
+        if !result.ok {
+            return Err(Error::Channel(format!(
+                "Slack upload failed: {}",
+                result.error.unwrap_or_default()
+            )));
         }
 
-        self.connected = true;
-        tracing::info!("Slack channel connected");
-
-        Ok(())
-    }
-
-    async fn disconnect(&mut self) -> Result<()> {
-        self.connected = false;
-        tracing::info!("Slack channel disconnected");
+        tracing::debug!(channel = %channel_id, filename, "Slack file uploaded");
         Ok(())
     }
 
-    async fn send(&self, message: OutgoingMessage) -> Result<()> {
+    /// Post one message that fits within the size limit
+    async fn send_chunk(&self, message: &OutgoingMessage) -> Result<()> {
         let response = if message.has_code_blocks() {
             // Build blocks for rich content
             let mut blocks = Vec::new();
@@ -328,7 +428,164 @@ impl Channel for SlackChannel {
             )));
         }
 
-        tracing::debug!(channel = %message.channel_id, "Slack message sent");
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Channel for SlackChannel {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    fn capabilities(&self) -> &'static [ChannelCapability] {
+        &[
+            ChannelCapability::Reactions,
+            ChannelCapability::MediaSend,
+            ChannelCapability::Streaming,
+        ]
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        // Test authentication
+        let response = self
+            .client
+            .post(format!("{SLACK_API_URL}/auth.test"))
+            .bearer_auth(&self.bot_token)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack request failed: {e}")))?;
+
+        let auth: SlackResponse<AuthTestResponse> = response
+            .json()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack parse error: {e}")))?;
+
+        if !auth.ok {
+            return Err(Error::Channel(format!(
+                "Slack auth failed: {}",
+                auth.error.unwrap_or_default()
+            )));
+        }
+
+        if let Some(data) = auth.data {
+            self.bot_user_id = Some(data.user_id.clone());
+            tracing::info!(
+                user_id = %data.user_id,
+                team = %data.team,
+                bot_id = ?data.bot_id,
+                "Slack authenticated"
+            );
+        }
+
+        self.connected = true;
+        tracing::info!("Slack channel connected");
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.connected = false;
+        tracing::info!("Slack channel disconnected");
+        Ok(())
+    }
+
+    async fn send(&self, message: OutgoingMessage) -> Result<()> {
+        let chunks = chunk_text(
+            &message.content,
+            SLACK_MESSAGE_LIMIT,
+            ChunkStrategy::Paragraph,
+        );
+        let count = chunks.len();
+        for content in chunks {
+            self.send_chunk(&OutgoingMessage {
+                content,
+                ..message.clone()
+            })
+            .await?;
+        }
+
+        tracing::debug!(channel = %message.channel_id, chunks = count, "Slack message sent");
+        Ok(())
+    }
+
+    async fn send_media(&self, message: OutgoingMessage) -> Result<()> {
+        if message.attachments.is_empty() {
+            return self.send(message).await;
+        }
+
+        let thread_ts = message.reply_to.as_deref();
+        let mut text = Some(message.content.as_str()).filter(|c| !c.trim().is_empty());
+
+        // Inline data is uploaded; the first upload carries the message text
+        for attachment in message.attachments.iter().filter(|a| a.data.is_some()) {
+            self.upload_file(&message.channel_id, thread_ts, attachment, text.take())
+                .await?;
+        }
+
+        // URL attachments can't be uploaded: show images inline, link the rest
+        let mut blocks = Vec::new();
+        if let Some(text) = text {
+            blocks.push(SlackBlock::Section {
+                text: SlackText {
+                    text_type: "mrkdwn",
+                    text: text.to_string(),
+                },
+            });
+        }
+        for attachment in message.attachments.iter().filter(|a| a.data.is_none()) {
+            let Some(url) = attachment.url.clone() else {
+                tracing::warn!("Slack attachment has no data or URL, skipping");
+                continue;
+            };
+            blocks.push(if attachment.kind == AttachmentKind::Image {
+                SlackBlock::Image {
+                    image_url: url,
+                    alt_text: attachment
+                        .filename
+                        .clone()
+                        .unwrap_or_else(|| "image".to_string()),
+                }
+            } else {
+                SlackBlock::Section {
+                    text: SlackText {
+                        text_type: "mrkdwn",
+                        text: url,
+                    },
+                }
+            });
+        }
+        if blocks.is_empty() {
+            return Ok(());
+        }
+
+        let fallback = message.content_with_attachment_links();
+        let request = PostMessageWithBlocksRequest {
+            channel: &message.channel_id,
+            text: &fallback,
+            blocks,
+            thread_ts,
+        };
+        let result: SlackResponse<serde_json::Value> = self
+            .client
+            .post(format!("{SLACK_API_URL}/chat.postMessage"))
+            .bearer_auth(&self.bot_token)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack parse error: {e}")))?;
+
+        if !result.ok {
+            return Err(Error::Channel(format!(
+                "Slack send failed: {}",
+                result.error.unwrap_or_default()
+            )));
+        }
+
+        tracing::debug!(channel = %message.channel_id, "Slack media sent");
         Ok(())
     }
 
@@ -336,6 +593,10 @@ impl Channel for SlackChannel {
         self.connected
     }
 
+    fn bot_user_id(&self) -> Option<&str> {
+        self.bot_user_id.as_deref()
+    }
+
     async fn send_typing(&self, channel_id: &str) -> Result<()> {
         // Slack's typing indicator requires Socket Mode or RTM API
         // The Web API doesn't have a direct typing endpoint that works without Socket Mode
@@ -419,6 +680,226 @@ impl Channel for SlackChannel {
         tracing::debug!(channel = %channel_id, message_id, emoji, "Slack reaction removed");
         Ok(())
     }
+
+    async fn send_streaming_start(
+        &self,
+        channel_id: &str,
+        initial_text: &str,
+        reply_to: Option<&str>,
+        thread_id: Option<&str>,
+    ) -> Result<String> {
+        let text = first_chunk(initial_text);
+        let thread_ts = thread_id.or(reply_to);
+        let request = PostMessageRequest {
+            channel: channel_id,
+            text: &text,
+            thread_ts,
+        };
+
+        let result: SlackResponse<PostMessageResponse> = self
+            .client
+            .post(format!("{SLACK_API_URL}/chat.postMessage"))
+            .bearer_auth(&self.bot_token)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack parse error: {e}")))?;
+
+        match (result.ok, result.data) {
+            (true, Some(posted)) => {
+                self.edits.start(&posted.ts, initial_text);
+                if let Some(thread_ts) = thread_ts {
+                    self.stream_threads
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner)
+                        .insert(posted.ts.clone(), thread_ts.to_string());
+                }
+                Ok(posted.ts)
+            }
+            _ => Err(Error::Channel(format!(
+                "Slack send failed: {}",
+                result.error.unwrap_or_default()
+            ))),
+        }
+    }
+
+    async fn send_streaming_update(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<()> {
+        match self.edits.update(message_id, text) {
+            Some(text) => {
+                self.edit_message(channel_id, message_id, &first_chunk(&text))
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn send_streaming_end(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        final_text: &str,
+    ) -> Result<()> {
+        // Always edit on final (bypass the coalescer) to ensure final text lands
+        self.edits.finish(message_id);
+        let thread_ts = self
+            .stream_threads
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(message_id);
+
+        let mut chunks =
+            chunk_text(final_text, SLACK_MESSAGE_LIMIT, ChunkStrategy::Paragraph).into_iter();
+        let Some(first) = chunks.next() else {
+            return Ok(());
+        };
+        self.edit_message(channel_id, message_id, &first).await?;
+
+        // Overflow goes out as follow-up messages in the same thread
+        for content in chunks {
+            let message = match &thread_ts {
+                Some(ts) => OutgoingMessage::reply(channel_id.to_string(), content, ts.clone()),
+                None => OutgoingMessage::text(channel_id.to_string(), content),
+            };
+            self.send_chunk(&message).await?;
+        }
+        Ok(())
+    }
+
+    async fn edit_message(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        new_content: &str,
+    ) -> Result<()> {
+        let request = UpdateMessageRequest {
+            channel: channel_id,
+            ts: message_id,
+            text: new_content,
+        };
+
+        let result: SlackResponse<serde_json::Value> = self
+            .client
+            .post(format!("{SLACK_API_URL}/chat.update"))
+            .bearer_auth(&self.bot_token)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Channel(format!("Slack parse error: {e}")))?;
+
+        if !result.ok {
+            return Err(Error::Channel(format!(
+                "Slack update failed: {}",
+                result.error.unwrap_or_default()
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_history(&self, channel_id: &str, limit: usize) -> Result<Vec<IncomingMessage>> {
+        // Pages come back newest first, linked by a cursor
+        let mut messages: Vec<HistoryMessage> = Vec::new();
+        let mut cursor: Option<String> = None;
+        while messages.len() < limit {
+            let page_size = (limit - messages.len()).min(SLACK_HISTORY_PAGE).to_string();
+            let mut query = vec![("channel", channel_id), ("limit", page_size.as_str())];
+            if let Some(cursor) = cursor.as_deref() {
+                query.push(("cursor", cursor));
+            }
+
+            let response = self
+                .client
+                .get(format!("{SLACK_API_URL}/conversations.history"))
+                .bearer_auth(&self.bot_token)
+                .query(&query)
+                .send()
+                .await
+                .map_err(|e| Error::Channel(format!("Slack history request failed: {e}")))?;
+
+            let result: SlackResponse<HistoryResponse> = response
+                .json()
+                .await
+                .map_err(|e| Error::Channel(format!("Slack history parse error: {e}")))?;
+
+            if !result.ok {
+                return Err(Error::Channel(format!(
+                    "Slack history failed: {}",
+                    result.error.unwrap_or_default()
+                )));
+            }
+
+            let Some(page) = result.data else { break };
+            messages.extend(page.messages);
+            cursor = page
+                .response_metadata
+                .and_then(|m| m.next_cursor)
+                .filter(|c| !c.is_empty());
+            if cursor.is_none() {
+                break;
+            }
+        }
+        messages.truncate(limit);
+
+        tracing::debug!(channel = %channel_id, count = messages.len(), "Slack history fetched");
+        Ok(messages
+            .into_iter()
+            .rev()
+            .map(|msg| {
+                let sender = msg.user.unwrap_or_default();
+                IncomingMessage {
+                    id: msg.ts,
+                    channel_id: channel_id.to_string(),
+                    sender_id: sender.clone(),
+                    sender_name: sender,
+                    content: msg.text.unwrap_or_default(),
+                    is_dm: channel_id.starts_with('D'),
+                    reply_to: msg.thread_ts.clone(),
+                    attachments: file_attachments(msg.files.as_deref()),
+                    thread_id: msg.thread_ts,
+                    callback_data: None,
+                    request_id: crate::request_id::current_or_new(),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Leading part of a streaming message that fits in one Slack message
+fn first_chunk(text: &str) -> String {
+    chunk_text(text, SLACK_MESSAGE_LIMIT, ChunkStrategy::Paragraph)
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+}
+
+/// Convert Slack file metadata into downloadable attachments
+fn file_attachments(files: Option<&[SlackFile]>) -> Vec<Attachment> {
+    files
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|f| {
+            let mime_type = f
+                .mimetype
+                .clone()
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+            let url = f
+                .url_private_download
+                .clone()
+                .or_else(|| f.url_private.clone())?;
+            Some(Attachment::from_url(url, mime_type, f.name.clone()))
+        })
+        .collect()
 }
 
 /// Slack event from Events API
@@ -0,0 +1,335 @@
+//! Per-channel streaming and typing cadence
+//!
+//! Fast channels want frequent partial updates; chat platforms with strict
+//! edit rate limits want fewer. Each channel gets its own edit
+//! interval, typing heartbeat interval, and initial-response behavior.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio_util::sync::CancellationToken;
+
+use super::Channel;
+
+/// Default minimum interval between streaming edits (1000ms)
+pub const DEFAULT_STREAM_INTERVAL_MS: u64 = 1000;
+
+/// Default interval between repeated typing indicators (4000ms)
+pub const DEFAULT_TYPING_INTERVAL_MS: u64 = 4000;
+
+/// Streaming behavior for a single channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamingConfig {
+    /// Minimum interval between streaming edits of the same chat
+    pub edit_interval_ms: u64,
+    /// Interval between repeated typing indicators while processing (0 = send once)
+    pub typing_interval_ms: u64,
+    /// Send a placeholder message immediately; otherwise wait for the first text
+    pub placeholder: bool,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            edit_interval_ms: DEFAULT_STREAM_INTERVAL_MS,
+            typing_interval_ms: DEFAULT_TYPING_INTERVAL_MS,
+            placeholder: true,
+        }
+    }
+}
+
+impl StreamingConfig {
+    /// Built-in defaults for a channel
+    ///
+    /// Telegram and Discord refresh typing just before the platform indicator
+    /// expires (~5s and ~10s).
+    #[must_use]
+    pub fn for_channel(channel: &str) -> Self {
+        match channel {
+            "discord" => Self {
+                edit_interval_ms: 1500,
+                typing_interval_ms: 8000,
+                placeholder: true,
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// Minimum interval between streaming edits
+    #[must_use]
+    pub const fn edit_interval(&self) -> Duration {
+        Duration::from_millis(self.edit_interval_ms)
+    }
+
+    /// Typing heartbeat interval, or `None` to send the indicator once
+    #[must_use]
+    pub const fn typing_interval(&self) -> Option<Duration> {
+        if self.typing_interval_ms == 0 {
+            None
+        } else {
+            Some(Duration::from_millis(self.typing_interval_ms))
+        }
+    }
+}
+
+/// Streaming configuration for all channels, with per-channel overrides
+#[derive(Debug, Clone, Default)]
+pub struct StreamingSettings {
+    overrides: HashMap<String, StreamingConfig>,
+}
+
+impl StreamingSettings {
+    /// Override the configuration for a channel
+    pub fn insert(&mut self, channel: impl Into<String>, config: StreamingConfig) {
+        self.overrides.insert(channel.into(), config);
+    }
+
+    /// Resolve the configuration for a channel (override or built-in default)
+    #[must_use]
+    pub fn for_channel(&self, channel: &str) -> StreamingConfig {
+        self.overrides
+            .get(channel)
+            .copied()
+            .unwrap_or_else(|| StreamingConfig::for_channel(channel))
+    }
+}
+
+/// Coalesces streaming edits so each message is edited at most once per interval
+///
+/// Streaming updates carry the full text so far, so an update that arrives
+/// too soon can be skipped: the next due update includes its deltas. The
+/// final edit is not throttled; adapters issue it unconditionally at the end
+/// of the stream and call [`Self::finish`].
+#[derive(Debug)]
+pub struct StreamingEditCoalescer {
+    interval: Duration,
+    messages: Mutex<HashMap<String, StreamedMessage>>,
+}
+
+/// Edit state of one streaming message
+#[derive(Debug)]
+struct StreamedMessage {
+    last_edit: Instant,
+    text: String,
+}
+
+impl StreamingEditCoalescer {
+    /// Create a coalescer with the given minimum interval between edits
+    #[must_use]
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            messages: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a coalescer using a channel's configured streaming edit interval
+    #[must_use]
+    pub fn from_config(config: &StreamingConfig) -> Self {
+        Self::new(config.edit_interval())
+    }
+
+    /// Record that `message_id` was just sent with `text`
+    pub fn start(&self, message_id: &str, text: &str) {
+        self.lock().insert(
+            message_id.to_string(),
+            StreamedMessage {
+                last_edit: Instant::now(),
+                text: text.to_string(),
+            },
+        );
+    }
+
+    /// Text to edit `message_id` to now, or `None` to skip this update
+    ///
+    /// Updates are skipped within the interval of the previous edit and when
+    /// the text hasn't changed.
+    #[must_use]
+    pub fn update(&self, message_id: &str, text: &str) -> Option<String> {
+        let mut messages = self.lock();
+        let now = Instant::now();
+        if let Some(message) = messages.get(message_id)
+            && (message.text == text || now.duration_since(message.last_edit) < self.interval)
+        {
+            return None;
+        }
+
+        messages.insert(
+            message_id.to_string(),
+            StreamedMessage {
+                last_edit: now,
+                text: text.to_string(),
+            },
+        );
+        Some(text.to_string())
+    }
+
+    /// Forget `message_id` once its stream has ended
+    pub fn finish(&self, message_id: &str) {
+        self.lock().remove(message_id);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, StreamedMessage>> {
+        self.messages
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Keeps the typing indicator alive while a response is generated
+///
+/// Platforms expire a typing indicator after a few seconds, so a long
+/// tool-heavy turn would otherwise look idle. The heartbeat ends when the
+/// work completes, when the [`Self::run`] future is dropped, or when
+/// [`Self::stop`] is called, e.g. once the response starts streaming and the
+/// text itself shows progress.
+#[derive(Debug, Clone)]
+pub struct TypingKeepalive {
+    interval: Option<Duration>,
+    stop: CancellationToken,
+}
+
+impl TypingKeepalive {
+    /// Create a keepalive re-sending the indicator every `interval`
+    ///
+    /// With no interval the indicator is not repeated.
+    #[must_use]
+    pub fn new(interval: Option<Duration>) -> Self {
+        Self {
+            interval,
+            stop: CancellationToken::new(),
+        }
+    }
+
+    /// Stop re-sending the indicator; the work keeps running
+    pub fn stop(&self) {
+        self.stop.cancel();
+    }
+
+    /// Drive `work` to completion, re-sending the typing indicator until stopped
+    ///
+    /// The caller is expected to have sent the first indicator already.
+    pub async fn run<C, F>(&self, channel: &C, channel_id: &str, work: F) -> F::Output
+    where
+        C: Channel + ?Sized,
+        F: Future,
+    {
+        let Some(interval) = self.interval else {
+            return work.await;
+        };
+
+        tokio::pin!(work);
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                biased;
+                () = self.stop.cancelled() => return work.await,
+                output = &mut work => return output,
+                _ = ticker.tick() => {
+                    if let Err(e) = channel.send_typing(channel_id).await {
+                        tracing::debug!(error = %e, "typing heartbeat failed");
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discord_edits_less_often_than_default() {
+        let discord = StreamingConfig::for_channel("discord");
+        let telegram = StreamingConfig::for_channel("telegram");
+        assert!(discord.edit_interval() > telegram.edit_interval());
+        assert_eq!(telegram, StreamingConfig::default());
+        assert!(discord.placeholder);
+    }
+
+    #[test]
+    fn coalescer_skips_edits_within_interval() {
+        let edits = StreamingEditCoalescer::new(Duration::from_secs(60));
+        edits.start("m1", "Hel");
+
+        assert_eq!(edits.update("m1", "Hello"), None);
+        // Messages are throttled independently
+        assert_eq!(edits.update("m2", "Hi"), Some("Hi".to_string()));
+
+        let edits = StreamingEditCoalescer::new(Duration::ZERO);
+        edits.start("m1", "Hel");
+        assert_eq!(edits.update("m1", "Hello"), Some("Hello".to_string()));
+        assert_eq!(edits.update("m1", "Hello"), None);
+
+        edits.finish("m1");
+        assert_eq!(edits.update("m1", "Hello"), Some("Hello".to_string()));
+    }
+
+    #[tokio::test]
+    async fn typing_keepalive_stops_on_request() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Counting(AtomicUsize);
+
+        #[async_trait::async_trait]
+        impl Channel for Counting {
+            fn name(&self) -> &'static str {
+                "counting"
+            }
+            async fn connect(&mut self) -> crate::Result<()> {
+                Ok(())
+            }
+            async fn disconnect(&mut self) -> crate::Result<()> {
+                Ok(())
+            }
+            async fn send(&self, _message: crate::channels::OutgoingMessage) -> crate::Result<()> {
+                Ok(())
+            }
+            fn is_connected(&self) -> bool {
+                true
+            }
+            async fn send_typing(&self, _channel_id: &str) -> crate::Result<()> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        let channel = Counting(AtomicUsize::new(0));
+        let typing = TypingKeepalive::new(Some(Duration::from_millis(10)));
+        let at_stop = typing
+            .run(&channel, "c1", async {
+                // Heartbeats while "thinking", then streaming starts
+                tokio::time::sleep(Duration::from_millis(35)).await;
+                typing.stop();
+                let at_stop = channel.0.load(Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                at_stop
+            })
+            .await;
+
+        assert!(at_stop >= 1);
+        assert_eq!(channel.0.load(Ordering::SeqCst), at_stop);
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        let mut settings = StreamingSettings::default();
+        let custom = StreamingConfig {
+            edit_interval_ms: 300,
+            typing_interval_ms: 0,
+            placeholder: false,
+        };
+        settings.insert("telegram", custom);
+
+        assert_eq!(settings.for_channel("telegram"), custom);
+        assert_eq!(
+            settings.for_channel("discord"),
+            StreamingConfig::for_channel("discord")
+        );
+    }
+}
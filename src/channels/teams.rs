@@ -372,6 +372,7 @@ impl TeamsChannel {
             attachments,
             thread_id: None,
             callback_data: None,
+            request_id: crate::request_id::current_or_new(),
         };
 
         if let Some(tx) = &self.message_tx {
@@ -23,6 +23,25 @@ impl super::TelegramChannel {
         chat_id: i64,
         text: &str,
         reply_to: Option<i64>,
+    ) -> Result<()> {
+        self.send_message_to_thread(chat_id, text, reply_to, None)
+            .await
+    }
+
+    /// Send a message to a chat, inside a forum topic when `thread_id` is set
+    ///
+    /// Uses HTML parse mode with plain-text fallback; both attempts target
+    /// the same topic.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the API request fails
+    pub async fn send_message_to_thread(
+        &self,
+        chat_id: i64,
+        text: &str,
+        reply_to: Option<i64>,
+        thread_id: Option<i64>,
     ) -> Result<()> {
         let url = format!("{API_BASE}{}/sendMessage", self.token);
 
@@ -32,7 +51,7 @@ impl super::TelegramChannel {
             text: html_text,
             parse_mode: Some("HTML".to_string()),
             reply_to_message_id: reply_to,
-            message_thread_id: None,
+            message_thread_id: thread_id,
             disable_web_page_preview: None,
             disable_notification: None,
             reply_markup: None,
@@ -66,7 +85,7 @@ impl super::TelegramChannel {
                 text: text.to_string(),
                 parse_mode: None,
                 reply_to_message_id: reply_to,
-                message_thread_id: None,
+                message_thread_id: thread_id,
                 disable_web_page_preview: None,
                 disable_notification: None,
                 reply_markup: None,
@@ -710,6 +729,79 @@ impl super::TelegramChannel {
         Ok(())
     }
 
+    /// Upload an image (as a photo) or any other file (as a document)
+    ///
+    /// Inline data is uploaded as multipart; URL attachments are fetched by
+    /// Telegram. The caption is sent as HTML.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the attachment is empty or the API request fails
+    pub async fn send_attachment(
+        &self,
+        chat_id: i64,
+        attachment: &crate::channels::Attachment,
+        caption: Option<&str>,
+        reply_to: Option<i64>,
+        thread_id: Option<i64>,
+    ) -> Result<()> {
+        let (method, field) = match attachment.kind {
+            crate::channels::AttachmentKind::Image => ("sendPhoto", "photo"),
+            _ => ("sendDocument", "document"),
+        };
+        let url = format!("{API_BASE}{}/{method}", self.token);
+
+        let mut form = reqwest::multipart::Form::new().text("chat_id", chat_id.to_string());
+        if let Some(caption) = caption.filter(|c| !c.trim().is_empty()) {
+            form = form
+                .text("caption", markdown_to_telegram_html(caption))
+                .text("parse_mode", "HTML");
+        }
+        if let Some(reply_to) = reply_to {
+            form = form.text("reply_to_message_id", reply_to.to_string());
+        }
+        if let Some(thread_id) = thread_id {
+            form = form.text("message_thread_id", thread_id.to_string());
+        }
+        form = match (&attachment.data, &attachment.url) {
+            (Some(data), _) => {
+                let part = reqwest::multipart::Part::bytes(data.clone())
+                    .file_name(
+                        attachment
+                            .filename
+                            .clone()
+                            .unwrap_or_else(|| field.to_string()),
+                    )
+                    .mime_str(&attachment.mime_type)
+                    .map_err(|e| Error::Channel(format!("Telegram {method} error: {e}")))?;
+                form.part(field, part)
+            }
+            (None, Some(file_url)) => form.text(field, file_url.clone()),
+            (None, None) => {
+                return Err(Error::Channel("attachment has no data or URL".to_string()));
+            }
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Telegram {method} error: {e}")))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Channel(format!(
+                "Telegram {method} error: {status} - {body}"
+            )));
+        }
+
+        tracing::debug!(chat_id, method, "Telegram attachment sent");
+        Ok(())
+    }
+
     /// Send a circular video note
     ///
     /// # Errors
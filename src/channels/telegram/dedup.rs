@@ -1,7 +1,14 @@
 //! Telegram update deduplication cache
+//!
+//! Optionally persisted to disk together with the polling offset, so a
+//! restart neither re-fetches nor reprocesses updates that were already
+//! handled.
 
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
 
 /// Default dedup TTL (5 minutes)
 const DEDUP_TTL_SECS: u64 = 300;
@@ -18,6 +25,19 @@ pub struct UpdateDedup {
     cache: HashMap<String, Instant>,
     ttl: Duration,
     max_entries: usize,
+    /// Next `getUpdates` offset (polling mode)
+    offset: Option<i64>,
+    /// File the cache is persisted to
+    path: Option<PathBuf>,
+}
+
+/// On-disk form of the cache
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DedupState {
+    offset: Option<i64>,
+    /// Keys with the unix time they were first seen
+    #[serde(default)]
+    seen: HashMap<String, u64>,
 }
 
 impl Default for UpdateDedup {
@@ -26,11 +46,26 @@ impl Default for UpdateDedup {
             cache: HashMap::new(),
             ttl: Duration::from_secs(DEDUP_TTL_SECS),
             max_entries: DEDUP_MAX_ENTRIES,
+            offset: None,
+            path: None,
         }
     }
 }
 
 impl UpdateDedup {
+    /// Create a cache persisted at `path`, restoring any saved state
+    ///
+    /// A missing or unreadable file starts an empty cache.
+    #[must_use]
+    pub fn persistent(path: PathBuf) -> Self {
+        let mut dedup = Self::default();
+        if let Some(state) = load_state(&path) {
+            dedup.restore(state);
+        }
+        dedup.path = Some(path);
+        dedup
+    }
+
     /// Check if the given key has been seen recently.
     ///
     /// Returns `true` if this is a duplicate (already seen within TTL).
@@ -64,4 +99,147 @@ impl UpdateDedup {
         self.cache.insert(key.to_string(), now);
         false
     }
+
+    /// Next `getUpdates` offset, if one was recorded
+    #[must_use]
+    pub const fn offset(&self) -> Option<i64> {
+        self.offset
+    }
+
+    /// Record the next `getUpdates` offset
+    pub const fn set_offset(&mut self, offset: i64) {
+        self.offset = Some(offset);
+    }
+
+    /// Write unexpired entries and the offset to disk (no-op when not persistent)
+    pub fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let now = Instant::now();
+        let unix_now = unix_secs(SystemTime::now());
+        let state = DedupState {
+            offset: self.offset,
+            seen: self
+                .cache
+                .iter()
+                .filter(|(_, ts)| now.duration_since(**ts) < self.ttl)
+                .map(|(key, ts)| {
+                    let age = now.duration_since(*ts).as_secs();
+                    (key.clone(), unix_now.saturating_sub(age))
+                })
+                .collect(),
+        };
+
+        if let Err(e) = write_state(path, &state) {
+            tracing::warn!(path = %path.display(), error = %e, "failed to persist Telegram dedup state");
+        }
+    }
+
+    /// Load saved entries that are still within the TTL
+    fn restore(&mut self, state: DedupState) {
+        let now = Instant::now();
+        let unix_now = unix_secs(SystemTime::now());
+        let ttl = self.ttl.as_secs();
+
+        let mut seen: Vec<(String, u64)> = state
+            .seen
+            .into_iter()
+            .map(|(key, seen_at)| (key, unix_now.saturating_sub(seen_at)))
+            .filter(|(_, age)| *age < ttl)
+            .collect();
+        // Keep the most recent entries when the file exceeds the cap
+        seen.sort_by_key(|(_, age)| *age);
+        seen.truncate(self.max_entries);
+
+        self.cache = seen
+            .into_iter()
+            .filter_map(|(key, age)| {
+                now.checked_sub(Duration::from_secs(age))
+                    .map(|ts| (key, ts))
+            })
+            .collect();
+        self.offset = state.offset;
+    }
+}
+
+fn load_state(path: &Path) -> Option<DedupState> {
+    let data = std::fs::read(path).ok()?;
+    serde_json::from_slice(&data)
+        .map_err(|e| {
+            tracing::warn!(path = %path.display(), error = %e, "ignoring corrupt Telegram dedup state");
+        })
+        .ok()
+}
+
+/// Write via a temp file so a crash never leaves a truncated state file
+fn write_state(path: &Path, state: &DedupState) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, serde_json::to_vec(state)?)?;
+    std::fs::rename(tmp, path)
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_duplicates() {
+        let mut dedup = UpdateDedup::default();
+        assert!(!dedup.is_duplicate("poll:1"));
+        assert!(dedup.is_duplicate("poll:1"));
+        assert!(!dedup.is_duplicate("poll:2"));
+    }
+
+    #[test]
+    fn persists_keys_and_offset_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telegram-dedup.json");
+
+        let mut dedup = UpdateDedup::persistent(path.clone());
+        assert_eq!(dedup.offset(), None);
+        assert!(!dedup.is_duplicate("poll:41"));
+        dedup.set_offset(42);
+        dedup.save();
+
+        let mut restored = UpdateDedup::persistent(path);
+        assert_eq!(restored.offset(), Some(42));
+        assert!(restored.is_duplicate("poll:41"));
+        assert!(!restored.is_duplicate("poll:42"));
+    }
+
+    #[test]
+    fn restore_drops_expired_entries() {
+        let mut dedup = UpdateDedup::default();
+        let now = unix_secs(SystemTime::now());
+        dedup.restore(DedupState {
+            offset: Some(7),
+            seen: HashMap::from([
+                ("fresh".to_string(), now),
+                ("stale".to_string(), now - DEDUP_TTL_SECS - 1),
+            ]),
+        });
+        assert_eq!(dedup.offset(), Some(7));
+        assert!(dedup.is_duplicate("fresh"));
+        assert!(!dedup.is_duplicate("stale"));
+    }
+
+    #[test]
+    fn corrupt_state_starts_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telegram-dedup.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        let mut dedup = UpdateDedup::persistent(path);
+        assert_eq!(dedup.offset(), None);
+        assert!(!dedup.is_duplicate("poll:1"));
+    }
 }
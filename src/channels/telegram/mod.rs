@@ -3,7 +3,6 @@
 //! Uses webhooks for receiving messages and Bot API for sending
 
 mod api;
-pub mod chunking;
 pub mod dedup;
 pub mod html;
 pub mod polling;
@@ -12,13 +11,12 @@ pub mod retry;
 pub mod types;
 
 use std::collections::HashMap;
-use std::time::Duration;
 
 use async_trait::async_trait;
 use reqwest::Client;
 use tokio::sync::mpsc;
 
-use super::{Channel, ChannelCapability, IncomingMessage, OutgoingMessage};
+use super::{Channel, ChannelCapability, IncomingMessage, OutgoingMessage, StreamingConfig};
 use crate::{Error, Result};
 
 pub use dedup::UpdateDedup;
@@ -26,8 +24,8 @@ pub use polling::extract_update_media_refs;
 pub use rate_limiter::TelegramRateLimiter;
 pub use types::{BotCommand, MediaFileRef};
 
-/// Default streaming edit interval (1000ms)
-const DEFAULT_STREAM_INTERVAL_MS: u64 = 1000;
+/// Maximum caption length for photos and documents
+const MAX_CAPTION_CHARS: usize = 1024;
 
 /// Telegram channel adapter
 #[derive(Clone)]
@@ -38,6 +36,10 @@ pub struct TelegramChannel {
     connected: bool,
     /// Rate limiter for streaming edit operations
     rate_limiter: TelegramRateLimiter,
+    /// Streaming edit/typing cadence
+    streaming: StreamingConfig,
+    /// File persisting the polling offset and dedup cache
+    polling_state_path: Option<std::path::PathBuf>,
 }
 
 impl TelegramChannel {
@@ -49,9 +51,9 @@ impl TelegramChannel {
             client: Client::new(),
             message_tx: None,
             connected: false,
-            rate_limiter: TelegramRateLimiter::new(Duration::from_millis(
-                DEFAULT_STREAM_INTERVAL_MS,
-            )),
+            rate_limiter: TelegramRateLimiter::from_config(&StreamingConfig::default()),
+            streaming: StreamingConfig::default(),
+            polling_state_path: None,
         }
     }
 
@@ -66,12 +68,35 @@ impl TelegramChannel {
             client: Client::new(),
             message_tx: Some(tx),
             connected: false,
-            rate_limiter: TelegramRateLimiter::new(Duration::from_millis(
-                DEFAULT_STREAM_INTERVAL_MS,
-            )),
+            rate_limiter: TelegramRateLimiter::from_config(&StreamingConfig::default()),
+            streaming: StreamingConfig::default(),
+            polling_state_path: None,
         };
         (channel, rx)
     }
+
+    /// Apply streaming cadence (edit interval, typing heartbeat, placeholder)
+    #[must_use]
+    pub fn with_streaming(mut self, config: StreamingConfig) -> Self {
+        self.rate_limiter = TelegramRateLimiter::from_config(&config);
+        self.streaming = config;
+        self
+    }
+
+    /// Persist the polling offset and dedup cache at `path`
+    ///
+    /// Lets polling resume after a restart without reprocessing updates.
+    #[must_use]
+    pub fn with_polling_state(mut self, path: std::path::PathBuf) -> Self {
+        self.polling_state_path = Some(path);
+        self
+    }
+
+    /// Streaming cadence applied to this channel
+    #[must_use]
+    pub const fn streaming(&self) -> StreamingConfig {
+        self.streaming
+    }
 }
 
 #[async_trait]
@@ -88,6 +113,7 @@ impl Channel for TelegramChannel {
             ChannelCapability::Reactions,
             ChannelCapability::ForumTopics,
             ChannelCapability::Stickers,
+            ChannelCapability::MediaSend,
         ]
     }
 
@@ -111,6 +137,7 @@ impl Channel for TelegramChannel {
             .map_err(|_| Error::Channel("Invalid chat ID".to_string()))?;
 
         let reply_to = message.reply_to.as_ref().and_then(|id| id.parse().ok());
+        let thread_id = message.thread_id.as_ref().and_then(|id| id.parse().ok());
 
         // If edit_target is set, edit instead of sending new
         if let Some(ref target) = message.edit_target
@@ -121,7 +148,35 @@ impl Channel for TelegramChannel {
                 .await;
         }
 
-        self.send_message(chat_id, &message.content, reply_to).await
+        self.send_message_to_thread(chat_id, &message.content, reply_to, thread_id)
+            .await
+    }
+
+    async fn send_media(&self, message: OutgoingMessage) -> Result<()> {
+        if message.attachments.is_empty() {
+            return self.send(message).await;
+        }
+
+        let chat_id: i64 = message
+            .channel_id
+            .parse()
+            .map_err(|_| Error::Channel("Invalid chat ID".to_string()))?;
+        let reply_to = message.reply_to.as_ref().and_then(|id| id.parse().ok());
+        let thread_id = message.thread_id.as_ref().and_then(|id| id.parse().ok());
+
+        // Captions are capped; longer text goes out as its own message first
+        let mut caption = Some(message.content.as_str()).filter(|c| !c.trim().is_empty());
+        if caption.is_some_and(|c| c.chars().count() > MAX_CAPTION_CHARS) {
+            self.send_message_to_thread(chat_id, &message.content, reply_to, thread_id)
+                .await?;
+            caption = None;
+        }
+
+        for attachment in &message.attachments {
+            self.send_attachment(chat_id, attachment, caption.take(), reply_to, thread_id)
+                .await?;
+        }
+        Ok(())
     }
 
     fn is_connected(&self) -> bool {
@@ -96,7 +96,8 @@ impl super::TelegramChannel {
     /// Spawn a background task that polls Telegram's getUpdates API
     ///
     /// Polls every `interval` and forwards received messages into the mpsc channel.
-    /// Deletes any existing webhook before starting to avoid conflicts.
+    /// Deletes any existing webhook before starting to avoid conflicts. Resumes
+    /// from the offset saved by [`Self::with_polling_state`] when configured.
     ///
     /// # Panics
     ///
@@ -105,13 +106,17 @@ impl super::TelegramChannel {
     pub fn start_polling(&self, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
         let token = self.token.clone();
         let client = self.client.clone();
+        let dedup = self
+            .polling_state_path
+            .clone()
+            .map_or_else(UpdateDedup::default, UpdateDedup::persistent);
         let tx = self
             .message_tx
             .clone()
             .expect("start_polling requires a message_tx (use with_receiver)");
 
         tokio::spawn(async move {
-            polling_loop(token, client, tx, interval).await;
+            polling_loop(token, client, tx, interval, dedup).await;
         })
     }
 }
@@ -122,6 +127,7 @@ async fn polling_loop(
     client: reqwest::Client,
     tx: mpsc::Sender<IncomingMessage>,
     interval: std::time::Duration,
+    mut dedup: UpdateDedup,
 ) {
     // Delete any existing webhook so getUpdates works
     let delete_url = format!("{API_BASE}{token}/deleteWebhook");
@@ -129,8 +135,10 @@ async fn polling_loop(
         tracing::warn!(error = %e, "failed to delete Telegram webhook before polling");
     }
 
-    let mut offset: Option<i64> = None;
-    let mut dedup = UpdateDedup::default();
+    let mut offset = dedup.offset();
+    if let Some(off) = offset {
+        tracing::info!(offset = off, "resuming Telegram polling from saved offset");
+    }
 
     loop {
         let url = format!("{API_BASE}{token}/getUpdates");
@@ -163,6 +171,13 @@ async fn polling_loop(
                             tracing::warn!(error = %e, "failed to forward Telegram message");
                         }
                     }
+
+                    if let Some(off) = offset
+                        && !updates.result.is_empty()
+                    {
+                        dedup.set_offset(off);
+                        dedup.save();
+                    }
                 }
             }
             Err(e) => {
@@ -310,6 +325,7 @@ fn update_to_incoming(update: &PollingUpdate) -> Option<IncomingMessage> {
         attachments: vec![],
         thread_id: msg.message_thread_id.map(|id| id.to_string()),
         callback_data: None,
+        request_id: crate::request_id::current_or_new(),
     })
 }
 
@@ -0,0 +1,365 @@
+//! Generic webhook channel adapter
+//!
+//! Replies are POSTed as JSON to a configured URL; messages arrive through
+//! `POST /api/webhooks/generic`, which is only served when a secret is
+//! configured. Signed requests carry an `X-Beacon-Timestamp` header (unix
+//! seconds) and an `X-Beacon-Signature` header with the hex HMAC-SHA256 of
+//! `<timestamp>.<body>`; inbound requests outside [`MAX_TIMESTAMP_SKEW`] are
+//! rejected so captured requests can't be replayed.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use super::{Channel, IncomingMessage, OutgoingMessage};
+use crate::security::hmac;
+use crate::{Error, Result};
+
+/// Header carrying the body signature (`sha256=<hex>`)
+pub const SIGNATURE_HEADER: &str = "X-Beacon-Signature";
+
+/// Header carrying the signing time in unix seconds
+pub const TIMESTAMP_HEADER: &str = "X-Beacon-Timestamp";
+
+/// How far an inbound timestamp may be from the local clock
+pub const MAX_TIMESTAMP_SKEW: Duration = Duration::from_secs(300);
+
+/// Generic webhook channel adapter
+pub struct WebhookChannel {
+    url: String,
+    secret: Option<String>,
+    client: reqwest::Client,
+    message_tx: Option<mpsc::Sender<IncomingMessage>>,
+    connected: bool,
+}
+
+/// Body POSTed to the configured URL for each reply
+#[derive(Debug, Serialize)]
+struct OutgoingPayload<'a> {
+    channel_id: &'a str,
+    content: &'a str,
+    reply_to: Option<&'a str>,
+}
+
+/// Body accepted by `POST /api/webhooks/generic`
+#[derive(Debug, Deserialize)]
+pub struct InboundPayload {
+    /// Message ID (generated when absent)
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Conversation identifier, echoed back in replies
+    pub channel_id: String,
+    /// Sender identifier
+    pub sender_id: String,
+    /// Sender display name (defaults to `sender_id`)
+    #[serde(default)]
+    pub sender_name: Option<String>,
+    /// Message text
+    pub content: String,
+    /// Message this is replying to
+    #[serde(default)]
+    pub reply_to: Option<String>,
+    /// Thread identifier
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// Whether this is a direct conversation
+    #[serde(default)]
+    pub is_dm: bool,
+}
+
+impl InboundPayload {
+    /// Convert into an `IncomingMessage` for the processing pipeline
+    #[must_use]
+    pub fn into_incoming(self) -> IncomingMessage {
+        IncomingMessage {
+            id: self.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            sender_name: self.sender_name.unwrap_or_else(|| self.sender_id.clone()),
+            channel_id: self.channel_id,
+            sender_id: self.sender_id,
+            content: self.content,
+            is_dm: self.is_dm,
+            reply_to: self.reply_to,
+            attachments: Vec::new(),
+            thread_id: self.thread_id,
+            callback_data: None,
+            request_id: crate::request_id::current_or_new(),
+        }
+    }
+}
+
+/// Bytes covered by the signature: `<timestamp>.<body>`
+fn signed_payload(timestamp: &str, body: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(timestamp.len() + 1 + body.len());
+    payload.extend_from_slice(timestamp.as_bytes());
+    payload.push(b'.');
+    payload.extend_from_slice(body);
+    payload
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Check a `<timestamp>.<body>` signature made with `secret`, rejecting
+/// timestamps outside [`MAX_TIMESTAMP_SKEW`]
+///
+/// Shared by every inbound endpoint that uses this signing scheme
+#[must_use]
+pub fn verify_signed(
+    secret: &str,
+    signature: Option<&str>,
+    timestamp: Option<&str>,
+    body: &[u8],
+) -> bool {
+    verify_signed_at(secret, signature, timestamp, body, unix_now())
+}
+
+fn verify_signed_at(
+    secret: &str,
+    signature: Option<&str>,
+    timestamp: Option<&str>,
+    body: &[u8],
+    now: u64,
+) -> bool {
+    let (Some(signature), Some(timestamp)) = (signature, timestamp) else {
+        return false;
+    };
+    let Ok(sent_at) = timestamp.parse::<u64>() else {
+        return false;
+    };
+    if now.abs_diff(sent_at) > MAX_TIMESTAMP_SKEW.as_secs() {
+        return false;
+    }
+    hmac::verify(secret, signature, &signed_payload(timestamp, body))
+}
+
+/// Inbound side of the webhook channel, held by the API server
+#[derive(Clone)]
+pub struct WebhookInbound {
+    message_tx: mpsc::Sender<IncomingMessage>,
+    secret: String,
+}
+
+impl WebhookInbound {
+    /// Check the request timestamp and signature
+    #[must_use]
+    pub fn verify(&self, signature: Option<&str>, timestamp: Option<&str>, body: &[u8]) -> bool {
+        verify_signed(&self.secret, signature, timestamp, body)
+    }
+
+    #[cfg(test)]
+    fn verify_at(
+        &self,
+        signature: Option<&str>,
+        timestamp: Option<&str>,
+        body: &[u8],
+        now: u64,
+    ) -> bool {
+        verify_signed_at(&self.secret, signature, timestamp, body, now)
+    }
+
+    /// Push a message into the processing pipeline
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the channel handler has stopped
+    pub async fn deliver(&self, message: IncomingMessage) -> Result<()> {
+        self.message_tx
+            .send(message)
+            .await
+            .map_err(|e| Error::Channel(format!("Failed to forward webhook message: {e}")))
+    }
+}
+
+impl WebhookChannel {
+    /// Create a send-only webhook adapter
+    #[must_use]
+    pub fn new(url: String, secret: Option<String>) -> Self {
+        Self {
+            url,
+            secret,
+            client: reqwest::Client::new(),
+            message_tx: None,
+            connected: false,
+        }
+    }
+
+    /// Create with a message receiver
+    ///
+    /// Returns the channel and a receiver for messages posted to the inbound
+    /// endpoint; pass [`WebhookChannel::inbound`] to the API server.
+    #[must_use]
+    pub fn with_receiver(
+        url: String,
+        secret: Option<String>,
+    ) -> (Self, mpsc::Receiver<IncomingMessage>) {
+        let (tx, rx) = mpsc::channel(100);
+        let channel = Self {
+            message_tx: Some(tx),
+            ..Self::new(url, secret)
+        };
+        (channel, rx)
+    }
+
+    /// Inbound handle for the API server
+    ///
+    /// Only channels with a receiver and a secret accept inbound messages;
+    /// without a secret anyone could post as any sender.
+    #[must_use]
+    pub fn inbound(&self) -> Option<WebhookInbound> {
+        Some(WebhookInbound {
+            message_tx: self.message_tx.clone()?,
+            secret: self.secret.clone()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Channel for WebhookChannel {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        self.connected = true;
+        tracing::info!(url = %self.url, "webhook channel connected");
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.connected = false;
+        tracing::info!("webhook channel disconnected");
+        Ok(())
+    }
+
+    async fn send(&self, message: OutgoingMessage) -> Result<()> {
+        let body = serde_json::to_vec(&OutgoingPayload {
+            channel_id: &message.channel_id,
+            content: &message.content,
+            reply_to: message.reply_to.as_deref(),
+        })?;
+
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        if let Some(secret) = &self.secret {
+            let timestamp = unix_now().to_string();
+            request = request.header(TIMESTAMP_HEADER, &timestamp).header(
+                SIGNATURE_HEADER,
+                hmac::sign(secret, &signed_payload(&timestamp, &body)),
+            );
+        }
+
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| Error::Channel(format!("Webhook request failed: {e}")))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(Error::Channel(format!("Webhook error: {status} - {text}")));
+        }
+
+        tracing::debug!(channel = %message.channel_id, "webhook message sent");
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inbound_payload_defaults() {
+        let payload: InboundPayload =
+            serde_json::from_str(r#"{"channel_id": "c1", "sender_id": "u1", "content": "hello"}"#)
+                .unwrap();
+        let incoming = payload.into_incoming();
+        assert_eq!(incoming.sender_name, "u1");
+        assert_eq!(incoming.content, "hello");
+        assert!(!incoming.id.is_empty());
+        assert!(!incoming.is_dm);
+    }
+
+    #[test]
+    fn inbound_verification_requires_fresh_signature() {
+        let (signed, _rx) = WebhookChannel::with_receiver(
+            "http://localhost/hook".to_string(),
+            Some("s3cret".to_string()),
+        );
+        let inbound = signed.inbound().unwrap();
+        let body = br#"{"content":"hi"}"#;
+        let now = 1_700_000_000;
+        let ts = now.to_string();
+        let sig = hmac::sign("s3cret", &signed_payload(&ts, body));
+
+        assert!(inbound.verify_at(Some(&sig), Some(&ts), body, now));
+        assert!(!inbound.verify_at(Some(&sig), None, body, now));
+        assert!(!inbound.verify_at(None, Some(&ts), body, now));
+        let other = hmac::sign("other", &signed_payload(&ts, body));
+        assert!(!inbound.verify_at(Some(&other), Some(&ts), body, now));
+        // The timestamp is covered by the signature
+        let later = (now + 1).to_string();
+        assert!(!inbound.verify_at(Some(&sig), Some(&later), body, now + 1));
+        // Replays past the skew window are rejected
+        assert!(!inbound.verify_at(Some(&sig), Some(&ts), body, now + 301));
+    }
+
+    #[test]
+    fn inbound_requires_secret() {
+        let (open, _rx) = WebhookChannel::with_receiver("http://localhost/hook".to_string(), None);
+        assert!(open.inbound().is_none());
+        assert!(
+            WebhookChannel::new(
+                "http://localhost/hook".to_string(),
+                Some("s3cret".to_string())
+            )
+            .inbound()
+            .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn send_posts_signed_json() {
+        use axum::{Router, body::Bytes, http::HeaderMap, routing::post};
+
+        let app = Router::new().route(
+            "/hook",
+            post(|headers: HeaderMap, body: Bytes| async move {
+                let signature = headers[SIGNATURE_HEADER].to_str().unwrap();
+                let timestamp = headers[TIMESTAMP_HEADER].to_str().unwrap();
+                assert!(hmac::verify(
+                    "s3cret",
+                    signature,
+                    &signed_payload(timestamp, &body)
+                ));
+                let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                assert_eq!(json["channel_id"], "c1");
+                assert_eq!(json["content"], "pong");
+                assert_eq!(json["reply_to"], "m1");
+            }),
+        );
+        let base = crate::test_support::serve(app).await;
+
+        let channel = WebhookChannel::new(format!("{base}/hook"), Some("s3cret".to_string()));
+        channel
+            .send(OutgoingMessage::reply(
+                "c1".to_string(),
+                "pong".to_string(),
+                "m1".to_string(),
+            ))
+            .await
+            .unwrap();
+    }
+}
@@ -8,9 +8,13 @@ use reqwest::Client;
 use serde::Deserialize;
 use tokio::sync::mpsc;
 
+use super::chunking::{ChunkStrategy, chunk_text};
 use super::{Attachment, AttachmentKind, Channel, IncomingMessage, OutgoingMessage};
 use crate::{Error, Result};
 
+/// `WhatsApp`'s per-message text limit
+const WHATSAPP_MESSAGE_LIMIT: usize = 4096;
+
 /// `WhatsApp` channel adapter
 pub struct WhatsAppChannel {
     /// `WhatsApp` Business API access token
@@ -89,6 +93,7 @@ impl WhatsAppChannel {
                             attachments,
                             thread_id: None,
                             callback_data: None,
+                            request_id: crate::request_id::current_or_new(),
                         };
 
                         if let Some(tx) = &self.message_tx {
@@ -269,12 +274,21 @@ impl Channel for WhatsAppChannel {
     }
 
     async fn send(&self, message: OutgoingMessage) -> Result<()> {
-        self.send_text(
-            &message.channel_id,
+        // Only the first chunk quotes the message being replied to
+        let chunks = chunk_text(
             &message.content,
-            message.reply_to.as_deref(),
-        )
-        .await
+            WHATSAPP_MESSAGE_LIMIT,
+            ChunkStrategy::Paragraph,
+        );
+        for (i, chunk) in chunks.iter().enumerate() {
+            let reply_to = if i == 0 {
+                message.reply_to.as_deref()
+            } else {
+                None
+            };
+            self.send_text(&message.channel_id, chunk, reply_to).await?;
+        }
+        Ok(())
     }
 
     fn is_connected(&self) -> bool {
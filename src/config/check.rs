@@ -0,0 +1,294 @@
+//! Cross-field configuration checks
+//!
+//! Each field can be valid on its own while the combination can't work, e.g.
+//! a webhook-only channel with no public URL to receive webhooks on. These
+//! checks catch that before startup instead of at runtime.
+
+use super::{ApiKeys, ApiServerConfig, Config};
+use crate::relay::{RelayConfig, RelayMode, find_binary};
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// Configuration is usable
+    Pass,
+    /// Works, but probably not as intended
+    Warn,
+    /// Will fail at runtime
+    Fail,
+}
+
+impl CheckStatus {
+    /// Short label for reports
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "ok",
+            Self::Warn => "warn",
+            Self::Fail => "FAIL",
+        }
+    }
+}
+
+/// Result of one configuration check
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    /// What was checked (e.g. "cloud mode", "relay ssh_tunnel")
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Results of all configuration checks
+#[derive(Debug, Clone, Default)]
+pub struct ConfigReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl ConfigReport {
+    /// Number of failed checks
+    #[must_use]
+    pub fn failures(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Fail)
+            .count()
+    }
+}
+
+impl Config {
+    /// Check cross-field invariants
+    ///
+    /// Relay checks look up the mode binaries on `PATH`.
+    #[must_use]
+    pub fn check(&self) -> ConfigReport {
+        let mut checks = vec![check_cloud_mode(self)];
+        checks.extend(check_public_url(
+            &self.api_keys,
+            self.api_server.public_url.as_deref(),
+            &self.relay,
+        ));
+        checks.extend(check_relay(&self.relay, |name| find_binary(name).is_ok()));
+        checks.extend(check_vortex(&self.api_server));
+        ConfigReport { checks }
+    }
+}
+
+/// Cloud mode resolves each user's keys, which needs Gatekeeper or Synapse
+fn check_cloud_mode(config: &Config) -> CheckResult {
+    let has_synapse = config.synapse_api_url.is_some() && config.synapse_gateway_secret.is_some();
+    byok_check(
+        config.cloud_mode,
+        config.gatekeeper_url.is_some(),
+        has_synapse,
+    )
+}
+
+fn byok_check(cloud_mode: bool, has_gatekeeper: bool, has_synapse: bool) -> CheckResult {
+    const NAME: &str = "cloud mode";
+    if !cloud_mode {
+        CheckResult::new(NAME, CheckStatus::Pass, "disabled")
+    } else if has_gatekeeper || has_synapse {
+        CheckResult::new(NAME, CheckStatus::Pass, "BYOK key resolution configured")
+    } else {
+        CheckResult::new(
+            NAME,
+            CheckStatus::Fail,
+            "BEACON_CLOUD_MODE requires GATEKEEPER_URL, or SYNAPSE_API_URL with SYNAPSE_GATEWAY_SECRET",
+        )
+    }
+}
+
+/// Channels that only receive messages through webhooks need a public URL
+fn check_public_url(
+    keys: &ApiKeys,
+    public_url: Option<&str>,
+    relay: &RelayConfig,
+) -> Vec<CheckResult> {
+    let mut checks = Vec::new();
+
+    if let Some(url) = public_url
+        && !(url.starts_with("https://") || url.starts_with("http://"))
+    {
+        checks.push(CheckResult::new(
+            "public url",
+            CheckStatus::Fail,
+            format!("BEACON_PUBLIC_URL must be an http(s) URL, got `{url}`"),
+        ));
+    }
+
+    // A public relay publishes its URL at runtime
+    let public_relay = relay.enabled && relay.modes.iter().any(|m| m.is_public());
+    let reachable = public_url.is_some() || public_relay;
+
+    let webhook_channels = [
+        (
+            "whatsapp",
+            keys.whatsapp.is_some() && keys.whatsapp_phone_id.is_some(),
+        ),
+        ("teams", keys.teams_client_id.is_some()),
+        ("google_chat", keys.google_chat_service_account.is_some()),
+        ("webhook", keys.webhook_url.is_some()),
+    ];
+    for (channel, _) in webhook_channels.iter().filter(|(_, enabled)| *enabled) {
+        checks.push(if reachable {
+            CheckResult::new(*channel, CheckStatus::Pass, "webhooks reachable")
+        } else {
+            CheckResult::new(
+                *channel,
+                CheckStatus::Fail,
+                "receives messages by webhook; set BEACON_PUBLIC_URL or a public relay mode",
+            )
+        });
+    }
+
+    if keys.telegram.is_some() {
+        checks.push(if public_url.is_some() {
+            CheckResult::new("telegram", CheckStatus::Pass, "webhook mode")
+        } else {
+            CheckResult::new(
+                "telegram",
+                CheckStatus::Warn,
+                "no BEACON_PUBLIC_URL, falling back to polling",
+            )
+        });
+    }
+
+    checks
+}
+
+/// Vortex callbacks are refused unless signed or explicitly allowed unsigned
+fn check_vortex(server: &ApiServerConfig) -> Option<CheckResult> {
+    const NAME: &str = "vortex callbacks";
+    server.vortex_url.as_ref()?;
+    Some(if server.vortex_webhook_secret.is_some() {
+        CheckResult::new(NAME, CheckStatus::Pass, "signed with VORTEX_WEBHOOK_SECRET")
+    } else if server.vortex_allow_unsigned {
+        CheckResult::new(
+            NAME,
+            CheckStatus::Warn,
+            "unsigned callbacks accepted; anyone reaching the endpoint can trigger deliveries",
+        )
+    } else {
+        CheckResult::new(
+            NAME,
+            CheckStatus::Fail,
+            "callbacks will be rejected; set VORTEX_WEBHOOK_SECRET (or VORTEX_ALLOW_UNSIGNED_CALLBACKS=true)",
+        )
+    })
+}
+
+/// Every relay mode needs its binary, and SSH needs a host to tunnel to
+fn check_relay(relay: &RelayConfig, has_binary: impl Fn(&str) -> bool) -> Vec<CheckResult> {
+    if !relay.enabled {
+        return Vec::new();
+    }
+    if relay.modes.is_empty() {
+        return vec![CheckResult::new(
+            "relay",
+            CheckStatus::Warn,
+            "BEACON_RELAY_ENABLED is set but BEACON_RELAY_MODE names no known mode",
+        )];
+    }
+
+    relay
+        .modes
+        .iter()
+        .filter_map(|mode| {
+            let name = format!("relay {}", mode.name());
+            let binary = mode.binary()?;
+            Some(if !has_binary(binary) {
+                CheckResult::new(
+                    name,
+                    CheckStatus::Fail,
+                    format!("`{binary}` binary not found on PATH"),
+                )
+            } else if matches!(mode, RelayMode::SshTunnel { host, .. } if host.is_empty()) {
+                CheckResult::new(name, CheckStatus::Fail, "BEACON_SSH_HOST is not set")
+            } else {
+                CheckResult::new(name, CheckStatus::Pass, format!("using `{binary}`"))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cloud_mode_requires_byok() {
+        assert_eq!(byok_check(false, false, false).status, CheckStatus::Pass);
+        assert_eq!(byok_check(true, false, true).status, CheckStatus::Pass);
+        assert_eq!(byok_check(true, false, false).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn webhook_channels_need_a_public_url() {
+        let keys = ApiKeys {
+            teams_client_id: Some("client".to_string()),
+            telegram: Some("token".to_string()),
+            ..ApiKeys::default()
+        };
+        let relay = RelayConfig::default();
+
+        let checks = check_public_url(&keys, None, &relay);
+        let status = |name: &str| checks.iter().find(|c| c.name == name).unwrap().status;
+        assert_eq!(status("teams"), CheckStatus::Fail);
+        assert_eq!(status("telegram"), CheckStatus::Warn);
+
+        let checks = check_public_url(&keys, Some("https://beacon.example.com"), &relay);
+        assert!(checks.iter().all(|c| c.status == CheckStatus::Pass));
+
+        // A public relay provides the URL at runtime
+        let relay = RelayConfig {
+            enabled: true,
+            modes: vec![RelayMode::Cloudflare { token: None }],
+            local_port: 18790,
+        };
+        let checks = check_public_url(&keys, None, &relay);
+        assert!(
+            checks
+                .iter()
+                .any(|c| c.name == "teams" && c.status == CheckStatus::Pass)
+        );
+
+        let checks = check_public_url(&ApiKeys::default(), Some("beacon.example.com"), &relay);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn relay_modes_need_their_binaries() {
+        let relay = RelayConfig {
+            enabled: true,
+            modes: vec![
+                RelayMode::TailscaleServe { port: 443 },
+                RelayMode::SshTunnel {
+                    host: String::new(),
+                    port: 22,
+                    key_path: None,
+                    user: None,
+                },
+            ],
+            local_port: 18790,
+        };
+
+        let checks = check_relay(&relay, |name| name == "ssh");
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert!(checks[0].detail.contains("tailscale"));
+        assert_eq!(checks[1].status, CheckStatus::Fail);
+        assert!(checks[1].detail.contains("BEACON_SSH_HOST"));
+
+        assert!(check_relay(&RelayConfig::default(), |_| false).is_empty());
+    }
+}
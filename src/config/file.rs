@@ -2,11 +2,22 @@
 //!
 //! Supports `~/.config/omni/beacon/config.toml` as a persistent config source.
 //! All fields are optional — the file is a partial overlay on top of defaults.
+//!
+//! A file can pull in others with `include = ["channels.toml", "secrets.toml"]`.
+//! Included files are layered over the including file in order, so later
+//! files win; tables merge key by key while other values are replaced.
+//! Relative paths resolve against the including file's directory.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
+use crate::{Error, Result};
+
+/// Key listing files to layer over the current one
+const INCLUDE_KEY: &str = "include";
+
 /// Top-level TOML configuration file schema
 #[derive(Debug, Default, Deserialize)]
 pub struct BeaconConfigFile {
@@ -14,6 +25,14 @@ pub struct BeaconConfigFile {
     #[serde(default)]
     pub persona: Option<String>,
 
+    /// Persona to fall back to when `persona` cannot be resolved
+    #[serde(default)]
+    pub default_persona: Option<String>,
+
+    /// Refuse to start when `persona` cannot be resolved instead of falling back
+    #[serde(default)]
+    pub persona_strict: Option<bool>,
+
     /// LLM configuration
     #[serde(default)]
     pub llm: LlmFileConfig,
@@ -91,6 +110,27 @@ pub struct VoiceFileConfig {
 
     /// TTS speed multiplier
     pub tts_speed: Option<f64>,
+
+    /// Piper `.onnx` voice model for offline TTS fallback
+    pub piper_model: Option<PathBuf>,
+
+    /// Piper model config JSON (defaults to the one beside the model)
+    pub piper_config: Option<PathBuf>,
+
+    /// Overlapping wake-word triggers: "drop" or "queue"
+    pub overlap_policy: Option<String>,
+
+    /// STT language hint (BCP 47, e.g. "de" or "pt-BR")
+    pub stt_language: Option<String>,
+
+    /// Wake word backend: "stt" or "porcupine"
+    pub wake_word_backend: Option<String>,
+
+    /// Porcupine `.ppn` keyword model path
+    pub porcupine_keyword_path: Option<PathBuf>,
+
+    /// Porcupine detection sensitivity (0.0 to 1.0)
+    pub porcupine_sensitivity: Option<f32>,
 }
 
 /// API keys configuration
@@ -101,6 +141,7 @@ pub struct ApiKeysFileConfig {
     pub openrouter: Option<String>,
     pub elevenlabs: Option<String>,
     pub deepgram: Option<String>,
+    pub gemini: Option<String>,
     pub discord: Option<String>,
     pub slack: Option<String>,
     pub telegram: Option<String>,
@@ -126,6 +167,15 @@ pub struct ChannelsFileConfig {
 #[derive(Debug, Default, Clone, Deserialize)]
 pub struct ChannelToggle {
     pub enabled: Option<bool>,
+
+    /// Minimum interval between streaming edits, in milliseconds
+    pub stream_interval_ms: Option<u64>,
+
+    /// Interval between repeated typing indicators, in milliseconds (0 = send once)
+    pub typing_interval_ms: Option<u64>,
+
+    /// Send a placeholder message before the first streamed text
+    pub stream_placeholder: Option<bool>,
 }
 
 /// iMessage-specific channel config
@@ -149,6 +199,60 @@ pub struct ServerFileConfig {
 
     /// Cloud mode toggle
     pub cloud_mode: Option<bool>,
+
+    /// Scope required per route prefix in cloud mode (e.g. `"/api/admin" = "admin"`)
+    pub route_scopes: Option<HashMap<String, String>>,
+
+    /// Require credentials on the chat routes outside cloud mode (default: false)
+    pub require_chat_auth: Option<bool>,
+
+    /// Seconds chat responses are kept for `Idempotency-Key` replay (default: 86400)
+    pub idempotency_ttl_secs: Option<u64>,
+
+    /// Persist node registrations across restarts (default: true)
+    pub persist_nodes: Option<bool>,
+
+    /// Seconds a disconnected node is remembered before it is pruned (default: 604800)
+    pub node_stale_ttl_secs: Option<u64>,
+
+    /// Node commands refused on every device (e.g. `["system.run"]`)
+    pub node_deny_commands: Option<Vec<String>>,
+
+    /// Advertise the gateway over mDNS (default: false)
+    pub mdns: Option<bool>,
+
+    /// Accept unsigned Vortex callbacks when no secret is set (default: false)
+    pub vortex_allow_unsigned: Option<bool>,
+
+    /// Start in maintenance mode
+    pub maintenance: Option<bool>,
+
+    /// Reply sent while in maintenance mode (empty = stay silent)
+    pub maintenance_message: Option<String>,
+
+    /// Memory scope: `per_user`, `per_user_persona`, or `shared`
+    pub memory_scope: Option<String>,
+
+    /// Memory lifetime in days keyed by category (0 never expires)
+    pub memory_ttl_days: Option<HashMap<String, u32>>,
+
+    /// Components that must be healthy for `/ready` (`database`, `agent`, `channel`, `billing`)
+    pub readiness_require: Option<Vec<String>>,
+
+    /// Channel whose connection gates readiness (default: first channel started)
+    pub primary_channel: Option<String>,
+
+    /// Tokens per user per UTC day (unset = unlimited)
+    pub daily_token_cap: Option<u64>,
+
+    /// Estimated spend per user per UTC day, in USD
+    pub daily_cost_cap_usd: Option<f64>,
+
+    /// Blended price used to estimate spend for `daily_cost_cap_usd`
+    pub usd_per_million_tokens: Option<f64>,
+
+    /// Reply sent once a user reaches the daily cap
+    pub daily_limit_message: Option<String>,
 }
 
 /// Skills system configuration
@@ -164,6 +268,8 @@ pub struct SkillsFileConfig {
     pub max_skill_file_bytes: Option<usize>,
     /// Additional skill directories to scan
     pub extra_dirs: Option<Vec<String>>,
+    /// Remote skill manifest URLs
+    pub remote_manifests: Option<Vec<String>>,
     /// Personal agent skills directory
     pub personal_dir: Option<String>,
     /// Bundled skill allowlist (empty = all)
@@ -184,7 +290,8 @@ pub struct SkillsFileConfig {
 
 /// Load the TOML config file from the standard path
 ///
-/// Returns `BeaconConfigFile::default()` if the file doesn't exist or can't be parsed.
+/// Returns `BeaconConfigFile::default()` if the file doesn't exist or can't be
+/// read, parsed, or have its includes resolved.
 pub fn load_config_file() -> BeaconConfigFile {
     let Some(path) = config_file_path() else {
         return BeaconConfigFile::default();
@@ -194,32 +301,99 @@ pub fn load_config_file() -> BeaconConfigFile {
         return BeaconConfigFile::default();
     }
 
-    match std::fs::read_to_string(&path) {
-        Ok(content) => match toml::from_str(&content) {
-            Ok(config) => {
-                tracing::info!(path = %path.display(), "loaded config file");
-                config
-            }
-            Err(e) => {
-                tracing::warn!(
-                    path = %path.display(),
-                    error = %e,
-                    "failed to parse config file, using defaults"
-                );
-                BeaconConfigFile::default()
-            }
-        },
+    match load_config_from(&path) {
+        Ok(config) => config,
         Err(e) => {
             tracing::warn!(
                 path = %path.display(),
                 error = %e,
-                "failed to read config file"
+                "failed to load config file, using defaults"
             );
             BeaconConfigFile::default()
         }
     }
 }
 
+/// Load a TOML config file and the files it includes
+///
+/// # Errors
+///
+/// Returns error if any file can't be read or parsed, or includes form a cycle
+pub fn load_config_from(path: &Path) -> Result<BeaconConfigFile> {
+    let table = load_layered(path, &mut Vec::new())?;
+    let config = table
+        .try_into()
+        .map_err(|e| Error::Config(format!("invalid config in {}: {e}", path.display())))?;
+    tracing::info!(path = %path.display(), "loaded config file");
+    Ok(config)
+}
+
+/// Read `path` and layer its includes over it, depth first
+///
+/// `stack` holds the files currently being loaded, to catch cycles.
+fn load_layered(path: &Path, stack: &mut Vec<PathBuf>) -> Result<toml::Table> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| Error::Config(format!("failed to read {}: {e}", path.display())))?;
+    if stack.contains(&canonical) {
+        let chain: Vec<_> = stack
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(Error::Config(format!(
+            "config include cycle: {}",
+            chain.join(" -> ")
+        )));
+    }
+
+    let content = std::fs::read_to_string(&canonical)
+        .map_err(|e| Error::Config(format!("failed to read {}: {e}", path.display())))?;
+    let mut table: toml::Table = toml::from_str(&content)
+        .map_err(|e| Error::Config(format!("failed to parse {}: {e}", path.display())))?;
+
+    let includes = match table.remove(INCLUDE_KEY) {
+        None => return Ok(table),
+        Some(toml::Value::Array(items)) => items,
+        Some(_) => {
+            return Err(Error::Config(format!(
+                "`{INCLUDE_KEY}` in {} must be an array of paths",
+                path.display()
+            )));
+        }
+    };
+
+    let base_dir = canonical.parent().unwrap_or_else(|| Path::new("."));
+    stack.push(canonical.clone());
+    for item in includes {
+        let toml::Value::String(include) = item else {
+            return Err(Error::Config(format!(
+                "`{INCLUDE_KEY}` in {} must be an array of paths",
+                path.display()
+            )));
+        };
+        let layer = load_layered(&base_dir.join(include), stack)?;
+        merge_tables(&mut table, layer);
+    }
+    stack.pop();
+
+    Ok(table)
+}
+
+/// Merge `overlay` into `base`: tables merge recursively, other values replace
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(incoming)) => {
+                merge_tables(existing, incoming);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 /// Return the config file path: `~/.config/omni/beacon/config.toml`
 #[must_use]
 pub fn config_file_path() -> Option<PathBuf> {
@@ -230,3 +404,65 @@ pub fn config_file_path() -> Option<PathBuf> {
             .join("config.toml")
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn includes_layer_over_base_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("conf.d")).unwrap();
+        let base = write(
+            dir.path(),
+            "config.toml",
+            r#"
+            include = ["conf.d/llm.toml", "conf.d/override.toml"]
+            persona = "orin"
+
+            [llm]
+            model = "base-model"
+            provider = "anthropic"
+            "#,
+        );
+        write(
+            &dir.path().join("conf.d"),
+            "llm.toml",
+            "[llm]\nmodel = \"included-model\"\n",
+        );
+        write(
+            &dir.path().join("conf.d"),
+            "override.toml",
+            "persona = \"sage\"\n[llm]\nmodel = \"last-model\"\n",
+        );
+
+        let config = load_config_from(&base).unwrap();
+        assert_eq!(config.persona.as_deref(), Some("sage"));
+        assert_eq!(config.llm.model.as_deref(), Some("last-model"));
+        assert_eq!(config.llm.provider.as_deref(), Some("anthropic"));
+    }
+
+    #[test]
+    fn include_cycles_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = write(dir.path(), "a.toml", "include = [\"b.toml\"]\n");
+        write(dir.path(), "b.toml", "include = [\"a.toml\"]\n");
+
+        let err = load_config_from(&base).unwrap_err().to_string();
+        assert!(err.contains("config include cycle"), "{err}");
+    }
+
+    #[test]
+    fn missing_include_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = write(dir.path(), "config.toml", "include = [\"nope.toml\"]\n");
+
+        assert!(load_config_from(&base).is_err());
+    }
+}
@@ -1,18 +1,32 @@
 //! Configuration management for Beacon gateway
 
+pub mod check;
 pub mod file;
 #[cfg(feature = "embedded-synapse")]
 pub mod synapse_bridge;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::hooks::HooksConfig;
 use crate::relay::RelayConfig;
 use crate::security::{AuthConfig, DmPolicy};
 use crate::{Error, Persona, Result};
 
+/// Shown in place of secrets in `Debug` output
+pub(crate) const REDACTED: &str = "***";
+
+/// `Debug` stand-in for an optional secret: whether it is set, never its value
+pub(crate) const fn redact<T>(secret: &Option<T>) -> Option<&'static str> {
+    match secret {
+        Some(_) => Some(REDACTED),
+        None => None,
+    }
+}
+
 /// Beacon gateway configuration
-#[derive(Debug, Clone)]
+///
+/// `Debug` output redacts secrets, so the config is safe to log.
+#[derive(Clone)]
 pub struct Config {
     /// Active persona
     pub persona: Persona,
@@ -51,6 +65,27 @@ pub struct Config {
     /// Cloud relay configuration
     pub relay: RelayConfig,
 
+    /// Tool result truncation/summarization
+    pub tool_output: crate::tools::ToolOutputConfig,
+
+    /// Maintenance mode (pause agent replies without stopping the process)
+    pub maintenance: crate::maintenance::MaintenanceConfig,
+
+    /// Per-channel streaming edit and typing cadence
+    pub streaming: crate::channels::StreamingSettings,
+
+    /// How memories are scoped across users and personas
+    pub memory_scope: crate::db::MemoryScope,
+
+    /// Per-category memory lifetimes
+    pub memory_ttl: crate::db::MemoryTtl,
+
+    /// Components that must be healthy for `/ready`
+    pub readiness: crate::readiness::ReadinessConfig,
+
+    /// Local per-user daily usage caps
+    pub usage_cap: crate::usage::UsageCapConfig,
+
     /// Gateway authentication configuration
     pub auth: AuthConfig,
 
@@ -102,6 +137,54 @@ pub struct Config {
     pub ecosystem: EcosystemConfig,
 }
 
+impl std::fmt::Debug for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Config")
+            .field("persona", &self.persona)
+            .field("persona_cache_dir", &self.persona_cache_dir)
+            .field("data_dir", &self.data_dir)
+            .field("extension_dir", &self.extension_dir)
+            .field("voice", &self.voice)
+            .field("api_keys", &self.api_keys)
+            .field("life_json_path", &self.life_json_path)
+            .field("api_server", &self.api_server)
+            .field("llm_provider", &self.llm_provider)
+            .field("imessage", &self.imessage)
+            .field("dm_policy", &self.dm_policy)
+            .field("relay", &self.relay)
+            .field("tool_output", &self.tool_output)
+            .field("maintenance", &self.maintenance)
+            .field("streaming", &self.streaming)
+            .field("memory_scope", &self.memory_scope)
+            .field("memory_ttl", &self.memory_ttl)
+            .field("readiness", &self.readiness)
+            .field("usage_cap", &self.usage_cap)
+            .field("auth", &self.auth)
+            .field("hooks", &self.hooks)
+            .field("auth_base_url", &self.auth_base_url)
+            .field("synapse_api_url", &self.synapse_api_url)
+            .field(
+                "synapse_gateway_secret",
+                &redact(&self.synapse_gateway_secret),
+            )
+            .field("synapse_url", &self.synapse_url)
+            .field("llm_model", &self.llm_model)
+            .field("cloud_mode", &self.cloud_mode)
+            .field("knowledge_cache_dir", &self.knowledge_cache_dir)
+            .field("sync", &self.sync)
+            .field("skills", &self.skills)
+            .field("telegram", &self.telegram)
+            .field("gatekeeper_url", &self.gatekeeper_url)
+            .field(
+                "gatekeeper_service_key",
+                &redact(&self.gatekeeper_service_key),
+            )
+            .field("mcp_servers", &self.mcp_servers)
+            .field("ecosystem", &self.ecosystem)
+            .finish()
+    }
+}
+
 /// URLs for Omni ecosystem services (optional, graceful degradation)
 #[derive(Debug, Clone, Default)]
 pub struct EcosystemConfig {
@@ -116,7 +199,7 @@ pub struct EcosystemConfig {
 }
 
 /// HTTP API server configuration
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ApiServerConfig {
     /// Port to listen on
     pub port: u16,
@@ -134,8 +217,65 @@ pub struct ApiServerConfig {
     /// Vortex workflow automation URL
     pub vortex_url: Option<String>,
 
+    /// Secret Vortex signs scheduled-job callbacks with
+    pub vortex_webhook_secret: Option<String>,
+
+    /// Accept unsigned Vortex callbacks when no secret is set (default: false)
+    pub vortex_allow_unsigned: bool,
+
     /// Path to static files directory (web UI)
     pub static_dir: Option<PathBuf>,
+
+    /// Scope required per route prefix in cloud mode, merged over the defaults
+    /// (`"public"` requires no authentication)
+    pub route_scopes: std::collections::HashMap<String, String>,
+
+    /// Require credentials on the chat HTTP, SSE and WebSocket routes outside
+    /// cloud mode (default: false)
+    pub require_chat_auth: bool,
+
+    /// How long `POST /api/chat` responses are kept for `Idempotency-Key`
+    /// replay (default: 24h)
+    pub idempotency_ttl: std::time::Duration,
+
+    /// Persist node registrations so known devices are listed as stale after
+    /// a restart (default: true)
+    pub persist_nodes: bool,
+
+    /// How long a disconnected node is remembered before it is pruned
+    /// (default: 7 days)
+    pub node_stale_ttl: std::time::Duration,
+
+    /// Node commands refused on every device, on top of platform policy
+    pub node_deny_commands: Vec<String>,
+
+    /// Advertise the gateway on the local network over mDNS (default: false)
+    pub mdns: bool,
+}
+
+impl std::fmt::Debug for ApiServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiServerConfig")
+            .field("port", &self.port)
+            .field("api_key", &redact(&self.api_key))
+            .field("public_url", &self.public_url)
+            .field("manifold_url", &self.manifold_url)
+            .field("vortex_url", &self.vortex_url)
+            .field(
+                "vortex_webhook_secret",
+                &redact(&self.vortex_webhook_secret),
+            )
+            .field("vortex_allow_unsigned", &self.vortex_allow_unsigned)
+            .field("static_dir", &self.static_dir)
+            .field("route_scopes", &self.route_scopes)
+            .field("require_chat_auth", &self.require_chat_auth)
+            .field("idempotency_ttl", &self.idempotency_ttl)
+            .field("persist_nodes", &self.persist_nodes)
+            .field("node_stale_ttl", &self.node_stale_ttl)
+            .field("node_deny_commands", &self.node_deny_commands)
+            .field("mdns", &self.mdns)
+            .finish()
+    }
 }
 
 /// Voice processing configuration
@@ -155,6 +295,24 @@ pub struct VoiceConfig {
 
     /// TTS speed multiplier (0.25 to 4.0)
     pub tts_speed: f64,
+
+    /// Offline TTS used when Synapse synthesis fails (`None` = no fallback)
+    pub tts_fallback: Option<crate::voice::TtsBackend>,
+
+    /// What to do with wake-word triggers while a voice turn is active
+    pub overlap_policy: crate::voice::OverlapPolicy,
+
+    /// Default STT language hint (BCP 47); `None` auto-detects
+    pub stt_language: Option<String>,
+
+    /// How the wake word is recognized (STT matching or offline Porcupine)
+    pub wake_word_backend: crate::voice::WakeWordBackend,
+
+    /// Porcupine `.ppn` keyword model (required for the Porcupine backend)
+    pub porcupine_keyword_path: Option<PathBuf>,
+
+    /// Porcupine detection sensitivity (0.0 to 1.0)
+    pub porcupine_sensitivity: f32,
 }
 
 /// iMessage channel configuration (macOS only)
@@ -177,7 +335,7 @@ pub struct IMessageConfig {
 }
 
 /// API keys for external services
-#[derive(Debug, Clone, Default)]
+#[derive(Clone, Default)]
 pub struct ApiKeys {
     /// `OpenAI` API key (for Whisper and TTS)
     pub openai: Option<String>,
@@ -195,6 +353,9 @@ pub struct ApiKeys {
     /// `Deepgram` API key (optional STT)
     pub deepgram: Option<String>,
 
+    /// Google Gemini API key (optional vision)
+    pub gemini: Option<String>,
+
     /// Discord bot token
     pub discord: Option<String>,
 
@@ -225,6 +386,36 @@ pub struct ApiKeys {
     /// Matrix user ID (e.g., "@bot:matrix.org")
     pub matrix_user_id: Option<String>,
 
+    /// Mastodon instance URL (e.g., `<https://mastodon.social>`)
+    pub mastodon_instance_url: Option<String>,
+
+    /// Mastodon access token (`read` and `write` scopes)
+    pub mastodon_access_token: Option<String>,
+
+    /// IRC server hostname (e.g., "irc.libera.chat")
+    pub irc_server: Option<String>,
+
+    /// IRC server port (default: 6697 with TLS, 6667 without)
+    pub irc_port: Option<u16>,
+
+    /// Connect to IRC over TLS (default: true)
+    pub irc_tls: bool,
+
+    /// IRC nickname
+    pub irc_nick: Option<String>,
+
+    /// IRC server password
+    pub irc_password: Option<String>,
+
+    /// IRC channels to join (e.g., `#beacon`)
+    pub irc_channels: Vec<String>,
+
+    /// URL that receives replies from the generic webhook channel
+    pub webhook_url: Option<String>,
+
+    /// HMAC secret for signing outgoing and verifying incoming webhook bodies
+    pub webhook_secret: Option<String>,
+
     /// Microsoft Teams tenant ID (Azure AD)
     pub teams_tenant_id: Option<String>,
 
@@ -241,6 +432,50 @@ pub struct ApiKeys {
     pub google_chat_service_account: Option<std::path::PathBuf>,
 }
 
+impl std::fmt::Debug for ApiKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKeys")
+            .field("openai", &redact(&self.openai))
+            .field("anthropic", &redact(&self.anthropic))
+            .field("openrouter", &redact(&self.openrouter))
+            .field("elevenlabs", &redact(&self.elevenlabs))
+            .field("deepgram", &redact(&self.deepgram))
+            .field("gemini", &redact(&self.gemini))
+            .field("discord", &redact(&self.discord))
+            .field("slack", &redact(&self.slack))
+            .field("telegram", &redact(&self.telegram))
+            .field("whatsapp", &redact(&self.whatsapp))
+            .field("whatsapp_phone_id", &self.whatsapp_phone_id)
+            .field("signal_api_url", &self.signal_api_url)
+            .field("signal_phone", &self.signal_phone)
+            .field("matrix_homeserver", &self.matrix_homeserver)
+            .field("matrix_access_token", &redact(&self.matrix_access_token))
+            .field("matrix_user_id", &self.matrix_user_id)
+            .field("mastodon_instance_url", &self.mastodon_instance_url)
+            .field(
+                "mastodon_access_token",
+                &redact(&self.mastodon_access_token),
+            )
+            .field("irc_server", &self.irc_server)
+            .field("irc_port", &self.irc_port)
+            .field("irc_tls", &self.irc_tls)
+            .field("irc_nick", &self.irc_nick)
+            .field("irc_password", &redact(&self.irc_password))
+            .field("irc_channels", &self.irc_channels)
+            .field("webhook_url", &self.webhook_url)
+            .field("webhook_secret", &redact(&self.webhook_secret))
+            .field("teams_tenant_id", &self.teams_tenant_id)
+            .field("teams_client_id", &self.teams_client_id)
+            .field("teams_client_secret", &redact(&self.teams_client_secret))
+            .field("teams_bot_id", &self.teams_bot_id)
+            .field(
+                "google_chat_service_account",
+                &self.google_chat_service_account,
+            )
+            .finish()
+    }
+}
+
 /// Skills system configuration
 #[derive(Debug, Clone)]
 pub struct SkillsConfig {
@@ -254,6 +489,8 @@ pub struct SkillsConfig {
     pub max_skill_file_bytes: usize,
     /// Additional skill directories to scan (lowest precedence after bundled)
     pub extra_dirs: Vec<PathBuf>,
+    /// Manifests of remote skills whose bodies are fetched on first use
+    pub remote_manifests: Vec<String>,
     /// Personal agent skills directory (~/.agents/skills/)
     pub personal_dir: PathBuf,
     /// Bundled skill allowlist. Empty = all allowed
@@ -276,6 +513,7 @@ impl Default for SkillsConfig {
             max_skills_prompt_chars: 30_000,
             max_skill_file_bytes: 256_000,
             extra_dirs: Vec::new(),
+            remote_manifests: Vec::new(),
             personal_dir: default_personal_dir(),
             allow_bundled: Vec::new(),
             install_prefs: crate::skills::SkillInstallPreferences::default(),
@@ -303,7 +541,7 @@ fn default_personal_dir() -> PathBuf {
 }
 
 /// Telegram channel configuration
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct TelegramConfig {
     /// Bot token (default account)
@@ -340,6 +578,29 @@ pub struct TelegramConfig {
     pub accounts: std::collections::HashMap<String, TelegramAccountConfig>,
 }
 
+impl std::fmt::Debug for TelegramConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TelegramConfig")
+            .field("bot_token", &REDACTED)
+            .field("bot_username", &self.bot_username)
+            .field("require_mention_in_groups", &self.require_mention_in_groups)
+            .field("reaction_level", &self.reaction_level)
+            .field("ack_reaction", &self.ack_reaction)
+            .field("done_reaction", &self.done_reaction)
+            .field("webhook_secret", &redact(&self.webhook_secret))
+            .field("streaming_mode", &self.streaming_mode)
+            .field("text_chunk_limit", &self.text_chunk_limit)
+            .field("show_reasoning", &self.show_reasoning)
+            .field("api_timeout_secs", &self.api_timeout_secs)
+            .field("download_timeout_secs", &self.download_timeout_secs)
+            .field("proxy", &self.proxy)
+            .field("debug_updates", &self.debug_updates)
+            .field("debug_responses", &self.debug_responses)
+            .field("accounts", &self.accounts)
+            .finish()
+    }
+}
+
 impl TelegramConfig {
     /// Create from a bot token with defaults
     #[must_use]
@@ -407,7 +668,7 @@ impl TelegramConfig {
 ///
 /// All fields except `bot_token` are optional and fall back to the global
 /// `TelegramConfig` defaults when not set.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct TelegramAccountConfig {
     /// Bot token (required per account)
     pub bot_token: String,
@@ -427,8 +688,23 @@ pub struct TelegramAccountConfig {
     pub text_chunk_limit: Option<usize>,
 }
 
+impl std::fmt::Debug for TelegramAccountConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TelegramAccountConfig")
+            .field("bot_token", &REDACTED)
+            .field("bot_username", &self.bot_username)
+            .field("require_mention_in_groups", &self.require_mention_in_groups)
+            .field("reaction_level", &self.reaction_level)
+            .field("ack_reaction", &self.ack_reaction)
+            .field("done_reaction", &self.done_reaction)
+            .field("streaming_mode", &self.streaming_mode)
+            .field("text_chunk_limit", &self.text_chunk_limit)
+            .finish()
+    }
+}
+
 /// Fully resolved per-account config (no Optional fields)
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ResolvedTelegramAccountConfig {
     pub bot_token: String,
     pub bot_username: Option<String>,
@@ -440,6 +716,21 @@ pub struct ResolvedTelegramAccountConfig {
     pub text_chunk_limit: usize,
 }
 
+impl std::fmt::Debug for ResolvedTelegramAccountConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResolvedTelegramAccountConfig")
+            .field("bot_token", &REDACTED)
+            .field("bot_username", &self.bot_username)
+            .field("require_mention_in_groups", &self.require_mention_in_groups)
+            .field("reaction_level", &self.reaction_level)
+            .field("ack_reaction", &self.ack_reaction)
+            .field("done_reaction", &self.done_reaction)
+            .field("streaming_mode", &self.streaming_mode)
+            .field("text_chunk_limit", &self.text_chunk_limit)
+            .finish()
+    }
+}
+
 /// How the bot delivers streaming responses
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum StreamingMode {
@@ -509,6 +800,12 @@ pub struct SyncConfig {
 
     /// Sync interval in seconds (default: 300 = 5 minutes)
     pub interval_secs: u64,
+
+    /// How conflicting edits from other devices are resolved
+    pub merge_strategy: crate::sync::MergeStrategy,
+
+    /// End-to-end encryption of memory bodies (None = plaintext)
+    pub encryption: Option<crate::sync::SyncEncryption>,
 }
 
 /// Return the XDG cache directory for persona files, creating it if needed
@@ -531,6 +828,56 @@ pub fn persona_cache_dir() -> PathBuf {
     cache_dir
 }
 
+/// Path of the marker that flags a cached persona as locally authored
+///
+/// Locally authored personas live in the persona cache dir so the normal load
+/// chain finds them; the marker keeps Manifold fetches from overwriting them.
+#[must_use]
+pub fn local_persona_marker(cache_dir: &std::path::Path, persona_id: &str) -> PathBuf {
+    cache_dir.join(format!("{persona_id}.local"))
+}
+
+/// Reject a loaded persona that fails [`Persona::validate`]
+///
+/// All problems are reported in one error so they can be fixed together.
+fn validated(persona_id: &str, persona: Persona) -> Result<Persona> {
+    persona.validate().map_err(|errors| {
+        let problems: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        Error::Config(format!(
+            "invalid persona {persona_id}: {}",
+            problems.join("; ")
+        ))
+    })?;
+    Ok(persona)
+}
+
+/// Persona used when the requested one cannot be resolved and no fallback is configured
+pub const DEFAULT_FALLBACK_PERSONA: &str = "orin";
+
+/// Load a persona, falling back to `default_id` when `id` cannot be found
+///
+/// Only `PersonaNotFound` triggers the fallback; other load errors (bad JSON,
+/// IO failures) are returned as-is. In strict mode the original error is
+/// returned so a misconfigured persona fails startup.
+fn resolve_persona(
+    id: &str,
+    default_id: &str,
+    strict: bool,
+    load: impl Fn(&str) -> Result<Persona>,
+) -> Result<Persona> {
+    match load(id) {
+        Err(Error::PersonaNotFound(_)) if !strict && id != default_id => {
+            tracing::warn!(
+                requested = id,
+                fallback = default_id,
+                "persona not found, falling back to default persona"
+            );
+            load(default_id)
+        }
+        result => result,
+    }
+}
+
 impl Config {
     /// Load configuration for a persona
     ///
@@ -550,19 +897,54 @@ impl Config {
     /// # Errors
     ///
     /// Returns error if persona file cannot be loaded
-    #[allow(clippy::too_many_lines)]
     pub fn load_with_options(persona_id: Option<&str>, disable_voice: bool) -> Result<Self> {
         // Load optional TOML config file (env > toml > default)
-        let fc = file::load_config_file();
+        Self::load_with_file(file::load_config_file(), persona_id, disable_voice)
+    }
 
+    /// Load configuration from an explicit TOML file and its includes
+    ///
+    /// Unlike the default config file, a missing or invalid file here is an
+    /// error. Env vars still take precedence over values from the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the config file, its includes, or the persona can't be loaded
+    pub fn from_file(path: &Path, persona_id: Option<&str>) -> Result<Self> {
+        Self::load_with_file(file::load_config_from(path)?, persona_id, false)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn load_with_file(
+        fc: file::BeaconConfigFile,
+        persona_id: Option<&str>,
+        disable_voice: bool,
+    ) -> Result<Self> {
         // Resolve effective persona ID: CLI arg > TOML config > None
         let effective_id = persona_id
             .filter(|s| !s.is_empty())
             .or_else(|| fc.persona.as_deref().filter(|s| !s.is_empty()));
 
+        // Fallback persona for unresolvable IDs (env > toml > default)
+        let default_persona = std::env::var("BEACON_DEFAULT_PERSONA")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .or_else(|| fc.default_persona.clone().filter(|s| !s.is_empty()))
+            .unwrap_or_else(|| DEFAULT_FALLBACK_PERSONA.to_string());
+        let persona_strict = std::env::var("BEACON_PERSONA_STRICT")
+            .ok()
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .or(fc.persona_strict)
+            .unwrap_or(false);
+
         // Load persona (or use default for no-persona mode)
         let persona = match effective_id {
-            Some(id) => Self::load_persona_with_priority(id)?,
+            Some(id) => resolve_persona(
+                id,
+                &default_persona,
+                persona_strict,
+                Self::load_persona_with_priority,
+            )?,
             None => Persona::default(),
         };
         let cache_dir = persona_cache_dir();
@@ -582,6 +964,7 @@ impl Config {
             deepgram: std::env::var("DEEPGRAM_API_KEY")
                 .ok()
                 .or(fc.api_keys.deepgram),
+            gemini: std::env::var("GEMINI_API_KEY").ok().or(fc.api_keys.gemini),
             discord: std::env::var("DISCORD_TOKEN").ok().or(fc.api_keys.discord),
             slack: std::env::var("SLACK_BOT_TOKEN").ok().or(fc.api_keys.slack),
             telegram: std::env::var("TELEGRAM_BOT_TOKEN")
@@ -594,6 +977,26 @@ impl Config {
             matrix_homeserver: std::env::var("MATRIX_HOMESERVER").ok(),
             matrix_access_token: std::env::var("MATRIX_ACCESS_TOKEN").ok(),
             matrix_user_id: std::env::var("MATRIX_USER_ID").ok(),
+            mastodon_instance_url: std::env::var("MASTODON_INSTANCE_URL").ok(),
+            mastodon_access_token: std::env::var("MASTODON_ACCESS_TOKEN").ok(),
+            irc_server: std::env::var("IRC_SERVER").ok(),
+            irc_port: std::env::var("IRC_PORT").ok().and_then(|v| v.parse().ok()),
+            irc_tls: std::env::var("IRC_TLS")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(true),
+            irc_nick: std::env::var("IRC_NICK").ok(),
+            irc_password: std::env::var("IRC_PASSWORD").ok(),
+            irc_channels: std::env::var("IRC_CHANNELS")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|c| !c.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            webhook_url: std::env::var("WEBHOOK_URL").ok(),
+            webhook_secret: std::env::var("WEBHOOK_SECRET").ok(),
             teams_tenant_id: std::env::var("TEAMS_TENANT_ID").ok(),
             teams_client_id: std::env::var("TEAMS_CLIENT_ID").ok(),
             teams_client_secret: std::env::var("TEAMS_CLIENT_SECRET").ok(),
@@ -615,7 +1018,64 @@ impl Config {
             public_url: std::env::var("BEACON_PUBLIC_URL").ok(),
             manifold_url: std::env::var("MANIFOLD_URL").ok(),
             vortex_url: std::env::var("VORTEX_URL").ok(),
+            vortex_webhook_secret: std::env::var("VORTEX_WEBHOOK_SECRET").ok(),
+            vortex_allow_unsigned: std::env::var("VORTEX_ALLOW_UNSIGNED_CALLBACKS")
+                .ok()
+                .map(|v| v == "true" || v == "1")
+                .or(fc.server.vortex_allow_unsigned)
+                .unwrap_or(false),
             static_dir: std::env::var("BEACON_STATIC_DIR").ok().map(PathBuf::from),
+            // `BEACON_ROUTE_SCOPES=/api/admin=admin,/api/voice=chat`
+            route_scopes: std::env::var("BEACON_ROUTE_SCOPES").map_or_else(
+                |_| fc.server.route_scopes.clone().unwrap_or_default(),
+                |v| {
+                    v.split(',')
+                        .filter_map(|pair| pair.split_once('='))
+                        .map(|(route, scope)| (route.trim().to_string(), scope.trim().to_string()))
+                        .collect()
+                },
+            ),
+            require_chat_auth: std::env::var("BEACON_REQUIRE_CHAT_AUTH")
+                .ok()
+                .map(|v| v == "true" || v == "1")
+                .or(fc.server.require_chat_auth)
+                .unwrap_or(false),
+            idempotency_ttl: std::env::var("BEACON_IDEMPOTENCY_TTL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .or(fc.server.idempotency_ttl_secs)
+                .map_or(
+                    crate::api::chat::DEFAULT_IDEMPOTENCY_TTL,
+                    std::time::Duration::from_secs,
+                ),
+            persist_nodes: std::env::var("BEACON_PERSIST_NODES")
+                .ok()
+                .map(|v| v == "true" || v == "1")
+                .or(fc.server.persist_nodes)
+                .unwrap_or(true),
+            node_stale_ttl: std::env::var("BEACON_NODE_STALE_TTL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .or(fc.server.node_stale_ttl_secs)
+                .map_or(
+                    crate::nodes::DEFAULT_STALE_TTL,
+                    std::time::Duration::from_secs,
+                ),
+            node_deny_commands: std::env::var("BEACON_NODE_DENY_COMMANDS")
+                .ok()
+                .map(|s| {
+                    s.split(',')
+                        .map(|c| c.trim().to_string())
+                        .filter(|c| !c.is_empty())
+                        .collect()
+                })
+                .or_else(|| fc.server.node_deny_commands.clone())
+                .unwrap_or_default(),
+            mdns: std::env::var("BEACON_MDNS_ENABLED")
+                .ok()
+                .map(|v| v == "true" || v == "1")
+                .or(fc.server.mdns)
+                .unwrap_or(false),
         };
 
         // Voice config (env > toml > persona > default)
@@ -638,6 +1098,41 @@ impl Config {
                 .unwrap_or_else(|| "tts-1".to_string()),
             tts_voice: fc.voice.tts_voice.unwrap_or(tts_voice),
             tts_speed: fc.voice.tts_speed.unwrap_or(tts_speed),
+            tts_fallback: std::env::var("BEACON_PIPER_MODEL")
+                .ok()
+                .map(PathBuf::from)
+                .or(fc.voice.piper_model)
+                .map(|model_path| crate::voice::TtsBackend::Piper {
+                    model_path,
+                    config_path: std::env::var("BEACON_PIPER_CONFIG")
+                        .ok()
+                        .map(PathBuf::from)
+                        .or(fc.voice.piper_config),
+                }),
+            overlap_policy: std::env::var("BEACON_VOICE_OVERLAP")
+                .ok()
+                .or(fc.voice.overlap_policy)
+                .map(|s| crate::voice::OverlapPolicy::from_str(&s))
+                .unwrap_or_default(),
+            stt_language: std::env::var("BEACON_STT_LANGUAGE")
+                .ok()
+                .or(fc.voice.stt_language)
+                .or_else(|| persona.stt_language().map(String::from))
+                .filter(|l| !l.trim().is_empty()),
+            wake_word_backend: std::env::var("BEACON_WAKE_WORD_BACKEND")
+                .ok()
+                .or(fc.voice.wake_word_backend)
+                .map(|s| crate::voice::WakeWordBackend::from_str(&s))
+                .unwrap_or_default(),
+            porcupine_keyword_path: std::env::var("BEACON_PORCUPINE_KEYWORD_PATH")
+                .ok()
+                .map(PathBuf::from)
+                .or(fc.voice.porcupine_keyword_path),
+            porcupine_sensitivity: std::env::var("BEACON_PORCUPINE_SENSITIVITY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .or(fc.voice.porcupine_sensitivity)
+                .unwrap_or(0.5),
         };
 
         if disable_voice {
@@ -677,6 +1172,9 @@ impl Config {
             .ok()
             .or(fc.llm.provider);
 
+        // Per-channel streaming cadence (env > toml > built-in default)
+        let streaming = Self::load_streaming(&fc.channels);
+
         // iMessage config (env > toml > default)
         let imessage_toml = fc.channels.imessage.unwrap_or_default();
         let imessage = IMessageConfig {
@@ -707,6 +1205,9 @@ impl Config {
         // Cloud relay configuration
         let relay = RelayConfig::from_env();
 
+        // Tool result truncation/summarization
+        let tool_output = crate::tools::ToolOutputConfig::from_env();
+
         // Gateway authentication configuration
         let auth = AuthConfig::from_env();
 
@@ -749,6 +1250,81 @@ impl Config {
             .or(fc.server.cloud_mode)
             .unwrap_or(false);
 
+        // Maintenance mode (env > toml > default); an empty message means stay silent
+        let maintenance = crate::maintenance::MaintenanceConfig {
+            enabled: std::env::var("BEACON_MAINTENANCE")
+                .ok()
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .or(fc.server.maintenance)
+                .unwrap_or(false),
+            message: std::env::var("BEACON_MAINTENANCE_MESSAGE")
+                .ok()
+                .or(fc.server.maintenance_message)
+                .map_or_else(
+                    || Some(crate::maintenance::DEFAULT_MAINTENANCE_MESSAGE.to_string()),
+                    |m| Some(m).filter(|m| !m.trim().is_empty()),
+                ),
+        };
+
+        // Memory scope (env > toml > per-user)
+        let memory_scope = std::env::var("BEACON_MEMORY_SCOPE")
+            .ok()
+            .or(fc.server.memory_scope)
+            .map(|s| crate::db::MemoryScope::from_str(&s))
+            .unwrap_or_default();
+
+        // Memory TTL in days per category (env > toml > defaults; 0 never expires)
+        let file_ttl = fc.server.memory_ttl_days.unwrap_or_default();
+        let memory_ttl = crate::db::MemoryCategory::ALL.into_iter().fold(
+            crate::db::MemoryTtl::default(),
+            |ttl, category| {
+                std::env::var(format!(
+                    "BEACON_MEMORY_TTL_{}",
+                    category.as_str().to_ascii_uppercase()
+                ))
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .or_else(|| file_ttl.get(category.as_str()).copied())
+                .map_or(ttl, |days| ttl.with_days(category, days))
+            },
+        );
+
+        // Readiness requirements (env > toml > defaults for the mode)
+        let readiness = crate::readiness::ReadinessConfig {
+            require: std::env::var("BEACON_READINESS_REQUIRE")
+                .ok()
+                .or_else(|| fc.server.readiness_require.map(|list| list.join(",")))
+                .map(|s| crate::readiness::ReadinessComponent::parse_list(&s)),
+            primary_channel: std::env::var("BEACON_PRIMARY_CHANNEL")
+                .ok()
+                .or(fc.server.primary_channel)
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty()),
+        };
+
+        // Daily usage caps (env > toml > unlimited)
+        let usage_cap = crate::usage::UsageCapConfig {
+            daily_tokens: std::env::var("BEACON_DAILY_TOKEN_CAP")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .or(fc.server.daily_token_cap)
+                .filter(|&t| t > 0),
+            daily_cost_usd: std::env::var("BEACON_DAILY_COST_CAP_USD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .or(fc.server.daily_cost_cap_usd)
+                .filter(|&c| c > 0.0),
+            usd_per_million_tokens: std::env::var("BEACON_USD_PER_MILLION_TOKENS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .or(fc.server.usd_per_million_tokens),
+            message: std::env::var("BEACON_DAILY_LIMIT_MESSAGE")
+                .ok()
+                .or(fc.server.daily_limit_message)
+                .filter(|m| !m.trim().is_empty())
+                .unwrap_or_else(|| crate::usage::DEFAULT_DAILY_LIMIT_MESSAGE.to_string()),
+        };
+
         // Knowledge pack cache directory
         let knowledge_cache_dir = std::env::var("BEACON_KNOWLEDGE_CACHE_DIR").map_or_else(
             |_| {
@@ -761,19 +1337,38 @@ impl Config {
         );
 
         // Memory sync configuration (opt-in via env vars)
-        let sync = std::env::var("BEACON_SYNC_API_URL").ok().map(|api_url| {
-            let device_id = std::env::var("BEACON_DEVICE_ID")
-                .unwrap_or_else(|_| format!("gw_{}", uuid::Uuid::new_v4()));
-            let interval_secs = std::env::var("BEACON_SYNC_INTERVAL")
-                .ok()
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(300);
-            SyncConfig {
-                api_url,
-                device_id,
-                interval_secs,
-            }
-        });
+        let sync = std::env::var("BEACON_SYNC_API_URL")
+            .ok()
+            .map(|api_url| -> Result<SyncConfig> {
+                let device_id = std::env::var("BEACON_DEVICE_ID")
+                    .unwrap_or_else(|_| format!("gw_{}", uuid::Uuid::new_v4()));
+                let interval_secs = std::env::var("BEACON_SYNC_INTERVAL")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(300);
+                let merge_strategy = std::env::var("BEACON_SYNC_MERGE_STRATEGY")
+                    .ok()
+                    .map(|s| s.parse::<crate::sync::MergeStrategy>())
+                    .transpose()?
+                    .unwrap_or_default();
+                // `BEACON_SYNC_PASSPHRASE` takes precedence over the device key
+                let encryption = std::env::var("BEACON_SYNC_PASSPHRASE")
+                    .ok()
+                    .map(crate::sync::SyncEncryption::Passphrase)
+                    .or_else(|| {
+                        std::env::var("BEACON_SYNC_ENCRYPTION")
+                            .is_ok_and(|v| v.eq_ignore_ascii_case("device"))
+                            .then_some(crate::sync::SyncEncryption::DeviceKey)
+                    });
+                Ok(SyncConfig {
+                    api_url,
+                    device_id,
+                    interval_secs,
+                    merge_strategy,
+                    encryption,
+                })
+            })
+            .transpose()?;
 
         // Skills configuration (env > toml > default)
         let skills = {
@@ -791,6 +1386,17 @@ impl Config {
                 })
                 .unwrap_or(default.extra_dirs);
 
+            let remote_manifests = std::env::var("BEACON_SKILLS_REMOTE_MANIFESTS")
+                .ok()
+                .map(|s| {
+                    s.split(',')
+                        .map(|u| u.trim().to_string())
+                        .filter(|u| !u.is_empty())
+                        .collect()
+                })
+                .or_else(|| toml_skills.remote_manifests.clone())
+                .unwrap_or(default.remote_manifests);
+
             let personal_dir = std::env::var("BEACON_SKILLS_PERSONAL_DIR")
                 .map(PathBuf::from)
                 .or_else(|_| {
@@ -890,6 +1496,7 @@ impl Config {
                     .max_skill_file_bytes
                     .unwrap_or(default.max_skill_file_bytes),
                 extra_dirs,
+                remote_manifests,
                 personal_dir,
                 allow_bundled,
                 install_prefs: crate::skills::SkillInstallPreferences {
@@ -999,6 +1606,13 @@ impl Config {
             imessage,
             dm_policy,
             relay,
+            tool_output,
+            maintenance,
+            streaming,
+            memory_scope,
+            memory_ttl,
+            readiness,
+            usage_cap,
             auth,
             hooks,
             auth_base_url,
@@ -1033,10 +1647,10 @@ impl Config {
 
     /// Find life.json in standard locations
     fn find_life_json() -> Option<PathBuf> {
-        // 1. Environment variable
+        // 1. Environment variable (a local path or an https:// URL)
         if let Ok(path) = std::env::var("LIFE_JSON_PATH") {
-            let p = PathBuf::from(path);
-            if p.exists() {
+            let p = PathBuf::from(&path);
+            if path.starts_with("https://") || p.exists() {
                 return Some(p);
             }
         }
@@ -1060,6 +1674,66 @@ impl Config {
         None
     }
 
+    /// Load per-channel streaming overrides from env and the channel toml tables
+    ///
+    /// Reads `BEACON_<CHANNEL>_STREAM_INTERVAL_MS`, `BEACON_<CHANNEL>_TYPING_INTERVAL_MS`
+    /// and `BEACON_<CHANNEL>_STREAM_PLACEHOLDER` on top of the built-in defaults.
+    fn load_streaming(channels: &file::ChannelsFileConfig) -> crate::channels::StreamingSettings {
+        use crate::channels::StreamingConfig;
+
+        const CHANNELS: [&str; 12] = [
+            "discord",
+            "slack",
+            "telegram",
+            "whatsapp",
+            "signal",
+            "imessage",
+            "matrix",
+            "mastodon",
+            "irc",
+            "webhook",
+            "teams",
+            "google_chat",
+        ];
+
+        let env_u64 = |key: String| std::env::var(key).ok().and_then(|v| v.parse().ok());
+
+        let mut settings = crate::channels::StreamingSettings::default();
+        for name in CHANNELS {
+            let toml = match name {
+                "discord" => channels.discord.as_ref(),
+                "slack" => channels.slack.as_ref(),
+                "telegram" => channels.telegram.as_ref(),
+                _ => None,
+            };
+            let prefix = format!("BEACON_{}", name.to_uppercase());
+
+            let edit_interval_ms = env_u64(format!("{prefix}_STREAM_INTERVAL_MS"))
+                .or_else(|| toml.and_then(|t| t.stream_interval_ms));
+            let typing_interval_ms = env_u64(format!("{prefix}_TYPING_INTERVAL_MS"))
+                .or_else(|| toml.and_then(|t| t.typing_interval_ms));
+            let placeholder = std::env::var(format!("{prefix}_STREAM_PLACEHOLDER"))
+                .ok()
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .or_else(|| toml.and_then(|t| t.stream_placeholder));
+
+            if edit_interval_ms.is_none() && typing_interval_ms.is_none() && placeholder.is_none() {
+                continue;
+            }
+
+            let defaults = StreamingConfig::for_channel(name);
+            settings.insert(
+                name,
+                StreamingConfig {
+                    edit_interval_ms: edit_interval_ms.unwrap_or(defaults.edit_interval_ms),
+                    typing_interval_ms: typing_interval_ms.unwrap_or(defaults.typing_interval_ms),
+                    placeholder: placeholder.unwrap_or(defaults.placeholder),
+                },
+            );
+        }
+        settings
+    }
+
     /// Load hooks configuration from file or defaults
     fn load_hooks_config(data_dir: &std::path::Path) -> HooksConfig {
         // Check for hooks.toml in ~/.beacon or data_dir
@@ -1104,6 +1778,9 @@ impl Config {
             enabled,
             path: None,
             auto_reply: vec![],
+            timeout_secs: std::env::var("BEACON_HOOK_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok()),
         }
     }
 
@@ -1112,7 +1789,7 @@ impl Config {
     /// # Errors
     ///
     /// Returns error if persona cannot be loaded from any source
-    fn load_persona_with_priority(persona_id: &str) -> Result<Persona> {
+    pub fn load_persona_with_priority(persona_id: &str) -> Result<Persona> {
         // 1. BEACON_PERSONAS_DIR env var (dev override)
         if let Ok(dir) = std::env::var("BEACON_PERSONAS_DIR") {
             let path = PathBuf::from(&dir);
@@ -1190,7 +1867,7 @@ impl Config {
             let persona: Persona = serde_json::from_str(&content)
                 .map_err(|e| Error::Config(format!("failed to parse {persona_id}.json: {e}")))?;
             tracing::debug!(path = %json_path.display(), "loaded persona from JSON");
-            return Ok(persona);
+            return validated(persona_id, persona);
         }
 
         // Fall back to TOML (legacy format)
@@ -1203,7 +1880,7 @@ impl Config {
                 path = %toml_path.display(),
                 "loaded persona from legacy TOML format, consider migrating to JSON"
             );
-            return Ok(persona);
+            return validated(persona_id, persona);
         }
 
         Err(Error::PersonaNotFound(persona_id.to_string()))
@@ -1221,7 +1898,7 @@ impl Config {
     ///
     /// Returns error if persona ID is not found in embedded data
     pub fn load_embedded_persona(persona_id: &str) -> Result<Persona> {
-        Self::EMBEDDED_PERSONAS
+        let persona = Self::EMBEDDED_PERSONAS
             .iter()
             .find(|(id, _)| *id == persona_id)
             .and_then(|(_, json)| {
@@ -1229,7 +1906,8 @@ impl Config {
                 tracing::info!(persona_id, "loaded persona from embedded data");
                 Some(persona)
             })
-            .ok_or_else(|| Error::PersonaNotFound(persona_id.to_string()))
+            .ok_or_else(|| Error::PersonaNotFound(persona_id.to_string()))?;
+        validated(persona_id, persona)
     }
 
     /// Return the embedded persona array for enumeration
@@ -1241,6 +1919,13 @@ impl Config {
     /// Write persona JSON to the cache directory
     fn cache_persona(persona_id: &str, persona: &Persona) {
         let cache_dir = persona_cache_dir();
+        if local_persona_marker(&cache_dir, persona_id).exists() {
+            tracing::debug!(
+                persona_id,
+                "keeping locally authored persona over Manifold copy"
+            );
+            return;
+        }
         let path = cache_dir.join(format!("{persona_id}.json"));
 
         match serde_json::to_string_pretty(persona) {
@@ -1317,6 +2002,7 @@ impl Config {
 
             let persona: Persona = serde_json::from_str(&content)
                 .map_err(|e| Error::Config(format!("failed to parse persona JSON: {e}")))?;
+            let persona = validated(&persona_id_owned, persona)?;
 
             tracing::info!(
                 persona_id = persona_id_owned,
@@ -1330,3 +2016,77 @@ impl Config {
         .map_err(|_| Error::Config("persona fetch thread panicked".to_string()))?
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stub_loader(id: &str) -> Result<Persona> {
+        if id == "orin" {
+            Ok(Persona::default())
+        } else {
+            Err(Error::PersonaNotFound(id.to_string()))
+        }
+    }
+
+    #[test]
+    fn unknown_persona_falls_back_to_default() {
+        let persona = resolve_persona("orni", "orin", false, stub_loader);
+        assert!(persona.is_ok());
+    }
+
+    #[test]
+    fn strict_mode_fails_on_unknown_persona() {
+        let err = resolve_persona("orni", "orin", true, stub_loader).unwrap_err();
+        assert!(matches!(err, Error::PersonaNotFound(id) if id == "orni"));
+    }
+
+    #[test]
+    fn missing_default_persona_reports_default_id() {
+        let err = resolve_persona("orni", "nope", false, stub_loader).unwrap_err();
+        assert!(matches!(err, Error::PersonaNotFound(id) if id == "nope"));
+    }
+
+    #[test]
+    fn invalid_persona_lists_all_problems() {
+        let mut persona = Persona::default();
+        persona.identity.name = String::new();
+        persona.voice = Some(crate::persona::Voice {
+            wake_words: vec![String::new()],
+            tts: None,
+            stt: None,
+        });
+
+        let Err(Error::Config(msg)) = validated("orin", persona) else {
+            panic!("expected a config error");
+        };
+        assert!(msg.contains("identity.name"), "{msg}");
+        assert!(msg.contains("voice.wakeWords[0]"), "{msg}");
+    }
+
+    #[test]
+    fn non_not_found_errors_do_not_fall_back() {
+        let err = resolve_persona("broken", "orin", false, |_| {
+            Err(Error::Config("bad json".to_string()))
+        })
+        .unwrap_err();
+        assert!(matches!(err, Error::Config(_)));
+    }
+
+    #[test]
+    fn debug_output_redacts_secrets() {
+        let keys = ApiKeys {
+            anthropic: Some("sk-ant-secret".to_string()),
+            irc_server: Some("irc.libera.chat".to_string()),
+            ..ApiKeys::default()
+        };
+        let debug = format!("{keys:?}");
+        assert!(!debug.contains("sk-ant-secret"));
+        assert!(debug.contains(r#"anthropic: Some("***")"#));
+        assert!(debug.contains("openai: None"));
+        assert!(debug.contains("irc.libera.chat"));
+
+        let telegram = TelegramConfig::new("123:bot-token".to_string());
+        assert!(!format!("{telegram:?}").contains("bot-token"));
+    }
+}
@@ -1,7 +1,13 @@
 //! Context builder for assembling conversation context
 
+use std::sync::LazyLock;
+
+use tiktoken_rs::CoreBPE;
+
 use crate::Result;
+use crate::channels::Channel;
 use crate::db::{Memory, MemoryRepo, Message, MessageRole, SessionRepo, UserContext, UserRepo};
+use crate::knowledge::cosine_similarity;
 
 use super::life_json::{LifeJson, LifeJsonReader};
 
@@ -10,12 +16,16 @@ use super::life_json::{LifeJson, LifeJsonReader};
 pub struct ContextConfig {
     /// Maximum number of messages to include from history
     pub max_messages: usize,
-    /// Maximum approximate token count for context
+    /// Token budget for the system prompt, context, and history
     pub max_tokens: usize,
+    /// Tokenizer the budget is counted with
+    pub tokenizer: Tokenizer,
     /// Persona/assistant ID for life.json lookup
     pub persona_id: String,
     /// Maximum number of memories to include
     pub max_memories: usize,
+    /// How the included memories are chosen
+    pub memory_selection: MemorySelection,
     /// Persona system prompt to include in context
     pub persona_system_prompt: Option<String>,
 }
@@ -25,13 +35,78 @@ impl Default for ContextConfig {
         Self {
             max_messages: 20,
             max_tokens: 4000,
+            tokenizer: Tokenizer::default(),
             persona_id: "orin".to_string(),
             max_memories: 10,
+            memory_selection: MemorySelection::default(),
             persona_system_prompt: None,
         }
     }
 }
 
+static CL100K: LazyLock<Option<CoreBPE>> = LazyLock::new(|| tiktoken_rs::cl100k_base().ok());
+static O200K: LazyLock<Option<CoreBPE>> = LazyLock::new(|| tiktoken_rs::o200k_base().ok());
+
+/// Token encoding used to count context budgets, chosen by model
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tokenizer {
+    /// `o200k_base`, used by GPT-4o and newer OpenAI models
+    O200k,
+    /// `cl100k_base`, used by GPT-4 and as the default for other models
+    #[default]
+    Cl100k,
+    /// Claude's tokenizer isn't public and produces more tokens than
+    /// `cl100k_base`, so `cl100k_base` counts are padded by a fifth
+    Claude,
+}
+
+impl Tokenizer {
+    /// Pick the tokenizer for a model id, with or without a provider prefix
+    #[must_use]
+    pub fn for_model(model: &str) -> Self {
+        let name = model.rsplit('/').next().unwrap_or(model);
+        if name.contains("claude") {
+            return Self::Claude;
+        }
+        match tiktoken_rs::tokenizer::get_tokenizer(name) {
+            Some(tiktoken_rs::tokenizer::Tokenizer::O200kBase) => Self::O200k,
+            _ => Self::Cl100k,
+        }
+    }
+
+    /// Count the tokens in `text`
+    ///
+    /// Falls back to a character estimate if the encoding fails to load.
+    #[must_use]
+    pub fn count(self, text: &str) -> usize {
+        let bpe = match self {
+            Self::O200k => O200K.as_ref(),
+            Self::Cl100k | Self::Claude => CL100K.as_ref(),
+        };
+        let Some(bpe) = bpe else {
+            return estimate_tokens(text);
+        };
+
+        let tokens = bpe.encode_ordinary(text).len();
+        if self == Self::Claude {
+            tokens + tokens.div_ceil(5)
+        } else {
+            tokens
+        }
+    }
+}
+
+/// How stored memories are chosen for the context
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemorySelection {
+    /// Pinned and most-used memories, most recently accessed first
+    Recent,
+    /// Memories most similar to the current message, falling back to
+    /// `Recent` when there is nothing to compare against
+    #[default]
+    Relevant,
+}
+
 /// Built context ready for injection into agent
 #[derive(Debug, Clone)]
 pub struct BuiltContext {
@@ -43,7 +118,8 @@ pub struct BuiltContext {
     pub system_context: String,
     /// Recent messages for conversation history
     pub messages: Vec<ContextMessage>,
-    /// Approximate token count
+    /// Tokens used by the persona prompt, system context, and history, as
+    /// counted by the configured tokenizer
     pub estimated_tokens: usize,
 }
 
@@ -204,11 +280,15 @@ impl ContextBuilder {
             }
         }
 
-        // Load memories from database using query-driven hybrid search
+        // Load memories from database, by query-driven hybrid search when relevant
         if let Some((repo, query)) = memory {
-            let memories = repo
-                .search_hybrid(user_id, query, None, self.config.max_memories)
-                .unwrap_or_default();
+            let memories = match self.config.memory_selection {
+                MemorySelection::Relevant => {
+                    repo.search_hybrid(user_id, query, None, self.config.max_memories)
+                }
+                MemorySelection::Recent => repo.get_context(user_id, self.config.max_memories),
+            }
+            .unwrap_or_default();
             if !memories.is_empty() {
                 let memory_context = format_memories(&memories);
                 if !memory_context.is_empty() {
@@ -253,9 +333,9 @@ impl ContextBuilder {
 
     /// Build context using semantic (embedding-based) memory retrieval
     ///
-    /// When `query_embedding` is provided, uses vector similarity search
-    /// to find contextually relevant memories. Falls back to access-count
-    /// ordering when embedding is absent.
+    /// With `MemorySelection::Relevant` and a `query_embedding`, memories come
+    /// from the vector index (cosine ranking in memory when no index exists).
+    /// Falls back to access-count ordering when the embedding is absent.
     ///
     /// # Errors
     ///
@@ -285,16 +365,7 @@ impl ContextBuilder {
 
         // Load memories — semantic when embedding available, access-count otherwise
         if let Some(repo) = memory_repo {
-            let memories = query_embedding.map_or_else(
-                || {
-                    repo.get_context(user_id, self.config.max_memories)
-                        .unwrap_or_default()
-                },
-                |embedding| {
-                    repo.search_similar(user_id, embedding, self.config.max_memories)
-                        .unwrap_or_default()
-                },
-            );
+            let memories = self.select_memories(repo, user_id, query_embedding);
 
             if !memories.is_empty() {
                 let memory_context = format_memories(&memories);
@@ -329,11 +400,82 @@ impl ContextBuilder {
         })
     }
 
+    /// Choose up to `max_memories` memories per the configured selection
+    ///
+    /// Relevant memories come from the vector index; they are only ranked in
+    /// memory when the index is unavailable.
+    fn select_memories(
+        &self,
+        repo: &MemoryRepo,
+        user_id: &str,
+        query_embedding: Option<&[f32]>,
+    ) -> Vec<Memory> {
+        let limit = self.config.max_memories;
+        if self.config.memory_selection == MemorySelection::Relevant
+            && let Some(query) = query_embedding
+        {
+            let similar = repo.search_similar(user_id, query, limit).unwrap_or_else(|e| {
+                tracing::debug!(error = %e, "vector search unavailable, ranking memories in memory");
+                rank_by_similarity(repo.list(user_id, None).unwrap_or_default(), query, limit)
+            });
+            if !similar.is_empty() {
+                return similar;
+            }
+        }
+        repo.get_context(user_id, limit).unwrap_or_default()
+    }
+
+    /// Seed an empty session with recent history fetched from the channel
+    ///
+    /// Does nothing when the session already has messages. Messages the bot
+    /// posted (see [`Channel::bot_user_id`]) are stored as assistant turns;
+    /// the rest as user turns prefixed with the sender name. `skip_id`
+    /// excludes the message currently being handled. Returns the number of
+    /// messages stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the channel cannot fetch history or a database
+    /// operation fails
+    pub async fn seed_from_channel(
+        &self,
+        session_id: &str,
+        session_repo: &SessionRepo,
+        channel: &dyn Channel,
+        channel_id: &str,
+        skip_id: Option<&str>,
+    ) -> Result<usize> {
+        if session_repo.message_count(session_id)? > 0 {
+            return Ok(0);
+        }
+
+        let history = channel
+            .fetch_history(channel_id, self.config.max_messages)
+            .await?;
+
+        let bot_user_id = channel.bot_user_id();
+        let mut seeded = 0;
+        for msg in history
+            .iter()
+            .filter(|m| Some(m.id.as_str()) != skip_id && !m.content.trim().is_empty())
+        {
+            if bot_user_id == Some(msg.sender_id.as_str()) {
+                session_repo.add_message(session_id, MessageRole::Assistant, &msg.content)?;
+            } else {
+                let content = format!("{}: {}", msg.sender_name, msg.content);
+                session_repo.add_message(session_id, MessageRole::User, &content)?;
+            }
+            seeded += 1;
+        }
+
+        Ok(seeded)
+    }
+
     /// Build context from just a life.json file (for initial setup)
     #[must_use]
     pub fn build_from_life_json(&self, life_json: &LifeJson) -> BuiltContext {
         let system_context = life_json.build_context_string(&self.config.persona_id);
-        let estimated_tokens = estimate_tokens(&system_context);
+        let estimated_tokens = self.config.tokenizer.count(&system_context);
 
         BuiltContext {
             persona_prompt: self.config.persona_system_prompt.clone(),
@@ -345,31 +487,28 @@ impl ContextBuilder {
     }
 
     /// Prune messages to fit within token budget
+    ///
+    /// The persona prompt and system context are always kept, as is the
+    /// latest message; older messages are dropped first once the budget is
+    /// spent.
     fn prune_messages(
         &self,
         messages: &[Message],
         system_context: &str,
     ) -> (Vec<ContextMessage>, usize) {
-        let system_tokens = estimate_tokens(system_context);
-        let _available_tokens = self.config.max_tokens.saturating_sub(system_tokens);
-
+        let tokenizer = self.config.tokenizer;
+        let mut used_tokens = tokenizer.count(system_context)
+            + self
+                .config
+                .persona_system_prompt
+                .as_deref()
+                .map_or(0, |p| tokenizer.count(p));
+
+        // Walk from newest to oldest (messages come in chronological order)
         let mut context_messages = Vec::new();
-        let mut used_tokens = system_tokens;
-
-        // Process messages from oldest to newest (they come in chronological order)
-        for msg in messages {
-            let msg_tokens = estimate_tokens(&msg.content);
-
-            // Check if we'd exceed the budget
-            if used_tokens + msg_tokens > self.config.max_tokens {
-                // If we have no messages yet, include at least the last one
-                if context_messages.is_empty() {
-                    context_messages.push(ContextMessage {
-                        role: role_to_string(msg.role),
-                        content: msg.content.clone(),
-                    });
-                    used_tokens += msg_tokens;
-                }
+        for msg in messages.iter().rev() {
+            let msg_tokens = tokenizer.count(&msg.content);
+            if !context_messages.is_empty() && used_tokens + msg_tokens > self.config.max_tokens {
                 break;
             }
 
@@ -379,6 +518,7 @@ impl ContextBuilder {
             });
             used_tokens += msg_tokens;
         }
+        context_messages.reverse();
 
         (context_messages, used_tokens)
     }
@@ -456,8 +596,23 @@ fn role_to_string(role: MessageRole) -> String {
     }
 }
 
+/// Keep the `limit` memories most similar to `query`
+///
+/// Memories without an embedding can't be compared and are skipped.
+fn rank_by_similarity(memories: Vec<Memory>, query: &[f32], limit: usize) -> Vec<Memory> {
+    let mut scored: Vec<(f32, Memory)> = memories
+        .into_iter()
+        .filter_map(|m| {
+            let score = cosine_similarity(query, m.embedding.as_deref()?);
+            Some((score, m))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().take(limit).map(|(_, m)| m).collect()
+}
+
 /// Rough token estimation (4 chars per token on average)
-const fn estimate_tokens(text: &str) -> usize {
+pub(crate) const fn estimate_tokens(text: &str) -> usize {
     text.len() / 4
 }
 
@@ -575,6 +730,9 @@ mod tests {
             deleted_at: None,
             synced_at: None,
             cloud_id: None,
+            persona_id: None,
+            reinforcement_count: 0,
+            expires_at: None,
         };
         let result = format_memories(&[mem]);
         assert!(result.contains("<relevant-memories>"), "must wrap in tag");
@@ -606,6 +764,9 @@ mod tests {
             deleted_at: None,
             synced_at: None,
             cloud_id: None,
+            persona_id: None,
+            reinforcement_count: 0,
+            expires_at: None,
         };
         let result = format_memories(&[mem]);
         assert!(!result.contains("<script>"), "must escape html tags");
@@ -633,6 +794,9 @@ mod tests {
             deleted_at: None,
             synced_at: None,
             cloud_id: None,
+            persona_id: None,
+            reinforcement_count: 0,
+            expires_at: None,
         };
         let result = format_memories(&[mem]);
         // Either empty (filtered) or tag with no injection content
@@ -642,6 +806,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rank_by_similarity_keeps_closest_memories() {
+        let memory = |content: &str, embedding: Option<Vec<f32>>| {
+            let m = crate::db::Memory::new(
+                "u1".to_string(),
+                crate::db::MemoryCategory::Fact,
+                content.to_string(),
+            );
+            match embedding {
+                Some(e) => m.with_embedding(e),
+                None => m,
+            }
+        };
+        let memories = vec![
+            memory("cats", Some(vec![1.0, 0.0, 0.0])),
+            memory("unembedded", None),
+            memory("dogs", Some(vec![0.0, 1.0, 0.0])),
+            memory("puppies", Some(vec![0.1, 0.9, 0.0])),
+        ];
+
+        let ranked = rank_by_similarity(memories, &[0.0, 1.0, 0.0], 2);
+        let contents: Vec<&str> = ranked.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["dogs", "puppies"]);
+    }
+
+    #[test]
+    fn tokenizer_is_chosen_by_model() {
+        assert_eq!(Tokenizer::for_model("gpt-4o"), Tokenizer::O200k);
+        assert_eq!(Tokenizer::for_model("openai/gpt-4"), Tokenizer::Cl100k);
+        assert_eq!(
+            Tokenizer::for_model("anthropic/claude-sonnet-4"),
+            Tokenizer::Claude
+        );
+        assert_eq!(Tokenizer::for_model("llama-3.1-8b"), Tokenizer::Cl100k);
+
+        let text = "The quick brown fox jumps over the lazy dog";
+        assert!(Tokenizer::Claude.count(text) > Tokenizer::Cl100k.count(text));
+    }
+
+    #[test]
+    fn prune_drops_oldest_messages_first() {
+        let pool = crate::db::init_memory().unwrap();
+        let session_repo = crate::db::SessionRepo::new(pool.clone());
+        let user_repo = crate::db::UserRepo::new(pool);
+        let user = user_repo.find_or_create("prune_user").unwrap();
+        let session = session_repo
+            .find_or_create(&user.id, "test", "prune-chan", "orin")
+            .unwrap();
+
+        let long = "word ".repeat(40);
+        for text in [&long, &long, &"latest question".to_string()] {
+            session_repo
+                .add_message(&session.id, MessageRole::User, text)
+                .unwrap();
+        }
+
+        let builder = ContextBuilder::new(ContextConfig {
+            max_tokens: 50,
+            ..ContextConfig::default()
+        });
+        let ctx = builder
+            .build(&session.id, &user.id, None, &session_repo, &user_repo)
+            .unwrap();
+        assert_eq!(ctx.messages.len(), 2);
+        assert_eq!(ctx.messages[1].content, "latest question");
+        assert!(ctx.estimated_tokens <= 50);
+
+        // The latest message is kept even when it alone is over budget
+        let builder = ContextBuilder::new(ContextConfig {
+            max_tokens: 1,
+            ..ContextConfig::default()
+        });
+        let ctx = builder
+            .build(&session.id, &user.id, None, &session_repo, &user_repo)
+            .unwrap();
+        assert_eq!(ctx.messages.len(), 1);
+        assert_eq!(ctx.messages[0].content, "latest question");
+    }
+
     #[test]
     fn format_memories_empty_returns_empty_string() {
         assert_eq!(format_memories(&[]), String::new());
@@ -686,6 +929,102 @@ mod tests {
         );
     }
 
+    struct HistoryChannel;
+
+    #[async_trait::async_trait]
+    impl Channel for HistoryChannel {
+        fn name(&self) -> &'static str {
+            "history"
+        }
+
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, _message: crate::channels::OutgoingMessage) -> Result<()> {
+            Ok(())
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn bot_user_id(&self) -> Option<&str> {
+            Some("beacon")
+        }
+
+        async fn fetch_history(
+            &self,
+            channel_id: &str,
+            limit: usize,
+        ) -> Result<Vec<crate::channels::IncomingMessage>> {
+            let message =
+                |id: &str, sender: &str, content: &str| crate::channels::IncomingMessage {
+                    id: id.to_string(),
+                    channel_id: channel_id.to_string(),
+                    sender_id: sender.to_string(),
+                    sender_name: sender.to_string(),
+                    content: content.to_string(),
+                    is_dm: false,
+                    reply_to: None,
+                    attachments: Vec::new(),
+                    thread_id: None,
+                    callback_data: None,
+                    request_id: crate::request_id::generate(),
+                };
+            let mut history = vec![
+                message("1", "alice", "Lunch at noon?"),
+                message("2", "bob", "Sounds good"),
+                message("3", "beacon", "Noon works for me"),
+                message("4", "alice", "@bot what do you think?"),
+            ];
+            history.truncate(limit);
+            Ok(history)
+        }
+    }
+
+    #[tokio::test]
+    async fn seed_from_channel_backfills_empty_session_once() {
+        let pool = crate::db::init_memory().unwrap();
+        let session_repo = crate::db::SessionRepo::new(pool.clone());
+        let user_repo = crate::db::UserRepo::new(pool);
+        let user = user_repo.find_or_create("seed_user").unwrap();
+        let session = session_repo
+            .find_or_create(&user.id, "history", "general", "orin")
+            .unwrap();
+
+        let builder = ContextBuilder::new(ContextConfig::default());
+        let seeded = builder
+            .seed_from_channel(
+                &session.id,
+                &session_repo,
+                &HistoryChannel,
+                "general",
+                Some("4"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(seeded, 3);
+
+        let messages = session_repo.get_messages(&session.id, 10).unwrap();
+        assert_eq!(messages[0].content, "alice: Lunch at noon?");
+        assert_eq!(messages[1].content, "bob: Sounds good");
+        // The bot's own messages come back as its turns, unprefixed
+        assert_eq!(messages[2].role, MessageRole::Assistant);
+        assert_eq!(messages[2].content, "Noon works for me");
+
+        // Sessions with history are left alone
+        let seeded = builder
+            .seed_from_channel(&session.id, &session_repo, &HistoryChannel, "general", None)
+            .await
+            .unwrap();
+        assert_eq!(seeded, 0);
+    }
+
     #[test]
     fn build_with_semantic_memory_uses_search_similar_when_embedding_provided() {
         let pool = crate::db::init_memory().unwrap();
@@ -1,22 +1,30 @@
 //! Session compaction with optional memory flush
 //!
-//! When a conversation exceeds a message threshold, the oldest messages
-//! are summarized via LLM, optionally flushed to long-term memory,
-//! then replaced with a concise system summary
+//! When a conversation exceeds a message or estimated token threshold, the
+//! oldest messages are summarized via LLM, optionally flushed to long-term
+//! memory, then replaced with a concise system summary.
+//!
+//! [`SessionCompactor::compact_in_background`] runs this off the request
+//! path: the turn that triggered it keeps the context it already built, and
+//! later turns read the summarized history.
 
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use synapse_client::SynapseClient;
 
+use super::builder::estimate_tokens;
 use crate::Result;
-use crate::db::{Indexer, MemoryRepo, SessionRepo};
+use crate::db::{Indexer, MemoryRepo, Message, SessionRepo};
 
 /// Configuration for session compaction
 #[derive(Debug, Clone)]
 pub struct CompactionConfig {
     /// Trigger compaction when message count exceeds this threshold
     pub max_messages_before_compact: usize,
+    /// Trigger compaction when the session's estimated tokens exceed this (0 disables)
+    pub max_tokens_before_compact: usize,
     /// Fraction of messages to summarize (0.0–1.0)
     pub compact_fraction: f64,
     /// Timeout for LLM summarization call
@@ -29,6 +37,7 @@ impl Default for CompactionConfig {
     fn default() -> Self {
         Self {
             max_messages_before_compact: 40,
+            max_tokens_before_compact: 8000,
             compact_fraction: 0.5,
             summarize_timeout: Duration::from_secs(60),
             flush_to_memory: true,
@@ -48,6 +57,12 @@ impl CompactionConfig {
             config.max_messages_before_compact = n;
         }
 
+        if let Ok(val) = std::env::var("BEACON_COMPACT_TOKEN_THRESHOLD")
+            && let Ok(n) = val.parse()
+        {
+            config.max_tokens_before_compact = n;
+        }
+
         if let Ok(val) = std::env::var("BEACON_COMPACT_FLUSH_MEMORY") {
             config.flush_to_memory = !matches!(val.as_str(), "false" | "0" | "no");
         }
@@ -65,6 +80,10 @@ pub struct CompactionResult {
     pub summary_tokens: usize,
     /// Number of facts extracted to memory (0 if flush disabled)
     pub facts_extracted: usize,
+    /// Estimated session tokens before compaction
+    pub tokens_before: usize,
+    /// Estimated session tokens after compaction
+    pub tokens_after: usize,
 }
 
 /// Session compactor that summarizes old messages and optionally flushes facts to memory
@@ -72,16 +91,19 @@ pub struct SessionCompactor {
     config: CompactionConfig,
     synapse: Arc<SynapseClient>,
     model: String,
+    /// Sessions with a background compaction running
+    in_flight: Mutex<HashSet<String>>,
 }
 
 impl SessionCompactor {
     /// Create a new compactor
     #[must_use]
-    pub const fn new(config: CompactionConfig, synapse: Arc<SynapseClient>, model: String) -> Self {
+    pub fn new(config: CompactionConfig, synapse: Arc<SynapseClient>, model: String) -> Self {
         Self {
             config,
             synapse,
             model,
+            in_flight: Mutex::new(HashSet::new()),
         }
     }
 
@@ -91,6 +113,74 @@ impl SessionCompactor {
         message_count > self.config.max_messages_before_compact
     }
 
+    /// Check if compaction is needed based on estimated session tokens
+    #[must_use]
+    pub const fn needs_compaction_for_tokens(&self, estimated_tokens: usize) -> bool {
+        self.config.max_tokens_before_compact > 0
+            && estimated_tokens > self.config.max_tokens_before_compact
+    }
+
+    /// Compact a session in the background if it is over either threshold
+    ///
+    /// The thresholds are checked inside the spawned task, so this never
+    /// blocks the caller. At most one compaction runs per session; calls
+    /// while one is running are ignored. Publishes a
+    /// `beacon.session.compacted` event when messages were summarized.
+    pub fn compact_in_background(
+        self: &Arc<Self>,
+        session_id: &str,
+        session_repo: SessionRepo,
+        memory_repo: MemoryRepo,
+        indexer: Option<Arc<Indexer>>,
+        user_id: &str,
+    ) {
+        if !self.lock_in_flight().insert(session_id.to_string()) {
+            return;
+        }
+
+        let compactor = Arc::clone(self);
+        let session_id = session_id.to_string();
+        let user_id = user_id.to_string();
+        let request_id = crate::request_id::current_or_new();
+        tokio::spawn(crate::request_id::scope(request_id, async move {
+            match compactor
+                .compact(
+                    &session_id,
+                    &session_repo,
+                    &memory_repo,
+                    indexer.as_deref(),
+                    &user_id,
+                )
+                .await
+            {
+                Ok(result) if result.messages_removed > 0 => {
+                    crate::events::publish(crate::events::build_session_compacted_event(
+                        &session_id,
+                        &user_id,
+                        result.tokens_before,
+                        result.tokens_after,
+                        result.messages_removed,
+                    ));
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(
+                        session = %session_id,
+                        error = %e,
+                        "background session compaction failed"
+                    );
+                }
+            }
+            compactor.lock_in_flight().remove(&session_id);
+        }));
+    }
+
+    fn lock_in_flight(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
     /// Compact a session by summarizing old messages
     ///
     /// # Errors
@@ -107,12 +197,17 @@ impl SessionCompactor {
     ) -> Result<CompactionResult> {
         // Fetch all messages for the session
         let messages = session_repo.get_messages(session_id, 1000)?;
+        let tokens_before = session_tokens(&messages);
 
-        if messages.len() <= self.config.max_messages_before_compact {
+        if !self.needs_compaction(messages.len())
+            && !self.needs_compaction_for_tokens(tokens_before)
+        {
             return Ok(CompactionResult {
                 messages_removed: 0,
                 summary_tokens: 0,
                 facts_extracted: 0,
+                tokens_before,
+                tokens_after: tokens_before,
             });
         }
 
@@ -202,12 +297,15 @@ impl SessionCompactor {
         let messages_removed = session_repo.delete_messages_before(session_id, cutoff_id)?;
         let summary_header = format!("[Conversation summary]\n{summary_text}");
         session_repo.insert_summary(session_id, &summary_header)?;
+        let tokens_after = session_tokens(&session_repo.get_messages(session_id, 1000)?);
 
         tracing::info!(
             session = session_id,
             removed = messages_removed,
             summary_tokens,
             facts = facts_extracted,
+            tokens_before,
+            tokens_after,
             "session compacted"
         );
 
@@ -215,10 +313,17 @@ impl SessionCompactor {
             messages_removed,
             summary_tokens,
             facts_extracted,
+            tokens_before,
+            tokens_after,
         })
     }
 }
 
+/// Estimated tokens of a session's stored history
+fn session_tokens(messages: &[Message]) -> usize {
+    messages.iter().map(|m| estimate_tokens(&m.content)).sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,6 +354,27 @@ mod tests {
         assert!(compactor.needs_compaction(100));
     }
 
+    #[test]
+    fn needs_compaction_for_tokens_respects_high_water_mark() {
+        let synapse =
+            Arc::new(synapse_client::SynapseClient::new("http://localhost:1234").unwrap());
+        let config = CompactionConfig {
+            max_tokens_before_compact: 1000,
+            ..Default::default()
+        };
+        let compactor = SessionCompactor::new(config, Arc::clone(&synapse), "test".to_string());
+        assert!(!compactor.needs_compaction_for_tokens(1000));
+        assert!(compactor.needs_compaction_for_tokens(1001));
+
+        // Zero disables the token trigger
+        let config = CompactionConfig {
+            max_tokens_before_compact: 0,
+            ..Default::default()
+        };
+        let compactor = SessionCompactor::new(config, synapse, "test".to_string());
+        assert!(!compactor.needs_compaction_for_tokens(usize::MAX));
+    }
+
     #[test]
     fn delete_messages_removes_old() {
         let pool = crate::db::init_memory().unwrap();
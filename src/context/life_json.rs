@@ -1,14 +1,144 @@
 //! life.json reader for portable digital identity
-
-use std::path::Path;
+//!
+//! Files carry a semver `version`. Older schemas are migrated to the
+//! current shape on read; files from a newer major version are rejected
+//! rather than partially parsed.
+//!
+//! A path may also be an `https://` URL. Remote files are cached in the data
+//! dir and revalidated with their ETag in the background once
+//! `LIFE_JSON_CACHE_TTL_SECS` (default 300) has passed; reads always serve
+//! the cached copy, so a slow or unreachable remote never holds up a turn.
+//! `LIFE_JSON_AUTHORIZATION` is sent as the `Authorization` header.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
 
 use crate::{Error, Result};
 
+/// life.json schema version written by this build
+pub const LIFE_JSON_VERSION: &str = "1.0.0";
+
+/// Major schema version this build reads; older majors are migrated
+const SCHEMA_MAJOR: u64 = 1;
+
+/// How long a fetched remote life.json is used before revalidating
+const DEFAULT_REMOTE_TTL: Duration = Duration::from_secs(300);
+
+/// Timeout for fetching a remote life.json
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+static REMOTE_CONFIG: LazyLock<RemoteConfig> = LazyLock::new(RemoteConfig::from_env);
+
+/// URLs with a refresh in flight, so concurrent reads start only one
+static REFRESHING: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Mutex::default);
+
+/// Settings for fetching remote life.json files
+#[derive(Clone)]
+struct RemoteConfig {
+    /// `Authorization` header value sent with fetches
+    authorization: Option<String>,
+    /// How long a cached copy is used before revalidating
+    ttl: Duration,
+    /// Where fetched copies and their ETags are kept
+    cache_dir: PathBuf,
+}
+
+impl RemoteConfig {
+    fn from_env() -> Self {
+        Self {
+            authorization: std::env::var("LIFE_JSON_AUTHORIZATION")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            ttl: std::env::var("LIFE_JSON_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map_or(DEFAULT_REMOTE_TTL, Duration::from_secs),
+            cache_dir: directories::BaseDirs::new().map_or_else(
+                || PathBuf::from(".life-json-cache"),
+                |d| d.data_dir().join("omni").join("beacon").join("life-json"),
+            ),
+        }
+    }
+}
+
+/// Cached copy of one remote life.json
+struct RemoteCache {
+    content_path: PathBuf,
+    etag_path: PathBuf,
+}
+
+impl RemoteCache {
+    fn new(dir: &Path, url: &str) -> Self {
+        let key = hex::encode(Sha256::digest(url.as_bytes()));
+        Self {
+            content_path: dir.join(format!("{key}.json")),
+            etag_path: dir.join(format!("{key}.etag")),
+        }
+    }
+
+    fn content(&self) -> Option<String> {
+        std::fs::read_to_string(&self.content_path).ok()
+    }
+
+    fn etag(&self) -> Option<String> {
+        std::fs::read_to_string(&self.etag_path).ok()
+    }
+
+    /// Whether the copy was fetched or revalidated within `ttl`
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        std::fs::metadata(&self.content_path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age < ttl)
+    }
+
+    fn store(&self, content: &str, etag: Option<&str>) -> std::io::Result<()> {
+        if let Some(dir) = self.content_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.content_path, content)?;
+        match etag {
+            Some(etag) => std::fs::write(&self.etag_path, etag),
+            None => std::fs::remove_file(&self.etag_path).or_else(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    Ok(())
+                } else {
+                    Err(e)
+                }
+            }),
+        }
+    }
+
+    /// Restart the TTL after the remote confirmed the copy is current
+    fn touch(&self) -> std::io::Result<()> {
+        std::fs::File::options()
+            .append(true)
+            .open(&self.content_path)?
+            .set_modified(SystemTime::now())
+    }
+}
+
+/// Response to a remote life.json fetch
+enum Fetched {
+    /// The cached copy's ETag still matches
+    NotModified,
+    Body {
+        content: String,
+        etag: Option<String>,
+    },
+}
+
 /// life.json root structure (partial - only what Beacon needs)
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct LifeJson {
+    /// Schema version (semver); absent in files that predate versioning
     pub version: Option<String>,
     pub identity: Option<Identity>,
     pub preferences: Option<Preferences>,
@@ -144,14 +274,19 @@ pub struct WorkingHours {
 pub struct LifeJsonReader;
 
 impl LifeJsonReader {
-    /// Read and parse a life.json file
+    /// Read and parse a life.json file, or fetch it from an `https://` URL
     ///
     /// # Errors
     ///
-    /// Returns error if file cannot be read or parsed
+    /// Returns error if file cannot be read or parsed, or a remote file
+    /// cannot be fetched and has no cached copy
     pub fn read<P: AsRef<Path>>(path: P) -> Result<LifeJson> {
         let path = path.as_ref();
 
+        if let Some(url) = path.to_str().filter(|p| p.starts_with("https://")) {
+            return Self::read_remote(url, &REMOTE_CONFIG);
+        }
+
         if !path.exists() {
             return Ok(LifeJson::default());
         }
@@ -159,13 +294,76 @@ impl LifeJsonReader {
         let content = std::fs::read_to_string(path)
             .map_err(|e| Error::Config(format!("failed to read life.json: {e}")))?;
 
-        let life_json: LifeJson = serde_json::from_str(&content)
-            .map_err(|e| Error::Config(format!("failed to parse life.json: {e}")))?;
+        let life_json = Self::parse(&content)?;
 
         tracing::debug!(path = %path.display(), "loaded life.json");
         Ok(life_json)
     }
 
+    /// Read a remote life.json from the cache, refreshing a stale copy
+    ///
+    /// Inside a Tokio runtime the refresh runs in the background and this
+    /// read serves the cached copy (failing until the first fetch lands).
+    /// Outside one, the refresh completes before the cache is read.
+    fn read_remote(url: &str, config: &RemoteConfig) -> Result<LifeJson> {
+        let cache = RemoteCache::new(&config.cache_dir, url);
+        if !cache.is_fresh(config.ttl) {
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    if begin_refresh(url) {
+                        let (url, config) = (url.to_string(), config.clone());
+                        handle.spawn(async move {
+                            if let Err(e) = refresh_remote(&url, &config).await {
+                                tracing::warn!(url, error = %e, "life.json refresh failed");
+                            }
+                            end_refresh(&url);
+                        });
+                    }
+                }
+                Err(_) => {
+                    let refreshed = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .map_err(|e| Error::Config(format!("failed to start runtime: {e}")))?
+                        .block_on(refresh_remote(url, config));
+                    if let Err(e) = refreshed {
+                        tracing::warn!(url, error = %e, "life.json refresh failed");
+                    }
+                }
+            }
+        }
+
+        let content = cache
+            .content()
+            .ok_or_else(|| Error::Config(format!("remote life.json not fetched yet: {url}")))?;
+        Self::parse(&content)
+    }
+
+    /// Parse life.json content, migrating older schemas to the current one
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the content is not valid life.json or comes from a
+    /// newer schema version than this build understands
+    pub fn parse(content: &str) -> Result<LifeJson> {
+        let mut value: Value = serde_json::from_str(content)
+            .map_err(|e| Error::Config(format!("failed to parse life.json: {e}")))?;
+
+        let from = schema_major(&value)?;
+        let changes = migrate(&mut value, from);
+        if !changes.is_empty() {
+            tracing::info!(
+                from_version = from,
+                to_version = LIFE_JSON_VERSION,
+                changes = ?changes,
+                "migrated life.json to the current schema"
+            );
+        }
+
+        serde_json::from_value(value)
+            .map_err(|e| Error::Config(format!("failed to parse life.json: {e}")))
+    }
+
     /// Get assistant-specific config from life.json
     #[must_use]
     pub fn get_assistant_config<'a>(
@@ -176,6 +374,214 @@ impl LifeJsonReader {
     }
 }
 
+/// Claim the refresh of `url`; false if one is already running
+fn begin_refresh(url: &str) -> bool {
+    REFRESHING
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .insert(url.to_string())
+}
+
+fn end_refresh(url: &str) {
+    REFRESHING
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .remove(url);
+}
+
+/// Revalidate the cached copy of a remote life.json
+///
+/// Only copies that parse are cached, so a bad upload can't replace a good
+/// cached one. The cache is left alone when the remote is unreachable.
+async fn refresh_remote(url: &str, config: &RemoteConfig) -> Result<()> {
+    let cache = RemoteCache::new(&config.cache_dir, url);
+    let etag = cache.content().and_then(|_| cache.etag());
+    match fetch_remote(url, config.authorization.as_deref(), etag).await? {
+        Fetched::NotModified => cache
+            .touch()
+            .map_err(|e| Error::Config(format!("failed to refresh cached life.json: {e}"))),
+        Fetched::Body { content, etag } => {
+            LifeJsonReader::parse(&content)?;
+            cache
+                .store(&content, etag.as_deref())
+                .map_err(|e| Error::Config(format!("failed to cache life.json: {e}")))?;
+            tracing::debug!(url, "fetched remote life.json");
+            Ok(())
+        }
+    }
+}
+
+/// Fetch a remote life.json, conditional on `etag` when given
+async fn fetch_remote(
+    url: &str,
+    authorization: Option<&str>,
+    etag: Option<String>,
+) -> Result<Fetched> {
+    use reqwest::header::{AUTHORIZATION, ETAG, IF_NONE_MATCH};
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| Error::Config(format!("failed to create HTTP client: {e}")))?;
+
+    let mut request = client.get(url);
+    if let Some(authorization) = authorization {
+        request = request.header(AUTHORIZATION, authorization);
+    }
+    if let Some(etag) = etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| Error::Config(format!("failed to fetch life.json: {e}")))?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(Fetched::NotModified);
+    }
+    if !response.status().is_success() {
+        return Err(Error::Config(format!(
+            "failed to fetch life.json: {}",
+            response.status()
+        )));
+    }
+
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let content = response
+        .text()
+        .await
+        .map_err(|e| Error::Config(format!("failed to read life.json response: {e}")))?;
+    Ok(Fetched::Body { content, etag })
+}
+
+/// Major schema version of a life.json document (0 when unversioned)
+fn schema_major(value: &Value) -> Result<u64> {
+    if !value.is_object() {
+        return Err(Error::Config(
+            "failed to parse life.json: expected a JSON object".to_string(),
+        ));
+    }
+
+    let major = match value.get("version") {
+        None | Some(Value::Null) => Some(0),
+        Some(Value::Number(n)) => n.as_u64(),
+        Some(Value::String(s)) => s.split('.').next().and_then(|m| m.trim().parse().ok()),
+        Some(_) => None,
+    };
+    let Some(major) = major else {
+        return Err(Error::Config(format!(
+            "failed to parse life.json: invalid version {}",
+            value["version"]
+        )));
+    };
+
+    if major > SCHEMA_MAJOR {
+        return Err(Error::Config(format!(
+            "cannot parse life.json version {}: this build supports up to {SCHEMA_MAJOR}.x",
+            value["version"]
+        )));
+    }
+    Ok(major)
+}
+
+/// Upgrade a document from schema major `from` to the current schema
+///
+/// Returns a description of each change made.
+fn migrate(value: &mut Value, from: u64) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    // 0 -> 1: snake_case keys became camelCase, and learned facts became
+    // objects instead of bare strings
+    if from < 1 {
+        camel_case_keys(value, "", &mut changes);
+        wrap_string_facts(value, &mut changes);
+    }
+
+    if from < SCHEMA_MAJOR {
+        value["version"] = Value::String(LIFE_JSON_VERSION.to_string());
+        changes.push(format!("set version to {LIFE_JSON_VERSION}"));
+    }
+    changes
+}
+
+/// Rename snake_case object keys to camelCase, except assistant ids
+fn camel_case_keys(value: &mut Value, path: &str, changes: &mut Vec<String>) {
+    let Value::Object(map) = value else {
+        if let Value::Array(items) = value {
+            for item in items {
+                camel_case_keys(item, path, changes);
+            }
+        }
+        return;
+    };
+
+    // Keys of `assistants` are ids, not field names
+    let rename_keys = path != "assistants";
+    let entries: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+    for (key, mut child) in entries {
+        let new_key = if rename_keys {
+            to_camel_case(&key)
+        } else {
+            key.clone()
+        };
+        let child_path = if path.is_empty() {
+            new_key.clone()
+        } else {
+            format!("{path}.{new_key}")
+        };
+        camel_case_keys(&mut child, &child_path, changes);
+
+        if new_key != key {
+            changes.push(format!("renamed {key} to {child_path}"));
+        }
+        map.insert(new_key, child);
+    }
+}
+
+fn to_camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' && !out.is_empty() {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Turn bare-string `learnedFacts` entries into `{"fact": ...}` objects
+fn wrap_string_facts(value: &mut Value, changes: &mut Vec<String>) {
+    let Some(Value::Object(assistants)) = value.get_mut("assistants") else {
+        return;
+    };
+    for (id, config) in assistants {
+        let Some(Value::Array(facts)) = config.get_mut("learnedFacts") else {
+            continue;
+        };
+        let mut wrapped = 0;
+        for fact in facts.iter_mut() {
+            if let Value::String(text) = fact {
+                *fact = serde_json::json!({ "fact": std::mem::take(text) });
+                wrapped += 1;
+            }
+        }
+        if wrapped > 0 {
+            changes.push(format!(
+                "converted {wrapped} learned facts for {id} to objects"
+            ));
+        }
+    }
+}
+
 impl LifeJson {
     /// Build a context string from identity and preferences for the assistant
     #[must_use]
@@ -332,6 +738,120 @@ mod tests {
         assert!(context.contains("rust, typescript"));
     }
 
+    #[test]
+    fn parse_migrates_unversioned_schema() {
+        let json = r#"{
+            "identity": {"name": "Brian", "full_name": "Brian Example"},
+            "preferences": {"units": {"time_format": "24h"}},
+            "assistants": {
+                "my_bot": {
+                    "learned_facts": ["Prefers Rust", {"fact": "Uses Vim"}],
+                    "context": {"current_projects": ["beacon"]}
+                }
+            }
+        }"#;
+        let life_json = LifeJsonReader::parse(json).unwrap();
+
+        assert_eq!(life_json.version.as_deref(), Some(LIFE_JSON_VERSION));
+        let identity = life_json.identity.as_ref().unwrap();
+        assert_eq!(identity.full_name.as_deref(), Some("Brian Example"));
+
+        // Assistant ids are kept as-is while their fields are renamed
+        let bot = LifeJsonReader::get_assistant_config(&life_json, "my_bot").unwrap();
+        let facts = bot.learned_facts.as_ref().unwrap();
+        assert_eq!(facts[0].fact, "Prefers Rust");
+        assert_eq!(facts[1].fact, "Uses Vim");
+        assert_eq!(
+            bot.context.as_ref().unwrap().current_projects,
+            Some(vec!["beacon".to_string()])
+        );
+        assert!(
+            life_json
+                .build_context_string("my_bot")
+                .contains("Time format: 24h")
+        );
+    }
+
+    #[test]
+    fn parse_leaves_current_schema_untouched() {
+        let mut value: Value =
+            serde_json::from_str(r#"{"version": "1.2.0", "identity": {"fullName": "Brian"}}"#)
+                .unwrap();
+        let before = value.clone();
+        assert!(migrate(&mut value, schema_major(&value).unwrap()).is_empty());
+        assert_eq!(value, before);
+    }
+
+    #[test]
+    fn parse_rejects_newer_schema() {
+        let err = LifeJsonReader::parse(r#"{"version": "2.0.0"}"#).unwrap_err();
+        assert!(err.to_string().contains("supports up to 1.x"), "{err}");
+
+        assert!(LifeJsonReader::parse(r#"{"version": "latest"}"#).is_err());
+    }
+
+    /// A URL nothing listens on, so fetches fail fast
+    const UNREACHABLE: &str = "https://127.0.0.1:9/life.json";
+
+    fn remote_config(dir: &Path, ttl: Duration) -> RemoteConfig {
+        RemoteConfig {
+            authorization: None,
+            ttl,
+            cache_dir: dir.to_path_buf(),
+        }
+    }
+
+    #[test]
+    fn remote_read_uses_fresh_cache_without_fetching() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = remote_config(dir.path(), Duration::from_secs(300));
+        RemoteCache::new(dir.path(), UNREACHABLE)
+            .store(
+                r#"{"version": "1.0.0", "identity": {"name": "Brian"}}"#,
+                Some("\"v1\""),
+            )
+            .unwrap();
+
+        let life_json = LifeJsonReader::read_remote(UNREACHABLE, &config).unwrap();
+        assert_eq!(life_json.identity.unwrap().name.as_deref(), Some("Brian"));
+    }
+
+    #[test]
+    fn remote_read_falls_back_to_stale_cache_when_unreachable() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = remote_config(dir.path(), Duration::ZERO);
+
+        // Nothing cached yet: the fetch error surfaces
+        assert!(LifeJsonReader::read_remote(UNREACHABLE, &config).is_err());
+
+        RemoteCache::new(dir.path(), UNREACHABLE)
+            .store(
+                r#"{"version": "1.0.0", "identity": {"name": "Brian"}}"#,
+                None,
+            )
+            .unwrap();
+        let life_json = LifeJsonReader::read_remote(UNREACHABLE, &config).unwrap();
+        assert_eq!(life_json.identity.unwrap().name.as_deref(), Some("Brian"));
+    }
+
+    #[tokio::test]
+    async fn remote_read_serves_cache_while_refreshing_in_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = remote_config(dir.path(), Duration::ZERO);
+
+        // The first fetch runs in the background; nothing to serve yet
+        assert!(LifeJsonReader::read_remote(UNREACHABLE, &config).is_err());
+
+        RemoteCache::new(dir.path(), UNREACHABLE)
+            .store(
+                r#"{"version": "1.0.0", "identity": {"name": "Brian"}}"#,
+                None,
+            )
+            .unwrap();
+        let life_json = LifeJsonReader::read_remote(UNREACHABLE, &config).unwrap();
+        assert_eq!(life_json.identity.unwrap().name.as_deref(), Some("Brian"));
+    }
+
     #[test]
     fn test_read_nonexistent_returns_default() {
         let result = LifeJsonReader::read("/nonexistent/path/life.json");
@@ -9,7 +9,7 @@ use std::collections::HashMap;
 use crate::Result;
 use crate::db::{Memory, MemoryCategory, MemoryRepo};
 
-use super::life_json::{AssistantConfig, LearnedFact, LifeJson};
+use super::life_json::{AssistantConfig, LIFE_JSON_VERSION, LearnedFact, LifeJson, LifeJsonReader};
 
 /// Maximum number of memories to include in an export
 const DEFAULT_EXPORT_LIMIT: usize = 50;
@@ -72,7 +72,7 @@ pub fn export_memories(
     assistants.insert(persona_id.to_string(), config);
 
     let life_json = LifeJson {
-        version: Some("1.0.0".to_string()),
+        version: Some(LIFE_JSON_VERSION.to_string()),
         assistants: Some(assistants),
         ..LifeJson::default()
     };
@@ -91,18 +91,20 @@ pub fn export_memories(
 ///
 /// Parses `learnedFacts` from the specified assistant section (or all
 /// assistants if `persona_id` is `None`) and creates memories, skipping
-/// any whose content hash already exists for the user.
+/// any whose content hash already exists for the user. Older schemas are
+/// migrated first.
 ///
 /// # Errors
 ///
-/// Returns error if JSON parsing or database operations fail
+/// Returns error if JSON parsing or database operations fail, or the
+/// content comes from a newer schema version
 pub fn import_memories(
     repo: &MemoryRepo,
     user_id: &str,
     content: &str,
     persona_id: Option<&str>,
 ) -> Result<ImportResult> {
-    let life_json: LifeJson = serde_json::from_str(content)?;
+    let life_json = LifeJsonReader::parse(content)?;
 
     let mut imported = 0;
     let mut skipped = 0;
@@ -370,6 +372,43 @@ mod tests {
         assert_eq!(memories.len(), 2);
     }
 
+    #[test]
+    fn test_roundtrip_from_unversioned_schema() {
+        let (repo, user_id) = setup();
+
+        // Pre-versioning export: snake_case keys and bare-string facts
+        let legacy = r#"{
+            "assistants": {
+                "orin": {"learned_facts": ["Knows Rust", "Vim user"]}
+            }
+        }"#;
+        let import = import_memories(&repo, &user_id, legacy, Some("orin")).unwrap();
+        assert_eq!(import.imported, 2);
+
+        // Re-export is written in the current schema
+        let export = export_memories(&repo, &user_id, "orin", None).unwrap();
+        assert_eq!(export.life_json.version.as_deref(), Some(LIFE_JSON_VERSION));
+        let json = serde_json::to_string(&export.life_json).unwrap();
+        assert!(json.contains("learnedFacts"));
+
+        // And imports cleanly, deduplicating against the legacy import
+        let import = import_memories(&repo, &user_id, &json, Some("orin")).unwrap();
+        assert_eq!(import.imported, 0);
+        assert_eq!(import.skipped, 2);
+    }
+
+    #[test]
+    fn test_import_rejects_newer_schema() {
+        let (repo, user_id) = setup();
+
+        let json = r#"{
+            "version": "2.0.0",
+            "assistants": {"orin": {"learnedFacts": [{"fact": "From the future"}]}}
+        }"#;
+        assert!(import_memories(&repo, &user_id, json, None).is_err());
+        assert!(repo.list(&user_id, None).unwrap().is_empty());
+    }
+
     #[test]
     fn test_confidence_from_memory() {
         let pinned =
@@ -10,7 +10,9 @@ pub mod compaction;
 mod life_json;
 pub mod life_json_sync;
 
-pub use builder::{BuiltContext, ContextBuilder, ContextConfig, ContextMessage};
+pub use builder::{
+    BuiltContext, ContextBuilder, ContextConfig, ContextMessage, MemorySelection, Tokenizer,
+};
 pub use compaction::{CompactionConfig, CompactionResult, SessionCompactor};
 pub use life_json::{LifeJson, LifeJsonReader};
 pub use life_json_sync::{ExportResult, ImportResult};
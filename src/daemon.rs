@@ -7,22 +7,30 @@ use std::time::Duration;
 
 use synapse_client::SynapseClient;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 
 use crate::api::{ApiServerBuilder, ModelInfo};
 use crate::attachments::{AttachmentProcessor, VisionClient};
 #[cfg(target_os = "macos")]
 use crate::channels::IMessageChannel;
 use crate::channels::{
-    Channel, ChannelCapability, DiscordChannel, GoogleChatChannel, IncomingMessage, MatrixChannel,
-    OutgoingMessage, SignalChannel, SlackChannel, TeamsChannel, TelegramChannel, WhatsAppChannel,
+    Channel, ChannelCapability, DiscordChannel, GoogleChatChannel, IncomingMessage, IrcChannel,
+    IrcConfig, MastodonChannel, MatrixChannel, OutgoingMessage, SignalChannel, SlackChannel,
+    TeamsChannel, TelegramChannel, TypingKeepalive, WebhookChannel, WhatsAppChannel,
 };
-use crate::context::{ContextBuilder, ContextConfig};
+use crate::context::{ContextBuilder, ContextConfig, MemorySelection, Tokenizer};
 use crate::db::{self, DbPool, MessageRole, SessionRepo, SkillRepo, UserRepo};
 use crate::hooks::{HookAction, HookEvent, HookManager};
-use crate::security::{DmPolicy, PairingManager};
-use crate::voice::{AudioCapture, AudioPlayback, SAMPLE_RATE, WakeWordDetector, samples_to_wav};
+use crate::security::{DmPolicy, PairingError, PairingManager};
+use crate::voice::{
+    AudioCapture, AudioPlayback, DetectorState, PartialTranscript, SAMPLE_RATE, SpeechToText,
+    TextToSpeech, TurnAdmission, VoiceTurnGuard, WakeWordBackend, WakeWordDetector,
+    samples_to_pcm16, samples_to_wav,
+};
 use crate::{Config, Error, Result};
-use futures::StreamExt as _;
+use futures::future::LocalBoxFuture;
+use futures::{FutureExt as _, StreamExt as _};
 
 /// Audio processing chunk size (100ms at 16kHz)
 const CHUNK_SIZE: usize = 1600;
@@ -33,6 +41,9 @@ pub(crate) const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
 /// Max tokens for responses
 const MAX_TOKENS: u32 = 1024;
 
+/// How long shutdown waits for channel handlers to finish in-flight replies
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// The Beacon daemon - orchestrates voice and messaging
 pub struct Daemon {
     config: Config,
@@ -62,6 +73,39 @@ impl Daemon {
         self.config.persona.wake_word()
     }
 
+    /// Whisper provider for language-hinted transcription (requires an `OpenAI` key)
+    fn whisper_provider(&self) -> Option<Arc<crate::media::providers::WhisperProvider>> {
+        self.config.api_keys.openai.as_ref().map(|key| {
+            Arc::new(crate::media::providers::WhisperProvider::new(
+                key.clone(),
+                &crate::media::MediaConfig::default(),
+            ))
+        })
+    }
+
+    /// Wake word detector for the configured backend
+    ///
+    /// Falls back to STT matching when the Porcupine model cannot be loaded.
+    fn wake_word_detector(&self, wake_word: &str) -> Result<WakeWordDetector> {
+        let voice = &self.config.voice;
+        if voice.wake_word_backend == WakeWordBackend::Porcupine {
+            let loaded = voice
+                .porcupine_keyword_path
+                .as_ref()
+                .ok_or_else(|| Error::Config("BEACON_PORCUPINE_KEYWORD_PATH not set".to_string()))
+                .and_then(|path| {
+                    WakeWordDetector::with_porcupine(path, voice.porcupine_sensitivity)
+                });
+            match loaded {
+                Ok(detector) => return Ok(detector),
+                Err(e) => {
+                    tracing::warn!(error = %e, "porcupine unavailable, using STT wake word");
+                }
+            }
+        }
+        WakeWordDetector::new(vec![wake_word.to_string()])
+    }
+
     /// Initialize the Synapse AI router client
     ///
     /// Returns (client, `model_info`) - client is None only if the URL is invalid.
@@ -136,6 +180,13 @@ impl Daemon {
             self.config.api_server.vortex_url
         {
             tracing::info!(url = %vortex_url, "Vortex scheduling integration available");
+            if self.config.api_server.vortex_webhook_secret.is_none()
+                && !self.config.api_server.vortex_allow_unsigned
+            {
+                tracing::warn!(
+                    "VORTEX_WEBHOOK_SECRET is not set; Vortex callbacks will be rejected"
+                );
+            }
             let vortex_api_key = std::env::var("VORTEX_API_KEY").ok();
             let vortex_client = crate::integrations::VortexClient::new(vortex_url, vortex_api_key);
             let callback_url = self.config.api_server.public_url.as_deref().map_or_else(
@@ -164,9 +215,18 @@ impl Daemon {
         let plugin_manager: crate::api::plugins::SharedPluginManager = {
             let mut pm = crate::plugins::PluginManager::new();
             let dirs = crate::plugins::default_plugin_dirs();
-            let loaded = pm.load_all(&dirs);
-            if !loaded.is_empty() {
-                tracing::info!(count = loaded.len(), plugins = ?loaded, "loaded plugins");
+            let report = pm.load_all(&dirs);
+            if !report.loaded.is_empty() {
+                tracing::info!(
+                    count = report.loaded.len(),
+                    plugins = ?report.loaded,
+                    "loaded plugins"
+                );
+            }
+            if !report.errors.is_empty() {
+                let rejected: Vec<&str> =
+                    report.errors.iter().map(|e| e.plugin_id.as_str()).collect();
+                tracing::warn!(plugins = ?rejected, "rejected plugins");
             }
             Arc::new(tokio::sync::Mutex::new(pm))
         };
@@ -227,6 +287,16 @@ impl Daemon {
                     }
                 }
             }
+            // Record remote skills from manifests; bodies are fetched on first use
+            if !self.config.skills.remote_manifests.is_empty() {
+                let loader = crate::skills::RemoteSkillLoader::for_config(&self.config.skills);
+                let count = registry
+                    .discover_remote(&loader, &self.config.skills.remote_manifests)
+                    .await;
+                if count > 0 {
+                    tracing::info!(count, "discovered remote skills");
+                }
+            }
             if let Err(e) = crate::skills::sync_discovered_skills(&skill_repo, &registry) {
                 tracing::warn!(error = %e, "skill sync failed");
             }
@@ -311,49 +381,39 @@ impl Daemon {
         };
 
         // Resolve knowledge packs from Manifold and merge with inline chunks
-        let resolved_knowledge = if self.config.persona.knowledge.packs.is_empty() {
-            Vec::new()
-        } else {
-            let manifold_url = self
-                .config
+        let all_knowledge = crate::knowledge::load_persona_knowledge(
+            &self.config.persona,
+            self.config
                 .api_server
                 .manifold_url
                 .as_deref()
-                .unwrap_or("https://api.manifold.omni.dev");
-            let resolver = crate::knowledge::KnowledgePackResolver::new(
-                manifold_url,
-                self.config.knowledge_cache_dir.clone(),
-            );
-            let results = resolver
-                .resolve_all(&self.config.persona.knowledge.packs)
-                .await;
-            let mut extra_chunks = Vec::new();
-            for result in results {
-                match result {
-                    Ok(pack) => {
-                        tracing::info!(name = %pack.name, chunks = pack.chunks.len(), "loaded knowledge pack");
-                        extra_chunks.extend(pack.chunks);
-                    }
-                    Err(e) => {
-                        tracing::warn!(error = %e, "failed to resolve knowledge pack");
-                    }
-                }
-            }
-            extra_chunks
+                .unwrap_or("https://api.manifold.omni.dev"),
+            self.config.knowledge_cache_dir.clone(),
+        )
+        .await;
+
+        // Channel handlers and voice resolve skills for their own scope and
+        // read the persona shared with the API, so a reload reaches them too
+        let channel_prompt = ChannelPrompt {
+            persona_name: self.config.persona.name().to_string(),
+            persona: Arc::new(tokio::sync::RwLock::new(crate::api::ActivePersona {
+                id: self.config.persona.id().to_string(),
+                system_prompt: self.config.persona.system_prompt().map(String::from),
+            })),
+            knowledge: Arc::new(tokio::sync::RwLock::new(Arc::new(all_knowledge))),
+            skill_repo: skill_repo.clone(),
         };
 
-        // Merge inline knowledge with resolved pack knowledge
-        let mut all_knowledge = self.config.persona.knowledge.inline.clone();
-        all_knowledge.extend(resolved_knowledge);
-
         if synapse.is_none() {
             tracing::info!("running in setup mode - chat unavailable until Synapse is reachable");
         }
 
         // Spawn periodic memory sync if configured
         if let Some(ref sync_config) = self.config.sync {
-            let sync_client =
-                crate::sync::SyncClient::new(&sync_config.api_url, &sync_config.device_id);
+            let mut sync_client =
+                crate::sync::SyncClient::new(&sync_config.api_url, &sync_config.device_id)
+                    .with_merge_strategy(sync_config.merge_strategy);
+            let mut pending_encryption = sync_config.encryption.clone();
             let sync_db = self.db.clone();
             let sync_interval = sync_config.interval_secs;
 
@@ -361,6 +421,7 @@ impl Daemon {
                 api_url = %sync_config.api_url,
                 device_id = %sync_config.device_id,
                 interval_secs = sync_interval,
+                encrypted = sync_config.encryption.is_some(),
                 "memory sync enabled"
             );
 
@@ -371,33 +432,93 @@ impl Daemon {
 
                 loop {
                     interval.tick().await;
-                    if let Err(e) = sync_client.full_sync(&sync_db).await {
+                    // Encrypted sync fails closed: no key means no sync, never plaintext.
+                    // Passphrase salts live on the API, so the key is derived here
+                    if let Some(encryption) = &pending_encryption {
+                        match sync_client.clone().with_encryption(encryption).await {
+                            Ok(client) => {
+                                sync_client = client;
+                                pending_encryption = None;
+                            }
+                            Err(e) => {
+                                tracing::warn!(
+                                    error = %e,
+                                    "sync encryption key unavailable, skipping sync"
+                                );
+                                continue;
+                            }
+                        }
+                    }
+                    if let Err(e) = sync_client.delta_sync(&sync_db).await {
                         tracing::warn!(error = %e, "memory sync failed");
                     }
                 }
             });
         }
 
-        // Set up shutdown signal
-        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
-        let shutdown_tx_clone = shutdown_tx.clone();
-        tokio::spawn(async move {
-            if tokio::signal::ctrl_c().await.is_ok() {
-                let _ = shutdown_tx_clone.send(()).await;
+        // Sweep expired memories hourly
+        {
+            let expiry_repo = db::MemoryRepo::new(self.db.clone());
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(3600));
+                loop {
+                    interval.tick().await;
+                    match expiry_repo.purge_expired() {
+                        Ok(0) => {}
+                        Ok(purged) => tracing::info!(purged, "expired memories purged"),
+                        Err(e) => tracing::warn!(error = %e, "memory expiry sweep failed"),
+                    }
+                }
+            });
+        }
+
+        // Set up shutdown signal; channel handlers are tracked so shutdown can drain them
+        let shutdown = CancellationToken::new();
+        let tasks = TaskTracker::new();
+        {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    tracing::info!("shutdown requested");
+                    shutdown.cancel();
+                }
+            });
+        }
+
+        // Cloud relays (stopped by the monitor on shutdown)
+        let (relay_handle, relay_status) = if self.config.relay.enabled {
+            let mut relay = crate::relay::RelayManager::new(self.config.relay.clone());
+            if let Err(e) = relay.start().await {
+                tracing::warn!(error = %e, "relay start failed");
             }
-        });
+            let status = relay.subscribe();
+            (Some(relay.monitor(shutdown.clone())), Some(status))
+        } else {
+            (None, None)
+        };
+
+        // Readiness tracker; channels record their connection outcome as they start
+        let readiness = Arc::new(crate::readiness::Readiness::new(
+            &self.config.readiness,
+            self.config.cloud_mode,
+        ));
 
         // Initialize Telegram channel(s) if configured
         // Webhook mode when BEACON_PUBLIC_URL is set, polling mode otherwise
         let telegram_token = self.config.api_keys.telegram.clone();
         let telegram_public_url = self.config.api_server.public_url.clone();
         let mut telegram_polling_rx: Option<tokio::sync::mpsc::Receiver<IncomingMessage>> = None;
+        // Bots with a registered webhook, deregistered on shutdown
+        let mut telegram_webhooks: Vec<TelegramChannel> = Vec::new();
+        let telegram_streaming = self.config.streaming.for_channel("telegram");
 
         let telegram = if let Some(token) = &telegram_token {
             if let Some(ref pub_url) = telegram_public_url {
                 // Webhook mode: create simple channel for API state
-                let mut tg = TelegramChannel::new(token.clone());
-                if let Err(e) = tg.connect().await {
+                let mut tg = TelegramChannel::new(token.clone()).with_streaming(telegram_streaming);
+                let connected = tg.connect().await;
+                readiness.record_channel("telegram", connected.is_ok());
+                if let Err(e) = connected {
                     tracing::error!(error = %e, "Telegram connect failed");
                     None
                 } else {
@@ -411,13 +532,20 @@ impl Daemon {
                         .and_then(|c| c.webhook_secret.as_deref());
                     if let Err(e) = tg.set_webhook(&webhook_url, webhook_secret).await {
                         tracing::error!(error = %e, "Telegram webhook registration failed");
+                    } else {
+                        telegram_webhooks.push(tg.clone());
                     }
                     Some(tg)
                 }
             } else {
                 // Polling mode: create channel with receiver
-                let (mut tg, rx) = TelegramChannel::with_receiver(token.clone());
-                if let Err(e) = tg.connect().await {
+                let (tg, rx) = TelegramChannel::with_receiver(token.clone());
+                let mut tg = tg
+                    .with_streaming(telegram_streaming)
+                    .with_polling_state(self.config.data_dir.join("telegram-polling.json"));
+                let connected = tg.connect().await;
+                readiness.record_channel("telegram", connected.is_ok());
+                if let Err(e) = connected {
                     tracing::error!(error = %e, "Telegram connect failed");
                     None
                 } else {
@@ -483,7 +611,8 @@ impl Daemon {
                     let base_url = telegram_public_url.as_deref();
                     if let Some(base) = base_url {
                         // Webhook mode for this account
-                        let mut tg = TelegramChannel::new(acct_config.bot_token.clone());
+                        let mut tg = TelegramChannel::new(acct_config.bot_token.clone())
+                            .with_streaming(telegram_streaming);
                         if let Err(e) = tg.connect().await {
                             tracing::error!(account = %acct_id, error = %e, "Telegram account connect failed");
                             continue;
@@ -499,6 +628,8 @@ impl Daemon {
                             .and_then(|c| c.webhook_secret.as_deref());
                         if let Err(e) = tg.set_webhook(&webhook_url, webhook_secret).await {
                             tracing::error!(account = %acct_id, error = %e, "Telegram account webhook failed");
+                        } else {
+                            telegram_webhooks.push(tg.clone());
                         }
                         accounts.insert(
                             acct_id.clone(),
@@ -510,8 +641,13 @@ impl Daemon {
                         );
                     } else {
                         // Polling mode for this account
-                        let (mut tg, _rx) =
+                        let (tg, _rx) =
                             TelegramChannel::with_receiver(acct_config.bot_token.clone());
+                        let mut tg = tg.with_streaming(telegram_streaming).with_polling_state(
+                            self.config
+                                .data_dir
+                                .join(format!("telegram-polling-{acct_id}.json")),
+                        );
                         if let Err(e) = tg.connect().await {
                             tracing::error!(account = %acct_id, error = %e, "Telegram account connect failed");
                             continue;
@@ -549,7 +685,7 @@ impl Daemon {
 
         // Sync bot commands with Telegram (skill-derived command menu)
         let skill_commands: Vec<crate::channels::BotCommand> = skill_repo
-            .list_enabled_for_user(None)
+            .list_enabled_for_user(None, Some("telegram"))
             .unwrap_or_default()
             .iter()
             .filter(|s| s.skill.metadata.user_invocable)
@@ -587,12 +723,27 @@ impl Daemon {
             .as_ref()
             .and_then(|key| VisionClient::new(key.clone()).map(Arc::new).ok());
 
-        // Create attachment processor with vision and Synapse (for audio transcription)
-        let attachment_processor = Arc::new(AttachmentProcessor::new(
+        // Create attachment processor with vision, the media provider chain as
+        // its fallback, and Synapse (for audio transcription)
+        let mut attachment_processor = AttachmentProcessor::new(
             vision,
             synapse.as_ref().map(Arc::clone),
             self.config.voice.stt_model.clone(),
-        ));
+        )
+        .with_cache(crate::media::MediaCache::new(
+            &crate::media::MediaCacheConfig::from_env(),
+        ))
+        .with_media(crate::media::MediaProcessor::from_api_keys(
+            crate::media::MediaConfig::default(),
+            &self.config.api_keys,
+        ))
+        .with_stt_language(self.config.voice.stt_language.clone())
+        .with_video_keyframes(crate::attachments::keyframes_from_env())
+        .with_limits(crate::attachments::AttachmentLimits::from_env());
+        if let Some(whisper) = self.whisper_provider() {
+            attachment_processor = attachment_processor.with_whisper(whisper);
+        }
+        let attachment_processor = Arc::new(attachment_processor);
 
         // Construct local key store for self-hosted provider management
         let local_key_store = crate::providers::LocalKeyStore::new(self.db.clone());
@@ -611,11 +762,15 @@ impl Daemon {
         .api_key(self.config.api_server.api_key.clone())
         .manifold_url(self.config.api_server.manifold_url.clone())
         .static_dir(self.config.api_server.static_dir.clone())
-        .persona_knowledge(all_knowledge.clone())
+        .active_persona(Arc::clone(&channel_prompt.persona))
+        .shared_knowledge(Arc::clone(&channel_prompt.knowledge))
         .max_context_tokens(self.config.persona.memory.max_context_tokens)
         .knowledge_cache_dir(self.config.knowledge_cache_dir.clone())
         .plugin_manager(plugin_manager.clone())
         .cloud_mode(self.config.cloud_mode)
+        .require_chat_auth(self.config.api_server.require_chat_auth)
+        .route_scopes(&self.config.api_server.route_scopes)
+        .idempotency_ttl(self.config.api_server.idempotency_ttl)
         .skills_config(self.config.skills.clone());
 
         if let Some(ref mcp) = mcp_manager {
@@ -643,22 +798,68 @@ impl Daemon {
         if let Some(ref tg_config) = self.config.telegram {
             api_builder = api_builder.telegram_config(tg_config.clone());
         }
+        api_builder =
+            api_builder.telegram_dedup_path(self.config.data_dir.join("telegram-webhook.json"));
         if let Some(registry) = telegram_registry {
             api_builder = api_builder.telegram_registry(registry);
         }
         if let Some(ref ct) = cron_tools {
             api_builder = api_builder.cron_tools(Arc::clone(ct));
         }
+        api_builder = api_builder
+            .vortex_webhook_secret(self.config.api_server.vortex_webhook_secret.clone())
+            .vortex_allow_unsigned(self.config.api_server.vortex_allow_unsigned)
+            .tool_output(self.config.tool_output.clone());
 
-        // Initialize session compactor when Synapse is available
-        if let Some(ref synapse) = synapse {
-            let compact_config = crate::context::CompactionConfig::from_env();
-            let compactor = Arc::new(crate::context::SessionCompactor::new(
-                compact_config,
+        // Send-only adapters so scheduled callbacks can reach channels owned by their handlers
+        if let Some(token) = &self.config.api_keys.discord {
+            api_builder =
+                api_builder.outbound_channel(Arc::new(DiscordChannel::send_only(token.clone())));
+        }
+        if let Some(token) = &self.config.api_keys.slack {
+            api_builder = api_builder.outbound_channel(Arc::new(SlackChannel::new(token.clone())));
+        }
+
+        // Extensions provide send-only channels; reloads swap them in place
+        let mut extensions = crate::extensions::ExtensionRegistry::from_env();
+        if let Err(e) = extensions.load_all() {
+            tracing::warn!(error = %e, "failed to load extensions");
+        }
+        api_builder = api_builder.extension_channels(extensions.channel_map());
+        let extensions = Arc::new(tokio::sync::Mutex::new(extensions));
+        let extension_watch = match Arc::clone(&extensions).watch().await {
+            Ok(handle) => handle,
+            Err(e) => {
+                tracing::warn!(error = %e, "extension hot-reload unavailable");
+                None
+            }
+        };
+
+        // Generic webhook channel; the API server feeds its inbound endpoint
+        let mut generic_webhook = None;
+        if let Some(url) = &self.config.api_keys.webhook_url {
+            let secret = self.config.api_keys.webhook_secret.clone();
+            let (webhook, rx) = WebhookChannel::with_receiver(url.clone(), secret.clone());
+            if let Some(inbound) = webhook.inbound() {
+                api_builder = api_builder.generic_webhook(inbound);
+            } else {
+                tracing::warn!("WEBHOOK_SECRET not set, generic webhook inbound endpoint disabled");
+            }
+            api_builder =
+                api_builder.outbound_channel(Arc::new(WebhookChannel::new(url.clone(), secret)));
+            generic_webhook = Some((webhook, rx));
+        }
+
+        // Session compactor when Synapse is available, shared with channel handlers
+        let session_compactor = synapse.as_ref().map(|synapse| {
+            Arc::new(crate::context::SessionCompactor::new(
+                crate::context::CompactionConfig::from_env(),
                 Arc::clone(synapse),
                 model_id.clone(),
-            ));
-            api_builder = api_builder.session_compactor(compactor);
+            ))
+        });
+        if let Some(ref compactor) = session_compactor {
+            api_builder = api_builder.session_compactor(Arc::clone(compactor));
         }
 
         api_builder = api_builder.voice_config(&self.config.voice);
@@ -678,16 +879,159 @@ impl Daemon {
         api_builder = api_builder.local_key_store(local_key_store);
 
         // Initialize pairing manager (before API build so webhook can use it)
-        let pairing_manager = Arc::new(PairingManager::new(self.config.dm_policy, self.db.clone()));
+        let pairing_manager = Arc::new(
+            PairingManager::new(self.config.dm_policy, self.db.clone())
+                .with_lockout(self.config.auth.pairing_lockout),
+        );
         tracing::info!(policy = %self.config.dm_policy, "DM security policy");
 
+        // Sweep expired pairing codes hourly
+        if self.config.dm_policy == DmPolicy::Pairing {
+            let pairing = Arc::clone(&pairing_manager);
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(3600));
+                loop {
+                    interval.tick().await;
+                    match pairing.sweep_expired() {
+                        Ok(0) => {}
+                        Ok(swept) => tracing::info!(swept, "expired pairing codes swept"),
+                        Err(e) => tracing::warn!(error = %e, "pairing code sweep failed"),
+                    }
+                }
+            });
+        }
+
         // Initialize hook manager (before API build so webhook can use it)
         let hook_manager = Arc::new(HookManager::new(&self.config.hooks, &self.config.data_dir));
 
+        // Maintenance toggle shared by the admin API and channel handlers
+        let maintenance = Arc::new(crate::maintenance::MaintenanceMode::new(
+            &self.config.maintenance,
+        ));
+        if maintenance.is_enabled() {
+            tracing::warn!("starting in maintenance mode - agent replies are paused");
+        }
+
+        // Daily usage caps shared by the admin API and channel handlers
+        let usage_cap = Arc::new(crate::usage::UsageCap::new(
+            self.db.clone(),
+            self.config.usage_cap.clone(),
+        ));
+
+        // Fails Synapse calls fast during an outage, shared with `/ready`
+        let synapse_breaker = Arc::new(crate::synapse::CircuitBreaker::new(
+            crate::synapse::CircuitBreakerConfig::from_env(),
+        ));
+
+        // One `WebFetch` cache for the API and every channel handler
+        let fetch_cache = Arc::new(crate::tools::WebFetchCache::new(
+            crate::tools::WebFetchCacheConfig::from_env(),
+        ));
+
         api_builder = api_builder
             .hook_manager(Arc::clone(&hook_manager))
             .pairing_manager(Arc::clone(&pairing_manager))
-            .attachment_processor(Arc::clone(&attachment_processor));
+            .attachment_processor(Arc::clone(&attachment_processor))
+            .maintenance(Arc::clone(&maintenance))
+            .memory_scope(self.config.memory_scope)
+            .memory_ttl(self.config.memory_ttl)
+            .readiness(Arc::clone(&readiness))
+            .usage_cap(Arc::clone(&usage_cap))
+            .synapse_breaker(Arc::clone(&synapse_breaker))
+            .fetch_cache(Arc::clone(&fetch_cache));
+        if let Some(status) = relay_status {
+            api_builder = api_builder.relay_status(status);
+        }
+
+        // Node registry, persisted so known devices survive restarts as stale
+        let node_registry = if self.config.api_server.persist_nodes {
+            crate::nodes::NodeRegistry::with_store(db::NodeRepo::new(self.db.clone()))
+        } else {
+            crate::nodes::NodeRegistry::new()
+        };
+        let node_registry = Arc::new(tokio::sync::Mutex::new(
+            node_registry.with_deny_list(
+                self.config
+                    .api_server
+                    .node_deny_commands
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+        ));
+        {
+            let registry = Arc::clone(&node_registry);
+            let max_age = self.config.api_server.node_stale_ttl;
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(3600));
+                loop {
+                    interval.tick().await;
+                    let pruned = registry.lock().await.prune_stale(max_age);
+                    if pruned > 0 {
+                        tracing::info!(pruned, "stale node registrations pruned");
+                    }
+                }
+            });
+        }
+        api_builder = api_builder.node_registry(node_registry);
+
+        if self.config.auth.mode == crate::security::AuthMode::ApiKeyPerUser {
+            tracing::info!("per-user API key authentication enabled");
+            api_builder = api_builder.user_api_keys(db::UserApiKeyRepo::new(self.db.clone()));
+        }
+
+        // Device pairing needs the gateway's own identity for mutual auth
+        let mut device_id = format!("beacon-{}", self.config.persona.id());
+        match crate::security::DeviceIdentity::load_or_create(
+            &crate::security::DeviceIdentity::default_path(),
+            "beacon-gateway",
+        ) {
+            Ok(identity) => {
+                device_id.clone_from(&identity.device_id);
+                api_builder =
+                    api_builder.device_pairing(Arc::new(crate::api::pairing::PairingState::new(
+                        crate::security::DeviceManager::new(self.db.clone()),
+                        identity,
+                    )));
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "device identity unavailable, device pairing disabled");
+            }
+        }
+
+        // Local discovery, re-advertised when the host address changes
+        let mdns_handle = if self.config.api_server.mdns {
+            match crate::discovery::MdnsAdvertiser::new() {
+                Ok(advertiser) => {
+                    let advertiser = Arc::new(advertiser);
+                    match advertiser
+                        .start(
+                            self.config.persona.id(),
+                            &device_id,
+                            self.config.api_server.port,
+                            synapse.is_some(),
+                            false,
+                        )
+                        .await
+                    {
+                        Ok(()) => {
+                            api_builder = api_builder.mdns(Arc::clone(&advertiser));
+                            Some(advertiser.monitor(shutdown.clone()))
+                        }
+                        Err(e) => {
+                            tracing::warn!(error = %e, "mDNS advertisement failed");
+                            None
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "mDNS unavailable");
+                    None
+                }
+            }
+        } else {
+            None
+        };
 
         let api_server = api_builder.build();
         let _api_handle = api_server.spawn();
@@ -698,16 +1042,25 @@ impl Daemon {
             self.start_channels(
                 Arc::clone(synapse),
                 model_id.clone(),
-                system_prompt.clone(),
+                channel_prompt.clone(),
                 MAX_TOKENS,
                 Arc::clone(&tool_policy),
                 Arc::clone(&pairing_manager),
                 Arc::clone(&attachment_processor),
                 Arc::clone(&hook_manager),
                 plugin_manager.clone(),
-                all_knowledge,
                 telegram_for_polling,
                 telegram_polling_rx,
+                generic_webhook,
+                cron_tools,
+                maintenance,
+                &readiness,
+                usage_cap,
+                synapse_breaker,
+                fetch_cache,
+                session_compactor,
+                &shutdown,
+                &tasks,
             )
             .await;
         } else {
@@ -720,10 +1073,10 @@ impl Daemon {
             self.run_voice_loop(
                 Arc::clone(syn),
                 model_id,
-                system_prompt,
+                channel_prompt.build(&channel_prompt.persona().await, None, "voice"),
                 MAX_TOKENS,
                 Arc::clone(&tool_policy),
-                &mut shutdown_rx,
+                &shutdown,
                 plugin_manager,
             )
             .await?;
@@ -733,9 +1086,55 @@ impl Daemon {
             } else {
                 tracing::info!("voice disabled - running in messaging-only mode");
             }
-            shutdown_rx.recv().await;
+            shutdown.cancelled().await;
+        }
+
+        // Let channel handlers finish in-flight replies, bounded by SHUTDOWN_DRAIN_TIMEOUT
+        tasks.close();
+        let in_flight = tasks.len();
+        let drained = tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, tasks.wait())
+            .await
+            .is_ok();
+        if drained {
+            tracing::info!(handlers = in_flight, "channel handlers drained");
+        } else {
+            tracing::warn!(
+                remaining = tasks.len(),
+                "shutdown drain timed out, abandoning in-flight replies"
+            );
+        }
+
+        if let Some(handle) = extension_watch {
+            handle.abort();
+        }
+        extensions.lock().await.shutdown_all();
+
+        for tg in &telegram_webhooks {
+            if let Err(e) = tg.delete_webhook().await {
+                tracing::warn!(error = %e, "failed to deregister Telegram webhook");
+            }
+        }
+
+        if let Some(handle) = relay_handle
+            && let Err(e) = handle.await
+        {
+            tracing::warn!(error = %e, "relay monitor task failed");
+        }
+
+        if let Some(handle) = mdns_handle
+            && let Err(e) = handle.await
+        {
+            tracing::warn!(error = %e, "mDNS monitor task failed");
         }
 
+        crate::events::publish(crate::events::build_gateway_stopped_event(
+            self.config.persona.id(),
+            drained,
+        ));
+
+        // Deliver any buffered OmniEvents before exiting
+        crate::events::flush().await;
+
         tracing::info!("daemon stopped");
         Ok(())
     }
@@ -746,26 +1145,36 @@ impl Daemon {
         &self,
         synapse: Arc<SynapseClient>,
         model_id: String,
-        system_prompt: String,
+        system_prompt: ChannelPrompt,
         max_tokens: u32,
         tool_policy: Arc<crate::tools::ToolPolicy>,
         pairing_manager: Arc<PairingManager>,
         attachment_processor: Arc<AttachmentProcessor>,
         hook_manager: Arc<HookManager>,
         plugin_manager: crate::api::plugins::SharedPluginManager,
-        knowledge_chunks: Vec<crate::persona::KnowledgeChunk>,
         telegram: Option<TelegramChannel>,
         telegram_polling_rx: Option<tokio::sync::mpsc::Receiver<IncomingMessage>>,
+        generic_webhook: Option<(WebhookChannel, tokio::sync::mpsc::Receiver<IncomingMessage>)>,
+        cron_tools: Option<Arc<crate::tools::BuiltinCronTools>>,
+        maintenance: Arc<crate::maintenance::MaintenanceMode>,
+        readiness: &crate::readiness::Readiness,
+        usage_cap: Arc<crate::usage::UsageCap>,
+        synapse_breaker: Arc<crate::synapse::CircuitBreaker>,
+        fetch_cache: Arc<crate::tools::WebFetchCache>,
+        session_compactor: Option<Arc<crate::context::SessionCompactor>>,
+        shutdown: &CancellationToken,
+        tasks: &TaskTracker,
     ) {
-        let persona_id = self.config.persona.id().to_string();
-        let persona_system_prompt = self.config.persona.system_prompt().map(String::from);
         let max_context_tokens = self.config.persona.memory.max_context_tokens;
 
         // Discord
         if let Some(token) = &self.config.api_keys.discord {
-            let (mut discord, rx) = DiscordChannel::with_receiver(token.clone());
+            let (discord, rx) = DiscordChannel::with_receiver(token.clone());
+            let mut discord = discord.with_streaming(self.config.streaming.for_channel("discord"));
 
-            if let Err(e) = discord.connect().await {
+            let connected = discord.connect().await;
+            readiness.record_channel("discord", connected.is_ok());
+            if let Err(e) = connected {
                 tracing::error!(error = %e, "Discord connect failed");
             } else {
                 let synapse = Arc::clone(&synapse);
@@ -773,16 +1182,28 @@ impl Daemon {
                 let system_prompt = system_prompt.clone();
                 let session_repo = SessionRepo::new(self.db.clone());
                 let user_repo = UserRepo::new(self.db.clone());
-                let memory_repo = db::MemoryRepo::new(self.db.clone());
-                let persona_id = persona_id.clone();
-                let persona_system_prompt = persona_system_prompt.clone();
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
                 let policy = Arc::clone(&tool_policy);
                 let pairing = Arc::clone(&pairing_manager);
                 let attachments = Arc::clone(&attachment_processor);
                 let hooks = Arc::clone(&hook_manager);
-                let knowledge = knowledge_chunks.clone();
                 let pm = plugin_manager.clone();
-                tokio::spawn(async move {
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("discord");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let discord = crate::channels::RateLimitedChannel::new(
+                    Box::new(discord),
+                    crate::channels::RateLimitPolicy::discord(),
+                );
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
                     handle_channel_messages(
                         "discord",
                         rx,
@@ -794,16 +1215,22 @@ impl Daemon {
                         session_repo,
                         user_repo,
                         memory_repo,
-                        persona_id,
-                        persona_system_prompt,
                         policy,
                         pairing,
                         attachments,
                         hooks,
-                        knowledge,
                         max_context_tokens,
                         pm,
                         None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
                     )
                     .await;
                 });
@@ -812,9 +1239,12 @@ impl Daemon {
 
         // Slack
         if let Some(token) = &self.config.api_keys.slack {
-            let (mut slack, rx) = SlackChannel::with_receiver(token.clone());
+            let (slack, rx) = SlackChannel::with_receiver(token.clone());
+            let mut slack = slack.with_streaming(self.config.streaming.for_channel("slack"));
 
-            if let Err(e) = slack.connect().await {
+            let connected = slack.connect().await;
+            readiness.record_channel("slack", connected.is_ok());
+            if let Err(e) = connected {
                 tracing::error!(error = %e, "Slack connect failed");
             } else {
                 let synapse = Arc::clone(&synapse);
@@ -822,16 +1252,28 @@ impl Daemon {
                 let system_prompt = system_prompt.clone();
                 let session_repo = SessionRepo::new(self.db.clone());
                 let user_repo = UserRepo::new(self.db.clone());
-                let memory_repo = db::MemoryRepo::new(self.db.clone());
-                let persona_id = persona_id.clone();
-                let persona_system_prompt = persona_system_prompt.clone();
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
                 let policy = Arc::clone(&tool_policy);
                 let pairing = Arc::clone(&pairing_manager);
                 let attachments = Arc::clone(&attachment_processor);
                 let hooks = Arc::clone(&hook_manager);
-                let knowledge = knowledge_chunks.clone();
                 let pm = plugin_manager.clone();
-                tokio::spawn(async move {
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("slack");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let slack = crate::channels::RateLimitedChannel::new(
+                    Box::new(slack),
+                    crate::channels::RateLimitPolicy::slack(),
+                );
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
                     handle_channel_messages(
                         "slack",
                         rx,
@@ -843,16 +1285,22 @@ impl Daemon {
                         session_repo,
                         user_repo,
                         memory_repo,
-                        persona_id,
-                        persona_system_prompt,
                         policy,
                         pairing,
                         attachments,
                         hooks,
-                        knowledge,
                         max_context_tokens,
                         pm,
                         None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
                     )
                     .await;
                 });
@@ -867,7 +1315,9 @@ impl Daemon {
             let (mut whatsapp, rx) =
                 WhatsAppChannel::with_receiver(token.clone(), phone_id.clone());
 
-            if let Err(e) = whatsapp.connect().await {
+            let connected = whatsapp.connect().await;
+            readiness.record_channel("whatsapp", connected.is_ok());
+            if let Err(e) = connected {
                 tracing::error!(error = %e, "WhatsApp connect failed");
             } else {
                 let synapse = Arc::clone(&synapse);
@@ -875,16 +1325,24 @@ impl Daemon {
                 let system_prompt = system_prompt.clone();
                 let session_repo = SessionRepo::new(self.db.clone());
                 let user_repo = UserRepo::new(self.db.clone());
-                let memory_repo = db::MemoryRepo::new(self.db.clone());
-                let persona_id = persona_id.clone();
-                let persona_system_prompt = persona_system_prompt.clone();
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
                 let policy = Arc::clone(&tool_policy);
                 let pairing = Arc::clone(&pairing_manager);
                 let attachments = Arc::clone(&attachment_processor);
                 let hooks = Arc::clone(&hook_manager);
-                let knowledge = knowledge_chunks.clone();
                 let pm = plugin_manager.clone();
-                tokio::spawn(async move {
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("whatsapp");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
                     handle_channel_messages(
                         "whatsapp",
                         rx,
@@ -896,16 +1354,22 @@ impl Daemon {
                         session_repo,
                         user_repo,
                         memory_repo,
-                        persona_id,
-                        persona_system_prompt,
                         policy,
                         pairing,
                         attachments,
                         hooks,
-                        knowledge,
                         max_context_tokens,
                         pm,
                         None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
                     )
                     .await;
                 });
@@ -919,7 +1383,9 @@ impl Daemon {
         ) {
             let (mut signal, rx) = SignalChannel::with_receiver(api_url.clone(), phone.clone());
 
-            if let Err(e) = signal.connect().await {
+            let connected = signal.connect().await;
+            readiness.record_channel("signal", connected.is_ok());
+            if let Err(e) = connected {
                 tracing::error!(error = %e, "Signal connect failed");
             } else {
                 // Spawn polling loop to fetch incoming messages
@@ -930,16 +1396,24 @@ impl Daemon {
                 let system_prompt = system_prompt.clone();
                 let session_repo = SessionRepo::new(self.db.clone());
                 let user_repo = UserRepo::new(self.db.clone());
-                let memory_repo = db::MemoryRepo::new(self.db.clone());
-                let persona_id = persona_id.clone();
-                let persona_system_prompt = persona_system_prompt.clone();
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
                 let policy = Arc::clone(&tool_policy);
                 let pairing = Arc::clone(&pairing_manager);
                 let attachments = Arc::clone(&attachment_processor);
                 let hooks = Arc::clone(&hook_manager);
-                let knowledge = knowledge_chunks.clone();
                 let pm = plugin_manager.clone();
-                tokio::spawn(async move {
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("signal");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
                     handle_channel_messages(
                         "signal",
                         rx,
@@ -951,16 +1425,22 @@ impl Daemon {
                         session_repo,
                         user_repo,
                         memory_repo,
-                        persona_id,
-                        persona_system_prompt,
                         policy,
                         pairing,
                         attachments,
                         hooks,
-                        knowledge,
                         max_context_tokens,
                         pm,
                         None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
                     )
                     .await;
                 });
@@ -977,7 +1457,9 @@ impl Daemon {
                 self.config.imessage.service.clone(),
             );
 
-            if let Err(e) = imessage.connect().await {
+            let connected = imessage.connect().await;
+            readiness.record_channel("imessage", connected.is_ok());
+            if let Err(e) = connected {
                 tracing::error!(error = %e, "iMessage connect failed");
             } else {
                 let synapse = Arc::clone(&synapse);
@@ -985,16 +1467,24 @@ impl Daemon {
                 let system_prompt = system_prompt.clone();
                 let session_repo = SessionRepo::new(self.db.clone());
                 let user_repo = UserRepo::new(self.db.clone());
-                let memory_repo = db::MemoryRepo::new(self.db.clone());
-                let persona_id = persona_id.clone();
-                let persona_system_prompt = persona_system_prompt.clone();
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
                 let policy = Arc::clone(&tool_policy);
                 let pairing = Arc::clone(&pairing_manager);
                 let attachments = Arc::clone(&attachment_processor);
                 let hooks = Arc::clone(&hook_manager);
-                let knowledge = knowledge_chunks.clone();
                 let pm = plugin_manager.clone();
-                tokio::spawn(async move {
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("imessage");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
                     handle_channel_messages(
                         "imessage",
                         rx,
@@ -1006,16 +1496,22 @@ impl Daemon {
                         session_repo,
                         user_repo,
                         memory_repo,
-                        persona_id,
-                        persona_system_prompt,
                         policy,
                         pairing,
                         attachments,
                         hooks,
-                        knowledge,
                         max_context_tokens,
                         pm,
                         None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
                     )
                     .await;
                 });
@@ -1034,7 +1530,9 @@ impl Daemon {
                 user_id.clone(),
             );
 
-            if let Err(e) = matrix.connect().await {
+            let connected = matrix.connect().await;
+            readiness.record_channel("matrix", connected.is_ok());
+            if let Err(e) = connected {
                 tracing::error!(error = %e, "Matrix connect failed");
             } else {
                 let synapse = Arc::clone(&synapse);
@@ -1042,16 +1540,24 @@ impl Daemon {
                 let system_prompt = system_prompt.clone();
                 let session_repo = SessionRepo::new(self.db.clone());
                 let user_repo = UserRepo::new(self.db.clone());
-                let memory_repo = db::MemoryRepo::new(self.db.clone());
-                let persona_id = persona_id.clone();
-                let persona_system_prompt = persona_system_prompt.clone();
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
                 let policy = Arc::clone(&tool_policy);
                 let pairing = Arc::clone(&pairing_manager);
                 let attachments = Arc::clone(&attachment_processor);
                 let hooks = Arc::clone(&hook_manager);
-                let knowledge = knowledge_chunks.clone();
                 let pm = plugin_manager.clone();
-                tokio::spawn(async move {
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("matrix");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
                     handle_channel_messages(
                         "matrix",
                         rx,
@@ -1063,175 +1569,439 @@ impl Daemon {
                         session_repo,
                         user_repo,
                         memory_repo,
-                        persona_id,
-                        persona_system_prompt,
                         policy,
                         pairing,
                         attachments,
                         hooks,
-                        knowledge,
                         max_context_tokens,
                         pm,
                         None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
                     )
                     .await;
                 });
             }
         }
 
-        // Microsoft Teams
-        if let (Some(tenant_id), Some(client_id), Some(client_secret), Some(bot_id)) = (
-            &self.config.api_keys.teams_tenant_id,
-            &self.config.api_keys.teams_client_id,
-            &self.config.api_keys.teams_client_secret,
-            &self.config.api_keys.teams_bot_id,
+        // Mastodon
+        if let (Some(instance_url), Some(access_token)) = (
+            &self.config.api_keys.mastodon_instance_url,
+            &self.config.api_keys.mastodon_access_token,
         ) {
-            let (mut teams, rx) = TeamsChannel::with_receiver(
-                tenant_id.clone(),
-                client_id.clone(),
-                client_secret.clone(),
-                bot_id.clone(),
-            );
+            let (mut mastodon, rx) =
+                MastodonChannel::with_receiver(instance_url, access_token.clone());
 
-            if let Err(e) = teams.connect().await {
-                tracing::error!(error = %e, "Teams connect failed");
+            let connected = mastodon.connect().await;
+            readiness.record_channel("mastodon", connected.is_ok());
+            if let Err(e) = connected {
+                tracing::error!(error = %e, "Mastodon connect failed");
             } else {
                 let synapse = Arc::clone(&synapse);
                 let model_id = model_id.clone();
                 let system_prompt = system_prompt.clone();
                 let session_repo = SessionRepo::new(self.db.clone());
                 let user_repo = UserRepo::new(self.db.clone());
-                let memory_repo = db::MemoryRepo::new(self.db.clone());
-                let persona_id = persona_id.clone();
-                let persona_system_prompt = persona_system_prompt.clone();
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
                 let policy = Arc::clone(&tool_policy);
                 let pairing = Arc::clone(&pairing_manager);
                 let attachments = Arc::clone(&attachment_processor);
                 let hooks = Arc::clone(&hook_manager);
-                let knowledge = knowledge_chunks.clone();
                 let pm = plugin_manager.clone();
-                tokio::spawn(async move {
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("mastodon");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
                     handle_channel_messages(
-                        "teams",
+                        "mastodon",
                         rx,
                         synapse,
                         model_id,
                         system_prompt,
                         max_tokens,
-                        teams,
+                        mastodon,
                         session_repo,
                         user_repo,
                         memory_repo,
-                        persona_id,
-                        persona_system_prompt,
                         policy,
                         pairing,
                         attachments,
                         hooks,
-                        knowledge,
                         max_context_tokens,
                         pm,
                         None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
                     )
                     .await;
                 });
             }
         }
 
-        // Google Chat
-        if let Some(service_account_path) = &self.config.api_keys.google_chat_service_account {
-            let (mut google_chat, rx) =
-                GoogleChatChannel::with_receiver(service_account_path.clone());
+        // IRC
+        if let (Some(server), Some(nick)) = (
+            &self.config.api_keys.irc_server,
+            &self.config.api_keys.irc_nick,
+        ) {
+            let tls = self.config.api_keys.irc_tls;
+            let (mut irc, rx) = IrcChannel::with_receiver(IrcConfig {
+                server: server.clone(),
+                port: self
+                    .config
+                    .api_keys
+                    .irc_port
+                    .unwrap_or(if tls { 6697 } else { 6667 }),
+                tls,
+                nick: nick.clone(),
+                password: self.config.api_keys.irc_password.clone(),
+                channels: self.config.api_keys.irc_channels.clone(),
+            });
 
-            if let Err(e) = google_chat.connect().await {
-                tracing::error!(error = %e, "Google Chat connect failed");
+            let connected = irc.connect().await;
+            readiness.record_channel("irc", connected.is_ok());
+            if let Err(e) = connected {
+                tracing::error!(error = %e, "IRC connect failed");
             } else {
                 let synapse = Arc::clone(&synapse);
                 let model_id = model_id.clone();
                 let system_prompt = system_prompt.clone();
                 let session_repo = SessionRepo::new(self.db.clone());
                 let user_repo = UserRepo::new(self.db.clone());
-                let memory_repo = db::MemoryRepo::new(self.db.clone());
-                let persona_id = persona_id.clone();
-                let persona_system_prompt = persona_system_prompt.clone();
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
                 let policy = Arc::clone(&tool_policy);
                 let pairing = Arc::clone(&pairing_manager);
                 let attachments = Arc::clone(&attachment_processor);
                 let hooks = Arc::clone(&hook_manager);
-                let knowledge = knowledge_chunks.clone();
                 let pm = plugin_manager.clone();
-                tokio::spawn(async move {
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("irc");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
                     handle_channel_messages(
-                        "google_chat",
+                        "irc",
                         rx,
                         synapse,
                         model_id,
                         system_prompt,
                         max_tokens,
-                        google_chat,
+                        irc,
                         session_repo,
                         user_repo,
                         memory_repo,
-                        persona_id,
-                        persona_system_prompt,
                         policy,
                         pairing,
                         attachments,
                         hooks,
-                        knowledge,
                         max_context_tokens,
                         pm,
                         None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
                     )
                     .await;
                 });
             }
         }
 
-        // Telegram (polling mode — only when no public URL for webhooks)
-        if let (Some(tg), Some(rx)) = (telegram, telegram_polling_rx) {
-            drop(tg.start_polling(std::time::Duration::from_secs(1)));
-
-            let synapse = Arc::clone(&synapse);
-            let model_id = model_id.clone();
-            let system_prompt = system_prompt.clone();
-            let session_repo = SessionRepo::new(self.db.clone());
-            let user_repo = UserRepo::new(self.db.clone());
-            let memory_repo = db::MemoryRepo::new(self.db.clone());
-            let persona_id = persona_id.clone();
-            let persona_system_prompt = persona_system_prompt.clone();
-            let policy = Arc::clone(&tool_policy);
-            let pairing = Arc::clone(&pairing_manager);
-            let attachments = Arc::clone(&attachment_processor);
-            let hooks = Arc::clone(&hook_manager);
-            let knowledge = knowledge_chunks.clone();
-            let pm = plugin_manager.clone();
-            let tg_config = self.config.telegram.clone();
-            tokio::spawn(async move {
-                handle_channel_messages(
-                    "telegram",
-                    rx,
-                    synapse,
-                    model_id,
-                    system_prompt,
-                    max_tokens,
-                    tg,
-                    session_repo,
-                    user_repo,
-                    memory_repo,
-                    persona_id,
-                    persona_system_prompt,
-                    policy,
-                    pairing,
-                    attachments,
-                    hooks,
-                    knowledge,
-                    max_context_tokens,
-                    pm,
-                    tg_config,
-                )
-                .await;
-            });
+        // Generic webhook
+        if let Some((mut webhook, rx)) = generic_webhook {
+            let connected = webhook.connect().await;
+            readiness.record_channel("webhook", connected.is_ok());
+            if let Err(e) = connected {
+                tracing::error!(error = %e, "webhook connect failed");
+            } else {
+                let synapse = Arc::clone(&synapse);
+                let model_id = model_id.clone();
+                let system_prompt = system_prompt.clone();
+                let session_repo = SessionRepo::new(self.db.clone());
+                let user_repo = UserRepo::new(self.db.clone());
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
+                let policy = Arc::clone(&tool_policy);
+                let pairing = Arc::clone(&pairing_manager);
+                let attachments = Arc::clone(&attachment_processor);
+                let hooks = Arc::clone(&hook_manager);
+                let pm = plugin_manager.clone();
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("webhook");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
+                    handle_channel_messages(
+                        "webhook",
+                        rx,
+                        synapse,
+                        model_id,
+                        system_prompt,
+                        max_tokens,
+                        webhook,
+                        session_repo,
+                        user_repo,
+                        memory_repo,
+                        policy,
+                        pairing,
+                        attachments,
+                        hooks,
+                        max_context_tokens,
+                        pm,
+                        None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
+                    )
+                    .await;
+                });
+            }
+        }
+
+        // Microsoft Teams
+        if let (Some(tenant_id), Some(client_id), Some(client_secret), Some(bot_id)) = (
+            &self.config.api_keys.teams_tenant_id,
+            &self.config.api_keys.teams_client_id,
+            &self.config.api_keys.teams_client_secret,
+            &self.config.api_keys.teams_bot_id,
+        ) {
+            let (mut teams, rx) = TeamsChannel::with_receiver(
+                tenant_id.clone(),
+                client_id.clone(),
+                client_secret.clone(),
+                bot_id.clone(),
+            );
+
+            let connected = teams.connect().await;
+            readiness.record_channel("teams", connected.is_ok());
+            if let Err(e) = connected {
+                tracing::error!(error = %e, "Teams connect failed");
+            } else {
+                let synapse = Arc::clone(&synapse);
+                let model_id = model_id.clone();
+                let system_prompt = system_prompt.clone();
+                let session_repo = SessionRepo::new(self.db.clone());
+                let user_repo = UserRepo::new(self.db.clone());
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
+                let policy = Arc::clone(&tool_policy);
+                let pairing = Arc::clone(&pairing_manager);
+                let attachments = Arc::clone(&attachment_processor);
+                let hooks = Arc::clone(&hook_manager);
+                let pm = plugin_manager.clone();
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("teams");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
+                    handle_channel_messages(
+                        "teams",
+                        rx,
+                        synapse,
+                        model_id,
+                        system_prompt,
+                        max_tokens,
+                        teams,
+                        session_repo,
+                        user_repo,
+                        memory_repo,
+                        policy,
+                        pairing,
+                        attachments,
+                        hooks,
+                        max_context_tokens,
+                        pm,
+                        None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
+                    )
+                    .await;
+                });
+            }
+        }
+
+        // Google Chat
+        if let Some(service_account_path) = &self.config.api_keys.google_chat_service_account {
+            let (mut google_chat, rx) =
+                GoogleChatChannel::with_receiver(service_account_path.clone());
+
+            let connected = google_chat.connect().await;
+            readiness.record_channel("google_chat", connected.is_ok());
+            if let Err(e) = connected {
+                tracing::error!(error = %e, "Google Chat connect failed");
+            } else {
+                let synapse = Arc::clone(&synapse);
+                let model_id = model_id.clone();
+                let system_prompt = system_prompt.clone();
+                let session_repo = SessionRepo::new(self.db.clone());
+                let user_repo = UserRepo::new(self.db.clone());
+                let memory_repo = db::MemoryRepo::new(self.db.clone())
+                    .with_scope(self.config.memory_scope, self.config.persona.id())
+                    .with_ttl(self.config.memory_ttl);
+                let policy = Arc::clone(&tool_policy);
+                let pairing = Arc::clone(&pairing_manager);
+                let attachments = Arc::clone(&attachment_processor);
+                let hooks = Arc::clone(&hook_manager);
+                let pm = plugin_manager.clone();
+                let cron = cron_tools.clone();
+                let tool_output = self.config.tool_output.clone();
+                let maintenance = Arc::clone(&maintenance);
+                let streaming = self.config.streaming.for_channel("google_chat");
+                let usage_cap = Arc::clone(&usage_cap);
+                let synapse_breaker = Arc::clone(&synapse_breaker);
+                let fetch_cache = Arc::clone(&fetch_cache);
+                let session_compactor = session_compactor.clone();
+                let shutdown = shutdown.clone();
+                tasks.spawn(async move {
+                    handle_channel_messages(
+                        "google_chat",
+                        rx,
+                        synapse,
+                        model_id,
+                        system_prompt,
+                        max_tokens,
+                        google_chat,
+                        session_repo,
+                        user_repo,
+                        memory_repo,
+                        policy,
+                        pairing,
+                        attachments,
+                        hooks,
+                        max_context_tokens,
+                        pm,
+                        None,
+                        cron,
+                        tool_output,
+                        maintenance,
+                        streaming,
+                        usage_cap,
+                        synapse_breaker,
+                        fetch_cache,
+                        session_compactor,
+                        shutdown,
+                    )
+                    .await;
+                });
+            }
+        }
+
+        // Telegram (polling mode — only when no public URL for webhooks)
+        if let (Some(tg), Some(rx)) = (telegram, telegram_polling_rx) {
+            drop(tg.start_polling(std::time::Duration::from_secs(1)));
+
+            let synapse = Arc::clone(&synapse);
+            let model_id = model_id.clone();
+            let system_prompt = system_prompt.clone();
+            let session_repo = SessionRepo::new(self.db.clone());
+            let user_repo = UserRepo::new(self.db.clone());
+            let memory_repo = db::MemoryRepo::new(self.db.clone())
+                .with_scope(self.config.memory_scope, self.config.persona.id())
+                .with_ttl(self.config.memory_ttl);
+            let policy = Arc::clone(&tool_policy);
+            let pairing = Arc::clone(&pairing_manager);
+            let attachments = Arc::clone(&attachment_processor);
+            let hooks = Arc::clone(&hook_manager);
+            let pm = plugin_manager.clone();
+            let cron = cron_tools.clone();
+            let tool_output = self.config.tool_output.clone();
+            let maintenance = Arc::clone(&maintenance);
+            let streaming = self.config.streaming.for_channel("telegram");
+            let usage_cap = Arc::clone(&usage_cap);
+            let synapse_breaker = Arc::clone(&synapse_breaker);
+            let fetch_cache = Arc::clone(&fetch_cache);
+            let session_compactor = session_compactor.clone();
+            let tg_config = self.config.telegram.clone();
+            let shutdown = shutdown.clone();
+            tasks.spawn(async move {
+                handle_channel_messages(
+                    "telegram",
+                    rx,
+                    synapse,
+                    model_id,
+                    system_prompt,
+                    max_tokens,
+                    tg,
+                    session_repo,
+                    user_repo,
+                    memory_repo,
+                    policy,
+                    pairing,
+                    attachments,
+                    hooks,
+                    max_context_tokens,
+                    pm,
+                    tg_config,
+                    cron,
+                    tool_output,
+                    maintenance,
+                    streaming,
+                    usage_cap,
+                    synapse_breaker,
+                    fetch_cache,
+                    session_compactor,
+                    shutdown,
+                )
+                .await;
+            });
         }
     }
 
@@ -1244,7 +2014,7 @@ impl Daemon {
         system_prompt: String,
         max_tokens: u32,
         tool_policy: Arc<crate::tools::ToolPolicy>,
-        shutdown_rx: &mut mpsc::Receiver<()>,
+        shutdown: &CancellationToken,
         plugin_manager: crate::api::plugins::SharedPluginManager,
     ) -> Result<()> {
         // Voice uses Full profile — tool_policy checked at handler level
@@ -1257,51 +2027,111 @@ impl Daemon {
         let persona_id = self.config.persona.id();
 
         let stt_model = self.config.voice.stt_model.clone();
-        let tts_model = self.config.voice.tts_model.clone();
-        let tts_voice = self.config.voice.tts_voice.clone();
-        let tts_speed = self.config.voice.tts_speed;
+        let tts = TextToSpeech::new(
+            Arc::clone(&synapse),
+            self.config.voice.tts_model.clone(),
+            self.config.voice.tts_voice.clone(),
+            self.config.voice.tts_speed,
+        )
+        .with_fallback(self.config.voice.tts_fallback.clone());
 
-        let mut detector = WakeWordDetector::new(vec![wake_word.to_string()])?;
+        let mut detector = self.wake_word_detector(wake_word)?;
         let mut capture = AudioCapture::new()?;
-        let mut playback = AudioPlayback::new()?;
+        let playback = tokio::sync::Mutex::new(AudioPlayback::new()?);
+        let turn_guard = VoiceTurnGuard::new(self.config.voice.overlap_policy);
 
         // Load life.json context for voice user
-        let voice_context = self.config.life_json_path.as_ref().and_then(|path| {
-            crate::context::LifeJsonReader::read(path).ok().map(|lj| {
-                let ctx = lj.build_context_string(persona_id);
-                tracing::debug!(path = %path.display(), "loaded life.json for voice");
-                ctx
-            })
+        let life_json = self
+            .config
+            .life_json_path
+            .as_ref()
+            .and_then(|path| crate::context::LifeJsonReader::read(path).ok());
+        let voice_context = life_json.as_ref().map(|lj| {
+            tracing::debug!("loaded life.json for voice");
+            lj.build_context_string(persona_id)
         });
 
+        // STT language hint: life.json preference, then persona/config
+        let stt_language = crate::media::language::resolve_language(&[
+            life_json
+                .as_ref()
+                .and_then(|lj| lj.preferences.as_ref())
+                .and_then(|p| p.language.as_deref()),
+            self.config.voice.stt_language.as_deref(),
+        ]);
+        let stt = SpeechToText::new(Arc::clone(&synapse), stt_model)
+            .with_language(self.whisper_provider(), stt_language);
+        let mut stt_stream = None;
+
         capture.start()?;
         tracing::info!(wake_word, "listening for wake word");
 
-        loop {
-            tokio::select! {
-                _ = shutdown_rx.recv() => {
-                    tracing::info!("shutdown requested");
-                    break;
+        let start_turn = |command: String| {
+            self.run_voice_turn(
+                &playback,
+                &synapse,
+                &model_id,
+                &system_prompt,
+                max_tokens,
+                &tts,
+                voice_context.as_deref(),
+                &plugin_manager,
+                command,
+            )
+            .boxed_local()
+        };
+        let mut turn: Option<LocalBoxFuture<'_, Result<()>>> = None;
+
+        'voice: loop {
+            let listen = async {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                self.process_voice_chunk(
+                    &capture,
+                    &playback,
+                    &mut detector,
+                    &turn_guard,
+                    &stt,
+                    &mut stt_stream,
+                    &tts,
+                )
+                .await
+            };
+            tokio::pin!(listen);
+
+            // Keep the active turn running while listening so triggers that
+            // arrive mid-turn reach the guard instead of waiting behind it
+            let heard = loop {
+                tokio::select! {
+                    () = shutdown.cancelled() => break 'voice,
+                    heard = &mut listen => break heard,
+                    result = async {
+                        match turn.as_mut() {
+                            Some(active) => active.await,
+                            None => std::future::pending().await,
+                        }
+                    }, if turn.is_some() => {
+                        turn = match result {
+                            Ok(()) => turn_guard.finish().map(&start_turn),
+                            Err(e) => {
+                                tracing::error!(error = %e, "voice turn failed");
+                                // Release the guard so later triggers aren't blocked forever
+                                turn_guard.reset();
+                                None
+                            }
+                        };
+                    }
                 }
-                () = tokio::time::sleep(Duration::from_millis(100)) => {
-                    if let Err(e) = self.process_voice_chunk(
-                        &capture,
-                        &mut playback,
-                        &mut detector,
-                        &synapse,
-                        &model_id,
-                        &system_prompt,
-                        max_tokens,
-                        &stt_model,
-                        &tts_model,
-                        &tts_voice,
-                        tts_speed,
-                        voice_context.as_deref(),
-                        &plugin_manager,
-                    ).await {
-                        tracing::error!(error = %e, "voice processing error");
+            };
+
+            match heard {
+                // Single-flight: run now, or queue/drop behind the active turn
+                Ok(Some(command)) => {
+                    if let TurnAdmission::Start(command) = turn_guard.admit(command) {
+                        turn = Some(start_turn(command));
                     }
                 }
+                Ok(None) => {}
+                Err(e) => tracing::error!(error = %e, "voice processing error"),
             }
         }
 
@@ -1309,33 +2139,71 @@ impl Daemon {
         Ok(())
     }
 
-    /// Process a chunk of voice audio
+    /// Run one voice turn, holding the speaker until its reply has played
     #[allow(clippy::future_not_send, clippy::too_many_arguments)]
-    async fn process_voice_chunk(
+    async fn run_voice_turn(
         &self,
-        capture: &AudioCapture,
-        playback: &mut AudioPlayback,
-        detector: &mut WakeWordDetector,
+        playback: &tokio::sync::Mutex<AudioPlayback>,
         synapse: &Arc<SynapseClient>,
         model_id: &str,
         system_prompt: &str,
         max_tokens: u32,
-        stt_model: &str,
-        tts_model: &str,
-        tts_voice: &str,
-        tts_speed: f64,
+        tts: &TextToSpeech,
         voice_context: Option<&str>,
         plugin_manager: &crate::api::plugins::SharedPluginManager,
+        command: String,
     ) -> Result<()> {
+        let mut playback = playback.lock().await;
+        handle_voice_command(
+            &mut playback,
+            synapse,
+            model_id,
+            system_prompt,
+            max_tokens,
+            tts,
+            &command,
+            voice_context,
+            plugin_manager,
+            &self.config.tool_output,
+        )
+        .await
+    }
+
+    /// Process a chunk of voice audio, returning a command once one is heard
+    #[allow(clippy::future_not_send, clippy::too_many_arguments)]
+    async fn process_voice_chunk(
+        &self,
+        capture: &AudioCapture,
+        playback: &tokio::sync::Mutex<AudioPlayback>,
+        detector: &mut WakeWordDetector,
+        turn_guard: &VoiceTurnGuard,
+        stt: &SpeechToText,
+        stt_stream: &mut Option<SttStream>,
+        tts: &TextToSpeech,
+    ) -> Result<Option<String>> {
         let samples = capture.take_buffer();
 
         if samples.len() < CHUNK_SIZE {
-            return Ok(());
+            return Ok(None);
+        }
+
+        // Offline keyword spotting: no STT round-trip until the wake word is heard
+        if detector.backend() == WakeWordBackend::Porcupine && !detector.is_activated() {
+            if detector.process_frame(&samples_to_pcm16(&samples)) == DetectorState::Detected {
+                capture.clear_buffer();
+                acknowledge(playback, turn_guard, tts).await?;
+            }
+            return Ok(None);
         }
 
         let speech_detected = detector.process(&samples);
+        if !detector.is_activated() {
+            // Any streaming session belongs to a finished or abandoned command
+            *stt_stream = None;
+        }
         // Safe to unwrap: wake_word is validated in run_voice_loop
         let wake_word = self.config.persona.wake_word().unwrap_or("hey");
+        let mut command = None;
 
         if speech_detected && !detector.is_activated() {
             let speech_samples = detector.take_speech_buffer();
@@ -1345,77 +2213,87 @@ impl Daemon {
                 tracing::debug!(samples = speech_samples.len(), "checking for wake word");
 
                 let wav = samples_to_wav(&speech_samples, SAMPLE_RATE)?;
-                let transcription = synapse.transcribe(wav.into(), "audio.wav", stt_model).await;
-                if let Ok(result) = transcription {
-                    tracing::debug!(transcript = %result.text, "transcribed");
-
-                    if detector.check_wake_word(&result.text) {
-                        let command = extract_command(&result.text, wake_word);
-                        if command.is_empty() {
-                            speak(playback, synapse, tts_model, tts_voice, tts_speed, "Yes?")
-                                .await?;
+                if let Ok(text) = stt.transcribe(wav).await {
+                    tracing::debug!(transcript = %text, "transcribed");
+
+                    if detector.check_wake_word(&text) {
+                        let extracted = extract_command(&text, wake_word);
+                        if extracted.is_empty() {
+                            acknowledge(playback, turn_guard, tts).await?;
                         } else {
-                            handle_voice_command(
-                                playback,
-                                synapse,
-                                model_id,
-                                system_prompt,
-                                max_tokens,
-                                tts_model,
-                                tts_voice,
-                                tts_speed,
-                                &command,
-                                voice_context,
-                                plugin_manager,
-                            )
-                            .await?;
+                            command = Some(extracted);
                         }
                         detector.reset();
                     }
                 }
             }
-        } else if detector.is_activated() && detector.is_utterance_complete() {
-            let speech_samples = detector.take_speech_buffer();
-            capture.clear_buffer();
+        } else if detector.is_activated() {
+            // Stream the command so it runs as soon as the speaker pauses
+            let (audio, transcripts) = stt_stream.get_or_insert_with(|| stt.stream());
+            if audio.send(samples).await.is_err() {
+                tracing::warn!("streaming STT session ended unexpectedly");
+            }
+            let mut final_text = None;
+            while let Ok(transcript) = transcripts.try_recv() {
+                if transcript.is_final {
+                    final_text = Some(transcript.text);
+                    break;
+                }
+                tracing::debug!(partial = %transcript.text, "interim transcript");
+            }
 
-            let wav = samples_to_wav(&speech_samples, SAMPLE_RATE)?;
-            match synapse.transcribe(wav.into(), "audio.wav", stt_model).await {
-                Ok(result) => {
-                    tracing::info!(command = %result.text, "command received");
-                    handle_voice_command(
-                        playback,
-                        synapse,
-                        model_id,
-                        system_prompt,
-                        max_tokens,
-                        tts_model,
-                        tts_voice,
-                        tts_speed,
-                        &result.text,
-                        voice_context,
-                        plugin_manager,
-                    )
-                    .await?;
+            // Detector ended the utterance first: close input and await the final
+            if final_text.is_none()
+                && detector.is_utterance_complete()
+                && let Some((audio, mut transcripts)) = stt_stream.take()
+            {
+                drop(audio);
+                while let Some(transcript) = transcripts.recv().await {
+                    if transcript.is_final {
+                        final_text = Some(transcript.text);
+                        break;
+                    }
                 }
-                Err(e) => {
-                    tracing::warn!(error = %e, "STT failed");
-                    speak(
-                        playback,
-                        synapse,
-                        tts_model,
-                        tts_voice,
-                        tts_speed,
-                        "Sorry, I didn't catch that",
-                    )
-                    .await?;
+                if final_text.is_none() {
+                    tracing::warn!("STT produced no transcript");
+                    detector.reset();
+                    capture.clear_buffer();
+                    // The active turn owns the speaker; stay quiet rather than wait
+                    if let Ok(mut playback) = playback.try_lock() {
+                        tts.speak(&mut playback, "Sorry, I didn't catch that")
+                            .await?;
+                    }
                 }
             }
-            detector.reset();
+
+            if let Some(text) = final_text {
+                tracing::info!(command = %text, "command received");
+                command = Some(text);
+                *stt_stream = None;
+                detector.reset();
+                capture.clear_buffer();
+            }
         } else if samples.len() > SAMPLE_RATE as usize * 5 {
             capture.clear_buffer();
         }
 
-        Ok(())
+        Ok(command)
+    }
+}
+
+/// Acknowledge a bare wake word unless that would talk over an active turn
+#[allow(clippy::future_not_send)]
+async fn acknowledge(
+    playback: &tokio::sync::Mutex<AudioPlayback>,
+    turn_guard: &VoiceTurnGuard,
+    tts: &TextToSpeech,
+) -> Result<()> {
+    if turn_guard.is_active() {
+        return Ok(());
+    }
+    match playback.try_lock() {
+        Ok(mut playback) if !playback.is_playing() => tts.speak(&mut playback, "Yes?").await,
+        _ => Ok(()),
     }
 }
 
@@ -1474,10 +2352,11 @@ async fn check_pairing<C: Channel>(
         DmPolicy::Pairing => {
             // Check if message is a pairing code verification attempt
             let trimmed = msg.content.trim().to_uppercase();
+            let mut rejection = None;
             if trimmed.len() == 6 && trimmed.chars().all(|c| c.is_ascii_alphanumeric()) {
-                // Try to verify the code
-                match pairing_manager.verify_pairing(&msg.sender_id, channel_name, &trimmed) {
-                    Ok(true) => {
+                // Try to redeem the code
+                match pairing_manager.redeem_code(&msg.sender_id, channel_name, &trimmed) {
+                    Ok(()) => {
                         // Send success message
                         let response = OutgoingMessage {
                             channel_id: msg.channel_id.clone(),
@@ -1488,17 +2367,37 @@ async fn check_pairing<C: Channel>(
                             media: vec![],
                             edit_target: None,
                             voice_note: false,
+                            attachments: vec![],
                         };
                         if let Err(e) = channel.send(response).await {
                             tracing::warn!(error = %e, "failed to send pairing success message");
                         }
                         return PairingResult::Allowed;
                     }
-                    Ok(false) => {
-                        tracing::debug!(sender = %msg.sender_id, "invalid pairing code");
+                    Err(PairingError::Storage(e)) => {
+                        tracing::error!(error = %e, "pairing verification failed");
+                    }
+                    Err(e @ PairingError::Locked { .. }) => {
+                        // No new code while blocked, or the block could be sidestepped
+                        let response = OutgoingMessage {
+                            channel_id: msg.channel_id.clone(),
+                            content: e.notice().to_string(),
+                            reply_to: None,
+                            thread_id: None,
+                            keyboard: None,
+                            media: vec![],
+                            edit_target: None,
+                            voice_note: false,
+                            attachments: vec![],
+                        };
+                        if let Err(e) = channel.send(response).await {
+                            tracing::warn!(error = %e, "failed to send pairing blocked message");
+                        }
+                        return PairingResult::Denied;
                     }
                     Err(e) => {
-                        tracing::error!(error = %e, "pairing verification failed");
+                        tracing::debug!(sender = %msg.sender_id, reason = %e, "pairing code rejected");
+                        rejection = Some(e.notice());
                     }
                 }
             }
@@ -1509,7 +2408,9 @@ async fn check_pairing<C: Channel>(
                     let response = OutgoingMessage {
                         channel_id: msg.channel_id.clone(),
                         content: format!(
-                            "Please enter the pairing code to start messaging.\n\nYour code: {code}\n\n(This code expires in 10 minutes)"
+                            "{}{}",
+                            rejection.map(|n| format!("{n} ")).unwrap_or_default(),
+                            pairing_manager.code_prompt(&code)
                         ),
                         reply_to: None,
                         thread_id: None,
@@ -1517,6 +2418,7 @@ async fn check_pairing<C: Channel>(
                         media: vec![],
                         edit_target: None,
                         voice_note: false,
+                        attachments: vec![],
                     };
                     if let Err(e) = channel.send(response).await {
                         tracing::warn!(error = %e, "failed to send pairing code");
@@ -1549,6 +2451,53 @@ struct DaemonPendingToolCall {
     arguments: String,
 }
 
+/// System prompt source for channel handlers
+///
+/// Rebuilt per message from the skills enabled for the sender and channel,
+/// so scoped skill toggles apply without a restart.
+#[derive(Clone)]
+struct ChannelPrompt {
+    persona_name: String,
+    /// Active persona, shared with the API so `/admin/persona/reload` applies
+    persona: Arc<tokio::sync::RwLock<crate::api::ActivePersona>>,
+    knowledge: crate::api::SharedKnowledge,
+    skill_repo: SkillRepo,
+}
+
+impl ChannelPrompt {
+    /// Snapshot of the active persona for one turn
+    async fn persona(&self) -> crate::api::ActivePersona {
+        self.persona.read().await.clone()
+    }
+
+    /// Knowledge chunks of the active persona for one turn
+    async fn knowledge(&self) -> Arc<Vec<crate::persona::KnowledgeChunk>> {
+        Arc::clone(&*self.knowledge.read().await)
+    }
+
+    /// Build the prompt for `persona` with the skills visible to `user_id`
+    /// on `channel`
+    fn build(
+        &self,
+        persona: &crate::api::ActivePersona,
+        user_id: Option<&str>,
+        channel: &str,
+    ) -> String {
+        let skills = self
+            .skill_repo
+            .list_enabled_for_user(user_id, Some(channel))
+            .unwrap_or_else(|e| {
+                tracing::warn!(channel, error = %e, "failed to load skills for prompt");
+                Vec::new()
+            });
+        crate::prompt::build_system_prompt(
+            &self.persona_name,
+            persona.system_prompt.as_deref().unwrap_or_default(),
+            &skills,
+        )
+    }
+}
+
 /// Handle incoming messages from a channel
 #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 async fn handle_channel_messages<C: Channel + Send + 'static>(
@@ -1556,706 +2505,988 @@ async fn handle_channel_messages<C: Channel + Send + 'static>(
     mut rx: mpsc::Receiver<IncomingMessage>,
     synapse: Arc<SynapseClient>,
     model_id: String,
-    system_prompt: String,
+    system_prompt: ChannelPrompt,
     max_tokens: u32,
     channel: C,
     session_repo: SessionRepo,
     user_repo: UserRepo,
     memory_repo: crate::db::MemoryRepo,
-    persona_id: String,
-    persona_system_prompt: Option<String>,
     tool_policy: Arc<crate::tools::ToolPolicy>,
     pairing_manager: Arc<PairingManager>,
     attachment_processor: Arc<AttachmentProcessor>,
     hook_manager: Arc<HookManager>,
-    knowledge_chunks: Vec<crate::persona::KnowledgeChunk>,
     max_context_tokens: usize,
     plugin_manager: crate::api::plugins::SharedPluginManager,
     telegram_config: Option<crate::config::TelegramConfig>,
+    cron_tools: Option<Arc<crate::tools::BuiltinCronTools>>,
+    tool_output: crate::tools::ToolOutputConfig,
+    maintenance: Arc<crate::maintenance::MaintenanceMode>,
+    streaming: crate::channels::StreamingConfig,
+    usage_cap: Arc<crate::usage::UsageCap>,
+    synapse_breaker: Arc<crate::synapse::CircuitBreaker>,
+    fetch_cache: Arc<crate::tools::WebFetchCache>,
+    session_compactor: Option<Arc<crate::context::SessionCompactor>>,
+    shutdown: CancellationToken,
 ) {
     let exec_tool = Arc::new(crate::tools::BuiltinExecTool::default());
     let browser_tools = Arc::new(crate::tools::BuiltinBrowserTools::new());
+    let search_tool = crate::tools::BuiltinSearchTool::from_env().map(Arc::new);
+    let fetch_tool = crate::tools::BuiltinFetchTool::from_env().map(Arc::new);
+    let link_previews = Some(crate::links::LinkProcessor::new(
+        crate::links::LinkConfig::from_env(),
+    ))
+    .filter(crate::links::LinkProcessor::is_enabled);
 
     tracing::info!(channel = channel_name, "channel handler started");
 
-    while let Some(msg) = rx.recv().await {
-        // Check DM security policy
-        match check_pairing(&pairing_manager, &msg, channel_name, &channel).await {
-            PairingResult::Allowed => (),
-            PairingResult::Denied | PairingResult::PendingPairing => continue,
-        }
+    // Stop taking new messages on shutdown; a reply already in progress finishes
+    loop {
+        let msg = tokio::select! {
+            () = shutdown.cancelled() => {
+                tracing::info!(channel = channel_name, "channel handler stopping");
+                break;
+            }
+            msg = rx.recv() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+        };
 
-        // Hook: message:received - can skip processing or provide auto-reply
-        let hook_event = HookEvent::new(HookAction::MessageReceived, channel_name, &msg);
-        let hook_result = hook_manager.trigger(&hook_event).await;
+        // Everything logged or published for this message carries its request id
+        let request_id = msg.request_id.clone();
+        let turn = async {
+            // Check DM security policy
+            match check_pairing(&pairing_manager, &msg, channel_name, &channel).await {
+                PairingResult::Allowed => (),
+                PairingResult::Denied | PairingResult::PendingPairing => return,
+            }
 
-        if hook_result.skip_processing {
-            tracing::debug!(channel = channel_name, "hook skipped processing");
-            continue;
-        }
+            // Maintenance mode: answer with the notice (or stay silent) and skip the agent
+            if maintenance.intercept(&channel, &msg).await {
+                tracing::debug!(channel = channel_name, "maintenance mode, skipping agent");
+                return;
+            }
 
-        // Send hook auto-reply if provided (but continue processing unless skip_agent)
-        if let Some(ref reply) = hook_result.reply {
-            let outgoing = OutgoingMessage {
-                channel_id: msg.channel_id.clone(),
-                content: reply.clone(),
-                reply_to: Some(msg.id.clone()),
-                thread_id: None,
-                keyboard: None,
-                media: vec![],
-                edit_target: None,
-                voice_note: false,
-            };
-            if let Err(e) = channel.send(outgoing).await {
-                tracing::error!(error = %e, "hook reply send error");
+            // Hook: message:received - can skip processing or provide auto-reply
+            let hook_event = HookEvent::new(HookAction::MessageReceived, channel_name, &msg);
+            let hook_result = hook_manager.trigger(&hook_event).await;
+
+            if hook_result.skip_processing {
+                tracing::debug!(channel = channel_name, "hook skipped processing");
+                return;
             }
-            if hook_result.skip_agent {
-                continue;
+
+            // Send hook auto-reply if provided (but continue processing unless skip_agent)
+            if let Some(ref reply) = hook_result.reply {
+                let outgoing = OutgoingMessage {
+                    channel_id: msg.channel_id.clone(),
+                    content: reply.clone(),
+                    reply_to: Some(msg.id.clone()),
+                    thread_id: None,
+                    keyboard: None,
+                    media: vec![],
+                    edit_target: None,
+                    voice_note: false,
+                    attachments: vec![],
+                };
+                if let Err(e) = channel.send(outgoing).await {
+                    tracing::error!(error = %e, "hook reply send error");
+                }
+                if hook_result.skip_agent {
+                    return;
+                }
             }
-        }
 
-        // Find or create user and session
-        let user = match user_repo.find_or_create(&msg.sender_id) {
-            Ok(u) => u,
-            Err(e) => {
-                tracing::error!(error = %e, "failed to find/create user");
-                continue;
+            // Reminder slash commands are handled directly, without the agent
+            if let Some(command) = crate::tools::ReminderCommand::parse(&msg.content) {
+                let reply = match cron_tools {
+                    Some(ref ct) => {
+                        let origin = crate::tools::ReminderOrigin::new(
+                            &msg.sender_id,
+                            channel_name,
+                            &msg.channel_id,
+                        )
+                        .with_thread(msg.thread_id.clone());
+                        ct.run_command(command, &origin).await
+                    }
+                    None => {
+                        "Reminders are not available (scheduling is not configured).".to_string()
+                    }
+                };
+                if let Err(e) = channel
+                    .send(OutgoingMessage::reply(
+                        msg.channel_id.clone(),
+                        reply,
+                        msg.id.clone(),
+                    ))
+                    .await
+                {
+                    tracing::error!(error = %e, "reminder reply send error");
+                }
+                return;
             }
-        };
 
-        let session =
-            match session_repo.find_or_create(&user.id, channel_name, &msg.channel_id, &persona_id)
+            // Daily usage cap: answer with the notice and skip the agent until reset
+            if let crate::usage::UsageDecision::LimitReached(notice) =
+                usage_cap.check(&msg.sender_id)
             {
+                let reply = OutgoingMessage::reply(msg.channel_id.clone(), notice, msg.id.clone());
+                if let Err(e) = channel.send(reply).await {
+                    tracing::warn!(error = %e, "usage limit notice send error");
+                }
+                return;
+            }
+
+            // Find or create user and session
+            let user = match user_repo.find_or_create(&msg.sender_id) {
+                Ok(u) => u,
+                Err(e) => {
+                    tracing::error!(error = %e, "failed to find/create user");
+                    return;
+                }
+            };
+            // Unknown slash command close to an existing one: suggest instead of guessing
+            match crate::skills::slash_suggestion(
+                &msg.content,
+                &system_prompt.skill_repo,
+                Some(&user.id),
+                Some(channel_name),
+            ) {
+                Ok(Some(suggestion)) => {
+                    let reply =
+                        OutgoingMessage::reply(msg.channel_id.clone(), suggestion, msg.id.clone());
+                    if let Err(e) = channel.send(reply).await {
+                        tracing::warn!(error = %e, "command suggestion send error");
+                    }
+                    return;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::debug!(error = %e, "slash command lookup failed"),
+            }
+
+            // Read the persona per message so a reload applies to the next turn
+            let persona = system_prompt.persona().await;
+            let knowledge_chunks = system_prompt.knowledge().await;
+            let system_prompt = system_prompt.build(&persona, Some(&user.id), channel_name);
+
+            // Threads and forum topics get their own session and history
+            let session = match session_repo.find_or_create_threaded(
+                &user.id,
+                channel_name,
+                &msg.channel_id,
+                &persona.id,
+                msg.thread_id.as_deref(),
+            ) {
                 Ok(s) => s,
                 Err(e) => {
                     tracing::error!(error = %e, "failed to find/create session");
-                    continue;
+                    return;
                 }
             };
 
-        // Publish beacon.conversation.started for new sessions (best-effort)
-        match session_repo.message_count(&session.id) {
-            Ok(0) => {
-                crate::events::publish(crate::events::build_conversation_started_event(
-                    &session.id,
-                    channel_name,
-                    &msg.sender_id,
-                ));
+            // Memories follow the persona this session runs as
+            let turn_memory = memory_repo.for_persona(&session.persona_id);
+
+            let context_config = ContextConfig {
+                max_messages: 20,
+                max_tokens: 4000,
+                tokenizer: Tokenizer::for_model(&model_id),
+                persona_id: persona.id.clone(),
+                max_memories: 10,
+                memory_selection: MemorySelection::Relevant,
+                persona_system_prompt: persona.system_prompt.clone(),
+            };
+            let context_builder = ContextBuilder::new(context_config);
+
+            // Publish beacon.conversation.started for new sessions (best-effort)
+            match session_repo.message_count(&session.id) {
+                Ok(0) => {
+                    crate::events::publish(crate::events::build_conversation_started_event(
+                        &session.id,
+                        channel_name,
+                        &msg.sender_id,
+                    ));
+
+                    // Backfill recent platform history lost across restarts
+                    match context_builder
+                        .seed_from_channel(
+                            &session.id,
+                            &session_repo,
+                            &channel,
+                            &msg.channel_id,
+                            Some(&msg.id),
+                        )
+                        .await
+                    {
+                        Ok(0) => {}
+                        Ok(seeded) => {
+                            tracing::debug!(session = %session.id, seeded, "seeded session from channel history");
+                        }
+                        Err(e) => {
+                            tracing::debug!(error = %e, "channel history unavailable");
+                        }
+                    }
+                }
+                Ok(_) => {} // existing session, don't re-publish started
+                Err(e) => {
+                    tracing::warn!(
+                        "failed to check message count for session {}: {}",
+                        session.id,
+                        e
+                    );
+                }
             }
-            Ok(_) => {} // existing session, don't re-publish started
-            Err(e) => {
-                tracing::warn!(
-                    "failed to check message count for session {}: {}",
-                    session.id,
-                    e
-                );
+
+            // Extract thread_id for threading support
+            // For platforms like Slack/Discord, reply_to contains the thread identifier
+            let thread_id = msg.reply_to.as_deref();
+
+            // Store user message with thread context
+            if let Err(e) = session_repo.add_message_with_thread(
+                &session.id,
+                MessageRole::User,
+                &msg.content,
+                thread_id,
+            ) {
+                tracing::warn!(error = %e, "failed to store user message");
             }
-        }
 
-        // Extract thread_id for threading support
-        // For platforms like Slack/Discord, reply_to contains the thread identifier
-        let thread_id = msg.reply_to.as_deref();
+            // Build context from life.json + session history + memories
+            // Filter by thread if message is part of a thread
+            let mut built_context = context_builder.build_with_thread(
+                &session.id,
+                &user.id,
+                user.life_json_path.as_deref(),
+                &session_repo,
+                &user_repo,
+                Some((&turn_memory, msg.content.as_str())),
+                thread_id,
+            );
 
-        // Store user message with thread context
-        if let Err(e) = session_repo.add_message_with_thread(
-            &session.id,
-            MessageRole::User,
-            &msg.content,
-            thread_id,
-        ) {
-            tracing::warn!(error = %e, "failed to store user message");
-        }
+            // Compact long sessions in the background; this turn keeps the
+            // history it just loaded and later turns see the summary
+            if let Some(ref compactor) = session_compactor {
+                compactor.compact_in_background(
+                    &session.id,
+                    session_repo.clone(),
+                    turn_memory.clone(),
+                    None,
+                    &user.id,
+                );
+            }
 
-        // Build context from life.json + session history + memories
-        // Filter by thread if message is part of a thread
-        let context_config = ContextConfig {
-            max_messages: 20,
-            max_tokens: 4000,
-            persona_id: persona_id.clone(),
-            max_memories: 10,
-            persona_system_prompt: persona_system_prompt.clone(),
-        };
-        let context_builder = ContextBuilder::new(context_config);
-        let mut built_context = context_builder.build_with_thread(
-            &session.id,
-            &user.id,
-            user.life_json_path.as_deref(),
-            &session_repo,
-            &user_repo,
-            Some((&memory_repo, msg.content.as_str())),
-            thread_id,
-        );
+            if let Ok(ctx) = &built_context {
+                tracing::debug!(
+                    session = %session.id,
+                    estimated_tokens = ctx.estimated_tokens,
+                    message_count = ctx.messages.len(),
+                    has_system_context = !ctx.system_context.is_empty(),
+                    "built conversation context"
+                );
+            }
 
-        if let Ok(ctx) = &built_context {
-            tracing::debug!(
+            tracing::info!(
+                channel = channel_name,
                 session = %session.id,
-                estimated_tokens = ctx.estimated_tokens,
-                message_count = ctx.messages.len(),
-                has_system_context = !ctx.system_context.is_empty(),
-                "built conversation context"
+                sender = %msg.sender_name,
+                content = %msg.content,
+                attachments = msg.attachments.len(),
+                thread_id = ?thread_id,
+                "message received"
             );
-        }
-
-        tracing::info!(
-            channel = channel_name,
-            session = %session.id,
-            sender = %msg.sender_name,
-            content = %msg.content,
-            attachments = msg.attachments.len(),
-            thread_id = ?thread_id,
-            "message received"
-        );
-
-        // Publish beacon.message.received event (best-effort)
-        crate::events::publish(
-            crate::events::OmniEvent::new(
-                "beacon.message.received",
-                &msg.sender_id,
-                serde_json::json!({
-                    "channel": channel_name,
-                    "messageId": msg.id,
-                    "userId": msg.sender_id,
-                }),
-            )
-            .with_subject(&msg.sender_id),
-        );
 
-        // Acknowledge message with reaction (configurable for Telegram)
-        let reaction_level = telegram_config
-            .as_ref()
-            .filter(|_| channel_name == "telegram")
-            .map_or(crate::config::ReactionLevel::Ack, |c| c.reaction_level);
-        let ack_emoji = telegram_config
-            .as_ref()
-            .filter(|_| channel_name == "telegram")
-            .map_or("\u{1F440}", |c| c.ack_reaction.as_str());
+            // Publish beacon.message.received event (best-effort)
+            crate::events::publish(
+                crate::events::OmniEvent::new(
+                    "beacon.message.received",
+                    &msg.sender_id,
+                    serde_json::json!({
+                        "channel": channel_name,
+                        "messageId": msg.id,
+                        "userId": msg.sender_id,
+                    }),
+                )
+                .with_subject(&msg.sender_id),
+            );
 
-        if reaction_level != crate::config::ReactionLevel::Off
-            && let Err(e) = channel
-                .add_reaction(&msg.channel_id, &msg.id, ack_emoji)
-                .await
-        {
-            tracing::debug!(error = %e, "ack reaction failed");
-        }
+            // Acknowledge message with reaction (configurable for Telegram)
+            let reaction_level = telegram_config
+                .as_ref()
+                .filter(|_| channel_name == "telegram")
+                .map_or(crate::config::ReactionLevel::Ack, |c| c.reaction_level);
+            let ack_emoji = telegram_config
+                .as_ref()
+                .filter(|_| channel_name == "telegram")
+                .map_or("\u{1F440}", |c| c.ack_reaction.as_str());
 
-        // Process attachments to augment message content
-        let content_with_attachments = if msg.attachments.is_empty() {
-            msg.content.clone()
-        } else {
-            // Process attachments (images via vision, audio via STT)
-            let attachment_text = attachment_processor
-                .process_attachments(&msg.attachments)
-                .await
-                .unwrap_or_default();
+            if reaction_level != crate::config::ReactionLevel::Off
+                && let Err(e) = channel
+                    .add_reaction(&msg.channel_id, &msg.id, ack_emoji)
+                    .await
+            {
+                tracing::debug!(error = %e, "ack reaction failed");
+            }
 
-            if attachment_text.is_empty() {
+            // Process attachments to augment message content
+            let content_with_attachments = if msg.attachments.is_empty() {
                 msg.content.clone()
             } else {
-                format!("{}\n\n{attachment_text}", msg.content)
+                // Process attachments (images via vision, audio via STT)
+                let user_language = user_repo
+                    .get_context_value(&user.id, crate::media::language::USER_LANGUAGE_KEY)
+                    .ok()
+                    .flatten();
+                let attachment_text = attachment_processor
+                    .process_attachments_for(&msg.attachments, user_language.as_deref())
+                    .await
+                    .unwrap_or_default();
+
+                if attachment_text.is_empty() {
+                    msg.content.clone()
+                } else {
+                    format!("{}\n\n{attachment_text}", msg.content)
+                }
+            };
+
+            // Inject knowledge based on user message
+            if let Ok(ref mut ctx) = built_context
+                && !knowledge_chunks.is_empty()
+            {
+                let max_knowledge_tokens = max_context_tokens / 4;
+                let selected = crate::knowledge::select_knowledge(
+                    &knowledge_chunks,
+                    &content_with_attachments,
+                    max_knowledge_tokens,
+                );
+                if !selected.is_empty() {
+                    ctx.knowledge_context = crate::knowledge::format_knowledge(&selected);
+                }
             }
-        };
 
-        // Inject knowledge based on user message
-        if let Ok(ref mut ctx) = built_context
-            && !knowledge_chunks.is_empty()
-        {
-            let max_knowledge_tokens = max_context_tokens / 4;
-            let selected = crate::knowledge::select_knowledge(
-                &knowledge_chunks,
-                &content_with_attachments,
-                max_knowledge_tokens,
-            );
-            if !selected.is_empty() {
-                ctx.knowledge_context = crate::knowledge::format_knowledge(&selected);
+            // Build augmented prompt with context and history
+            let augmented_prompt = match &built_context {
+                Ok(ctx) => ctx.format_prompt(&content_with_attachments),
+                Err(_) => content_with_attachments,
+            };
+
+            // Show typing indicator while processing
+            if let Err(e) = channel.send_typing(&msg.channel_id).await {
+                tracing::debug!(error = %e, "typing indicator failed");
             }
-        }
 
-        // Build augmented prompt with context and history
-        let augmented_prompt = match &built_context {
-            Ok(ctx) => ctx.format_prompt(&content_with_attachments),
-            Err(_) => content_with_attachments,
-        };
+            // Hook: message:before_agent - can provide direct reply or skip agent
+            let hook_event = HookEvent::new(HookAction::BeforeAgent, channel_name, &msg)
+                .with_session(&session.id);
+            let hook_result = hook_manager.trigger(&hook_event).await;
 
-        // Show typing indicator while processing
-        if let Err(e) = channel.send_typing(&msg.channel_id).await {
-            tracing::debug!(error = %e, "typing indicator failed");
-        }
+            // If hook provides a reply and wants to skip agent, send and move on
+            if hook_result.skip_agent
+                && let Some(reply) = hook_result.reply
+            {
+                let outgoing = OutgoingMessage {
+                    channel_id: msg.channel_id.clone(),
+                    content: reply,
+                    reply_to: Some(msg.id.clone()),
+                    thread_id: None,
+                    keyboard: None,
+                    media: vec![],
+                    edit_target: None,
+                    voice_note: false,
+                    attachments: vec![],
+                };
+                if let Err(e) = channel.send(outgoing).await {
+                    tracing::error!(error = %e, "hook reply send error");
+                }
+                return;
+            }
 
-        // Hook: message:before_agent - can provide direct reply or skip agent
-        let hook_event =
-            HookEvent::new(HookAction::BeforeAgent, channel_name, &msg).with_session(&session.id);
-        let hook_result = hook_manager.trigger(&hook_event).await;
+            // Send hook reply if provided (but continue to agent)
+            if let Some(hook_reply) = hook_result.reply {
+                let outgoing = OutgoingMessage {
+                    channel_id: msg.channel_id.clone(),
+                    content: hook_reply,
+                    reply_to: Some(msg.id.clone()),
+                    thread_id: None,
+                    keyboard: None,
+                    media: vec![],
+                    edit_target: None,
+                    voice_note: false,
+                    attachments: vec![],
+                };
+                if let Err(e) = channel.send(outgoing).await {
+                    tracing::error!(error = %e, "hook reply send error");
+                }
+            }
 
-        // If hook provides a reply and wants to skip agent, send and move on
-        if hook_result.skip_agent
-            && let Some(reply) = hook_result.reply
-        {
-            let outgoing = OutgoingMessage {
-                channel_id: msg.channel_id.clone(),
-                content: reply,
-                reply_to: Some(msg.id.clone()),
-                thread_id: None,
-                keyboard: None,
-                media: vec![],
-                edit_target: None,
-                voice_note: false,
+            // Reminder tools deliver back to this conversation
+            let reminder_origin =
+                crate::tools::ReminderOrigin::new(&msg.sender_id, channel_name, &msg.channel_id)
+                    .with_thread(msg.thread_id.clone());
+
+            // Effective tool profile: session override, then channel, then persona default
+            let session_profile = || {
+                session
+                    .tool_profile
+                    .as_deref()
+                    .and_then(crate::tools::SessionToolProfile::parse)
             };
-            if let Err(e) = channel.send(outgoing).await {
-                tracing::error!(error = %e, "hook reply send error");
-            }
-            continue;
-        }
 
-        // Send hook reply if provided (but continue to agent)
-        if let Some(hook_reply) = hook_result.reply {
-            let outgoing = OutgoingMessage {
-                channel_id: msg.channel_id.clone(),
-                content: hook_reply,
-                reply_to: Some(msg.id.clone()),
-                thread_id: None,
-                keyboard: None,
-                media: vec![],
-                edit_target: None,
-                voice_note: false,
+            // Fetch available tools from Synapse MCP and plugins, filtered by policy
+            let tools = {
+                let mut executor = crate::tools::executor::ToolExecutor::new(
+                    Arc::clone(&synapse),
+                    plugin_manager.clone(),
+                )
+                .with_exec_tool(Arc::clone(&exec_tool))
+                .with_browser_tools(Arc::clone(&browser_tools))
+                .with_policy(Arc::clone(&tool_policy), channel_name)
+                .with_session_profile(session_profile());
+                if let Some(ref ct) = cron_tools {
+                    executor = executor
+                        .with_cron_tools(Arc::clone(ct))
+                        .with_reminder_origin(reminder_origin.clone());
+                }
+                if let Some(ref st) = search_tool {
+                    executor = executor.with_search_tool(Arc::clone(st));
+                }
+                if let Some(ref ft) = fetch_tool {
+                    executor = executor.with_fetch_tool(Arc::clone(ft));
+                }
+                executor.list_tools().await.ok().inspect(|tools| {
+                    let names: Vec<&str> = tools.iter().map(|t| t.function.name.as_str()).collect();
+                    tracing::info!(channel = channel_name, tools = ?names, "tools available for LLM");
+                })
             };
-            if let Err(e) = channel.send(outgoing).await {
-                tracing::error!(error = %e, "hook reply send error");
-            }
-        }
 
-        // Fetch available tools from Synapse MCP and plugins, filtered by policy
-        let tools = {
-            let executor = crate::tools::executor::ToolExecutor::new(
-                Arc::clone(&synapse),
-                plugin_manager.clone(),
-            )
-            .with_exec_tool(Arc::clone(&exec_tool))
-            .with_browser_tools(Arc::clone(&browser_tools));
-            executor.list_tools().await.ok().map(|tools| {
-                let filtered: Vec<_> = tools
-                    .into_iter()
-                    .filter(|t| {
-                        tool_policy.is_allowed(channel_name, &normalize_tool_name(&t.function.name))
-                    })
-                    .collect();
-                let names: Vec<&str> = filtered.iter().map(|t| t.function.name.as_str()).collect();
-                tracing::info!(channel = channel_name, tools = ?names, "tools available for LLM");
-                filtered
-            })
-        };
+            let use_streaming = channel
+                .capabilities()
+                .contains(&ChannelCapability::Streaming);
+
+            // Start streaming placeholder if channel supports it (or defer until the first text)
+            let mut defer_stream_start = use_streaming && !streaming.placeholder;
+            let mut streaming_msg_id = if use_streaming && streaming.placeholder {
+                channel
+                    .send_streaming_start(
+                        &msg.channel_id,
+                        "\u{2026}",
+                        Some(&msg.id),
+                        msg.thread_id.as_deref(),
+                    )
+                    .await
+                    .ok()
+            } else {
+                None
+            };
 
-        let use_streaming = channel
-            .capabilities()
-            .contains(&ChannelCapability::Streaming);
-
-        // Start streaming placeholder if channel supports it
-        let streaming_msg_id = if use_streaming {
-            channel
-                .send_streaming_start(
-                    &msg.channel_id,
-                    "\u{2026}",
-                    Some(&msg.id),
-                    msg.thread_id.as_deref(),
+            // Process with Synapse (multi-turn tool loop), refreshing typing until text streams
+            let mut reported_tokens: u64 = 0;
+            let typing = TypingKeepalive::new(streaming.typing_interval());
+            let response = typing.run(&channel, &msg.channel_id, async {
+                let mut llm_messages = vec![
+                    synapse_client::Message::system(&system_prompt),
+                    synapse_client::Message::user(&augmented_prompt),
+                ];
+                let mut final_response = String::new();
+                let mut executor = crate::tools::executor::ToolExecutor::new(
+                    Arc::clone(&synapse),
+                    plugin_manager.clone(),
                 )
-                .await
-                .ok()
-        } else {
-            None
-        };
+                .with_exec_tool(Arc::clone(&exec_tool))
+                .with_browser_tools(Arc::clone(&browser_tools))
+                .with_fetch_cache(Arc::clone(&fetch_cache))
+                .with_output_config(tool_output.clone())
+                .with_policy(Arc::clone(&tool_policy), channel_name)
+                .with_session_profile(session_profile())
+                .with_audit_context(&session.id, &msg.sender_id);
+                if let Some(ref ct) = cron_tools {
+                    executor = executor
+                        .with_cron_tools(Arc::clone(ct))
+                        .with_reminder_origin(reminder_origin);
+                }
+                if let Some(ref st) = search_tool {
+                    executor = executor.with_search_tool(Arc::clone(st));
+                }
+                if let Some(ref ft) = fetch_tool {
+                    executor = executor.with_fetch_tool(Arc::clone(ft));
+                }
+                let mut loop_detector = crate::tools::LoopDetector::default();
+
+                for _turn in 0..10 {
+                    let request = synapse_client::ChatRequest {
+                        model: model_id.clone(),
+                        messages: llm_messages.clone(),
+                        stream: use_streaming,
+                        temperature: None,
+                        top_p: None,
+                        max_tokens: Some(max_tokens),
+                        stop: None,
+                        tools: tools.clone(),
+                        tool_choice: None,
+                    };
 
-        // Process with Synapse (multi-turn tool loop)
-        let response = {
-            let mut llm_messages = vec![
-                synapse_client::Message::system(&system_prompt),
-                synapse_client::Message::user(&augmented_prompt),
-            ];
-            let mut final_response = String::new();
-            let executor = crate::tools::executor::ToolExecutor::new(
-                Arc::clone(&synapse),
-                plugin_manager.clone(),
-            )
-            .with_exec_tool(Arc::clone(&exec_tool))
-            .with_browser_tools(Arc::clone(&browser_tools));
-            let mut loop_detector = crate::tools::LoopDetector::default();
-
-            for _turn in 0..10 {
-                let request = synapse_client::ChatRequest {
-                    model: model_id.clone(),
-                    messages: llm_messages.clone(),
-                    stream: use_streaming,
-                    temperature: None,
-                    top_p: None,
-                    max_tokens: Some(max_tokens),
-                    stop: None,
-                    tools: tools.clone(),
-                    tool_choice: None,
-                };
+                    let permit = match synapse_breaker.acquire() {
+                        Ok(permit) => permit,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "skipping synapse call");
+                            final_response = crate::synapse::UNAVAILABLE_REPLY.to_string();
+                            break;
+                        }
+                    };
 
-                #[allow(clippy::redundant_else)]
-                if use_streaming {
-                    // Streaming path: use chat_completion_stream
-                    match synapse.chat_completion_stream(&request).await {
-                        Ok(mut stream) => {
-                            let mut turn_text = String::new();
-                            let mut pending_tool_calls: Vec<DaemonPendingToolCall> = Vec::new();
-                            let mut finish_reason: Option<String> = None;
-
-                            while let Some(event) = stream.next().await {
-                                match event {
-                                    Ok(synapse_client::ChatEvent::ContentDelta(text)) => {
-                                        turn_text.push_str(&text);
-                                        if let Some(ref mid) = streaming_msg_id {
-                                            let _ = channel
-                                                .send_streaming_update(
-                                                    &msg.channel_id,
-                                                    mid,
-                                                    &turn_text,
-                                                )
-                                                .await;
+                    #[allow(clippy::redundant_else)]
+                    if use_streaming {
+                        // Streaming path: use chat_completion_stream
+                        match synapse.chat_completion_stream(&request).await {
+                            Ok(mut stream) => {
+                                let mut stream_failed = false;
+                                let mut turn_text = String::new();
+                                let mut pending_tool_calls: Vec<DaemonPendingToolCall> = Vec::new();
+                                let mut finish_reason: Option<String> = None;
+
+                                while let Some(event) = stream.next().await {
+                                    match event {
+                                        Ok(synapse_client::ChatEvent::ContentDelta(text)) => {
+                                            typing.stop();
+                                            turn_text.push_str(&text);
+                                            if defer_stream_start {
+                                                defer_stream_start = false;
+                                                streaming_msg_id = channel
+                                                    .send_streaming_start(
+                                                        &msg.channel_id,
+                                                        &turn_text,
+                                                        Some(&msg.id),
+                                                        msg.thread_id.as_deref(),
+                                                    )
+                                                    .await
+                                                    .ok();
+                                            } else if let Some(ref mid) = streaming_msg_id {
+                                                let _ = channel
+                                                    .send_streaming_update(
+                                                        &msg.channel_id,
+                                                        mid,
+                                                        &turn_text,
+                                                    )
+                                                    .await;
+                                            }
                                         }
-                                    }
-                                    Ok(synapse_client::ChatEvent::ToolCallStart {
-                                        index,
-                                        id,
-                                        name,
-                                    }) => {
-                                        let idx = index as usize;
-                                        while pending_tool_calls.len() <= idx {
-                                            pending_tool_calls
-                                                .push(DaemonPendingToolCall::default());
+                                        Ok(synapse_client::ChatEvent::ToolCallStart {
+                                            index,
+                                            id,
+                                            name,
+                                        }) => {
+                                            let idx = index as usize;
+                                            while pending_tool_calls.len() <= idx {
+                                                pending_tool_calls
+                                                    .push(DaemonPendingToolCall::default());
+                                            }
+                                            pending_tool_calls[idx].id = id;
+                                            pending_tool_calls[idx].name = name;
                                         }
-                                        pending_tool_calls[idx].id = id;
-                                        pending_tool_calls[idx].name = name;
-                                    }
-                                    Ok(synapse_client::ChatEvent::ToolCallDelta {
-                                        index,
-                                        arguments,
-                                    }) => {
-                                        let idx = index as usize;
-                                        if idx < pending_tool_calls.len() {
-                                            pending_tool_calls[idx].arguments.push_str(&arguments);
+                                        Ok(synapse_client::ChatEvent::ToolCallDelta {
+                                            index,
+                                            arguments,
+                                        }) => {
+                                            let idx = index as usize;
+                                            if idx < pending_tool_calls.len() {
+                                                pending_tool_calls[idx].arguments.push_str(&arguments);
+                                            }
                                         }
-                                    }
-                                    Ok(synapse_client::ChatEvent::Done {
-                                        finish_reason: fr,
-                                        ..
-                                    }) => {
-                                        finish_reason = fr;
-                                        break;
-                                    }
-                                    Ok(synapse_client::ChatEvent::Error(e)) => {
-                                        tracing::error!(error = %e, "streaming error");
-                                        break;
-                                    }
-                                    Err(e) => {
-                                        tracing::error!(error = %e, "stream event error");
-                                        break;
-                                    }
-                                }
-                            }
-
-                            if !turn_text.is_empty() {
-                                final_response.push_str(&turn_text);
-                            }
-
-                            // Handle tool calls from streaming
-                            if finish_reason.as_deref() == Some("tool_calls")
-                                && !pending_tool_calls.is_empty()
-                            {
-                                let tool_calls: Vec<synapse_client::ToolCall> = pending_tool_calls
-                                    .iter()
-                                    .map(|tc| synapse_client::ToolCall {
-                                        id: tc.id.clone(),
-                                        tool_type: "function".to_owned(),
-                                        function: synapse_client::FunctionCall {
-                                            name: tc.name.clone(),
-                                            arguments: tc.arguments.clone(),
-                                        },
-                                    })
-                                    .collect();
-
-                                let assistant_content = if turn_text.is_empty() {
-                                    serde_json::Value::Null
-                                } else {
-                                    serde_json::Value::String(turn_text)
-                                };
-
-                                llm_messages.push(synapse_client::Message {
-                                    role: "assistant".to_owned(),
-                                    content: assistant_content,
-                                    tool_calls: Some(tool_calls.clone()),
-                                    tool_call_id: None,
-                                });
-
-                                let mut should_break = false;
-                                for tc in &tool_calls {
-                                    tracing::info!(
-                                        tool = %tc.function.name,
-                                        args_len = tc.function.arguments.len(),
-                                        "executing tool call"
-                                    );
-                                    let result = executor
-                                        .execute(&tc.function.name, &tc.function.arguments)
-                                        .await
-                                        .unwrap_or_else(|e| format!("Error: {e}"));
-
-                                    let severity = loop_detector.record(
-                                        &tc.function.name,
-                                        &tc.function.arguments,
-                                        &result,
-                                    );
-                                    match severity {
-                                        crate::tools::LoopSeverity::CircuitBreaker => {
-                                            tracing::warn!(tool = %tc.function.name, "circuit breaker: tool loop detected");
-                                            llm_messages.push(synapse_client::Message::tool(
-                                                &tc.id,
-                                                "Error: Circuit breaker triggered — this tool has been called too many times with the same arguments. Please try a different approach.",
-                                            ));
-                                            should_break = true;
+                                        Ok(synapse_client::ChatEvent::Done {
+                                            finish_reason: fr,
+                                            usage,
+                                        }) => {
+                                            finish_reason = fr;
+                                            if let Some(u) = usage {
+                                                reported_tokens += u64::from(u.prompt_tokens)
+                                                    + u64::from(u.completion_tokens);
+                                            }
                                             break;
                                         }
-                                        crate::tools::LoopSeverity::Critical => {
-                                            tracing::warn!(tool = %tc.function.name, "critical: possible tool loop");
-                                            llm_messages.push(synapse_client::Message::tool(
-                                                &tc.id, &result,
-                                            ));
-                                            llm_messages.push(synapse_client::Message::system(
-                                                "Warning: You appear to be in a loop calling the same tool repeatedly. Please try a different approach or provide a final answer.",
-                                            ));
-                                        }
-                                        crate::tools::LoopSeverity::Warning => {
-                                            tracing::info!(tool = %tc.function.name, "warning: repeated tool call pattern");
-                                            llm_messages.push(synapse_client::Message::tool(
-                                                &tc.id, &result,
-                                            ));
+                                        Ok(synapse_client::ChatEvent::Error(e)) => {
+                                            tracing::error!(error = %e, "streaming error");
+                                            stream_failed = true;
+                                            break;
                                         }
-                                        crate::tools::LoopSeverity::None => {
-                                            llm_messages.push(synapse_client::Message::tool(
-                                                &tc.id, &result,
-                                            ));
+                                        Err(e) => {
+                                            tracing::error!(error = %e, "stream event error");
+                                            stream_failed = true;
+                                            break;
                                         }
                                     }
-
-                                    let tool_success = !result.starts_with("Error: ");
-                                    crate::events::publish(
-                                        crate::events::build_tool_executed_event(
-                                            &session.id,
-                                            &tc.function.name,
-                                            tool_success,
-                                            &msg.sender_id,
-                                        ),
-                                    );
                                 }
-
-                                if should_break {
-                                    break;
+                                // A stream that breaks off is a failed call
+                                if stream_failed {
+                                    permit.failure();
+                                } else {
+                                    permit.success();
                                 }
-                                continue;
-                            }
-
-                            break;
-                        }
-                        Err(e) => {
-                            tracing::error!(error = %e, "synapse stream error");
-                            final_response =
-                                "Sorry, I encountered an error processing your message."
-                                    .to_string();
-                            break;
-                        }
-                    }
-                } else {
-                    // Non-streaming path: use chat_completion
-                    match synapse.chat_completion(&request).await {
-                        Ok(resp) => {
-                            let Some(choice) = resp.choices.first() else {
-                                break;
-                            };
 
-                            if let Some(ref text) = choice.message.content {
-                                final_response.push_str(text);
-                            }
+                                if !turn_text.is_empty() {
+                                    final_response.push_str(&turn_text);
+                                }
 
-                            if choice.finish_reason.as_deref() == Some("tool_calls")
-                                && let Some(ref tool_calls) = choice.message.tool_calls
-                            {
-                                let assistant_content = choice
-                                    .message
-                                    .content
-                                    .as_ref()
-                                    .map_or(serde_json::Value::Null, |t| {
-                                        serde_json::Value::String(t.clone())
+                                // Handle tool calls from streaming
+                                if finish_reason.as_deref() == Some("tool_calls")
+                                    && !pending_tool_calls.is_empty()
+                                {
+                                    let tool_calls: Vec<synapse_client::ToolCall> = pending_tool_calls
+                                        .iter()
+                                        .map(|tc| synapse_client::ToolCall {
+                                            id: tc.id.clone(),
+                                            tool_type: "function".to_owned(),
+                                            function: synapse_client::FunctionCall {
+                                                name: tc.name.clone(),
+                                                arguments: tc.arguments.clone(),
+                                            },
+                                        })
+                                        .collect();
+
+                                    let assistant_content = if turn_text.is_empty() {
+                                        serde_json::Value::Null
+                                    } else {
+                                        serde_json::Value::String(turn_text)
+                                    };
+
+                                    llm_messages.push(synapse_client::Message {
+                                        role: "assistant".to_owned(),
+                                        content: assistant_content,
+                                        tool_calls: Some(tool_calls.clone()),
+                                        tool_call_id: None,
                                     });
 
-                                llm_messages.push(synapse_client::Message {
-                                    role: "assistant".to_owned(),
-                                    content: assistant_content,
-                                    tool_calls: Some(tool_calls.clone()),
-                                    tool_call_id: None,
-                                });
-
-                                let mut should_break = false;
-                                for tc in tool_calls {
-                                    let result = executor
-                                        .execute(&tc.function.name, &tc.function.arguments)
-                                        .await
-                                        .unwrap_or_else(|e| format!("Error: {e}"));
-
-                                    let severity = loop_detector.record(
-                                        &tc.function.name,
-                                        &tc.function.arguments,
-                                        &result,
-                                    );
-                                    match severity {
-                                        crate::tools::LoopSeverity::CircuitBreaker => {
-                                            tracing::warn!(
-                                                tool = %tc.function.name,
-                                                "circuit breaker: tool loop detected, breaking"
-                                            );
-                                            llm_messages.push(synapse_client::Message::tool(
+                                    let mut should_break = false;
+                                    for tc in &tool_calls {
+                                        tracing::info!(
+                                            tool = %tc.function.name,
+                                            args_len = tc.function.arguments.len(),
+                                            "executing tool call"
+                                        );
+                                        let started = std::time::Instant::now();
+                                        let result = executor
+                                            .execute(&tc.function.name, &tc.function.arguments)
+                                            .await
+                                            .unwrap_or_else(|e| format!("Error: {e}"));
+
+                                        let severity = loop_detector.record(
+                                            &tc.function.name,
+                                            &tc.function.arguments,
+                                            &result,
+                                        );
+                                        match severity {
+                                            crate::tools::LoopSeverity::CircuitBreaker => {
+                                                tracing::warn!(tool = %tc.function.name, "circuit breaker: tool loop detected");
+                                                llm_messages.push(synapse_client::Message::tool(
                                                     &tc.id,
                                                     "Error: Circuit breaker triggered — this tool has been called too many times with the same arguments. Please try a different approach.",
                                                 ));
-                                            should_break = true;
-                                            break;
-                                        }
-                                        crate::tools::LoopSeverity::Critical => {
-                                            tracing::warn!(
-                                                tool = %tc.function.name,
-                                                "critical: possible tool loop detected"
-                                            );
-                                            llm_messages.push(synapse_client::Message::tool(
-                                                &tc.id, &result,
-                                            ));
-                                            llm_messages.push(synapse_client::Message::system(
+                                                should_break = true;
+                                                break;
+                                            }
+                                            crate::tools::LoopSeverity::Critical => {
+                                                tracing::warn!(tool = %tc.function.name, "critical: possible tool loop");
+                                                llm_messages.push(synapse_client::Message::tool(
+                                                    &tc.id, &result,
+                                                ));
+                                                llm_messages.push(synapse_client::Message::system(
                                                     "Warning: You appear to be in a loop calling the same tool repeatedly. Please try a different approach or provide a final answer.",
                                                 ));
+                                            }
+                                            crate::tools::LoopSeverity::Warning => {
+                                                tracing::info!(tool = %tc.function.name, "warning: repeated tool call pattern");
+                                                llm_messages.push(synapse_client::Message::tool(
+                                                    &tc.id, &result,
+                                                ));
+                                            }
+                                            crate::tools::LoopSeverity::None => {
+                                                llm_messages.push(synapse_client::Message::tool(
+                                                    &tc.id, &result,
+                                                ));
+                                            }
                                         }
-                                        crate::tools::LoopSeverity::Warning => {
-                                            tracing::info!(
-                                                tool = %tc.function.name,
-                                                "warning: repeated tool call pattern detected"
-                                            );
-                                            llm_messages.push(synapse_client::Message::tool(
-                                                &tc.id, &result,
-                                            ));
-                                        }
-                                        crate::tools::LoopSeverity::None => {
-                                            llm_messages.push(synapse_client::Message::tool(
-                                                &tc.id, &result,
-                                            ));
-                                        }
+
+                                        let tool_success = !result.starts_with("Error: ");
+                                        crate::events::publish(
+                                            crate::events::build_tool_executed_event(
+                                                &session.id,
+                                                &tc.function.name,
+                                                tool_success,
+                                                &msg.sender_id,
+                                                u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+                                                &tc.function.arguments,
+                                            ),
+                                        );
                                     }
 
-                                    let tool_success = !result.starts_with("Error: ");
-                                    crate::events::publish(
-                                        crate::events::build_tool_executed_event(
-                                            &session.id,
-                                            &tc.function.name,
-                                            tool_success,
-                                            &msg.sender_id,
-                                        ),
-                                    );
+                                    if should_break {
+                                        break;
+                                    }
+                                    continue;
                                 }
 
-                                if should_break {
+                                break;
+                            }
+                            Err(e) => {
+                                permit.failure();
+                                tracing::error!(error = %e, "synapse stream error");
+                                final_response =
+                                    "Sorry, I encountered an error processing your message."
+                                        .to_string();
+                                break;
+                            }
+                        }
+                    } else {
+                        // Non-streaming path: use chat_completion
+                        match synapse.chat_completion(&request).await {
+                            Ok(resp) => {
+                                permit.success();
+                                let Some(choice) = resp.choices.first() else {
                                     break;
+                                };
+
+                                if let Some(ref text) = choice.message.content {
+                                    final_response.push_str(text);
                                 }
-                                continue;
-                            }
 
-                            break;
-                        }
-                        Err(e) => {
-                            tracing::error!(error = %e, "synapse error");
-                            final_response =
-                                "Sorry, I encountered an error processing your message."
-                                    .to_string();
-                            break;
+                                if choice.finish_reason.as_deref() == Some("tool_calls")
+                                    && let Some(ref tool_calls) = choice.message.tool_calls
+                                {
+                                    let assistant_content = choice
+                                        .message
+                                        .content
+                                        .as_ref()
+                                        .map_or(serde_json::Value::Null, |t| {
+                                            serde_json::Value::String(t.clone())
+                                        });
+
+                                    llm_messages.push(synapse_client::Message {
+                                        role: "assistant".to_owned(),
+                                        content: assistant_content,
+                                        tool_calls: Some(tool_calls.clone()),
+                                        tool_call_id: None,
+                                    });
+
+                                    let mut should_break = false;
+                                    for tc in tool_calls {
+                                        let started = std::time::Instant::now();
+                                        let result = executor
+                                            .execute(&tc.function.name, &tc.function.arguments)
+                                            .await
+                                            .unwrap_or_else(|e| format!("Error: {e}"));
+
+                                        let severity = loop_detector.record(
+                                            &tc.function.name,
+                                            &tc.function.arguments,
+                                            &result,
+                                        );
+                                        match severity {
+                                            crate::tools::LoopSeverity::CircuitBreaker => {
+                                                tracing::warn!(
+                                                    tool = %tc.function.name,
+                                                    "circuit breaker: tool loop detected, breaking"
+                                                );
+                                                llm_messages.push(synapse_client::Message::tool(
+                                                        &tc.id,
+                                                        "Error: Circuit breaker triggered — this tool has been called too many times with the same arguments. Please try a different approach.",
+                                                    ));
+                                                should_break = true;
+                                                break;
+                                            }
+                                            crate::tools::LoopSeverity::Critical => {
+                                                tracing::warn!(
+                                                    tool = %tc.function.name,
+                                                    "critical: possible tool loop detected"
+                                                );
+                                                llm_messages.push(synapse_client::Message::tool(
+                                                    &tc.id, &result,
+                                                ));
+                                                llm_messages.push(synapse_client::Message::system(
+                                                        "Warning: You appear to be in a loop calling the same tool repeatedly. Please try a different approach or provide a final answer.",
+                                                    ));
+                                            }
+                                            crate::tools::LoopSeverity::Warning => {
+                                                tracing::info!(
+                                                    tool = %tc.function.name,
+                                                    "warning: repeated tool call pattern detected"
+                                                );
+                                                llm_messages.push(synapse_client::Message::tool(
+                                                    &tc.id, &result,
+                                                ));
+                                            }
+                                            crate::tools::LoopSeverity::None => {
+                                                llm_messages.push(synapse_client::Message::tool(
+                                                    &tc.id, &result,
+                                                ));
+                                            }
+                                        }
+
+                                        let tool_success = !result.starts_with("Error: ");
+                                        crate::events::publish(
+                                            crate::events::build_tool_executed_event(
+                                                &session.id,
+                                                &tc.function.name,
+                                                tool_success,
+                                                &msg.sender_id,
+                                                u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+                                                &tc.function.arguments,
+                                            ),
+                                        );
+                                    }
+
+                                    if should_break {
+                                        break;
+                                    }
+                                    continue;
+                                }
+
+                                break;
+                            }
+                            Err(e) => {
+                                permit.failure();
+                                tracing::error!(error = %e, "synapse error");
+                                final_response =
+                                    "Sorry, I encountered an error processing your message."
+                                        .to_string();
+                                break;
+                            }
                         }
                     }
                 }
-            }
 
-            // Finalize streaming message
-            if let Some(ref mid) = streaming_msg_id {
-                let _ = channel
-                    .send_streaming_end(&msg.channel_id, mid, &final_response)
-                    .await;
+                // Finalize streaming message
+                if let Some(ref mid) = streaming_msg_id {
+                    let _ = channel
+                        .send_streaming_end(&msg.channel_id, mid, &final_response)
+                        .await;
+                }
+
+                final_response
+            })
+            .await;
+
+            usage_cap.record(
+                &user.id,
+                crate::usage::turn_tokens(reported_tokens, &augmented_prompt, &response),
+            );
+
+            // Hook: message:after_agent - can modify response
+            let hook_event = HookEvent::new(HookAction::AfterAgent, channel_name, &msg)
+                .with_session(&session.id)
+                .with_response(&response);
+            let hook_result = hook_manager.trigger(&hook_event).await;
+            let response = hook_result.modified_response.unwrap_or(response);
+
+            // Hook: response:generated - final text before send, can rewrite or suppress it
+            let hook_event = HookEvent::new(HookAction::ResponseGenerated, channel_name, &msg)
+                .with_session(&session.id)
+                .with_response(&response);
+            let hook_result = hook_manager.trigger(&hook_event).await;
+            if hook_result.skip_processing {
+                tracing::debug!(channel = channel_name, "hook suppressed response");
+                // Streaming may already have shown part of the response; retract it
+                if let Some(ref mid) = streaming_msg_id
+                    && let Err(e) = channel.delete_message(&msg.channel_id, mid).await
+                {
+                    tracing::warn!(error = %e, "failed to delete suppressed streamed response");
+                }
+                return;
             }
+            let response = match hook_result.modified_response {
+                Some(rewritten) if rewritten != response => {
+                    // Streaming already delivered the original text; replace it in place
+                    if let Some(ref mid) = streaming_msg_id
+                        && let Err(e) = channel.edit_message(&msg.channel_id, mid, &rewritten).await
+                    {
+                        tracing::warn!(error = %e, "failed to apply rewritten response");
+                    }
+                    rewritten
+                }
+                _ => response,
+            };
 
-            final_response
-        };
+            // Store assistant response with thread context
+            if let Err(e) = session_repo.add_message_with_thread(
+                &session.id,
+                MessageRole::Assistant,
+                &response,
+                thread_id,
+            ) {
+                tracing::warn!(error = %e, "failed to store assistant message");
+            }
 
-        // Hook: message:after_agent - can modify response
-        let hook_event = HookEvent::new(HookAction::AfterAgent, channel_name, &msg)
-            .with_session(&session.id)
-            .with_response(&response);
-        let hook_result = hook_manager.trigger(&hook_event).await;
-        let response = hook_result.modified_response.unwrap_or(response);
-
-        // Store assistant response with thread context
-        if let Err(e) = session_repo.add_message_with_thread(
-            &session.id,
-            MessageRole::Assistant,
-            &response,
-            thread_id,
-        ) {
-            tracing::warn!(error = %e, "failed to store assistant message");
-        }
-
-        // Send response (skip if streaming already delivered it)
-        if streaming_msg_id.is_none() {
-            let outgoing = OutgoingMessage {
-                channel_id: msg.channel_id.clone(),
-                content: response,
-                reply_to: thread_id.map(String::from).or_else(|| Some(msg.id.clone())),
-                thread_id: None,
-                keyboard: None,
-                media: vec![],
-                edit_target: None,
-                voice_note: false,
+            // Preview images for links in the reply ride along as attachments
+            let attachments: Vec<_> = match link_previews {
+                Some(ref links) => links
+                    .process_message(&response)
+                    .await
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(crate::links::LinkPreview::image_attachment)
+                    .collect(),
+                None => vec![],
             };
 
-            if let Err(e) = channel.send(outgoing).await {
-                tracing::error!(error = %e, "send error");
+            // Send response; if streaming already delivered the text, only
+            // the attachments are left to send
+            if streaming_msg_id.is_none() || !attachments.is_empty() {
+                let content = if streaming_msg_id.is_none() {
+                    response
+                } else {
+                    String::new()
+                };
+                let outgoing = OutgoingMessage {
+                    channel_id: msg.channel_id.clone(),
+                    content,
+                    reply_to: thread_id.map(String::from).or_else(|| Some(msg.id.clone())),
+                    thread_id: msg.thread_id.clone(),
+                    keyboard: None,
+                    media: vec![],
+                    edit_target: None,
+                    voice_note: false,
+                    attachments,
+                };
+
+                if let Err(e) = channel.send_media(outgoing).await {
+                    tracing::error!(error = %e, "send error");
+                }
             }
-        }
 
-        // Mark complete with reaction (configurable for Telegram)
-        if reaction_level != crate::config::ReactionLevel::Off {
-            let done_emoji = telegram_config
-                .as_ref()
-                .filter(|_| channel_name == "telegram")
-                .map_or("\u{2705}", |c| c.done_reaction.as_str());
-            if let Err(e) = channel
-                .add_reaction(&msg.channel_id, &msg.id, done_emoji)
-                .await
-            {
-                tracing::debug!(error = %e, "done reaction failed");
+            // Mark complete with reaction (configurable for Telegram)
+            if reaction_level != crate::config::ReactionLevel::Off {
+                let done_emoji = telegram_config
+                    .as_ref()
+                    .filter(|_| channel_name == "telegram")
+                    .map_or("\u{2705}", |c| c.done_reaction.as_str());
+                if let Err(e) = channel
+                    .add_reaction(&msg.channel_id, &msg.id, done_emoji)
+                    .await
+                {
+                    tracing::debug!(error = %e, "done reaction failed");
+                }
             }
-        }
 
-        // Publish beacon.message.processed event (best-effort)
-        crate::events::publish(
-            crate::events::OmniEvent::new(
-                "beacon.message.processed",
-                &msg.sender_id,
-                serde_json::json!({
-                    "channel": channel_name,
-                    "messageId": msg.id,
-                    "userId": msg.sender_id,
-                }),
-            )
-            .with_subject(&msg.sender_id),
-        );
+            // Publish beacon.message.processed event (best-effort)
+            crate::events::publish(
+                crate::events::OmniEvent::new(
+                    "beacon.message.processed",
+                    &msg.sender_id,
+                    serde_json::json!({
+                        "channel": channel_name,
+                        "messageId": msg.id,
+                        "userId": msg.sender_id,
+                    }),
+                )
+                .with_subject(&msg.sender_id),
+            );
 
-        // Publish beacon.conversation.ended event (best-effort)
-        // For daemon channels, one request-response exchange = one conversation
-        crate::events::publish(crate::events::build_conversation_ended_event(
-            &session.id,
-            channel_name,
-            &msg.sender_id,
-        ));
+            // Publish beacon.conversation.ended event (best-effort)
+            // For daemon channels, one request-response exchange = one conversation
+            crate::events::publish(crate::events::build_conversation_ended_event(
+                &session.id,
+                channel_name,
+                &msg.sender_id,
+            ));
+        };
+        crate::request_id::scope(request_id, Box::pin(turn)).await;
     }
 }
 
@@ -2267,12 +3498,11 @@ async fn handle_voice_command(
     model_id: &str,
     system_prompt: &str,
     max_tokens: u32,
-    tts_model: &str,
-    tts_voice: &str,
-    tts_speed: f64,
+    tts: &TextToSpeech,
     command: &str,
     voice_context: Option<&str>,
     plugin_manager: &crate::api::plugins::SharedPluginManager,
+    tool_output: &crate::tools::ToolOutputConfig,
 ) -> Result<()> {
     tracing::info!(command, "processing voice command");
 
@@ -2287,11 +3517,19 @@ async fn handle_voice_command(
     // Fetch available tools from Synapse MCP and plugins
     let exec_tool = Arc::new(crate::tools::BuiltinExecTool::default());
     let browser_tools = Arc::new(crate::tools::BuiltinBrowserTools::new());
+    let search_tool = crate::tools::BuiltinSearchTool::from_env().map(Arc::new);
+    let fetch_tool = crate::tools::BuiltinFetchTool::from_env().map(Arc::new);
     let tools = {
-        let executor =
+        let mut executor =
             crate::tools::executor::ToolExecutor::new(Arc::clone(synapse), plugin_manager.clone())
                 .with_exec_tool(Arc::clone(&exec_tool))
                 .with_browser_tools(Arc::clone(&browser_tools));
+        if let Some(ref st) = search_tool {
+            executor = executor.with_search_tool(Arc::clone(st));
+        }
+        if let Some(ref ft) = fetch_tool {
+            executor = executor.with_fetch_tool(Arc::clone(ft));
+        }
         executor.list_tools().await.ok()
     };
 
@@ -2300,10 +3538,17 @@ async fn handle_voice_command(
         synapse_client::Message::user(&prompt),
     ];
     let mut final_text = String::new();
-    let executor =
+    let mut executor =
         crate::tools::executor::ToolExecutor::new(Arc::clone(synapse), plugin_manager.clone())
             .with_exec_tool(exec_tool)
-            .with_browser_tools(browser_tools);
+            .with_browser_tools(browser_tools)
+            .with_output_config(tool_output.clone());
+    if let Some(st) = search_tool {
+        executor = executor.with_search_tool(st);
+    }
+    if let Some(ft) = fetch_tool {
+        executor = executor.with_fetch_tool(ft);
+    }
 
     for _turn in 0..10 {
         let request = synapse_client::ChatRequest {
@@ -2366,40 +3611,11 @@ async fn handle_voice_command(
     }
 
     tracing::debug!(response_len = final_text.len(), "synapse responded");
-    speak(
-        playback,
-        synapse,
-        tts_model,
-        tts_voice,
-        tts_speed,
-        &final_text,
-    )
-    .await
+    tts.speak(playback, &final_text).await
 }
 
-/// Speak via Synapse TTS
-async fn speak(
-    playback: &mut AudioPlayback,
-    synapse: &SynapseClient,
-    tts_model: &str,
-    tts_voice: &str,
-    tts_speed: f64,
-    text: &str,
-) -> Result<()> {
-    tracing::debug!(text, "speaking");
-    let request = synapse_client::SpeechRequest {
-        model: tts_model.to_string(),
-        input: text.to_string(),
-        voice: tts_voice.to_string(),
-        response_format: None,
-        speed: Some(tts_speed),
-    };
-    let audio = synapse
-        .synthesize(&request)
-        .await
-        .map_err(|e| Error::Tts(e.to_string()))?;
-    playback.play_mp3(&audio).await
-}
+/// Open streaming STT session: audio in, transcripts out
+type SttStream = (mpsc::Sender<Vec<f32>>, mpsc::Receiver<PartialTranscript>);
 
 /// Extract command after wake word
 fn extract_command(transcript: &str, wake_word: &str) -> String {
@@ -2416,17 +3632,6 @@ fn extract_command(transcript: &str, wake_word: &str) -> String {
     )
 }
 
-/// Map LLM tool names to policy category names
-fn normalize_tool_name(name: &str) -> String {
-    match name {
-        "Bash" => "shell".to_string(),
-        "Read" | "Glob" | "Grep" | "WebFetch" | "ListDir" => "read_file".to_string(),
-        "Write" | "Edit" => "write_file".to_string(),
-        "WebSearch" => "web_search".to_string(),
-        other => other.to_string(),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2439,20 +3644,4 @@ mod tests {
         );
         assert_eq!(extract_command("Hey Orin", "hey orin"), "");
     }
-
-    #[test]
-    fn test_normalize_tool_name() {
-        assert_eq!(normalize_tool_name("Bash"), "shell");
-        assert_eq!(normalize_tool_name("Read"), "read_file");
-        assert_eq!(normalize_tool_name("Write"), "write_file");
-        assert_eq!(normalize_tool_name("Edit"), "write_file");
-        assert_eq!(normalize_tool_name("WebSearch"), "web_search");
-        assert_eq!(normalize_tool_name("WebFetch"), "read_file");
-        assert_eq!(normalize_tool_name("Glob"), "read_file");
-        assert_eq!(normalize_tool_name("Grep"), "read_file");
-        assert_eq!(normalize_tool_name("ListDir"), "read_file");
-        // Unknown tools pass through
-        assert_eq!(normalize_tool_name("memory_store"), "memory_store");
-        assert_eq!(normalize_tool_name("cron_list"), "cron_list");
-    }
 }
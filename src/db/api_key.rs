@@ -0,0 +1,269 @@
+//! Per-user API keys
+//!
+//! Keys look like `bk_<id>_<secret>`. The id locates the row; only a salted
+//! Argon2 hash of the whole key is stored, so a leaked database doesn't leak
+//! keys.
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use rusqlite::Row;
+use serde::Serialize;
+
+use super::DbPool;
+use crate::security::auth::{hash_api_key, verify_api_key};
+use crate::{Error, Result};
+
+/// Prefix of every per-user API key
+pub const API_KEY_PREFIX: &str = "bk_";
+
+/// Scope granted to keys minted without explicit scopes
+pub const DEFAULT_API_KEY_SCOPE: &str = "chat";
+
+/// Key id length in bytes (hex encoded)
+const KEY_ID_BYTES: usize = 6;
+
+/// Key secret length in bytes (hex encoded)
+const KEY_SECRET_BYTES: usize = 32;
+
+const API_KEY_COLUMNS: &str = "id, user_id, name, scopes, created_at, last_used_at, revoked_at";
+
+/// A per-user API key (without its secret)
+#[derive(Debug, Clone, Serialize)]
+pub struct UserApiKey {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl UserApiKey {
+    /// Whether the key grants `scope`
+    #[must_use]
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+/// Repository for per-user API keys
+#[derive(Debug, Clone)]
+pub struct UserApiKeyRepo {
+    pool: DbPool,
+}
+
+impl UserApiKeyRepo {
+    /// Create a new repository
+    #[must_use]
+    pub const fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Mint a key for a user
+    ///
+    /// Returns the stored key and the raw key, which is not recoverable later.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if hashing or the database operation fails
+    pub fn create(
+        &self,
+        user_id: &str,
+        name: &str,
+        scopes: &[String],
+    ) -> Result<(UserApiKey, String)> {
+        let (id, secret) = {
+            let mut rng = rand::thread_rng();
+            let id: [u8; KEY_ID_BYTES] = rng.r#gen();
+            let secret: [u8; KEY_SECRET_BYTES] = rng.r#gen();
+            (hex::encode(id), hex::encode(secret))
+        };
+        let raw = format!("{API_KEY_PREFIX}{id}_{secret}");
+        let key_hash = hash_api_key(&raw)?;
+
+        let scopes = if scopes.is_empty() {
+            vec![DEFAULT_API_KEY_SCOPE.to_string()]
+        } else {
+            scopes.to_vec()
+        };
+        let now = Utc::now();
+
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+        conn.execute(
+            "INSERT INTO user_api_keys (id, user_id, name, key_hash, scopes, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            [
+                &id,
+                user_id,
+                name,
+                &key_hash,
+                &scopes.join(" "),
+                &now.to_rfc3339(),
+            ],
+        )?;
+
+        tracing::info!(key_id = %id, user_id, "minted API key");
+        Ok((
+            UserApiKey {
+                id,
+                user_id: user_id.to_string(),
+                name: name.to_string(),
+                scopes,
+                created_at: now,
+                last_used_at: None,
+                revoked_at: None,
+            },
+            raw,
+        ))
+    }
+
+    /// Resolve a presented raw key to its active record
+    ///
+    /// Returns None for malformed, unknown, revoked, or mismatched keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn authenticate(&self, raw: &str) -> Result<Option<UserApiKey>> {
+        let Some((id, _)) = raw
+            .strip_prefix(API_KEY_PREFIX)
+            .and_then(|rest| rest.split_once('_'))
+        else {
+            return Ok(None);
+        };
+
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+        let record = conn.query_row(
+            &format!(
+                "SELECT {API_KEY_COLUMNS}, key_hash FROM user_api_keys
+                 WHERE id = ?1 AND revoked_at IS NULL"
+            ),
+            [id],
+            |row| Ok((row_to_key(row)?, row.get::<_, String>(7)?)),
+        );
+        let (key, key_hash) = match record {
+            Ok(record) => record,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        if !verify_api_key(raw, &key_hash) {
+            tracing::warn!(key_id = %key.id, "API key secret mismatch");
+            return Ok(None);
+        }
+
+        conn.execute(
+            "UPDATE user_api_keys SET last_used_at = ?1 WHERE id = ?2",
+            [&Utc::now().to_rfc3339(), &key.id],
+        )?;
+        Ok(Some(key))
+    }
+
+    /// Revoke a key
+    ///
+    /// Returns false if no active key has this id.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn revoke(&self, key_id: &str) -> Result<bool> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let rows = conn.execute(
+            "UPDATE user_api_keys SET revoked_at = ?1 WHERE id = ?2 AND revoked_at IS NULL",
+            [&Utc::now().to_rfc3339(), key_id],
+        )?;
+        if rows > 0 {
+            tracing::info!(key_id, "revoked API key");
+        }
+
+        Ok(rows > 0)
+    }
+
+    /// List a user's keys, newest first, including revoked ones
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn list(&self, user_id: &str) -> Result<Vec<UserApiKey>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {API_KEY_COLUMNS} FROM user_api_keys WHERE user_id = ?1 ORDER BY created_at DESC"
+        ))?;
+        let keys = stmt
+            .query_map([user_id], row_to_key)?
+            .filter_map(std::result::Result::ok)
+            .collect();
+
+        Ok(keys)
+    }
+}
+
+fn row_to_key(row: &Row<'_>) -> rusqlite::Result<UserApiKey> {
+    let parse = |s: String| {
+        DateTime::parse_from_rfc3339(&s).map_or_else(|_| Utc::now(), |dt| dt.with_timezone(&Utc))
+    };
+    Ok(UserApiKey {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        name: row.get(2)?,
+        scopes: row
+            .get::<_, String>(3)?
+            .split_whitespace()
+            .map(ToString::to_string)
+            .collect(),
+        created_at: parse(row.get(4)?),
+        last_used_at: row.get::<_, Option<String>>(5)?.map(parse),
+        revoked_at: row.get::<_, Option<String>>(6)?.map(parse),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_memory;
+
+    #[test]
+    fn minted_key_authenticates_until_revoked() {
+        let repo = UserApiKeyRepo::new(init_memory().unwrap());
+
+        let (key, raw) = repo.create("alice", "laptop", &[]).unwrap();
+        assert!(raw.starts_with(API_KEY_PREFIX));
+        assert_eq!(key.scopes, vec!["chat"]);
+
+        let found = repo.authenticate(&raw).unwrap().unwrap();
+        assert_eq!(found.user_id, "alice");
+
+        // Right id, wrong secret
+        let (prefix, _) = raw.rsplit_once('_').unwrap();
+        assert!(
+            repo.authenticate(&format!("{prefix}_{}", "0".repeat(64)))
+                .unwrap()
+                .is_none()
+        );
+        assert!(repo.authenticate("not-a-key").unwrap().is_none());
+
+        assert!(repo.revoke(&key.id).unwrap());
+        assert!(repo.authenticate(&raw).unwrap().is_none());
+        assert!(!repo.revoke(&key.id).unwrap());
+
+        let keys = repo.list("alice").unwrap();
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].revoked_at.is_some());
+        assert!(keys[0].last_used_at.is_some());
+    }
+}
@@ -1,6 +1,337 @@
 //! Text embedding for semantic memory search
 //!
-//! Re-exports from agent-core. Error conversion from `EmbedderError`
-//! to Beacon's `Error` is handled via `From` impl in `error.rs`
+//! Embeddings come from a pluggable [`EmbeddingBackend`], selected with
+//! `BEACON_EMBEDDING_BACKEND`: `openai` (default, requires `OPENAI_API_KEY`)
+//! or `local`, which runs an ONNX model in-process and requires the
+//! `local-embeddings` cargo feature. Error conversion from agent-core's
+//! `EmbedderError` to Beacon's `Error` is handled via `From` impl in `error.rs`
 
-pub use agent_core::knowledge::{EMBEDDING_DIM, Embedder, EmbedderError};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{Error, Result};
+
+/// Embedding dimension of the `OpenAI` backend
+pub const OPENAI_EMBEDDING_DIM: usize = agent_core::knowledge::EMBEDDING_DIM;
+
+/// Embedding dimension of the local backend (BGE small, English v1.5)
+pub const LOCAL_EMBEDDING_DIM: usize = 384;
+
+/// A source of text embeddings
+#[async_trait]
+pub trait EmbeddingBackend: Send + Sync {
+    /// Backend name for logs
+    fn name(&self) -> &'static str;
+
+    /// Length of every vector this backend produces
+    fn dimension(&self) -> usize;
+
+    /// Embed several texts, returning one vector per input in order
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>>;
+
+    /// Embed a single text
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch(&[text])
+            .await?
+            .pop()
+            .ok_or_else(|| Error::Embedding("backend returned no embedding".to_string()))
+    }
+}
+
+/// Which embedding backend is active
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmbeddingBackendKind {
+    /// `OpenAI` embeddings API
+    #[default]
+    OpenAi,
+    /// In-process ONNX model
+    Local,
+}
+
+impl std::str::FromStr for EmbeddingBackendKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "openai" => Ok(Self::OpenAi),
+            "local" | "fastembed" | "onnx" => Ok(Self::Local),
+            other => Err(Error::Config(format!(
+                "unknown BEACON_EMBEDDING_BACKEND: {other} (expected `openai` or `local`)"
+            ))),
+        }
+    }
+}
+
+impl EmbeddingBackendKind {
+    /// Backend from `BEACON_EMBEDDING_BACKEND` (default: `openai`)
+    ///
+    /// # Errors
+    ///
+    /// Returns a config error for an unknown backend name
+    pub fn from_env() -> Result<Self> {
+        std::env::var("BEACON_EMBEDDING_BACKEND")
+            .ok()
+            .map_or_else(|| Ok(Self::default()), |v| v.parse())
+    }
+
+    /// Vector dimension produced by this backend
+    #[must_use]
+    pub const fn dimension(self) -> usize {
+        match self {
+            Self::OpenAi => OPENAI_EMBEDDING_DIM,
+            Self::Local => LOCAL_EMBEDDING_DIM,
+        }
+    }
+}
+
+/// `OpenAI` embeddings via agent-core
+pub struct OpenAiBackend {
+    inner: agent_core::knowledge::Embedder,
+}
+
+impl OpenAiBackend {
+    /// Create a backend using the given API key
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the HTTP client cannot be created
+    pub fn new(api_key: String) -> Result<Self> {
+        Ok(Self {
+            inner: agent_core::knowledge::Embedder::new(api_key)?,
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingBackend for OpenAiBackend {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn dimension(&self) -> usize {
+        OPENAI_EMBEDDING_DIM
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        Ok(self.inner.embed_batch(texts).await?)
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(self.inner.embed(text).await?)
+    }
+}
+
+/// In-process embeddings with `fastembed` (ONNX Runtime)
+#[cfg(feature = "local-embeddings")]
+pub struct LocalBackend {
+    model: Arc<fastembed::TextEmbedding>,
+}
+
+#[cfg(feature = "local-embeddings")]
+impl LocalBackend {
+    /// Load the local model, downloading it to the cache on first use
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the model cannot be downloaded or loaded
+    pub fn new() -> Result<Self> {
+        // Models are cached in `~/.cache/omni/beacon/models/`
+        let cache_dir = directories::BaseDirs::new().map_or_else(
+            || std::path::PathBuf::from(".cache/omni/beacon/models"),
+            |d| d.cache_dir().join("omni").join("beacon").join("models"),
+        );
+        let options = fastembed::InitOptions::new(fastembed::EmbeddingModel::BGESmallENV15)
+            .with_cache_dir(cache_dir)
+            .with_show_download_progress(false);
+        let model = fastembed::TextEmbedding::try_new(options)
+            .map_err(|e| Error::Embedding(format!("failed to load local model: {e}")))?;
+        Ok(Self {
+            model: Arc::new(model),
+        })
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+#[async_trait]
+impl EmbeddingBackend for LocalBackend {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn dimension(&self) -> usize {
+        LOCAL_EMBEDDING_DIM
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let model = Arc::clone(&self.model);
+        let texts: Vec<String> = texts.iter().map(ToString::to_string).collect();
+        tokio::task::spawn_blocking(move || model.embed(texts, None))
+            .await
+            .map_err(|e| Error::Embedding(format!("local embedding task failed: {e}")))?
+            .map_err(|e| Error::Embedding(format!("local embedding failed: {e}")))
+    }
+}
+
+/// Text embedder backed by the active [`EmbeddingBackend`]
+#[derive(Clone)]
+pub struct Embedder {
+    backend: Arc<dyn EmbeddingBackend>,
+}
+
+impl std::fmt::Debug for Embedder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Embedder")
+            .field("backend", &self.backend.name())
+            .field("dimension", &self.backend.dimension())
+            .finish()
+    }
+}
+
+impl Embedder {
+    /// Create an `OpenAI` embedder
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the HTTP client cannot be created
+    pub fn new(api_key: String) -> Result<Self> {
+        Ok(Self::with_backend(Arc::new(OpenAiBackend::new(api_key)?)))
+    }
+
+    /// Create an embedder from any backend
+    #[must_use]
+    pub fn with_backend(backend: Arc<dyn EmbeddingBackend>) -> Self {
+        Self { backend }
+    }
+
+    /// Create the embedder selected by `BEACON_EMBEDDING_BACKEND`
+    ///
+    /// Returns `Ok(None)` when the `OpenAI` backend is selected but
+    /// `OPENAI_API_KEY` is not set.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend name is unknown, the backend cannot be
+    /// initialized, or the local backend is selected in a build without the
+    /// `local-embeddings` feature
+    pub fn from_env() -> Result<Option<Self>> {
+        match EmbeddingBackendKind::from_env()? {
+            EmbeddingBackendKind::OpenAi => std::env::var("OPENAI_API_KEY")
+                .ok()
+                .map(Self::new)
+                .transpose(),
+            #[cfg(feature = "local-embeddings")]
+            EmbeddingBackendKind::Local => {
+                Ok(Some(Self::with_backend(Arc::new(LocalBackend::new()?))))
+            }
+            #[cfg(not(feature = "local-embeddings"))]
+            EmbeddingBackendKind::Local => Err(Error::Config(
+                "BEACON_EMBEDDING_BACKEND=local requires the `local-embeddings` feature"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Backend name for logs
+    #[must_use]
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Length of every vector this embedder produces
+    #[must_use]
+    pub fn dimension(&self) -> usize {
+        self.backend.dimension()
+    }
+
+    /// Embed a single text
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.backend.embed(text).await
+    }
+
+    /// Embed several texts, returning one vector per input in order
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    pub async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        self.backend.embed_batch(texts).await
+    }
+
+    /// Encode an embedding as little-endian `f32` bytes for sqlite-vec
+    #[must_use]
+    pub fn to_bytes(embedding: &[f32]) -> Vec<u8> {
+        embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    /// Decode an embedding stored by [`Embedder::to_bytes`]
+    #[must_use]
+    pub fn from_bytes(bytes: &[u8]) -> Vec<f32> {
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedBackend;
+
+    #[async_trait]
+    impl EmbeddingBackend for FixedBackend {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn dimension(&self) -> usize {
+            2
+        }
+
+        async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            #[allow(clippy::cast_precision_loss)]
+            Ok(texts.iter().map(|t| vec![t.len() as f32, 1.0]).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_backend_embeds_through_embedder() {
+        let embedder = Embedder::with_backend(Arc::new(FixedBackend));
+        assert_eq!(embedder.dimension(), 2);
+        assert_eq!(embedder.backend_name(), "fixed");
+        assert_eq!(embedder.embed("abc").await.unwrap(), vec![3.0, 1.0]);
+        assert_eq!(
+            embedder.embed_batch(&["a", "bb"]).await.unwrap(),
+            vec![vec![1.0, 1.0], vec![2.0, 1.0]]
+        );
+    }
+
+    #[test]
+    fn backend_kind_parses_and_sizes() {
+        assert_eq!(
+            "Local".parse::<EmbeddingBackendKind>().unwrap(),
+            EmbeddingBackendKind::Local
+        );
+        assert_eq!(
+            "openai".parse::<EmbeddingBackendKind>().unwrap(),
+            EmbeddingBackendKind::OpenAi
+        );
+        assert!("open-ai".parse::<EmbeddingBackendKind>().is_err());
+        assert_eq!(EmbeddingBackendKind::OpenAi.dimension(), 1536);
+        assert_eq!(EmbeddingBackendKind::Local.dimension(), 384);
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let embedding = vec![0.5, -1.25, 3.0];
+        let bytes = Embedder::to_bytes(&embedding);
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(Embedder::from_bytes(&bytes), embedding);
+    }
+}
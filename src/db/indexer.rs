@@ -6,7 +6,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::embedder::Embedder;
-use super::memory::{Memory, MemoryCategory, MemoryRepo};
+use super::memory::{DEFAULT_DEDUP_THRESHOLD, Memory, MemoryCategory, MemoryRepo, UpsertOutcome};
 use crate::{Error, Result};
 
 /// Extracted fact from a conversation
@@ -53,8 +53,20 @@ impl Indexer {
         }
     }
 
+    /// Copy of this indexer that stores facts under `persona_id`
+    #[must_use]
+    pub fn for_persona(&self, persona_id: &str) -> Self {
+        Self {
+            memory_repo: self.memory_repo.for_persona(persona_id),
+            ..self.clone()
+        }
+    }
+
     /// Extract facts from a conversation and store as memories
     ///
+    /// Facts that near-duplicate an existing memory are merged into it rather
+    /// than stored again; only newly stored memories are returned.
+    ///
     /// # Arguments
     ///
     /// * `user_id` - User ID to associate memories with
@@ -85,12 +97,14 @@ impl Indexer {
 
         // Create and store memories
         let mut memories = Vec::new();
+        let mut merged = 0_usize;
 
         for (fact, embedding) in extracted.facts.into_iter().zip(embeddings.into_iter()) {
             let category = match fact.category.as_str() {
                 "preference" => MemoryCategory::Preference,
                 "correction" => MemoryCategory::Correction,
                 "fact" => MemoryCategory::Fact,
+                "ephemeral" => MemoryCategory::Ephemeral,
                 _ => MemoryCategory::General,
             };
 
@@ -107,13 +121,20 @@ impl Indexer {
                 memory = memory.with_source(sid.to_string(), ch.to_string());
             }
 
-            self.memory_repo.add(&memory)?;
-            memories.push(memory);
+            match self
+                .memory_repo
+                .upsert_deduplicated(memory.clone(), &self.embedder, DEFAULT_DEDUP_THRESHOLD)
+                .await?
+            {
+                UpsertOutcome::Inserted(_) => memories.push(memory),
+                UpsertOutcome::Merged(_) => merged += 1,
+            }
         }
 
         tracing::info!(
             user_id,
             count = memories.len(),
+            merged,
             "indexed conversation facts"
         );
 
@@ -128,7 +149,7 @@ impl Indexer {
 Output JSON with this structure:
 {
   "facts": [
-    {"content": "...", "category": "preference|fact|correction", "tags": ["tag1"]}
+    {"content": "...", "category": "preference|fact|correction|ephemeral", "tags": ["tag1"]}
   ]
 }
 
@@ -136,6 +157,7 @@ Categories:
 - preference: How the user likes things done (communication style, preferences)
 - fact: Factual information about the user (name, location, job, relationships)
 - correction: When the user corrects a previous assumption or error
+- ephemeral: Time-bound context that will stop being true ("traveling this week")
 
 Only extract meaningful, persistent information. Ignore:
 - Temporary states ("I'm tired")
@@ -1,5 +1,7 @@
 //! Knowledge pack repository for installed knowledge persistence
 
+use std::collections::HashMap;
+
 use uuid::Uuid;
 
 use super::DbPool;
@@ -61,17 +63,37 @@ impl KnowledgePackRepo {
     ///
     /// Serializes the pack to JSON and stores it alongside metadata.
     /// If the pack includes pre-computed embeddings, they are inserted
-    /// into the `knowledge_vec` virtual table.
+    /// into the `knowledge_vec` virtual table. The pack row and its vectors
+    /// are written in one transaction.
     ///
     /// # Errors
     ///
-    /// Returns error if database operation or serialization fails
+    /// Returns error if database operation or serialization fails, or if the
+    /// pack's embeddings do not match the active backend's dimension
     pub fn install(&self, pack: &KnowledgePack, namespace: &str) -> Result<String> {
         let conn = self
             .pool
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
+        // Vectors from another backend are not comparable; refuse them
+        // rather than fail halfway through or store unusable embeddings
+        if let Some(ref embeddings) = pack.embeddings
+            && let Some(dimension) = super::schema::vector_dimension(&conn, "knowledge_vec")?
+            && let Some((_, bad)) = embeddings
+                .vectors
+                .iter()
+                .find(|(_, v)| v.len() != dimension)
+        {
+            return Err(Error::Config(format!(
+                "knowledge pack {} has {}-dimensional embeddings but the active embedding backend uses {dimension}",
+                pack.name,
+                bad.len()
+            )));
+        }
+
+        let tx = conn.unchecked_transaction()?;
+
         let id = format!("kp_{}", Uuid::new_v4());
         let tags_json = serde_json::to_string(&pack.tags)?;
         let content_json = serde_json::to_string(pack)?;
@@ -80,7 +102,7 @@ impl KnowledgePackRepo {
         let chunk_count = pack.chunks.len() as i64;
         let has_embeddings = pack.embeddings.is_some();
 
-        conn.execute(
+        tx.execute(
             &format!(
                 "INSERT INTO installed_knowledge_packs ({PACK_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, datetime('now'), datetime('now'))"
             ),
@@ -103,12 +125,13 @@ impl KnowledgePackRepo {
                 let chunk_id = format!("{id}:{chunk_idx}");
                 let embedding_bytes = super::embedder::Embedder::to_bytes(embedding);
 
-                conn.execute(
+                tx.execute(
                     "INSERT INTO knowledge_vec (chunk_id, embedding) VALUES (?1, ?2)",
                     rusqlite::params![chunk_id, embedding_bytes],
                 )?;
             }
         }
+        tx.commit()?;
 
         tracing::info!(
             pack_id = %id,
@@ -246,6 +269,61 @@ impl KnowledgePackRepo {
         Ok(packs)
     }
 
+    /// Load the stored embeddings of installed pack chunks
+    ///
+    /// Returns vectors keyed by chunk content, since loaded knowledge does not
+    /// carry the install ID its `knowledge_vec` rows are keyed by.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn chunk_embeddings(&self) -> Result<HashMap<String, Vec<f32>>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        // Rows are keyed `{pack_id}:{chunk_index}`
+        let mut by_pack: HashMap<String, Vec<(usize, Vec<f32>)>> = HashMap::new();
+        let mut stmt = conn.prepare("SELECT chunk_id, embedding FROM knowledge_vec")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+        for row in rows {
+            let (chunk_id, bytes) = row?;
+            if let Some((pack_id, index)) = chunk_id.rsplit_once(':')
+                && let Ok(index) = index.parse()
+            {
+                by_pack
+                    .entry(pack_id.to_string())
+                    .or_default()
+                    .push((index, super::embedder::Embedder::from_bytes(&bytes)));
+            }
+        }
+
+        let mut embeddings = HashMap::new();
+        for (pack_id, vectors) in by_pack {
+            let content: Option<String> = conn
+                .query_row(
+                    "SELECT content FROM installed_knowledge_packs WHERE id = ?1",
+                    rusqlite::params![pack_id],
+                    |row| row.get(0),
+                )
+                .ok();
+            let Some(pack) = content.and_then(|c| serde_json::from_str::<KnowledgePack>(&c).ok())
+            else {
+                continue;
+            };
+            for (index, vector) in vectors {
+                if let Some(chunk) = pack.chunks.get(index) {
+                    embeddings.insert(chunk.content.clone(), vector);
+                }
+            }
+        }
+
+        Ok(embeddings)
+    }
+
     /// Search knowledge chunk embeddings by vector similarity
     ///
     /// Returns `(chunk_id, distance)` pairs ordered by distance (closest first).
@@ -367,6 +445,32 @@ mod tests {
         assert_eq!(packs[0].name, "test-knowledge");
     }
 
+    #[test]
+    fn test_chunk_embeddings_keyed_by_content() {
+        let pool = init_memory().unwrap();
+        let repo = KnowledgePackRepo::new(pool.clone());
+
+        let id = repo.install(&test_pack(), "omni").unwrap();
+        let conn = pool.get().unwrap();
+        let dimension = crate::db::schema::vector_dimension(&conn, "knowledge_vec")
+            .unwrap()
+            .unwrap_or(1536);
+        let vector = vec![0.5_f32; dimension];
+        conn.execute(
+            "INSERT INTO knowledge_vec (chunk_id, embedding) VALUES (?1, ?2)",
+            rusqlite::params![
+                format!("{id}:1"),
+                crate::db::embedder::Embedder::to_bytes(&vector)
+            ],
+        )
+        .unwrap();
+        drop(conn);
+
+        let embeddings = repo.chunk_embeddings().unwrap();
+        assert_eq!(embeddings.len(), 1);
+        assert_eq!(embeddings.get("Write tests for your code"), Some(&vector));
+    }
+
     #[test]
     fn test_uninstall() {
         let pool = init_memory().unwrap();
@@ -100,7 +100,7 @@ fn mmr_rerank(candidates: Vec<MmrCandidate>, limit: usize, lambda: f64) -> Vec<M
 }
 
 /// Column list for all memory SELECT queries
-const MEMORY_COLUMNS: &str = "id, user_id, category, content, tags, pinned, access_count, created_at, accessed_at, embedding, source_session_id, source_channel, content_hash, origin_device_id, updated_at, deleted_at, synced_at, cloud_id";
+const MEMORY_COLUMNS: &str = "id, user_id, category, content, tags, pinned, access_count, created_at, accessed_at, embedding, source_session_id, source_channel, content_hash, origin_device_id, updated_at, deleted_at, synced_at, cloud_id, persona_id, reinforcement_count, expires_at";
 
 /// Map a database row to a `MemoryRow`
 fn row_to_memory_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<MemoryRow> {
@@ -123,6 +123,9 @@ fn row_to_memory_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<MemoryRow> {
         deleted_at: row.get(15)?,
         synced_at: row.get(16)?,
         cloud_id: row.get(17)?,
+        persona_id: row.get(18)?,
+        reinforcement_count: row.get(19)?,
+        expires_at: row.get(20)?,
     })
 }
 
@@ -138,15 +141,29 @@ pub enum MemoryCategory {
     Correction,
     /// General learned information
     General,
+    /// Time-bound context that expires (e.g. travel plans this week)
+    Ephemeral,
 }
 
 impl MemoryCategory {
-    const fn as_str(self) -> &'static str {
+    /// All categories
+    pub const ALL: [Self; 5] = [
+        Self::Preference,
+        Self::Fact,
+        Self::Correction,
+        Self::General,
+        Self::Ephemeral,
+    ];
+
+    /// Stored category name
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::Preference => "preference",
             Self::Fact => "fact",
             Self::Correction => "correction",
             Self::General => "general",
+            Self::Ephemeral => "ephemeral",
         }
     }
 
@@ -157,11 +174,75 @@ impl MemoryCategory {
             "fact" => Some(Self::Fact),
             "correction" => Some(Self::Correction),
             "general" => Some(Self::General),
+            "ephemeral" => Some(Self::Ephemeral),
             _ => None,
         }
     }
 }
 
+/// Default lifetime of ephemeral memories in days
+pub const DEFAULT_EPHEMERAL_TTL_DAYS: u32 = 7;
+
+/// Per-category memory lifetime; categories without a TTL never expire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryTtl {
+    /// Days indexed by position in [`MemoryCategory::ALL`]
+    days: [Option<u32>; MemoryCategory::ALL.len()],
+}
+
+impl Default for MemoryTtl {
+    fn default() -> Self {
+        Self::none().with_days(MemoryCategory::Ephemeral, DEFAULT_EPHEMERAL_TTL_DAYS)
+    }
+}
+
+impl MemoryTtl {
+    /// No category expires
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            days: [None; MemoryCategory::ALL.len()],
+        }
+    }
+
+    /// Set the TTL for `category` in days (0 disables expiry)
+    #[must_use]
+    pub const fn with_days(mut self, category: MemoryCategory, days: u32) -> Self {
+        self.days[category as usize] = if days == 0 { None } else { Some(days) };
+        self
+    }
+
+    /// TTL for `category` in days, if it expires
+    #[must_use]
+    pub const fn days(&self, category: MemoryCategory) -> Option<u32> {
+        self.days[category as usize]
+    }
+
+    /// Expiry for a memory of `category` created at `created_at`
+    #[must_use]
+    pub fn expires_at(
+        &self,
+        category: MemoryCategory,
+        created_at: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        self.days(category)
+            .map(|days| created_at + chrono::Duration::days(i64::from(days)))
+    }
+}
+
+/// Format an expiry so stored values compare correctly as text
+fn expiry_timestamp(at: &DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// SQL condition excluding memories whose expiry has passed
+fn not_expired(alias: &str) -> String {
+    format!(
+        "({alias}expires_at IS NULL OR {alias}expires_at > '{}')",
+        expiry_timestamp(&Utc::now())
+    )
+}
+
 impl std::fmt::Display for MemoryCategory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_str())
@@ -198,6 +279,12 @@ pub struct Memory {
     pub synced_at: Option<String>,
     /// API-side UUID for cross-device identity
     pub cloud_id: Option<String>,
+    /// Persona that learned this memory (`None` for memories predating scoping)
+    pub persona_id: Option<String>,
+    /// Times a near-duplicate was merged into this memory
+    pub reinforcement_count: u32,
+    /// When this memory stops being used (`None` never expires)
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl Memory {
@@ -225,6 +312,9 @@ impl Memory {
             deleted_at: None,
             synced_at: None,
             cloud_id: None,
+            persona_id: None,
+            reinforcement_count: 0,
+            expires_at: None,
         }
     }
 
@@ -259,6 +349,13 @@ impl Memory {
         self
     }
 
+    /// Set an explicit expiry, overriding the category's default TTL
+    #[must_use]
+    pub const fn with_expiry(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
     /// Set the source session and channel
     #[must_use]
     pub fn with_source(mut self, session_id: String, channel: String) -> Self {
@@ -268,17 +365,141 @@ impl Memory {
     }
 }
 
+/// Which memories are visible to a conversation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryScope {
+    /// Memories belong to the user and are shared across personas
+    #[default]
+    PerUser,
+    /// Memories belong to a (user, persona) pair; switching personas starts fresh
+    ///
+    /// Memories stored before scoping (no persona recorded) stay visible to all
+    /// of the user's personas.
+    PerUserPersona,
+    /// Every user sees every memory (single-household or team deployments)
+    Shared,
+}
+
+impl MemoryScope {
+    /// Parse from string representation
+    #[must_use]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "per_user_persona" | "persona" => Self::PerUserPersona,
+            "shared" | "global" => Self::Shared,
+            _ => Self::PerUser,
+        }
+    }
+
+    /// String representation
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::PerUser => "per_user",
+            Self::PerUserPersona => "per_user_persona",
+            Self::Shared => "shared",
+        }
+    }
+}
+
+impl std::fmt::Display for MemoryScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Default cosine similarity above which a new memory merges into an existing one
+pub const DEFAULT_DEDUP_THRESHOLD: f32 = 0.92;
+
+/// Result of [`MemoryRepo::upsert_deduplicated`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    /// Stored as a new memory with this ID
+    Inserted(String),
+    /// Merged into the existing memory with this ID
+    Merged(String),
+}
+
 /// Memory repository for database operations
 #[derive(Debug, Clone)]
 pub struct MemoryRepo {
     pool: DbPool,
+    scope: MemoryScope,
+    persona_id: Option<String>,
+    ttl: MemoryTtl,
 }
 
 impl MemoryRepo {
     /// Create a new memory repository
     #[must_use]
     pub const fn new(pool: DbPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            scope: MemoryScope::PerUser,
+            persona_id: None,
+            ttl: MemoryTtl::none(),
+        }
+    }
+
+    /// Expire new memories according to per-category TTLs
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: MemoryTtl) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Scope reads to `scope` and tag new memories with the active persona
+    #[must_use]
+    pub fn with_scope(mut self, scope: MemoryScope, persona_id: impl Into<String>) -> Self {
+        self.scope = scope;
+        self.persona_id = Some(persona_id.into());
+        self
+    }
+
+    /// Copy of this repository bound to `persona_id` for a single turn
+    ///
+    /// Keeps the scope and TTLs; callers derive this from the persona the turn
+    /// runs as so persona switches take effect without rebuilding the repo.
+    #[must_use]
+    pub fn for_persona(&self, persona_id: impl Into<String>) -> Self {
+        Self {
+            persona_id: Some(persona_id.into()),
+            ..self.clone()
+        }
+    }
+
+    /// Configured memory scope
+    #[must_use]
+    pub const fn scope(&self) -> MemoryScope {
+        self.scope
+    }
+
+    /// SQL filter selecting the memories visible to `user_id`
+    ///
+    /// Placeholders are numbered from `first_param`; the returned values must be
+    /// bound at those positions.
+    fn scope_filter(
+        &self,
+        alias: &str,
+        user_id: &str,
+        first_param: usize,
+    ) -> (String, Vec<String>) {
+        match (self.scope, &self.persona_id) {
+            (MemoryScope::Shared, _) => ("1 = 1".to_string(), Vec::new()),
+            (MemoryScope::PerUserPersona, Some(persona_id)) => (
+                format!(
+                    "{alias}user_id = ?{first_param} AND ({alias}persona_id = ?{} OR {alias}persona_id IS NULL)",
+                    first_param + 1
+                ),
+                vec![user_id.to_string(), persona_id.clone()],
+            ),
+            _ => (
+                format!("{alias}user_id = ?{first_param}"),
+                vec![user_id.to_string()],
+            ),
+        }
     }
 
     /// Add a new memory
@@ -299,9 +520,18 @@ impl MemoryRepo {
             .as_ref()
             .map(|e| super::embedder::Embedder::to_bytes(e));
 
+        // Tag with the repository's persona unless the memory already names one
+        let persona_id = memory.persona_id.as_ref().or(self.persona_id.as_ref());
+
+        // Apply the category's default TTL unless the memory sets its own expiry
+        let expires_at = memory
+            .expires_at
+            .or_else(|| self.ttl.expires_at(memory.category, memory.created_at))
+            .map(|at| expiry_timestamp(&at));
+
         conn.execute(
-            r"INSERT INTO memories (id, user_id, category, content, tags, pinned, access_count, created_at, accessed_at, embedding, source_session_id, source_channel, content_hash, origin_device_id, updated_at, deleted_at, synced_at, cloud_id)
-              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+            r"INSERT INTO memories (id, user_id, category, content, tags, pinned, access_count, created_at, accessed_at, embedding, source_session_id, source_channel, content_hash, origin_device_id, updated_at, deleted_at, synced_at, cloud_id, persona_id, reinforcement_count, expires_at)
+              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
             rusqlite::params![
                 memory.id,
                 memory.user_id,
@@ -321,6 +551,9 @@ impl MemoryRepo {
                 memory.deleted_at,
                 memory.synced_at,
                 memory.cloud_id,
+                persona_id,
+                memory.reinforcement_count,
+                expires_at,
             ],
         )?;
 
@@ -336,6 +569,105 @@ impl MemoryRepo {
         Ok(())
     }
 
+    /// Add a memory, merging it into a near-duplicate if one exists
+    ///
+    /// The memory is compared by embedding against the user's memories in the
+    /// same category. When the closest exceeds `threshold` cosine similarity,
+    /// the two are merged: the longer text is kept (the new one on ties, being
+    /// more recent), tags are combined, and `reinforcement_count` is bumped.
+    /// The memory is embedded first if it has no embedding yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if embedding or the database operation fails
+    pub async fn upsert_deduplicated(
+        &self,
+        mut memory: Memory,
+        embedder: &super::embedder::Embedder,
+        threshold: f32,
+    ) -> Result<UpsertOutcome> {
+        let embedding = match memory.embedding.take() {
+            Some(embedding) => embedding,
+            None => embedder.embed(&memory.content).await?,
+        };
+
+        let closest = self
+            .list(&memory.user_id, Some(memory.category))?
+            .into_iter()
+            .filter(|existing| existing.user_id == memory.user_id)
+            .filter_map(|existing| {
+                let similarity = cosine_similarity(&embedding, existing.embedding.as_deref()?);
+                Some((existing, similarity))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((existing, similarity)) = closest
+            && similarity > threshold
+        {
+            tracing::debug!(
+                id = %existing.id,
+                similarity,
+                "merging near-duplicate memory"
+            );
+            self.merge_into(&existing, &memory, &embedding)?;
+            return Ok(UpsertOutcome::Merged(existing.id));
+        }
+
+        memory.embedding = Some(embedding);
+        self.add(&memory)?;
+        Ok(UpsertOutcome::Inserted(memory.id))
+    }
+
+    /// Fold `incoming` into `existing`, keeping the longer text
+    fn merge_into(&self, existing: &Memory, incoming: &Memory, embedding: &[f32]) -> Result<()> {
+        let replace = incoming.content.chars().count() >= existing.content.chars().count();
+
+        let mut tags = existing.tags.clone();
+        for tag in &incoming.tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        let tags_json = serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string());
+        let now = Utc::now().to_rfc3339();
+
+        {
+            let conn = self
+                .pool
+                .get()
+                .map_err(|e| Error::Database(e.to_string()))?;
+
+            if replace {
+                conn.execute(
+                    "UPDATE memories SET content = ?1, content_hash = ?2, tags = ?3,
+                     reinforcement_count = reinforcement_count + 1, accessed_at = ?4, updated_at = ?4
+                     WHERE id = ?5",
+                    rusqlite::params![
+                        incoming.content,
+                        Memory::compute_content_hash(&incoming.content),
+                        tags_json,
+                        now,
+                        existing.id,
+                    ],
+                )?;
+            } else {
+                conn.execute(
+                    "UPDATE memories SET tags = ?1,
+                     reinforcement_count = reinforcement_count + 1, accessed_at = ?2, updated_at = ?2
+                     WHERE id = ?3",
+                    rusqlite::params![tags_json, now, existing.id],
+                )?;
+            }
+        }
+
+        // The stored embedding must describe the text that was kept
+        if replace {
+            self.set_embedding(&existing.id, embedding)?;
+        }
+
+        Ok(())
+    }
+
     /// Get a memory by ID (and update access stats)
     ///
     /// # Errors
@@ -402,22 +734,27 @@ impl MemoryRepo {
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
+        let (scope, scope_params) = self.scope_filter("", user_id, 1);
+        let live = not_expired("");
         let sql = category.map_or_else(
             || {
                 format!(
-                    "SELECT {MEMORY_COLUMNS} FROM memories WHERE user_id = ?1 AND deleted_at IS NULL ORDER BY pinned DESC, accessed_at DESC"
+                    "SELECT {MEMORY_COLUMNS} FROM memories WHERE {scope} AND deleted_at IS NULL AND {live} ORDER BY pinned DESC, accessed_at DESC"
                 )
             },
             |cat| {
                 let cat_str = cat.as_str();
                 format!(
-                    "SELECT {MEMORY_COLUMNS} FROM memories WHERE user_id = ?1 AND category = '{cat_str}' AND deleted_at IS NULL ORDER BY pinned DESC, accessed_at DESC"
+                    "SELECT {MEMORY_COLUMNS} FROM memories WHERE {scope} AND category = '{cat_str}' AND deleted_at IS NULL AND {live} ORDER BY pinned DESC, accessed_at DESC"
                 )
             },
         );
 
         let mut stmt = conn.prepare(&sql)?;
-        let rows = stmt.query_map([user_id], row_to_memory_row)?;
+        let rows = stmt.query_map(
+            rusqlite::params_from_iter(scope_params.iter()),
+            row_to_memory_row,
+        )?;
 
         let memories: Vec<Memory> = rows.flatten().map(MemoryRow::into_memory).collect();
         Ok(memories)
@@ -435,12 +772,17 @@ impl MemoryRepo {
             .map_err(|e| Error::Database(e.to_string()))?;
         let pattern = format!("%{query}%");
 
+        let (scope, scope_params) = self.scope_filter("", user_id, 2);
+        let live = not_expired("");
         let sql = format!(
-            "SELECT {MEMORY_COLUMNS} FROM memories WHERE user_id = ?1 AND (content LIKE ?2 OR tags LIKE ?2) AND deleted_at IS NULL ORDER BY pinned DESC, accessed_at DESC"
+            "SELECT {MEMORY_COLUMNS} FROM memories WHERE (content LIKE ?1 OR tags LIKE ?1) AND {scope} AND deleted_at IS NULL AND {live} ORDER BY pinned DESC, accessed_at DESC"
         );
         let mut stmt = conn.prepare(&sql)?;
 
-        let rows = stmt.query_map([user_id, &pattern], row_to_memory_row)?;
+        let rows = stmt.query_map(
+            rusqlite::params_from_iter(std::iter::once(&pattern).chain(&scope_params)),
+            row_to_memory_row,
+        )?;
 
         let memories: Vec<Memory> = rows.flatten().map(MemoryRow::into_memory).collect();
         Ok(memories)
@@ -457,14 +799,18 @@ impl MemoryRepo {
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
+        let (scope, scope_params) = self.scope_filter("", user_id, 2);
+        let live = not_expired("");
         let sql = format!(
-            "SELECT {MEMORY_COLUMNS} FROM memories WHERE user_id = ?1 AND deleted_at IS NULL ORDER BY pinned DESC, access_count DESC, accessed_at DESC LIMIT ?2"
+            "SELECT {MEMORY_COLUMNS} FROM memories WHERE {scope} AND deleted_at IS NULL AND {live} ORDER BY pinned DESC, access_count DESC, accessed_at DESC LIMIT ?1"
         );
         let mut stmt = conn.prepare(&sql)?;
 
         #[allow(clippy::cast_possible_wrap)]
         let limit = max_items as i64;
-        let rows = stmt.query_map(rusqlite::params![user_id, limit], row_to_memory_row)?;
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&limit];
+        params.extend(scope_params.iter().map(|p| p as &dyn rusqlite::ToSql));
+        let rows = stmt.query_map(params.as_slice(), row_to_memory_row)?;
 
         let memories: Vec<Memory> = rows.flatten().map(MemoryRow::into_memory).collect();
         Ok(memories)
@@ -494,12 +840,14 @@ impl MemoryRepo {
         let fetch_limit = limit * 3;
 
         // Use sqlite-vec to find similar memories
-        // Join with memories table to filter by user_id
+        // Join with memories table to filter by user (and persona, per scope)
+        let (scope, scope_params) = self.scope_filter("m.", user_id, 3);
         let prefixed_columns = MEMORY_COLUMNS
             .split(", ")
             .map(|c| format!("m.{c}"))
             .collect::<Vec<_>>()
             .join(", ");
+        let live = not_expired("m.");
 
         let sql = format!(
             r"SELECT {prefixed_columns}, v.distance
@@ -511,19 +859,19 @@ impl MemoryRepo {
                   ORDER BY distance
                   LIMIT ?2
               ) v ON m.id = v.memory_id
-              WHERE m.user_id = ?3 AND m.deleted_at IS NULL"
+              WHERE {scope} AND m.deleted_at IS NULL AND {live}"
         );
         let mut stmt = conn.prepare(&sql)?;
 
         #[allow(clippy::cast_possible_wrap)]
-        let rows = stmt.query_map(
-            rusqlite::params![embedding_bytes, fetch_limit as i64, user_id],
-            |row| {
-                let memory_row = row_to_memory_row(row)?;
-                let distance: f64 = row.get(18)?; // distance is the 19th column (0-indexed)
-                Ok((memory_row, distance))
-            },
-        )?;
+        let fetch_limit = fetch_limit as i64;
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&embedding_bytes, &fetch_limit];
+        params.extend(scope_params.iter().map(|p| p as &dyn rusqlite::ToSql));
+        let rows = stmt.query_map(params.as_slice(), |row| {
+            let memory_row = row_to_memory_row(row)?;
+            let distance: f64 = row.get(21)?; // distance follows the 21 memory columns
+            Ok((memory_row, distance))
+        })?;
 
         let now = Utc::now();
         let candidates: Vec<MmrCandidate> = rows
@@ -649,6 +997,42 @@ impl MemoryRepo {
         Ok(deleted > 0)
     }
 
+    /// Tombstone memories whose expiry has passed
+    ///
+    /// Expired memories are soft-deleted like [`Self::delete`] so the removal
+    /// syncs; [`Self::purge_tombstones`] later removes the rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn purge_expired(&self) -> Result<usize> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+        let now = expiry_timestamp(&Utc::now());
+
+        conn.execute(
+            "DELETE FROM memories_vec WHERE memory_id IN (
+                 SELECT id FROM memories
+                 WHERE expires_at IS NOT NULL AND expires_at <= ?1 AND deleted_at IS NULL
+             )",
+            [&now],
+        )?;
+
+        let expired = conn.execute(
+            "UPDATE memories SET deleted_at = datetime('now'), updated_at = datetime('now')
+             WHERE expires_at IS NOT NULL AND expires_at <= ?1 AND deleted_at IS NULL",
+            [&now],
+        )?;
+
+        if expired > 0 {
+            tracing::info!(count = expired, "expired memories");
+        }
+
+        Ok(expired)
+    }
+
     /// Hard-delete memories with tombstones older than the given cutoff
     ///
     /// # Errors
@@ -794,6 +1178,28 @@ impl MemoryRepo {
         Ok(())
     }
 
+    /// Queue every memory for upload again
+    ///
+    /// Used when the upload format changes, e.g. once sync encryption is
+    /// enabled and existing plaintext copies must be replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn mark_all_unsynced(&self) -> Result<usize> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let updated = conn.execute(
+            "UPDATE memories SET synced_at = NULL WHERE synced_at IS NOT NULL",
+            [],
+        )?;
+
+        Ok(updated)
+    }
+
     /// Set the `cloud_id` for a memory after successful push
     ///
     /// # Errors
@@ -905,11 +1311,13 @@ impl MemoryRepo {
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM memories WHERE user_id = ?1 AND content_hash = ?2 AND deleted_at IS NULL",
-            rusqlite::params![user_id, content_hash],
-            |row| row.get(0),
-        )?;
+        let (scope, scope_params) = self.scope_filter("", user_id, 2);
+        let sql = format!(
+            "SELECT COUNT(*) FROM memories WHERE content_hash = ?1 AND {scope} AND deleted_at IS NULL"
+        );
+        let params = std::iter::once(content_hash).chain(scope_params.iter().map(String::as_str));
+        let count: i64 =
+            conn.query_row(&sql, rusqlite::params_from_iter(params), |row| row.get(0))?;
 
         Ok(count > 0)
     }
@@ -925,8 +1333,9 @@ impl MemoryRepo {
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
+        let live = not_expired("");
         let sql = format!(
-            "SELECT {MEMORY_COLUMNS} FROM memories WHERE user_id = ?1 AND deleted_at IS NULL ORDER BY pinned DESC, access_count DESC, accessed_at DESC LIMIT ?2"
+            "SELECT {MEMORY_COLUMNS} FROM memories WHERE user_id = ?1 AND deleted_at IS NULL AND {live} ORDER BY pinned DESC, access_count DESC, accessed_at DESC LIMIT ?2"
         );
         let mut stmt = conn.prepare(&sql)?;
 
@@ -977,6 +1386,9 @@ struct MemoryRow {
     deleted_at: Option<String>,
     synced_at: Option<String>,
     cloud_id: Option<String>,
+    persona_id: Option<String>,
+    reinforcement_count: i64,
+    expires_at: Option<String>,
 }
 
 impl MemoryRow {
@@ -1006,6 +1418,12 @@ impl MemoryRow {
             deleted_at: self.deleted_at,
             synced_at: self.synced_at,
             cloud_id: self.cloud_id,
+            persona_id: self.persona_id,
+            reinforcement_count: u32::try_from(self.reinforcement_count).unwrap_or(0),
+            expires_at: self
+                .expires_at
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
         }
     }
 }
@@ -1050,6 +1468,221 @@ mod tests {
         assert!(repo.get(&memory.id).unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn test_upsert_deduplicated_merges_near_duplicates() {
+        let pool = db::init_memory().unwrap();
+        let repo = MemoryRepo::new(pool.clone());
+        let user = crate::db::UserRepo::new(pool)
+            .find_or_create("dedup_user")
+            .unwrap();
+        // Embeddings are supplied, so the embedder is never called
+        let embedder = crate::db::Embedder::new("fake-key".to_string()).unwrap();
+
+        let embedding = |weights: &[(usize, f32)]| {
+            let mut v = vec![0.0; embedder.dimension()];
+            for &(i, w) in weights {
+                v[i] = w;
+            }
+            v
+        };
+        let fact = |content: &str, category, weights: &[(usize, f32)]| {
+            Memory::new(user.id.clone(), category, content.to_string())
+                .with_embedding(embedding(weights))
+        };
+
+        let UpsertOutcome::Inserted(id) = repo
+            .upsert_deduplicated(
+                fact("likes coffee", MemoryCategory::Preference, &[(0, 1.0)]),
+                &embedder,
+                DEFAULT_DEDUP_THRESHOLD,
+            )
+            .await
+            .unwrap()
+        else {
+            panic!("first memory should be inserted");
+        };
+
+        // Longer near-duplicate replaces the text
+        let outcome = repo
+            .upsert_deduplicated(
+                fact(
+                    "user likes coffee",
+                    MemoryCategory::Preference,
+                    &[(0, 1.0), (1, 0.1)],
+                ),
+                &embedder,
+                DEFAULT_DEDUP_THRESHOLD,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, UpsertOutcome::Merged(id.clone()));
+
+        // Shorter near-duplicate only reinforces
+        let outcome = repo
+            .upsert_deduplicated(
+                fact("coffee", MemoryCategory::Preference, &[(0, 1.0), (2, 0.1)]),
+                &embedder,
+                DEFAULT_DEDUP_THRESHOLD,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, UpsertOutcome::Merged(id.clone()));
+
+        let merged = repo.get_without_access_update(&id).unwrap().unwrap();
+        assert_eq!(merged.content, "user likes coffee");
+        assert_eq!(merged.reinforcement_count, 2);
+
+        // Other categories and unrelated facts are kept separate
+        let outcome = repo
+            .upsert_deduplicated(
+                fact("likes coffee", MemoryCategory::Fact, &[(0, 1.0)]),
+                &embedder,
+                DEFAULT_DEDUP_THRESHOLD,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(outcome, UpsertOutcome::Inserted(_)));
+        let outcome = repo
+            .upsert_deduplicated(
+                fact("owns a cat", MemoryCategory::Preference, &[(3, 1.0)]),
+                &embedder,
+                DEFAULT_DEDUP_THRESHOLD,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(outcome, UpsertOutcome::Inserted(_)));
+        assert_eq!(repo.list(&user.id, None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_expired_memories_excluded_and_purged() {
+        let pool = db::init_memory().unwrap();
+        let repo = MemoryRepo::new(pool.clone()).with_ttl(MemoryTtl::default());
+        let user = crate::db::UserRepo::new(pool)
+            .find_or_create("ttl_user")
+            .unwrap();
+
+        let lasting = Memory::new(
+            user.id.clone(),
+            MemoryCategory::Fact,
+            "Works at Acme".to_string(),
+        );
+        let ephemeral = Memory::new(
+            user.id.clone(),
+            MemoryCategory::Ephemeral,
+            "Traveling to Lisbon this week".to_string(),
+        );
+        let expired = Memory::new(
+            user.id.clone(),
+            MemoryCategory::Ephemeral,
+            "Has a cold".to_string(),
+        )
+        .with_expiry(Utc::now() - chrono::Duration::hours(1));
+        for memory in [&lasting, &ephemeral, &expired] {
+            repo.add(memory).unwrap();
+        }
+
+        // Default TTL applies to ephemeral memories only
+        let stored = repo
+            .get_without_access_update(&ephemeral.id)
+            .unwrap()
+            .unwrap();
+        let ttl = stored.expires_at.unwrap() - stored.created_at;
+        let expected = chrono::Duration::days(i64::from(DEFAULT_EPHEMERAL_TTL_DAYS));
+        // Stored expiries are truncated to whole seconds
+        assert!((ttl - expected).num_seconds().abs() <= 1);
+        assert!(
+            repo.get_without_access_update(&lasting.id)
+                .unwrap()
+                .unwrap()
+                .expires_at
+                .is_none()
+        );
+
+        let context = repo.get_context(&user.id, 10).unwrap();
+        assert_eq!(context.len(), 2);
+        assert!(context.iter().all(|m| m.id != expired.id));
+
+        assert_eq!(repo.purge_expired().unwrap(), 1);
+        assert_eq!(repo.purge_expired().unwrap(), 0);
+        assert!(repo.get(&expired.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_memory_ttl_per_category() {
+        let ttl = MemoryTtl::default()
+            .with_days(MemoryCategory::Fact, 30)
+            .with_days(MemoryCategory::Ephemeral, 0);
+        assert_eq!(ttl.days(MemoryCategory::Fact), Some(30));
+        assert_eq!(ttl.days(MemoryCategory::Ephemeral), None);
+        assert_eq!(ttl.days(MemoryCategory::Preference), None);
+    }
+
+    #[test]
+    fn test_per_user_persona_scope_hides_other_persona() {
+        let pool = db::init_memory().unwrap();
+        let user = crate::db::UserRepo::new(pool.clone())
+            .find_or_create("scoped_user")
+            .unwrap();
+
+        let orin = MemoryRepo::new(pool.clone()).with_scope(MemoryScope::PerUserPersona, "orin");
+        let microcap =
+            MemoryRepo::new(pool.clone()).with_scope(MemoryScope::PerUserPersona, "microcap");
+
+        orin.add(&Memory::new(
+            user.id.clone(),
+            MemoryCategory::Fact,
+            "Working on a Rust compiler".to_string(),
+        ))
+        .unwrap();
+        microcap
+            .add(&Memory::new(
+                user.id.clone(),
+                MemoryCategory::Fact,
+                "Holds shares in a small-cap miner".to_string(),
+            ))
+            .unwrap();
+
+        let orin_memories = orin.get_context(&user.id, 10).unwrap();
+        assert_eq!(orin_memories.len(), 1);
+        assert_eq!(orin_memories[0].persona_id.as_deref(), Some("orin"));
+        assert!(orin.search(&user.id, "small-cap").unwrap().is_empty());
+        assert!(
+            !orin
+                .exists_by_content_hash(
+                    &user.id,
+                    &Memory::compute_content_hash("Holds shares in a small-cap miner")
+                )
+                .unwrap()
+        );
+
+        // Per-user scope (the default) sees both
+        let per_user = MemoryRepo::new(pool).with_scope(MemoryScope::PerUser, "orin");
+        assert_eq!(per_user.list(&user.id, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_shared_scope_spans_users() {
+        let pool = db::init_memory().unwrap();
+        let user_repo = crate::db::UserRepo::new(pool.clone());
+        let alice = user_repo.find_or_create("alice").unwrap();
+        let bob = user_repo.find_or_create("bob").unwrap();
+
+        let repo = MemoryRepo::new(pool).with_scope(MemoryScope::Shared, "orin");
+        repo.add(&Memory::new(
+            alice.id.clone(),
+            MemoryCategory::Fact,
+            "The office wifi is on channel 11".to_string(),
+        ))
+        .unwrap();
+
+        assert_eq!(repo.get_context(&bob.id, 10).unwrap().len(), 1);
+        assert_eq!(
+            MemoryScope::from_str("per_user_persona"),
+            MemoryScope::PerUserPersona
+        );
+    }
+
     #[test]
     fn test_memory_context() {
         let pool = db::init_memory().unwrap();
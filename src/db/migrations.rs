@@ -0,0 +1,942 @@
+//! Versioned schema migrations
+//!
+//! Each step moves the schema forward one version. Pending steps run in
+//! order, each in its own transaction together with the `user_version` bump,
+//! so an interrupted upgrade resumes from the last completed step.
+
+use rusqlite::Connection;
+
+use crate::Result;
+
+/// A single forward schema migration
+pub struct Migration {
+    /// Schema version after this step is applied
+    pub version: i32,
+    /// Short description for logs
+    pub description: &'static str,
+    /// SQL executed for this step
+    pub sql: &'static str,
+    /// Rust step run after `sql` in the same transaction (data backfills,
+    /// DDL that depends on runtime checks)
+    pub backfill: Option<fn(&Connection) -> Result<()>>,
+}
+
+/// All migrations, in ascending version order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "core tables",
+        sql: r"
+            -- Users table
+            CREATE TABLE IF NOT EXISTS users (
+                id TEXT PRIMARY KEY,
+                life_json_path TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- Sessions table
+            CREATE TABLE IF NOT EXISTS sessions (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL REFERENCES users(id),
+                channel TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                persona_id TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_sessions_user ON sessions(user_id);
+            CREATE INDEX IF NOT EXISTS idx_sessions_channel ON sessions(channel, channel_id);
+
+            -- Messages table
+            CREATE TABLE IF NOT EXISTS messages (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL REFERENCES sessions(id),
+                role TEXT NOT NULL CHECK(role IN ('user', 'assistant', 'system')),
+                content TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_messages_session ON messages(session_id);
+
+            -- User context (learned preferences)
+            CREATE TABLE IF NOT EXISTS user_context (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL REFERENCES users(id),
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                source TEXT NOT NULL DEFAULT 'learned',
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                UNIQUE(user_id, key)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_user_context_user ON user_context(user_id);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 2,
+        description: "long-term memories",
+        sql: r"
+            -- Memories table for long-term memory storage
+            CREATE TABLE IF NOT EXISTS memories (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL REFERENCES users(id),
+                category TEXT NOT NULL CHECK(category IN ('preference', 'fact', 'correction', 'general')),
+                content TEXT NOT NULL,
+                tags TEXT NOT NULL DEFAULT '[]',
+                pinned INTEGER NOT NULL DEFAULT 0,
+                access_count INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                accessed_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_memories_user ON memories(user_id);
+            CREATE INDEX IF NOT EXISTS idx_memories_category ON memories(category);
+            CREATE INDEX IF NOT EXISTS idx_memories_pinned ON memories(pinned);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 3,
+        description: "installed skills",
+        sql: r"
+            -- Installed skills table
+            CREATE TABLE IF NOT EXISTS installed_skills (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT NOT NULL,
+                version TEXT,
+                author TEXT,
+                tags TEXT NOT NULL DEFAULT '[]',
+                permissions TEXT NOT NULL DEFAULT '[]',
+                content TEXT NOT NULL,
+                source_type TEXT NOT NULL CHECK(source_type IN ('local', 'manifold', 'bundled', 'plugin')),
+                source_namespace TEXT,
+                source_repository TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                installed_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_skills_name ON installed_skills(name);
+            CREATE INDEX IF NOT EXISTS idx_skills_enabled ON installed_skills(enabled);
+            CREATE INDEX IF NOT EXISTS idx_skills_source ON installed_skills(source_type);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 4,
+        description: "installed personas",
+        sql: r"
+            -- Installed personas table (from marketplace)
+            CREATE TABLE IF NOT EXISTS installed_personas (
+                id TEXT PRIMARY KEY,
+                persona_id TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                tagline TEXT,
+                avatar TEXT,
+                accent_color TEXT,
+                content TEXT NOT NULL,
+                source_namespace TEXT NOT NULL,
+                installed_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_personas_persona_id ON installed_personas(persona_id);
+            CREATE INDEX IF NOT EXISTS idx_personas_namespace ON installed_personas(source_namespace);
+        ",
+        backfill: None,
+    },
+    // Note: sqlite-vec extension is registered globally in db::init()
+    // before any connections are created
+    Migration {
+        version: 5,
+        description: "vector search",
+        sql: r"
+            -- Add embedding column to memories
+            ALTER TABLE memories ADD COLUMN embedding BLOB;
+
+            -- Add source metadata columns
+            ALTER TABLE memories ADD COLUMN source_session_id TEXT;
+            ALTER TABLE memories ADD COLUMN source_channel TEXT;
+
+            -- Create virtual table for vector search
+            CREATE VIRTUAL TABLE IF NOT EXISTS memories_vec USING vec0(
+                memory_id TEXT PRIMARY KEY,
+                embedding FLOAT[1536]
+            );
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 6,
+        description: "DM pairing",
+        sql: r"
+            -- Paired users table for DM security
+            CREATE TABLE IF NOT EXISTS paired_users (
+                id TEXT PRIMARY KEY,
+                sender_id TEXT NOT NULL,
+                channel TEXT NOT NULL,
+                paired_at TEXT NOT NULL,
+                pairing_code TEXT,
+                code_expires_at TEXT,
+                UNIQUE(sender_id, channel)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_paired_users_sender ON paired_users(sender_id, channel);
+            CREATE INDEX IF NOT EXISTS idx_paired_users_channel ON paired_users(channel);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 7,
+        description: "device identity",
+        sql: r"
+            -- Paired devices table for device identity system
+            CREATE TABLE IF NOT EXISTS devices (
+                id TEXT PRIMARY KEY,
+                public_key BLOB NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                platform TEXT,
+                trust_level TEXT NOT NULL DEFAULT 'paired',
+                paired_at TEXT NOT NULL,
+                last_seen TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_devices_public_key ON devices(public_key);
+            CREATE INDEX IF NOT EXISTS idx_devices_last_seen ON devices(last_seen);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 8,
+        description: "message threading",
+        sql: r"
+            -- Add thread_id to messages for conversation threading
+            ALTER TABLE messages ADD COLUMN thread_id TEXT;
+
+            -- Index for efficient thread queries
+            CREATE INDEX IF NOT EXISTS idx_messages_thread ON messages(session_id, thread_id);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 9,
+        description: "memory sync",
+        sql: r"
+            -- Add sync metadata columns to memories
+            ALTER TABLE memories ADD COLUMN content_hash TEXT;
+            ALTER TABLE memories ADD COLUMN origin_device_id TEXT;
+            -- SQLite rejects non-constant defaults when adding a column to a non-empty
+            -- table, so start from a constant and copy the creation time
+            ALTER TABLE memories ADD COLUMN updated_at TEXT NOT NULL DEFAULT '';
+            UPDATE memories SET updated_at = created_at;
+            ALTER TABLE memories ADD COLUMN deleted_at TEXT;
+            ALTER TABLE memories ADD COLUMN synced_at TEXT;
+            ALTER TABLE memories ADD COLUMN cloud_id TEXT;
+
+            CREATE INDEX IF NOT EXISTS idx_memories_synced ON memories(synced_at);
+            CREATE INDEX IF NOT EXISTS idx_memories_updated ON memories(updated_at);
+        ",
+        backfill: Some(backfill_content_hashes),
+    },
+    Migration {
+        version: 10,
+        description: "knowledge packs",
+        sql: r"
+            -- Installed knowledge packs table
+            CREATE TABLE IF NOT EXISTS installed_knowledge_packs (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                version TEXT NOT NULL,
+                source_namespace TEXT NOT NULL,
+                description TEXT,
+                tags TEXT NOT NULL DEFAULT '[]',
+                chunk_count INTEGER NOT NULL DEFAULT 0,
+                has_embeddings INTEGER NOT NULL DEFAULT 0,
+                content TEXT NOT NULL,
+                installed_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                UNIQUE(name, source_namespace)
+            );
+
+            -- Vector table for knowledge chunk embeddings
+            CREATE VIRTUAL TABLE IF NOT EXISTS knowledge_vec USING vec0(
+                chunk_id TEXT PRIMARY KEY,
+                embedding FLOAT[1536]
+            );
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 11,
+        description: "local provider keys",
+        sql: r"
+            -- Gateway-local provider keys for self-hosted deployments
+            CREATE TABLE IF NOT EXISTS local_provider_keys (
+                provider TEXT PRIMARY KEY,
+                api_key TEXT NOT NULL,
+                model_preference TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 12,
+        description: "skill priority",
+        sql: r"
+            -- Add priority column to installed_skills for prompt hierarchy
+            ALTER TABLE installed_skills ADD COLUMN priority TEXT NOT NULL DEFAULT 'standard';
+
+            CREATE INDEX IF NOT EXISTS idx_skills_priority ON installed_skills(priority);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 13,
+        description: "extended skill metadata + per-user scoping",
+        sql: r"
+            -- Extended skill metadata and per-user scoping
+            ALTER TABLE installed_skills ADD COLUMN always_include INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE installed_skills ADD COLUMN user_invocable INTEGER NOT NULL DEFAULT 1;
+            ALTER TABLE installed_skills ADD COLUMN disable_model_invocation INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE installed_skills ADD COLUMN emoji TEXT;
+            ALTER TABLE installed_skills ADD COLUMN requires_env TEXT NOT NULL DEFAULT '[]';
+            ALTER TABLE installed_skills ADD COLUMN command_name TEXT;
+            ALTER TABLE installed_skills ADD COLUMN user_id TEXT;
+
+            CREATE INDEX IF NOT EXISTS idx_skills_command ON installed_skills(command_name);
+            CREATE INDEX IF NOT EXISTS idx_skills_user ON installed_skills(user_id);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 14,
+        description: "OS, bins, dispatch, per-skill env",
+        sql: r"
+            -- OS, binary, dispatch, and env fields for skills
+            ALTER TABLE installed_skills ADD COLUMN os TEXT NOT NULL DEFAULT '[]';
+            ALTER TABLE installed_skills ADD COLUMN requires_bins TEXT NOT NULL DEFAULT '[]';
+            ALTER TABLE installed_skills ADD COLUMN requires_any_bins TEXT NOT NULL DEFAULT '[]';
+            ALTER TABLE installed_skills ADD COLUMN primary_env TEXT;
+            ALTER TABLE installed_skills ADD COLUMN command_dispatch_tool TEXT;
+            ALTER TABLE installed_skills ADD COLUMN api_key TEXT;
+            ALTER TABLE installed_skills ADD COLUMN skill_env TEXT NOT NULL DEFAULT '{}';
+        ",
+        backfill: None,
+    },
+    // Recreate table to update CHECK constraint (add 'plugin' source_type)
+    // and add install_specs column
+    Migration {
+        version: 15,
+        description: "skill install specs, plugin source type",
+        sql: r"
+            -- Recreate installed_skills with updated source_type CHECK and new column
+            CREATE TABLE IF NOT EXISTS installed_skills_new (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT NOT NULL,
+                version TEXT,
+                author TEXT,
+                tags TEXT NOT NULL DEFAULT '[]',
+                permissions TEXT NOT NULL DEFAULT '[]',
+                content TEXT NOT NULL,
+                source_type TEXT NOT NULL CHECK(source_type IN ('local', 'manifold', 'bundled', 'plugin')),
+                source_namespace TEXT,
+                source_repository TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                installed_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                priority TEXT NOT NULL DEFAULT 'standard',
+                always_include INTEGER NOT NULL DEFAULT 0,
+                user_invocable INTEGER NOT NULL DEFAULT 1,
+                disable_model_invocation INTEGER NOT NULL DEFAULT 0,
+                emoji TEXT,
+                requires_env TEXT NOT NULL DEFAULT '[]',
+                command_name TEXT,
+                user_id TEXT,
+                os TEXT NOT NULL DEFAULT '[]',
+                requires_bins TEXT NOT NULL DEFAULT '[]',
+                requires_any_bins TEXT NOT NULL DEFAULT '[]',
+                primary_env TEXT,
+                command_dispatch_tool TEXT,
+                api_key TEXT,
+                skill_env TEXT NOT NULL DEFAULT '{}',
+                install_specs TEXT NOT NULL DEFAULT '[]'
+            );
+
+            INSERT OR IGNORE INTO installed_skills_new
+                SELECT id, name, description, version, author, tags, permissions,
+                       content, source_type, source_namespace, source_repository,
+                       enabled, installed_at, COALESCE(updated_at, datetime('now')),
+                       COALESCE(priority, 'standard'),
+                       COALESCE(always_include, 0), COALESCE(user_invocable, 1),
+                       COALESCE(disable_model_invocation, 0), emoji,
+                       COALESCE(requires_env, '[]'), command_name, user_id,
+                       COALESCE(os, '[]'), COALESCE(requires_bins, '[]'),
+                       COALESCE(requires_any_bins, '[]'), primary_env,
+                       command_dispatch_tool, api_key,
+                       COALESCE(skill_env, '{}'), '[]'
+                FROM installed_skills;
+
+            DROP TABLE installed_skills;
+            ALTER TABLE installed_skills_new RENAME TO installed_skills;
+
+            -- Re-create indices
+            CREATE INDEX IF NOT EXISTS idx_skills_name ON installed_skills(name);
+            CREATE INDEX IF NOT EXISTS idx_skills_enabled ON installed_skills(enabled);
+            CREATE INDEX IF NOT EXISTS idx_skills_source ON installed_skills(source_type);
+            CREATE INDEX IF NOT EXISTS idx_skills_priority ON installed_skills(priority);
+            CREATE INDEX IF NOT EXISTS idx_skills_command ON installed_skills(command_name);
+            CREATE INDEX IF NOT EXISTS idx_skills_user ON installed_skills(user_id);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 16,
+        description: "config-based eligibility",
+        sql: r"
+            -- Config-based eligibility paths
+            ALTER TABLE installed_skills ADD COLUMN requires_config TEXT NOT NULL DEFAULT '[]';
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 17,
+        description: "per-group Telegram config",
+        sql: r"
+            -- Per-group Telegram configuration overrides
+            CREATE TABLE IF NOT EXISTS telegram_group_config (
+                chat_id TEXT PRIMARY KEY,
+                chat_title TEXT,
+                require_mention INTEGER,
+                reaction_level TEXT,
+                ack_reaction TEXT,
+                done_reaction TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 18,
+        description: "skill location",
+        sql: r"
+            -- Skill file location for compact prompt mode
+            ALTER TABLE installed_skills ADD COLUMN location TEXT;
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 19,
+        description: "outbox",
+        sql: r"
+            -- Proactive messages awaiting delivery (e.g. reminders for offline channels)
+            CREATE TABLE IF NOT EXISTS outbox (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                channel TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                thread_id TEXT,
+                content TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                delivered_at TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_outbox_pending ON outbox(user_id, delivered_at);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 20,
+        description: "memory persona scope",
+        sql: r"
+            -- Persona that learned each memory (for per-persona memory scoping)
+            ALTER TABLE memories ADD COLUMN persona_id TEXT;
+
+            CREATE INDEX IF NOT EXISTS idx_memories_persona ON memories(user_id, persona_id);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 21,
+        description: "usage ledger",
+        sql: r"
+            -- Tokens used per user per UTC day (local daily caps)
+            CREATE TABLE IF NOT EXISTS usage_daily (
+                user_id TEXT NOT NULL,
+                day TEXT NOT NULL,
+                tokens INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (user_id, day)
+            );
+
+            -- Per-user daily cap overrides (NULL = unlimited)
+            CREATE TABLE IF NOT EXISTS usage_limits (
+                user_id TEXT PRIMARY KEY,
+                daily_tokens INTEGER,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 22,
+        description: "message full-text search",
+        sql: "",
+        backfill: Some(create_message_search),
+    },
+    Migration {
+        version: 23,
+        description: "memory reinforcement",
+        sql: r"
+            -- Times a memory was re-learned and merged instead of duplicated
+            ALTER TABLE memories ADD COLUMN reinforcement_count INTEGER NOT NULL DEFAULT 0;
+        ",
+        backfill: None,
+    },
+    // Recreate table to update CHECK constraint (add 'ephemeral' category)
+    // and add expires_at column
+    Migration {
+        version: 24,
+        description: "memory expiry",
+        sql: r"
+            CREATE TABLE memories_new (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL REFERENCES users(id),
+                category TEXT NOT NULL CHECK(category IN ('preference', 'fact', 'correction', 'general', 'ephemeral')),
+                content TEXT NOT NULL,
+                tags TEXT NOT NULL DEFAULT '[]',
+                pinned INTEGER NOT NULL DEFAULT 0,
+                access_count INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                accessed_at TEXT NOT NULL DEFAULT (datetime('now')),
+                embedding BLOB,
+                source_session_id TEXT,
+                source_channel TEXT,
+                content_hash TEXT,
+                origin_device_id TEXT,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                deleted_at TEXT,
+                synced_at TEXT,
+                cloud_id TEXT,
+                persona_id TEXT,
+                reinforcement_count INTEGER NOT NULL DEFAULT 0,
+                expires_at TEXT
+            );
+
+            INSERT INTO memories_new (id, user_id, category, content, tags, pinned, access_count, created_at, accessed_at, embedding, source_session_id, source_channel, content_hash, origin_device_id, updated_at, deleted_at, synced_at, cloud_id, persona_id, reinforcement_count)
+            SELECT id, user_id, category, content, tags, pinned, access_count, created_at, accessed_at, embedding, source_session_id, source_channel, content_hash, origin_device_id, updated_at, deleted_at, synced_at, cloud_id, persona_id, reinforcement_count
+            FROM memories;
+
+            DROP TABLE memories;
+            ALTER TABLE memories_new RENAME TO memories;
+
+            CREATE INDEX IF NOT EXISTS idx_memories_user ON memories(user_id);
+            CREATE INDEX IF NOT EXISTS idx_memories_category ON memories(category);
+            CREATE INDEX IF NOT EXISTS idx_memories_pinned ON memories(pinned);
+            CREATE INDEX IF NOT EXISTS idx_memories_synced ON memories(synced_at);
+            CREATE INDEX IF NOT EXISTS idx_memories_updated ON memories(updated_at);
+            CREATE INDEX IF NOT EXISTS idx_memories_persona ON memories(user_id, persona_id);
+            CREATE INDEX IF NOT EXISTS idx_memories_expires ON memories(expires_at);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 25,
+        description: "thread-scoped sessions",
+        sql: r"
+            -- Thread or forum topic a session is scoped to (NULL = whole conversation)
+            ALTER TABLE sessions ADD COLUMN thread_id TEXT;
+
+            CREATE INDEX IF NOT EXISTS idx_sessions_thread ON sessions(channel, channel_id, thread_id);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 26,
+        description: "scoped skill enablement",
+        sql: r"
+            -- Per-user and per-channel overrides of installed_skills.enabled
+            -- ('' = any user / any channel)
+            CREATE TABLE IF NOT EXISTS skill_scopes (
+                skill_id TEXT NOT NULL,
+                user_id TEXT NOT NULL DEFAULT '',
+                channel TEXT NOT NULL DEFAULT '',
+                enabled INTEGER NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (skill_id, user_id, channel)
+            );
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 27,
+        description: "persisted node registrations",
+        sql: r"
+            -- Last registration of each node device, kept across restarts
+            CREATE TABLE IF NOT EXISTS node_registrations (
+                device_id TEXT PRIMARY KEY,
+                display_name TEXT,
+                platform TEXT NOT NULL,
+                device_family TEXT,
+                caps TEXT NOT NULL DEFAULT '[]',
+                commands TEXT NOT NULL DEFAULT '[]',
+                last_seen TEXT NOT NULL
+            );
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 28,
+        description: "memory sync cursors",
+        sql: r"
+            -- Server cursor of the last successful pull, per sync API
+            CREATE TABLE IF NOT EXISTS sync_state (
+                api_url TEXT PRIMARY KEY,
+                cursor TEXT NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 29,
+        description: "session tool profiles",
+        sql: r"
+            -- Tool profile overriding the channel's for one session (NULL = none)
+            ALTER TABLE sessions ADD COLUMN tool_profile TEXT;
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 30,
+        description: "single-use pairing codes",
+        sql: r"
+            -- Issued DM pairing codes; consumed codes stay until expiry to detect reuse
+            CREATE TABLE IF NOT EXISTS pairing_codes (
+                id TEXT PRIMARY KEY,
+                code TEXT NOT NULL,
+                sender_id TEXT NOT NULL,
+                channel TEXT NOT NULL,
+                issued_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL,
+                consumed_at TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_pairing_codes_sender ON pairing_codes(sender_id, channel);
+            CREATE INDEX IF NOT EXISTS idx_pairing_codes_expires ON pairing_codes(expires_at);
+
+            -- Carry pending codes over, then drop the pending rows from paired_users
+            INSERT INTO pairing_codes (id, code, sender_id, channel, issued_at, expires_at)
+            SELECT id, pairing_code, sender_id, channel, paired_at, code_expires_at
+            FROM paired_users
+            WHERE pairing_code IS NOT NULL AND code_expires_at IS NOT NULL;
+
+            DELETE FROM paired_users WHERE pairing_code IS NOT NULL;
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 31,
+        description: "pairing attempt lockout",
+        sql: r"
+            -- Failed pairing attempts per sender; blocks counts consecutive lockouts
+            CREATE TABLE IF NOT EXISTS pairing_attempts (
+                sender_id TEXT NOT NULL,
+                channel TEXT NOT NULL,
+                failures INTEGER NOT NULL DEFAULT 0,
+                blocks INTEGER NOT NULL DEFAULT 0,
+                locked_until TEXT,
+                last_failure_at TEXT NOT NULL,
+                PRIMARY KEY (sender_id, channel)
+            );
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 32,
+        description: "device revocation and sessions",
+        sql: r"
+            ALTER TABLE devices ADD COLUMN user_id TEXT;
+            ALTER TABLE devices ADD COLUMN revoked_at TEXT;
+            CREATE INDEX IF NOT EXISTS idx_devices_user ON devices(user_id);
+
+            -- Sessions of devices that signed an auth challenge (token stored hashed)
+            CREATE TABLE IF NOT EXISTS device_sessions (
+                token_hash TEXT PRIMARY KEY,
+                device_id TEXT NOT NULL REFERENCES devices(id),
+                created_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_device_sessions_device ON device_sessions(device_id);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 33,
+        description: "per-user API keys",
+        sql: r"
+            -- Per-user API keys; only Argon2 hashes of the keys are stored
+            CREATE TABLE IF NOT EXISTS user_api_keys (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                key_hash TEXT NOT NULL,
+                scopes TEXT NOT NULL DEFAULT 'chat',
+                created_at TEXT NOT NULL,
+                last_used_at TEXT,
+                revoked_at TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_user_api_keys_user ON user_api_keys(user_id);
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 34,
+        description: "device pairing approval",
+        sql: r"
+            -- Who approved the pairing; sessions of unapproved devices are refused
+            ALTER TABLE devices ADD COLUMN approved_by TEXT;
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 35,
+        description: "outbox delivery attempts",
+        sql: r"
+            -- Retry bookkeeping: failed deliveries back off and are
+            -- dead-lettered after too many attempts; claimed rows are leased
+            -- to one deliverer at a time
+            ALTER TABLE outbox ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE outbox ADD COLUMN next_attempt_at TEXT;
+            ALTER TABLE outbox ADD COLUMN claimed_until TEXT;
+            ALTER TABLE outbox ADD COLUMN dead_at TEXT;
+        ",
+        backfill: None,
+    },
+    Migration {
+        version: 36,
+        description: "memory sync conflicts",
+        sql: r"
+            -- Fields a manual-strategy pull left unresolved; kept until an
+            -- admin resolves them or the two copies agree again
+            CREATE TABLE IF NOT EXISTS sync_conflicts (
+                memory_id TEXT NOT NULL,
+                field TEXT NOT NULL,
+                local_value TEXT NOT NULL,
+                remote_value TEXT NOT NULL,
+                detected_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (memory_id, field)
+            );
+        ",
+        backfill: None,
+    },
+];
+
+/// Read the schema version stored in the `user_version` pragma
+///
+/// # Errors
+///
+/// Returns error if the pragma cannot be read
+pub fn current_version(conn: &Connection) -> Result<i32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Apply every migration newer than the database's current version
+///
+/// # Errors
+///
+/// Returns error if a migration fails; earlier steps stay applied
+pub fn run(conn: &Connection) -> Result<()> {
+    let version = current_version(conn)?;
+    let latest = MIGRATIONS.last().map_or(0, |m| m.version);
+
+    if version > latest {
+        tracing::warn!(
+            version,
+            latest,
+            "database schema is newer than this build, skipping migrations"
+        );
+        return Ok(());
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > version) {
+        apply(conn, migration)?;
+    }
+
+    Ok(())
+}
+
+/// Apply one migration and bump `user_version` atomically
+fn apply(conn: &Connection, migration: &Migration) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute_batch(migration.sql)?;
+    if let Some(backfill) = migration.backfill {
+        backfill(&tx)?;
+    }
+    tx.pragma_update(None, "user_version", migration.version)?;
+    tx.commit()?;
+
+    tracing::info!(
+        version = migration.version,
+        description = migration.description,
+        "migrated schema"
+    );
+    Ok(())
+}
+
+/// Index message content with FTS5, kept in sync by triggers
+///
+/// Skipped when `SQLite` was built without FTS5; message search then falls
+/// back to `LIKE` scans.
+fn create_message_search(conn: &Connection) -> Result<()> {
+    let has_fts5: bool = conn.query_row(
+        "SELECT sqlite_compileoption_used('ENABLE_FTS5')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_fts5 {
+        tracing::warn!("SQLite built without FTS5, message search will scan with LIKE");
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        r"
+        -- Keyed by message id rather than rowid, which VACUUM may renumber
+        CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+            content,
+            message_id UNINDEXED
+        );
+
+        INSERT INTO messages_fts (content, message_id)
+        SELECT content, id FROM messages;
+
+        CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages BEGIN
+            INSERT INTO messages_fts (content, message_id) VALUES (new.content, new.id);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+            DELETE FROM messages_fts WHERE message_id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE OF content ON messages BEGIN
+            UPDATE messages_fts SET content = new.content WHERE message_id = old.id;
+        END;
+        ",
+    )?;
+
+    Ok(())
+}
+
+/// Backfill `content_hash` for existing memories that lack one
+fn backfill_content_hashes(conn: &Connection) -> Result<()> {
+    use sha2::{Digest, Sha256};
+
+    let mut stmt = conn.prepare("SELECT id, content FROM memories WHERE content_hash IS NULL")?;
+
+    let rows: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .flatten()
+        .collect();
+
+    let count = rows.len();
+    for (id, content) in rows {
+        let mut hasher = Sha256::new();
+        hasher.update(content.as_bytes());
+        let hash = hex::encode(hasher.finalize());
+
+        conn.execute(
+            "UPDATE memories SET content_hash = ?1 WHERE id = ?2",
+            rusqlite::params![hash, id],
+        )?;
+    }
+
+    if count > 0 {
+        tracing::info!(count, "backfilled content hashes for existing memories");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_test_conn() -> Connection {
+        crate::db::register_sqlite_vec();
+        Connection::open_in_memory().unwrap()
+    }
+
+    #[test]
+    fn test_versions_are_contiguous() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(usize::try_from(migration.version).unwrap(), i + 1);
+        }
+        assert_eq!(
+            MIGRATIONS.last().map(|m| m.version),
+            Some(crate::db::SCHEMA_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_migrates_older_database_forward() {
+        let conn = setup_test_conn();
+
+        // Simulate a database created by a release at schema v8
+        for migration in MIGRATIONS.iter().take_while(|m| m.version <= 8) {
+            apply(&conn, migration).unwrap();
+        }
+        assert_eq!(current_version(&conn).unwrap(), 8);
+        conn.execute_batch(
+            "INSERT INTO users (id) VALUES ('u1');
+             INSERT INTO memories (id, user_id, category, content)
+             VALUES ('m1', 'u1', 'fact', 'likes tea');",
+        )
+        .unwrap();
+
+        run(&conn).unwrap();
+
+        assert_eq!(current_version(&conn).unwrap(), crate::db::SCHEMA_VERSION);
+        let (content, hash): (String, Option<String>) = conn
+            .query_row(
+                "SELECT content, content_hash FROM memories WHERE id = 'm1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(content, "likes tea");
+        assert!(hash.is_some(), "v9 backfill should hash existing memories");
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        let conn = setup_test_conn();
+        let broken = Migration {
+            version: 1,
+            description: "broken",
+            sql: "CREATE TABLE t (id TEXT); INSERT INTO missing VALUES (1);",
+            backfill: None,
+        };
+
+        assert!(apply(&conn, &broken).is_err());
+        assert_eq!(current_version(&conn).unwrap(), 0);
+        let tables: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 't'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tables, 0);
+    }
+}
@@ -1,15 +1,21 @@
 // TODO: evaluate migrating from rusqlite to embedded Postgres (e.g. pglite-rs
 // or embedded-postgres) for schema parity with server-side Postgres services
 
+pub mod api_key;
 pub mod embedder;
 pub mod indexer;
 pub mod knowledge;
 pub mod memory;
+mod migrations;
+pub mod node;
+pub mod outbox;
 pub mod persona;
 mod schema;
 pub mod session;
 pub mod skill;
+pub mod sync_state;
 pub mod telegram;
+pub mod usage;
 pub mod user;
 
 use std::path::Path;
@@ -17,6 +23,7 @@ use std::sync::Once;
 
 use r2d2::{Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
+use serde::Serialize;
 
 use crate::{Error, Result};
 
@@ -48,15 +55,25 @@ pub(crate) fn register_sqlite_vec() {
     });
 }
 
-pub use embedder::{EMBEDDING_DIM, Embedder};
+pub use api_key::{UserApiKey, UserApiKeyRepo};
+pub use embedder::{
+    Embedder, EmbeddingBackend, EmbeddingBackendKind, LOCAL_EMBEDDING_DIM, OPENAI_EMBEDDING_DIM,
+};
 pub use indexer::{ExtractedFact, ExtractionResponse, Indexer};
 pub use knowledge::{KnowledgePackRepo, KnowledgePackRow};
-pub use memory::{Memory, MemoryCategory, MemoryRepo};
+pub use memory::{
+    DEFAULT_DEDUP_THRESHOLD, DEFAULT_EPHEMERAL_TTL_DAYS, Memory, MemoryCategory, MemoryRepo,
+    MemoryScope, MemoryTtl, UpsertOutcome,
+};
+pub use node::NodeRepo;
+pub use outbox::{OutboxMessage, OutboxRepo};
 pub use persona::{InstalledPersona, PersonaRepo};
 pub use schema::SCHEMA_VERSION;
 pub use session::{Message, MessageRole, Session, SessionRepo};
 pub use skill::SkillRepo;
+pub use sync_state::SyncStateRepo;
 pub use telegram::{TelegramGroupConfig, TelegramGroupConfigRepo};
+pub use usage::{UsageLimit, UsageRepo};
 pub use user::{User, UserContext, UserRepo};
 
 /// Database connection pool
@@ -65,6 +82,53 @@ pub type DbPool = Pool<SqliteConnectionManager>;
 /// Pooled database connection
 pub type DbConn = PooledConnection<SqliteConnectionManager>;
 
+/// Default maximum number of pooled connections
+pub const DEFAULT_POOL_SIZE: u32 = 4;
+
+/// Snapshot of connection pool usage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PoolStats {
+    /// Connections currently open (idle and checked out)
+    pub connections: u32,
+    /// Open connections waiting to be checked out
+    pub idle: u32,
+    /// Maximum connections the pool will open
+    pub max_size: u32,
+}
+
+impl PoolStats {
+    /// Connections currently checked out
+    #[must_use]
+    pub const fn in_use(&self) -> u32 {
+        self.connections.saturating_sub(self.idle)
+    }
+
+    /// Whether every connection is checked out, so new requests must wait
+    #[must_use]
+    pub const fn is_saturated(&self) -> bool {
+        self.in_use() >= self.max_size
+    }
+}
+
+/// Current connection pool usage
+#[must_use]
+pub fn pool_stats(pool: &DbPool) -> PoolStats {
+    let state = pool.state();
+    PoolStats {
+        connections: state.connections,
+        idle: state.idle_connections,
+        max_size: pool.max_size(),
+    }
+}
+
+/// Pool size from `BEACON_DB_POOL_SIZE` (default: 4, minimum 1)
+fn pool_size_from_env() -> u32 {
+    std::env::var("BEACON_DB_POOL_SIZE")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .map_or(DEFAULT_POOL_SIZE, |size| size.max(1))
+}
+
 /// Initialize the database
 ///
 /// # Errors
@@ -76,13 +140,13 @@ pub fn init<P: AsRef<Path>>(path: P) -> Result<DbPool> {
 
     let manager = SqliteConnectionManager::file(path);
     let pool = Pool::builder()
-        .max_size(4)
+        .max_size(pool_size_from_env())
         .build(manager)
         .map_err(|e| Error::Database(e.to_string()))?;
 
     // Run migrations on first connection
     let conn = pool.get().map_err(|e| Error::Database(e.to_string()))?;
-    schema::init(&conn)?;
+    schema::init(&conn, EmbeddingBackendKind::from_env()?.dimension())?;
 
     tracing::info!(version = SCHEMA_VERSION, "database initialized");
     Ok(pool)
@@ -104,7 +168,7 @@ pub fn init_memory() -> Result<DbPool> {
         .map_err(|e| Error::Database(e.to_string()))?;
 
     let conn = pool.get().map_err(|e| Error::Database(e.to_string()))?;
-    schema::init(&conn)?;
+    schema::init(&conn, EmbeddingBackendKind::default().dimension())?;
 
     Ok(pool)
 }
@@ -118,4 +182,20 @@ mod tests {
         let pool = init_memory().unwrap();
         let _conn = pool.get().unwrap();
     }
+
+    #[test]
+    fn test_pool_stats() {
+        let pool = init_memory().unwrap();
+
+        let stats = pool_stats(&pool);
+        assert_eq!(stats.max_size, 1);
+        assert_eq!(stats.in_use(), 0);
+        assert!(!stats.is_saturated());
+
+        let _conn = pool.get().unwrap();
+        let stats = pool_stats(&pool);
+        assert_eq!(stats.connections, 1);
+        assert_eq!(stats.idle, 0);
+        assert!(stats.is_saturated());
+    }
 }
@@ -0,0 +1,175 @@
+//! Persisted node registrations
+//!
+//! Stores the last registration of each node device so the registry can list
+//! known devices after a restart, before they reconnect.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Row;
+
+use super::DbPool;
+use crate::nodes::{KnownNode, NodeRegistration};
+use crate::{Error, Result};
+
+/// Repository for node registrations
+#[derive(Debug, Clone)]
+pub struct NodeRepo {
+    pool: DbPool,
+}
+
+impl NodeRepo {
+    /// Create a new repository
+    #[must_use]
+    pub const fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert or replace a device's registration
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn upsert(&self, registration: &NodeRegistration, last_seen: DateTime<Utc>) -> Result<()> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        conn.execute(
+            r"INSERT INTO node_registrations (device_id, display_name, platform, device_family, caps, commands, last_seen)
+              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+              ON CONFLICT(device_id) DO UPDATE SET
+                display_name = excluded.display_name,
+                platform = excluded.platform,
+                device_family = excluded.device_family,
+                caps = excluded.caps,
+                commands = excluded.commands,
+                last_seen = excluded.last_seen",
+            rusqlite::params![
+                registration.device_id,
+                registration.display_name,
+                registration.platform,
+                registration.device_family,
+                serde_json::to_string(&registration.caps)?,
+                serde_json::to_string(&registration.commands)?,
+                last_seen.to_rfc3339(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Record that a device was seen
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn touch(&self, device_id: &str, last_seen: DateTime<Utc>) -> Result<()> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        conn.execute(
+            "UPDATE node_registrations SET last_seen = ?2 WHERE device_id = ?1",
+            rusqlite::params![device_id, last_seen.to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    /// List all stored registrations
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn list(&self) -> Result<Vec<KnownNode>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT device_id, display_name, platform, device_family, caps, commands, last_seen
+             FROM node_registrations ORDER BY last_seen DESC",
+        )?;
+        let nodes = stmt
+            .query_map([], Self::row_to_node)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(nodes)
+    }
+
+    /// Delete a device's registration
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn delete(&self, device_id: &str) -> Result<bool> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let deleted = conn.execute(
+            "DELETE FROM node_registrations WHERE device_id = ?1",
+            [device_id],
+        )?;
+
+        Ok(deleted > 0)
+    }
+
+    fn row_to_node(row: &Row<'_>) -> rusqlite::Result<KnownNode> {
+        let caps: String = row.get(4)?;
+        let commands: String = row.get(5)?;
+        let last_seen: String = row.get(6)?;
+
+        Ok(KnownNode {
+            registration: NodeRegistration {
+                device_id: row.get(0)?,
+                display_name: row.get(1)?,
+                platform: row.get(2)?,
+                device_family: row.get(3)?,
+                caps: serde_json::from_str(&caps).unwrap_or_default(),
+                commands: serde_json::from_str(&commands).unwrap_or_default(),
+            },
+            last_seen: DateTime::parse_from_rfc3339(&last_seen)
+                .map_or_else(|_| Utc::now(), |t| t.with_timezone(&Utc)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_memory;
+
+    fn registration(device_id: &str) -> NodeRegistration {
+        NodeRegistration {
+            device_id: device_id.to_string(),
+            display_name: Some("My MacBook".to_string()),
+            platform: "darwin".to_string(),
+            device_family: None,
+            caps: vec!["audio".to_string()],
+            commands: vec!["system.run".to_string()],
+        }
+    }
+
+    #[test]
+    fn upsert_list_and_delete() {
+        let repo = NodeRepo::new(init_memory().unwrap());
+        let seen = Utc::now();
+
+        repo.upsert(&registration("d1"), seen).unwrap();
+        let mut updated = registration("d1");
+        updated.caps.push("display".to_string());
+        repo.upsert(&updated, seen).unwrap();
+
+        let nodes = repo.list().unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].registration.caps, vec!["audio", "display"]);
+        assert_eq!(nodes[0].last_seen.timestamp(), seen.timestamp());
+
+        assert!(repo.delete("d1").unwrap());
+        assert!(repo.list().unwrap().is_empty());
+    }
+}
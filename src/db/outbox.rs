@@ -0,0 +1,371 @@
+//! Outbox for proactive messages that could not be delivered immediately
+//!
+//! Scheduled deliveries (e.g. reminders) land here when neither the
+//! originating channel nor a live WebSocket connection is available. Pending
+//! entries are retried through their original channel and flushed to the
+//! user's next WebSocket connection, whichever comes first.
+//!
+//! Deliverers claim rows before sending so the retry loop and a reconnecting
+//! WebSocket never deliver the same message twice. Failed deliveries back off
+//! exponentially and are dead-lettered after [`MAX_DELIVERY_ATTEMPTS`].
+
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use super::DbPool;
+use crate::{Error, Result};
+
+/// A queued outbound message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxMessage {
+    pub id: String,
+    pub user_id: String,
+    /// Channel the message was originally meant for
+    pub channel: String,
+    pub channel_id: String,
+    pub thread_id: Option<String>,
+    pub content: String,
+    /// Failed delivery attempts so far
+    pub attempts: u32,
+}
+
+/// Failed attempts after which a message is dead-lettered
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 8;
+
+/// How long a claim is held before another deliverer may take the row
+const CLAIM_LEASE: Duration = Duration::from_secs(300);
+
+/// Longest delay between delivery attempts
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(3600);
+
+const MESSAGE_COLUMNS: &str =
+    "id, user_id, channel, channel_id, thread_id, content, attempts, created_at, rowid";
+
+/// Delay before the next attempt after `attempts` failures
+fn retry_delay(attempts: u32) -> Duration {
+    let exp = attempts.saturating_sub(1).min(16);
+    Duration::from_secs(60)
+        .saturating_mul(1 << exp)
+        .min(MAX_RETRY_DELAY)
+}
+
+/// `datetime()` modifier for an offset from now
+fn offset(delay: Duration) -> String {
+    format!("+{} seconds", delay.as_secs())
+}
+
+/// Map a row selected or returned with [`MESSAGE_COLUMNS`]
+fn row_to_message(row: &rusqlite::Row<'_>) -> rusqlite::Result<(OutboxMessage, String, i64)> {
+    Ok((
+        OutboxMessage {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            channel: row.get(2)?,
+            channel_id: row.get(3)?,
+            thread_id: row.get(4)?,
+            content: row.get(5)?,
+            attempts: row.get(6)?,
+        },
+        row.get(7)?,
+        row.get(8)?,
+    ))
+}
+
+/// Order rows oldest first; `RETURNING` does not guarantee an order
+fn oldest_first(mut rows: Vec<(OutboxMessage, String, i64)>) -> Vec<OutboxMessage> {
+    rows.sort_by(|a, b| (&a.1, a.2).cmp(&(&b.1, b.2)));
+    rows.into_iter().map(|(message, _, _)| message).collect()
+}
+
+/// Outbox repository
+#[derive(Debug, Clone)]
+pub struct OutboxRepo {
+    pool: DbPool,
+}
+
+impl OutboxRepo {
+    /// Create a new outbox repository
+    #[must_use]
+    pub const fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queue a message for later delivery
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn enqueue(
+        &self,
+        user_id: &str,
+        channel: &str,
+        channel_id: &str,
+        thread_id: Option<&str>,
+        content: &str,
+    ) -> Result<String> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let id = format!("out_{}", Uuid::new_v4());
+        conn.execute(
+            "INSERT INTO outbox (id, user_id, channel, channel_id, thread_id, content)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![id, user_id, channel, channel_id, thread_id, content],
+        )?;
+
+        Ok(id)
+    }
+
+    /// List undelivered, non-dead-lettered messages for a user, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn pending(&self, user_id: &str) -> Result<Vec<OutboxMessage>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {MESSAGE_COLUMNS}
+             FROM outbox WHERE user_id = ?1 AND delivered_at IS NULL AND dead_at IS NULL
+             ORDER BY created_at, rowid"
+        ))?;
+
+        let rows = stmt
+            .query_map([user_id], row_to_message)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(oldest_first(rows))
+    }
+
+    /// Claim messages due for a retry across all users, oldest first
+    ///
+    /// Claimed rows are skipped by other deliverers until they are marked
+    /// delivered, released, or the claim lease expires.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn claim_due(&self, limit: usize) -> Result<Vec<OutboxMessage>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut stmt = conn.prepare(&format!(
+            "UPDATE outbox SET claimed_until = datetime('now', ?1)
+             WHERE id IN (
+                 SELECT id FROM outbox
+                 WHERE delivered_at IS NULL AND dead_at IS NULL
+                   AND (next_attempt_at IS NULL OR next_attempt_at <= datetime('now'))
+                   AND (claimed_until IS NULL OR claimed_until <= datetime('now'))
+                 ORDER BY created_at, rowid LIMIT ?2
+             )
+             RETURNING {MESSAGE_COLUMNS}"
+        ))?;
+
+        let rows = stmt
+            .query_map(
+                rusqlite::params![
+                    offset(CLAIM_LEASE),
+                    i64::try_from(limit).unwrap_or(i64::MAX)
+                ],
+                row_to_message,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(oldest_first(rows))
+    }
+
+    /// Claim every undelivered message for a user, oldest first
+    ///
+    /// Used when the user connects, so retry backoff is ignored; rows already
+    /// claimed by another deliverer are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn claim_for_user(&self, user_id: &str) -> Result<Vec<OutboxMessage>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut stmt = conn.prepare(&format!(
+            "UPDATE outbox SET claimed_until = datetime('now', ?1)
+             WHERE user_id = ?2 AND delivered_at IS NULL AND dead_at IS NULL
+               AND (claimed_until IS NULL OR claimed_until <= datetime('now'))
+             RETURNING {MESSAGE_COLUMNS}"
+        ))?;
+
+        let rows = stmt
+            .query_map(
+                rusqlite::params![offset(CLAIM_LEASE), user_id],
+                row_to_message,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(oldest_first(rows))
+    }
+
+    /// Mark a message as delivered
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn mark_delivered(&self, id: &str) -> Result<()> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        conn.execute(
+            "UPDATE outbox SET delivered_at = datetime('now'), claimed_until = NULL
+             WHERE id = ?1",
+            [id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Record a failed delivery of a claimed message and release the claim
+    ///
+    /// Schedules the next attempt with exponential backoff. Returns `true`
+    /// when the message reached [`MAX_DELIVERY_ATTEMPTS`] and was
+    /// dead-lettered.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn record_failure(&self, message: &OutboxMessage) -> Result<bool> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let attempts = message.attempts.saturating_add(1);
+        let dead = attempts >= MAX_DELIVERY_ATTEMPTS;
+        conn.execute(
+            "UPDATE outbox SET attempts = ?1, claimed_until = NULL,
+                 next_attempt_at = datetime('now', ?2),
+                 dead_at = CASE WHEN ?3 THEN datetime('now') ELSE NULL END
+             WHERE id = ?4",
+            rusqlite::params![attempts, offset(retry_delay(attempts)), dead, message.id],
+        )?;
+
+        Ok(dead)
+    }
+
+    /// Release a claim without counting an attempt, retrying after `delay`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn release(&self, id: &str, delay: Duration) -> Result<()> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        conn.execute(
+            "UPDATE outbox SET claimed_until = NULL, next_attempt_at = datetime('now', ?1)
+             WHERE id = ?2",
+            rusqlite::params![offset(delay), id],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    #[test]
+    fn outbox_enqueue_and_deliver() {
+        let pool = db::init_memory().unwrap();
+        let repo = OutboxRepo::new(pool);
+
+        let first = repo
+            .enqueue("u1", "discord", "chan-1", Some("t1"), "first")
+            .unwrap();
+        repo.enqueue("u1", "telegram", "42", None, "second")
+            .unwrap();
+        repo.enqueue("u2", "slack", "C1", None, "other user")
+            .unwrap();
+
+        let pending = repo.pending("u1").unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].content, "first");
+        assert_eq!(pending[0].thread_id.as_deref(), Some("t1"));
+        assert_eq!(pending[1].channel, "telegram");
+
+        repo.mark_delivered(&first).unwrap();
+        let pending = repo.pending("u1").unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].content, "second");
+
+        let all = repo.claim_due(10).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].content, "second");
+        assert_eq!(all[1].user_id, "u2");
+    }
+
+    #[test]
+    fn outbox_claims_are_exclusive() {
+        let pool = db::init_memory().unwrap();
+        let repo = OutboxRepo::new(pool);
+
+        repo.enqueue("u1", "discord", "chan-1", None, "first")
+            .unwrap();
+        repo.enqueue("u1", "discord", "chan-1", None, "second")
+            .unwrap();
+
+        let claimed = repo.claim_due(1).unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].content, "first");
+
+        // The WebSocket flush only sees the row the retry loop left unclaimed
+        let flushed = repo.claim_for_user("u1").unwrap();
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].content, "second");
+        assert!(repo.claim_due(10).unwrap().is_empty());
+
+        repo.release(&claimed[0].id, Duration::ZERO).unwrap();
+        assert_eq!(repo.claim_for_user("u1").unwrap()[0].content, "first");
+    }
+
+    #[test]
+    fn outbox_failures_back_off_and_dead_letter() {
+        let pool = db::init_memory().unwrap();
+        let repo = OutboxRepo::new(pool);
+
+        repo.enqueue("u1", "discord", "chan-1", None, "poison")
+            .unwrap();
+
+        let mut message = repo.claim_due(10).unwrap().remove(0);
+        assert!(!repo.record_failure(&message).unwrap());
+        // Backed off, so not due yet, but still pending
+        assert!(repo.claim_due(10).unwrap().is_empty());
+        assert_eq!(repo.pending("u1").unwrap()[0].attempts, 1);
+
+        message.attempts = MAX_DELIVERY_ATTEMPTS - 1;
+        assert!(repo.record_failure(&message).unwrap());
+        assert!(repo.pending("u1").unwrap().is_empty());
+        assert!(repo.claim_for_user("u1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_cap() {
+        assert_eq!(retry_delay(1), Duration::from_secs(60));
+        assert_eq!(retry_delay(2), Duration::from_secs(120));
+        assert_eq!(retry_delay(4), Duration::from_secs(480));
+        assert_eq!(retry_delay(20), MAX_RETRY_DELAY);
+    }
+}
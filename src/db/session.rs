@@ -14,6 +14,10 @@ pub struct Session {
     pub channel: String,
     pub channel_id: String,
     pub persona_id: String,
+    /// Thread or forum topic this session is scoped to
+    pub thread_id: Option<String>,
+    /// Tool profile overriding the channel's (see [`crate::tools::SessionToolProfile`])
+    pub tool_profile: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -39,7 +43,9 @@ pub enum MessageRole {
 }
 
 impl MessageRole {
-    const fn as_str(self) -> &'static str {
+    /// Stored role name
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
         match self {
             Self::User => "user",
             Self::Assistant => "assistant",
@@ -92,29 +98,38 @@ impl SessionRepo {
         channel: &str,
         channel_id: &str,
         persona_id: &str,
+    ) -> Result<Session> {
+        self.find_or_create_threaded(user_id, channel, channel_id, persona_id, None)
+    }
+
+    /// Find or create a session scoped to a thread or forum topic
+    ///
+    /// Each `thread_id` gets its own session and history; `None` selects the
+    /// conversation-wide session.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn find_or_create_threaded(
+        &self,
+        user_id: &str,
+        channel: &str,
+        channel_id: &str,
+        persona_id: &str,
+        thread_id: Option<&str>,
     ) -> Result<Session> {
         let conn = self
             .pool
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
-        // Try to find existing session
+        // Try to find existing session (`IS` matches NULL thread IDs)
         let existing: Option<Session> = conn
             .query_row(
-                "SELECT id, user_id, channel, channel_id, persona_id, created_at, updated_at
-                 FROM sessions WHERE channel = ?1 AND channel_id = ?2",
-                [channel, channel_id],
-                |row| {
-                    Ok(Session {
-                        id: row.get(0)?,
-                        user_id: row.get(1)?,
-                        channel: row.get(2)?,
-                        channel_id: row.get(3)?,
-                        persona_id: row.get(4)?,
-                        created_at: parse_datetime(&row.get::<_, String>(5)?),
-                        updated_at: parse_datetime(&row.get::<_, String>(6)?),
-                    })
-                },
+                "SELECT id, user_id, channel, channel_id, persona_id, created_at, updated_at, thread_id, tool_profile
+                 FROM sessions WHERE channel = ?1 AND channel_id = ?2 AND thread_id IS ?3",
+                rusqlite::params![channel, channel_id, thread_id],
+                row_to_session,
             )
             .ok();
 
@@ -127,9 +142,9 @@ impl SessionRepo {
         let now = Utc::now().to_rfc3339();
 
         conn.execute(
-            "INSERT INTO sessions (id, user_id, channel, channel_id, persona_id, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)",
-            [&id, user_id, channel, channel_id, persona_id, &now],
+            "INSERT INTO sessions (id, user_id, channel, channel_id, persona_id, created_at, updated_at, thread_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, ?7)",
+            rusqlite::params![id, user_id, channel, channel_id, persona_id, now, thread_id],
         )
         .map_err(|e| Error::Database(e.to_string()))?;
 
@@ -139,6 +154,8 @@ impl SessionRepo {
             channel: channel.to_string(),
             channel_id: channel_id.to_string(),
             persona_id: persona_id.to_string(),
+            thread_id: thread_id.map(String::from),
+            tool_profile: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         })
@@ -157,23 +174,13 @@ impl SessionRepo {
 
         let mut stmt = conn
             .prepare(
-                "SELECT id, user_id, channel, channel_id, persona_id, created_at, updated_at
+                "SELECT id, user_id, channel, channel_id, persona_id, created_at, updated_at, thread_id, tool_profile
                  FROM sessions ORDER BY updated_at DESC",
             )
             .map_err(|e| Error::Database(e.to_string()))?;
 
         let sessions = stmt
-            .query_map([], |row| {
-                Ok(Session {
-                    id: row.get(0)?,
-                    user_id: row.get(1)?,
-                    channel: row.get(2)?,
-                    channel_id: row.get(3)?,
-                    persona_id: row.get(4)?,
-                    created_at: parse_datetime(&row.get::<_, String>(5)?),
-                    updated_at: parse_datetime(&row.get::<_, String>(6)?),
-                })
-            })
+            .query_map([], row_to_session)
             .map_err(|e| Error::Database(e.to_string()))?
             .filter_map(std::result::Result::ok)
             .collect();
@@ -181,6 +188,29 @@ impl SessionRepo {
         Ok(sessions)
     }
 
+    /// Set or clear a session's tool profile override
+    ///
+    /// Returns `false` if the session does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn set_tool_profile(&self, session_id: &str, profile: Option<&str>) -> Result<bool> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let updated = conn
+            .execute(
+                "UPDATE sessions SET tool_profile = ?2 WHERE id = ?1",
+                rusqlite::params![session_id, profile],
+            )
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(updated > 0)
+    }
+
     /// Add a message to a session
     ///
     /// # Errors
@@ -281,6 +311,72 @@ impl SessionRepo {
         Ok(messages)
     }
 
+    /// Full-text search over a user's messages across all sessions
+    ///
+    /// Results are ordered by FTS5 relevance, or newest first when `SQLite`
+    /// lacks FTS5 and the search falls back to a `LIKE` scan.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn search_messages(
+        &self,
+        user_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Message>> {
+        let Some(match_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let has_fts: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'messages_fts')",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let (sql, pattern) = if has_fts {
+            (
+                "SELECT m.id, m.session_id, m.role, m.content, m.created_at, m.thread_id
+                 FROM messages_fts f
+                 JOIN messages m ON m.id = f.message_id
+                 JOIN sessions s ON s.id = m.session_id
+                 WHERE messages_fts MATCH ?1 AND s.user_id = ?2
+                 ORDER BY f.rank LIMIT ?3",
+                match_query,
+            )
+        } else {
+            (
+                "SELECT m.id, m.session_id, m.role, m.content, m.created_at, m.thread_id
+                 FROM messages m
+                 JOIN sessions s ON s.id = m.session_id
+                 WHERE m.content LIKE ?1 ESCAPE '\\' AND s.user_id = ?2
+                 ORDER BY m.created_at DESC LIMIT ?3",
+                format!("%{}%", escape_like(query.trim())),
+            )
+        };
+
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let messages = stmt
+            .query_map(rusqlite::params![pattern, user_id, limit], message_from_row)
+            .map_err(|e| Error::Database(e.to_string()))?
+            .filter_map(std::result::Result::ok)
+            .collect();
+
+        Ok(messages)
+    }
+
     /// Get recent messages for a specific thread within a session
     ///
     /// If `thread_id` is None, returns messages that have no thread (root-level messages)
@@ -456,6 +552,52 @@ impl SessionRepo {
     }
 }
 
+fn message_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Message> {
+    Ok(Message {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        role: MessageRole::from_str(&row.get::<_, String>(2)?).unwrap_or(MessageRole::User),
+        content: row.get(3)?,
+        created_at: parse_datetime(&row.get::<_, String>(4)?),
+        thread_id: row.get(5)?,
+    })
+}
+
+/// Quote each whitespace-separated term so user input can't form FTS5 syntax
+///
+/// Terms are implicitly AND-ed. Returns `None` for a blank query.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Escape `LIKE` wildcards so the query matches literally (escape char `\`)
+fn escape_like(query: &str) -> String {
+    query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Map a `SELECT id, user_id, channel, channel_id, persona_id, created_at,
+/// updated_at, thread_id, tool_profile` row to a `Session`
+fn row_to_session(row: &rusqlite::Row<'_>) -> rusqlite::Result<Session> {
+    Ok(Session {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        channel: row.get(2)?,
+        channel_id: row.get(3)?,
+        persona_id: row.get(4)?,
+        thread_id: row.get(7)?,
+        tool_profile: row.get(8)?,
+        created_at: parse_datetime(&row.get::<_, String>(5)?),
+        updated_at: parse_datetime(&row.get::<_, String>(6)?),
+    })
+}
+
 fn parse_datetime(s: &str) -> DateTime<Utc> {
     DateTime::parse_from_rfc3339(s).map_or_else(|_| Utc::now(), |dt| dt.with_timezone(&Utc))
 }
@@ -495,6 +637,58 @@ mod tests {
         assert_eq!(session.id, session2.id);
     }
 
+    #[test]
+    fn test_tool_profile_override() {
+        let repo = setup();
+        let session = repo
+            .find_or_create("test-user", "telegram", "dm-1", "orin")
+            .unwrap();
+        assert_eq!(session.tool_profile, None);
+
+        assert!(
+            repo.set_tool_profile(&session.id, Some("readonly"))
+                .unwrap()
+        );
+        let session = repo
+            .find_or_create("test-user", "telegram", "dm-1", "orin")
+            .unwrap();
+        assert_eq!(session.tool_profile.as_deref(), Some("readonly"));
+
+        assert!(repo.set_tool_profile(&session.id, None).unwrap());
+        assert_eq!(repo.list_all().unwrap()[0].tool_profile, None);
+        assert!(!repo.set_tool_profile("missing", Some("full")).unwrap());
+    }
+
+    #[test]
+    fn test_threaded_sessions_are_isolated() {
+        let repo = setup();
+
+        let main = repo
+            .find_or_create("test-user", "telegram", "chat-1", "orin")
+            .unwrap();
+        let topic = repo
+            .find_or_create_threaded("test-user", "telegram", "chat-1", "orin", Some("42"))
+            .unwrap();
+        let other_topic = repo
+            .find_or_create_threaded("test-user", "telegram", "chat-1", "orin", Some("43"))
+            .unwrap();
+
+        assert_ne!(main.id, topic.id);
+        assert_ne!(topic.id, other_topic.id);
+        assert!(main.thread_id.is_none());
+        assert_eq!(topic.thread_id.as_deref(), Some("42"));
+
+        // Lookups resolve to the same session per thread
+        let again = repo
+            .find_or_create_threaded("test-user", "telegram", "chat-1", "orin", Some("42"))
+            .unwrap();
+        assert_eq!(again.id, topic.id);
+        let unthreaded = repo
+            .find_or_create_threaded("test-user", "telegram", "chat-1", "orin", None)
+            .unwrap();
+        assert_eq!(unthreaded.id, main.id);
+    }
+
     #[test]
     fn test_add_and_get_messages() {
         let repo = setup();
@@ -561,6 +755,76 @@ mod tests {
         assert_eq!(threaded[1].content, "Thread reply 1");
     }
 
+    #[test]
+    fn test_search_messages() {
+        let repo = setup();
+        let conn = repo.pool.get().unwrap();
+        conn.execute("INSERT INTO users (id) VALUES ('other-user')", [])
+            .unwrap();
+
+        let session = repo
+            .find_or_create("test-user", "discord", "channel-123", "orin")
+            .unwrap();
+        let other = repo
+            .find_or_create("other-user", "discord", "channel-999", "orin")
+            .unwrap();
+
+        repo.add_message(&session.id, MessageRole::User, "Book a table for dinner")
+            .unwrap();
+        repo.add_message(&session.id, MessageRole::Assistant, "Dinner is booked")
+            .unwrap();
+        repo.add_message(&session.id, MessageRole::User, "What's the weather?")
+            .unwrap();
+        repo.add_message(&other.id, MessageRole::User, "dinner plans")
+            .unwrap();
+
+        let results = repo.search_messages("test-user", "dinner", 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|m| m.session_id == session.id));
+
+        // FTS5 syntax in user input is matched literally
+        assert!(repo.search_messages("test-user", "\"dinner OR", 10).is_ok());
+        assert!(
+            repo.search_messages("test-user", "  ", 10)
+                .unwrap()
+                .is_empty()
+        );
+
+        // Deleted messages leave the index
+        conn.execute(
+            "DELETE FROM messages WHERE content = 'Dinner is booked'",
+            [],
+        )
+        .unwrap();
+        let results = repo.search_messages("test-user", "booked", 10).unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_search_messages_like_fallback() {
+        let repo = setup();
+        let conn = repo.pool.get().unwrap();
+        conn.execute_batch(
+            "DROP TRIGGER messages_fts_insert;
+             DROP TRIGGER messages_fts_delete;
+             DROP TRIGGER messages_fts_update;
+             DROP TABLE messages_fts;",
+        )
+        .unwrap();
+
+        let session = repo
+            .find_or_create("test-user", "voice", "local", "orin")
+            .unwrap();
+        repo.add_message(&session.id, MessageRole::User, "100% sure about dinner")
+            .unwrap();
+        repo.add_message(&session.id, MessageRole::User, "100 reasons")
+            .unwrap();
+
+        let results = repo.search_messages("test-user", "100%", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "100% sure about dinner");
+    }
+
     #[test]
     fn test_message_count() {
         let repo = setup();
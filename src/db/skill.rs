@@ -178,6 +178,10 @@ impl SkillRepo {
             "DELETE FROM installed_skills WHERE id = ?1",
             rusqlite::params![skill_id],
         )?;
+        conn.execute(
+            "DELETE FROM skill_scopes WHERE skill_id = ?1",
+            rusqlite::params![skill_id],
+        )?;
 
         if rows > 0 {
             tracing::info!(skill_id = %skill_id, "skill uninstalled");
@@ -198,6 +202,25 @@ impl SkillRepo {
         install_specs, requires_config, location
     ";
 
+    /// Number of columns in [`Self::SELECT_COLS`]
+    const SELECT_COL_COUNT: usize = 31;
+
+    /// Effective enabled flag for user `?1` on channel `?2`: the narrowest
+    /// matching scope override (user+channel, user, channel), else the
+    /// skill's own flag
+    const EFFECTIVE_ENABLED: &str = r"
+        COALESCE(
+            (SELECT sc.enabled FROM skill_scopes sc
+             WHERE sc.skill_id = installed_skills.id
+               AND (sc.user_id = '' OR sc.user_id = ?1)
+               AND (sc.channel = '' OR sc.channel = ?2)
+               AND (sc.user_id != '' OR sc.channel != '')
+             ORDER BY sc.user_id != '' DESC, sc.channel != '' DESC
+             LIMIT 1),
+            enabled
+        )
+    ";
+
     /// Get an installed skill by ID
     ///
     /// # Errors
@@ -256,6 +279,10 @@ impl SkillRepo {
 
     /// Look up a skill by its slash command name, scoped to a user
     ///
+    /// The returned `enabled` flag is the effective state for `user_id` on
+    /// `channel`, with scoped overrides applied as in
+    /// [`SkillRepo::list_enabled_for_user`].
+    ///
     /// # Errors
     ///
     /// Returns error if database operation fails
@@ -263,6 +290,7 @@ impl SkillRepo {
         &self,
         command: &str,
         user_id: Option<&str>,
+        channel: Option<&str>,
     ) -> Result<Option<InstalledSkill>> {
         let conn = self
             .pool
@@ -270,14 +298,20 @@ impl SkillRepo {
             .map_err(|e| Error::Database(e.to_string()))?;
 
         let sql = format!(
-            "SELECT {} FROM installed_skills WHERE command_name = ?1 AND (user_id IS NULL OR user_id = ?2)",
+            "SELECT {}, {} FROM installed_skills WHERE command_name = ?3 AND (user_id IS NULL OR user_id = ?1)",
             Self::SELECT_COLS,
+            Self::EFFECTIVE_ENABLED,
         );
         let mut stmt = conn.prepare(&sql)?;
 
-        let result = stmt.query_row(rusqlite::params![command, user_id.unwrap_or("")], |row| {
-            Self::row_to_installed_skill(row)
-        });
+        let result = stmt.query_row(
+            rusqlite::params![user_id.unwrap_or(""), channel.unwrap_or(""), command],
+            |row| {
+                let mut skill = Self::row_to_installed_skill(row)?;
+                skill.enabled = row.get(Self::SELECT_COL_COUNT)?;
+                Ok(skill)
+            },
+        );
 
         match result {
             Ok(skill) => Ok(Some(skill)),
@@ -342,34 +376,131 @@ impl SkillRepo {
 
     /// List enabled skills visible to a specific user (shared + user-specific)
     ///
+    /// Scoped overrides from [`SkillRepo::set_scope`] apply with the narrowest
+    /// match winning: user+channel, then user, then channel, then the skill's
+    /// own enabled flag.
+    ///
     /// # Errors
     ///
     /// Returns error if database operation fails
-    pub fn list_enabled_for_user(&self, user_id: Option<&str>) -> Result<Vec<InstalledSkill>> {
+    pub fn list_enabled_for_user(
+        &self,
+        user_id: Option<&str>,
+        channel: Option<&str>,
+    ) -> Result<Vec<InstalledSkill>> {
         let conn = self
             .pool
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
         let sql = format!(
-            "SELECT {} FROM installed_skills WHERE enabled = 1 AND (user_id IS NULL OR user_id = ?1) ORDER BY name",
+            r"
+            SELECT {} FROM installed_skills
+            WHERE (user_id IS NULL OR user_id = ?1)
+              AND {} = 1
+            ORDER BY name
+            ",
             Self::SELECT_COLS,
+            Self::EFFECTIVE_ENABLED,
         );
         let mut stmt = conn.prepare(&sql)?;
 
         let rows = stmt.query_map(
-            rusqlite::params![user_id.unwrap_or("")],
+            rusqlite::params![user_id.unwrap_or(""), channel.unwrap_or("")],
             Self::row_to_installed_skill,
         )?;
 
         let mut skills = Vec::new();
         for row in rows {
-            skills.push(row?);
+            let mut skill = row?;
+            // Report the effective state, which a scope may have turned on
+            skill.enabled = true;
+            skills.push(skill);
         }
 
         Ok(skills)
     }
 
+    /// Enable or disable a skill for a user, a channel, or both
+    ///
+    /// With neither `user_id` nor `channel` this sets the skill's global flag.
+    /// Returns false if the skill does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn set_scope(
+        &self,
+        skill_id: &str,
+        user_id: Option<&str>,
+        channel: Option<&str>,
+        enabled: bool,
+    ) -> Result<bool> {
+        if user_id.is_none() && channel.is_none() {
+            return self.set_enabled(skill_id, enabled);
+        }
+        if self.get(skill_id)?.is_none() {
+            return Ok(false);
+        }
+
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        conn.execute(
+            r"
+            INSERT INTO skill_scopes (skill_id, user_id, channel, enabled)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(skill_id, user_id, channel) DO UPDATE SET
+                enabled = excluded.enabled,
+                updated_at = datetime('now')
+            ",
+            rusqlite::params![
+                skill_id,
+                user_id.unwrap_or(""),
+                channel.unwrap_or(""),
+                enabled
+            ],
+        )?;
+
+        tracing::info!(
+            skill_id = %skill_id,
+            user_id = ?user_id,
+            channel = ?channel,
+            enabled = %enabled,
+            "skill scope changed"
+        );
+
+        Ok(true)
+    }
+
+    /// Remove a scoped override, falling back to the next broader scope
+    ///
+    /// Returns false if no such override existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn clear_scope(
+        &self,
+        skill_id: &str,
+        user_id: Option<&str>,
+        channel: Option<&str>,
+    ) -> Result<bool> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let rows = conn.execute(
+            "DELETE FROM skill_scopes WHERE skill_id = ?1 AND user_id = ?2 AND channel = ?3",
+            rusqlite::params![skill_id, user_id.unwrap_or(""), channel.unwrap_or("")],
+        )?;
+
+        Ok(rows > 0)
+    }
+
     /// List all existing command names (for deduplication)
     fn list_command_names(&self) -> Result<Vec<String>> {
         let conn = self
@@ -824,6 +955,7 @@ mod tests {
             package: None,
             module: None,
             url: None,
+            rev: None,
             archive: None,
             strip_components: None,
             target_dir: None,
@@ -847,4 +979,39 @@ mod tests {
             vec!["voice.enabled"]
         );
     }
+
+    #[test]
+    fn test_scoped_enablement_precedence() {
+        let pool = init_memory().unwrap();
+        let repo = SkillRepo::new(pool);
+        let id = repo.install(&test_skill()).unwrap().skill.id;
+        let visible = |user: Option<&str>, channel: Option<&str>| {
+            !repo
+                .list_enabled_for_user(user, channel)
+                .unwrap()
+                .is_empty()
+        };
+
+        // Global off, enabled for one channel, disabled for one user,
+        // re-enabled for that user in that channel
+        assert!(repo.set_scope(&id, None, None, false).unwrap());
+        assert!(repo.set_scope(&id, None, Some("slack"), true).unwrap());
+        assert!(repo.set_scope(&id, Some("u1"), None, false).unwrap());
+        assert!(
+            repo.set_scope(&id, Some("u1"), Some("slack"), true)
+                .unwrap()
+        );
+
+        assert!(!visible(None, None));
+        assert!(visible(None, Some("slack")));
+        assert!(visible(Some("u2"), Some("slack")));
+        assert!(visible(Some("u1"), Some("slack")));
+        assert!(!visible(Some("u1"), Some("discord")));
+
+        // Clearing the narrowest scope falls back to the user scope
+        assert!(repo.clear_scope(&id, Some("u1"), Some("slack")).unwrap());
+        assert!(!visible(Some("u1"), Some("slack")));
+
+        assert!(!repo.set_scope("missing", Some("u1"), None, true).unwrap());
+    }
 }
@@ -0,0 +1,208 @@
+//! Memory sync cursor and conflict repository
+
+use super::DbPool;
+use crate::sync::MergeConflict;
+use crate::{Error, Result};
+
+/// Repository for the server cursor of each sync API and unresolved conflicts
+#[derive(Debug, Clone)]
+pub struct SyncStateRepo {
+    pool: DbPool,
+}
+
+impl SyncStateRepo {
+    /// Create a new repository
+    #[must_use]
+    pub const fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Cursor of the last successful pull from `api_url`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn cursor(&self, api_url: &str) -> Result<Option<String>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let result = conn.query_row(
+            "SELECT cursor FROM sync_state WHERE api_url = ?1",
+            [api_url],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(cursor) => Ok(Some(cursor)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Store the cursor of a successful pull
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn set_cursor(&self, api_url: &str, cursor: &str) -> Result<()> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        conn.execute(
+            r"INSERT INTO sync_state (api_url, cursor, updated_at)
+              VALUES (?1, ?2, datetime('now'))
+              ON CONFLICT(api_url) DO UPDATE SET
+                cursor = excluded.cursor,
+                updated_at = datetime('now')",
+            [api_url, cursor],
+        )?;
+
+        Ok(())
+    }
+
+    /// Forget the cursor so the next sync starts from scratch
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn clear_cursor(&self, api_url: &str) -> Result<()> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        conn.execute("DELETE FROM sync_state WHERE api_url = ?1", [api_url])?;
+
+        Ok(())
+    }
+
+    /// Replace the unresolved conflicts of one memory with the latest merge's
+    ///
+    /// An empty `conflicts` clears the memory's entries, since its copies agree.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn replace_conflicts(&self, memory_id: &str, conflicts: &[MergeConflict]) -> Result<()> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute(
+            "DELETE FROM sync_conflicts WHERE memory_id = ?1",
+            [memory_id],
+        )?;
+        for conflict in conflicts {
+            tx.execute(
+                r"INSERT INTO sync_conflicts (memory_id, field, local_value, remote_value)
+                  VALUES (?1, ?2, ?3, ?4)",
+                [
+                    memory_id,
+                    conflict.field.as_str(),
+                    conflict.local.as_str(),
+                    conflict.remote.as_str(),
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// All unresolved conflicts, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn conflicts(&self) -> Result<Vec<MergeConflict>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut stmt = conn.prepare(
+            r"SELECT memory_id, field, local_value, remote_value FROM sync_conflicts
+              ORDER BY detected_at, memory_id, field",
+        )?;
+        let conflicts = stmt
+            .query_map([], |row| {
+                Ok(MergeConflict {
+                    memory_id: row.get(0)?,
+                    field: row.get(1)?,
+                    local: row.get(2)?,
+                    remote: row.get(3)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(conflicts)
+    }
+
+    /// Mark a memory's conflicts resolved, returning how many were cleared
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn resolve_conflicts(&self, memory_id: &str) -> Result<usize> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(conn.execute(
+            "DELETE FROM sync_conflicts WHERE memory_id = ?1",
+            [memory_id],
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_memory;
+
+    #[test]
+    fn cursor_round_trip() {
+        let repo = SyncStateRepo::new(init_memory().unwrap());
+        let api = "https://api.example.com";
+
+        assert_eq!(repo.cursor(api).unwrap(), None);
+        repo.set_cursor(api, "c1").unwrap();
+        repo.set_cursor(api, "c2").unwrap();
+        assert_eq!(repo.cursor(api).unwrap().as_deref(), Some("c2"));
+        assert_eq!(repo.cursor("https://other.example.com").unwrap(), None);
+
+        repo.clear_cursor(api).unwrap();
+        assert_eq!(repo.cursor(api).unwrap(), None);
+    }
+
+    #[test]
+    fn conflicts_persist_until_resolved() {
+        let repo = SyncStateRepo::new(init_memory().unwrap());
+        let conflict = |memory_id: &str, field: &str| MergeConflict {
+            memory_id: memory_id.to_string(),
+            field: field.to_string(),
+            local: "tea".to_string(),
+            remote: "coffee".to_string(),
+        };
+
+        repo.replace_conflicts("m1", &[conflict("m1", "content"), conflict("m1", "tags")])
+            .unwrap();
+        repo.replace_conflicts("m2", &[conflict("m2", "content")])
+            .unwrap();
+        assert_eq!(repo.conflicts().unwrap().len(), 3);
+
+        // A later merge where the copies agree clears that memory only
+        repo.replace_conflicts("m1", &[]).unwrap();
+        assert_eq!(repo.conflicts().unwrap(), vec![conflict("m2", "content")]);
+
+        assert_eq!(repo.resolve_conflicts("m2").unwrap(), 1);
+        assert!(repo.conflicts().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+//! Local usage ledger
+//!
+//! Tallies LLM tokens per user per UTC day and stores per-user daily cap
+//! overrides. Independent of Aether billing so self-hosted gateways can
+//! enforce simple daily limits.
+
+use super::DbPool;
+use crate::{Error, Result};
+
+/// Per-user daily cap override
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UsageLimit {
+    /// Daily token cap (`None` = unlimited for this user)
+    pub daily_tokens: Option<u64>,
+}
+
+/// Usage ledger repository
+#[derive(Debug, Clone)]
+pub struct UsageRepo {
+    pool: DbPool,
+}
+
+impl UsageRepo {
+    /// Create a new usage repository
+    #[must_use]
+    pub const fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Add tokens to a user's tally for `day` (`YYYY-MM-DD`)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn add_tokens(&self, user_id: &str, day: &str, tokens: u64) -> Result<()> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let tokens = i64::try_from(tokens).unwrap_or(i64::MAX);
+        conn.execute(
+            "INSERT INTO usage_daily (user_id, day, tokens) VALUES (?1, ?2, ?3)
+             ON CONFLICT(user_id, day) DO UPDATE SET tokens = tokens + excluded.tokens",
+            rusqlite::params![user_id, day, tokens],
+        )?;
+
+        Ok(())
+    }
+
+    /// Tokens used by a user on `day` (`YYYY-MM-DD`)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn tokens_on(&self, user_id: &str, day: &str) -> Result<u64> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let tokens: Option<i64> = conn
+            .query_row(
+                "SELECT tokens FROM usage_daily WHERE user_id = ?1 AND day = ?2",
+                [user_id, day],
+                |row| row.get(0),
+            )
+            .ok();
+
+        Ok(tokens.map_or(0, |t| u64::try_from(t).unwrap_or(0)))
+    }
+
+    /// Get a user's cap override, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn get_limit(&self, user_id: &str) -> Result<Option<UsageLimit>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let limit = conn
+            .query_row(
+                "SELECT daily_tokens FROM usage_limits WHERE user_id = ?1",
+                [user_id],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .ok()
+            .map(|daily_tokens| UsageLimit {
+                daily_tokens: daily_tokens.map(|t| u64::try_from(t).unwrap_or(0)),
+            });
+
+        Ok(limit)
+    }
+
+    /// Set a user's cap override (replaces any existing one)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn set_limit(&self, user_id: &str, limit: UsageLimit) -> Result<()> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let daily_tokens = limit
+            .daily_tokens
+            .map(|t| i64::try_from(t).unwrap_or(i64::MAX));
+        conn.execute(
+            "INSERT INTO usage_limits (user_id, daily_tokens, updated_at)
+             VALUES (?1, ?2, datetime('now'))
+             ON CONFLICT(user_id) DO UPDATE SET
+                daily_tokens = excluded.daily_tokens,
+                updated_at = excluded.updated_at",
+            rusqlite::params![user_id, daily_tokens],
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove a user's cap override
+    ///
+    /// Returns whether an override existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn delete_limit(&self, user_id: &str) -> Result<bool> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let rows = conn.execute("DELETE FROM usage_limits WHERE user_id = ?1", [user_id])?;
+        Ok(rows > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    #[test]
+    fn tallies_per_user_per_day() {
+        let repo = UsageRepo::new(db::init_memory().unwrap());
+
+        repo.add_tokens("u1", "2026-01-01", 100).unwrap();
+        repo.add_tokens("u1", "2026-01-01", 50).unwrap();
+        repo.add_tokens("u1", "2026-01-02", 7).unwrap();
+        repo.add_tokens("u2", "2026-01-01", 1).unwrap();
+
+        assert_eq!(repo.tokens_on("u1", "2026-01-01").unwrap(), 150);
+        assert_eq!(repo.tokens_on("u1", "2026-01-02").unwrap(), 7);
+        assert_eq!(repo.tokens_on("u3", "2026-01-01").unwrap(), 0);
+    }
+
+    #[test]
+    fn limit_overrides_round_trip() {
+        let repo = UsageRepo::new(db::init_memory().unwrap());
+        assert_eq!(repo.get_limit("u1").unwrap(), None);
+
+        let limit = UsageLimit {
+            daily_tokens: Some(500),
+        };
+        repo.set_limit("u1", limit).unwrap();
+        assert_eq!(repo.get_limit("u1").unwrap(), Some(limit));
+
+        let unlimited = UsageLimit { daily_tokens: None };
+        repo.set_limit("u1", unlimited).unwrap();
+        assert_eq!(repo.get_limit("u1").unwrap(), Some(unlimited));
+
+        assert!(repo.delete_limit("u1").unwrap());
+        assert!(!repo.delete_limit("u1").unwrap());
+    }
+}
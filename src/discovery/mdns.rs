@@ -12,25 +12,160 @@
 //! - `persona`: Active persona ID
 //! - `voice`: Whether voice is supported ("true"/"false")
 //! - `tls`: Whether TLS is enabled ("true"/"false")
+//!
+//! The advertised address follows the host's primary interface: when it
+//! changes (Wi-Fi roam, new DHCP lease) the record is withdrawn and
+//! re-advertised with the new address.
+//!
+//! [`browse`] finds other gateways advertising the same service type.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, UdpSocket};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use mdns_sd::{ServiceDaemon, ServiceInfo};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo, UnregisterStatus};
+use serde::Serialize;
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 use crate::Result;
 
 /// mDNS service type for beacon gateway
 pub const SERVICE_TYPE: &str = "_beacon-gateway._tcp.local.";
 
+/// How often the monitor checks for a changed host address
+pub const ADDRESS_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long de-registration waits for the goodbye announcement to go out
+const UNREGISTER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long [`browse`] listens for gateways by default
+pub const DEFAULT_BROWSE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A gateway found on the local network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiscoveredGateway {
+    /// Instance name (`{persona}-{device_id_short}`)
+    pub name: String,
+    /// mDNS hostname (e.g. `studio.local.`)
+    pub hostname: String,
+    /// Addresses the gateway answered from, sorted
+    pub addresses: Vec<IpAddr>,
+    /// HTTP API port
+    pub port: u16,
+    pub device_id: Option<String>,
+    pub persona: Option<String>,
+    pub version: Option<String>,
+    pub voice: bool,
+    pub tls: bool,
+}
+
+impl DiscoveredGateway {
+    fn from_service(info: &ServiceInfo) -> Self {
+        let txt = |key: &str| info.get_property_val_str(key).map(ToString::to_string);
+        let fullname = info.get_fullname();
+        let mut addresses: Vec<IpAddr> = info.get_addresses().iter().copied().collect();
+        addresses.sort_unstable();
+
+        Self {
+            name: fullname
+                .strip_suffix(SERVICE_TYPE)
+                .and_then(|n| n.strip_suffix('.'))
+                .unwrap_or(fullname)
+                .to_string(),
+            hostname: info.get_hostname().to_string(),
+            addresses,
+            port: info.get_port(),
+            device_id: txt("device_id"),
+            persona: txt("persona"),
+            version: txt("version"),
+            voice: txt("voice").as_deref() == Some("true"),
+            tls: txt("tls").as_deref() == Some("true"),
+        }
+    }
+
+    /// Fold in a later resolution of the same instance
+    fn merge(&mut self, other: Self) {
+        let mut addresses = std::mem::take(&mut self.addresses);
+        addresses.extend(other.addresses);
+        addresses.sort_unstable();
+        addresses.dedup();
+        *self = Self { addresses, ..other };
+    }
+}
+
+/// Find gateways advertising on the local network
+///
+/// Listens for `timeout`, then returns each instance once, sorted by name.
+/// The result includes this gateway if it is advertising; filter by
+/// `device_id` to exclude it.
+///
+/// # Errors
+///
+/// Returns error if the mDNS daemon cannot be created or browsing fails
+pub async fn browse(timeout: Duration) -> Result<Vec<DiscoveredGateway>> {
+    let daemon = ServiceDaemon::new()
+        .map_err(|e| crate::Error::Config(format!("failed to create mDNS daemon: {e}")))?;
+    let receiver = daemon
+        .browse(SERVICE_TYPE)
+        .map_err(|e| crate::Error::Config(format!("failed to browse mDNS: {e}")))?;
+
+    // The receiver is blocking, so collect on a blocking thread
+    let gateways = tokio::task::spawn_blocking(move || {
+        let deadline = Instant::now() + timeout;
+        let mut gateways: BTreeMap<String, DiscoveredGateway> = BTreeMap::new();
+        while let Ok(event) = receiver.recv_deadline(deadline) {
+            if let ServiceEvent::ServiceResolved(info) = event {
+                let gateway = DiscoveredGateway::from_service(&info);
+                match gateways.get_mut(&gateway.name) {
+                    Some(existing) => existing.merge(gateway),
+                    None => {
+                        gateways.insert(gateway.name.clone(), gateway);
+                    }
+                }
+            }
+        }
+        gateways.into_values().collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| crate::Error::Config(format!("mDNS browse task failed: {e}")))?;
+
+    if let Err(e) = daemon.shutdown() {
+        tracing::trace!(error = %e, "mDNS daemon shutdown error");
+    }
+    tracing::debug!(found = gateways.len(), "mDNS browse finished");
+
+    Ok(gateways)
+}
+
+/// What the gateway advertises, kept so it can be re-advertised
+#[derive(Debug, Clone)]
+struct ServiceParams {
+    persona_id: String,
+    device_id: String,
+    port: u16,
+    voice_enabled: bool,
+    tls_enabled: bool,
+}
+
+/// A registered service
+#[derive(Debug, Clone)]
+struct Registration {
+    fullname: String,
+    /// Address in the record, or None when left to the daemon
+    address: Option<IpAddr>,
+    params: ServiceParams,
+}
+
 /// mDNS advertiser for beacon gateway discovery
 pub struct MdnsAdvertiser {
     /// mDNS daemon
     daemon: ServiceDaemon,
 
     /// Currently registered service (if any)
-    registered_service: Arc<RwLock<Option<String>>>,
+    registration: RwLock<Option<Registration>>,
 }
 
 impl MdnsAdvertiser {
@@ -45,7 +180,7 @@ impl MdnsAdvertiser {
 
         Ok(Self {
             daemon,
-            registered_service: Arc::new(RwLock::new(None)),
+            registration: RwLock::new(None),
         })
     }
 
@@ -70,9 +205,93 @@ impl MdnsAdvertiser {
         voice_enabled: bool,
         tls_enabled: bool,
     ) -> Result<()> {
+        let params = ServiceParams {
+            persona_id: persona_id.to_string(),
+            device_id: device_id.to_string(),
+            port,
+            voice_enabled,
+            tls_enabled,
+        };
+        self.register(params, primary_address()).await
+    }
+
+    /// Re-advertise if the host's primary address has changed
+    ///
+    /// Returns whether the service was re-advertised. Does nothing while not
+    /// advertising.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the service cannot be registered at the new address
+    pub async fn refresh(&self) -> Result<bool> {
+        let Some(current) = self.registration.read().await.clone() else {
+            return Ok(false);
+        };
+        let address = primary_address();
+        if address == current.address {
+            return Ok(false);
+        }
+
+        tracing::info!(
+            old = ?current.address,
+            new = ?address,
+            "host address changed, re-advertising mDNS service"
+        );
+        self.unregister(&current.fullname).await;
+        self.register(current.params, address).await?;
+        Ok(true)
+    }
+
+    /// Re-advertise on address changes until `shutdown`, then de-register
+    #[must_use]
+    pub fn monitor(self: Arc<Self>, shutdown: CancellationToken) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(ADDRESS_CHECK_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately; the address was just read
+            interval.tick().await;
+
+            loop {
+                tokio::select! {
+                    () = shutdown.cancelled() => {
+                        self.stop().await;
+                        return;
+                    }
+                    _ = interval.tick() => {}
+                }
+
+                if let Err(e) = self.refresh().await {
+                    tracing::warn!(error = %e, "mDNS re-advertise failed");
+                }
+            }
+        })
+    }
+
+    /// Stop advertising the gateway
+    ///
+    /// Waits briefly for the goodbye announcement so clients drop the record
+    /// instead of waiting for it to expire.
+    pub async fn stop(&self) {
+        let registration = self.registration.write().await.take();
+        if let Some(registration) = registration {
+            self.unregister(&registration.fullname).await;
+        }
+    }
+
+    /// Check if currently advertising
+    pub async fn is_advertising(&self) -> bool {
+        self.registration.read().await.is_some()
+    }
+
+    /// Address in the advertised record, if advertising with a known address
+    pub async fn advertised_address(&self) -> Option<IpAddr> {
+        self.registration.read().await.as_ref()?.address
+    }
+
+    async fn register(&self, params: ServiceParams, address: Option<IpAddr>) -> Result<()> {
         // Build instance name: {persona}-{device_id_short}
-        let device_id_short = &device_id[..8.min(device_id.len())];
-        let instance_name = format!("{persona_id}-{device_id_short}");
+        let device_id_short = &params.device_id[..8.min(params.device_id.len())];
+        let instance_name = format!("{}-{device_id_short}", params.persona_id);
 
         // Get hostname
         let hostname = hostname::get().map_or_else(
@@ -83,20 +302,33 @@ impl MdnsAdvertiser {
         // Build TXT record properties
         let mut properties = HashMap::new();
         properties.insert("version".to_string(), env!("CARGO_PKG_VERSION").to_string());
-        properties.insert("device_id".to_string(), device_id.to_string());
-        properties.insert("persona".to_string(), persona_id.to_string());
-        properties.insert("voice".to_string(), voice_enabled.to_string());
-        properties.insert("tls".to_string(), tls_enabled.to_string());
+        properties.insert("device_id".to_string(), params.device_id.clone());
+        properties.insert("persona".to_string(), params.persona_id.clone());
+        properties.insert("voice".to_string(), params.voice_enabled.to_string());
+        properties.insert("tls".to_string(), params.tls_enabled.to_string());
 
-        // Create service info
-        let service = ServiceInfo::new(
-            SERVICE_TYPE,
-            &instance_name,
-            &format!("{hostname}.local."),
-            "",
-            port,
-            properties,
-        )
+        // Create service info, leaving addresses to the daemon when the
+        // primary address is unknown
+        let host = format!("{hostname}.local.");
+        let service = match address {
+            Some(ip) => ServiceInfo::new(
+                SERVICE_TYPE,
+                &instance_name,
+                &host,
+                ip,
+                params.port,
+                properties,
+            ),
+            None => ServiceInfo::new(
+                SERVICE_TYPE,
+                &instance_name,
+                &host,
+                "",
+                params.port,
+                properties,
+            )
+            .map(ServiceInfo::enable_addr_auto),
+        }
         .map_err(|e| crate::Error::Config(format!("failed to create service info: {e}")))?;
 
         // Get the full name before registering
@@ -107,51 +339,53 @@ impl MdnsAdvertiser {
             .register(service)
             .map_err(|e| crate::Error::Config(format!("failed to register mDNS service: {e}")))?;
 
-        // Store the registered service name
-        {
-            let mut registered = self.registered_service.write().await;
-            *registered = Some(fullname.clone());
-        }
-
         tracing::info!(
             service_type = SERVICE_TYPE,
             instance = instance_name,
-            port = port,
+            port = params.port,
+            address = ?address,
             "mDNS service registered"
         );
 
+        *self.registration.write().await = Some(Registration {
+            fullname,
+            address,
+            params,
+        });
+
         Ok(())
     }
 
-    /// Stop advertising the gateway
-    pub async fn stop(&self) {
-        let fullname = {
-            let mut registered = self.registered_service.write().await;
-            registered.take()
+    /// Withdraw a record, waiting for the goodbye announcement
+    async fn unregister(&self, fullname: &str) {
+        let receiver = match self.daemon.unregister(fullname) {
+            Ok(receiver) => receiver,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to unregister mDNS service");
+                return;
+            }
         };
 
-        if let Some(name) = fullname {
-            if let Err(e) = self.daemon.unregister(&name) {
-                tracing::warn!(error = %e, "failed to unregister mDNS service");
-            } else {
-                tracing::info!("mDNS service unregistered");
+        let status =
+            tokio::task::spawn_blocking(move || receiver.recv_timeout(UNREGISTER_TIMEOUT)).await;
+        match status {
+            Ok(Ok(UnregisterStatus::OK)) => tracing::info!("mDNS service unregistered"),
+            Ok(Ok(UnregisterStatus::NotFound)) => {
+                tracing::debug!("mDNS service was not registered");
             }
+            Ok(Err(e)) => tracing::warn!(error = %e, "mDNS unregister not confirmed"),
+            Err(e) => tracing::warn!(error = %e, "mDNS unregister task failed"),
         }
     }
-
-    /// Check if currently advertising
-    pub async fn is_advertising(&self) -> bool {
-        self.registered_service.read().await.is_some()
-    }
 }
 
 impl Drop for MdnsAdvertiser {
     fn drop(&mut self) {
         // Try to unregister on drop (best effort, synchronous)
-        if let Ok(guard) = self.registered_service.try_read()
-            && let Some(name) = guard.as_ref()
+        if let Ok(guard) = self.registration.try_read()
+            && let Some(registration) = guard.as_ref()
         {
-            let _ = self.daemon.unregister(name);
+            let _ = self.daemon.unregister(&registration.fullname);
         }
         // Shutdown the daemon
         if let Err(e) = self.daemon.shutdown() {
@@ -160,6 +394,20 @@ impl Drop for MdnsAdvertiser {
     }
 }
 
+/// Address of the interface carrying the default route
+///
+/// Connecting a UDP socket only selects a route; no packets are sent.
+fn primary_address() -> Option<IpAddr> {
+    [("0.0.0.0:0", "192.0.2.1:9"), ("[::]:0", "[2001:db8::1]:9")]
+        .into_iter()
+        .find_map(|(bind, target)| {
+            let socket = UdpSocket::bind(bind).ok()?;
+            socket.connect(target).ok()?;
+            let ip = socket.local_addr().ok()?.ip();
+            (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,6 +432,69 @@ mod tests {
         // On others (like CI), it might fail - that's OK
         if let Ok(advertiser) = result {
             assert!(!advertiser.is_advertising().await);
+            assert_eq!(advertiser.advertised_address().await, None);
+            // Nothing to refresh before advertising starts
+            assert!(!advertiser.refresh().await.unwrap());
+        }
+    }
+
+    fn service(ip: &str, persona: &str) -> ServiceInfo {
+        let properties = HashMap::from([
+            ("persona".to_string(), persona.to_string()),
+            ("version".to_string(), "1.2.3".to_string()),
+            ("voice".to_string(), "true".to_string()),
+        ]);
+        ServiceInfo::new(
+            SERVICE_TYPE,
+            "orin-abcd1234",
+            "studio.local.",
+            ip,
+            18790,
+            properties,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_discovered_gateway_from_service() {
+        let gateway = DiscoveredGateway::from_service(&service("192.168.1.5", "orin"));
+
+        assert_eq!(gateway.name, "orin-abcd1234");
+        assert_eq!(gateway.hostname, "studio.local.");
+        assert_eq!(
+            gateway.addresses,
+            vec!["192.168.1.5".parse::<IpAddr>().unwrap()]
+        );
+        assert_eq!(gateway.port, 18790);
+        assert_eq!(gateway.persona.as_deref(), Some("orin"));
+        assert_eq!(gateway.version.as_deref(), Some("1.2.3"));
+        assert!(gateway.voice);
+        assert!(!gateway.tls);
+        assert_eq!(gateway.device_id, None);
+    }
+
+    #[test]
+    fn test_discovered_gateway_merge_unions_addresses() {
+        let mut gateway = DiscoveredGateway::from_service(&service("192.168.1.5", "orin"));
+        gateway.merge(DiscoveredGateway::from_service(&service(
+            "10.0.0.7", "sage",
+        )));
+        gateway.merge(DiscoveredGateway::from_service(&service(
+            "192.168.1.5",
+            "sage",
+        )));
+
+        assert_eq!(gateway.addresses.len(), 2);
+        // Later resolutions carry the current TXT metadata
+        assert_eq!(gateway.persona.as_deref(), Some("sage"));
+    }
+
+    #[test]
+    fn test_primary_address_is_routable() {
+        // Hosts without a default route have no primary address
+        if let Some(ip) = primary_address() {
+            assert!(!ip.is_loopback());
+            assert!(!ip.is_unspecified());
         }
     }
 }
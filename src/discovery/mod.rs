@@ -1,8 +1,9 @@
 //! Service discovery using mDNS/DNS-SD
 //!
 //! Advertises the beacon gateway on the local network so clients can
-//! discover it without manual configuration
+//! discover it without manual configuration, and finds peer gateways for
+//! node relay
 
 pub mod mdns;
 
-pub use mdns::MdnsAdvertiser;
+pub use mdns::{DiscoveredGateway, MdnsAdvertiser, browse};
@@ -1,5 +1,8 @@
 //! Error types for Beacon gateway
 
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
 use thiserror::Error;
 
 /// Result type alias for Beacon operations
@@ -100,10 +103,26 @@ pub enum Error {
     #[error("vault error: {0}")]
     Vault(String),
 
+    /// Encryption or decryption error
+    #[error("encryption error: {0}")]
+    Encryption(String),
+
     /// Attachment processing error
     #[error("attachment error: {0}")]
     Attachment(String),
 
+    /// Attachment over its size limit
+    #[error("attachment too large: {0}")]
+    AttachmentTooLarge(String),
+
+    /// Rate limit or queue capacity exceeded
+    #[error("rate limited: {0}")]
+    RateLimited(String),
+
+    /// Upstream service is temporarily unavailable (e.g. circuit breaker open)
+    #[error("service unavailable: {0}")]
+    Unavailable(String),
+
     /// Vision API error
     #[error("vision error: {0}")]
     Vision(String),
@@ -120,9 +139,135 @@ pub enum Error {
     #[error("tool error: {0}")]
     Tool(String),
 
+    /// Tool call denied by the tool policy
+    #[error("tool not permitted: {0}")]
+    ToolNotPermitted(String),
+
     /// Skill install automation error
     #[error("install error: {0}")]
     Install(String),
+
+    /// Extension loading error
+    #[error("extension error: {0}")]
+    Extension(String),
+}
+
+impl Error {
+    /// HTTP status an API handler should answer with for this error
+    #[must_use]
+    pub const fn status_code(&self) -> StatusCode {
+        match self {
+            Self::PersonaNotFound(_) | Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Auth(_) => StatusCode::UNAUTHORIZED,
+            Self::ToolNotPermitted(_) => StatusCode::FORBIDDEN,
+            Self::AttachmentTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Attachment(_) | Self::Media(_) | Self::Link(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Self::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            // Failures of an upstream service rather than of the gateway
+            Self::Http(_)
+            | Self::Agent(_)
+            | Self::Channel(_)
+            | Self::Manifold(_)
+            | Self::WebFetch(_)
+            | Self::Browser(_)
+            | Self::Stt(_)
+            | Self::Tts(_)
+            | Self::Vision(_)
+            | Self::Embedding(_) => StatusCode::BAD_GATEWAY,
+            Self::Config(_)
+            | Self::Voice(_)
+            | Self::Audio(_)
+            | Self::WakeWord(_)
+            | Self::Io(_)
+            | Self::Serialization(_)
+            | Self::Toml(_)
+            | Self::Database(_)
+            | Self::Sqlite(_)
+            | Self::Skill(_)
+            | Self::Vault(_)
+            | Self::Encryption(_)
+            | Self::Tool(_)
+            | Self::Install(_)
+            | Self::Extension(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Machine-readable code used in API error bodies
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Config(_) => "config_error",
+            Self::PersonaNotFound(_) => "persona_not_found",
+            Self::Voice(_) => "voice_error",
+            Self::Audio(_) => "audio_error",
+            Self::Stt(_) => "stt_error",
+            Self::Tts(_) => "tts_error",
+            Self::WakeWord(_) => "wake_word_error",
+            Self::Channel(_) => "channel_error",
+            Self::Browser(_) => "browser_error",
+            Self::WebFetch(_) => "web_fetch_error",
+            Self::Agent(_) => "agent_error",
+            Self::Io(_) => "io_error",
+            Self::Http(_) => "http_error",
+            Self::Serialization(_) => "serialization_error",
+            Self::Toml(_) => "toml_error",
+            Self::Database(_) | Self::Sqlite(_) => "db_error",
+            Self::Skill(_) => "skill_error",
+            Self::Manifold(_) => "manifold_error",
+            Self::NotFound(_) => "not_found",
+            Self::Embedding(_) => "embedding_error",
+            Self::Auth(_) => "unauthorized",
+            Self::Vault(_) => "vault_error",
+            Self::Encryption(_) => "encryption_error",
+            Self::Attachment(_) => "attachment_error",
+            Self::AttachmentTooLarge(_) => "attachment_too_large",
+            Self::RateLimited(_) => "rate_limited",
+            Self::Unavailable(_) => "unavailable",
+            Self::Vision(_) => "vision_error",
+            Self::Media(_) => "media_error",
+            Self::Link(_) => "link_error",
+            Self::Tool(_) => "tool_error",
+            Self::ToolNotPermitted(_) => "tool_not_permitted",
+            Self::Install(_) => "install_error",
+            Self::Extension(_) => "extension_error",
+        }
+    }
+}
+
+/// Lets API handlers return `crate::Result` and `?` errors directly
+///
+/// The body has the same `{"error": {"code", "message"}}` shape the handlers
+/// build by hand, plus the request id when one is in scope.
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        if status.is_server_error() {
+            tracing::error!(error = %self, "request failed");
+        }
+
+        let mut body = serde_json::json!({
+            "error": {
+                "code": self.code(),
+                "message": self.to_string(),
+            }
+        });
+        if let Some(request_id) = crate::request_id::current() {
+            body["error"]["request_id"] = request_id.into();
+        }
+        (status, Json(body)).into_response()
+    }
+}
+
+impl From<crate::integrations::VortexError> for Error {
+    fn from(e: crate::integrations::VortexError) -> Self {
+        match e {
+            crate::integrations::VortexError::NotFound(_) => Self::NotFound(e.to_string()),
+            _ => Self::Channel(e.to_string()),
+        }
+    }
 }
 
 impl From<agent_core::knowledge::EmbedderError> for Error {
@@ -136,3 +281,37 @@ impl From<agent_core::tools::browser::BrowserError> for Error {
         Self::Browser(e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn errors_map_to_status_and_json_body() {
+        assert_eq!(
+            Error::PersonaNotFound("orion".into()).status_code(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            Error::RateLimited("queue full".into()).status_code(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(
+            Error::Channel("discord down".into()).status_code(),
+            StatusCode::BAD_GATEWAY
+        );
+
+        let response = Error::AttachmentTooLarge("Image over 5 bytes".into()).into_response();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "attachment_too_large");
+        assert_eq!(
+            body["error"]["message"],
+            "attachment too large: Image over 5 bytes"
+        );
+    }
+}
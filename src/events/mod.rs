@@ -2,14 +2,20 @@
 //!
 //! Publishes lifecycle events to the `omni-events` Iggy stream.
 //! Publishing is best-effort — errors are logged and never propagate to callers.
+//! Events are buffered per organization topic and delivered in batches, either
+//! every [`FLUSH_INTERVAL`] or as soon as a topic holds [`MAX_BATCH_SIZE`] events.
 //!
 //! Initialize once at startup with [`init_publisher`], then call [`publish`] anywhere.
+//! Call [`flush`] during graceful shutdown to deliver anything still buffered.
 
-use std::sync::OnceLock;
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::Duration;
 
 use base64::Engine as _;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
+use tokio_util::task::TaskTracker;
 use uuid::Uuid;
 
 /// Iggy HTTP REST API default port
@@ -21,9 +27,42 @@ const STREAM_NAME: &str = "omni-events";
 /// Number of partitions per organization topic
 const TOPIC_PARTITIONS: u32 = 3;
 
+/// How often buffered events are flushed to Iggy
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Number of buffered events for one topic that triggers an immediate flush
+pub const MAX_BATCH_SIZE: usize = 50;
+
+/// Default number of retries after a failed publish attempt
+const DEFAULT_PUBLISH_RETRIES: u32 = 3;
+
+/// Backoff before the first retry; doubles on each subsequent attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+
+/// Upper bound on the backoff between retries
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
 /// 90-day message retention in seconds
 const RETENTION_SECS: u64 = 90 * 24 * 60 * 60;
 
+/// Maximum length of the tool arguments summary in `beacon.tool.executed`
+const ARGS_SUMMARY_MAX_CHARS: usize = 60;
+
+/// Argument names whose values are never included in event summaries
+const SENSITIVE_ARG_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "authorization",
+    "auth",
+    "cookie",
+    "credential",
+    "private_key",
+];
+
 /// Global publisher configuration
 static CONFIG: OnceLock<EventsConfig> = OnceLock::new();
 
@@ -34,9 +73,29 @@ fn token_cache() -> &'static RwLock<Option<String>> {
     TOKEN_CACHE.get_or_init(|| RwLock::new(None))
 }
 
+/// Events awaiting delivery
+static BUFFER: OnceLock<Mutex<EventBuffer>> = OnceLock::new();
+
+fn buffer() -> &'static Mutex<EventBuffer> {
+    BUFFER.get_or_init(|| Mutex::new(EventBuffer::default()))
+}
+
+/// Batches being delivered, so [`flush`] can wait for them
+static IN_FLIGHT: LazyLock<TaskTracker> = LazyLock::new(TaskTracker::new);
+
+/// Topics known to exist in Iggy, so batches skip the stream/topic checks
+static PROVISIONED_TOPICS: OnceLock<RwLock<HashSet<String>>> = OnceLock::new();
+
+fn provisioned_topics() -> &'static RwLock<HashSet<String>> {
+    PROVISIONED_TOPICS.get_or_init(|| RwLock::new(HashSet::new()))
+}
+
 /// Retrieve the cached token, authenticating if the cache is empty.
 #[allow(clippy::significant_drop_tightening)]
-async fn cached_token(client: &reqwest::Client, config: &EventsConfig) -> anyhow::Result<String> {
+async fn cached_token(
+    client: &reqwest::Client,
+    config: &EventsConfig,
+) -> Result<String, PublishError> {
     // Fast path: read-lock
     {
         let r = token_cache().read().await;
@@ -61,18 +120,30 @@ async fn invalidate_token() {
 }
 
 /// Authenticate against the Iggy HTTP API and return a bearer token.
-async fn login(client: &reqwest::Client, config: &EventsConfig) -> anyhow::Result<String> {
-    let resp: LoginResponse = client
+///
+/// Rejected credentials are reported as fatal: retrying them cannot succeed.
+async fn login(client: &reqwest::Client, config: &EventsConfig) -> Result<String, PublishError> {
+    let resp = client
         .post(format!("{}/users/login", config.base_url))
         .json(&LoginRequest {
             username: &config.username,
             password: &config.password,
         })
         .send()
-        .await?
-        .error_for_status()?
-        .json()
-        .await?;
+        .await
+        .map_err(PublishError::from_reqwest)?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        let err = anyhow::anyhow!("Iggy login failed: {status} - {body}");
+        return Err(match PublishError::from_status(status, err) {
+            PublishError::Unauthorized(e) => PublishError::Fatal(e),
+            other => other,
+        });
+    }
+
+    let resp: LoginResponse = resp.json().await.map_err(PublishError::from_reqwest)?;
     Ok(resp.tokens.access.token)
 }
 
@@ -85,13 +156,16 @@ pub struct EventsConfig {
     username: String,
     /// Iggy password
     password: String,
+    /// Retries after a failed publish attempt before the event is dropped
+    publish_retries: u32,
 }
 
 impl EventsConfig {
     /// Load configuration from environment variables.
     ///
     /// Reads `IGGY_HOST` (default: `localhost`), `IGGY_HTTP_PORT` (default: `3000`),
-    /// `IGGY_USERNAME` (default: `iggy`), and `IGGY_PASSWORD` (default: `iggy`).
+    /// `IGGY_USERNAME` (default: `iggy`), `IGGY_PASSWORD` (default: `iggy`), and
+    /// `IGGY_PUBLISH_RETRIES` (default: `3`).
     #[must_use]
     pub fn from_env() -> Self {
         let host = std::env::var("IGGY_HOST").unwrap_or_else(|_| "localhost".to_string());
@@ -103,6 +177,10 @@ impl EventsConfig {
             base_url: format!("http://{host}:{port}"),
             username: std::env::var("IGGY_USERNAME").unwrap_or_else(|_| "iggy".to_string()),
             password: std::env::var("IGGY_PASSWORD").unwrap_or_else(|_| "iggy".to_string()),
+            publish_retries: std::env::var("IGGY_PUBLISH_RETRIES")
+                .ok()
+                .and_then(|s| s.parse::<u32>().ok())
+                .unwrap_or(DEFAULT_PUBLISH_RETRIES),
         }
     }
 }
@@ -128,6 +206,9 @@ pub struct OmniEvent {
     pub timestamp: String,
     /// Organization ID — used as the Iggy topic name
     pub organization_id: String,
+    /// Request id of the turn that produced the event, for log correlation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl OmniEvent {
@@ -142,6 +223,7 @@ impl OmniEvent {
             data,
             timestamp: chrono::Utc::now().to_rfc3339(),
             organization_id: organization_id.to_string(),
+            request_id: crate::request_id::current(),
         }
     }
 
@@ -201,6 +283,23 @@ pub fn build_conversation_ended_event(
     .with_subject(session_id)
 }
 
+/// Build a `beacon.gateway.stopped` event.
+///
+/// # Arguments
+///
+/// - `organization_id` - Organization/user scoping identifier
+/// - `drained` - Whether in-flight replies finished before the drain timeout
+#[must_use]
+pub fn build_gateway_stopped_event(organization_id: &str, drained: bool) -> OmniEvent {
+    OmniEvent::new(
+        "beacon.gateway.stopped",
+        organization_id,
+        serde_json::json!({
+            "drained": drained,
+        }),
+    )
+}
+
 /// Build a `beacon.tool.executed` event.
 ///
 /// # Arguments
@@ -209,57 +308,273 @@ pub fn build_conversation_ended_event(
 /// - `tool_name` - Name of the tool that was executed
 /// - `success` - Whether the tool execution succeeded
 /// - `organization_id` - Organization/user scoping identifier
+/// - `duration_ms` - Wall-clock execution time in milliseconds
+/// - `arguments` - Raw JSON arguments; only a redacted, truncated summary is emitted
+///
+/// Plugin tools (`plugin_id::tool_name`) are tagged with `pluginId`.
 #[must_use]
 pub fn build_tool_executed_event(
     session_id: &str,
     tool_name: &str,
     success: bool,
     organization_id: &str,
+    duration_ms: u64,
+    arguments: &str,
+) -> OmniEvent {
+    let mut data = serde_json::json!({
+        "conversationId": session_id,
+        "toolName": tool_name,
+        "success": success,
+        "durationMs": duration_ms,
+        "argsSummary": summarize_tool_arguments(tool_name, arguments),
+    });
+    if let Some((plugin_id, _)) = tool_name.split_once("::") {
+        data["pluginId"] = serde_json::Value::String(plugin_id.to_string());
+    }
+
+    OmniEvent::new("beacon.tool.executed", organization_id, data).with_subject(session_id)
+}
+
+/// Build a `beacon.tool.denied` event.
+///
+/// # Arguments
+///
+/// - `session_id` - Session identifier for the conversation in which the call was made
+/// - `tool_name` - Name of the tool that was denied
+/// - `organization_id` - Organization/user scoping identifier
+/// - `channel` - Channel whose tool policy denied the call
+/// - `arguments` - Raw JSON arguments; only a redacted, truncated summary is emitted
+#[must_use]
+pub fn build_tool_denied_event(
+    session_id: &str,
+    tool_name: &str,
+    organization_id: &str,
+    channel: &str,
+    arguments: &str,
 ) -> OmniEvent {
     OmniEvent::new(
-        "beacon.tool.executed",
+        "beacon.tool.denied",
         organization_id,
         serde_json::json!({
             "conversationId": session_id,
             "toolName": tool_name,
-            "success": success,
+            "channel": channel,
+            "argsSummary": summarize_tool_arguments(tool_name, arguments),
         }),
     )
     .with_subject(session_id)
 }
 
+/// Build a `beacon.security.pairing_blocked` event.
+///
+/// Scoped to the sender, like conversation events, since pairing happens
+/// before the sender has a session.
+///
+/// # Arguments
+///
+/// - `sender_id` - Platform sender that was blocked
+/// - `channel` - Channel the pairing attempts came from
+/// - `failures` - Failed attempts that triggered the block
+/// - `locked_until` - When the sender may try again
+#[must_use]
+pub fn build_pairing_blocked_event(
+    sender_id: &str,
+    channel: &str,
+    failures: u32,
+    locked_until: &chrono::DateTime<chrono::Utc>,
+) -> OmniEvent {
+    OmniEvent::new(
+        "beacon.security.pairing_blocked",
+        sender_id,
+        serde_json::json!({
+            "senderId": sender_id,
+            "channel": channel,
+            "failedAttempts": failures,
+            "lockedUntil": locked_until.to_rfc3339(),
+        }),
+    )
+    .with_subject(sender_id)
+}
+
+/// Build a `beacon.session.compacted` event.
+///
+/// # Arguments
+///
+/// - `session_id` - Session whose history was summarized (used as subject)
+/// - `organization_id` - Organization/user scoping identifier
+/// - `tokens_before` - Estimated history tokens before compaction
+/// - `tokens_after` - Estimated history tokens after compaction
+/// - `messages_removed` - Messages replaced by the summary
+#[must_use]
+pub fn build_session_compacted_event(
+    session_id: &str,
+    organization_id: &str,
+    tokens_before: usize,
+    tokens_after: usize,
+    messages_removed: usize,
+) -> OmniEvent {
+    OmniEvent::new(
+        "beacon.session.compacted",
+        organization_id,
+        serde_json::json!({
+            "conversationId": session_id,
+            "tokensBefore": tokens_before,
+            "tokensAfter": tokens_after,
+            "messagesRemoved": messages_removed,
+        }),
+    )
+    .with_subject(session_id)
+}
+
+/// Short, redacted summary of tool arguments for event payloads
+///
+/// Values of sensitive-looking keys are masked before the
+/// [`format_invocation`](crate::tools::format_invocation) summary is taken,
+/// and the result is capped at [`ARGS_SUMMARY_MAX_CHARS`].
+#[must_use]
+pub fn summarize_tool_arguments(tool_name: &str, arguments: &str) -> String {
+    let redacted = serde_json::from_str::<serde_json::Value>(arguments).map_or_else(
+        |_| String::new(),
+        |mut args| {
+            redact_sensitive(&mut args);
+            args.to_string()
+        },
+    );
+    let summary = crate::tools::format_invocation(tool_name, &redacted);
+
+    if summary.chars().count() > ARGS_SUMMARY_MAX_CHARS {
+        let mut truncated: String = summary.chars().take(ARGS_SUMMARY_MAX_CHARS - 1).collect();
+        truncated.push('\u{2026}');
+        truncated
+    } else {
+        summary
+    }
+}
+
+/// Replace values of sensitive keys with a placeholder (recursively)
+fn redact_sensitive(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, val) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if SENSITIVE_ARG_KEYS.iter().any(|s| key.contains(s)) {
+                    *val = serde_json::Value::String("[redacted]".to_string());
+                } else {
+                    redact_sensitive(val);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_sensitive),
+        _ => {}
+    }
+}
+
 /// Initialize the global Iggy publisher.
 ///
-/// No-op if already initialized. Call once at daemon startup.
+/// No-op if already initialized. Call once at daemon startup, inside the
+/// runtime: this spawns the periodic flush task.
 pub fn init_publisher(config: EventsConfig) {
     if CONFIG.set(config).is_ok() {
+        drop(tokio::spawn(async {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                for (topic, events) in take_buffered() {
+                    spawn_batch(topic, events);
+                }
+            }
+        }));
         tracing::info!("Iggy event publisher initialized");
     }
 }
 
 /// Publish an `OmniEvent` to Iggy (best-effort, fire-and-forget).
 ///
+/// The event is buffered and sent with the next batch for its topic.
 /// No-op if the publisher has not been initialized.
 pub fn publish(event: OmniEvent) {
+    if CONFIG.get().is_none() {
+        return;
+    }
+    let full = buffer()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .push(event);
+    if let Some((topic, events)) = full {
+        spawn_batch(topic, events);
+    }
+}
+
+/// Deliver all buffered events and wait for every batch to complete,
+/// including batches already in flight.
+///
+/// Intended for graceful shutdown. Failures are logged, never returned.
+/// No-op if the publisher has not been initialized.
+pub async fn flush() {
+    if CONFIG.get().is_none() {
+        return;
+    }
+    for (topic, events) in take_buffered() {
+        spawn_batch(topic, events);
+    }
+    IN_FLIGHT.close();
+    IN_FLIGHT.wait().await;
+    IN_FLIGHT.reopen();
+}
+
+/// Drain every topic's buffered events
+fn take_buffered() -> Vec<(String, Vec<OmniEvent>)> {
+    buffer()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .drain()
+}
+
+/// Deliver a batch on a background task
+fn spawn_batch(topic: String, events: Vec<OmniEvent>) {
     let Some(config) = CONFIG.get() else {
         return;
     };
-    let config = config.clone();
-    drop(tokio::spawn(async move {
-        if let Err(e) = send_event(&config, &event).await {
-            tracing::warn!(
-                event_type = %event.event_type,
-                error = %e,
-                "failed to publish OmniEvent"
-            );
-        } else {
-            tracing::debug!(
-                event_type = %event.event_type,
-                topic = %event.organization_id,
-                "published OmniEvent"
-            );
+    drop(IN_FLIGHT.spawn(deliver_batch(config, topic, events)));
+}
+
+/// Send a batch and log the outcome
+async fn deliver_batch(config: &EventsConfig, topic: String, events: Vec<OmniEvent>) {
+    if let Err(e) = send_batch(config, &topic, &events).await {
+        tracing::warn!(
+            topic = %topic,
+            count = events.len(),
+            error = %e,
+            "failed to publish OmniEvent batch"
+        );
+    } else {
+        tracing::debug!(topic = %topic, count = events.len(), "published OmniEvent batch");
+    }
+}
+
+/// Pending events grouped by organization topic
+#[derive(Debug, Default)]
+struct EventBuffer {
+    topics: HashMap<String, Vec<OmniEvent>>,
+}
+
+impl EventBuffer {
+    /// Buffer an event, returning its topic's batch once it reaches [`MAX_BATCH_SIZE`]
+    fn push(&mut self, event: OmniEvent) -> Option<(String, Vec<OmniEvent>)> {
+        let topic = event.organization_id.clone();
+        let events = self.topics.entry(topic.clone()).or_default();
+        events.push(event);
+        if events.len() < MAX_BATCH_SIZE {
+            return None;
         }
-    }));
+        self.topics.remove(&topic).map(|events| (topic, events))
+    }
+
+    /// Take every buffered batch, leaving the buffer empty
+    fn drain(&mut self) -> Vec<(String, Vec<OmniEvent>)> {
+        self.topics.drain().collect()
+    }
 }
 
 // -- Private HTTP helpers --
@@ -315,43 +630,185 @@ struct IggyMessage {
     payload: String,
 }
 
-/// Send an event to the Iggy HTTP API.
+/// Failure of a single publish attempt, classified for retry handling
+#[derive(Debug)]
+enum PublishError {
+    /// Transient failure (timeout, connection error, 5xx, 429) worth retrying
+    Retryable(anyhow::Error),
+    /// The bearer token was rejected (401/403); retry after re-authenticating
+    Unauthorized(anyhow::Error),
+    /// Permanent failure (other 4xx, encoding errors); retrying cannot help
+    Fatal(anyhow::Error),
+}
+
+impl PublishError {
+    /// Classify a non-success HTTP status
+    fn from_status(status: reqwest::StatusCode, err: anyhow::Error) -> Self {
+        match status.as_u16() {
+            401 | 403 => Self::Unauthorized(err),
+            408 | 429 | 500..=599 => Self::Retryable(err),
+            _ => Self::Fatal(err),
+        }
+    }
+
+    /// Classify a transport or decoding error from reqwest
+    fn from_reqwest(err: reqwest::Error) -> Self {
+        if let Some(status) = err.status() {
+            return Self::from_status(status, err.into());
+        }
+        if err.is_timeout() || err.is_connect() || err.is_request() {
+            Self::Retryable(err.into())
+        } else {
+            Self::Fatal(err.into())
+        }
+    }
+
+    /// Whether another attempt may succeed
+    const fn is_retryable(&self) -> bool {
+        matches!(self, Self::Retryable(_) | Self::Unauthorized(_))
+    }
+
+    fn into_inner(self) -> anyhow::Error {
+        match self {
+            Self::Retryable(e) | Self::Unauthorized(e) | Self::Fatal(e) => e,
+        }
+    }
+}
+
+/// Backoff before retry number `attempt` (1-based).
+///
+/// Exponential from [`RETRY_BASE_DELAY`], capped at [`RETRY_MAX_DELAY`], then
+/// scaled by `jitter` in `[0.5, 1.0]` so concurrent publishers spread out.
+fn backoff_delay(attempt: u32, jitter: f64) -> Duration {
+    let exp = RETRY_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+    exp.min(RETRY_MAX_DELAY).mul_f64(jitter.clamp(0.5, 1.0))
+}
+
+/// Send a batch of events for one topic to the Iggy HTTP API, retrying transient failures.
 ///
-/// Uses a cached bearer token, re-authenticating on token expiry.
+/// Uses a cached bearer token. The token is only invalidated when Iggy rejects
+/// it (401/403), in which case the next attempt re-authenticates.
 ///
 /// # Errors
 ///
-/// Returns an error if authentication, stream/topic provisioning, or message delivery fails.
-async fn send_event(config: &EventsConfig, event: &OmniEvent) -> anyhow::Result<()> {
+/// Returns the last error once retries are exhausted, or immediately on a
+/// non-retryable failure.
+async fn send_batch(
+    config: &EventsConfig,
+    topic: &str,
+    events: &[OmniEvent],
+) -> anyhow::Result<()> {
+    use rand::Rng as _;
+
     let client = reqwest::Client::new();
-    let token = cached_token(&client, config).await?;
+    let mut attempt = 0;
+
+    loop {
+        let result = match cached_token(&client, config).await {
+            Ok(token) => inner_send(&client, config, topic, events, &token).await,
+            Err(e) => Err(e),
+        };
+        let Err(err) = result else {
+            return Ok(());
+        };
+
+        if matches!(err, PublishError::Unauthorized(_)) {
+            invalidate_token().await;
+        }
+        if !err.is_retryable() || attempt >= config.publish_retries {
+            return Err(err.into_inner());
+        }
+
+        attempt += 1;
+        let delay = backoff_delay(attempt, rand::thread_rng().gen_range(0.5..=1.0));
+        tracing::debug!(
+            topic,
+            attempt,
+            delay_ms = delay.as_millis(),
+            error = %err.into_inner(),
+            "retrying OmniEvent publish"
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
 
-    if let Err(e) = inner_send(&client, config, event, &token).await {
-        // Invalidate the cached token so the next publish re-authenticates
-        invalidate_token().await;
-        return Err(e);
+/// Provision stream/topic (unless already known) and publish the encoded messages.
+///
+/// # Errors
+///
+/// Returns a classified error if stream/topic creation or message delivery fails.
+async fn inner_send(
+    client: &reqwest::Client,
+    config: &EventsConfig,
+    topic_id: &str,
+    events: &[OmniEvent],
+    token: &str,
+) -> Result<(), PublishError> {
+    if !provisioned_topics().read().await.contains(topic_id) {
+        ensure_topic(client, config, topic_id, token).await?;
+        provisioned_topics()
+            .write()
+            .await
+            .insert(topic_id.to_string());
+    }
+
+    // Encode payloads as base64
+    let messages = events
+        .iter()
+        .map(|event| {
+            let payload_bytes =
+                serde_json::to_vec(event).map_err(|e| PublishError::Fatal(e.into()))?;
+            Ok(IggyMessage {
+                payload: base64::engine::general_purpose::STANDARD.encode(payload_bytes),
+            })
+        })
+        .collect::<Result<Vec<_>, PublishError>>()?;
+
+    // Publish messages
+    let send_resp = client
+        .post(format!(
+            "{}/streams/{STREAM_NAME}/topics/{topic_id}/messages",
+            config.base_url
+        ))
+        .bearer_auth(token)
+        .json(&SendMessagesRequest {
+            partitioning: Partitioning { kind: "balanced" },
+            messages,
+        })
+        .send()
+        .await
+        .map_err(PublishError::from_reqwest)?;
+
+    if !send_resp.status().is_success() {
+        let status = send_resp.status();
+        let body = send_resp.text().await.unwrap_or_default();
+        // The topic may have been deleted; check again on the next attempt
+        provisioned_topics().write().await.remove(topic_id);
+        let err = anyhow::anyhow!("Iggy send failed: {status} - {body}");
+        return Err(PublishError::from_status(status, err));
     }
 
     Ok(())
 }
 
-/// Provision stream/topic and publish the encoded message.
+/// Create the stream and organization topic if they do not exist yet.
 ///
 /// # Errors
 ///
-/// Returns an error if stream/topic creation or message delivery fails.
-async fn inner_send(
+/// Returns a classified error if the existence checks or creation requests fail.
+async fn ensure_topic(
     client: &reqwest::Client,
     config: &EventsConfig,
-    event: &OmniEvent,
+    topic_id: &str,
     token: &str,
-) -> anyhow::Result<()> {
+) -> Result<(), PublishError> {
     // Ensure stream exists
     let stream_resp = client
         .get(format!("{}/streams/{STREAM_NAME}", config.base_url))
         .bearer_auth(token)
         .send()
-        .await?;
+        .await
+        .map_err(PublishError::from_reqwest)?;
     if !stream_resp.status().is_success() {
         let _ = client
             .post(format!("{}/streams", config.base_url))
@@ -361,11 +818,11 @@ async fn inner_send(
                 name: STREAM_NAME,
             })
             .send()
-            .await?;
+            .await
+            .map_err(PublishError::from_reqwest)?;
     }
 
     // Ensure per-organization topic exists
-    let topic_id = &event.organization_id;
     let topic_resp = client
         .get(format!(
             "{}/streams/{STREAM_NAME}/topics/{topic_id}",
@@ -373,7 +830,8 @@ async fn inner_send(
         ))
         .bearer_auth(token)
         .send()
-        .await?;
+        .await
+        .map_err(PublishError::from_reqwest)?;
     if !topic_resp.status().is_success() {
         let _ = client
             .post(format!("{}/streams/{STREAM_NAME}/topics", config.base_url))
@@ -384,33 +842,8 @@ async fn inner_send(
                 message_expiry: RETENTION_SECS,
             })
             .send()
-            .await?;
-    }
-
-    // Encode payload as base64
-    let payload_bytes = serde_json::to_vec(event)?;
-    let payload_b64 = base64::engine::general_purpose::STANDARD.encode(payload_bytes);
-
-    // Publish message
-    let send_resp = client
-        .post(format!(
-            "{}/streams/{STREAM_NAME}/topics/{topic_id}/messages",
-            config.base_url
-        ))
-        .bearer_auth(token)
-        .json(&SendMessagesRequest {
-            partitioning: Partitioning { kind: "balanced" },
-            messages: vec![IggyMessage {
-                payload: payload_b64,
-            }],
-        })
-        .send()
-        .await?;
-
-    if !send_resp.status().is_success() {
-        let status = send_resp.status();
-        let body = send_resp.text().await.unwrap_or_default();
-        return Err(anyhow::anyhow!("Iggy send failed: {status} - {body}"));
+            .await
+            .map_err(PublishError::from_reqwest)?;
     }
 
     Ok(())
@@ -442,9 +875,19 @@ mod tests {
         assert_eq!(event.data["conversationId"], "sess-2");
     }
 
+    #[test]
+    fn gateway_stopped_event_has_correct_type() {
+        let event = build_gateway_stopped_event("org-4", false);
+        assert_eq!(event.event_type, "beacon.gateway.stopped");
+        assert_eq!(event.source, "beacon-gateway");
+        assert_eq!(event.subject, None);
+        assert_eq!(event.organization_id, "org-4");
+        assert_eq!(event.data["drained"], false);
+    }
+
     #[test]
     fn tool_executed_event_has_correct_type() {
-        let event = build_tool_executed_event("sess-3", "web_search", true, "org-3");
+        let event = build_tool_executed_event("sess-3", "web_search", true, "org-3", 0, "{}");
         assert_eq!(event.event_type, "beacon.tool.executed");
         assert_eq!(event.source, "beacon-gateway");
         assert_eq!(event.subject, Some("sess-3".to_string()));
@@ -454,9 +897,137 @@ mod tests {
         assert_eq!(event.data["conversationId"], "sess-3");
     }
 
+    #[test]
+    fn tool_executed_event_tags_plugin_tools() {
+        let event =
+            build_tool_executed_event("sess-3", "omni.weather::forecast", true, "org-3", 0, "{}");
+        assert_eq!(event.data["pluginId"], "omni.weather");
+        assert_eq!(event.data["toolName"], "omni.weather::forecast");
+
+        let event = build_tool_executed_event("sess-3", "web_search", true, "org-3", 0, "{}");
+        assert!(event.data.get("pluginId").is_none());
+    }
+
     #[test]
     fn tool_executed_event_captures_failure() {
-        let event = build_tool_executed_event("sess-4", "bash", false, "org-4");
+        let event = build_tool_executed_event("sess-4", "bash", false, "org-4", 0, "{}");
         assert_eq!(event.data["success"], false);
     }
+
+    #[test]
+    fn tool_executed_event_includes_timing_and_truncated_args() {
+        let command = format!("grep -r needle {}", "dir/".repeat(50));
+        let args = serde_json::json!({ "command": command }).to_string();
+        let event = build_tool_executed_event("sess-5", "shell", true, "org-5", 1234, &args);
+
+        assert_eq!(event.data["durationMs"], 1234);
+        let summary = event.data["argsSummary"].as_str().unwrap();
+        assert!(summary.starts_with("grep -r needle"));
+        assert!(summary.chars().count() <= ARGS_SUMMARY_MAX_CHARS);
+        // Existing fields are kept
+        assert_eq!(event.data["toolName"], "shell");
+        assert_eq!(event.data["success"], true);
+    }
+
+    #[test]
+    fn pairing_blocked_event_has_correct_type() {
+        let until = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:15:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let event = build_pairing_blocked_event("user-7", "telegram", 5, &until);
+        assert_eq!(event.event_type, "beacon.security.pairing_blocked");
+        assert_eq!(event.organization_id, "user-7");
+        assert_eq!(event.data["channel"], "telegram");
+        assert_eq!(event.data["failedAttempts"], 5);
+        assert_eq!(event.data["lockedUntil"], "2026-01-01T00:15:00+00:00");
+    }
+
+    #[test]
+    fn tool_denied_event_has_correct_type() {
+        let args = serde_json::json!({ "command": "rm -rf /", "token": "abc" }).to_string();
+        let event = build_tool_denied_event("sess-6", "Bash", "org-6", "discord", &args);
+        assert_eq!(event.event_type, "beacon.tool.denied");
+        assert_eq!(event.subject, Some("sess-6".to_string()));
+        assert_eq!(event.organization_id, "org-6");
+        assert_eq!(event.data["toolName"], "Bash");
+        assert_eq!(event.data["channel"], "discord");
+        assert_eq!(event.data["argsSummary"], "rm -rf /");
+    }
+
+    #[test]
+    fn session_compacted_event_has_correct_type() {
+        let event = build_session_compacted_event("sess-8", "org-8", 9000, 1200, 24);
+        assert_eq!(event.event_type, "beacon.session.compacted");
+        assert_eq!(event.subject, Some("sess-8".to_string()));
+        assert_eq!(event.data["tokensBefore"], 9000);
+        assert_eq!(event.data["tokensAfter"], 1200);
+        assert_eq!(event.data["messagesRemoved"], 24);
+    }
+
+    #[test]
+    fn publish_error_classifies_status_codes() {
+        let classify = |code: u16| {
+            PublishError::from_status(
+                reqwest::StatusCode::from_u16(code).unwrap(),
+                anyhow::anyhow!("x"),
+            )
+        };
+        assert!(matches!(classify(401), PublishError::Unauthorized(_)));
+        assert!(matches!(classify(403), PublishError::Unauthorized(_)));
+        assert!(matches!(classify(500), PublishError::Retryable(_)));
+        assert!(matches!(classify(503), PublishError::Retryable(_)));
+        assert!(matches!(classify(429), PublishError::Retryable(_)));
+        assert!(matches!(classify(400), PublishError::Fatal(_)));
+        assert!(matches!(classify(404), PublishError::Fatal(_)));
+        assert!(!classify(422).is_retryable());
+        assert!(classify(502).is_retryable());
+    }
+
+    #[test]
+    fn backoff_delay_grows_exponentially_and_caps() {
+        assert_eq!(backoff_delay(1, 1.0), RETRY_BASE_DELAY);
+        assert_eq!(backoff_delay(2, 1.0), RETRY_BASE_DELAY * 2);
+        assert_eq!(backoff_delay(3, 1.0), RETRY_BASE_DELAY * 4);
+        assert_eq!(backoff_delay(30, 1.0), RETRY_MAX_DELAY);
+    }
+
+    #[test]
+    fn backoff_delay_applies_jitter_within_bounds() {
+        assert_eq!(backoff_delay(2, 0.5), RETRY_BASE_DELAY);
+        // Out-of-range jitter is clamped
+        assert_eq!(backoff_delay(2, 0.0), RETRY_BASE_DELAY);
+        assert_eq!(backoff_delay(2, 7.0), RETRY_BASE_DELAY * 2);
+    }
+
+    #[test]
+    fn event_buffer_groups_by_topic_and_flushes_full_batches() {
+        let mut buffer = EventBuffer::default();
+        for i in 0..MAX_BATCH_SIZE - 1 {
+            let event = build_conversation_started_event(&format!("s{i}"), "discord", "org-a");
+            assert!(buffer.push(event).is_none());
+        }
+        assert!(
+            buffer
+                .push(build_conversation_started_event("b", "slack", "org-b"))
+                .is_none()
+        );
+
+        let (topic, events) = buffer
+            .push(build_conversation_started_event("last", "discord", "org-a"))
+            .expect("batch should be full");
+        assert_eq!(topic, "org-a");
+        assert_eq!(events.len(), MAX_BATCH_SIZE);
+
+        let remaining = buffer.drain();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].0, "org-b");
+        assert!(buffer.drain().is_empty());
+    }
+
+    #[test]
+    fn tool_args_summary_redacts_sensitive_values() {
+        let args = r#"{"api_key": "sk-live-123", "city": "Berlin"}"#;
+        let summary = summarize_tool_arguments("weather", args);
+        assert!(!summary.contains("sk-live-123"));
+    }
 }
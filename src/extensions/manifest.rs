@@ -0,0 +1,190 @@
+//! Extension manifest (`extension.toml`)
+//!
+//! Each extension lives in its own subdirectory of the extension directory,
+//! containing an `extension.toml` manifest next to its `.wasm` module:
+//!
+//! ```toml
+//! id = "matrix-bridge"
+//! name = "Matrix Bridge"
+//! version = "0.1.0"
+//! # Optional; defaults to the only `.wasm` file in the directory
+//! module = "matrix_bridge.wasm"
+//! # Channels the module exposes through the host bridge
+//! channels = ["matrix"]
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{Error, Result};
+
+/// Manifest file name expected in each extension directory
+pub const MANIFEST_FILE: &str = "extension.toml";
+
+/// Parsed and validated extension manifest
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtensionManifest {
+    /// Extension unique identifier (lowercase, hyphenated)
+    pub id: String,
+    /// Human-readable name
+    pub name: String,
+    /// Version string (semver)
+    pub version: String,
+    /// WASM module file name, relative to the manifest
+    #[serde(default)]
+    pub module: Option<String>,
+    /// Channel names provided by the module
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+impl ExtensionManifest {
+    /// Read and validate the manifest in `dir`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the manifest is missing, malformed, or invalid
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        let raw = std::fs::read_to_string(&path)
+            .map_err(|e| Error::Extension(format!("failed to read {}: {e}", path.display())))?;
+        let manifest: Self = toml::from_str(&raw)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Check required fields and their format
+    ///
+    /// # Errors
+    ///
+    /// Returns error describing the first invalid field
+    pub fn validate(&self) -> Result<()> {
+        let valid_id = !self.id.is_empty()
+            && !self.id.starts_with('-')
+            && !self.id.ends_with('-')
+            && self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !valid_id {
+            return Err(Error::Extension(format!(
+                "invalid extension id `{}`: expected lowercase, hyphenated",
+                self.id
+            )));
+        }
+
+        if self.name.trim().is_empty() {
+            return Err(Error::Extension(format!(
+                "extension `{}` has an empty name",
+                self.id
+            )));
+        }
+
+        let core = self.version.split(['-', '+']).next().unwrap_or_default();
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() != 3
+            || parts
+                .iter()
+                .any(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit()))
+        {
+            return Err(Error::Extension(format!(
+                "extension `{}` has invalid version `{}`: expected semver",
+                self.id, self.version
+            )));
+        }
+
+        if let Some(name) = self.channels.iter().find(|c| c.trim().is_empty()) {
+            return Err(Error::Extension(format!(
+                "extension `{}` declares an invalid channel name `{name}`",
+                self.id
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the WASM module path for an extension in `dir`
+    ///
+    /// Uses `module` when set, otherwise the single `.wasm` file in `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the module is missing or ambiguous
+    pub fn module_path(&self, dir: &Path) -> Result<PathBuf> {
+        if let Some(ref module) = self.module {
+            let path = dir.join(module);
+            if !path.is_file() {
+                return Err(Error::Extension(format!(
+                    "extension `{}` module not found: {}",
+                    self.id,
+                    path.display()
+                )));
+            }
+            return Ok(path);
+        }
+
+        let mut modules: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(std::result::Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+            .collect();
+
+        match modules.len() {
+            1 => Ok(modules.remove(0)),
+            0 => Err(Error::Extension(format!(
+                "extension `{}` has no .wasm module",
+                self.id
+            ))),
+            _ => Err(Error::Extension(format!(
+                "extension `{}` has several .wasm modules; set `module` in {MANIFEST_FILE}",
+                self.id
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(id: &str, version: &str) -> ExtensionManifest {
+        ExtensionManifest {
+            id: id.to_string(),
+            name: "Test".to_string(),
+            version: version.to_string(),
+            module: None,
+            channels: vec!["matrix".to_string()],
+        }
+    }
+
+    #[test]
+    fn validate_accepts_well_formed_manifest() {
+        assert!(manifest("matrix-bridge", "0.1.0").validate().is_ok());
+        assert!(manifest("ext2", "1.2.3-beta.1").validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_bad_id_and_version() {
+        assert!(manifest("Matrix Bridge", "0.1.0").validate().is_err());
+        assert!(manifest("-bridge", "0.1.0").validate().is_err());
+        assert!(manifest("bridge", "1.0").validate().is_err());
+        assert!(manifest("bridge", "one.two.three").validate().is_err());
+    }
+
+    #[test]
+    fn module_path_defaults_to_single_wasm_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let m = manifest("bridge", "0.1.0");
+        assert!(m.module_path(dir.path()).is_err());
+
+        std::fs::write(dir.path().join("bridge.wasm"), b"\0asm").unwrap();
+        assert_eq!(
+            m.module_path(dir.path()).unwrap(),
+            dir.path().join("bridge.wasm")
+        );
+
+        std::fs::write(dir.path().join("other.wasm"), b"\0asm").unwrap();
+        assert!(m.module_path(dir.path()).is_err());
+    }
+}
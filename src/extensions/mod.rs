@@ -1,8 +1,9 @@
 //! Extension/plugin system for Beacon gateway
 //!
-//! Extensions allow dynamic loading of channels and tools. For now, this module
-//! provides the trait and registry - actual dynamic loading (WASM or .so) can
-//! be implemented later.
+//! Extensions allow dynamic loading of channels and tools. Extensions can be
+//! registered programmatically or loaded from WASM modules in the extension
+//! directory (with the `wasm-extensions` feature); see [`manifest`] for the
+//! on-disk layout.
 //!
 //! # Example
 //!
@@ -17,12 +18,29 @@
 //! }
 //! ```
 
-use std::path::PathBuf;
+pub mod manifest;
+#[cfg(feature = "wasm-extensions")]
+pub mod wasm;
+mod watch;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
 
 use serde::Serialize;
 
-use crate::Result;
 use crate::channels::Channel;
+use crate::{Error, Result};
+
+pub use manifest::ExtensionManifest;
+#[cfg(feature = "wasm-extensions")]
+pub use wasm::WasmExtension;
+
+/// Channels provided by loaded extensions, keyed by extension ID
+///
+/// The registry keeps this map in step with loads and reloads; it is shared
+/// with [`crate::channels::OutboundChannels`] for proactive delivery.
+pub type ExtensionChannels = Arc<RwLock<HashMap<String, Vec<Arc<dyn Channel>>>>>;
 
 /// Extension trait for plugins
 ///
@@ -88,6 +106,9 @@ pub struct ExtensionInfo {
 pub struct ExtensionRegistry {
     extensions: Vec<Box<dyn Extension>>,
     extension_dir: PathBuf,
+    /// Extension IDs keyed by the directory they were loaded from
+    sources: HashMap<PathBuf, String>,
+    channels: ExtensionChannels,
 }
 
 impl ExtensionRegistry {
@@ -101,9 +122,26 @@ impl ExtensionRegistry {
         Self {
             extensions: Vec::new(),
             extension_dir,
+            sources: HashMap::new(),
+            channels: ExtensionChannels::default(),
         }
     }
 
+    /// Registry for `BEACON_EXTENSION_DIR`, or the default directory
+    #[must_use]
+    pub fn from_env() -> Self {
+        std::env::var("BEACON_EXTENSION_DIR")
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .map_or_else(Self::default, |dir| Self::new(PathBuf::from(dir)))
+    }
+
+    /// Shared view of the channels provided by loaded extensions
+    #[must_use]
+    pub fn channel_map(&self) -> ExtensionChannels {
+        Arc::clone(&self.channels)
+    }
+
     /// Get the extension directory path
     #[must_use]
     pub fn extension_dir(&self) -> &std::path::Path {
@@ -112,13 +150,13 @@ impl ExtensionRegistry {
 
     /// Load all extensions from extension directory
     ///
-    /// For now, this is a no-op placeholder. Actual dynamic loading (WASM or
-    /// native plugins) will be implemented later.
+    /// Each subdirectory containing an `extension.toml` manifest is loaded as
+    /// a WASM extension and initialized. A failing extension is logged and
+    /// skipped; it does not abort the scan.
     ///
     /// # Errors
     ///
-    /// Returns error if extension directory cannot be read or an extension
-    /// fails to load
+    /// Returns error if extension directory cannot be created or read
     pub fn load_all(&mut self) -> Result<()> {
         // Ensure extension directory exists
         if !self.extension_dir.exists() {
@@ -129,19 +167,81 @@ impl ExtensionRegistry {
             );
         }
 
-        // TODO: implement actual extension loading (WASM, .so, etc.)
+        let mut dirs: Vec<PathBuf> = std::fs::read_dir(&self.extension_dir)?
+            .filter_map(std::result::Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.join(manifest::MANIFEST_FILE).is_file())
+            .collect();
+        dirs.sort();
+
+        for dir in dirs {
+            if let Err(e) = self.load_dir(&dir) {
+                tracing::warn!(
+                    path = %dir.display(),
+                    error = %e,
+                    "failed to load extension"
+                );
+            }
+        }
+
         tracing::debug!(
             path = %self.extension_dir.display(),
-            "extension loading not yet implemented"
+            count = self.extensions.len(),
+            "extensions loaded"
         );
 
         Ok(())
     }
 
+    /// Load and register the extension in `dir`, remembering where it came from
+    fn load_dir(&mut self, dir: &Path) -> Result<()> {
+        let extension = load_extension(dir)?;
+        let id = extension.id().to_string();
+        if self.get(&id).is_some() {
+            return Err(Error::Extension(format!("duplicate extension id `{id}`")));
+        }
+        self.register(extension)?;
+        self.sources.insert(dir.to_path_buf(), id);
+        Ok(())
+    }
+
+    /// Reload the extension in `dir` after its files changed
+    ///
+    /// The previously loaded extension is shut down and dropped before the
+    /// new one is initialized, so the two never run side by side. Its
+    /// channels are replaced by the new extension's. If the directory or its
+    /// manifest was removed, the extension is just unloaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the new extension fails to load or initialize
+    pub fn reload_dir(&mut self, dir: &Path) -> Result<()> {
+        if let Some(id) = self.sources.remove(dir)
+            && let Some(pos) = self.extensions.iter().position(|ext| ext.id() == id)
+        {
+            let mut extension = self.extensions.remove(pos);
+            self.channels
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&id);
+            tracing::info!(id = %id, "unloading extension for reload");
+            if let Err(e) = extension.shutdown() {
+                tracing::warn!(id = %id, error = %e, "extension shutdown failed");
+            }
+        }
+
+        if dir.join(manifest::MANIFEST_FILE).is_file() {
+            self.load_dir(dir)?;
+        }
+
+        Ok(())
+    }
+
     /// Register an extension manually
     ///
     /// This allows programmatic registration of extensions without loading
-    /// them from the filesystem.
+    /// them from the filesystem. The extension's channels are published to
+    /// the shared [`ExtensionChannels`] map.
     ///
     /// # Errors
     ///
@@ -152,6 +252,11 @@ impl ExtensionRegistry {
 
         tracing::info!(id = %id, name = %name, "registering extension");
         extension.init()?;
+        let channels = extension.channels().into_iter().map(Arc::from).collect();
+        self.channels
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id, channels);
         self.extensions.push(extension);
 
         Ok(())
@@ -200,6 +305,10 @@ impl ExtensionRegistry {
     /// Calls shutdown on each extension. Errors are logged but do not stop
     /// the shutdown process.
     pub fn shutdown_all(&mut self) {
+        self.channels
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
         for ext in &mut self.extensions {
             let id = ext.id().to_string();
             if let Err(e) = ext.shutdown() {
@@ -221,6 +330,25 @@ impl ExtensionRegistry {
     }
 }
 
+/// Load the extension in `dir` from its manifest and WASM module
+#[cfg(feature = "wasm-extensions")]
+fn load_extension(dir: &Path) -> Result<Box<dyn Extension>> {
+    let manifest = ExtensionManifest::load(dir)?;
+    let module_path = manifest.module_path(dir)?;
+    Ok(Box::new(WasmExtension::new(manifest, module_path)))
+}
+
+/// Validate the manifest in `dir`, then report that WASM support is disabled
+#[cfg(not(feature = "wasm-extensions"))]
+fn load_extension(dir: &Path) -> Result<Box<dyn Extension>> {
+    let manifest = ExtensionManifest::load(dir)?;
+    manifest.module_path(dir)?;
+    Err(Error::Extension(format!(
+        "cannot load `{}`: built without the `wasm-extensions` feature",
+        manifest.id
+    )))
+}
+
 impl Default for ExtensionRegistry {
     fn default() -> Self {
         // Default to ~/.local/share/omni/beacon/extensions/
@@ -310,6 +438,141 @@ mod tests {
         assert!(missing.is_none());
     }
 
+    #[test]
+    fn test_load_all_skips_invalid_extensions() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let bad_manifest = dir.path().join("bad");
+        std::fs::create_dir(&bad_manifest).unwrap();
+        std::fs::write(
+            bad_manifest.join(manifest::MANIFEST_FILE),
+            "id = \"Bad Id\"\nname = \"Bad\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        let bad_module = dir.path().join("broken");
+        std::fs::create_dir(&bad_module).unwrap();
+        std::fs::write(
+            bad_module.join(manifest::MANIFEST_FILE),
+            "id = \"broken\"\nname = \"Broken\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(bad_module.join("broken.wasm"), b"not wasm").unwrap();
+
+        let mut registry = ExtensionRegistry::new(dir.path().to_path_buf());
+        registry.load_all().unwrap();
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_reload_dir_shuts_down_removed_extension() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        struct TrackedExtension(Arc<AtomicBool>);
+
+        impl Extension for TrackedExtension {
+            fn id(&self) -> &str {
+                "tracked"
+            }
+            fn name(&self) -> &str {
+                "Tracked"
+            }
+            fn version(&self) -> &str {
+                "1.0.0"
+            }
+            fn init(&mut self) -> Result<()> {
+                Ok(())
+            }
+            fn channels(&self) -> Vec<Box<dyn Channel>> {
+                Vec::new()
+            }
+            fn shutdown(&mut self) -> Result<()> {
+                self.0.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("tracked");
+        let shut_down = Arc::new(AtomicBool::new(false));
+
+        let mut registry = ExtensionRegistry::new(root.path().to_path_buf());
+        registry
+            .register(Box::new(TrackedExtension(Arc::clone(&shut_down))))
+            .unwrap();
+        registry.sources.insert(dir.clone(), "tracked".to_string());
+
+        let channels = registry.channel_map();
+        assert!(channels.read().unwrap().contains_key("tracked"));
+
+        // Directory no longer exists: the extension is unloaded, not reloaded
+        registry.reload_dir(&dir).unwrap();
+        assert!(shut_down.load(Ordering::SeqCst));
+        assert!(registry.is_empty());
+        assert!(channels.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_registered_channels_reach_outbound_lookup() {
+        use crate::channels::{OutboundChannels, OutgoingMessage};
+
+        struct NullChannel;
+
+        #[async_trait::async_trait]
+        impl Channel for NullChannel {
+            fn name(&self) -> &'static str {
+                "ext-chan"
+            }
+            async fn connect(&mut self) -> Result<()> {
+                Ok(())
+            }
+            async fn disconnect(&mut self) -> Result<()> {
+                Ok(())
+            }
+            async fn send(&self, _message: OutgoingMessage) -> Result<()> {
+                Ok(())
+            }
+            fn is_connected(&self) -> bool {
+                true
+            }
+        }
+
+        struct ChannelExtension;
+
+        impl Extension for ChannelExtension {
+            fn id(&self) -> &str {
+                "with-channel"
+            }
+            fn name(&self) -> &str {
+                "With Channel"
+            }
+            fn version(&self) -> &str {
+                "1.0.0"
+            }
+            fn init(&mut self) -> Result<()> {
+                Ok(())
+            }
+            fn channels(&self) -> Vec<Box<dyn Channel>> {
+                vec![Box::new(NullChannel)]
+            }
+            fn shutdown(&mut self) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut registry = ExtensionRegistry::new(PathBuf::from("/tmp/test-extensions"));
+        registry.register(Box::new(ChannelExtension)).unwrap();
+
+        let mut outbound = OutboundChannels::default();
+        assert!(outbound.get("ext-chan").is_none());
+        outbound.set_extensions(registry.channel_map());
+        assert!(outbound.get("ext-chan").is_some());
+
+        registry.shutdown_all();
+        assert!(outbound.get("ext-chan").is_none());
+    }
+
     #[test]
     fn test_registry_list() {
         let mut registry = ExtensionRegistry::new(PathBuf::from("/tmp/test-extensions"));
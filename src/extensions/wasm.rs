@@ -0,0 +1,378 @@
+//! WASM extension runtime (requires the `wasm-extensions` feature)
+//!
+//! Guest ABI, all integers are `i32`:
+//! - `memory`: exported linear memory
+//! - `init() -> status`: called once on load; non-zero fails the load
+//! - `alloc(len) -> ptr`: reserve guest memory for host-written data
+//! - `channel_send(name_ptr, name_len, msg_ptr, msg_len) -> status`: deliver an
+//!   outgoing message (JSON) to one of the manifest's channels
+//! - `shutdown()`: optional, called on unload
+//!
+//! The host provides `beacon.log(ptr, len)` for guest logging.
+//!
+//! Every guest call runs under an epoch deadline of [`CALL_DEADLINE`]; a
+//! guest that loops past it traps instead of hanging the host. Calls made
+//! after the extension is shut down fail.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use wasmtime::{Caller, Config, Engine, Extern, Instance, Linker, Module, Store};
+
+use super::{Extension, ExtensionManifest};
+use crate::channels::{Channel, OutgoingMessage};
+use crate::{Error, Result};
+
+/// Interval between epoch ticks
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Longest a single guest call may run before it is interrupted
+const CALL_DEADLINE: Duration = Duration::from_secs(5);
+
+/// Background thread advancing the engine epoch while the runtime lives
+struct EpochTicker {
+    stop: Arc<AtomicBool>,
+}
+
+impl EpochTicker {
+    fn start(engine: Engine) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        std::thread::spawn(move || {
+            while !flag.load(Ordering::Relaxed) {
+                std::thread::sleep(EPOCH_TICK);
+                engine.increment_epoch();
+            }
+        });
+        Self { stop }
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Epoch ticks making up [`CALL_DEADLINE`]
+fn deadline_ticks() -> u64 {
+    u64::try_from(CALL_DEADLINE.as_millis() / EPOCH_TICK.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Instantiated guest module
+struct WasmRuntime {
+    store: Store<()>,
+    instance: Instance,
+    /// Set once `shutdown` ran; later calls are refused
+    closed: bool,
+    _ticker: EpochTicker,
+}
+
+impl WasmRuntime {
+    /// Compile and instantiate the module at `path`
+    fn instantiate(id: &str, path: &Path) -> Result<Self> {
+        let mut config = Config::new();
+        config.epoch_interruption(true);
+        let engine = Engine::new(&config)
+            .map_err(|e| Error::Extension(format!("failed to create engine: {e}")))?;
+        let ticker = EpochTicker::start(engine.clone());
+        let module = Module::from_file(&engine, path)
+            .map_err(|e| Error::Extension(format!("failed to compile {}: {e}", path.display())))?;
+
+        let mut linker = Linker::new(&engine);
+        let log_id = id.to_string();
+        linker
+            .func_wrap(
+                "beacon",
+                "log",
+                move |mut caller: Caller<'_, ()>, ptr: i32, len: i32| {
+                    if let Some(Extern::Memory(memory)) = caller.get_export("memory") {
+                        let mut buf = vec![0u8; usize::try_from(len).unwrap_or_default()];
+                        if memory
+                            .read(&caller, usize::try_from(ptr).unwrap_or_default(), &mut buf)
+                            .is_ok()
+                        {
+                            tracing::info!(
+                                extension = %log_id,
+                                message = %String::from_utf8_lossy(&buf),
+                                "extension log"
+                            );
+                        }
+                    }
+                },
+            )
+            .map_err(|e| Error::Extension(format!("failed to link host functions: {e}")))?;
+
+        let mut store = Store::new(&engine, ());
+        store.epoch_deadline_trap();
+        // Start functions run during instantiation, so they get a deadline too
+        store.set_epoch_deadline(deadline_ticks());
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .map_err(|e| Error::Extension(format!("failed to instantiate {id}: {e}")))?;
+
+        Ok(Self {
+            store,
+            instance,
+            closed: false,
+            _ticker: ticker,
+        })
+    }
+
+    /// Refuse calls after shutdown and arm the deadline for the next call
+    fn begin_call(&mut self) -> Result<()> {
+        if self.closed {
+            return Err(Error::Extension("extension is shut down".to_string()));
+        }
+        self.store.set_epoch_deadline(deadline_ticks());
+        Ok(())
+    }
+
+    /// Call an exported `() -> i32` function, failing on a non-zero status
+    fn call_status(&mut self, export: &str) -> Result<()> {
+        self.begin_call()?;
+        let func = self
+            .instance
+            .get_typed_func::<(), i32>(&mut self.store, export)
+            .map_err(|e| Error::Extension(format!("missing export `{export}`: {e}")))?;
+        let status = func
+            .call(&mut self.store, ())
+            .map_err(|e| Error::Extension(format!("`{export}` trapped: {e}")))?;
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(Error::Extension(format!("`{export}` returned {status}")))
+        }
+    }
+
+    /// Copy `bytes` into guest memory via the guest's `alloc` export
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(i32, i32)> {
+        let len = i32::try_from(bytes.len())
+            .map_err(|_| Error::Extension("payload too large for guest".to_string()))?;
+        let alloc = self
+            .instance
+            .get_typed_func::<i32, i32>(&mut self.store, "alloc")
+            .map_err(|e| Error::Extension(format!("missing export `alloc`: {e}")))?;
+        let ptr = alloc
+            .call(&mut self.store, len)
+            .map_err(|e| Error::Extension(format!("`alloc` trapped: {e}")))?;
+        let memory = self
+            .instance
+            .get_memory(&mut self.store, "memory")
+            .ok_or_else(|| Error::Extension("missing export `memory`".to_string()))?;
+        memory
+            .write(
+                &mut self.store,
+                usize::try_from(ptr).unwrap_or_default(),
+                bytes,
+            )
+            .map_err(|e| Error::Extension(format!("guest memory write failed: {e}")))?;
+        Ok((ptr, len))
+    }
+
+    /// Hand an outgoing message to the guest's `channel_send` export
+    fn channel_send(&mut self, channel: &str, payload: &[u8]) -> Result<()> {
+        self.begin_call()?;
+        let (name_ptr, name_len) = self.write_bytes(channel.as_bytes())?;
+        let (msg_ptr, msg_len) = self.write_bytes(payload)?;
+        let send = self
+            .instance
+            .get_typed_func::<(i32, i32, i32, i32), i32>(&mut self.store, "channel_send")
+            .map_err(|e| Error::Extension(format!("missing export `channel_send`: {e}")))?;
+        let status = send
+            .call(&mut self.store, (name_ptr, name_len, msg_ptr, msg_len))
+            .map_err(|e| Error::Extension(format!("`channel_send` trapped: {e}")))?;
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(Error::Channel(format!(
+                "{channel}: guest send returned {status}"
+            )))
+        }
+    }
+
+    /// Run the optional `shutdown` export and refuse any later calls
+    fn shutdown(&mut self) -> Result<()> {
+        self.begin_call()?;
+        self.closed = true;
+        // `shutdown` is optional
+        if let Ok(shutdown) = self
+            .instance
+            .get_typed_func::<(), ()>(&mut self.store, "shutdown")
+        {
+            shutdown
+                .call(&mut self.store, ())
+                .map_err(|e| Error::Extension(format!("`shutdown` trapped: {e}")))?;
+        }
+        Ok(())
+    }
+}
+
+/// Extension backed by a WASM module
+pub struct WasmExtension {
+    manifest: ExtensionManifest,
+    module_path: PathBuf,
+    channel_names: Vec<Arc<str>>,
+    runtime: Option<Arc<Mutex<WasmRuntime>>>,
+}
+
+impl WasmExtension {
+    /// Create an extension for `module_path`; the module is instantiated in `init`
+    #[must_use]
+    pub fn new(manifest: ExtensionManifest, module_path: PathBuf) -> Self {
+        let channel_names = manifest
+            .channels
+            .iter()
+            .map(|name| Arc::from(name.as_str()))
+            .collect();
+        Self {
+            manifest,
+            module_path,
+            channel_names,
+            runtime: None,
+        }
+    }
+}
+
+impl Extension for WasmExtension {
+    fn id(&self) -> &str {
+        &self.manifest.id
+    }
+
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn version(&self) -> &str {
+        &self.manifest.version
+    }
+
+    fn init(&mut self) -> Result<()> {
+        let mut runtime = WasmRuntime::instantiate(&self.manifest.id, &self.module_path)?;
+        runtime.call_status("init")?;
+        self.runtime = Some(Arc::new(Mutex::new(runtime)));
+        Ok(())
+    }
+
+    fn channels(&self) -> Vec<Box<dyn Channel>> {
+        let Some(ref runtime) = self.runtime else {
+            return Vec::new();
+        };
+        self.channel_names
+            .iter()
+            .map(|name| {
+                Box::new(WasmChannel {
+                    name: Arc::clone(name),
+                    runtime: Arc::clone(runtime),
+                    connected: false,
+                }) as Box<dyn Channel>
+            })
+            .collect()
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        let Some(runtime) = self.runtime.take() else {
+            return Ok(());
+        };
+        // Channels handed out earlier share the runtime; closing it makes
+        // their sends fail instead of calling into a shut-down guest
+        runtime
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .shutdown()
+    }
+}
+
+/// Channel proxy that forwards outgoing messages into the guest module
+struct WasmChannel {
+    name: Arc<str>,
+    runtime: Arc<Mutex<WasmRuntime>>,
+    connected: bool,
+}
+
+#[async_trait]
+impl Channel for WasmChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        self.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.connected = false;
+        Ok(())
+    }
+
+    async fn send(&self, message: OutgoingMessage) -> Result<()> {
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "channel_id": message.channel_id,
+            "content": message.content,
+            "reply_to": message.reply_to,
+            "thread_id": message.thread_id,
+        }))?;
+        let runtime = Arc::clone(&self.runtime);
+        let name = Arc::clone(&self.name);
+        tokio::task::spawn_blocking(move || {
+            runtime
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .channel_send(&name, &payload)
+        })
+        .await
+        .map_err(|e| Error::Channel(format!("{}: guest task failed: {e}", self.name)))?
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUEST: &str = r#"(module
+        (memory (export "memory") 1)
+        (global $next (mut i32) (i32.const 1024))
+        (func (export "init") (result i32) (i32.const 0))
+        (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+        (func (export "channel_send") (param i32 i32 i32 i32) (result i32) (i32.const 0))
+        (func (export "spin") (result i32) (loop $l (br $l)) (i32.const 0)))"#;
+
+    fn runtime() -> (tempfile::TempDir, WasmRuntime) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("guest.wat");
+        std::fs::write(&path, GUEST).unwrap();
+        let runtime = WasmRuntime::instantiate("test", &path).unwrap();
+        (dir, runtime)
+    }
+
+    #[test]
+    fn runaway_guest_is_interrupted() {
+        let (_dir, mut runtime) = runtime();
+        let started = std::time::Instant::now();
+        assert!(runtime.call_status("spin").is_err());
+        assert!(started.elapsed() < CALL_DEADLINE * 2);
+    }
+
+    #[test]
+    fn calls_fail_after_shutdown() {
+        let (_dir, mut runtime) = runtime();
+        runtime.call_status("init").unwrap();
+        runtime.channel_send("bridge", b"{}").unwrap();
+
+        runtime.shutdown().unwrap();
+        assert!(runtime.channel_send("bridge", b"{}").is_err());
+        assert!(runtime.call_status("init").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Extension hot-reload via filesystem watch
+//!
+//! Enabled with `BEACON_EXTENSIONS_WATCH=1` for extension development, in
+//! builds with the `extension-watch` feature; otherwise extensions are only
+//! loaded once at startup.
+
+#[cfg(feature = "extension-watch")]
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+#[cfg(feature = "extension-watch")]
+use std::time::Duration;
+
+#[cfg(feature = "extension-watch")]
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::Mutex;
+#[cfg(feature = "extension-watch")]
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::ExtensionRegistry;
+#[cfg(feature = "extension-watch")]
+use crate::Error;
+use crate::Result;
+
+/// Quiet period after a change before reloading, so multi-file writes
+/// (e.g. a rebuild replacing the module and manifest) trigger one reload
+#[cfg(feature = "extension-watch")]
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Whether extension hot-reload is enabled via `BEACON_EXTENSIONS_WATCH`
+#[must_use]
+pub fn watch_enabled() -> bool {
+    std::env::var("BEACON_EXTENSIONS_WATCH")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Extension directory containing `path`, i.e. the first component below `root`
+#[cfg_attr(not(feature = "extension-watch"), allow(dead_code))]
+fn extension_dir_for(root: &Path, path: &Path) -> Option<PathBuf> {
+    let first = path.strip_prefix(root).ok()?.components().next()?;
+    Some(root.join(first))
+}
+
+impl ExtensionRegistry {
+    /// Watch the extension directory and reload extensions as they change
+    ///
+    /// Returns `None` unless `BEACON_EXTENSIONS_WATCH` is set. Each reload
+    /// holds the registry lock while the old extension's `shutdown` runs to
+    /// completion and the replacement is initialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the filesystem watcher cannot be created
+    #[cfg(feature = "extension-watch")]
+    pub async fn watch(self: Arc<Mutex<Self>>) -> Result<Option<JoinHandle<()>>> {
+        if !watch_enabled() {
+            return Ok(None);
+        }
+
+        let root = self.lock().await.extension_dir.clone();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let mut watcher = RecommendedWatcher::new(
+            move |event: notify::Result<notify::Event>| match event {
+                Ok(event) => {
+                    let _ = tx.send(event.paths);
+                }
+                Err(e) => tracing::warn!(error = %e, "extension watcher error"),
+            },
+            notify::Config::default(),
+        )
+        .map_err(|e| Error::Extension(format!("failed to create watcher: {e}")))?;
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(|e| Error::Extension(format!("failed to watch {}: {e}", root.display())))?;
+
+        tracing::info!(path = %root.display(), "watching extensions for changes");
+
+        Ok(Some(tokio::spawn(async move {
+            // Keep the watcher alive for the lifetime of the task
+            let _watcher = watcher;
+
+            while let Some(paths) = rx.recv().await {
+                let mut dirs: BTreeSet<PathBuf> = paths
+                    .iter()
+                    .filter_map(|p| extension_dir_for(&root, p))
+                    .collect();
+
+                // Coalesce the burst of events from a single rebuild
+                while let Ok(Some(more)) = tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                    dirs.extend(more.iter().filter_map(|p| extension_dir_for(&root, p)));
+                }
+
+                let mut registry = self.lock().await;
+                for dir in dirs {
+                    tracing::info!(path = %dir.display(), "extension changed, reloading");
+                    if let Err(e) = registry.reload_dir(&dir) {
+                        tracing::warn!(
+                            path = %dir.display(),
+                            error = %e,
+                            "failed to reload extension"
+                        );
+                    }
+                }
+            }
+        })))
+    }
+
+    /// Hot-reload is unavailable without the `extension-watch` feature
+    ///
+    /// # Errors
+    ///
+    /// Never fails; the signature matches the feature-enabled version
+    #[cfg(not(feature = "extension-watch"))]
+    #[allow(clippy::unused_async)]
+    pub async fn watch(self: Arc<Mutex<Self>>) -> Result<Option<JoinHandle<()>>> {
+        if watch_enabled() {
+            tracing::warn!(
+                "BEACON_EXTENSIONS_WATCH is set but this build lacks the `extension-watch` feature"
+            );
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_dir_for_maps_nested_paths() {
+        let root = Path::new("/ext");
+        assert_eq!(
+            extension_dir_for(root, Path::new("/ext/bridge/target/bridge.wasm")),
+            Some(PathBuf::from("/ext/bridge"))
+        );
+        assert_eq!(
+            extension_dir_for(root, Path::new("/ext/bridge")),
+            Some(PathBuf::from("/ext/bridge"))
+        );
+        assert_eq!(extension_dir_for(root, Path::new("/other/file")), None);
+        assert_eq!(extension_dir_for(root, root), None);
+    }
+}
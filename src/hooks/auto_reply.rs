@@ -1,6 +1,6 @@
 //! Built-in auto-reply hook
 
-use regex::Regex;
+use regex::{Captures, Regex};
 use serde::Deserialize;
 
 use super::types::{HookAction, HookEvent, HookResult};
@@ -11,6 +11,10 @@ pub struct AutoReplyRule {
     /// Regex pattern to match against message content
     pub pattern: String,
     /// Reply text to send
+    ///
+    /// May reference capture groups from `pattern` as `$1` or `${name}`
+    /// (`$$` for a literal dollar sign) and the `{{sender}}`, `{{channel}}`
+    /// and `{{content}}` variables
     pub reply: String,
     /// Channels this rule applies to (all if empty)
     #[serde(default)]
@@ -81,15 +85,62 @@ impl CompiledRule {
         }
     }
 
-    /// Expand template variables in reply
+    /// Expand capture groups and template variables in reply
     fn expand_reply(&self, event: &HookEvent) -> String {
-        self.reply
+        let reply = self.pattern.captures(&event.content).map_or_else(
+            || self.reply.clone(),
+            |caps| expand_captures(&self.reply, &caps),
+        );
+
+        reply
             .replace("{{sender}}", &event.sender_name)
             .replace("{{channel}}", &event.channel)
             .replace("{{content}}", &event.content)
     }
 }
 
+/// Substitute `$0`..`$9` and `${name}` with capture groups
+///
+/// `$$` yields a literal dollar sign; unknown groups expand to nothing and a
+/// `$` not followed by a digit or `{` is kept as-is.
+fn expand_captures(template: &str, caps: &Captures<'_>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        match after.chars().next() {
+            Some('$') => {
+                out.push('$');
+                rest = &after[1..];
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let index = c.to_digit(10).unwrap_or_default() as usize;
+                out.push_str(caps.get(index).map_or("", |m| m.as_str()));
+                rest = &after[1..];
+            }
+            Some('{') if after.contains('}') => {
+                let end = after.find('}').unwrap_or_default();
+                let name = &after[1..end];
+                let group = name
+                    .parse::<usize>()
+                    .map_or_else(|_| caps.name(name), |i| caps.get(i));
+                out.push_str(group.map_or("", |m| m.as_str()));
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push('$');
+                rest = after;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
 /// Auto-reply handler
 pub struct AutoReplyHandler {
     rules: Vec<CompiledRule>,
@@ -233,6 +284,62 @@ mod tests {
         assert_eq!(result.reply.unwrap(), "Hello Alice!");
     }
 
+    #[test]
+    fn test_reply_without_captures_is_unchanged() {
+        let rules = vec![AutoReplyRule {
+            pattern: "^ping$".to_string(),
+            reply: "pong costs $5, or $$5".to_string(),
+            channels: vec![],
+            skip_agent: false,
+            case_insensitive: true,
+        }];
+
+        let handler = AutoReplyHandler::new(&rules);
+        let result = handler.handle(&make_event("discord", "ping")).unwrap();
+
+        // No group 5 exists, so `$5` expands to nothing
+        assert_eq!(result.reply.unwrap(), "pong costs , or $5");
+    }
+
+    #[test]
+    fn test_numbered_captures() {
+        let rules = vec![AutoReplyRule {
+            pattern: r"^/echo (\w+) (.+)$".to_string(),
+            reply: "You said: $2 ($1) [$0] $".to_string(),
+            channels: vec![],
+            skip_agent: true,
+            case_insensitive: true,
+        }];
+
+        let handler = AutoReplyHandler::new(&rules);
+        let result = handler
+            .handle(&make_event("discord", "/echo loud hello world"))
+            .unwrap();
+
+        assert_eq!(
+            result.reply.unwrap(),
+            "You said: hello world (loud) [/echo loud hello world] $"
+        );
+    }
+
+    #[test]
+    fn test_named_captures() {
+        let rules = vec![AutoReplyRule {
+            pattern: r"^remind me to (?P<task>.+) at (?P<time>\d+)$".to_string(),
+            reply: "{{sender}}: '${task}' at ${time}:00, ${1}${missing}".to_string(),
+            channels: vec![],
+            skip_agent: true,
+            case_insensitive: true,
+        }];
+
+        let handler = AutoReplyHandler::new(&rules);
+        let result = handler
+            .handle(&make_event("discord", "remind me to stretch at 3"))
+            .unwrap();
+
+        assert_eq!(result.reply.unwrap(), "Alice: 'stretch' at 3:00, stretch");
+    }
+
     #[test]
     fn test_case_insensitive() {
         let rules = vec![AutoReplyRule {
@@ -1,21 +1,47 @@
 //! Hook execution via subprocess
+//!
+//! # Protocol (version 1)
+//!
+//! The handler receives one JSON object on stdin: the serialized
+//! [`HookEvent`] plus a `protocol` field carrying [`PROTOCOL_VERSION`], which
+//! is also exported as `BEACON_HOOK_PROTOCOL`. It must write a single
+//! [`HookResult`]-shaped JSON object to stdout (or nothing, for no changes)
+//! and exit within the configured timeout. Handlers that time out are killed;
+//! like handlers whose output does not deserialize, they are treated as a
+//! no-op with a warning. Stderr lines are forwarded to tracing.
+//!
+//! Handlers run by extension: `.py`, `.js`, `.ts`, `.rb`, `.sh`, `.wasm`
+//! (WASI, via `wasmtime`), or an executable without extension.
 
 use std::path::Path;
 use std::process::Stdio;
 use std::time::Duration;
 
+use serde::Serialize;
 use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 use tokio::time::timeout;
 
 use super::types::{HookEvent, HookResult};
 
+/// Version of the stdin/stdout JSON protocol
+pub const PROTOCOL_VERSION: u32 = 1;
+
 /// Default timeout for hook execution
-const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Payload written to the handler's stdin
+#[derive(Serialize)]
+struct HookRequest<'a> {
+    protocol: u32,
+    #[serde(flatten)]
+    event: &'a HookEvent,
+}
 
 /// Execute a hook handler
 ///
-/// Passes event as JSON on stdin, expects JSON result on stdout
+/// Passes event as JSON on stdin, expects JSON result on stdout. Timeouts and
+/// malformed output yield an empty result rather than an error.
 pub async fn execute_hook(
     handler_path: &Path,
     event: &HookEvent,
@@ -24,23 +50,29 @@ pub async fn execute_hook(
     let timeout_duration = hook_timeout.unwrap_or(DEFAULT_TIMEOUT);
 
     // Serialize event to JSON
+    let request = HookRequest {
+        protocol: PROTOCOL_VERSION,
+        event,
+    };
     let event_json =
-        serde_json::to_string(event).map_err(|e| format!("failed to serialize event: {e}"))?;
+        serde_json::to_string(&request).map_err(|e| format!("failed to serialize event: {e}"))?;
 
     // Determine how to run the handler
     let (program, args) = determine_executor(handler_path)?;
 
-    // Spawn process
+    // Spawn process; dropping it on timeout kills it
     let mut child = Command::new(&program)
         .args(&args)
         .current_dir(handler_path.parent().unwrap_or_else(|| Path::new(".")))
+        .env("BEACON_HOOK_PROTOCOL", PROTOCOL_VERSION.to_string())
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
+        .kill_on_drop(true)
         .spawn()
         .map_err(|e| format!("failed to spawn hook: {e}"))?;
 
-    // Write event to stdin
+    // Write event to stdin, then close it so the handler sees EOF
     if let Some(mut stdin) = child.stdin.take() {
         stdin
             .write_all(event_json.as_bytes())
@@ -49,15 +81,21 @@ pub async fn execute_hook(
     }
 
     // Wait for completion with timeout
-    let output = timeout(timeout_duration, child.wait_with_output())
-        .await
-        .map_err(|_| format!("hook timed out after {timeout_duration:?}"))?
-        .map_err(|e| format!("hook execution failed: {e}"))?;
-
-    // Log stderr if present
-    if !output.stderr.is_empty() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        tracing::debug!(hook = %handler_path.display(), stderr = %stderr, "hook stderr");
+    let Ok(output) = timeout(timeout_duration, child.wait_with_output()).await else {
+        tracing::warn!(
+            hook = %handler_path.display(),
+            timeout = ?timeout_duration,
+            "hook timed out and was killed, ignoring"
+        );
+        return Ok(HookResult::default());
+    };
+    let output = output.map_err(|e| format!("hook execution failed: {e}"))?;
+
+    // Forward stderr to tracing
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        if !line.trim().is_empty() {
+            tracing::debug!(hook = %handler_path.display(), "{line}");
+        }
     }
 
     // Check exit status
@@ -73,7 +111,14 @@ pub async fn execute_hook(
         return Ok(HookResult::default());
     }
 
-    serde_json::from_str(&stdout).map_err(|e| format!("failed to parse hook output: {e}"))
+    Ok(serde_json::from_str(&stdout).unwrap_or_else(|e| {
+        tracing::warn!(
+            hook = %handler_path.display(),
+            error = %e,
+            "hook output is not a valid HookResult, ignoring"
+        );
+        HookResult::default()
+    }))
 }
 
 /// Determine how to execute the handler based on extension
@@ -94,6 +139,7 @@ fn determine_executor(handler_path: &Path) -> Result<(String, Vec<String>), Stri
         "ts" => Ok(("bun".to_string(), vec!["run".to_string(), path_str])),
         "rb" => Ok(("ruby".to_string(), vec![path_str])),
         "sh" => Ok(("bash".to_string(), vec![path_str])),
+        "wasm" => Ok(("wasmtime".to_string(), vec!["run".to_string(), path_str])),
         "" => {
             // No extension, assume executable binary or script with shebang
             Ok((path_str, vec![]))
@@ -106,6 +152,28 @@ fn determine_executor(handler_path: &Path) -> Result<(String, Vec<String>), Stri
 mod tests {
     use super::*;
 
+    fn event() -> HookEvent {
+        HookEvent {
+            action: "response:generated".to_string(),
+            channel: "test".to_string(),
+            channel_id: "ch1".to_string(),
+            message_id: "msg1".to_string(),
+            sender_id: "user1".to_string(),
+            sender_name: "Test".to_string(),
+            content: "hello".to_string(),
+            thread_id: None,
+            session_id: None,
+            response: Some("hi there".to_string()),
+            context: std::collections::HashMap::new(),
+        }
+    }
+
+    fn write_hook(dir: &Path, script: &str) -> std::path::PathBuf {
+        let path = dir.join("handler.sh");
+        std::fs::write(&path, script).unwrap();
+        path
+    }
+
     #[test]
     fn test_determine_executor_python() {
         let path = Path::new("/hooks/my-hook/handler.py");
@@ -129,4 +197,58 @@ mod tests {
         assert_eq!(prog, "bun");
         assert_eq!(args, vec!["run", "/hooks/my-hook/handler.ts"]);
     }
+
+    #[test]
+    fn test_determine_executor_wasm() {
+        let path = Path::new("/hooks/my-hook/handler.wasm");
+        let (prog, args) = determine_executor(path).unwrap();
+        assert_eq!(prog, "wasmtime");
+        assert_eq!(args, vec!["run", "/hooks/my-hook/handler.wasm"]);
+    }
+
+    #[tokio::test]
+    async fn test_shell_hook_follows_protocol() {
+        let dir = tempfile::TempDir::new().unwrap();
+        // Appends a disclaimer when it receives a protocol v1 event
+        let handler = write_hook(
+            dir.path(),
+            r#"input=$(cat)
+echo "got $BEACON_HOOK_PROTOCOL" >&2
+case "$input" in
+  *'"protocol":1'*'"response":"hi there"'*)
+    echo '{"modified_response": "hi there (automated reply)"}' ;;
+  *) echo '{}' ;;
+esac
+"#,
+        );
+
+        let result = execute_hook(&handler, &event(), None).await.unwrap();
+        assert_eq!(
+            result.modified_response.as_deref(),
+            Some("hi there (automated reply)")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_hook_is_noop() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let handler = write_hook(dir.path(), "sleep 5\necho '{\"skip_processing\": true}'\n");
+
+        let started = std::time::Instant::now();
+        let result = execute_hook(&handler, &event(), Some(Duration::from_millis(200)))
+            .await
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(4));
+        assert!(!result.skip_processing);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_output_is_noop() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let handler = write_hook(dir.path(), "cat >/dev/null\necho 'not json'\n");
+
+        let result = execute_hook(&handler, &event(), None).await.unwrap();
+        assert!(result.modified_response.is_none());
+        assert!(!result.skip_processing);
+    }
 }
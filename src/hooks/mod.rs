@@ -14,6 +14,7 @@ pub use types::{HookAction, HookEvent, HookResult};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use loader::DiscoveredHook;
 
@@ -28,6 +29,9 @@ pub struct HooksConfig {
     /// Auto-reply rules
     #[serde(default)]
     pub auto_reply: Vec<AutoReplyRule>,
+    /// Seconds an external hook may run before it is killed (default: 5)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 const fn default_true() -> bool {
@@ -39,6 +43,7 @@ pub struct HookManager {
     enabled: bool,
     auto_reply: auto_reply::AutoReplyHandler,
     external_hooks: HashMap<HookAction, Vec<Arc<DiscoveredHook>>>,
+    hook_timeout: Duration,
 }
 
 impl HookManager {
@@ -51,6 +56,7 @@ impl HookManager {
                 enabled: false,
                 auto_reply: auto_reply::AutoReplyHandler::new(&[]),
                 external_hooks: HashMap::new(),
+                hook_timeout: executor::DEFAULT_TIMEOUT,
             };
         }
 
@@ -88,12 +94,19 @@ impl HookManager {
             enabled: true,
             auto_reply,
             external_hooks,
+            hook_timeout: config
+                .timeout_secs
+                .map_or(executor::DEFAULT_TIMEOUT, Duration::from_secs),
         }
     }
 
     /// Trigger hooks for an event
     ///
-    /// Runs auto-reply first, then external hooks in discovery order
+    /// Runs auto-reply first, then external hooks in discovery order. Auto-reply
+    /// only matches `message:received` and `message:before_agent`, so replies it
+    /// sends never reach `response:generated`, which fires for agent replies
+    /// after `message:after_agent`. A result with `skip_processing` stops the
+    /// remaining hooks; for `response:generated` it also suppresses the reply.
     pub async fn trigger(&self, event: &HookEvent) -> HookResult {
         if !self.enabled {
             return HookResult::default();
@@ -124,7 +137,9 @@ impl HookManager {
 
         if let Some(hooks) = self.external_hooks.get(&action) {
             for hook in hooks {
-                match executor::execute_hook(&hook.handler_path, event, None).await {
+                match executor::execute_hook(&hook.handler_path, event, Some(self.hook_timeout))
+                    .await
+                {
                     Ok(hook_result) => {
                         tracing::debug!(
                             hook = %hook.name,
@@ -205,6 +220,7 @@ mod tests {
         let config = HooksConfig {
             enabled: true,
             path: None,
+            timeout_secs: None,
             auto_reply: vec![AutoReplyRule {
                 pattern: "^/ping$".to_string(),
                 reply: "pong".to_string(),
@@ -234,5 +250,31 @@ mod tests {
         let result = manager.trigger(&event).await;
         assert_eq!(result.reply, Some("pong".to_string()));
         assert!(result.skip_agent);
+
+        // Auto-reply does not fire on generated responses
+        let event = HookEvent {
+            action: HookAction::ResponseGenerated.as_str().to_string(),
+            response: Some("agent reply".to_string()),
+            ..event
+        };
+        let result = manager.trigger(&event).await;
+        assert!(result.reply.is_none());
+        assert!(result.modified_response.is_none());
+    }
+
+    #[test]
+    fn test_response_generated_action_and_result() {
+        assert_eq!(
+            HookAction::from_str("response:generated"),
+            Some(HookAction::ResponseGenerated)
+        );
+
+        // Hooks may rewrite the reply via `response` or `modified_response`
+        let result: HookResult =
+            serde_json::from_str(r#"{"response": "reply\n\n_Not financial advice._"}"#).unwrap();
+        assert_eq!(
+            result.modified_response.as_deref(),
+            Some("reply\n\n_Not financial advice._")
+        );
     }
 }
@@ -10,8 +10,10 @@ pub enum HookAction {
     MessageReceived,
     /// After context built, before agent call
     BeforeAgent,
-    /// After agent response, before send
+    /// After agent response, before it is stored
     AfterAgent,
+    /// Final response text, after `after_agent` hooks and right before send
+    ResponseGenerated,
 }
 
 impl HookAction {
@@ -23,6 +25,7 @@ impl HookAction {
             "message:received" | "message" => Some(Self::MessageReceived),
             "message:before_agent" => Some(Self::BeforeAgent),
             "message:after_agent" => Some(Self::AfterAgent),
+            "response:generated" => Some(Self::ResponseGenerated),
             _ => None,
         }
     }
@@ -34,6 +37,7 @@ impl HookAction {
             Self::MessageReceived => "message:received",
             Self::BeforeAgent => "message:before_agent",
             Self::AfterAgent => "message:after_agent",
+            Self::ResponseGenerated => "response:generated",
         }
     }
 }
@@ -61,7 +65,7 @@ pub struct HookEvent {
     /// Session ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_id: Option<String>,
-    /// Agent response (only for `after_agent`)
+    /// Agent response (only for `after_agent` and `response:generated`)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response: Option<String>,
     /// Additional context
@@ -95,7 +99,7 @@ impl HookEvent {
         self
     }
 
-    /// Set agent response (for `after_agent` events)
+    /// Set agent response (for `after_agent` and `response:generated` events)
     #[must_use]
     pub fn with_response(mut self, response: &str) -> Self {
         self.response = Some(response.to_string());
@@ -122,8 +126,8 @@ pub struct HookResult {
     /// Direct reply to send (bypasses or replaces agent)
     #[serde(default)]
     pub reply: Option<String>,
-    /// Modified response (for `after_agent` hooks)
-    #[serde(default)]
+    /// Modified response (for `after_agent` and `response:generated` hooks)
+    #[serde(default, alias = "response")]
     pub modified_response: Option<String>,
     /// Messages to log/display
     #[serde(default)]
@@ -4,4 +4,4 @@ pub mod trellis;
 mod vortex;
 
 pub use trellis::TrellisClient;
-pub use vortex::{Schedule, ScheduleRequest, VortexClient};
+pub use vortex::{Schedule, ScheduleRequest, VortexClient, VortexError, VortexResult};
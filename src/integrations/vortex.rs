@@ -1,13 +1,64 @@
 //! Vortex scheduling service integration
 //!
 //! Client for interacting with the Vortex scheduling service to create,
-//! list, and cancel scheduled workflows
+//! list, and cancel scheduled workflows. Creation sends an `Idempotency-Key`
+//! derived from the request, so transient failures are retried without
+//! producing duplicate schedules.
+
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Attempts made for a request before giving up on transient failures
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubles on each subsequent attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Per-request timeout
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors returned by the Vortex API
+#[derive(Debug, thiserror::Error)]
+pub enum VortexError {
+    /// An identical schedule already exists
+    #[error("schedule already exists{}", .id.as_deref().map(|id| format!(": {id}")).unwrap_or_default())]
+    AlreadyExists {
+        /// ID of the existing schedule, when Vortex reports it
+        id: Option<String>,
+    },
+
+    /// The schedule does not exist
+    #[error("schedule not found: {0}")]
+    NotFound(String),
+
+    /// Vortex answered with an error status
+    #[error("Vortex API error: {status} - {body}")]
+    Api { status: StatusCode, body: String },
+
+    /// The request could not be completed
+    #[error("Vortex request failed: {0}")]
+    Transport(#[from] reqwest::Error),
+}
+
+impl VortexError {
+    /// Whether the failure is worth retrying
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Api { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            Self::Transport(e) => e.is_timeout() || e.is_connect(),
+            Self::AlreadyExists { .. } | Self::NotFound(_) => false,
+        }
+    }
+}
 
-use crate::{Error, Result};
+/// Result type for Vortex calls
+pub type VortexResult<T> = std::result::Result<T, VortexError>;
 
 /// Client for the Vortex scheduling service
 #[derive(Debug, Clone)]
@@ -18,6 +69,8 @@ pub struct VortexClient {
     base_url: String,
     /// Optional API key for authentication
     api_key: Option<String>,
+    /// Delay before the first retry
+    retry_delay: Duration,
 }
 
 impl VortexClient {
@@ -30,43 +83,91 @@ impl VortexClient {
     #[must_use]
     pub fn new(base_url: impl Into<String>, api_key: Option<String>) -> Self {
         Self {
-            client: Client::new(),
+            client: Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
             base_url: base_url.into(),
             api_key,
+            retry_delay: RETRY_BASE_DELAY,
         }
     }
 
+    /// Override the delay before the first retry
+    #[must_use]
+    pub const fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
     /// Build the authorization header value
     fn auth_header(&self) -> Option<String> {
         self.api_key.as_ref().map(|key| format!("Bearer {key}"))
     }
 
-    /// Schedule a new workflow
-    ///
-    /// # Errors
+    /// Send a request, retrying transient failures with exponential backoff
     ///
-    /// Returns an error if the request fails or the response is invalid
-    pub async fn schedule(&self, request: &ScheduleRequest) -> Result<Schedule> {
-        let url = format!("{}/schedules", self.base_url);
-
-        let mut req = self.client.post(&url).json(request);
-
-        if let Some(auth) = self.auth_header() {
-            req = req.header("Authorization", auth);
+    /// Non-success statuses are converted to [`VortexError`]s.
+    async fn send(&self, build: impl Fn() -> RequestBuilder) -> VortexResult<Response> {
+        let mut attempt = 1;
+        loop {
+            let mut req = build();
+            if let Some(auth) = self.auth_header() {
+                req = req.header("Authorization", auth);
+            }
+
+            let err = match req.send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => Self::error_from(response).await,
+                Err(e) => VortexError::Transport(e),
+            };
+
+            if attempt >= MAX_ATTEMPTS || !err.is_transient() {
+                return Err(err);
+            }
+            let delay = self.retry_delay.saturating_mul(2u32.pow(attempt - 1));
+            tracing::warn!(attempt, error = %err, ?delay, "Vortex request failed, retrying");
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
+    }
 
-        let response = req.send().await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(Error::Channel(format!(
-                "Vortex API error: {status} - {body}"
-            )));
+    /// Convert an error response into a typed error
+    async fn error_from(response: Response) -> VortexError {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        match status {
+            StatusCode::CONFLICT => VortexError::AlreadyExists {
+                id: serde_json::from_str::<Schedule>(&body).ok().map(|s| s.id),
+            },
+            StatusCode::NOT_FOUND => VortexError::NotFound(body),
+            _ => VortexError::Api { status, body },
         }
+    }
 
-        let schedule = response.json().await?;
-        Ok(schedule)
+    /// Schedule a new workflow
+    ///
+    /// Sends [`ScheduleRequest::idempotency_key`] so a retried creation is
+    /// deduplicated by Vortex.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VortexError::AlreadyExists`] if Vortex already holds this
+    /// schedule, or another error if the request fails or the response is invalid
+    pub async fn schedule(&self, request: &ScheduleRequest) -> VortexResult<Schedule> {
+        let url = format!("{}/schedules", self.base_url);
+        let key = request.idempotency_key();
+
+        let response = self
+            .send(|| {
+                self.client
+                    .post(&url)
+                    .header("Idempotency-Key", &key)
+                    .json(request)
+            })
+            .await?;
+
+        Ok(response.json().await?)
     }
 
     /// List all schedules
@@ -74,53 +175,21 @@ impl VortexClient {
     /// # Errors
     ///
     /// Returns an error if the request fails or the response is invalid
-    pub async fn list_schedules(&self) -> Result<Vec<Schedule>> {
+    pub async fn list_schedules(&self) -> VortexResult<Vec<Schedule>> {
         let url = format!("{}/schedules", self.base_url);
-
-        let mut req = self.client.get(&url);
-
-        if let Some(auth) = self.auth_header() {
-            req = req.header("Authorization", auth);
-        }
-
-        let response = req.send().await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(Error::Channel(format!(
-                "Vortex API error: {status} - {body}"
-            )));
-        }
-
-        let schedules = response.json().await?;
-        Ok(schedules)
+        let response = self.send(|| self.client.get(&url)).await?;
+        Ok(response.json().await?)
     }
 
-    /// Cancel a schedule
+    /// Delete a schedule
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails
-    pub async fn cancel_schedule(&self, id: &str) -> Result<()> {
+    /// Returns [`VortexError::NotFound`] if the schedule does not exist, or
+    /// another error if the request fails
+    pub async fn delete_schedule(&self, id: &str) -> VortexResult<()> {
         let url = format!("{}/schedules/{id}", self.base_url);
-
-        let mut req = self.client.delete(&url);
-
-        if let Some(auth) = self.auth_header() {
-            req = req.header("Authorization", auth);
-        }
-
-        let response = req.send().await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(Error::Channel(format!(
-                "Vortex API error: {status} - {body}"
-            )));
-        }
-
+        self.send(|| self.client.delete(&url)).await?;
         Ok(())
     }
 
@@ -128,28 +197,12 @@ impl VortexClient {
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or schedule not found
-    pub async fn get_schedule(&self, id: &str) -> Result<Schedule> {
+    /// Returns [`VortexError::NotFound`] if the schedule does not exist, or
+    /// another error if the request fails or the response is invalid
+    pub async fn get_schedule(&self, id: &str) -> VortexResult<Schedule> {
         let url = format!("{}/schedules/{id}", self.base_url);
-
-        let mut req = self.client.get(&url);
-
-        if let Some(auth) = self.auth_header() {
-            req = req.header("Authorization", auth);
-        }
-
-        let response = req.send().await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(Error::Channel(format!(
-                "Vortex API error: {status} - {body}"
-            )));
-        }
-
-        let schedule = response.json().await?;
-        Ok(schedule)
+        let response = self.send(|| self.client.get(&url)).await?;
+        Ok(response.json().await?)
     }
 }
 
@@ -208,10 +261,135 @@ const fn default_active() -> bool {
     true
 }
 
+impl ScheduleRequest {
+    /// Key identifying this request's content, stable across retries
+    #[must_use]
+    pub fn idempotency_key(&self) -> String {
+        let canonical = serde_json::to_vec(self).unwrap_or_default();
+        hex::encode(Sha256::digest(&canonical))
+    }
+
+    /// Whether an existing schedule was created from an identical request
+    #[must_use]
+    pub fn matches(&self, schedule: &Schedule) -> bool {
+        schedule.cron == self.cron
+            && schedule.callback_url == self.callback_url
+            && schedule.action == self.action
+            && schedule.payload == self.payload
+            && schedule.timezone == self.timezone
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{Json, Router, http::HeaderMap, routing::post};
+
     use super::*;
 
+    fn request() -> ScheduleRequest {
+        ScheduleRequest {
+            cron: "0 9 * * MON".to_string(),
+            callback_url: "http://localhost:8080/webhooks/vortex".to_string(),
+            action: "remind".to_string(),
+            payload: serde_json::json!({ "message": "Weekly standup" }),
+            description: None,
+            timezone: None,
+        }
+    }
+
+    fn schedule_json(id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "cron": "0 9 * * MON",
+            "callback_url": "http://localhost:8080/webhooks/vortex",
+            "action": "remind",
+            "payload": { "message": "Weekly standup" },
+            "created_at": "2024-01-01T00:00:00Z"
+        })
+    }
+
+    /// Serve `/schedules`, failing with `failures` 503s before answering with `final_status`
+    async fn serve(
+        failures: usize,
+        final_status: StatusCode,
+    ) -> (
+        VortexClient,
+        Arc<AtomicUsize>,
+        Arc<std::sync::Mutex<Vec<String>>>,
+    ) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let keys = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (counter, seen) = (Arc::clone(&calls), Arc::clone(&keys));
+        let app = Router::new().route(
+            "/schedules",
+            post(move |headers: HeaderMap| {
+                let (counter, seen) = (Arc::clone(&counter), Arc::clone(&seen));
+                async move {
+                    if let Some(key) = headers.get("idempotency-key") {
+                        seen.lock().unwrap().push(key.to_str().unwrap().to_string());
+                    }
+                    if counter.fetch_add(1, Ordering::SeqCst) < failures {
+                        return (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({})));
+                    }
+                    (final_status, Json(schedule_json("sched_1")))
+                }
+            }),
+        );
+        let base = crate::test_support::serve(app).await;
+
+        let client = VortexClient::new(base, None).with_retry_delay(Duration::from_millis(1));
+        (client, calls, keys)
+    }
+
+    #[tokio::test]
+    async fn schedule_retries_server_errors_with_same_key() {
+        let (client, calls, keys) = serve(2, StatusCode::CREATED).await;
+
+        let schedule = client.schedule(&request()).await.unwrap();
+
+        assert_eq!(schedule.id, "sched_1");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        let keys = keys.lock().unwrap();
+        assert!(keys.iter().all(|k| *k == request().idempotency_key()));
+    }
+
+    #[tokio::test]
+    async fn schedule_gives_up_after_max_attempts() {
+        let (client, calls, _) = serve(5, StatusCode::CREATED).await;
+
+        let err = client.schedule(&request()).await.unwrap_err();
+
+        assert!(
+            matches!(err, VortexError::Api { status, .. } if status == StatusCode::SERVICE_UNAVAILABLE)
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), MAX_ATTEMPTS as usize);
+    }
+
+    #[tokio::test]
+    async fn conflict_is_already_exists() {
+        let (client, calls, _) = serve(0, StatusCode::CONFLICT).await;
+
+        let err = client.schedule(&request()).await.unwrap_err();
+
+        assert!(matches!(err, VortexError::AlreadyExists { id: Some(ref id) } if id == "sched_1"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn idempotency_key_follows_content() {
+        let mut other = request();
+        assert_eq!(request().idempotency_key(), other.idempotency_key());
+        other.cron = "0 10 * * MON".to_string();
+        assert_ne!(request().idempotency_key(), other.idempotency_key());
+
+        let existing: Schedule = serde_json::from_value(schedule_json("sched_1")).unwrap();
+        assert!(request().matches(&existing));
+        assert!(!other.matches(&existing));
+    }
+
     #[test]
     fn test_schedule_request_serialization() {
         let request = ScheduleRequest {
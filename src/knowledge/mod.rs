@@ -2,8 +2,40 @@
 //!
 //! Re-exports shared infrastructure from agent-core
 
+use std::path::PathBuf;
+
 pub use agent_core::knowledge::{
     KnowledgePackResolver, ResolverError, build_knowledge_context, build_retrieval_query,
     cosine_similarity, format_knowledge, hydrate_embeddings, resolve_and_merge, select_knowledge,
     select_knowledge_with_embeddings,
 };
+
+use crate::persona::{KnowledgeChunk, Persona};
+
+/// Resolve a persona's knowledge packs and merge them after its inline chunks
+///
+/// Packs that fail to resolve are logged and skipped.
+pub async fn load_persona_knowledge(
+    persona: &Persona,
+    manifold_url: &str,
+    cache_dir: PathBuf,
+) -> Vec<KnowledgeChunk> {
+    let mut chunks = persona.knowledge.inline.clone();
+    if persona.knowledge.packs.is_empty() {
+        return chunks;
+    }
+
+    let resolver = KnowledgePackResolver::new(manifold_url, cache_dir);
+    for result in resolver.resolve_all(&persona.knowledge.packs).await {
+        match result {
+            Ok(pack) => {
+                tracing::info!(name = %pack.name, chunks = pack.chunks.len(), "loaded knowledge pack");
+                chunks.extend(pack.chunks);
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to resolve knowledge pack");
+            }
+        }
+    }
+    chunks
+}
@@ -44,6 +44,7 @@ pub mod integrations;
 pub mod knowledge;
 pub mod lifecycle;
 pub mod links;
+pub mod maintenance;
 pub mod mcp;
 pub mod media;
 pub mod nodes;
@@ -51,12 +52,18 @@ pub mod persona;
 pub mod plugins;
 pub mod prompt;
 pub mod providers;
+pub mod readiness;
 pub mod relay;
+pub mod request_id;
 pub mod security;
 pub mod setup;
 pub mod skills;
+pub mod synapse;
 pub mod sync;
+#[cfg(test)]
+mod test_support;
 pub mod tools;
+pub mod usage;
 pub mod voice;
 
 /// Sentinel persona ID indicating no persona should be applied
@@ -71,23 +78,26 @@ pub use discovery::MdnsAdvertiser;
 pub use error::{Error, Result};
 pub use extensions::{Extension, ExtensionInfo, ExtensionRegistry};
 pub use hooks::{HookAction, HookEvent, HookManager, HookResult, HooksConfig};
-pub use integrations::{Schedule, ScheduleRequest, TrellisClient, VortexClient};
+pub use integrations::{Schedule, ScheduleRequest, TrellisClient, VortexClient, VortexError};
 pub use knowledge::{
     KnowledgePackResolver, ResolverError, cosine_similarity, format_knowledge, hydrate_embeddings,
     select_knowledge, select_knowledge_with_embeddings,
 };
+pub use maintenance::{MaintenanceConfig, MaintenanceMode};
 pub use mcp::{McpServerConfig, McpServerManager};
 pub use persona::{
     KnowledgeChunk, KnowledgeConfig, KnowledgePack, KnowledgePackRef, KnowledgePriority,
-    PackEmbeddings, Persona,
+    PackEmbeddings, Persona, ValidationError,
 };
 pub use plugins::{PluginKind, PluginManager, PluginManifest};
 pub use providers::KeyResolver;
+pub use readiness::{Readiness, ReadinessComponent, ReadinessConfig};
 pub use relay::{RelayConfig, RelayManager, RelayMode, RelayStatus};
-pub use security::{DmPolicy, PairedUser, PairingManager};
+pub use security::{DmPolicy, PairedUser, PairingError, PairingLockout, PairingManager};
 pub use skills::{Skill, SkillMetadata, SkillRegistry, SkillSource};
 pub use sync::SyncClient;
 pub use tools::{
     SearchProvider, SearchResult, ToolPolicy, ToolPolicyConfig, ToolProfile, WebFetchTool,
     WebResponse, WebSearchTool,
 };
+pub use usage::{UsageCap, UsageCapConfig, UsageDecision};
@@ -1,5 +1,7 @@
 //! Link processing configuration
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Configuration for link processing
@@ -12,6 +14,14 @@ pub struct LinkConfig {
     pub max_urls: usize,
     /// Request timeout in seconds
     pub timeout_secs: u64,
+    /// Custom oEmbed endpoints keyed by domain (e.g. `"example.com"`);
+    /// these take precedence over the built-in providers
+    pub oembed_providers: HashMap<String, String>,
+    /// CIDR ranges that are never fetched; defaults to loopback, RFC 1918,
+    /// link-local, and cloud metadata ranges
+    pub blocked_ranges: Vec<String>,
+    /// Skip URLs disallowed for Beacon by the site's robots.txt
+    pub respect_robots_txt: bool,
 }
 
 impl Default for LinkConfig {
@@ -20,6 +30,31 @@ impl Default for LinkConfig {
             enabled: false,
             max_urls: 3,
             timeout_secs: 10,
+            oembed_providers: HashMap::new(),
+            blocked_ranges: super::guard::DEFAULT_BLOCKED_RANGES
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            respect_robots_txt: false,
+        }
+    }
+}
+
+impl LinkConfig {
+    /// Load link preview settings from environment variables
+    ///
+    /// Reads `BEACON_LINK_PREVIEWS` (`true` to enable) and
+    /// `BEACON_LINK_MAX_URLS`; everything else keeps its default.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: std::env::var("BEACON_LINK_PREVIEWS").is_ok_and(|v| v == "true" || v == "1"),
+            max_urls: std::env::var("BEACON_LINK_MAX_URLS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.max_urls),
+            ..defaults
         }
     }
 }
@@ -0,0 +1,209 @@
+//! SSRF protection for link fetching
+//!
+//! Rejects URLs whose host is, or resolves to, a blocked address range. The
+//! same check is installed as the HTTP client's DNS resolver so redirects and
+//! DNS rebinding cannot reach internal addresses after the initial check.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use ipnet::IpNet;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+use crate::{Error, Result};
+
+/// Address ranges blocked by default
+pub const DEFAULT_BLOCKED_RANGES: &[&str] = &[
+    // "This" network and unspecified
+    "0.0.0.0/8",
+    "::/128",
+    // Loopback
+    "127.0.0.0/8",
+    "::1/128",
+    // RFC 1918 private networks
+    "10.0.0.0/8",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    // Carrier-grade NAT (includes Alibaba Cloud metadata at 100.100.100.200)
+    "100.64.0.0/10",
+    // Link-local (includes AWS/GCP/Azure metadata at 169.254.169.254)
+    "169.254.0.0/16",
+    "fe80::/10",
+    // IPv6 unique local (includes AWS metadata at fd00:ec2::254)
+    "fc00::/7",
+];
+
+/// Set of blocked address ranges
+#[derive(Debug, Clone)]
+pub struct AddressGuard {
+    ranges: Arc<[IpNet]>,
+}
+
+impl AddressGuard {
+    /// Build a guard from CIDR strings, skipping (and logging) invalid entries
+    #[must_use]
+    pub fn new(ranges: &[String]) -> Self {
+        let ranges = ranges
+            .iter()
+            .filter_map(|range| match range.parse::<IpNet>() {
+                Ok(net) => Some(net),
+                Err(e) => {
+                    tracing::warn!(range, error = %e, "ignoring invalid blocked range");
+                    None
+                }
+            })
+            .collect();
+        Self { ranges }
+    }
+
+    /// Whether `ip` falls in a blocked range
+    #[must_use]
+    pub fn is_blocked(&self, ip: IpAddr) -> bool {
+        // Check IPv4-mapped IPv6 addresses against the IPv4 ranges too
+        let mapped = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4),
+            IpAddr::V4(_) => None,
+        };
+        self.ranges
+            .iter()
+            .any(|net| net.contains(&ip) || mapped.is_some_and(|v4| net.contains(&v4)))
+    }
+
+    /// HTTP client builder that refuses blocked addresses
+    ///
+    /// Resolved hosts are filtered by the guard; redirects to literal IPs
+    /// bypass DNS, so those are checked in the redirect policy.
+    #[must_use]
+    pub fn client_builder(&self, max_redirects: usize) -> reqwest::ClientBuilder {
+        let redirect_guard = self.clone();
+        let redirect = reqwest::redirect::Policy::custom(move |attempt| {
+            let blocked = match attempt.url().host() {
+                Some(url::Host::Ipv4(ip)) => redirect_guard.is_blocked(IpAddr::V4(ip)),
+                Some(url::Host::Ipv6(ip)) => redirect_guard.is_blocked(IpAddr::V6(ip)),
+                _ => false,
+            };
+            if blocked {
+                attempt.error("redirect to blocked address")
+            } else if attempt.previous().len() >= max_redirects {
+                attempt.error("too many redirects")
+            } else {
+                attempt.follow()
+            }
+        });
+
+        reqwest::Client::builder()
+            .dns_resolver(Arc::new(self.clone()))
+            .redirect(redirect)
+    }
+
+    /// Validate a URL before fetching it
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Link` for non-HTTP schemes, missing hosts, unresolvable
+    /// hosts, or hosts that resolve to a blocked address
+    pub async fn check_url(&self, url: &str) -> Result<()> {
+        let parsed = url::Url::parse(url).map_err(|e| Error::Link(format!("Invalid URL: {e}")))?;
+
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(Error::Link(format!(
+                "Unsupported URL scheme: {}",
+                parsed.scheme()
+            )));
+        }
+
+        let host = parsed
+            .host()
+            .ok_or_else(|| Error::Link("URL has no host".to_string()))?;
+        let port = parsed.port_or_known_default().unwrap_or(80);
+
+        let addrs: Vec<IpAddr> = match host {
+            url::Host::Ipv4(ip) => vec![IpAddr::V4(ip)],
+            url::Host::Ipv6(ip) => vec![IpAddr::V6(ip)],
+            url::Host::Domain(domain) => tokio::net::lookup_host((domain, port))
+                .await
+                .map_err(|e| Error::Link(format!("Failed to resolve {domain}: {e}")))?
+                .map(|addr| addr.ip())
+                .collect(),
+        };
+
+        if let Some(ip) = addrs.into_iter().find(|ip| self.is_blocked(*ip)) {
+            return Err(Error::Link(format!("Blocked address {ip} for {url}")));
+        }
+
+        Ok(())
+    }
+}
+
+impl Resolve for AddressGuard {
+    fn resolve(&self, name: Name) -> Resolving {
+        let guard = self.clone();
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| !guard.is_blocked(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{host} resolves only to blocked addresses").into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_guard() -> AddressGuard {
+        let ranges: Vec<String> = DEFAULT_BLOCKED_RANGES
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        AddressGuard::new(&ranges)
+    }
+
+    #[test]
+    fn default_ranges_block_internal_addresses() {
+        let guard = default_guard();
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.20.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.100.100.200",
+            "::1",
+            "fd00:ec2::254",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(
+                guard.is_blocked(ip.parse().unwrap()),
+                "{ip} should be blocked"
+            );
+        }
+        assert!(!guard.is_blocked("93.184.216.34".parse().unwrap()));
+        assert!(!guard.is_blocked("2606:4700::1111".parse().unwrap()));
+    }
+
+    #[test]
+    fn invalid_ranges_are_skipped() {
+        let guard = AddressGuard::new(&["not-a-cidr".to_string(), "10.0.0.0/8".to_string()]);
+        assert!(guard.is_blocked("10.0.0.1".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn check_url_rejects_literal_blocked_hosts_and_bad_schemes() {
+        let guard = default_guard();
+        assert!(
+            guard
+                .check_url("http://169.254.169.254/latest/meta-data")
+                .await
+                .is_err()
+        );
+        assert!(guard.check_url("http://[::1]:8080/").await.is_err());
+        assert!(guard.check_url("file:///etc/passwd").await.is_err());
+        assert!(guard.check_url("http://93.184.216.34/").await.is_ok());
+    }
+}
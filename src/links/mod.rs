@@ -1,12 +1,18 @@
 //! Link understanding for URL preview extraction
 //!
-//! Detects URLs in messages and extracts Open Graph / Twitter Card metadata
+//! Detects URLs in messages and extracts Open Graph / Twitter Card metadata,
+//! falling back to oEmbed for sites that gate it. Internal addresses are never
+//! fetched (see [`LinkConfig::blocked_ranges`]).
 
 mod config;
 mod detector;
+mod guard;
+mod oembed;
+mod robots;
 
 pub use config::LinkConfig;
 pub use detector::detect_urls;
+pub use guard::DEFAULT_BLOCKED_RANGES;
 
 use std::num::NonZeroUsize;
 use std::sync::Arc;
@@ -17,8 +23,13 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
+pub(crate) use self::guard::AddressGuard;
+use self::robots::RobotsRules;
 use crate::{Error, Result};
 
+/// Maximum redirects followed per request
+const MAX_REDIRECTS: usize = 10;
+
 /// Extracted link preview metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LinkPreview {
@@ -41,10 +52,22 @@ pub struct LinkPreview {
     pub favicon_url: Option<String>,
 }
 
+impl LinkPreview {
+    /// Preview image as a channel attachment (for [`crate::channels::Channel::send_media`])
+    #[must_use]
+    pub fn image_attachment(&self) -> Option<crate::channels::Attachment> {
+        self.image_url
+            .as_deref()
+            .and_then(|url| crate::channels::Attachment::image(url, None))
+    }
+}
+
 /// Link processor with caching
 pub struct LinkProcessor {
     client: Client,
     cache: Arc<Mutex<LruCache<String, LinkPreview>>>,
+    robots_cache: Arc<Mutex<LruCache<String, RobotsRules>>>,
+    guard: AddressGuard,
     config: LinkConfig,
 }
 
@@ -57,13 +80,18 @@ impl LinkProcessor {
     #[must_use]
     pub fn new(config: LinkConfig) -> Self {
         let cache_size = NonZeroUsize::new(100).expect("100 is non-zero");
+        let guard = AddressGuard::new(&config.blocked_ranges);
+
         Self {
-            client: Client::builder()
+            client: guard
+                .client_builder(MAX_REDIRECTS)
                 .timeout(Duration::from_secs(config.timeout_secs))
                 .user_agent("Mozilla/5.0 (compatible; BeaconBot/1.0)")
                 .build()
                 .expect("failed to build HTTP client"),
             cache: Arc::new(Mutex::new(LruCache::new(cache_size))),
+            robots_cache: Arc::new(Mutex::new(LruCache::new(cache_size))),
+            guard,
             config,
         }
     }
@@ -125,7 +153,95 @@ impl LinkProcessor {
     }
 
     /// Fetch preview metadata from URL
+    ///
+    /// Falls back to oEmbed when the page has no Open Graph / Twitter title
+    /// or cannot be fetched at all.
     async fn fetch_preview(&self, url: &str) -> Result<LinkPreview> {
+        let html = match self.fetch_html(url).await {
+            Ok(html) => html,
+            Err(e) => {
+                // Gated sites often reject scrapers outright
+                let Some(endpoint) = oembed::endpoint_for(url, &self.config.oembed_providers)
+                else {
+                    return Err(e);
+                };
+                let mut preview = LinkPreview {
+                    url: url.to_string(),
+                    title: None,
+                    description: None,
+                    image_url: None,
+                    site_name: None,
+                    favicon_url: None,
+                };
+                self.apply_oembed(&mut preview, &endpoint).await?;
+                return Ok(preview);
+            }
+        };
+
+        let mut preview = self.parse_html(url, &html)?;
+
+        if !has_meta_title(&html) {
+            let endpoint = oembed::discover(&html)
+                .or_else(|| oembed::endpoint_for(url, &self.config.oembed_providers));
+            if let Some(endpoint) = endpoint
+                && let Err(e) = self.apply_oembed(&mut preview, &endpoint).await
+            {
+                tracing::debug!(url, error = %e, "oEmbed fallback failed");
+            }
+        }
+
+        Ok(preview)
+    }
+
+    /// Fill `preview` from an oEmbed endpoint
+    ///
+    /// The oEmbed title replaces the `<title>` fallback; existing image and
+    /// site name are kept when oEmbed has none.
+    async fn apply_oembed(&self, preview: &mut LinkPreview, endpoint: &str) -> Result<()> {
+        let request = oembed::request_url(endpoint, &preview.url)
+            .ok_or_else(|| Error::Link(format!("Invalid oEmbed endpoint: {endpoint}")))?;
+        // Discovered endpoints come from page content
+        self.guard.check_url(&request).await?;
+
+        let response = self
+            .client
+            .get(request)
+            .send()
+            .await
+            .map_err(|e| Error::Link(format!("Failed to fetch oEmbed: {e}")))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Link(format!(
+                "oEmbed HTTP error: {}",
+                response.status()
+            )));
+        }
+
+        let data: oembed::OEmbedResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::Link(format!("Invalid oEmbed response: {e}")))?;
+
+        preview.title = data.title.or_else(|| preview.title.take());
+        preview.site_name = data
+            .author_name
+            .or_else(|| preview.site_name.take())
+            .or(data.provider_name);
+        preview.image_url = data.thumbnail_url.or_else(|| preview.image_url.take());
+
+        Ok(())
+    }
+
+    /// Fetch the page HTML
+    ///
+    /// Rejects blocked addresses and, when enabled, paths disallowed by robots.txt.
+    async fn fetch_html(&self, url: &str) -> Result<String> {
+        self.guard.check_url(url).await?;
+
+        if self.config.respect_robots_txt && !self.robots_allowed(url).await {
+            return Err(Error::Link(format!("Disallowed by robots.txt: {url}")));
+        }
+
         let response = self
             .client
             .get(url)
@@ -137,12 +253,38 @@ impl LinkProcessor {
             return Err(Error::Link(format!("HTTP error: {}", response.status())));
         }
 
-        let html = response
+        response
             .text()
             .await
-            .map_err(|e| Error::Link(format!("Failed to read response: {e}")))?;
+            .map_err(|e| Error::Link(format!("Failed to read response: {e}")))
+    }
 
-        self.parse_html(url, &html)
+    /// Check robots.txt for `url`, caching rules per origin
+    ///
+    /// A missing or unreachable robots.txt allows everything.
+    async fn robots_allowed(&self, url: &str) -> bool {
+        let Ok(parsed) = url::Url::parse(url) else {
+            return false;
+        };
+        let origin = parsed.origin().ascii_serialization();
+
+        let cached = self.robots_cache.lock().await.get(&origin).cloned();
+        let rules = if let Some(rules) = cached {
+            rules
+        } else {
+            let rules = match self.client.get(format!("{origin}/robots.txt")).send().await {
+                Ok(resp) if resp.status().is_success() => resp
+                    .text()
+                    .await
+                    .map(|txt| RobotsRules::parse(&txt, robots::USER_AGENT_TOKEN))
+                    .unwrap_or_default(),
+                _ => RobotsRules::default(),
+            };
+            self.robots_cache.lock().await.put(origin, rules.clone());
+            rules
+        };
+
+        rules.is_allowed(parsed.path())
     }
 
     /// Parse HTML for Open Graph and Twitter Card metadata
@@ -229,6 +371,17 @@ impl LinkProcessor {
     }
 }
 
+/// Whether the page declares an Open Graph or Twitter Card title
+fn has_meta_title(html: &str) -> bool {
+    use scraper::{Html, Selector};
+
+    let document = Html::parse_document(html);
+    Selector::parse(
+        r#"meta[property="og:title"], meta[name="og:title"], meta[property="twitter:title"], meta[name="twitter:title"]"#,
+    )
+    .is_ok_and(|selector| document.select(&selector).next().is_some())
+}
+
 impl std::fmt::Debug for LinkProcessor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("LinkProcessor")
@@ -236,3 +389,19 @@ impl std::fmt::Debug for LinkProcessor {
             .finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn has_meta_title_ignores_plain_title_tag() {
+        assert!(has_meta_title(
+            r#"<head><meta property="og:title" content="Hello"></head>"#
+        ));
+        assert!(has_meta_title(
+            r#"<head><meta name="twitter:title" content="Hello"></head>"#
+        ));
+        assert!(!has_meta_title("<head><title>X</title></head>"));
+    }
+}
@@ -0,0 +1,145 @@
+//! oEmbed fallback for sites that gate Open Graph metadata
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Built-in oEmbed endpoints keyed by domain
+const PROVIDERS: &[(&str, &str)] = &[
+    ("youtube.com", "https://www.youtube.com/oembed"),
+    ("youtu.be", "https://www.youtube.com/oembed"),
+    ("twitter.com", "https://publish.twitter.com/oembed"),
+    ("x.com", "https://publish.twitter.com/oembed"),
+    ("vimeo.com", "https://vimeo.com/api/oembed.json"),
+    ("soundcloud.com", "https://soundcloud.com/oembed"),
+    ("open.spotify.com", "https://open.spotify.com/oembed"),
+    ("reddit.com", "https://www.reddit.com/oembed"),
+    ("tiktok.com", "https://www.tiktok.com/oembed"),
+    ("flickr.com", "https://www.flickr.com/services/oembed"),
+];
+
+/// Subset of an oEmbed response used for previews
+#[derive(Debug, Deserialize)]
+pub struct OEmbedResponse {
+    /// Resource title
+    pub title: Option<String>,
+    /// Author or account name
+    pub author_name: Option<String>,
+    /// Provider name (e.g. "YouTube")
+    pub provider_name: Option<String>,
+    /// Thumbnail image URL
+    pub thumbnail_url: Option<String>,
+}
+
+/// Whether `host` is `domain` or one of its subdomains
+fn host_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// Find the oEmbed endpoint for `url`
+///
+/// Custom endpoints (domain to endpoint URL) take precedence over built-ins.
+#[must_use]
+pub fn endpoint_for(url: &str, custom: &HashMap<String, String>) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_ascii_lowercase();
+
+    custom
+        .iter()
+        .map(|(domain, endpoint)| (domain.as_str(), endpoint.as_str()))
+        .chain(PROVIDERS.iter().copied())
+        .find(|(domain, _)| host_matches(&host, &domain.to_ascii_lowercase()))
+        .map(|(_, endpoint)| endpoint.to_string())
+}
+
+/// Build the request URL for `endpoint`, asking for JSON about `url`
+///
+/// Discovered endpoints already carry the `url` query parameter and are
+/// returned unchanged.
+#[must_use]
+pub fn request_url(endpoint: &str, url: &str) -> Option<String> {
+    let mut request = url::Url::parse(endpoint).ok()?;
+    if !request.query_pairs().any(|(key, _)| key == "url") {
+        request
+            .query_pairs_mut()
+            .append_pair("url", url)
+            .append_pair("format", "json");
+    }
+    Some(request.into())
+}
+
+/// Extract the JSON oEmbed discovery link from an HTML document
+#[must_use]
+pub fn discover(html: &str) -> Option<String> {
+    use scraper::{Html, Selector};
+
+    let document = Html::parse_document(html);
+    let selector = Selector::parse(r#"link[type="application/json+oembed"]"#).ok()?;
+    document
+        .select(&selector)
+        .next()
+        .and_then(|el| el.value().attr("href"))
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_for_matches_builtin_domains_and_subdomains() {
+        let custom = HashMap::new();
+        assert_eq!(
+            endpoint_for("https://www.youtube.com/watch?v=abc", &custom).as_deref(),
+            Some("https://www.youtube.com/oembed")
+        );
+        assert_eq!(
+            endpoint_for("https://x.com/user/status/1", &custom).as_deref(),
+            Some("https://publish.twitter.com/oembed")
+        );
+        assert!(endpoint_for("https://notx.com/page", &custom).is_none());
+        assert!(endpoint_for("https://example.com", &custom).is_none());
+    }
+
+    #[test]
+    fn custom_endpoints_take_precedence() {
+        let custom = HashMap::from([(
+            "youtube.com".to_string(),
+            "https://oembed.internal/yt".to_string(),
+        )]);
+        assert_eq!(
+            endpoint_for("https://youtube.com/watch?v=abc", &custom).as_deref(),
+            Some("https://oembed.internal/yt")
+        );
+    }
+
+    #[test]
+    fn request_url_appends_target_unless_discovered() {
+        let built = request_url("https://www.youtube.com/oembed", "https://youtu.be/abc").unwrap();
+        assert!(
+            built.starts_with("https://www.youtube.com/oembed?url=https%3A%2F%2Fyoutu.be%2Fabc")
+        );
+        assert!(built.ends_with("&format=json"));
+
+        let discovered = "https://example.com/oembed?url=https%3A%2F%2Fexample.com%2Fa";
+        assert_eq!(
+            request_url(discovered, "ignored").as_deref(),
+            Some(discovered)
+        );
+    }
+
+    #[test]
+    fn discover_finds_json_link() {
+        let html = r#"<html><head>
+            <link rel="alternate" type="application/json+oembed" href="https://example.com/oembed?url=x">
+        </head></html>"#;
+        assert_eq!(
+            discover(html).as_deref(),
+            Some("https://example.com/oembed?url=x")
+        );
+        assert!(discover("<html></html>").is_none());
+    }
+}
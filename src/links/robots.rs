@@ -0,0 +1,110 @@
+//! Minimal robots.txt parsing for link previews
+
+/// User agent token matched against robots.txt groups
+pub const USER_AGENT_TOKEN: &str = "beaconbot";
+
+/// Allow/disallow rules that apply to Beacon for one host
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    /// `(allow, path prefix)` pairs
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Parse robots.txt, keeping the group for `agent` or else the `*` group
+    #[must_use]
+    pub fn parse(robots_txt: &str, agent: &str) -> Self {
+        let agent = agent.to_ascii_lowercase();
+        let mut specific = Vec::new();
+        let mut wildcard = Vec::new();
+        let mut found_specific = false;
+
+        // Agents named by the current group; a rule line ends the agent list
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in robots_txt.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    group_agents.push(value.to_ascii_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    let is_specific = group_agents
+                        .iter()
+                        .any(|a| !a.is_empty() && a != "*" && agent.contains(a.as_str()));
+                    found_specific |= is_specific;
+
+                    // An empty disallow allows everything
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (key == "allow", value.to_string());
+                    if is_specific {
+                        specific.push(rule);
+                    } else if group_agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            rules: if found_specific { specific } else { wildcard },
+        }
+    }
+
+    /// Whether `path` may be fetched; the longest matching rule wins
+    #[must_use]
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, prefix)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(allow, prefix)| (prefix.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_group_applies_when_no_specific_group() {
+        let rules = RobotsRules::parse(
+            "User-agent: *\nDisallow: /private\nAllow: /private/public\n",
+            USER_AGENT_TOKEN,
+        );
+        assert!(rules.is_allowed("/"));
+        assert!(!rules.is_allowed("/private/data"));
+        assert!(rules.is_allowed("/private/public/page"));
+    }
+
+    #[test]
+    fn specific_group_overrides_wildcard() {
+        let rules = RobotsRules::parse(
+            "User-agent: *\nDisallow: /\n\nUser-agent: BeaconBot\nDisallow: /admin\n",
+            USER_AGENT_TOKEN,
+        );
+        assert!(rules.is_allowed("/articles/1"));
+        assert!(!rules.is_allowed("/admin"));
+    }
+
+    #[test]
+    fn empty_disallow_allows_everything() {
+        let rules = RobotsRules::parse("User-agent: *\nDisallow:\n", USER_AGENT_TOKEN);
+        assert!(rules.is_allowed("/anything"));
+    }
+}
@@ -4,7 +4,7 @@ use std::time::Duration;
 use clap::{Parser, Subcommand};
 use tracing_subscriber::EnvFilter;
 
-use beacon_gateway::db::{self, UserRepo};
+use beacon_gateway::db::{self, UserApiKeyRepo, UserRepo};
 use beacon_gateway::voice::{AudioCapture, AudioPlayback};
 use beacon_gateway::{Config, Daemon};
 
@@ -67,6 +67,31 @@ enum Command {
         #[arg(short, long)]
         user: String,
     },
+    /// Mint a per-user API key (printed once)
+    ApiKeyCreate {
+        /// User ID the key authenticates as
+        #[arg(short, long)]
+        user: String,
+        /// Label to tell keys apart
+        #[arg(short, long, default_value = "cli")]
+        name: String,
+        /// Also grant the `admin` scope
+        #[arg(long)]
+        admin: bool,
+    },
+    /// Revoke a per-user API key
+    ApiKeyRevoke {
+        /// Key ID (as shown by `api-key-list`)
+        id: String,
+    },
+    /// List a user's API keys
+    ApiKeyList {
+        /// User ID
+        #[arg(short, long)]
+        user: String,
+    },
+    /// Check the configuration for settings that can't work together
+    ConfigCheck,
     /// Install beacon as a system service
     Install,
     /// Uninstall the beacon system service
@@ -84,6 +109,20 @@ enum Command {
     },
     /// Interactive first-run setup
     Setup,
+    /// List available personas
+    Personas {
+        /// Also list personas published on Manifold
+        #[arg(long)]
+        manifold: bool,
+    },
+    /// Install a skill from a git repository
+    SkillInstall {
+        /// Repository URL (e.g., `https://git.example.com/team/my-skill.git`)
+        url: String,
+        /// Branch, tag or commit to pin (default: remote HEAD)
+        #[arg(long)]
+        rev: Option<String>,
+    },
 }
 
 #[tokio::main]
@@ -123,11 +162,21 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
             Command::TestTts { text } => Box::pin(test_tts(persona_ref, &text)).await,
             Command::SetLifeJson { user, path } => set_life_json(persona_ref, &user, &path),
             Command::GetLifeJson { user } => get_life_json(persona_ref, &user),
+            Command::ApiKeyCreate { user, name, admin } => {
+                api_key_create(persona_ref, &user, &name, admin)
+            }
+            Command::ApiKeyRevoke { id } => api_key_revoke(persona_ref, &id),
+            Command::ApiKeyList { user } => api_key_list(persona_ref, &user),
+            Command::ConfigCheck => cmd_config_check(persona_ref),
             Command::Install => cmd_install(persona_ref, cli.port),
             Command::Uninstall => cmd_uninstall(),
             Command::Status => cmd_status(),
             Command::Logs { lines, follow } => cmd_logs(lines, follow),
             Command::Setup => beacon_gateway::setup::run_setup(),
+            Command::Personas { manifold } => cmd_personas(persona_ref, manifold).await,
+            Command::SkillInstall { url, rev } => {
+                cmd_skill_install(persona_ref, &url, rev.as_deref()).await
+            }
         };
     }
 
@@ -363,6 +412,71 @@ fn get_life_json(persona: Option<&str>, user_id: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Mint a per-user API key
+fn api_key_create(
+    persona: Option<&str>,
+    user_id: &str,
+    name: &str,
+    admin: bool,
+) -> anyhow::Result<()> {
+    let config = Config::load(persona)?;
+    let pool = db::init(&config.data_dir.join("beacon.db"))?;
+
+    if UserRepo::new(pool.clone()).find(user_id)?.is_none() {
+        anyhow::bail!("unknown user: {user_id}");
+    }
+
+    let mut scopes = vec![db::api_key::DEFAULT_API_KEY_SCOPE.to_string()];
+    if admin {
+        scopes.push("admin".to_string());
+    }
+    let (key, raw) = UserApiKeyRepo::new(pool).create(user_id, name, &scopes)?;
+
+    println!("Created API key {} for user {user_id}", key.id);
+    println!("Scopes: {}", key.scopes.join(", "));
+    println!();
+    println!("  {raw}");
+    println!();
+    println!("Store it now; it cannot be shown again.");
+    Ok(())
+}
+
+/// Revoke a per-user API key
+fn api_key_revoke(persona: Option<&str>, key_id: &str) -> anyhow::Result<()> {
+    let config = Config::load(persona)?;
+    let pool = db::init(&config.data_dir.join("beacon.db"))?;
+
+    if UserApiKeyRepo::new(pool).revoke(key_id)? {
+        println!("Revoked API key {key_id}");
+    } else {
+        println!("No active API key {key_id}");
+    }
+    Ok(())
+}
+
+/// List a user's API keys
+fn api_key_list(persona: Option<&str>, user_id: &str) -> anyhow::Result<()> {
+    let config = Config::load(persona)?;
+    let pool = db::init(&config.data_dir.join("beacon.db"))?;
+
+    let keys = UserApiKeyRepo::new(pool).list(user_id)?;
+    if keys.is_empty() {
+        println!("User {user_id} has no API keys");
+    }
+    for key in keys {
+        let status = key
+            .revoked_at
+            .map_or_else(|| "active".to_string(), |t| format!("revoked {t}"));
+        println!(
+            "{}  {:<16}  {:<12}  {status}",
+            key.id,
+            key.name,
+            key.scopes.join(",")
+        );
+    }
+    Ok(())
+}
+
 /// Install beacon as a system service
 fn cmd_install(persona: Option<&str>, port: u16) -> anyhow::Result<()> {
     let binary = std::env::current_exe()?;
@@ -414,3 +528,83 @@ fn cmd_logs(lines: usize, follow: bool) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Load the configuration and report cross-field problems
+fn cmd_config_check(persona: Option<&str>) -> anyhow::Result<()> {
+    let config = Config::load(persona)?;
+    let report = config.check();
+
+    for check in &report.checks {
+        println!(
+            "  [{:>4}] {}: {}",
+            check.status.label(),
+            check.name,
+            check.detail
+        );
+    }
+
+    let failures = report.failures();
+    if failures > 0 {
+        anyhow::bail!("configuration check failed with {failures} error(s)");
+    }
+    println!("Configuration OK");
+
+    Ok(())
+}
+
+/// Install a skill from git and register it in the database
+async fn cmd_skill_install(
+    persona: Option<&str>,
+    url: &str,
+    rev: Option<&str>,
+) -> anyhow::Result<()> {
+    let config = Config::load(persona)?;
+    let pool = db::init(&config.data_dir.join("beacon.db"))?;
+    let skill_repo = db::SkillRepo::new(pool);
+
+    let skill =
+        beacon_gateway::skills::install_from_git(&skill_repo, &config.skills, url, rev).await?;
+    println!("Installed skill {} from {url}", skill.metadata.name);
+
+    Ok(())
+}
+
+/// List embedded, cached, and optionally Manifold personas
+async fn cmd_personas(persona: Option<&str>, manifold: bool) -> anyhow::Result<()> {
+    use beacon_gateway::api::health::{available_personas, merge_manifold_personas};
+
+    let active_id = persona
+        .filter(|s| !s.is_empty())
+        .map(ToString::to_string)
+        .or_else(|| beacon_gateway::config::file::load_config_file().persona)
+        .unwrap_or_default();
+
+    let cache_dir = beacon_gateway::config::persona_cache_dir();
+    let mut personas = available_personas(&cache_dir, &active_id);
+
+    if manifold {
+        let url = std::env::var("MANIFOLD_URL")
+            .unwrap_or_else(|_| "https://api.manifold.omni.dev".to_string());
+        let namespace =
+            std::env::var("MANIFOLD_NAMESPACE").unwrap_or_else(|_| "community".to_string());
+        match beacon_gateway::skills::ManifoldClient::new(&url)
+            .list_personas(&namespace)
+            .await
+        {
+            Ok(remote) => merge_manifold_personas(&mut personas, &remote, &active_id),
+            Err(e) => eprintln!("warning: failed to list Manifold personas: {e}"),
+        }
+    }
+
+    for p in &personas {
+        let marker = if p.active { "*" } else { " " };
+        let tagline = p.info.tagline.as_deref().unwrap_or("");
+        println!(
+            "{marker} {:<16} {:<10} {tagline}",
+            p.info.id,
+            p.source.as_str()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! Maintenance mode
+//!
+//! Lets operators pause agent responses (deploys, abuse) without stopping the
+//! process. While enabled, channel handlers answer with a notice (or stay
+//! silent) and skip the agent; the HTTP API keeps serving.
+
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::channels::{Channel, IncomingMessage, OutgoingMessage};
+
+/// Notice sent to users while maintenance mode is on
+pub const DEFAULT_MAINTENANCE_MESSAGE: &str =
+    "I'm temporarily unavailable for maintenance. Please try again shortly.";
+
+/// Maintenance mode configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaintenanceConfig {
+    /// Start with maintenance mode enabled
+    pub enabled: bool,
+    /// Reply sent while enabled (`None` = stay silent)
+    pub message: Option<String>,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            message: Some(DEFAULT_MAINTENANCE_MESSAGE.to_string()),
+        }
+    }
+}
+
+/// What a channel handler should do with an incoming message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaintenanceAction {
+    /// Not in maintenance, handle normally
+    Proceed,
+    /// Reply with this notice and skip the agent
+    Reply(String),
+    /// Drop the message without replying
+    Silent,
+}
+
+/// Runtime maintenance toggle shared by the API and channel handlers
+#[derive(Debug)]
+pub struct MaintenanceMode {
+    enabled: AtomicBool,
+    message: RwLock<Option<String>>,
+}
+
+impl Default for MaintenanceMode {
+    fn default() -> Self {
+        Self::new(&MaintenanceConfig::default())
+    }
+}
+
+impl MaintenanceMode {
+    /// Create from configuration
+    #[must_use]
+    pub fn new(config: &MaintenanceConfig) -> Self {
+        Self {
+            enabled: AtomicBool::new(config.enabled),
+            message: RwLock::new(config.message.clone()),
+        }
+    }
+
+    /// Whether maintenance mode is on
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Turn maintenance mode on or off
+    pub fn set_enabled(&self, enabled: bool) {
+        let was = self.enabled.swap(enabled, Ordering::Relaxed);
+        if was != enabled {
+            tracing::info!(enabled, "maintenance mode changed");
+        }
+    }
+
+    /// Current notice (`None` = silent)
+    #[must_use]
+    pub fn message(&self) -> Option<String> {
+        self.message
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Replace the notice (`None` or empty = silent)
+    pub fn set_message(&self, message: Option<String>) {
+        *self
+            .message
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) =
+            message.filter(|m| !m.trim().is_empty());
+    }
+
+    /// Decide how to treat an incoming message
+    #[must_use]
+    pub fn action(&self) -> MaintenanceAction {
+        if !self.is_enabled() {
+            return MaintenanceAction::Proceed;
+        }
+        self.message()
+            .map_or(MaintenanceAction::Silent, MaintenanceAction::Reply)
+    }
+
+    /// Short-circuit a channel message while in maintenance
+    ///
+    /// Sends the notice (if any) and returns `true` when the caller should
+    /// skip the agent.
+    pub async fn intercept<C: Channel + ?Sized>(&self, channel: &C, msg: &IncomingMessage) -> bool {
+        match self.action() {
+            MaintenanceAction::Proceed => false,
+            MaintenanceAction::Silent => true,
+            MaintenanceAction::Reply(notice) => {
+                let reply = OutgoingMessage::reply(msg.channel_id.clone(), notice, msg.id.clone());
+                if let Err(e) = channel.send(reply).await {
+                    tracing::warn!(error = %e, "maintenance notice send error");
+                }
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_mode_proceeds() {
+        let mode = MaintenanceMode::default();
+        assert_eq!(mode.action(), MaintenanceAction::Proceed);
+    }
+
+    #[test]
+    fn enabled_mode_replies_or_stays_silent() {
+        let mode = MaintenanceMode::default();
+        mode.set_enabled(true);
+        assert_eq!(
+            mode.action(),
+            MaintenanceAction::Reply(DEFAULT_MAINTENANCE_MESSAGE.to_string())
+        );
+
+        mode.set_message(Some(String::new()));
+        assert_eq!(mode.action(), MaintenanceAction::Silent);
+
+        mode.set_enabled(false);
+        assert_eq!(mode.action(), MaintenanceAction::Proceed);
+    }
+
+    #[tokio::test]
+    async fn intercept_short_circuits_channel_messages() {
+        use std::sync::Mutex;
+
+        struct Recording(Mutex<Vec<OutgoingMessage>>);
+
+        #[async_trait::async_trait]
+        impl Channel for Recording {
+            fn name(&self) -> &'static str {
+                "recording"
+            }
+            async fn connect(&mut self) -> crate::Result<()> {
+                Ok(())
+            }
+            async fn disconnect(&mut self) -> crate::Result<()> {
+                Ok(())
+            }
+            async fn send(&self, message: OutgoingMessage) -> crate::Result<()> {
+                self.0.lock().unwrap().push(message);
+                Ok(())
+            }
+            fn is_connected(&self) -> bool {
+                true
+            }
+        }
+
+        let channel = Recording(Mutex::new(Vec::new()));
+        let msg = IncomingMessage {
+            id: "m1".to_string(),
+            channel_id: "c1".to_string(),
+            sender_id: "u1".to_string(),
+            sender_name: "user".to_string(),
+            content: "hello".to_string(),
+            is_dm: true,
+            reply_to: None,
+            attachments: Vec::new(),
+            thread_id: None,
+            callback_data: None,
+            request_id: "r1".to_string(),
+        };
+        let mode = MaintenanceMode::default();
+
+        assert!(!mode.intercept(&channel, &msg).await);
+        assert!(channel.0.lock().unwrap().is_empty());
+
+        mode.set_enabled(true);
+        assert!(mode.intercept(&channel, &msg).await);
+        {
+            let sent = channel.0.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            assert_eq!(sent[0].channel_id, "c1");
+            assert_eq!(sent[0].content, DEFAULT_MAINTENANCE_MESSAGE);
+            assert_eq!(sent[0].reply_to.as_deref(), Some("m1"));
+        }
+
+        mode.set_message(None);
+        assert!(mode.intercept(&channel, &msg).await);
+        assert_eq!(channel.0.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,195 @@
+//! Content-addressed cache of media analysis results
+//!
+//! Identical images are often sent repeatedly (forwarded memes, re-shared
+//! screenshots). Results are keyed by a SHA-256 of the MIME type and raw bytes,
+//! so no user or conversation data is part of the key. The cached description
+//! is derived only from the image itself, but operators who do not want it
+//! reused across users can disable the cache.
+
+use std::time::Duration;
+
+use mini_moka::sync::Cache;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::MediaAnalysis;
+
+/// Media analysis cache configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MediaCacheConfig {
+    /// Reuse analysis results for identical media
+    pub enabled: bool,
+    /// Maximum number of cached results
+    pub max_entries: u64,
+    /// Time-to-live of a cached result, in seconds
+    pub ttl_secs: u64,
+}
+
+impl Default for MediaCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 1024,
+            ttl_secs: 3600,
+        }
+    }
+}
+
+impl MediaCacheConfig {
+    /// Load cache configuration from environment variables
+    ///
+    /// Reads from:
+    /// - `BEACON_MEDIA_CACHE`: enable the cache (default: true)
+    /// - `BEACON_MEDIA_CACHE_MAX_ENTRIES`: maximum cached results (default: 1024)
+    /// - `BEACON_MEDIA_CACHE_TTL_SECS`: result lifetime in seconds (default: 3600)
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let enabled = std::env::var("BEACON_MEDIA_CACHE")
+            .ok()
+            .map_or(defaults.enabled, |v| {
+                v == "1" || v.eq_ignore_ascii_case("true")
+            });
+
+        let max_entries = std::env::var("BEACON_MEDIA_CACHE_MAX_ENTRIES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.max_entries);
+
+        let ttl_secs = std::env::var("BEACON_MEDIA_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.ttl_secs);
+
+        Self {
+            enabled,
+            max_entries,
+            ttl_secs,
+        }
+    }
+}
+
+/// Bounded TTL cache of analysis results keyed by content hash
+#[derive(Clone, Debug)]
+pub struct MediaCache {
+    entries: Option<Cache<String, MediaAnalysis>>,
+}
+
+impl MediaCache {
+    /// Create a cache from configuration (a disabled cache never stores anything)
+    #[must_use]
+    pub fn new(config: &MediaCacheConfig) -> Self {
+        let entries = config.enabled.then(|| {
+            Cache::builder()
+                .max_capacity(config.max_entries)
+                .time_to_live(Duration::from_secs(config.ttl_secs))
+                .build()
+        });
+        Self { entries }
+    }
+
+    /// Content hash used as the cache key
+    #[must_use]
+    pub fn key(data: &[u8], mime_type: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(mime_type.as_bytes());
+        hasher.update([0]);
+        hasher.update(data);
+        hex::encode(hasher.finalize())
+    }
+
+    /// Look up a cached analysis for identical media
+    #[must_use]
+    pub fn get(&self, data: &[u8], mime_type: &str) -> Option<MediaAnalysis> {
+        self.entries.as_ref()?.get(&Self::key(data, mime_type))
+    }
+
+    /// Store an analysis result
+    pub fn insert(&self, data: &[u8], mime_type: &str, analysis: MediaAnalysis) {
+        if let Some(entries) = &self.entries {
+            entries.insert(Self::key(data, mime_type), analysis);
+        }
+    }
+}
+
+impl Default for MediaCache {
+    fn default() -> Self {
+        Self::new(&MediaCacheConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::media::{MediaConfig, MediaProcessor, MediaProvider};
+
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl MediaProvider for CountingProvider {
+        fn supports(&self, mime_type: &str) -> bool {
+            mime_type.starts_with("image/")
+        }
+
+        async fn process(&self, _data: &[u8], _mime_type: &str) -> crate::Result<MediaAnalysis> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(MediaAnalysis {
+                description: Some("a cat".to_string()),
+                ..MediaAnalysis::default()
+            })
+        }
+
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+    }
+
+    fn processor(cache: MediaCacheConfig) -> (MediaProcessor, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut processor = MediaProcessor::new(MediaConfig {
+            cache,
+            ..MediaConfig::default()
+        });
+        processor.add_provider(Box::new(CountingProvider {
+            calls: Arc::clone(&calls),
+        }));
+        (processor, calls)
+    }
+
+    #[tokio::test]
+    async fn identical_bytes_hit_the_cache() {
+        let (processor, calls) = processor(MediaCacheConfig::default());
+
+        let first = processor.process(b"png-bytes", "image/png").await.unwrap();
+        let second = processor.process(b"png-bytes", "image/png").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.description, second.description);
+
+        processor
+            .process(b"other-bytes", "image/png")
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn disabled_cache_always_calls_provider() {
+        let (processor, calls) = processor(MediaCacheConfig {
+            enabled: false,
+            ..MediaCacheConfig::default()
+        });
+
+        processor.process(b"png-bytes", "image/png").await.unwrap();
+        processor.process(b"png-bytes", "image/png").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::cache::MediaCacheConfig;
+
 /// Top-level media configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -12,8 +14,12 @@ pub struct MediaConfig {
     pub max_file_size: usize,
     /// `OpenAI` Vision configuration
     pub openai: OpenAIMediaConfig,
+    /// Gemini vision configuration
+    pub gemini: GeminiMediaConfig,
     /// Whisper transcription configuration
     pub whisper: WhisperConfig,
+    /// Analysis result cache
+    pub cache: MediaCacheConfig,
 }
 
 impl Default for MediaConfig {
@@ -22,7 +28,9 @@ impl Default for MediaConfig {
             enabled: true,
             max_file_size: 10 * 1024 * 1024, // 10MB
             openai: OpenAIMediaConfig::default(),
+            gemini: GeminiMediaConfig::default(),
             whisper: WhisperConfig::default(),
+            cache: MediaCacheConfig::default(),
         }
     }
 }
@@ -52,6 +60,28 @@ impl Default for OpenAIMediaConfig {
     }
 }
 
+/// Gemini vision provider configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeminiMediaConfig {
+    /// Enable Gemini vision
+    pub enabled: bool,
+    /// Model to use (default gemini-2.0-flash)
+    pub model: String,
+    /// Max tokens for response
+    pub max_output_tokens: u32,
+}
+
+impl Default for GeminiMediaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            model: "gemini-2.0-flash".to_string(),
+            max_output_tokens: 300,
+        }
+    }
+}
+
 /// Whisper transcription configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
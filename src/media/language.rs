@@ -0,0 +1,57 @@
+//! Speech-to-text language hints
+//!
+//! Whisper-style STT accepts an ISO 639-1 language code; without one it
+//! auto-detects, which is noticeably less accurate for short or non-English
+//! clips. Hints come from the user (life.json / learned context), the persona,
+//! or configuration, in that order.
+
+/// User context key holding a preferred STT language
+pub const USER_LANGUAGE_KEY: &str = "language";
+
+/// Normalize a BCP 47 tag (e.g. "pt-BR") to its primary language subtag ("pt")
+///
+/// Returns `None` for empty values, "auto", and tags that are not a 2-3 letter
+/// language code.
+#[must_use]
+pub fn normalize_language(tag: &str) -> Option<String> {
+    let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    if primary == "auto" || !(2..=3).contains(&primary.len()) {
+        return None;
+    }
+    primary
+        .chars()
+        .all(|c| c.is_ascii_alphabetic())
+        .then_some(primary)
+}
+
+/// Pick the first usable hint, most specific first (`None` = auto-detect)
+#[must_use]
+pub fn resolve_language(candidates: &[Option<&str>]) -> Option<String> {
+    candidates
+        .iter()
+        .flatten()
+        .find_map(|tag| normalize_language(tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_bcp47_tags() {
+        assert_eq!(normalize_language("pt-BR").as_deref(), Some("pt"));
+        assert_eq!(normalize_language(" EN ").as_deref(), Some("en"));
+        assert_eq!(normalize_language("auto"), None);
+        assert_eq!(normalize_language(""), None);
+    }
+
+    #[test]
+    fn user_hint_wins_over_persona() {
+        assert_eq!(
+            resolve_language(&[Some("de-AT"), Some("en")]).as_deref(),
+            Some("de")
+        );
+        assert_eq!(resolve_language(&[None, Some("fr")]).as_deref(), Some("fr"));
+        assert_eq!(resolve_language(&[None, None]), None);
+    }
+}
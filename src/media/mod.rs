@@ -2,9 +2,12 @@
 //!
 //! Provides a provider-based system for analyzing media attachments
 
+mod cache;
 mod config;
+pub mod language;
 pub mod providers;
 
+pub use cache::{MediaCache, MediaCacheConfig};
 pub use config::MediaConfig;
 
 use async_trait::async_trait;
@@ -46,6 +49,7 @@ pub trait MediaProvider: Send + Sync {
 /// Media processor with fallback chain
 pub struct MediaProcessor {
     providers: Vec<Box<dyn MediaProvider>>,
+    cache: MediaCache,
     config: MediaConfig,
 }
 
@@ -55,10 +59,39 @@ impl MediaProcessor {
     pub fn new(config: MediaConfig) -> Self {
         Self {
             providers: Vec::new(),
+            cache: MediaCache::new(&config.cache),
             config,
         }
     }
 
+    /// Create a media processor with every provider that has an API key
+    ///
+    /// Fallback order: `OpenAI` Vision, then Gemini for images and video;
+    /// Whisper for audio.
+    #[must_use]
+    pub fn from_api_keys(config: MediaConfig, keys: &crate::config::ApiKeys) -> Self {
+        let mut processor = Self::new(config);
+        let config = processor.config.clone();
+
+        if let Some(key) = keys.openai.clone().filter(|_| config.openai.enabled) {
+            processor.add_provider(Box::new(providers::OpenAIVisionProvider::new(key, &config)));
+        }
+        if let Some(key) = keys.gemini.clone().filter(|_| config.gemini.enabled) {
+            processor.add_provider(Box::new(providers::GeminiVisionProvider::new(key, &config)));
+        }
+        if let Some(key) = keys.openai.clone().filter(|_| config.whisper.enabled) {
+            processor.add_provider(Box::new(providers::WhisperProvider::new(key, &config)));
+        }
+
+        processor
+    }
+
+    /// Names of the configured providers, in fallback order
+    #[must_use]
+    pub fn provider_names(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
     /// Add a provider to the chain
     pub fn add_provider(&mut self, provider: Box<dyn MediaProvider>) {
         self.providers.push(provider);
@@ -80,11 +113,19 @@ impl MediaProcessor {
             return Ok(MediaAnalysis::default());
         }
 
+        if let Some(analysis) = self.cache.get(data, mime_type) {
+            tracing::debug!(mime_type, "media analysis cache hit");
+            return Ok(analysis);
+        }
+
         for provider in &self.providers {
             if provider.supports(mime_type) {
                 tracing::debug!(provider = provider.name(), mime_type, "processing media");
                 match provider.process(data, mime_type).await {
-                    Ok(analysis) => return Ok(analysis),
+                    Ok(analysis) => {
+                        self.cache.insert(data, mime_type, analysis.clone());
+                        return Ok(analysis);
+                    }
                     Err(e) => {
                         tracing::warn!(
                             provider = provider.name(),
@@ -101,3 +142,29 @@ impl MediaProcessor {
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_api_keys_orders_fallback_chain() {
+        let keys = crate::config::ApiKeys {
+            gemini: Some("gemini-key".to_string()),
+            ..Default::default()
+        };
+        let processor = MediaProcessor::from_api_keys(MediaConfig::default(), &keys);
+        assert_eq!(processor.provider_names(), ["gemini-vision"]);
+
+        let keys = crate::config::ApiKeys {
+            openai: Some("openai-key".to_string()),
+            gemini: Some("gemini-key".to_string()),
+            ..Default::default()
+        };
+        let processor = MediaProcessor::from_api_keys(MediaConfig::default(), &keys);
+        assert_eq!(
+            processor.provider_names(),
+            ["openai-vision", "gemini-vision", "whisper"]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Google Gemini provider for image and video understanding
+
+use async_trait::async_trait;
+use base64::Engine;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::media::{MediaAnalysis, MediaConfig, MediaProvider};
+use crate::{Error, Result};
+
+/// Default Gemini API base URL
+const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com";
+
+/// Gemini multimodal vision provider
+pub struct GeminiVisionProvider {
+    client: Client,
+    api_key: String,
+    model: String,
+    max_output_tokens: u32,
+    base_url: String,
+}
+
+impl GeminiVisionProvider {
+    /// Create a new Gemini vision provider
+    #[must_use]
+    pub fn new(api_key: String, config: &MediaConfig) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            model: config.gemini.model.clone(),
+            max_output_tokens: config.gemini.max_output_tokens,
+            base_url: DEFAULT_BASE_URL.to_string(),
+        }
+    }
+
+    /// Override the API base URL (e.g. for a proxy)
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Check if MIME type is a supported image or video format
+    fn is_supported(mime_type: &str) -> bool {
+        mime_type.starts_with("image/") || mime_type == "video/mp4"
+    }
+}
+
+#[async_trait]
+impl MediaProvider for GeminiVisionProvider {
+    fn supports(&self, mime_type: &str) -> bool {
+        Self::is_supported(mime_type)
+    }
+
+    async fn process(&self, data: &[u8], mime_type: &str) -> Result<MediaAnalysis> {
+        let prompt = if mime_type.starts_with("video/") {
+            "Describe this video concisely. Focus on the main subject, what happens, and any text visible."
+        } else {
+            "Describe this image concisely. Focus on the main subject and any text visible."
+        };
+
+        let request = GenerateContentRequest {
+            contents: vec![Content {
+                parts: vec![
+                    Part::Text {
+                        text: prompt.to_string(),
+                    },
+                    Part::InlineData {
+                        inline_data: InlineData {
+                            mime_type: mime_type.to_string(),
+                            data: base64::engine::general_purpose::STANDARD.encode(data),
+                        },
+                    },
+                ],
+            }],
+            generation_config: GenerationConfig {
+                max_output_tokens: self.max_output_tokens,
+            },
+        };
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/v1beta/models/{}:generateContent",
+                self.base_url.trim_end_matches('/'),
+                self.model
+            ))
+            .header("x-goog-api-key", &self.api_key)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| Error::Media(format!("Gemini request failed: {e}")))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Media(format!("Gemini API error: {status} - {body}")));
+        }
+
+        let raw: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Media(format!("Failed to parse Gemini response: {e}")))?;
+        let result: GenerateContentResponse = serde_json::from_value(raw.clone())
+            .map_err(|e| Error::Media(format!("Unexpected Gemini response: {e}")))?;
+
+        let description = result
+            .candidates
+            .first()
+            .map(|c| {
+                c.content
+                    .parts
+                    .iter()
+                    .filter_map(|p| p.text.as_deref())
+                    .collect::<String>()
+            })
+            .filter(|text| !text.is_empty());
+
+        Ok(MediaAnalysis {
+            description,
+            transcript: None,
+            metadata: serde_json::json!({
+                "provider": "gemini",
+                "model": self.model,
+                "response": raw,
+            }),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "gemini-vision"
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentRequest {
+    contents: Vec<Content>,
+    generation_config: GenerationConfig,
+}
+
+#[derive(Serialize)]
+struct Content {
+    parts: Vec<Part>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Part {
+    Text { text: String },
+    InlineData { inline_data: InlineData },
+}
+
+#[derive(Serialize)]
+struct InlineData {
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerationConfig {
+    max_output_tokens: u32,
+}
+
+#[derive(Deserialize)]
+struct GenerateContentResponse {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+}
+
+#[derive(Deserialize)]
+struct Candidate {
+    content: CandidateContent,
+}
+
+#[derive(Deserialize)]
+struct CandidateContent {
+    #[serde(default)]
+    parts: Vec<ResponsePart>,
+}
+
+#[derive(Deserialize)]
+struct ResponsePart {
+    text: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports_image_and_mp4() {
+        assert!(GeminiVisionProvider::is_supported("image/png"));
+        assert!(GeminiVisionProvider::is_supported("image/heic"));
+        assert!(GeminiVisionProvider::is_supported("video/mp4"));
+        assert!(!GeminiVisionProvider::is_supported("video/webm"));
+        assert!(!GeminiVisionProvider::is_supported("audio/mpeg"));
+    }
+
+    #[tokio::test]
+    async fn test_process_against_mock_server() {
+        use axum::{Json, Router, http::HeaderMap, routing::post};
+
+        let app = Router::new().route(
+            "/v1beta/models/{model}",
+            post(
+                |headers: HeaderMap, Json(body): Json<serde_json::Value>| async move {
+                    assert_eq!(headers["x-goog-api-key"], "test-key");
+                    let part = &body["contents"][0]["parts"][1]["inline_data"];
+                    assert_eq!(part["mime_type"], "image/png");
+                    assert_eq!(part["data"], "iVBORw==");
+                    Json(serde_json::json!({
+                        "candidates": [{
+                            "content": { "parts": [{ "text": "A red square." }] }
+                        }]
+                    }))
+                },
+            ),
+        );
+        let base = crate::test_support::serve(app).await;
+
+        let provider = GeminiVisionProvider::new("test-key".to_string(), &MediaConfig::default())
+            .with_base_url(base);
+        let analysis = provider
+            .process(&[0x89, 0x50, 0x4e, 0x47], "image/png")
+            .await
+            .unwrap();
+
+        assert_eq!(analysis.description.as_deref(), Some("A red square."));
+        assert_eq!(analysis.metadata["provider"], "gemini");
+        assert_eq!(
+            analysis.metadata["response"]["candidates"][0]["content"]["parts"][0]["text"],
+            "A red square."
+        );
+    }
+}
@@ -2,10 +2,13 @@
 //!
 //! Available providers:
 //! - `OpenAI` Vision for image understanding
+//! - Gemini for image and video understanding
 //! - Whisper for audio transcription
 
+mod gemini;
 mod openai;
 mod whisper;
 
+pub use gemini::GeminiVisionProvider;
 pub use openai::OpenAIVisionProvider;
 pub use whisper::WhisperProvider;
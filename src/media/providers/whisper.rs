@@ -28,42 +28,33 @@ impl WhisperProvider {
         }
     }
 
-    /// Check if MIME type is a supported audio format
-    fn is_supported_audio(mime_type: &str) -> bool {
-        matches!(
-            mime_type,
-            "audio/mpeg"
-                | "audio/mp3"
-                | "audio/mp4"
-                | "audio/m4a"
-                | "audio/wav"
-                | "audio/webm"
-                | "audio/ogg"
-                | "audio/flac"
-        )
+    /// Override the configured language hint
+    #[must_use]
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
     }
 
-    /// Get file extension for MIME type
-    fn extension_for_mime(mime_type: &str) -> &'static str {
-        match mime_type {
-            "audio/mp4" | "audio/m4a" => "m4a",
-            "audio/wav" => "wav",
-            "audio/webm" => "webm",
-            "audio/ogg" => "ogg",
-            "audio/flac" => "flac",
-            // audio/mpeg, audio/mp3, and everything else default to mp3
-            _ => "mp3",
+    /// Text fields of the transcription request (`language` overrides the configured hint)
+    fn form_fields(&self, language: Option<&str>) -> Vec<(&'static str, String)> {
+        let mut fields = vec![("model", self.model.clone())];
+        if let Some(lang) = language.or(self.language.as_deref()) {
+            fields.push(("language", lang.to_string()));
         }
+        fields
     }
-}
 
-#[async_trait]
-impl MediaProvider for WhisperProvider {
-    fn supports(&self, mime_type: &str) -> bool {
-        Self::is_supported_audio(mime_type)
-    }
-
-    async fn process(&self, data: &[u8], mime_type: &str) -> Result<MediaAnalysis> {
+    /// Transcribe audio with an optional per-request language hint
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the request fails or the response cannot be parsed
+    pub async fn transcribe(
+        &self,
+        data: &[u8],
+        mime_type: &str,
+        language: Option<&str>,
+    ) -> Result<String> {
         let extension = Self::extension_for_mime(mime_type);
         let filename = format!("audio.{extension}");
 
@@ -72,12 +63,9 @@ impl MediaProvider for WhisperProvider {
             .mime_str(mime_type)
             .map_err(|e| Error::Media(format!("Invalid MIME type: {e}")))?;
 
-        let mut form = Form::new()
-            .text("model", self.model.clone())
-            .part("file", part);
-
-        if let Some(ref lang) = self.language {
-            form = form.text("language", lang.clone());
+        let mut form = Form::new().part("file", part);
+        for (name, value) in self.form_fields(language) {
+            form = form.text(name, value);
         }
 
         let response = self
@@ -102,9 +90,50 @@ impl MediaProvider for WhisperProvider {
             .await
             .map_err(|e| Error::Media(format!("Failed to parse Whisper response: {e}")))?;
 
+        Ok(result.text)
+    }
+
+    /// Check if MIME type is a supported audio format
+    fn is_supported_audio(mime_type: &str) -> bool {
+        matches!(
+            mime_type,
+            "audio/mpeg"
+                | "audio/mp3"
+                | "audio/mp4"
+                | "audio/m4a"
+                | "audio/wav"
+                | "audio/webm"
+                | "audio/ogg"
+                | "audio/flac"
+        )
+    }
+
+    /// Get file extension for MIME type
+    fn extension_for_mime(mime_type: &str) -> &'static str {
+        match mime_type {
+            "audio/mp4" | "audio/m4a" => "m4a",
+            "audio/wav" => "wav",
+            "audio/webm" => "webm",
+            "audio/ogg" => "ogg",
+            "audio/flac" => "flac",
+            // audio/mpeg, audio/mp3, and everything else default to mp3
+            _ => "mp3",
+        }
+    }
+}
+
+#[async_trait]
+impl MediaProvider for WhisperProvider {
+    fn supports(&self, mime_type: &str) -> bool {
+        Self::is_supported_audio(mime_type)
+    }
+
+    async fn process(&self, data: &[u8], mime_type: &str) -> Result<MediaAnalysis> {
+        let text = self.transcribe(data, mime_type, None).await?;
+
         Ok(MediaAnalysis {
             description: None,
-            transcript: Some(result.text),
+            transcript: Some(text),
             metadata: serde_json::json!({
                 "provider": "whisper",
                 "model": self.model,
@@ -145,4 +174,27 @@ mod tests {
         assert_eq!(WhisperProvider::extension_for_mime("audio/ogg"), "ogg");
         assert_eq!(WhisperProvider::extension_for_mime("audio/flac"), "flac");
     }
+
+    #[test]
+    fn test_configured_language_flows_into_request() {
+        let mut config = MediaConfig::default();
+        config.whisper.language = Some("de".to_string());
+        let provider = WhisperProvider::new("sk-test".to_string(), &config);
+
+        let fields = provider.form_fields(None);
+        assert!(fields.contains(&("language", "de".to_string())));
+
+        // Per-request hint (user/persona) overrides the configured one
+        let fields = provider.form_fields(Some("pt"));
+        assert!(fields.contains(&("language", "pt".to_string())));
+        assert!(!fields.contains(&("language", "de".to_string())));
+
+        // Auto-detect when nothing is configured
+        let auto = WhisperProvider::new("sk-test".to_string(), &MediaConfig::default());
+        assert!(
+            auto.form_fields(None)
+                .iter()
+                .all(|(name, _)| *name != "language")
+        );
+    }
 }
@@ -8,5 +8,7 @@ pub mod registry;
 pub mod types;
 
 pub use policy::{is_command_allowed, platform_defaults};
-pub use registry::NodeRegistry;
-pub use types::{InvokeRequest, InvokeResult, NodeRegistration, NodeSession};
+pub use registry::{DEFAULT_STALE_TTL, InvokeError, NodeRegistry, SharedNodeRegistry};
+pub use types::{
+    InvokeRequest, InvokeResult, KnownNode, NodeDispatch, NodeRegistration, NodeSession, NodeStatus,
+};
@@ -1,18 +1,70 @@
 //! Node registry for tracking connected devices
+//!
+//! Live connections (`NodeSession`) exist only in memory. Registrations are
+//! tracked separately by device ID and, with a store attached, persisted so
+//! devices are still listed as stale after a restart until they reconnect.
+//!
+//! Invocations are dispatched through each node's outbox and awaited with a
+//! timeout; when it expires the node is told to abort the command.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{Mutex, mpsc, oneshot};
+use uuid::Uuid;
 
-use std::collections::HashMap;
+use super::policy::is_command_allowed;
+use super::types::{
+    InvokeRequest, InvokeResult, KnownNode, NodeDispatch, NodeRegistration, NodeSession, NodeStatus,
+};
+use crate::db::NodeRepo;
 
-use tokio::sync::oneshot;
-use uuid::Uuid;
+/// Shared node registry state
+pub type SharedNodeRegistry = Arc<Mutex<NodeRegistry>>;
+
+/// How long a disconnected node is remembered before it is pruned
+pub const DEFAULT_STALE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Why an invocation could not be dispatched
+#[derive(Debug, thiserror::Error)]
+pub enum InvokeError {
+    #[error("node '{0}' not found")]
+    NotFound(String),
+
+    #[error("command '{command}' not allowed for platform '{platform}'")]
+    NotAllowed { command: String, platform: String },
 
-use super::types::{InvokeResult, NodeRegistration, NodeSession};
+    #[error("node '{0}' disconnected")]
+    Disconnected(String),
+
+    #[error("no connected node has capability '{0}'")]
+    NoCapableNode(String),
+}
+
+/// An invocation awaiting its node's response
+#[derive(Debug)]
+struct PendingInvoke {
+    node_id: String,
+    tx: oneshot::Sender<InvokeResult>,
+}
 
 /// Registry of connected nodes
 #[derive(Debug)]
 pub struct NodeRegistry {
     nodes: HashMap<String, NodeSession>,
+    /// Channels delivering dispatches to each connected node
+    outboxes: HashMap<String, mpsc::UnboundedSender<NodeDispatch>>,
     /// Pending invocation responses keyed by correlation ID
-    pending: HashMap<String, oneshot::Sender<InvokeResult>>,
+    pending: HashMap<String, PendingInvoke>,
+    /// Commands refused on every node, on top of platform policy
+    deny_list: HashSet<String>,
+    /// Round-robin position for capability-targeted invocations
+    next_pick: usize,
+    /// Last registration of every known device, keyed by device ID
+    known: HashMap<String, KnownNode>,
+    /// Persistence for `known` (None = in-memory only)
+    store: Option<NodeRepo>,
 }
 
 impl Default for NodeRegistry {
@@ -27,12 +79,64 @@ impl NodeRegistry {
     pub fn new() -> Self {
         Self {
             nodes: HashMap::new(),
+            outboxes: HashMap::new(),
             pending: HashMap::new(),
+            deny_list: HashSet::new(),
+            next_pick: 0,
+            known: HashMap::new(),
+            store: None,
+        }
+    }
+
+    /// Create a registry persisting registrations to `store`
+    ///
+    /// Previously stored registrations are loaded as stale nodes.
+    #[must_use]
+    pub fn with_store(store: NodeRepo) -> Self {
+        let known = match store.list() {
+            Ok(nodes) => nodes
+                .into_iter()
+                .map(|n| (n.registration.device_id.clone(), n))
+                .collect(),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to load node registrations");
+                HashMap::new()
+            }
+        };
+        if !known.is_empty() {
+            tracing::info!(count = known.len(), "restored node registrations");
+        }
+
+        Self {
+            known,
+            store: Some(store),
+            ..Self::new()
         }
     }
 
+    /// Refuse these commands on every node
+    #[must_use]
+    pub fn with_deny_list(mut self, deny_list: HashSet<String>) -> Self {
+        self.deny_list = deny_list;
+        self
+    }
+
     /// Register a node and return its assigned node ID
     pub fn register(&mut self, registration: NodeRegistration) -> String {
+        let now = chrono::Utc::now();
+        if let Some(store) = &self.store
+            && let Err(e) = store.upsert(&registration, now)
+        {
+            tracing::warn!(device_id = %registration.device_id, error = %e, "failed to persist node registration");
+        }
+        self.known.insert(
+            registration.device_id.clone(),
+            KnownNode {
+                registration: registration.clone(),
+                last_seen: now,
+            },
+        );
+
         let node_id = format!("node_{}", Uuid::new_v4());
         let session = NodeSession {
             node_id: node_id.clone(),
@@ -42,15 +146,96 @@ impl NodeRegistry {
             device_family: registration.device_family,
             caps: registration.caps,
             commands: registration.commands,
-            connected_at: chrono::Utc::now(),
+            connected_at: now,
         };
         self.nodes.insert(node_id.clone(), session);
         node_id
     }
 
+    /// Attach the channel that delivers dispatches to a registered node
+    pub fn attach(&mut self, node_id: &str, outbox: mpsc::UnboundedSender<NodeDispatch>) {
+        if self.nodes.contains_key(node_id) {
+            self.outboxes.insert(node_id.to_string(), outbox);
+        }
+    }
+
     /// Unregister a node, cleaning up any pending invocations
+    ///
+    /// Callers awaiting the node see it as disconnected. The device's
+    /// registration is kept and reported as stale.
     pub fn unregister(&mut self, node_id: &str) -> Option<NodeSession> {
-        self.nodes.remove(node_id)
+        self.outboxes.remove(node_id);
+        self.pending.retain(|_, p| p.node_id != node_id);
+        let session = self.nodes.remove(node_id)?;
+        self.mark_seen(&session.device_id);
+        Some(session)
+    }
+
+    /// Record activity from a connected node (e.g. a keepalive)
+    pub fn touch(&mut self, node_id: &str) {
+        if let Some(device_id) = self.nodes.get(node_id).map(|n| n.device_id.clone()) {
+            self.mark_seen(&device_id);
+        }
+    }
+
+    fn mark_seen(&mut self, device_id: &str) {
+        let Some(known) = self.known.get_mut(device_id) else {
+            return;
+        };
+        known.last_seen = chrono::Utc::now();
+        if let Some(store) = &self.store
+            && let Err(e) = store.touch(device_id, known.last_seen)
+        {
+            tracing::warn!(device_id, error = %e, "failed to update node last-seen");
+        }
+    }
+
+    /// Whether a device currently has a live connection
+    #[must_use]
+    pub fn status(&self, device_id: &str) -> NodeStatus {
+        if self.nodes.values().any(|n| n.device_id == device_id) {
+            NodeStatus::Online
+        } else {
+            NodeStatus::Stale
+        }
+    }
+
+    /// List every known device registration with its status
+    #[must_use]
+    pub fn registrations(&self) -> Vec<(&KnownNode, NodeStatus)> {
+        self.known
+            .values()
+            .map(|n| (n, self.status(&n.registration.device_id)))
+            .collect()
+    }
+
+    /// Forget stale devices not seen within `max_age`, returning how many
+    ///
+    /// Devices with a live connection are never pruned.
+    pub fn prune_stale(&mut self, max_age: Duration) -> usize {
+        let Some(cutoff) = chrono::Duration::from_std(max_age)
+            .ok()
+            .and_then(|age| chrono::Utc::now().checked_sub_signed(age))
+        else {
+            return 0;
+        };
+        let expired: Vec<String> = self
+            .known
+            .values()
+            .filter(|n| n.last_seen < cutoff)
+            .map(|n| n.registration.device_id.clone())
+            .filter(|id| self.status(id) == NodeStatus::Stale)
+            .collect();
+
+        for device_id in &expired {
+            self.known.remove(device_id);
+            if let Some(store) = &self.store
+                && let Err(e) = store.delete(device_id)
+            {
+                tracing::warn!(device_id, error = %e, "failed to delete node registration");
+            }
+        }
+        expired.len()
     }
 
     /// Get a node by ID
@@ -73,6 +258,65 @@ impl NodeRegistry {
             .find(|n| n.caps.iter().any(|c| c == cap))
     }
 
+    /// Find every node with the given capability, optionally on one platform
+    ///
+    /// Nodes are ordered by connection time.
+    #[must_use]
+    pub fn find_by_capability(
+        &self,
+        capability: &str,
+        platform: Option<&str>,
+    ) -> Vec<&NodeSession> {
+        let mut matches: Vec<&NodeSession> = self
+            .nodes
+            .values()
+            .filter(|n| n.caps.iter().any(|c| c == capability))
+            .filter(|n| platform.is_none_or(|p| n.platform == p))
+            .collect();
+        matches.sort_by(|a, b| {
+            a.connected_at
+                .cmp(&b.connected_at)
+                .then_with(|| a.node_id.cmp(&b.node_id))
+        });
+        matches
+    }
+
+    /// Pick a node for `command` among those with `capability`, round-robin
+    ///
+    /// Only nodes whose policy allows the command are considered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoCapableNode` if no node has the capability, or `NotAllowed` if
+    /// none of them may run the command
+    pub fn pick_by_capability(
+        &mut self,
+        capability: &str,
+        platform: Option<&str>,
+        command: &str,
+    ) -> Result<String, InvokeError> {
+        let candidates = self.find_by_capability(capability, platform);
+        let Some(first) = candidates.first() else {
+            return Err(InvokeError::NoCapableNode(capability.to_string()));
+        };
+        let denied_platform = first.platform.clone();
+        let allowed: Vec<String> = candidates
+            .iter()
+            .filter(|n| is_command_allowed(&n.platform, &n.commands, &self.deny_list, command))
+            .map(|n| n.node_id.clone())
+            .collect();
+        if allowed.is_empty() {
+            return Err(InvokeError::NotAllowed {
+                command: command.to_string(),
+                platform: denied_platform,
+            });
+        }
+
+        let pick = allowed[self.next_pick % allowed.len()].clone();
+        self.next_pick = self.next_pick.wrapping_add(1);
+        Ok(pick)
+    }
+
     /// Find a node that supports the given command
     #[must_use]
     pub fn find_by_command(&self, command: &str) -> Option<&NodeSession> {
@@ -98,18 +342,137 @@ impl NodeRegistry {
 
         let correlation_id = Uuid::new_v4().to_string();
         let (tx, rx) = oneshot::channel();
-        self.pending.insert(correlation_id.clone(), tx);
+        self.pending.insert(
+            correlation_id.clone(),
+            PendingInvoke {
+                node_id: node_id.to_string(),
+                tx,
+            },
+        );
 
         Ok((correlation_id, rx))
     }
 
+    /// Check command policy and send an invocation to its node
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the node is unknown or disconnected, or the command is
+    /// not allowed on it
+    pub fn dispatch(
+        &mut self,
+        request: &InvokeRequest,
+    ) -> Result<(String, oneshot::Receiver<InvokeResult>), InvokeError> {
+        let node = self
+            .nodes
+            .get(&request.node_id)
+            .ok_or_else(|| InvokeError::NotFound(request.node_id.clone()))?;
+        if !is_command_allowed(
+            &node.platform,
+            &node.commands,
+            &self.deny_list,
+            &request.command,
+        ) {
+            return Err(InvokeError::NotAllowed {
+                command: request.command.clone(),
+                platform: node.platform.clone(),
+            });
+        }
+        let outbox = self
+            .outboxes
+            .get(&request.node_id)
+            .cloned()
+            .ok_or_else(|| InvokeError::Disconnected(request.node_id.clone()))?;
+
+        let (correlation_id, rx) = self
+            .prepare_invoke(&request.node_id)
+            .map_err(|_| InvokeError::NotFound(request.node_id.clone()))?;
+        let sent = outbox.send(NodeDispatch::Invoke {
+            correlation_id: correlation_id.clone(),
+            command: request.command.clone(),
+            params: request.params.clone(),
+        });
+        if sent.is_err() {
+            self.pending.remove(&correlation_id);
+            return Err(InvokeError::Disconnected(request.node_id.clone()));
+        }
+
+        Ok((correlation_id, rx))
+    }
+
+    /// Invoke a command on a node and wait for its result
+    ///
+    /// Waits at most `request.timeout_ms`; on timeout the node is told to
+    /// abort and the result has `timed_out` set.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the invocation cannot be dispatched, or the node
+    /// disconnects before answering
+    pub async fn invoke(
+        registry: &Mutex<Self>,
+        request: InvokeRequest,
+    ) -> Result<InvokeResult, InvokeError> {
+        let (correlation_id, rx) = registry.lock().await.dispatch(&request)?;
+
+        let timeout = Duration::from_millis(request.timeout_ms);
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => Err(InvokeError::Disconnected(request.node_id)),
+            Err(_) => {
+                registry.lock().await.cancel(&correlation_id);
+                tracing::warn!(
+                    node_id = %request.node_id,
+                    command = %request.command,
+                    "node invocation timed out"
+                );
+                Ok(InvokeResult {
+                    timed_out: true,
+                    ..InvokeResult::failed("invocation timed out")
+                })
+            }
+        }
+    }
+
+    /// Abandon a pending invocation and tell its node to abort
+    ///
+    /// Any caller still waiting receives a cancelled result. Returns true if
+    /// the invocation was pending.
+    pub fn cancel(&mut self, correlation_id: &str) -> bool {
+        let Some(pending) = self.pending.remove(correlation_id) else {
+            return false;
+        };
+        if let Some(outbox) = self.outboxes.get(&pending.node_id) {
+            let _ = outbox.send(NodeDispatch::Cancel {
+                correlation_id: correlation_id.to_string(),
+            });
+        }
+        let _ = pending
+            .tx
+            .send(InvokeResult::failed("invocation cancelled"));
+        true
+    }
+
     /// Handle a response from a node for a pending invocation
     ///
-    /// Returns true if the correlation ID was found and resolved
-    pub fn handle_response(&mut self, correlation_id: &str, result: InvokeResult) -> bool {
+    /// Returns true if the correlation ID was found and resolved. A response
+    /// from any node other than the one the invocation was sent to is ignored.
+    pub fn handle_response(
+        &mut self,
+        node_id: &str,
+        correlation_id: &str,
+        result: InvokeResult,
+    ) -> bool {
+        if self
+            .pending
+            .get(correlation_id)
+            .is_none_or(|p| p.node_id != node_id)
+        {
+            return false;
+        }
         self.pending
             .remove(correlation_id)
-            .is_some_and(|tx| tx.send(result).is_ok())
+            .is_some_and(|p| p.tx.send(result).is_ok())
     }
 
     /// Number of connected nodes
@@ -196,11 +559,187 @@ mod tests {
             ok: true,
             payload: Some(serde_json::json!({"status": "done"})),
             error: None,
+            timed_out: false,
         };
 
-        assert!(registry.handle_response(&corr_id, result));
+        // Another node cannot answer for it
+        let other = registry.register(NodeRegistration {
+            device_id: "device_456".to_string(),
+            ..sample_registration()
+        });
+        assert!(!registry.handle_response(&other, &corr_id, InvokeResult::failed("spoofed")));
+
+        assert!(registry.handle_response(&node_id, &corr_id, result));
 
         let received = rx.try_recv().unwrap();
         assert!(received.ok);
     }
+
+    fn invoke_request(node_id: &str, command: &str, timeout_ms: u64) -> InvokeRequest {
+        InvokeRequest {
+            node_id: node_id.to_string(),
+            command: command.to_string(),
+            params: serde_json::json!({}),
+            timeout_ms,
+            idempotency_key: None,
+        }
+    }
+
+    fn linux_shell_node(device_id: &str) -> NodeRegistration {
+        NodeRegistration {
+            device_id: device_id.to_string(),
+            display_name: None,
+            platform: "linux".to_string(),
+            device_family: None,
+            caps: vec!["shell".to_string()],
+            commands: vec!["system.run".to_string()],
+        }
+    }
+
+    #[test]
+    fn find_by_capability_filters_platform() {
+        let mut registry = NodeRegistry::new();
+        registry.register(linux_shell_node("box1"));
+        registry.register(sample_registration());
+
+        assert_eq!(registry.find_by_capability("shell", None).len(), 1);
+        assert_eq!(registry.find_by_capability("shell", Some("linux")).len(), 1);
+        assert!(
+            registry
+                .find_by_capability("shell", Some("darwin"))
+                .is_empty()
+        );
+        assert_eq!(registry.find_by_capability("audio", None).len(), 1);
+    }
+
+    #[test]
+    fn pick_by_capability_round_robins_allowed_nodes() {
+        let mut registry = NodeRegistry::new();
+        let a = registry.register(linux_shell_node("box1"));
+        let b = registry.register(linux_shell_node("box2"));
+
+        let picks: HashSet<String> = (0..4)
+            .map(|_| {
+                registry
+                    .pick_by_capability("shell", Some("linux"), "system.run")
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(picks, HashSet::from([a, b]));
+
+        assert!(matches!(
+            registry.pick_by_capability("shell", None, "camera.snap"),
+            Err(InvokeError::NotAllowed { .. })
+        ));
+        assert!(matches!(
+            registry.pick_by_capability("gpu", None, "system.run"),
+            Err(InvokeError::NoCapableNode(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn unresponsive_node_times_out_and_is_cancelled() {
+        let mut registry = NodeRegistry::new();
+        let node_id = registry.register(sample_registration());
+        let (tx, mut outbox) = mpsc::unbounded_channel();
+        registry.attach(&node_id, tx);
+        let registry = Mutex::new(registry);
+
+        // The node receives the command but never answers
+        let result = NodeRegistry::invoke(&registry, invoke_request(&node_id, "device.info", 20))
+            .await
+            .unwrap();
+
+        assert!(!result.ok);
+        assert!(result.timed_out);
+        let Some(NodeDispatch::Invoke { correlation_id, .. }) = outbox.recv().await else {
+            panic!("expected invoke dispatch");
+        };
+        assert_eq!(
+            outbox.recv().await,
+            Some(NodeDispatch::Cancel {
+                correlation_id: correlation_id.clone()
+            })
+        );
+
+        // A late answer is ignored
+        let late = InvokeResult::failed("too late");
+        assert!(
+            !registry
+                .lock()
+                .await
+                .handle_response(&node_id, &correlation_id, late)
+        );
+    }
+
+    #[tokio::test]
+    async fn disconnect_resolves_pending_invocations() {
+        let mut registry = NodeRegistry::new();
+        let node_id = registry.register(sample_registration());
+        let (tx, _outbox) = mpsc::unbounded_channel();
+        registry.attach(&node_id, tx);
+
+        let (_, rx) = registry
+            .dispatch(&invoke_request(&node_id, "device.info", 30_000))
+            .unwrap();
+        registry.unregister(&node_id);
+
+        assert!(rx.await.is_err());
+    }
+
+    #[test]
+    fn dispatch_checks_policy_before_sending() {
+        let mut registry =
+            NodeRegistry::new().with_deny_list(HashSet::from(["system.run".to_string()]));
+        let node_id = registry.register(sample_registration());
+        let (tx, mut outbox) = mpsc::unbounded_channel();
+        registry.attach(&node_id, tx);
+
+        // Denied by config, and not declared by the node
+        for command in ["system.run", "browser.proxy"] {
+            assert!(matches!(
+                registry.dispatch(&invoke_request(&node_id, command, 1000)),
+                Err(InvokeError::NotAllowed { .. })
+            ));
+        }
+        assert!(outbox.try_recv().is_err());
+        assert!(matches!(
+            registry.dispatch(&invoke_request("node_missing", "device.info", 1000)),
+            Err(InvokeError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn registrations_survive_restart_as_stale() {
+        let pool = crate::db::init_memory().unwrap();
+        let mut registry = NodeRegistry::with_store(NodeRepo::new(pool.clone()));
+        let node_id = registry.register(sample_registration());
+        assert_eq!(registry.status("device_123"), NodeStatus::Online);
+
+        let restarted = NodeRegistry::with_store(NodeRepo::new(pool));
+        assert!(restarted.is_empty());
+        let known = restarted.registrations();
+        assert_eq!(known.len(), 1);
+        assert_eq!(known[0].0.registration.caps, vec!["audio", "display"]);
+        assert_eq!(known[0].1, NodeStatus::Stale);
+
+        registry.unregister(&node_id);
+        assert_eq!(registry.status("device_123"), NodeStatus::Stale);
+    }
+
+    #[test]
+    fn prune_stale_keeps_connected_nodes() {
+        let pool = crate::db::init_memory().unwrap();
+        let mut registry = NodeRegistry::with_store(NodeRepo::new(pool.clone()));
+        let node_id = registry.register(sample_registration());
+
+        // Connected nodes are never pruned
+        assert_eq!(registry.prune_stale(Duration::ZERO), 0);
+
+        registry.unregister(&node_id);
+        assert_eq!(registry.prune_stale(Duration::from_secs(3600)), 0);
+        assert_eq!(registry.prune_stale(Duration::ZERO), 1);
+        assert!(registry.registrations().is_empty());
+        assert!(NodeRepo::new(pool).list().unwrap().is_empty());
+    }
 }
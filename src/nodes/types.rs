@@ -36,6 +36,35 @@ pub struct InvokeResult {
     pub ok: bool,
     pub payload: Option<serde_json::Value>,
     pub error: Option<String>,
+    /// The node did not answer before the invocation's timeout
+    #[serde(default)]
+    pub timed_out: bool,
+}
+
+impl InvokeResult {
+    /// Failed result with an error message
+    #[must_use]
+    pub fn failed(error: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            payload: None,
+            error: Some(error.into()),
+            timed_out: false,
+        }
+    }
+}
+
+/// Message the registry sends to a connected node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeDispatch {
+    /// Run a command and answer with the correlation ID
+    Invoke {
+        correlation_id: String,
+        command: String,
+        params: serde_json::Value,
+    },
+    /// Abort a command that is no longer awaited
+    Cancel { correlation_id: String },
 }
 
 /// Registration message from a connecting node
@@ -48,3 +77,21 @@ pub struct NodeRegistration {
     pub caps: Vec<String>,
     pub commands: Vec<String>,
 }
+
+/// A device's last registration, remembered after it disconnects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnownNode {
+    #[serde(flatten)]
+    pub registration: NodeRegistration,
+    pub last_seen: chrono::DateTime<chrono::Utc>,
+}
+
+/// Whether a known device currently has a live connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeStatus {
+    /// Connected now
+    Online,
+    /// Registered before but not connected since (e.g. after a restart)
+    Stale,
+}
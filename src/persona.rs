@@ -3,6 +3,8 @@
 //! Implements the persona.json specification for portable digital entity identity.
 //! See: <https://persona.omni.dev>
 
+use std::ops::RangeInclusive;
+
 use serde::{Deserialize, Serialize};
 
 use crate::tools::{ToolPolicy, ToolPolicyConfig};
@@ -13,6 +15,36 @@ pub use agent_core::knowledge::{
     PackEmbeddings,
 };
 
+/// Accepted TTS speech rate multipliers
+pub const TTS_SPEED_RANGE: RangeInclusive<f32> = 0.25..=4.0;
+
+/// Longest accepted wake word
+const MAX_WAKE_WORD_CHARS: usize = 64;
+
+/// A problem found by [`Persona::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// JSON path of the offending field (e.g. `voice.tts.speed`)
+    pub field: String,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl ValidationError {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
 /// A persona defines the identity of a digital entity
 ///
 /// Follows the persona.json v1 specification.
@@ -427,6 +459,12 @@ impl Persona {
         self.voice.as_ref()?.stt.as_ref()?.model.as_deref()
     }
 
+    /// Get the STT language hint (BCP 47)
+    #[must_use]
+    pub fn stt_language(&self) -> Option<&str> {
+        self.voice.as_ref()?.stt.as_ref()?.language.as_deref()
+    }
+
     /// Get the tool policy
     #[must_use]
     pub fn tool_policy(&self) -> ToolPolicy {
@@ -473,6 +511,111 @@ impl Persona {
     pub const fn has_knowledge(&self) -> bool {
         !self.knowledge.inline.is_empty() || !self.knowledge.packs.is_empty()
     }
+
+    /// Check values serde accepts but the gateway can't use
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found, not just the first
+    pub fn validate(&self) -> std::result::Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        let id = self.identity.id.trim();
+        if id.is_empty() {
+            errors.push(ValidationError::new("identity.id", "must not be empty"));
+        } else if id
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '/' | '\\'))
+        {
+            errors.push(ValidationError::new(
+                "identity.id",
+                format!("`{id}` must not contain whitespace or path separators"),
+            ));
+        }
+        if self.identity.name.trim().is_empty() {
+            errors.push(ValidationError::new("identity.name", "must not be empty"));
+        }
+
+        if let Some(voice) = &self.voice {
+            for (i, word) in voice.wake_words.iter().enumerate() {
+                if let Err(message) = check_wake_word(word) {
+                    errors.push(ValidationError::new(
+                        format!("voice.wakeWords[{i}]"),
+                        message,
+                    ));
+                }
+            }
+            if let Some(tts) = &voice.tts
+                && !TTS_SPEED_RANGE.contains(&tts.speed)
+            {
+                errors.push(ValidationError::new(
+                    "voice.tts.speed",
+                    format!(
+                        "{} is outside {}–{}",
+                        tts.speed,
+                        TTS_SPEED_RANGE.start(),
+                        TTS_SPEED_RANGE.end()
+                    ),
+                ));
+            }
+        }
+
+        for (i, pack) in self.knowledge.packs.iter().enumerate() {
+            if let Err(message) = check_pack_ref(&pack.pack_ref) {
+                errors.push(ValidationError::new(
+                    format!("knowledge.packs[{i}].ref"),
+                    message,
+                ));
+            }
+            if pack.version.as_deref().is_some_and(|v| v.trim().is_empty()) {
+                errors.push(ValidationError::new(
+                    format!("knowledge.packs[{i}].version"),
+                    "must not be empty when set",
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Wake words must be short, spoken-style phrases
+fn check_wake_word(word: &str) -> std::result::Result<(), String> {
+    let word = word.trim();
+    if word.is_empty() {
+        return Err("must not be empty".to_string());
+    }
+    if word.chars().count() > MAX_WAKE_WORD_CHARS {
+        return Err(format!("longer than {MAX_WAKE_WORD_CHARS} characters"));
+    }
+    if !word
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '\'' | '-'))
+    {
+        return Err(format!(
+            "`{word}` may only contain letters, digits, spaces, apostrophes and hyphens"
+        ));
+    }
+    Ok(())
+}
+
+/// Pack refs are Manifold artifact refs: `@namespace/kind/name`
+fn check_pack_ref(pack_ref: &str) -> std::result::Result<(), String> {
+    let valid = pack_ref.strip_prefix('@').is_some_and(|rest| {
+        let segments: Vec<_> = rest.split('/').collect();
+        segments.len() == 3 && segments.iter().all(|s| !s.trim().is_empty())
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{pack_ref}` is not a Manifold ref like `@community/knowledge/name`"
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -496,4 +639,47 @@ mod tests {
         assert_eq!(p.tts_voice(), None);
         assert_eq!(p.system_prompt(), None);
     }
+
+    #[test]
+    fn embedded_personas_are_valid() {
+        for (id, json) in crate::Config::embedded_personas() {
+            let persona: Persona = serde_json::from_str(json).unwrap();
+            assert_eq!(persona.validate(), Ok(()), "{id}");
+        }
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        let mut persona: Persona = serde_json::from_value(serde_json::json!({
+            "version": "1.0.0",
+            "identity": { "id": "", "name": " " },
+            "voice": {
+                "wakeWords": ["hey orin", "hey orin!"],
+                "tts": { "speed": 50.0 }
+            }
+        }))
+        .unwrap();
+        persona.knowledge.packs.push(KnowledgePackRef {
+            pack_ref: "solana-defi".to_string(),
+            version: None,
+            priority: None,
+        });
+
+        let fields: Vec<_> = persona
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "identity.id",
+                "identity.name",
+                "voice.wakeWords[1]",
+                "voice.tts.speed",
+                "knowledge.packs[0].ref",
+            ]
+        );
+    }
 }
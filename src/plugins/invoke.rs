@@ -0,0 +1,175 @@
+//! Plugin tool invocation
+//!
+//! Subprocess tools receive `<tool_name> <arguments_json>` as arguments and
+//! the arguments JSON again on stdin. Whatever they print to stdout is the
+//! result, except a JSON object with an `error` string, which fails the
+//! call, and one with a `result` string, which is unwrapped.
+
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+
+use super::sandbox::ExecPolicy;
+use crate::{Error, Result};
+
+/// Default upper bound on one plugin tool call
+pub const PLUGIN_TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A plugin tool call that passed the plugin's capability checks
+pub struct PluginInvocation {
+    plugin_id: String,
+    tool_name: String,
+    policy: ExecPolicy,
+}
+
+impl PluginInvocation {
+    pub(super) fn exec(plugin_id: &str, tool_name: &str, policy: ExecPolicy) -> Self {
+        Self {
+            plugin_id: plugin_id.to_string(),
+            tool_name: tool_name.to_string(),
+            policy,
+        }
+    }
+
+    /// Plugin providing the tool
+    #[must_use]
+    pub fn plugin_id(&self) -> &str {
+        &self.plugin_id
+    }
+
+    /// Run the call, giving up after `timeout`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the plugin fails, reports an error, or times out
+    pub async fn call(&self, arguments: &str, timeout: Duration) -> Result<String> {
+        let started = std::time::Instant::now();
+        let result =
+            tokio::time::timeout(timeout, run_exec(&self.policy, &self.tool_name, arguments))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(Error::Tool(format!(
+                        "plugin {} timed out after {}s",
+                        self.plugin_id,
+                        timeout.as_secs()
+                    )))
+                });
+
+        tracing::debug!(
+            plugin = %self.plugin_id,
+            tool = %self.tool_name,
+            success = result.is_ok(),
+            duration_ms = started.elapsed().as_millis(),
+            "plugin tool call finished"
+        );
+        result
+    }
+}
+
+/// Spawn the plugin entry, feed it the arguments, and read its reply
+async fn run_exec(policy: &ExecPolicy, tool_name: &str, arguments: &str) -> Result<String> {
+    // TODO: add "wasm" arm using Extism for sandboxed execution of untrusted plugins
+    let mut cmd = policy.command(tool_name, arguments);
+    cmd.stdin(std::process::Stdio::piped());
+    cmd.stdout(std::process::Stdio::piped());
+    cmd.stderr(std::process::Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(|e| {
+        Error::Tool(format!(
+            "failed to spawn plugin process {}: {e}",
+            policy.program().display()
+        ))
+    })?;
+
+    // Write input while reading output, so neither side blocks on a full pipe
+    let stdin = child.stdin.take();
+    let write = async move {
+        match stdin {
+            // Dropping stdin closes it, signalling end of input
+            Some(mut stdin) => stdin.write_all(arguments.as_bytes()).await,
+            None => Ok(()),
+        }
+    };
+    let (written, output) = tokio::join!(write, child.wait_with_output());
+
+    let output = output.map_err(|e| Error::Tool(format!("plugin process error: {e}")))?;
+    // A plugin that ignores stdin may exit before reading it
+    if let Err(e) = written
+        && e.kind() != std::io::ErrorKind::BrokenPipe
+    {
+        return Err(Error::Tool(format!("failed to write plugin input: {e}")));
+    }
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Tool(format!(
+            "plugin exited with {}: {stderr}",
+            output.status
+        )));
+    }
+
+    parse_reply(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Unwrap `{"result": ...}` and fail on `{"error": ...}`; other output is the result
+fn parse_reply(stdout: String) -> Result<String> {
+    let Ok(serde_json::Value::Object(reply)) = serde_json::from_str(&stdout) else {
+        return Ok(stdout);
+    };
+    if let Some(error) = reply.get("error").and_then(serde_json::Value::as_str) {
+        return Err(Error::Tool(error.to_string()));
+    }
+    match reply.get("result").and_then(serde_json::Value::as_str) {
+        Some(result) => Ok(result.to_string()),
+        None => Ok(stdout),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reply_protocol() {
+        assert_eq!(parse_reply("plain text".into()).unwrap(), "plain text");
+        assert_eq!(parse_reply(r#"{"result":"ok"}"#.into()).unwrap(), "ok");
+        assert!(matches!(
+            parse_reply(r#"{"error":"bad input"}"#.into()),
+            Err(Error::Tool(msg)) if msg == "bad input"
+        ));
+        // Structured results pass through whole
+        let json = r#"{"temp":21}"#;
+        assert_eq!(parse_reply(json.into()).unwrap(), json);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn call_respects_timeout() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("slow.sh");
+        std::fs::write(&script, "#!/bin/sh\nsleep 5\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let plugin = crate::plugins::LoadedPlugin {
+            manifest: serde_json::from_str(
+                r#"{"id":"omni.slow","name":"Slow","version":"1.0.0","kind":"tool",
+                    "entry":"slow.sh"}"#,
+            )
+            .unwrap(),
+            path: dir.path().to_path_buf(),
+            enabled: true,
+        };
+
+        let slow = PluginInvocation::exec(
+            "omni.slow",
+            "wait",
+            ExecPolicy::for_plugin(&plugin).unwrap(),
+        );
+        let err = slow
+            .call("{}", Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+}
@@ -1,10 +1,17 @@
 //! Plugin loader and lifecycle manager
+//!
+//! Plugins are registered only if their manifest stays within its declared
+//! kind (see [`super::sandbox`]); a rejected plugin is reported and skipped
+//! without stopping the scan.
 
 use std::collections::HashMap;
 use std::path::PathBuf;
 
 use super::discovery::discover_plugins;
-use super::manifest::{EnvValue, PluginManifest, PluginToolDef, PluginTransport};
+use super::invoke::PluginInvocation;
+use super::manifest::{PluginManifest, PluginToolDef, PluginTransport};
+use super::sandbox::{Capability, ExecPolicy, PluginLoadError, check_manifest, resolve_env};
+use crate::{Error, Result};
 
 /// A discovered and loaded plugin
 #[derive(Debug, Clone)]
@@ -17,6 +24,22 @@ pub struct LoadedPlugin {
     pub enabled: bool,
 }
 
+impl LoadedPlugin {
+    /// Whether the plugin is enabled and its kind allows `capability`
+    fn provides(&self, capability: Capability) -> bool {
+        self.enabled && self.manifest.kind.allows(capability)
+    }
+}
+
+/// Outcome of a plugin scan
+#[derive(Debug, Default)]
+pub struct PluginLoadReport {
+    /// IDs of newly loaded plugins
+    pub loaded: Vec<String>,
+    /// Plugins rejected by their manifest checks
+    pub errors: Vec<PluginLoadError>,
+}
+
 /// Manage discovered plugins
 #[derive(Debug)]
 pub struct PluginManager {
@@ -40,40 +63,75 @@ impl PluginManager {
 
     /// Discover and load plugins from the given directories
     ///
-    /// Returns the IDs of all newly loaded plugins
-    pub fn load_all(&mut self, dirs: &[PathBuf]) -> Vec<String> {
-        let discovered = discover_plugins(dirs);
-        let mut loaded_ids = Vec::new();
+    /// A plugin that fails its manifest checks is reported in the result and
+    /// the scan continues with the rest.
+    pub fn load_all(&mut self, dirs: &[PathBuf]) -> PluginLoadReport {
+        let mut report = PluginLoadReport::default();
 
-        for (path, manifest) in discovered {
+        for (path, manifest) in discover_plugins(dirs) {
             let id = manifest.id.clone();
-
-            if self.plugins.contains_key(&id) {
-                tracing::debug!(plugin_id = %id, "plugin already loaded, skipping");
-                continue;
+            match self.register(path, manifest) {
+                Ok(true) => report.loaded.push(id),
+                Ok(false) => {}
+                Err(e) => report.errors.push(e),
             }
+        }
 
-            tracing::info!(
-                plugin_id = %id,
-                name = %manifest.name,
-                version = %manifest.version,
-                kind = ?manifest.kind,
-                "loaded plugin"
-            );
+        report
+    }
 
-            self.plugins.insert(
-                id.clone(),
-                LoadedPlugin {
-                    manifest,
-                    path,
-                    enabled: true,
-                },
-            );
+    /// Register a discovered plugin
+    ///
+    /// Returns false if a plugin with the same ID is already loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the manifest requests capabilities beyond its kind
+    /// or points outside the plugin directory
+    pub fn register(
+        &mut self,
+        path: PathBuf,
+        manifest: PluginManifest,
+    ) -> std::result::Result<bool, PluginLoadError> {
+        let id = manifest.id.clone();
+
+        if self.plugins.contains_key(&id) {
+            tracing::debug!(plugin_id = %id, "plugin already loaded, skipping");
+            return Ok(false);
+        }
 
-            loaded_ids.push(id);
+        if let Err(reason) = check_manifest(&manifest) {
+            tracing::warn!(
+                plugin_id = %id,
+                path = %path.display(),
+                reason = %reason,
+                "plugin manifest violates its declared kind, not loading"
+            );
+            return Err(PluginLoadError {
+                plugin_id: id,
+                path,
+                reason,
+            });
         }
 
-        loaded_ids
+        tracing::info!(
+            plugin_id = %id,
+            name = %manifest.name,
+            version = %manifest.version,
+            kind = ?manifest.kind,
+            "loaded plugin"
+        );
+
+        self.plugins.insert(
+            id,
+            LoadedPlugin {
+                manifest,
+                path,
+                enabled: true,
+            },
+        );
+
+        Ok(true)
     }
 
     /// Get a plugin by ID
@@ -82,6 +140,45 @@ impl PluginManager {
         self.plugins.get(id)
     }
 
+    /// Resolve a scoped `plugin_id::tool_name` call to its plugin and tool
+    ///
+    /// The plugin must be enabled, allowed to provide tools, and declare the
+    /// tool in its manifest; calls beyond that are logged and refused.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ToolNotPermitted` for calls outside the plugin's
+    /// declared tools, or error if the plugin is unknown or disabled
+    pub fn declared_tool<'a>(&self, scoped_name: &'a str) -> Result<(&LoadedPlugin, &'a str)> {
+        let (plugin_id, tool_name) = scoped_name
+            .split_once("::")
+            .ok_or_else(|| Error::Tool(format!("not a plugin tool: {scoped_name}")))?;
+
+        let plugin = self
+            .plugins
+            .get(plugin_id)
+            .ok_or_else(|| Error::Tool(format!("plugin not found: {plugin_id}")))?;
+
+        if !plugin.enabled {
+            return Err(Error::Tool(format!("plugin disabled: {plugin_id}")));
+        }
+
+        if !plugin.manifest.kind.allows(Capability::Tools)
+            || !plugin.manifest.tools.iter().any(|t| t.name == tool_name)
+        {
+            tracing::warn!(
+                plugin = %plugin_id,
+                tool = %tool_name,
+                "call to a tool the plugin manifest does not declare"
+            );
+            return Err(Error::ToolNotPermitted(format!(
+                "{scoped_name} is not declared by the plugin"
+            )));
+        }
+
+        Ok((plugin, tool_name))
+    }
+
     /// List all loaded plugins
     #[must_use]
     pub fn list(&self) -> Vec<&LoadedPlugin> {
@@ -112,12 +209,15 @@ impl PluginManager {
 
     /// Collect all tool definitions from enabled tool plugins
     ///
-    /// Tool names are scoped as `plugin_id::tool_name`
+    /// Tool names are scoped as `plugin_id::tool_name`. MCP plugins are left
+    /// out; their tools are listed by their MCP server.
     #[must_use]
     pub fn tools(&self) -> Vec<(String, PluginToolDef)> {
         self.plugins
             .values()
-            .filter(|p| p.enabled)
+            .filter(|p| {
+                p.provides(Capability::Tools) && p.manifest.transport != PluginTransport::McpStdio
+            })
             .flat_map(|p| {
                 p.manifest.tools.iter().map(move |tool| {
                     let scoped_name = format!("{}::{}", p.manifest.id, tool.name);
@@ -127,6 +227,28 @@ impl PluginManager {
             .collect()
     }
 
+    /// Resolve a scoped `plugin_id::tool_name` call to its invocation
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the call fails [`Self::declared_tool`], the plugin
+    /// is served over MCP, or its entry can't be run
+    pub fn invocation(&self, scoped_name: &str) -> Result<PluginInvocation> {
+        let (plugin, tool_name) = self.declared_tool(scoped_name)?;
+        let plugin_id = &plugin.manifest.id;
+
+        match plugin.manifest.transport {
+            PluginTransport::Subprocess => Ok(PluginInvocation::exec(
+                plugin_id,
+                tool_name,
+                ExecPolicy::for_plugin(plugin)?,
+            )),
+            PluginTransport::McpStdio => Err(Error::Tool(format!(
+                "plugin {plugin_id} tools are served by its MCP server"
+            ))),
+        }
+    }
+
     /// Collect skill directories from enabled plugins that declare `skills_dir`
     ///
     /// Returns `(plugin_path/skills_dir)` for each match. The caller should
@@ -135,7 +257,7 @@ impl PluginManager {
     pub fn skill_dirs(&self) -> Vec<PathBuf> {
         self.plugins
             .values()
-            .filter(|p| p.enabled)
+            .filter(|p| p.provides(Capability::Skills))
             .filter_map(|p| p.manifest.skills_dir.as_ref().map(|dir| p.path.join(dir)))
             .filter(|p| p.is_dir())
             .collect()
@@ -149,36 +271,27 @@ impl PluginManager {
     pub fn mcp_configs(&self) -> Vec<crate::mcp::McpServerConfig> {
         self.plugins
             .values()
-            .filter(|p| p.enabled && p.manifest.transport == PluginTransport::McpStdio)
+            .filter(|p| {
+                p.provides(Capability::McpServer)
+                    && p.manifest.transport == PluginTransport::McpStdio
+            })
             .filter_map(|p| {
                 let entry = p.manifest.entry.as_deref()?;
                 let parts: Vec<&str> = entry.split_whitespace().collect();
                 let (command, args) = parts.split_first()?;
 
-                // Resolve env vars: literal values pass through, required vars
-                // are read from process env, missing required vars are skipped
-                let mut env = std::collections::HashMap::new();
-                for (key, value) in &p.manifest.env {
-                    match value {
-                        EnvValue::Value(v) => {
-                            env.insert(key.clone(), v.clone());
-                        }
-                        EnvValue::Descriptor { required, default } => {
-                            if let Ok(v) = std::env::var(key) {
-                                env.insert(key.clone(), v);
-                            } else if let Some(d) = default {
-                                env.insert(key.clone(), d.clone());
-                            } else if *required {
-                                tracing::warn!(
-                                    plugin = %p.manifest.id,
-                                    env_var = %key,
-                                    "required env var missing, skipping MCP plugin"
-                                );
-                                return None;
-                            }
-                        }
+                // Missing required env vars skip the plugin
+                let env = match resolve_env(&p.manifest) {
+                    Ok(env) => env,
+                    Err(key) => {
+                        tracing::warn!(
+                            plugin = %p.manifest.id,
+                            env_var = %key,
+                            "required env var missing, skipping MCP plugin"
+                        );
+                        return None;
                     }
-                }
+                };
 
                 Some(crate::mcp::McpServerConfig {
                     name: p.manifest.id.clone(),
@@ -231,9 +344,10 @@ mod tests {
         .unwrap();
 
         let mut manager = PluginManager::new();
-        let loaded = manager.load_all(&[dir.path().to_path_buf()]);
+        let report = manager.load_all(&[dir.path().to_path_buf()]);
 
-        assert_eq!(loaded, vec!["omni.test"]);
+        assert_eq!(report.loaded, vec!["omni.test"]);
+        assert!(report.errors.is_empty());
         assert_eq!(manager.len(), 1);
 
         let plugin = manager.get("omni.test").unwrap();
@@ -330,8 +444,9 @@ mod tests {
         let first = manager.load_all(&[dir.path().to_path_buf()]);
         let second = manager.load_all(&[dir.path().to_path_buf()]);
 
-        assert_eq!(first.len(), 1);
-        assert!(second.is_empty()); // Already loaded
+        assert_eq!(first.loaded.len(), 1);
+        assert!(second.loaded.is_empty()); // Already loaded
+        assert!(second.errors.is_empty());
         assert_eq!(manager.len(), 1);
     }
 
@@ -367,6 +482,115 @@ mod tests {
         assert_eq!(skill_dirs[0], skills_dir);
     }
 
+    #[test]
+    fn rejected_plugin_does_not_abort_scan() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // A channel plugin trying to register tools
+        let bad_dir = dir.path().join("bad");
+        std::fs::create_dir(&bad_dir).unwrap();
+        std::fs::write(
+            bad_dir.join("omni.plugin.json"),
+            r#"{
+                "id": "omni.bad",
+                "name": "Bad",
+                "version": "1.0.0",
+                "kind": "channel",
+                "tools": [{"name": "a", "description": "Tool A", "input_schema": {}}]
+            }"#,
+        )
+        .unwrap();
+
+        let good_dir = dir.path().join("good");
+        std::fs::create_dir(&good_dir).unwrap();
+        std::fs::write(
+            good_dir.join("omni.plugin.json"),
+            r#"{"id":"omni.good","name":"Good","version":"1.0.0","kind":"tool"}"#,
+        )
+        .unwrap();
+
+        let mut manager = PluginManager::new();
+        let report = manager.load_all(&[dir.path().to_path_buf()]);
+
+        assert_eq!(report.loaded, vec!["omni.good"]);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].plugin_id, "omni.bad");
+        assert_eq!(report.errors[0].path, bad_dir);
+        assert!(manager.get("omni.bad").is_none());
+        assert!(manager.tools().is_empty());
+    }
+
+    #[test]
+    fn declared_tool_refuses_undeclared_calls() {
+        let mut manager = PluginManager::new();
+        manager
+            .register(
+                PathBuf::from("/nonexistent"),
+                serde_json::from_str(
+                    r#"{"id":"omni.echo","name":"Echo","version":"1.0.0","kind":"tool",
+                        "entry":"echo.sh",
+                        "tools":[{"name":"echo","description":"Echo","input_schema":{}}]}"#,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        let (plugin, tool) = manager.declared_tool("omni.echo::echo").unwrap();
+        assert_eq!(plugin.manifest.id, "omni.echo");
+        assert_eq!(tool, "echo");
+
+        assert!(matches!(
+            manager.declared_tool("omni.echo::rm"),
+            Err(Error::ToolNotPermitted(_))
+        ));
+        manager.disable("omni.echo");
+        assert!(manager.declared_tool("omni.echo::echo").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn invoke_subprocess_tool_over_stdin() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let plugin_dir = dir.path().join("echo");
+        std::fs::create_dir(&plugin_dir).unwrap();
+        std::fs::write(
+            plugin_dir.join("omni.plugin.json"),
+            r#"{
+                "id": "omni.echo",
+                "name": "Echo",
+                "version": "1.0.0",
+                "kind": "tool",
+                "entry": "echo.sh",
+                "tools": [{"name": "echo", "description": "Echo input", "input_schema": {}}]
+            }"#,
+        )
+        .unwrap();
+        let script = plugin_dir.join("echo.sh");
+        std::fs::write(&script, "#!/bin/sh\ncat\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut manager = PluginManager::new();
+        manager.load_all(&[dir.path().to_path_buf()]);
+
+        let invocation = manager.invocation("omni.echo::echo").unwrap();
+        assert_eq!(invocation.plugin_id(), "omni.echo");
+        let output = invocation
+            .call(r#"{"text":"hi"}"#, crate::plugins::PLUGIN_TOOL_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(output, r#"{"text":"hi"}"#);
+
+        // Input and output larger than a pipe buffer
+        let large = format!(r#"{{"text":"{}"}}"#, "x".repeat(100 * 1024));
+        let output = invocation
+            .call(&large, crate::plugins::PLUGIN_TOOL_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(output.len(), large.len());
+    }
+
     #[test]
     fn skill_dirs_skips_missing_dir() {
         let dir = tempfile::tempdir().unwrap();
@@ -2,12 +2,16 @@
 //!
 //! Plugins are discovered from `omni.plugin.json` manifests in standard
 //! directories. Each plugin declares its kind (tool, channel, provider, etc.)
-//! and the capabilities it provides.
+//! and the capabilities it provides; the kind bounds what it may register.
 
 pub mod discovery;
+pub mod invoke;
 pub mod loader;
 pub mod manifest;
+pub mod sandbox;
 
 pub use discovery::{default_plugin_dirs, discover_plugins};
-pub use loader::{LoadedPlugin, PluginManager};
+pub use invoke::{PLUGIN_TOOL_TIMEOUT, PluginInvocation};
+pub use loader::{LoadedPlugin, PluginLoadReport, PluginManager};
 pub use manifest::{PluginKind, PluginManifest, PluginToolDef, PluginTransport};
+pub use sandbox::{Capability, ExecPolicy, PluginLoadError};
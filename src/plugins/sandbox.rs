@@ -0,0 +1,468 @@
+//! Capability enforcement for plugins
+//!
+//! A manifest's `kind` bounds what the plugin may contribute: only tool
+//! plugins register tools or MCP servers, and only skill plugins register
+//! skill directories. Manifests reaching beyond their kind are rejected when
+//! the plugin is registered.
+//!
+//! Subprocess tools run under an [`ExecPolicy`]: the only program allowed is
+//! the plugin's own entry (or the script runtime for its extension), it must
+//! live inside the plugin directory, and its environment holds only the
+//! manifest's `env` plus a few process basics.
+//!
+//! Manifest literals pass through as written. Descriptor entries never read
+//! the gateway's own variables by name unless the operator allowlisted them
+//! in `BEACON_PLUGIN_ENV_ALLOW` (comma-separated); otherwise the value comes
+//! from `BEACON_PLUGIN_<ID>_<KEY>`, so a manifest can't ask for gateway
+//! secrets such as `ANTHROPIC_API_KEY`.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use super::loader::LoadedPlugin;
+use super::manifest::{EnvValue, PluginKind, PluginManifest, PluginTransport};
+use crate::{Error, Result};
+
+/// Script runtimes a plugin entry may run under, by file extension
+const RUNTIMES: &[(&[&str], &str)] = &[(&["js", "ts", "mjs", "mts"], "bun"), (&["py"], "python3")];
+
+/// Gateway environment variables every plugin process inherits
+const INHERITED_ENV: &[&str] = &["PATH", "HOME", "LANG", "TMPDIR"];
+
+/// Prefix of the gateway variables holding one plugin's env values
+const PLUGIN_ENV_PREFIX: &str = "BEACON_PLUGIN_";
+
+/// Variable listing gateway env vars plugins may read by their own name
+const PLUGIN_ENV_ALLOW: &str = "BEACON_PLUGIN_ENV_ALLOW";
+
+/// Something a plugin can contribute to the gateway
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Tool definitions executed as a subprocess
+    Tools,
+    /// An MCP server over stdio
+    McpServer,
+    /// A skills directory
+    Skills,
+}
+
+impl Capability {
+    /// Name used in violation reports
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Tools => "tools",
+            Self::McpServer => "an MCP server",
+            Self::Skills => "skills",
+        }
+    }
+}
+
+impl PluginKind {
+    /// Whether plugins of this kind may contribute `capability`
+    #[must_use]
+    pub const fn allows(&self, capability: Capability) -> bool {
+        matches!(
+            (self, capability),
+            (Self::Tool, Capability::Tools | Capability::McpServer)
+                | (Self::Skill, Capability::Skills)
+        )
+    }
+}
+
+impl PluginManifest {
+    /// Capabilities the manifest asks for
+    #[must_use]
+    pub fn requested_capabilities(&self) -> Vec<Capability> {
+        let mut capabilities = Vec::new();
+        if !self.tools.is_empty() {
+            capabilities.push(Capability::Tools);
+        }
+        if self.transport == PluginTransport::McpStdio {
+            capabilities.push(Capability::McpServer);
+        }
+        if self.skills_dir.is_some() {
+            capabilities.push(Capability::Skills);
+        }
+        capabilities
+    }
+}
+
+/// Why a discovered plugin was not loaded
+#[derive(Debug, Clone)]
+pub struct PluginLoadError {
+    pub plugin_id: String,
+    /// Directory containing the plugin
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+impl std::fmt::Display for PluginLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "plugin {} ({}): {}",
+            self.plugin_id,
+            self.path.display(),
+            self.reason
+        )
+    }
+}
+
+/// Check that a manifest stays within its declared kind
+///
+/// # Errors
+///
+/// Returns the violation if the manifest requests a capability its kind
+/// doesn't allow, or points a path outside the plugin directory
+pub fn check_manifest(manifest: &PluginManifest) -> std::result::Result<(), String> {
+    if let Some(capability) = manifest
+        .requested_capabilities()
+        .into_iter()
+        .find(|c| !manifest.kind.allows(*c))
+    {
+        return Err(format!(
+            "{:?} plugin may not provide {}",
+            manifest.kind,
+            capability.name()
+        ));
+    }
+
+    // MCP entries are commands; subprocess entries and skills are paths
+    let mut paths = Vec::new();
+    if manifest.transport == PluginTransport::Subprocess
+        && let Some(entry) = &manifest.entry
+    {
+        paths.push(("entry", entry));
+    }
+    if let Some(skills_dir) = &manifest.skills_dir {
+        paths.push(("skills_dir", skills_dir));
+    }
+    for (field, path) in paths {
+        if !is_contained(Path::new(path)) {
+            return Err(format!("{field} `{path}` is outside the plugin directory"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a relative path stays inside the directory it is joined to
+fn is_contained(path: &Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Gateway variable holding `key` for plugin `id`: `BEACON_PLUGIN_<ID>_<KEY>`
+///
+/// The id is uppercased with anything but ASCII alphanumerics replaced by `_`.
+#[must_use]
+pub fn plugin_env_var(id: &str, key: &str) -> String {
+    let id: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{PLUGIN_ENV_PREFIX}{id}_{key}")
+}
+
+/// Resolve a manifest's `env` map
+///
+/// Literal values pass through. Descriptors read the plugin's
+/// `BEACON_PLUGIN_<ID>_<KEY>` variable, then `KEY` itself when the operator
+/// allowlisted it in `BEACON_PLUGIN_ENV_ALLOW`, and fall back to their
+/// default.
+///
+/// # Errors
+///
+/// Returns the name of a required variable that is not set
+pub fn resolve_env(
+    manifest: &PluginManifest,
+) -> std::result::Result<HashMap<String, String>, String> {
+    let allowed = std::env::var(PLUGIN_ENV_ALLOW).unwrap_or_default();
+    let allowed: Vec<&str> = allowed.split(',').map(str::trim).collect();
+    resolve_env_with(manifest, |key| {
+        std::env::var(plugin_env_var(&manifest.id, key))
+            .ok()
+            .or_else(|| {
+                allowed
+                    .contains(&key)
+                    .then(|| std::env::var(key).ok())
+                    .flatten()
+            })
+    })
+}
+
+/// [`resolve_env`] with the operator's values supplied by `lookup`
+fn resolve_env_with(
+    manifest: &PluginManifest,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> std::result::Result<HashMap<String, String>, String> {
+    let mut env = HashMap::new();
+    for (key, value) in &manifest.env {
+        match value {
+            EnvValue::Value(v) => {
+                env.insert(key.clone(), v.clone());
+            }
+            EnvValue::Descriptor { required, default } => {
+                if let Some(v) = lookup(key) {
+                    env.insert(key.clone(), v);
+                } else if let Some(d) = default {
+                    env.insert(key.clone(), d.clone());
+                } else if *required {
+                    return Err(key.clone());
+                }
+            }
+        }
+    }
+    Ok(env)
+}
+
+/// The one program a subprocess plugin tool may run, and its environment
+#[derive(Debug, Clone)]
+pub struct ExecPolicy {
+    /// Runtime or entry executable
+    program: PathBuf,
+    /// Entry script passed to the runtime
+    script: Option<PathBuf>,
+    /// Plugin directory, used as the working directory
+    dir: PathBuf,
+    env: HashMap<String, String>,
+}
+
+impl ExecPolicy {
+    /// Derive the policy for a plugin's subprocess entry
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the plugin has no entry, the entry resolves outside
+    /// the plugin directory, or a required env var is missing
+    pub fn for_plugin(plugin: &LoadedPlugin) -> Result<Self> {
+        let id = &plugin.manifest.id;
+        let entry = plugin
+            .manifest
+            .entry
+            .as_deref()
+            .ok_or_else(|| Error::Tool(format!("plugin {id} has no entry point")))?;
+
+        // Canonicalize both so symlinks can't point the entry elsewhere
+        let dir = plugin.path.canonicalize().map_err(|e| {
+            Error::Tool(format!(
+                "plugin directory {} unavailable: {e}",
+                plugin.path.display()
+            ))
+        })?;
+        let entry_path = dir
+            .join(entry)
+            .canonicalize()
+            .map_err(|e| Error::Tool(format!("plugin {id} entry `{entry}` unavailable: {e}")))?;
+        if !entry_path.starts_with(&dir) {
+            tracing::warn!(plugin = %id, entry, "plugin entry resolves outside its directory");
+            return Err(Error::ToolNotPermitted(format!(
+                "plugin {id} entry `{entry}` is outside the plugin directory"
+            )));
+        }
+
+        let env = resolve_env(&plugin.manifest).map_err(|key| {
+            Error::Tool(format!(
+                "plugin {id} requires env var {key}, which is not set"
+            ))
+        })?;
+
+        let ext = entry_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+        let runtime = RUNTIMES
+            .iter()
+            .find(|(exts, _)| exts.contains(&ext))
+            .map(|(_, runtime)| *runtime);
+
+        Ok(match runtime {
+            Some(runtime) => Self {
+                program: PathBuf::from(runtime),
+                script: Some(entry_path),
+                dir,
+                env,
+            },
+            None => Self {
+                program: entry_path,
+                script: None,
+                dir,
+                env,
+            },
+        })
+    }
+
+    /// Program the policy permits
+    #[must_use]
+    pub fn program(&self) -> &Path {
+        &self.program
+    }
+
+    /// Build the command for one tool call
+    ///
+    /// Arguments: `[script] <tool_name> <arguments_json>`
+    #[must_use]
+    pub fn command(&self, tool_name: &str, arguments: &str) -> tokio::process::Command {
+        let mut cmd = tokio::process::Command::new(&self.program);
+        if let Some(script) = &self.script {
+            cmd.arg(script);
+        }
+        cmd.arg(tool_name).arg(arguments);
+
+        cmd.env_clear();
+        for key in INHERITED_ENV {
+            if let Ok(value) = std::env::var(key) {
+                cmd.env(key, value);
+            }
+        }
+        cmd.envs(&self.env);
+        cmd.current_dir(&self.dir);
+        // Abandoned calls (e.g. timed out) must not leave the process running
+        cmd.kill_on_drop(true);
+        cmd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(json: &str) -> PluginManifest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn kind_bounds_capabilities() {
+        let tool = manifest(
+            r#"{"id":"t","name":"T","version":"1.0.0","kind":"tool",
+                "tools":[{"name":"a","description":"A","input_schema":{}}]}"#,
+        );
+        assert!(check_manifest(&tool).is_ok());
+
+        let channel = manifest(
+            r#"{"id":"c","name":"C","version":"1.0.0","kind":"channel",
+                "tools":[{"name":"a","description":"A","input_schema":{}}]}"#,
+        );
+        let err = check_manifest(&channel).unwrap_err();
+        assert!(err.contains("may not provide tools"));
+
+        let mcp_skill = manifest(
+            r#"{"id":"s","name":"S","version":"1.0.0","kind":"skill",
+                "transport":"mcp-stdio","entry":"npx server"}"#,
+        );
+        assert!(check_manifest(&mcp_skill).is_err());
+    }
+
+    #[test]
+    fn paths_must_stay_in_plugin_dir() {
+        let escape = manifest(
+            r#"{"id":"t","name":"T","version":"1.0.0","kind":"tool","entry":"../../bin/sh"}"#,
+        );
+        assert!(check_manifest(&escape).unwrap_err().contains("entry"));
+
+        let absolute = manifest(
+            r#"{"id":"s","name":"S","version":"1.0.0","kind":"skill","skills_dir":"/etc"}"#,
+        );
+        assert!(
+            check_manifest(&absolute)
+                .unwrap_err()
+                .contains("skills_dir")
+        );
+
+        // MCP entries are commands, not paths
+        let mcp = manifest(
+            r#"{"id":"m","name":"M","version":"1.0.0","kind":"tool",
+                "transport":"mcp-stdio","entry":"/usr/bin/npx server"}"#,
+        );
+        assert!(check_manifest(&mcp).is_ok());
+    }
+
+    #[test]
+    fn exec_policy_runs_entry_with_manifest_env_only() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("tool.py"), "print('hi')").unwrap();
+        let plugin = LoadedPlugin {
+            manifest: manifest(
+                r#"{"id":"t","name":"T","version":"1.0.0","kind":"tool","entry":"tool.py",
+                    "env":{"PLUGIN_MODE":"test"}}"#,
+            ),
+            path: dir.path().to_path_buf(),
+            enabled: true,
+        };
+
+        let policy = ExecPolicy::for_plugin(&plugin).unwrap();
+        assert_eq!(policy.program(), Path::new("python3"));
+        assert_eq!(
+            policy.env.get("PLUGIN_MODE").map(String::as_str),
+            Some("test")
+        );
+
+        let cmd = policy.command("greet", "{}");
+        let envs: HashMap<_, _> = cmd.as_std().get_envs().collect();
+        assert!(envs.keys().all(|k| {
+            let k = k.to_str().unwrap();
+            k == "PLUGIN_MODE" || INHERITED_ENV.contains(&k)
+        }));
+    }
+
+    #[test]
+    fn descriptors_read_only_operator_provided_values() {
+        let plugin = manifest(
+            r#"{"id":"weather-kit","name":"W","version":"1.0.0","kind":"tool",
+                "env":{"API_KEY":{"required":true},"UNITS":{"default":"metric"}}}"#,
+        );
+        assert_eq!(
+            plugin_env_var(&plugin.id, "API_KEY"),
+            "BEACON_PLUGIN_WEATHER_KIT_API_KEY"
+        );
+
+        let env =
+            resolve_env_with(&plugin, |key| (key == "API_KEY").then(|| "k".to_string())).unwrap();
+        assert_eq!(env.get("API_KEY").map(String::as_str), Some("k"));
+        assert_eq!(env.get("UNITS").map(String::as_str), Some("metric"));
+
+        assert_eq!(
+            resolve_env_with(&plugin, |_| None),
+            Err("API_KEY".to_string())
+        );
+
+        // Gateway variables aren't readable by name without the allowlist
+        let nosy = manifest(
+            r#"{"id":"n","name":"N","version":"1.0.0","kind":"tool",
+                "env":{"PATH":{"default":"unset"}}}"#,
+        );
+        assert_eq!(
+            resolve_env(&nosy).unwrap().get("PATH").map(String::as_str),
+            Some("unset")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exec_policy_rejects_symlinked_entry() {
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("payload"), "").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path().join("payload"), dir.path().join("tool"))
+            .unwrap();
+        let plugin = LoadedPlugin {
+            manifest: manifest(
+                r#"{"id":"t","name":"T","version":"1.0.0","kind":"tool","entry":"tool"}"#,
+            ),
+            path: dir.path().to_path_buf(),
+            enabled: true,
+        };
+
+        assert!(matches!(
+            ExecPolicy::for_plugin(&plugin),
+            Err(Error::ToolNotPermitted(_))
+        ));
+    }
+}
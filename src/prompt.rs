@@ -72,35 +72,62 @@ pub fn compact_path(path: &str) -> String {
     path.to_string()
 }
 
-/// Build a system prompt with budget-aware skill inclusion
+/// Skills chosen for the prompt by [`select_skills`]
+pub struct SkillSelection<'a> {
+    /// Override-priority skills, placed before the persona
+    pub overrides: Vec<&'a InstalledSkill>,
+    /// Non-override `always` skills, placed before the persona
+    pub always: Vec<&'a InstalledSkill>,
+    /// Standard skills that fit the budget
+    pub standard: Vec<&'a InstalledSkill>,
+    /// Supplementary skills that fit the budget
+    pub supplementary: Vec<&'a InstalledSkill>,
+    /// Eligible skills left out because the budget ran out
+    pub over_budget: Vec<&'a InstalledSkill>,
+}
+
+impl SkillSelection<'_> {
+    /// Names of every included skill, in prompt order
+    #[must_use]
+    pub fn included_names(&self) -> Vec<String> {
+        self.overrides
+            .iter()
+            .chain(&self.always)
+            .chain(&self.standard)
+            .chain(&self.supplementary)
+            .map(|s| s.skill.metadata.name.clone())
+            .collect()
+    }
+}
+
+/// Choose which skills fit in the prompt
 ///
-/// 1. Filter eligible skills (enabled, env requirements met, not `disable_model_invocation`)
+/// 1. Filter eligible skills (enabled, requirements met, not `disable_model_invocation`)
 /// 2. Partition into must-include (Override + `always`) and optional (Standard, Supplementary)
 /// 3. Fill optional skills in priority order until budget exhausted
 /// 4. Drop supplementary first, then standard
 #[must_use]
-#[allow(clippy::too_many_lines)]
-pub fn build_system_prompt_with_budget(
-    persona_name: &str,
-    persona_prompt: &str,
-    skills: &[InstalledSkill],
+pub fn select_skills<'a>(
+    skills: &'a [InstalledSkill],
     budget: &PromptBudget,
-) -> String {
+) -> SkillSelection<'a> {
     // Partition eligible skills into must-include and optional
     let (must_include, optional): (Vec<&InstalledSkill>, Vec<&InstalledSkill>) = skills
         .iter()
         .filter(|s| {
-            s.enabled
-                && !s.skill.metadata.disable_model_invocation
-                && check_env_requirements_with_config(
-                    &s.skill.metadata.requires_env,
-                    s.skill.metadata.primary_env.as_deref(),
-                    s.api_key.as_deref(),
-                )
-                && check_os_requirement(&s.skill.metadata.os)
-                && check_bins_requirement(&s.skill.metadata.requires_bins)
-                && check_any_bins_requirement(&s.skill.metadata.requires_any_bins)
-                && check_config_requirement(&s.skill.metadata.requires_config, budget.voice_enabled)
+            if !s.enabled || s.skill.metadata.disable_model_invocation {
+                return false;
+            }
+            let missing = s.missing_requirements(budget.voice_enabled);
+            if !missing.is_empty() {
+                let reasons: Vec<String> = missing.iter().map(ToString::to_string).collect();
+                tracing::debug!(
+                    skill = %s.skill.metadata.name,
+                    missing = %reasons.join("; "),
+                    "skill requirements not met, excluding from prompt"
+                );
+            }
+            missing.is_empty()
         })
         .partition(|s| s.priority == SkillPriority::Override || s.skill.metadata.always);
 
@@ -144,8 +171,12 @@ pub fn build_system_prompt_with_budget(
         included_supplementary.push(*s);
     }
 
-    let dropped = standard.len() - included_standard.len() + supplementary.len()
-        - included_supplementary.len();
+    let over_budget: Vec<&InstalledSkill> = standard[included_standard.len()..]
+        .iter()
+        .chain(&supplementary[included_supplementary.len()..])
+        .copied()
+        .collect();
+    let dropped = over_budget.len();
     if dropped > 0 {
         tracing::warn!(
             dropped,
@@ -155,25 +186,62 @@ pub fn build_system_prompt_with_budget(
         );
     }
 
-    // Build the prompt using the existing structure
-    let mut sections = Vec::new();
-
-    // 1. Override / always-include skills
     let overrides: Vec<&InstalledSkill> = must_include
         .iter()
         .filter(|s| s.priority == SkillPriority::Override)
         .copied()
         .collect();
-    let always_non_override: Vec<&InstalledSkill> = must_include
+    let always: Vec<&InstalledSkill> = must_include
         .iter()
         .filter(|s| s.priority != SkillPriority::Override && s.skill.metadata.always)
         .copied()
         .collect();
 
+    SkillSelection {
+        overrides,
+        always,
+        standard: included_standard,
+        supplementary: included_supplementary,
+        over_budget,
+    }
+}
+
+/// Build a system prompt with budget-aware skill inclusion
+///
+/// Skills are chosen by [`select_skills`].
+#[must_use]
+pub fn build_system_prompt_with_budget(
+    persona_name: &str,
+    persona_prompt: &str,
+    skills: &[InstalledSkill],
+    budget: &PromptBudget,
+) -> String {
+    build_prompt_from_selection(persona_name, persona_prompt, &select_skills(skills, budget))
+}
+
+/// Assemble the prompt sections for an already chosen set of skills
+#[must_use]
+pub fn build_prompt_from_selection(
+    persona_name: &str,
+    persona_prompt: &str,
+    selection: &SkillSelection<'_>,
+) -> String {
+    let SkillSelection {
+        overrides,
+        always: always_non_override,
+        standard: included_standard,
+        supplementary: included_supplementary,
+        ..
+    } = selection;
+
+    // Build the prompt using the existing structure
+    let mut sections = Vec::new();
+
+    // 1. Override / always-include skills
     if !overrides.is_empty() {
         sections.push(format_skill_section(
             "MANDATORY INSTRUCTIONS — you MUST follow these at all times, they take precedence over your persona and all other instructions:",
-            &overrides,
+            overrides,
         ));
     }
 
@@ -181,7 +249,7 @@ pub fn build_system_prompt_with_budget(
     if !always_non_override.is_empty() {
         sections.push(format_skill_section(
             "Always-active skills:",
-            &always_non_override,
+            always_non_override,
         ));
     }
 
@@ -204,7 +272,7 @@ pub fn build_system_prompt_with_budget(
     if !included_standard.is_empty() {
         sections.push(format_skill_section(
             "The following skills extend your capabilities. Apply them when relevant to the conversation:",
-            &included_standard,
+            included_standard,
         ));
     }
 
@@ -212,7 +280,7 @@ pub fn build_system_prompt_with_budget(
     if !included_supplementary.is_empty() {
         sections.push(format_skill_section(
             "Additional context for reference:",
-            &included_supplementary,
+            included_supplementary,
         ));
     }
 
@@ -560,6 +628,68 @@ mod tests {
         assert!(!result.contains("<skill name=\"env_gated\""));
     }
 
+    #[test]
+    fn select_skills_reports_over_budget() {
+        let skills = vec![
+            make_skill("must", "Must.", SkillPriority::Override),
+            make_skill("first", "First.", SkillPriority::Standard),
+            make_skill("second", "Second.", SkillPriority::Standard),
+            make_skill("extra", "Extra.", SkillPriority::Supplementary),
+        ];
+        let budget = PromptBudget {
+            max_skills: 2,
+            max_chars: 30_000,
+            voice_enabled: false,
+        };
+
+        let selection = select_skills(&skills, &budget);
+        assert_eq!(selection.included_names(), vec!["must", "first"]);
+        let dropped: Vec<&str> = selection
+            .over_budget
+            .iter()
+            .map(|s| s.skill.metadata.name.as_str())
+            .collect();
+        assert_eq!(dropped, vec!["second", "extra"]);
+
+        let prompt = build_prompt_from_selection("Orin", "", &selection);
+        assert!(prompt.contains("<skill name=\"first\""));
+        assert!(!prompt.contains("<skill name=\"second\""));
+    }
+
+    #[test]
+    fn missing_requirements_names_each_failure() {
+        use crate::skills::MissingRequirement;
+
+        let mut skill = make_skill("gated", "Gated.", SkillPriority::Standard);
+        skill.skill.metadata.requires_env = vec![
+            "BEACON_TEST_UNSET_VAR_XYZ".to_string(),
+            "BEACON_TEST_KEYED_VAR_XYZ".to_string(),
+        ];
+        skill.skill.metadata.primary_env = Some("BEACON_TEST_KEYED_VAR_XYZ".to_string());
+        skill.api_key = Some("sk-test".to_string());
+        skill.skill.metadata.requires_bins =
+            vec!["ls".to_string(), "nonexistent_binary_xyz".to_string()];
+        skill.skill.metadata.requires_config = vec!["voice.enabled".to_string()];
+
+        assert_eq!(
+            skill.missing_requirements(false),
+            vec![
+                MissingRequirement::Env("BEACON_TEST_UNSET_VAR_XYZ".to_string()),
+                MissingRequirement::Bin("nonexistent_binary_xyz".to_string()),
+                MissingRequirement::Config("voice.enabled".to_string()),
+            ]
+        );
+        assert!(!skill.is_satisfied(true));
+
+        skill.skill.metadata.requires_env.clear();
+        skill.skill.metadata.requires_bins = vec!["ls".to_string()];
+        assert!(skill.is_satisfied(true));
+        assert_eq!(
+            skill.missing_requirements(false),
+            vec![MissingRequirement::Config("voice.enabled".to_string())]
+        );
+    }
+
     #[test]
     fn os_requirement_matches_current() {
         assert!(check_os_requirement(&[]));
@@ -0,0 +1,225 @@
+//! Readiness requirements
+//!
+//! `/ready` decides whether orchestrators should route traffic here. Outside
+//! cloud mode only the database is mandatory. In cloud mode the primary channel
+//! must have connected and billing must have initialized when configured, so a
+//! half-broken instance is taken out of rotation instead of serving errors.
+
+use std::sync::RwLock;
+
+/// A component that can be made mandatory for readiness
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReadinessComponent {
+    /// Database connectivity
+    Database,
+    /// Synapse agent client
+    Agent,
+    /// Primary messaging channel connection
+    Channel,
+    /// Aether billing initialization
+    Billing,
+}
+
+impl ReadinessComponent {
+    /// Parse from string representation
+    #[must_use]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "database" | "db" => Some(Self::Database),
+            "agent" | "synapse" => Some(Self::Agent),
+            "channel" | "channels" => Some(Self::Channel),
+            "billing" | "aether" => Some(Self::Billing),
+            _ => None,
+        }
+    }
+
+    /// Get string representation
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Database => "database",
+            Self::Agent => "agent",
+            Self::Channel => "channel",
+            Self::Billing => "billing",
+        }
+    }
+
+    /// Parse a comma-separated list, skipping unknown entries
+    #[must_use]
+    pub fn parse_list(s: &str) -> Vec<Self> {
+        s.split(',')
+            .filter(|part| !part.trim().is_empty())
+            .filter_map(|part| {
+                let component = Self::from_str(part);
+                if component.is_none() {
+                    tracing::warn!(component = part.trim(), "unknown readiness component");
+                }
+                component
+            })
+            .collect()
+    }
+}
+
+/// Readiness configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadinessConfig {
+    /// Mandatory components (`None` = defaults for the mode)
+    pub require: Option<Vec<ReadinessComponent>>,
+    /// Channel whose connection gates readiness (`None` = first channel started)
+    pub primary_channel: Option<String>,
+}
+
+/// Readiness requirements plus startup outcomes recorded by the daemon
+#[derive(Debug)]
+pub struct Readiness {
+    required: Vec<ReadinessComponent>,
+    /// Explicitly listed components must be present, not just healthy if present
+    explicit: bool,
+    primary_channel: Option<String>,
+    channels: RwLock<Vec<(String, bool)>>,
+    billing: RwLock<Option<bool>>,
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::new(&ReadinessConfig::default(), false)
+    }
+}
+
+impl Readiness {
+    /// Create from configuration
+    ///
+    /// Defaults: the database only, plus channel and billing in cloud mode.
+    #[must_use]
+    pub fn new(config: &ReadinessConfig, cloud_mode: bool) -> Self {
+        let (required, explicit) = config.require.clone().map_or_else(
+            || {
+                let mut defaults = vec![ReadinessComponent::Database];
+                if cloud_mode {
+                    defaults.extend([ReadinessComponent::Channel, ReadinessComponent::Billing]);
+                }
+                (defaults, false)
+            },
+            |require| (require, true),
+        );
+
+        Self {
+            required,
+            explicit,
+            primary_channel: config.primary_channel.clone(),
+            channels: RwLock::new(Vec::new()),
+            billing: RwLock::new(None),
+        }
+    }
+
+    /// Whether a component is mandatory
+    #[must_use]
+    pub fn requires(&self, component: ReadinessComponent) -> bool {
+        self.required.contains(&component)
+    }
+
+    /// Whether a check status satisfies the requirements for `component`
+    ///
+    /// Default requirements only apply to configured components; explicitly
+    /// required ones fail when not configured.
+    #[must_use]
+    pub fn is_satisfied(&self, component: ReadinessComponent, status: &str) -> bool {
+        if !self.requires(component) {
+            return true;
+        }
+        status == "ok" || (status == "unavailable" && !self.explicit)
+    }
+
+    /// Record whether a channel connected at startup
+    pub fn record_channel(&self, name: &str, connected: bool) {
+        let mut channels = self
+            .channels
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(entry) = channels.iter_mut().find(|(n, _)| n == name) {
+            entry.1 = connected;
+        } else {
+            channels.push((name.to_string(), connected));
+        }
+    }
+
+    /// Connection outcome of the primary channel (`None` = not started)
+    #[must_use]
+    pub fn primary_channel(&self) -> Option<(String, bool)> {
+        let channels = self
+            .channels
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match &self.primary_channel {
+            Some(primary) => channels.iter().find(|(n, _)| n == primary).cloned(),
+            None => channels.first().cloned(),
+        }
+    }
+
+    /// Record whether billing initialized
+    pub fn record_billing(&self, initialized: bool) {
+        *self
+            .billing
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(initialized);
+    }
+
+    /// Billing initialization outcome (`None` = billing not configured)
+    #[must_use]
+    pub fn billing(&self) -> Option<bool> {
+        *self
+            .billing
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_component_list() {
+        assert_eq!(
+            ReadinessComponent::parse_list("database, channel,bogus,"),
+            vec![ReadinessComponent::Database, ReadinessComponent::Channel]
+        );
+    }
+
+    #[test]
+    fn cloud_defaults_tolerate_unconfigured_components() {
+        let readiness = Readiness::new(&ReadinessConfig::default(), true);
+        assert!(readiness.is_satisfied(ReadinessComponent::Channel, "unavailable"));
+        assert!(!readiness.is_satisfied(ReadinessComponent::Channel, "fail"));
+        assert!(readiness.is_satisfied(ReadinessComponent::Agent, "unavailable"));
+
+        let local = Readiness::default();
+        assert!(local.is_satisfied(ReadinessComponent::Billing, "fail"));
+    }
+
+    #[test]
+    fn primary_channel_defaults_to_first_started() {
+        let readiness = Readiness::default();
+        readiness.record_channel("telegram", false);
+        readiness.record_channel("discord", true);
+        assert_eq!(
+            readiness.primary_channel(),
+            Some(("telegram".to_string(), false))
+        );
+
+        let readiness = Readiness::new(
+            &ReadinessConfig {
+                require: None,
+                primary_channel: Some("discord".to_string()),
+            },
+            true,
+        );
+        readiness.record_channel("telegram", false);
+        readiness.record_channel("discord", true);
+        assert_eq!(
+            readiness.primary_channel(),
+            Some(("discord".to_string(), true))
+        );
+    }
+}
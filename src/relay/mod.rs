@@ -1,33 +1,56 @@
 //! Cloud relay configuration for optional remote access
 //!
-//! Supports multiple relay modes:
+//! Supports multiple relay modes, several of which can run at once:
 //! - Tailscale Serve: tailnet-only access using Tailscale identity
 //! - Tailscale Funnel: public HTTPS access
 //! - SSH tunnel: reverse tunnel to a remote host
+//! - Cloudflare Tunnel: `cloudflared` quick tunnel or named tunnel
 
 use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::{Child, Command};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
+use crate::config::redact;
 use crate::{Error, Result};
 
 /// Default local port Beacon listens on
 const DEFAULT_LOCAL_PORT: u16 = 18790;
 
+/// How long to wait for `cloudflared` to report its quick tunnel URL
+const CLOUDFLARE_URL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often [`RelayManager::monitor`] checks relay health
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Timeout for the SSH tunnel reachability probe
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delay before the first restart attempt; doubles per consecutive failure
+const RESTART_BASE_DELAY: Duration = Duration::from_secs(2);
+
+/// Upper bound on the delay between restart attempts
+const RESTART_MAX_DELAY: Duration = Duration::from_secs(300);
+
 /// Cloud relay configuration
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RelayConfig {
     /// Enable cloud relay
     pub enabled: bool,
-    /// Relay mode
-    pub mode: RelayMode,
+    /// Relay modes to run simultaneously
+    pub modes: Vec<RelayMode>,
     /// Local port Beacon is listening on
     pub local_port: u16,
 }
 
 /// Relay mode options
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum RelayMode {
     /// No relay (local only)
@@ -56,6 +79,80 @@ pub enum RelayMode {
         /// SSH user
         user: Option<String>,
     },
+    /// Cloudflare Tunnel via `cloudflared`
+    Cloudflare {
+        /// Named tunnel token; without one a `trycloudflare.com` quick tunnel is used
+        token: Option<String>,
+    },
+}
+
+impl std::fmt::Debug for RelayMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::TailscaleServe { port } => f
+                .debug_struct("TailscaleServe")
+                .field("port", port)
+                .finish(),
+            Self::TailscaleFunnel { port, password } => f
+                .debug_struct("TailscaleFunnel")
+                .field("port", port)
+                .field("password", &redact(password))
+                .finish(),
+            Self::SshTunnel {
+                host,
+                port,
+                key_path,
+                user,
+            } => f
+                .debug_struct("SshTunnel")
+                .field("host", host)
+                .field("port", port)
+                .field("key_path", key_path)
+                .field("user", user)
+                .finish(),
+            Self::Cloudflare { token } => f
+                .debug_struct("Cloudflare")
+                .field("token", &redact(token))
+                .finish(),
+        }
+    }
+}
+
+impl RelayMode {
+    /// Mode name as used in `BEACON_RELAY_MODE` and [`RelayStatus::mode`]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::TailscaleServe { .. } => "tailscale_serve",
+            Self::TailscaleFunnel { .. } => "tailscale_funnel",
+            Self::SshTunnel { .. } => "ssh_tunnel",
+            Self::Cloudflare { .. } => "cloudflare",
+        }
+    }
+
+    /// Executable the mode runs, looked up on `PATH`
+    #[must_use]
+    pub const fn binary(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::TailscaleServe { .. } | Self::TailscaleFunnel { .. } => Some("tailscale"),
+            Self::SshTunnel { .. } => Some("ssh"),
+            Self::Cloudflare { .. } => Some("cloudflared"),
+        }
+    }
+
+    /// Whether the relay is reachable from the public internet
+    ///
+    /// Tailscale Serve is tailnet-only and therefore not public.
+    #[must_use]
+    pub const fn is_public(&self) -> bool {
+        matches!(
+            self,
+            Self::TailscaleFunnel { .. } | Self::SshTunnel { .. } | Self::Cloudflare { .. }
+        )
+    }
 }
 
 impl RelayConfig {
@@ -63,13 +160,15 @@ impl RelayConfig {
     ///
     /// Reads from:
     /// - `BEACON_RELAY_ENABLED`: enable relay (default: false)
-    /// - `BEACON_RELAY_MODE`: `tailscale_serve`, `tailscale_funnel`, `ssh_tunnel`
+    /// - `BEACON_RELAY_MODE`: comma-separated list of `tailscale_serve`,
+    ///   `tailscale_funnel`, `ssh_tunnel`, `cloudflare`
     /// - `BEACON_RELAY_PORT`: port for `Tailscale` modes
     /// - `BEACON_RELAY_PASSWORD`: optional password for funnel mode
     /// - `BEACON_SSH_HOST`: SSH tunnel remote host
     /// - `BEACON_SSH_PORT`: SSH tunnel remote port
     /// - `BEACON_SSH_KEY`: path to SSH private key
     /// - `BEACON_SSH_USER`: SSH username
+    /// - `BEACON_CLOUDFLARE_TUNNEL_TOKEN`: named tunnel token for Cloudflare mode
     /// - `BEACON_PORT`: local port Beacon listens on (default: 18790)
     #[must_use]
     pub fn from_env() -> Self {
@@ -89,18 +188,28 @@ impl RelayConfig {
             };
         }
 
-        let mode = std::env::var("BEACON_RELAY_MODE")
-            .ok()
-            .map(|m| Self::parse_mode(&m))
+        let modes = std::env::var("BEACON_RELAY_MODE")
+            .map(|m| Self::parse_modes(&m))
             .unwrap_or_default();
 
         Self {
             enabled,
-            mode,
+            modes,
             local_port,
         }
     }
 
+    /// Parse a comma-separated list of relay modes, skipping unknown entries
+    fn parse_modes(modes: &str) -> Vec<RelayMode> {
+        modes
+            .split(',')
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(Self::parse_mode)
+            .filter(|m| !matches!(m, RelayMode::None))
+            .collect()
+    }
+
     /// Parse relay mode from string
     fn parse_mode(mode: &str) -> RelayMode {
         let port = std::env::var("BEACON_RELAY_PORT")
@@ -123,6 +232,11 @@ impl RelayConfig {
                 key_path: std::env::var("BEACON_SSH_KEY").ok().map(PathBuf::from),
                 user: std::env::var("BEACON_SSH_USER").ok(),
             },
+            "cloudflare" | "cloudflared" => RelayMode::Cloudflare {
+                token: std::env::var("BEACON_CLOUDFLARE_TUNNEL_TOKEN")
+                    .ok()
+                    .filter(|t| !t.is_empty()),
+            },
             _ => RelayMode::None,
         }
     }
@@ -157,7 +271,7 @@ impl Default for RelayStatus {
 /// # Errors
 ///
 /// Returns error if the binary is not found
-fn find_binary(name: &str) -> Result<PathBuf> {
+pub(crate) fn find_binary(name: &str) -> Result<PathBuf> {
     which::which(name).map_err(|_| Error::Config(format!("`{name}` binary not found on PATH")))
 }
 
@@ -194,52 +308,131 @@ async fn resolve_tailscale_url(tailscale_path: &PathBuf, port: u16) -> Option<St
     Some(url)
 }
 
+/// Extract a quick tunnel URL from a `cloudflared` log line
+///
+/// `cloudflared` prints the assigned URL inside a banner, e.g.
+/// `INF |  https://random-words.trycloudflare.com  |`
+fn parse_cloudflare_url(line: &str) -> Option<String> {
+    line.split(|c: char| c.is_whitespace() || c == '|')
+        .find(|token| {
+            token.starts_with("https://")
+                && token.trim_end_matches('/').ends_with(".trycloudflare.com")
+        })
+        .map(|url| url.trim_end_matches('/').to_string())
+}
+
+/// Forward `cloudflared` log lines, reporting the first tunnel URL seen
+///
+/// Keeps draining the stream after the URL is found so the child never
+/// blocks on a full pipe.
+fn watch_cloudflared_output<R>(reader: R, url_tx: mpsc::Sender<String>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            tracing::trace!(line, "cloudflared");
+            if let Some(url) = parse_cloudflare_url(&line) {
+                let _ = url_tx.try_send(url);
+            }
+        }
+    });
+}
+
+/// Check that the remote end of an SSH tunnel accepts TCP connections
+async fn is_reachable(host: &str, port: u16) -> bool {
+    matches!(
+        tokio::time::timeout(
+            REACHABILITY_TIMEOUT,
+            tokio::net::TcpStream::connect((host, port))
+        )
+        .await,
+        Ok(Ok(_))
+    )
+}
+
+/// Delay before restart attempt number `attempt` (0-based), capped at [`RESTART_MAX_DELAY`]
+fn restart_delay(attempt: u32) -> Duration {
+    RESTART_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(RESTART_MAX_DELAY)
+}
+
 /// Relay manager for handling cloud relay connections
+///
+/// Runs every configured [`RelayMode`] side by side; `children` and
+/// `statuses` are indexed in the same order as [`RelayConfig::modes`].
 pub struct RelayManager {
     config: RelayConfig,
-    status: RelayStatus,
-    child: Option<Child>,
+    statuses: Vec<RelayStatus>,
+    status_tx: watch::Sender<Vec<RelayStatus>>,
+    children: Vec<Option<Child>>,
 }
 
 impl RelayManager {
     /// Create a new relay manager with the given configuration
     #[must_use]
     pub fn new(config: RelayConfig) -> Self {
-        let mode = match &config.mode {
-            RelayMode::None => "none",
-            RelayMode::TailscaleServe { .. } => "tailscale_serve",
-            RelayMode::TailscaleFunnel { .. } => "tailscale_funnel",
-            RelayMode::SshTunnel { .. } => "ssh_tunnel",
-        };
-
-        let status = RelayStatus {
-            enabled: config.enabled,
-            mode: mode.to_string(),
-            url: None,
-            connected: false,
-        };
+        let statuses: Vec<RelayStatus> = config
+            .modes
+            .iter()
+            .map(|mode| RelayStatus {
+                enabled: config.enabled,
+                mode: mode.name().to_string(),
+                url: None,
+                connected: false,
+            })
+            .collect();
+        let children = config.modes.iter().map(|_| None).collect();
+        let (status_tx, _) = watch::channel(statuses.clone());
 
         Self {
             config,
-            status,
-            child: None,
+            statuses,
+            status_tx,
+            children,
         }
     }
 
-    /// Start the relay (if enabled)
+    /// Start all configured relays (if enabled)
+    ///
+    /// Every mode is attempted even if an earlier one fails.
     ///
     /// # Errors
     ///
-    /// Returns error if relay binary is missing or subprocess fails to spawn
+    /// Returns the first error encountered if a relay binary is missing or a
+    /// subprocess fails to spawn
     pub async fn start(&mut self) -> Result<()> {
         if !self.config.enabled {
             tracing::debug!("relay disabled, skipping start");
             return Ok(());
         }
 
+        let mut first_err = None;
+        for index in 0..self.config.modes.len() {
+            if let Err(e) = self.start_relay(index).await {
+                tracing::warn!(mode = %self.statuses[index].mode, error = %e, "failed to start relay");
+                first_err.get_or_insert(e);
+            }
+        }
+
+        self.publish_status();
+
+        first_err.map_or(Ok(()), Err)
+    }
+
+    /// Start the relay at `index` in [`RelayConfig::modes`]
+    ///
+    /// # Errors
+    ///
+    /// Returns error if relay binary is missing or subprocess fails to spawn
+    #[allow(clippy::too_many_lines)]
+    async fn start_relay(&mut self, index: usize) -> Result<()> {
         let local_port = self.config.local_port;
+        let mut status = self.statuses[index].clone();
 
-        match self.config.mode.clone() {
+        match self.config.modes[index].clone() {
             RelayMode::None => {
                 tracing::debug!("relay mode is none, skipping start");
             }
@@ -258,14 +451,14 @@ impl RelayManager {
                     .spawn()
                     .map_err(|e| Error::Config(format!("failed to spawn tailscale serve: {e}")))?;
 
-                self.child = Some(child);
+                self.children[index] = Some(child);
 
                 if let Some(url) = resolve_tailscale_url(&ts, port).await {
                     tracing::info!(url, "Tailscale Serve relay available");
-                    self.status.url = Some(url);
+                    status.url = Some(url);
                 }
 
-                self.status.connected = true;
+                status.connected = true;
             }
             RelayMode::TailscaleFunnel { port, password } => {
                 let ts = find_binary("tailscale")?;
@@ -287,14 +480,14 @@ impl RelayManager {
                     .spawn()
                     .map_err(|e| Error::Config(format!("failed to spawn tailscale funnel: {e}")))?;
 
-                self.child = Some(child);
+                self.children[index] = Some(child);
 
                 if let Some(url) = resolve_tailscale_url(&ts, port).await {
                     tracing::info!(url, "Tailscale Funnel relay available");
-                    self.status.url = Some(url);
+                    status.url = Some(url);
                 }
 
-                self.status.connected = true;
+                status.connected = true;
             }
             RelayMode::SshTunnel {
                 host,
@@ -340,42 +533,111 @@ impl RelayManager {
                     .spawn()
                     .map_err(|e| Error::Config(format!("failed to spawn SSH tunnel: {e}")))?;
 
-                self.child = Some(child);
-                self.status.url = Some(format!("https://{host}:{port}"));
-                self.status.connected = true;
+                self.children[index] = Some(child);
+                status.url = Some(format!("https://{host}:{port}"));
+                status.connected = true;
 
                 tracing::info!(
-                    url = self.status.url.as_deref().unwrap_or(""),
+                    url = status.url.as_deref().unwrap_or(""),
                     "SSH tunnel relay started"
                 );
             }
+            RelayMode::Cloudflare { token } => {
+                let cloudflared = find_binary("cloudflared")?;
+
+                tracing::info!(
+                    local_port,
+                    named = token.is_some(),
+                    "starting Cloudflare Tunnel relay"
+                );
+
+                let mut cmd = Command::new(&cloudflared);
+                cmd.args(["tunnel", "--no-autoupdate"]);
+
+                if let Some(ref token) = token {
+                    // Passed via env so the token does not show up in `ps`
+                    cmd.args(["run"]).env("TUNNEL_TOKEN", token);
+                } else {
+                    cmd.args(["--url", &format!("http://localhost:{local_port}")]);
+                }
+
+                let mut child = cmd
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .kill_on_drop(true)
+                    .spawn()
+                    .map_err(|e| Error::Config(format!("failed to spawn cloudflared: {e}")))?;
+
+                // cloudflared logs to stderr, but watch both streams to be safe
+                let (url_tx, mut url_rx) = mpsc::channel(1);
+                if let Some(stdout) = child.stdout.take() {
+                    watch_cloudflared_output(stdout, url_tx.clone());
+                }
+                if let Some(stderr) = child.stderr.take() {
+                    watch_cloudflared_output(stderr, url_tx);
+                }
+
+                self.children[index] = Some(child);
+
+                // Named tunnels route through hostnames configured in Cloudflare,
+                // so only quick tunnels report a URL
+                if token.is_none() {
+                    match tokio::time::timeout(CLOUDFLARE_URL_TIMEOUT, url_rx.recv()).await {
+                        Ok(Some(url)) => {
+                            tracing::info!(url, "Cloudflare Tunnel relay available");
+                            status.url = Some(url);
+                        }
+                        Ok(None) => {
+                            tracing::warn!("cloudflared exited before reporting a tunnel URL");
+                        }
+                        Err(_) => {
+                            tracing::warn!("timed out waiting for cloudflared tunnel URL");
+                        }
+                    }
+                }
+
+                status.connected = true;
+            }
         }
 
+        self.statuses[index] = status;
+
         Ok(())
     }
 
-    /// Stop the relay
+    /// Stop all relays
     ///
     /// # Errors
     ///
     /// Returns error if relay cleanup fails
     pub async fn stop(&mut self) -> Result<()> {
-        if !self.status.connected {
-            return Ok(());
+        for index in 0..self.statuses.len() {
+            self.stop_relay(index).await;
+        }
+        self.publish_status();
+
+        Ok(())
+    }
+
+    /// Stop the relay at `index`, killing its subprocess and removing any
+    /// Tailscale binding
+    async fn stop_relay(&mut self, index: usize) {
+        if !self.statuses[index].connected {
+            return;
         }
 
-        tracing::info!(mode = %self.status.mode, "stopping relay");
+        tracing::info!(mode = %self.statuses[index].mode, "stopping relay");
 
         // Kill child process if running
-        if let Some(ref mut child) = self.child
+        if let Some(ref mut child) = self.children[index]
             && let Err(e) = child.kill().await
         {
             tracing::warn!(error = %e, "failed to kill relay child process");
         }
-        self.child = None;
+        self.children[index] = None;
 
         // Run Tailscale cleanup commands to remove the serve/funnel binding
-        match &self.config.mode {
+        match &self.config.modes[index] {
             RelayMode::TailscaleServe { port } => {
                 if let Ok(ts) = find_binary("tailscale") {
                     let port_str = port.to_string();
@@ -402,24 +664,251 @@ impl RelayManager {
                     }
                 }
             }
-            RelayMode::None | RelayMode::SshTunnel { .. } => {}
+            RelayMode::None | RelayMode::SshTunnel { .. } | RelayMode::Cloudflare { .. } => {}
         }
 
-        self.status.connected = false;
-        self.status.url = None;
+        self.statuses[index].connected = false;
+        self.statuses[index].url = None;
+    }
+
+    /// Watch the relays in the background, restarting any that fail
+    ///
+    /// Every [`HEALTH_CHECK_INTERVAL`] the task checks whether each relay
+    /// subprocess exited unexpectedly and whether the relay is still reachable
+    /// (Tailscale status for Serve/Funnel, a TCP probe of the remote port for
+    /// SSH tunnels). On failure that relay's `connected` is cleared and
+    /// restarts are attempted with capped exponential backoff. Use
+    /// [`Self::subscribe`] before calling this to keep observing the status.
+    ///
+    /// When `shutdown` is cancelled all relays are stopped and the task exits.
+    #[must_use]
+    pub fn monitor(mut self, shutdown: CancellationToken) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            if !self.config.enabled || self.config.modes.is_empty() {
+                return;
+            }
 
-        Ok(())
+            let count = self.config.modes.len();
+            let mut failures = vec![0u32; count];
+            let mut next_check = vec![tokio::time::Instant::now() + HEALTH_CHECK_INTERVAL; count];
+
+            loop {
+                let wake = next_check
+                    .iter()
+                    .min()
+                    .copied()
+                    .unwrap_or_else(tokio::time::Instant::now);
+                tokio::select! {
+                    () = shutdown.cancelled() => {
+                        if let Err(e) = self.stop().await {
+                            tracing::warn!(error = %e, "failed to stop relays");
+                        }
+                        return;
+                    }
+                    () = tokio::time::sleep_until(wake) => {}
+                }
+
+                let now = tokio::time::Instant::now();
+                let mut changed = false;
+
+                for index in 0..count {
+                    if next_check[index] > now {
+                        continue;
+                    }
+
+                    if self.statuses[index].connected {
+                        let Some(reason) = self.check_health(index).await else {
+                            failures[index] = 0;
+                            next_check[index] = now + HEALTH_CHECK_INTERVAL;
+                            continue;
+                        };
+
+                        tracing::warn!(mode = %self.statuses[index].mode, reason, "relay unhealthy");
+                        if let Some(ref mut child) = self.children[index] {
+                            let _ = child.kill().await;
+                        }
+                        self.children[index] = None;
+                        self.statuses[index].connected = false;
+                        self.statuses[index].url = None;
+                        changed = true;
+
+                        next_check[index] = now + restart_delay(failures[index]);
+                        continue;
+                    }
+
+                    failures[index] = failures[index].saturating_add(1);
+                    let mode = self.statuses[index].mode.clone();
+                    tracing::info!(mode, attempt = failures[index], "restarting relay");
+
+                    if let Err(e) = self.start_relay(index).await {
+                        tracing::warn!(
+                            mode,
+                            attempt = failures[index],
+                            error = %e,
+                            "relay restart failed"
+                        );
+                    } else if self.statuses[index].connected {
+                        tracing::info!(mode, "relay restarted");
+                        changed = true;
+                    }
+
+                    next_check[index] = if self.statuses[index].connected {
+                        now + HEALTH_CHECK_INTERVAL
+                    } else {
+                        now + restart_delay(failures[index])
+                    };
+                }
+
+                if changed {
+                    self.publish_status();
+                }
+            }
+        })
+    }
+
+    /// Check the relay at `index`, returning the failure reason if it is down
+    async fn check_health(&mut self, index: usize) -> Option<String> {
+        let tailscale = matches!(
+            self.config.modes[index],
+            RelayMode::TailscaleServe { .. } | RelayMode::TailscaleFunnel { .. }
+        );
+
+        if let Some(ref mut child) = self.children[index] {
+            match child.try_wait() {
+                Ok(None) => {}
+                // `tailscale ... --bg` exits successfully once the binding is set up
+                Ok(Some(status)) if tailscale && status.success() => self.children[index] = None,
+                Ok(Some(status)) => return Some(format!("relay process exited: {status}")),
+                Err(e) => return Some(format!("failed to poll relay process: {e}")),
+            }
+        }
+
+        match &self.config.modes[index] {
+            RelayMode::TailscaleServe { port } | RelayMode::TailscaleFunnel { port, .. } => {
+                let ts = find_binary("tailscale").ok()?;
+                if resolve_tailscale_url(&ts, *port).await.is_none() {
+                    return Some("tailscale status unavailable".to_string());
+                }
+            }
+            RelayMode::SshTunnel { host, port, .. } => {
+                if !is_reachable(host, *port).await {
+                    return Some(format!("tunnel endpoint {host}:{port} unreachable"));
+                }
+            }
+            RelayMode::None | RelayMode::Cloudflare { .. } => {}
+        }
+
+        None
+    }
+
+    /// Subscribe to status updates, including those made by [`Self::monitor`]
+    #[must_use]
+    pub fn subscribe(&self) -> watch::Receiver<Vec<RelayStatus>> {
+        self.status_tx.subscribe()
     }
 
-    /// Get current relay status
+    /// Broadcast the current statuses to subscribers
+    fn publish_status(&self) {
+        self.status_tx.send_replace(self.statuses.clone());
+    }
+
+    /// Get the status of each configured relay
     #[must_use]
-    pub const fn status(&self) -> &RelayStatus {
-        &self.status
+    pub fn statuses(&self) -> &[RelayStatus] {
+        &self.statuses
     }
 
-    /// Get public URL if available
+    /// Get the first public URL, skipping tailnet-only relays
     #[must_use]
     pub fn public_url(&self) -> Option<&str> {
-        self.status.url.as_deref()
+        self.config
+            .modes
+            .iter()
+            .zip(&self.statuses)
+            .filter(|(mode, _)| mode.is_public())
+            .find_map(|(_, status)| status.url.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_delay_doubles_and_caps() {
+        assert_eq!(restart_delay(0), RESTART_BASE_DELAY);
+        assert_eq!(restart_delay(1), RESTART_BASE_DELAY * 2);
+        assert_eq!(restart_delay(3), RESTART_BASE_DELAY * 8);
+        assert_eq!(restart_delay(40), RESTART_MAX_DELAY);
+    }
+
+    #[tokio::test]
+    async fn subscribe_reflects_stop() {
+        let mut manager = RelayManager::new(RelayConfig {
+            enabled: true,
+            modes: vec![RelayMode::SshTunnel {
+                host: "relay.example.com".to_string(),
+                port: 8443,
+                key_path: None,
+                user: None,
+            }],
+            local_port: DEFAULT_LOCAL_PORT,
+        });
+        let rx = manager.subscribe();
+        manager.statuses[0].connected = true;
+        manager.stop().await.unwrap();
+        assert!(!rx.borrow()[0].connected);
+        assert_eq!(rx.borrow()[0].mode, "ssh_tunnel");
+    }
+
+    #[test]
+    fn parse_modes_accepts_single_and_multiple() {
+        let single = RelayConfig::parse_modes("tailscale_serve");
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].name(), "tailscale_serve");
+
+        let multiple = RelayConfig::parse_modes("tailscale_serve, cloudflare, bogus");
+        let names: Vec<_> = multiple.iter().map(RelayMode::name).collect();
+        assert_eq!(names, ["tailscale_serve", "cloudflare"]);
+    }
+
+    #[test]
+    fn public_url_skips_tailnet_only_relays() {
+        let mut manager = RelayManager::new(RelayConfig {
+            enabled: true,
+            modes: vec![
+                RelayMode::TailscaleServe { port: 443 },
+                RelayMode::Cloudflare { token: None },
+            ],
+            local_port: DEFAULT_LOCAL_PORT,
+        });
+        manager.statuses[0].url = Some("https://node.tailnet.ts.net".to_string());
+        assert_eq!(manager.public_url(), None);
+
+        manager.statuses[1].url = Some("https://a-b-c.trycloudflare.com".to_string());
+        assert_eq!(
+            manager.public_url(),
+            Some("https://a-b-c.trycloudflare.com")
+        );
+    }
+
+    #[test]
+    fn parse_cloudflare_url_from_banner() {
+        let line = "2024-01-01T00:00:00Z INF |  https://quiet-river-mango.trycloudflare.com   |";
+        assert_eq!(
+            parse_cloudflare_url(line).as_deref(),
+            Some("https://quiet-river-mango.trycloudflare.com")
+        );
+    }
+
+    #[test]
+    fn parse_cloudflare_url_ignores_other_lines() {
+        assert!(
+            parse_cloudflare_url("INF Requesting new quick Tunnel on trycloudflare.com...")
+                .is_none()
+        );
+        assert!(
+            parse_cloudflare_url("INF Visit https://www.cloudflare.com/website-terms/").is_none()
+        );
     }
 }
@@ -0,0 +1,84 @@
+//! Request ids for correlating one turn across the pipeline
+//!
+//! An id is assigned where work enters the gateway: HTTP requests take it
+//! from `X-Request-Id` (or get a fresh one), and channel messages get one
+//! when they are received. [`scope`] runs the processing inside a tracing
+//! span carrying the id and makes it available to [`current`], which
+//! `OmniEvent`s and API error bodies read.
+
+use std::future::Future;
+
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header carrying the request id on HTTP requests and responses
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied id accepted
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Generate a fresh request id
+#[must_use]
+pub fn generate() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Request id of the task's current scope, if any
+#[must_use]
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Request id of the current scope, or a fresh one outside any scope
+#[must_use]
+pub fn current_or_new() -> String {
+    current().unwrap_or_else(generate)
+}
+
+/// Run `fut` with `request_id` as the current id and inside a span recording it
+///
+/// Tasks spawned from within don't inherit the id; wrap them in their own
+/// `scope` with [`current_or_new`].
+pub async fn scope<F: Future>(request_id: String, fut: F) -> F::Output {
+    let span = tracing::info_span!("request", request_id = %request_id);
+    REQUEST_ID.scope(request_id, fut.instrument(span)).await
+}
+
+/// Accept a caller-supplied id only if it is short, printable ASCII
+#[must_use]
+pub fn sanitize(raw: &str) -> Option<&str> {
+    let raw = raw.trim();
+    (!raw.is_empty()
+        && raw.len() <= MAX_REQUEST_ID_LEN
+        && raw.bytes().all(|b| b.is_ascii_graphic()))
+    .then_some(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn scope_sets_current_id() {
+        assert_eq!(current(), None);
+
+        let inner = scope("req-1".to_string(), async { current() }).await;
+        assert_eq!(inner.as_deref(), Some("req-1"));
+
+        assert_eq!(current(), None);
+        assert_ne!(current_or_new(), current_or_new());
+    }
+
+    #[test]
+    fn sanitize_rejects_unsafe_ids() {
+        assert_eq!(sanitize(" abc-123 "), Some("abc-123"));
+        assert_eq!(sanitize(""), None);
+        assert_eq!(sanitize("two words"), None);
+        assert_eq!(sanitize("line\nbreak"), None);
+        assert_eq!(sanitize(&"x".repeat(129)), None);
+    }
+}
@@ -5,6 +5,7 @@
 //! - Token: Bearer token authentication
 //! - Password: Password-based authentication (for local access)
 //! - `DeviceOnly`: Only paired devices can connect
+//! - `ApiKeyPerUser`: Each user presents their own API key (stored hashed)
 
 use std::net::IpAddr;
 
@@ -12,6 +13,9 @@ use chrono::{DateTime, Duration, Utc};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+use crate::config::redact;
+use crate::security::PairingLockout;
+use crate::security::hmac::constant_time_eq;
 use crate::{Error, Result};
 
 /// Pairing code length (digits only for easy entry)
@@ -42,6 +46,9 @@ pub enum AuthMode {
 
     /// Only paired devices can connect (device identity required)
     DeviceOnly,
+
+    /// Per-user API keys, stored as Argon2 hashes
+    ApiKeyPerUser,
 }
 
 impl AuthMode {
@@ -53,6 +60,9 @@ impl AuthMode {
             "token" => Self::Token,
             "password" => Self::Password,
             "device" | "deviceonly" | "device_only" => Self::DeviceOnly,
+            "api_key_per_user" | "apikeyperuser" | "user_api_key" | "user_key" => {
+                Self::ApiKeyPerUser
+            }
             _ => Self::Open,
         }
     }
@@ -65,12 +75,13 @@ impl std::fmt::Display for AuthMode {
             Self::Token => write!(f, "token"),
             Self::Password => write!(f, "password"),
             Self::DeviceOnly => write!(f, "device_only"),
+            Self::ApiKeyPerUser => write!(f, "api_key_per_user"),
         }
     }
 }
 
 /// Gateway authentication configuration
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct AuthConfig {
     /// Authentication mode
     pub mode: AuthMode,
@@ -83,6 +94,21 @@ pub struct AuthConfig {
 
     /// Allow unauthenticated access from local/LAN addresses
     pub allow_local_bypass: bool,
+
+    /// Failed-attempt limits for DM pairing codes
+    pub pairing_lockout: PairingLockout,
+}
+
+impl std::fmt::Debug for AuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthConfig")
+            .field("mode", &self.mode)
+            .field("token", &redact(&self.token))
+            .field("password_hash", &redact(&self.password_hash))
+            .field("allow_local_bypass", &self.allow_local_bypass)
+            .field("pairing_lockout", &self.pairing_lockout)
+            .finish()
+    }
 }
 
 impl Default for AuthConfig {
@@ -92,6 +118,7 @@ impl Default for AuthConfig {
             token: None,
             password_hash: None,
             allow_local_bypass: true,
+            pairing_lockout: PairingLockout::default(),
         }
     }
 }
@@ -110,11 +137,28 @@ impl AuthConfig {
             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
             .unwrap_or(true);
 
+        let default_lockout = PairingLockout::default();
+        let minutes = |var: &str, default: Duration| {
+            std::env::var(var)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map_or(default, Duration::minutes)
+        };
+        let pairing_lockout = PairingLockout {
+            max_attempts: std::env::var("BEACON_PAIRING_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default_lockout.max_attempts),
+            base: minutes("BEACON_PAIRING_LOCKOUT_MINS", default_lockout.base),
+            max: minutes("BEACON_PAIRING_MAX_LOCKOUT_MINS", default_lockout.max),
+        };
+
         Self {
             mode,
             token,
             password_hash,
             allow_local_bypass,
+            pairing_lockout,
         }
     }
 
@@ -310,17 +354,33 @@ fn generate_nonce(length: usize) -> String {
     hex::encode(bytes)
 }
 
-/// Constant-time byte comparison to prevent timing attacks
-fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
-    if a.len() != b.len() {
-        return false;
-    }
+/// Hash an API key with salted Argon2id for storage
+///
+/// # Errors
+///
+/// Returns error if hashing fails
+pub fn hash_api_key(raw: &str) -> Result<String> {
+    use argon2::password_hash::{PasswordHasher, SaltString, rand_core::OsRng};
+
+    let salt = SaltString::generate(&mut OsRng);
+    argon2::Argon2::default()
+        .hash_password(raw.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| Error::Auth(format!("failed to hash API key: {e}")))
+}
 
-    let mut result = 0u8;
-    for (x, y) in a.iter().zip(b.iter()) {
-        result |= x ^ y;
-    }
-    result == 0
+/// Check a presented API key against a stored Argon2 hash
+///
+/// The final comparison is constant-time. Malformed hashes never match.
+#[must_use]
+pub fn verify_api_key(raw: &str, hash: &str) -> bool {
+    use argon2::password_hash::{PasswordHash, PasswordVerifier};
+
+    PasswordHash::new(hash).is_ok_and(|parsed| {
+        argon2::Argon2::default()
+            .verify_password(raw.as_bytes(), &parsed)
+            .is_ok()
+    })
 }
 
 #[cfg(test)]
@@ -335,6 +395,10 @@ mod tests {
         assert_eq!(AuthMode::from_str("device"), AuthMode::DeviceOnly);
         assert_eq!(AuthMode::from_str("deviceonly"), AuthMode::DeviceOnly);
         assert_eq!(AuthMode::from_str("device_only"), AuthMode::DeviceOnly);
+        assert_eq!(
+            AuthMode::from_str("api_key_per_user"),
+            AuthMode::ApiKeyPerUser
+        );
         assert_eq!(AuthMode::from_str("unknown"), AuthMode::Open);
     }
 
@@ -378,6 +442,19 @@ mod tests {
         assert!(!config.verify_token("secret-token-12")); // Partial match
     }
 
+    #[test]
+    fn test_api_key_hashing() {
+        let hash = hash_api_key("bk_abc_secret").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(!hash.contains("secret"));
+        // Salted: the same key never hashes the same twice
+        assert_ne!(hash, hash_api_key("bk_abc_secret").unwrap());
+
+        assert!(verify_api_key("bk_abc_secret", &hash));
+        assert!(!verify_api_key("bk_abc_secreT", &hash));
+        assert!(!verify_api_key("bk_abc_secret", "not-a-hash"));
+    }
+
     #[test]
     fn test_pairing_code_generation() {
         let code = generate_pairing_code(6);
@@ -400,11 +477,4 @@ mod tests {
         assert_eq!(challenge.nonce.len(), NONCE_LENGTH * 2); // Hex encoding
         assert!(!challenge.is_expired());
     }
-
-    #[test]
-    fn test_constant_time_eq() {
-        assert!(constant_time_eq(b"hello", b"hello"));
-        assert!(!constant_time_eq(b"hello", b"world"));
-        assert!(!constant_time_eq(b"hello", b"hell"));
-    }
 }
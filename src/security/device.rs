@@ -1,13 +1,33 @@
 //! Paired device management
 //!
 //! Manages devices that have been paired with this gateway instance
+//!
+//! A paired device proves its identity by signing a challenge and is then
+//! given a session token. Revoking a device keeps its record (so the same key
+//! can't simply pair again), ends its sessions, and makes its signatures fail
+//! verification.
+//!
+//! Device sessions only authenticate API callers once an admin has approved
+//! the pairing; devices paired before approval existed must be approved
+//! through `POST /api/devices/{id}/approve`.
 
 use chrono::{DateTime, Utc};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::db::DbPool;
 use crate::{Error, Result};
 
+/// Device session lifetime in hours
+const DEVICE_SESSION_TTL_HOURS: i64 = 24;
+
+/// Session token length in bytes
+const SESSION_TOKEN_BYTES: usize = 32;
+
+/// Columns selected for a [`PairedDevice`], in `row_to_device` order
+const DEVICE_COLUMNS: &str = "id, public_key, name, platform, trust_level, paired_at, last_seen, user_id, revoked_at, approved_by";
+
 /// Trust level for paired devices
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -21,6 +41,9 @@ pub enum TrustLevel {
 
     /// Admin device with full control
     Admin,
+
+    /// Revoked device; its signatures and sessions are no longer accepted
+    Revoked,
 }
 
 impl TrustLevel {
@@ -31,6 +54,7 @@ impl TrustLevel {
         match s.to_lowercase().as_str() {
             "trusted" => Self::Trusted,
             "admin" => Self::Admin,
+            "revoked" => Self::Revoked,
             _ => Self::Paired,
         }
     }
@@ -42,6 +66,7 @@ impl std::fmt::Display for TrustLevel {
             Self::Paired => write!(f, "paired"),
             Self::Trusted => write!(f, "trusted"),
             Self::Admin => write!(f, "admin"),
+            Self::Revoked => write!(f, "revoked"),
         }
     }
 }
@@ -70,6 +95,32 @@ pub struct PairedDevice {
 
     /// When the device was last seen
     pub last_seen: DateTime<Utc>,
+
+    /// User the device belongs to, if assigned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+
+    /// When the device was revoked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<DateTime<Utc>>,
+
+    /// Admin who approved the pairing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+}
+
+impl PairedDevice {
+    /// Whether the device has been revoked
+    #[must_use]
+    pub const fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Whether an admin approved the pairing
+    #[must_use]
+    pub const fn is_approved(&self) -> bool {
+        self.approved_by.is_some()
+    }
 }
 
 /// Manages paired device storage and operations
@@ -136,9 +187,62 @@ impl DeviceManager {
             trust_level,
             paired_at: now,
             last_seen: now,
+            user_id: None,
+            revoked_at: None,
+            approved_by: None,
         })
     }
 
+    /// Record the admin who approved a device's pairing
+    ///
+    /// Returns false if the device doesn't exist or is revoked.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn approve(&self, device_id: &str, approver: &str) -> Result<bool> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let rows = conn
+            .execute(
+                "UPDATE devices SET approved_by = ?1 WHERE id = ?2 AND revoked_at IS NULL",
+                [approver, device_id],
+            )
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        if rows > 0 {
+            tracing::info!(device_id, approver, "device pairing approved");
+        }
+
+        Ok(rows > 0)
+    }
+
+    /// Assign a device to a user
+    ///
+    /// Returns false if the device doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn assign_user(&self, device_id: &str, user_id: &str) -> Result<bool> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let rows = conn
+            .execute(
+                "UPDATE devices SET user_id = ?1 WHERE id = ?2",
+                [user_id, device_id],
+            )
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(rows > 0)
+    }
+
     /// Get a device by ID
     ///
     /// # Errors
@@ -151,20 +255,9 @@ impl DeviceManager {
             .map_err(|e| Error::Database(e.to_string()))?;
 
         let result = conn.query_row(
-            "SELECT id, public_key, name, platform, trust_level, paired_at, last_seen
-             FROM devices WHERE id = ?1",
+            &format!("SELECT {DEVICE_COLUMNS} FROM devices WHERE id = ?1"),
             [device_id],
-            |row| {
-                Ok(PairedDevice {
-                    id: row.get(0)?,
-                    public_key: row.get(1)?,
-                    name: row.get(2)?,
-                    platform: row.get::<_, Option<String>>(3)?,
-                    trust_level: TrustLevel::from_str(&row.get::<_, String>(4)?),
-                    paired_at: parse_datetime(&row.get::<_, String>(5)?),
-                    last_seen: parse_datetime(&row.get::<_, String>(6)?),
-                })
-            },
+            row_to_device,
         );
 
         match result {
@@ -186,20 +279,9 @@ impl DeviceManager {
             .map_err(|e| Error::Database(e.to_string()))?;
 
         let result = conn.query_row(
-            "SELECT id, public_key, name, platform, trust_level, paired_at, last_seen
-             FROM devices WHERE public_key = ?1",
+            &format!("SELECT {DEVICE_COLUMNS} FROM devices WHERE public_key = ?1"),
             [public_key],
-            |row| {
-                Ok(PairedDevice {
-                    id: row.get(0)?,
-                    public_key: row.get(1)?,
-                    name: row.get(2)?,
-                    platform: row.get::<_, Option<String>>(3)?,
-                    trust_level: TrustLevel::from_str(&row.get::<_, String>(4)?),
-                    paired_at: parse_datetime(&row.get::<_, String>(5)?),
-                    last_seen: parse_datetime(&row.get::<_, String>(6)?),
-                })
-            },
+            row_to_device,
         );
 
         match result {
@@ -232,17 +314,28 @@ impl DeviceManager {
 
     /// Update device trust level
     ///
+    /// Revocation is one-way: use [`Self::revoke`] to revoke a device, and
+    /// revoked devices can't be given a new trust level.
+    ///
     /// # Errors
     ///
-    /// Returns error if database operation fails
+    /// Returns error if database operation fails, or `Error::Auth` if the
+    /// device is revoked or `trust_level` is [`TrustLevel::Revoked`]
     pub fn update_trust_level(&self, device_id: &str, trust_level: TrustLevel) -> Result<()> {
+        if trust_level == TrustLevel::Revoked {
+            return Err(Error::Auth("use revoke to revoke a device".to_string()));
+        }
+        if self.get(device_id)?.is_some_and(|d| d.is_revoked()) {
+            return Err(Error::Auth("device revoked".to_string()));
+        }
+
         let conn = self
             .pool
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
         conn.execute(
-            "UPDATE devices SET trust_level = ?1 WHERE id = ?2",
+            "UPDATE devices SET trust_level = ?1 WHERE id = ?2 AND revoked_at IS NULL",
             [&trust_level.to_string(), device_id],
         )
         .map_err(|e| Error::Database(e.to_string()))?;
@@ -271,7 +364,7 @@ impl DeviceManager {
         Ok(())
     }
 
-    /// Remove a device
+    /// Remove a device and its sessions
     ///
     /// # Errors
     ///
@@ -282,6 +375,11 @@ impl DeviceManager {
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
+        conn.execute(
+            "DELETE FROM device_sessions WHERE device_id = ?1",
+            [device_id],
+        )
+        .map_err(|e| Error::Database(e.to_string()))?;
         let rows = conn
             .execute("DELETE FROM devices WHERE id = ?1", [device_id])
             .map_err(|e| Error::Database(e.to_string()))?;
@@ -293,6 +391,180 @@ impl DeviceManager {
         Ok(rows > 0)
     }
 
+    /// Revoke a device and end all of its sessions
+    ///
+    /// The record is kept and marked revoked, so its key can't pair again
+    /// and its signatures fail [`Self::verify_signature`]. Returns false if the
+    /// device doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn revoke(&self, device_id: &str) -> Result<bool> {
+        let mut conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let rows = tx
+            .execute(
+                "UPDATE devices SET trust_level = ?1, revoked_at = COALESCE(revoked_at, ?2)
+                 WHERE id = ?3",
+                [
+                    &TrustLevel::Revoked.to_string(),
+                    &Utc::now().to_rfc3339(),
+                    device_id,
+                ],
+            )
+            .map_err(|e| Error::Database(e.to_string()))?;
+        let sessions = tx
+            .execute(
+                "DELETE FROM device_sessions WHERE device_id = ?1",
+                [device_id],
+            )
+            .map_err(|e| Error::Database(e.to_string()))?;
+        tx.commit().map_err(|e| Error::Database(e.to_string()))?;
+
+        if rows > 0 {
+            tracing::info!(device_id, sessions, "device revoked");
+        }
+
+        Ok(rows > 0)
+    }
+
+    /// Verify a signature made by a paired device
+    ///
+    /// Updates the device's last-seen time when the signature is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Auth` if the device is unknown or revoked, or if the
+    /// signature is malformed
+    pub fn verify_signature(
+        &self,
+        device_id: &str,
+        payload: &[u8],
+        signature: &str,
+    ) -> Result<bool> {
+        let device = self
+            .get(device_id)?
+            .ok_or_else(|| Error::Auth("unknown device".to_string()))?;
+        if device.is_revoked() {
+            tracing::warn!(device_id, "signature from revoked device rejected");
+            return Err(Error::Auth("device revoked".to_string()));
+        }
+
+        let valid = crate::security::verify_signature(&device.public_key, payload, signature)?;
+        if valid {
+            self.update_last_seen(device_id)?;
+        }
+        Ok(valid)
+    }
+
+    /// Start a session for a device that has proven its identity
+    ///
+    /// Returns the session token and its expiry. Only a hash of the token is
+    /// stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Auth` if the device is unknown or revoked, or error if
+    /// database operation fails
+    pub fn create_session(&self, device_id: &str) -> Result<(String, DateTime<Utc>)> {
+        let device = self
+            .get(device_id)?
+            .ok_or_else(|| Error::Auth("unknown device".to_string()))?;
+        if device.is_revoked() {
+            return Err(Error::Auth("device revoked".to_string()));
+        }
+
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let token = {
+            let mut rng = rand::thread_rng();
+            let bytes: Vec<u8> = (0..SESSION_TOKEN_BYTES).map(|_| rng.r#gen()).collect();
+            hex::encode(bytes)
+        };
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::hours(DEVICE_SESSION_TTL_HOURS);
+
+        conn.execute(
+            "INSERT INTO device_sessions (token_hash, device_id, created_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            [
+                &hash_token(&token),
+                device_id,
+                &now.to_rfc3339(),
+                &expires_at.to_rfc3339(),
+            ],
+        )
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok((token, expires_at))
+    }
+
+    /// Look up the device behind a session token
+    ///
+    /// Returns None for unknown or expired tokens and for revoked devices.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn validate_session(&self, token: &str) -> Result<Option<PairedDevice>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let device_id: Option<String> = conn
+            .query_row(
+                "SELECT device_id FROM device_sessions WHERE token_hash = ?1 AND expires_at > ?2",
+                [&hash_token(token), &Utc::now().to_rfc3339()],
+                |row| row.get(0),
+            )
+            .ok();
+        drop(conn);
+
+        let Some(device_id) = device_id else {
+            return Ok(None);
+        };
+        Ok(self.get(&device_id)?.filter(|d| !d.is_revoked()))
+    }
+
+    /// List devices belonging to a user, most recently seen first
+    ///
+    /// Revoked devices are included so they can be shown as such.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn list_devices(&self, user_id: &str) -> Result<Vec<PairedDevice>> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {DEVICE_COLUMNS} FROM devices WHERE user_id = ?1 ORDER BY last_seen DESC"
+            ))
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let devices = stmt
+            .query_map([user_id], row_to_device)
+            .map_err(|e| Error::Database(e.to_string()))?
+            .filter_map(std::result::Result::ok)
+            .collect();
+
+        Ok(devices)
+    }
+
     /// List all paired devices
     ///
     /// # Errors
@@ -305,24 +577,13 @@ impl DeviceManager {
             .map_err(|e| Error::Database(e.to_string()))?;
 
         let mut stmt = conn
-            .prepare(
-                "SELECT id, public_key, name, platform, trust_level, paired_at, last_seen
-                 FROM devices ORDER BY last_seen DESC",
-            )
+            .prepare(&format!(
+                "SELECT {DEVICE_COLUMNS} FROM devices ORDER BY last_seen DESC"
+            ))
             .map_err(|e| Error::Database(e.to_string()))?;
 
         let devices = stmt
-            .query_map([], |row| {
-                Ok(PairedDevice {
-                    id: row.get(0)?,
-                    public_key: row.get(1)?,
-                    name: row.get(2)?,
-                    platform: row.get::<_, Option<String>>(3)?,
-                    trust_level: TrustLevel::from_str(&row.get::<_, String>(4)?),
-                    paired_at: parse_datetime(&row.get::<_, String>(5)?),
-                    last_seen: parse_datetime(&row.get::<_, String>(6)?),
-                })
-            })
+            .query_map([], row_to_device)
             .map_err(|e| Error::Database(e.to_string()))?
             .filter_map(std::result::Result::ok)
             .collect();
@@ -369,6 +630,32 @@ impl DeviceManager {
     }
 }
 
+fn row_to_device(row: &rusqlite::Row<'_>) -> rusqlite::Result<PairedDevice> {
+    Ok(PairedDevice {
+        id: row.get(0)?,
+        public_key: row.get(1)?,
+        name: row.get(2)?,
+        platform: row.get::<_, Option<String>>(3)?,
+        trust_level: TrustLevel::from_str(&row.get::<_, String>(4)?),
+        paired_at: parse_datetime(&row.get::<_, String>(5)?),
+        last_seen: parse_datetime(&row.get::<_, String>(6)?),
+        user_id: row.get(7)?,
+        revoked_at: row.get::<_, Option<String>>(8)?.map(|s| parse_datetime(&s)),
+        approved_by: row.get(9)?,
+    })
+}
+
+/// Whether `token` has the shape of a device session token
+#[must_use]
+pub fn is_session_token(token: &str) -> bool {
+    token.len() == SESSION_TOKEN_BYTES * 2 && token.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Hex SHA-256 of a session token, as stored
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
 fn parse_datetime(s: &str) -> DateTime<Utc> {
     DateTime::parse_from_rfc3339(s).map_or_else(|_| Utc::now(), |dt| dt.with_timezone(&Utc))
 }
@@ -510,11 +797,99 @@ mod tests {
         assert_eq!(manager.count().unwrap(), 1);
     }
 
+    #[test]
+    fn test_revoke_device() {
+        let identity = crate::security::DeviceIdentity::generate("Lost Phone");
+        let manager = setup();
+        manager
+            .register(
+                &identity.device_id,
+                &identity.public_key,
+                "Lost Phone",
+                None,
+                TrustLevel::Trusted,
+            )
+            .unwrap();
+
+        let payload = b"nonce";
+        let signature = identity.sign(payload).unwrap();
+        assert!(
+            manager
+                .verify_signature(&identity.device_id, payload, &signature)
+                .unwrap()
+        );
+        let (token, _) = manager.create_session(&identity.device_id).unwrap();
+        assert!(manager.validate_session(&token).unwrap().is_some());
+
+        assert!(manager.revoke(&identity.device_id).unwrap());
+
+        let device = manager.get(&identity.device_id).unwrap().unwrap();
+        assert_eq!(device.trust_level, TrustLevel::Revoked);
+        assert!(device.is_revoked());
+        assert!(manager.validate_session(&token).unwrap().is_none());
+        assert!(
+            manager
+                .verify_signature(&identity.device_id, payload, &signature)
+                .is_err()
+        );
+        assert!(manager.create_session(&identity.device_id).is_err());
+        assert!(
+            manager
+                .update_trust_level(&identity.device_id, TrustLevel::Admin)
+                .is_err()
+        );
+
+        assert!(!manager.revoke("nonexistent").unwrap());
+    }
+
+    #[test]
+    fn test_approve_device() {
+        let manager = setup();
+        manager
+            .register("d1", "pk1", "Laptop", None, TrustLevel::Paired)
+            .unwrap();
+        assert!(!manager.get("d1").unwrap().unwrap().is_approved());
+
+        assert!(manager.approve("d1", "alice").unwrap());
+        let device = manager.get("d1").unwrap().unwrap();
+        assert_eq!(device.approved_by.as_deref(), Some("alice"));
+
+        manager.revoke("d1").unwrap();
+        assert!(!manager.approve("d1", "alice").unwrap());
+        assert!(!manager.approve("nonexistent", "alice").unwrap());
+    }
+
+    #[test]
+    fn test_list_devices_by_user() {
+        let manager = setup();
+
+        manager
+            .register("d1", "pk1", "Laptop", None, TrustLevel::Paired)
+            .unwrap();
+        manager
+            .register("d2", "pk2", "Phone", None, TrustLevel::Trusted)
+            .unwrap();
+        manager
+            .register("d3", "pk3", "Other", None, TrustLevel::Paired)
+            .unwrap();
+        assert!(manager.assign_user("d1", "alice").unwrap());
+        assert!(manager.assign_user("d2", "alice").unwrap());
+        assert!(manager.assign_user("d3", "bob").unwrap());
+        manager.revoke("d2").unwrap();
+
+        let devices = manager.list_devices("alice").unwrap();
+        assert_eq!(devices.len(), 2);
+        let phone = devices.iter().find(|d| d.id == "d2").unwrap();
+        assert_eq!(phone.trust_level, TrustLevel::Revoked);
+        assert!(manager.list_devices("carol").unwrap().is_empty());
+    }
+
     #[test]
     fn test_trust_level_parsing() {
         assert_eq!(TrustLevel::from_str("paired"), TrustLevel::Paired);
         assert_eq!(TrustLevel::from_str("trusted"), TrustLevel::Trusted);
         assert_eq!(TrustLevel::from_str("admin"), TrustLevel::Admin);
+        assert_eq!(TrustLevel::from_str("revoked"), TrustLevel::Revoked);
         assert_eq!(TrustLevel::from_str("unknown"), TrustLevel::Paired);
     }
 }
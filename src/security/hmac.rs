@@ -0,0 +1,83 @@
+//! HMAC-SHA256 request signing shared by webhook senders and receivers
+//!
+//! Signatures travel as hex, optionally prefixed with `sha256=`.
+
+use sha2::{Digest, Sha256};
+
+/// HMAC-SHA256 (RFC 2104)
+#[must_use]
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(data);
+    let inner = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner);
+    outer.finalize().into()
+}
+
+/// Signature header value for `body`: `sha256=<hex hmac>`
+#[must_use]
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    format!(
+        "sha256={}",
+        hex::encode(hmac_sha256(secret.as_bytes(), body))
+    )
+}
+
+/// Check a hex signature (with or without `sha256=`) against `body`
+#[must_use]
+pub fn verify(secret: &str, signature: &str, body: &[u8]) -> bool {
+    let hex_sig = signature.strip_prefix("sha256=").unwrap_or(signature);
+    hex::decode(hex_sig)
+        .is_ok_and(|sig| constant_time_eq(&sig, &hmac_sha256(secret.as_bytes(), body)))
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch
+#[must_use]
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac_matches_rfc4231_vector() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            hex::encode(mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn sign_and_verify_round_trip() {
+        let body = br#"{"content":"hi"}"#;
+        let sig = sign("s3cret", body);
+        assert!(sig.starts_with("sha256="));
+        assert!(verify("s3cret", &sig, body));
+        assert!(verify("s3cret", sig.trim_start_matches("sha256="), body));
+        assert!(!verify("other", &sig, body));
+        assert!(!verify("s3cret", "sha256=zz", body));
+    }
+
+    #[test]
+    fn constant_time_eq_compares_whole_input() {
+        assert!(constant_time_eq(b"hello", b"hello"));
+        assert!(!constant_time_eq(b"hello", b"world"));
+        assert!(!constant_time_eq(b"hello", b"hell"));
+    }
+}
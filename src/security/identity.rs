@@ -157,6 +157,28 @@ impl DeviceIdentity {
         }
     }
 
+    /// Derive a 256-bit symmetric key from the secret key for `context`
+    ///
+    /// Different contexts yield unrelated keys, so the signing key is never
+    /// used directly for encryption.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if identity has no secret key
+    pub fn derive_key(&self, context: &str) -> Result<[u8; 32]> {
+        let secret_key = self
+            .secret_key
+            .as_ref()
+            .ok_or_else(|| Error::Auth("identity has no secret key".to_string()))?;
+        let key_bytes = base64_decode(secret_key)?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(context.as_bytes());
+        hasher.update([0]);
+        hasher.update(&key_bytes);
+        Ok(hasher.finalize().into())
+    }
+
     /// Check if this identity has a secret key
     #[must_use]
     pub const fn has_secret_key(&self) -> bool {
@@ -235,6 +257,16 @@ mod tests {
         assert!(!identity.verify(b"tampered", &signature).unwrap());
     }
 
+    #[test]
+    fn test_derive_key_is_per_context() {
+        let identity = DeviceIdentity::generate("test");
+
+        let a = identity.derive_key("sync").unwrap();
+        assert_eq!(a, identity.derive_key("sync").unwrap());
+        assert_ne!(a, identity.derive_key("other").unwrap());
+        assert!(identity.public_only().derive_key("sync").is_err());
+    }
+
     #[test]
     fn test_public_only() {
         let identity = DeviceIdentity::generate("test");
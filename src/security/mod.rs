@@ -2,10 +2,11 @@
 
 pub mod auth;
 pub mod device;
+pub mod hmac;
 pub mod identity;
 pub mod pairing;
 
 pub use auth::{AuthChallenge, AuthConfig, AuthMode, PairingRequest};
 pub use device::{DeviceManager, PairedDevice, TrustLevel};
 pub use identity::{DeviceIdentity, verify_signature};
-pub use pairing::{DmPolicy, PairedUser, PairingManager};
+pub use pairing::{DmPolicy, PairedUser, PairingError, PairingLockout, PairingManager};
@@ -5,6 +5,15 @@
 //! - Pairing: New senders must enter a pairing code to be approved
 //! - Allowlist: Only pre-approved sender IDs can message
 //! - Disabled: Ignore all DMs entirely
+//!
+//! Pairing codes are single-use and expire after ten minutes by default.
+//! Redeemed codes are kept until they would have expired so a replay can be
+//! reported as reuse, then swept by [`PairingManager::sweep_expired`].
+//!
+//! Failed redemptions are counted per sender. After
+//! [`PairingLockout::max_attempts`] failures the sender is blocked, for twice
+//! as long on each further block, and a `beacon.security.pairing_blocked`
+//! event is published.
 
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
@@ -18,6 +27,86 @@ const PAIRING_CODE_LENGTH: usize = 6;
 /// Pairing code valid duration in minutes
 const PAIRING_CODE_EXPIRY_MINUTES: i64 = 10;
 
+/// Failed pairing attempts allowed before a block
+const DEFAULT_MAX_PAIRING_ATTEMPTS: u32 = 5;
+
+/// Length of the first pairing block in minutes
+const DEFAULT_PAIRING_LOCKOUT_MINUTES: i64 = 15;
+
+/// Longest pairing block in minutes
+const DEFAULT_MAX_PAIRING_LOCKOUT_MINUTES: i64 = 24 * 60;
+
+/// Default failed-attempt limits
+const DEFAULT_LOCKOUT: PairingLockout = PairingLockout {
+    max_attempts: DEFAULT_MAX_PAIRING_ATTEMPTS,
+    base: chrono::Duration::minutes(DEFAULT_PAIRING_LOCKOUT_MINUTES),
+    max: chrono::Duration::minutes(DEFAULT_MAX_PAIRING_LOCKOUT_MINUTES),
+};
+
+/// Limits on failed pairing attempts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairingLockout {
+    /// Failed attempts allowed before the sender is blocked (0 disables)
+    pub max_attempts: u32,
+    /// Length of the first block; doubles with each consecutive block
+    pub base: chrono::Duration,
+    /// Longest a single block can last
+    pub max: chrono::Duration,
+}
+
+impl Default for PairingLockout {
+    fn default() -> Self {
+        DEFAULT_LOCKOUT
+    }
+}
+
+impl PairingLockout {
+    /// Length of the `n`th consecutive block (1-based)
+    #[must_use]
+    pub fn block_duration(&self, n: u32) -> chrono::Duration {
+        2i32.checked_pow(n.saturating_sub(1))
+            .and_then(|factor| self.base.checked_mul(factor))
+            .map_or(self.max, |d| d.min(self.max))
+    }
+}
+
+/// Why a pairing code was rejected
+#[derive(Debug, thiserror::Error)]
+pub enum PairingError {
+    /// No code like this was issued to the sender
+    #[error("pairing code not recognized")]
+    Invalid,
+
+    /// The code was issued but its time ran out
+    #[error("pairing code expired")]
+    Expired,
+
+    /// The code was already redeemed
+    #[error("pairing code already used")]
+    AlreadyUsed,
+
+    /// Too many failed attempts; the sender is blocked until `until`
+    #[error("too many failed pairing attempts, blocked until {until}")]
+    Locked { until: DateTime<Utc> },
+
+    /// The pairing store failed
+    #[error(transparent)]
+    Storage(#[from] Error),
+}
+
+impl PairingError {
+    /// Short explanation suitable for replying to the sender
+    #[must_use]
+    pub const fn notice(&self) -> &'static str {
+        match self {
+            Self::Expired => "That pairing code has expired.",
+            Self::AlreadyUsed => "That pairing code has already been used.",
+            Self::Locked { .. } => "Too many failed pairing attempts. Please try again later.",
+            Self::Invalid | Self::Storage(_) => "That pairing code is not valid.",
+        }
+    }
+}
+
 /// DM access policy
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum DmPolicy {
@@ -76,13 +165,43 @@ pub struct PairedUser {
 pub struct PairingManager {
     policy: DmPolicy,
     pool: DbPool,
+    code_ttl: chrono::Duration,
+    lockout: PairingLockout,
 }
 
 impl PairingManager {
     /// Create a new pairing manager
     #[must_use]
     pub const fn new(policy: DmPolicy, pool: DbPool) -> Self {
-        Self { policy, pool }
+        Self {
+            policy,
+            pool,
+            code_ttl: chrono::Duration::minutes(PAIRING_CODE_EXPIRY_MINUTES),
+            lockout: DEFAULT_LOCKOUT,
+        }
+    }
+
+    /// Set how long issued pairing codes stay valid
+    #[must_use]
+    pub const fn with_code_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.code_ttl = ttl;
+        self
+    }
+
+    /// Message asking a new sender to enter `code`, with its configured expiry
+    #[must_use]
+    pub fn code_prompt(&self, code: &str) -> String {
+        format!(
+            "Please enter the pairing code to start messaging.\n\nYour code: {code}\n\n(This code expires in {})",
+            describe_ttl(self.code_ttl)
+        )
+    }
+
+    /// Set the failed-attempt limits
+    #[must_use]
+    pub const fn with_lockout(mut self, lockout: PairingLockout) -> Self {
+        self.lockout = lockout;
+        self
     }
 
     /// Get the current policy
@@ -135,105 +254,272 @@ impl PairingManager {
     ///
     /// Returns error if database operation fails
     pub fn generate_pairing_code(&self, sender_id: &str, channel: &str) -> Result<Option<String>> {
-        // Check if already paired
         if self.is_paired(sender_id, channel)? {
             return Ok(None);
         }
 
+        self.issue_code(sender_id, channel)
+            .map(|(code, _)| Some(code))
+    }
+
+    /// Issue a single-use pairing code for a sender
+    ///
+    /// Any earlier unredeemed code for the sender is revoked, so only the
+    /// latest code works.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn issue_code(&self, sender_id: &str, channel: &str) -> Result<(String, DateTime<Utc>)> {
         let conn = self
             .pool
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
-        // Generate a 6-character alphanumeric code
         let code = generate_code(PAIRING_CODE_LENGTH);
         let now = Utc::now();
-        let expires_at = now + chrono::Duration::minutes(PAIRING_CODE_EXPIRY_MINUTES);
+        let expires_at = now + self.code_ttl;
 
-        // Check if pending code already exists
-        let existing_id: Option<String> = conn
+        conn.execute(
+            "DELETE FROM pairing_codes
+             WHERE sender_id = ?1 AND channel = ?2 AND consumed_at IS NULL",
+            [sender_id, channel],
+        )
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        conn.execute(
+            "INSERT INTO pairing_codes (id, code, sender_id, channel, issued_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            [
+                &Uuid::new_v4().to_string(),
+                &code,
+                sender_id,
+                channel,
+                &now.to_rfc3339(),
+                &expires_at.to_rfc3339(),
+            ],
+        )
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tracing::debug!(sender_id, channel, %expires_at, "issued pairing code");
+        Ok((code, expires_at))
+    }
+
+    /// Redeem a pairing code and approve the sender
+    ///
+    /// The code is consumed on success and can't be used again. Rejected
+    /// codes count toward the sender's lockout.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PairingError::Locked` while the sender is blocked,
+    /// `Invalid`, `Expired`, or `AlreadyUsed` when the code is rejected, or
+    /// `Storage` if a database operation fails
+    pub fn redeem_code(
+        &self,
+        sender_id: &str,
+        channel: &str,
+        code: &str,
+    ) -> std::result::Result<(), PairingError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        let locked_until: Option<String> = conn
             .query_row(
-                "SELECT id FROM paired_users WHERE sender_id = ?1 AND channel = ?2",
+                "SELECT locked_until FROM pairing_attempts WHERE sender_id = ?1 AND channel = ?2",
                 [sender_id, channel],
                 |row| row.get(0),
             )
+            .ok()
+            .flatten();
+        if let Some(until) = locked_until.map(|s| parse_datetime(&s))
+            && Utc::now() < until
+        {
+            tracing::debug!(sender_id, channel, %until, "pairing attempt while blocked");
+            return Err(PairingError::Locked { until });
+        }
+
+        match Self::consume_code(&conn, sender_id, channel, code) {
+            Ok(()) => {
+                conn.execute(
+                    "DELETE FROM pairing_attempts WHERE sender_id = ?1 AND channel = ?2",
+                    [sender_id, channel],
+                )
+                .map_err(|e| Error::Database(e.to_string()))?;
+                Ok(())
+            }
+            Err(PairingError::Storage(e)) => Err(PairingError::Storage(e)),
+            Err(rejection) => {
+                self.record_failure(&conn, sender_id, channel)?;
+                Err(rejection)
+            }
+        }
+    }
+
+    /// Check a code and, if valid, consume it and pair the sender
+    fn consume_code(
+        conn: &rusqlite::Connection,
+        sender_id: &str,
+        channel: &str,
+        code: &str,
+    ) -> std::result::Result<(), PairingError> {
+        let record: Option<(String, String, Option<String>)> = conn
+            .query_row(
+                "SELECT id, expires_at, consumed_at FROM pairing_codes
+                 WHERE sender_id = ?1 AND channel = ?2 AND code = ?3
+                 ORDER BY issued_at DESC LIMIT 1",
+                [sender_id, channel, code],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
             .ok();
 
-        if let Some(id) = existing_id {
-            // Update existing pending record
-            conn.execute(
-                "UPDATE paired_users SET pairing_code = ?1, code_expires_at = ?2 WHERE id = ?3",
-                [&code, &expires_at.to_rfc3339(), &id],
+        let Some((id, expires_at, consumed_at)) = record else {
+            tracing::debug!(sender_id, channel, "invalid pairing code");
+            return Err(PairingError::Invalid);
+        };
+        if consumed_at.is_some() {
+            tracing::warn!(sender_id, channel, "pairing code reused");
+            return Err(PairingError::AlreadyUsed);
+        }
+        let now = Utc::now();
+        if now >= parse_datetime(&expires_at) {
+            tracing::debug!(sender_id, channel, "pairing code expired");
+            return Err(PairingError::Expired);
+        }
+
+        // Guard on consumed_at so a concurrent redemption can't also succeed
+        let consumed = conn
+            .execute(
+                "UPDATE pairing_codes SET consumed_at = ?1 WHERE id = ?2 AND consumed_at IS NULL",
+                [&now.to_rfc3339(), &id],
             )
             .map_err(|e| Error::Database(e.to_string()))?;
-        } else {
-            // Create new pending record
-            let id = Uuid::new_v4().to_string();
-            conn.execute(
-                "INSERT INTO paired_users (id, sender_id, channel, paired_at, pairing_code, code_expires_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                [
-                    &id,
-                    sender_id,
-                    channel,
-                    &now.to_rfc3339(),
-                    &code,
-                    &expires_at.to_rfc3339(),
-                ],
+        if consumed == 0 {
+            return Err(PairingError::AlreadyUsed);
+        }
+
+        conn.execute(
+            "INSERT INTO paired_users (id, sender_id, channel, paired_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(sender_id, channel) DO UPDATE
+             SET pairing_code = NULL, code_expires_at = NULL, paired_at = ?4",
+            [
+                &Uuid::new_v4().to_string(),
+                sender_id,
+                channel,
+                &now.to_rfc3339(),
+            ],
+        )
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        tracing::info!(sender_id, channel, "sender paired successfully");
+        Ok(())
+    }
+
+    /// Count a failed attempt, blocking the sender once the limit is hit
+    fn record_failure(
+        &self,
+        conn: &rusqlite::Connection,
+        sender_id: &str,
+        channel: &str,
+    ) -> Result<()> {
+        if self.lockout.max_attempts == 0 {
+            return Ok(());
+        }
+
+        let (mut failures, mut blocks): (u32, u32) = conn
+            .query_row(
+                "SELECT failures, blocks FROM pairing_attempts WHERE sender_id = ?1 AND channel = ?2",
+                [sender_id, channel],
+                |row| Ok((row.get(0)?, row.get(1)?)),
             )
-            .map_err(|e| Error::Database(e.to_string()))?;
+            .unwrap_or((0, 0));
+
+        let now = Utc::now();
+        failures += 1;
+        let mut locked_until = None;
+        if failures >= self.lockout.max_attempts {
+            blocks += 1;
+            let until = now + self.lockout.block_duration(blocks);
+            tracing::warn!(sender_id, channel, failures, %until, "pairing blocked after failed attempts");
+            crate::events::publish(crate::events::build_pairing_blocked_event(
+                sender_id, channel, failures, &until,
+            ));
+            locked_until = Some(until.to_rfc3339());
+            failures = 0;
         }
 
-        tracing::debug!(sender_id, channel, "generated pairing code");
-        Ok(Some(code))
+        conn.execute(
+            "INSERT INTO pairing_attempts (sender_id, channel, failures, blocks, locked_until, last_failure_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(sender_id, channel) DO UPDATE SET
+                 failures = excluded.failures,
+                 blocks = excluded.blocks,
+                 locked_until = excluded.locked_until,
+                 last_failure_at = excluded.last_failure_at",
+            rusqlite::params![
+                sender_id,
+                channel,
+                failures,
+                blocks,
+                locked_until,
+                now.to_rfc3339()
+            ],
+        )
+        .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(())
     }
 
     /// Verify a pairing code and approve the sender
     ///
-    /// Returns true if code is valid and sender is now paired
+    /// Returns true if code is valid and sender is now paired. Use
+    /// [`Self::redeem_code`] to tell invalid, expired, and reused codes apart.
     ///
     /// # Errors
     ///
     /// Returns error if database operation fails
     pub fn verify_pairing(&self, sender_id: &str, channel: &str, code: &str) -> Result<bool> {
+        match self.redeem_code(sender_id, channel, code) {
+            Ok(()) => Ok(true),
+            Err(PairingError::Storage(e)) => Err(e),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Delete pairing codes past their expiry, redeemed or not
+    ///
+    /// Failure counts of senders that are not blocked and haven't failed for
+    /// the longest block length are cleared too, so old mistakes stop
+    /// escalating future blocks. Returns the number of codes removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn sweep_expired(&self) -> Result<usize> {
         let conn = self
             .pool
             .get()
             .map_err(|e| Error::Database(e.to_string()))?;
 
-        // Find pending pairing with matching code
-        let record: Option<(String, String)> = conn
-            .query_row(
-                "SELECT id, code_expires_at FROM paired_users
-                 WHERE sender_id = ?1 AND channel = ?2 AND pairing_code = ?3",
-                [sender_id, channel, code],
-                |row| Ok((row.get(0)?, row.get(1)?)),
+        let now = Utc::now();
+        let swept = conn
+            .execute(
+                "DELETE FROM pairing_codes WHERE expires_at <= ?1",
+                [now.to_rfc3339()],
             )
-            .ok();
-
-        let Some((id, expires_at_str)) = record else {
-            tracing::debug!(sender_id, channel, "invalid pairing code");
-            return Ok(false);
-        };
-
-        // Check expiry
-        if let Ok(expires_at) = DateTime::parse_from_rfc3339(&expires_at_str)
-            && Utc::now() > expires_at.with_timezone(&Utc)
-        {
-            tracing::debug!(sender_id, channel, "pairing code expired");
-            return Ok(false);
-        }
+            .map_err(|e| Error::Database(e.to_string()))?;
 
-        // Clear the code to mark as paired
-        let now = Utc::now().to_rfc3339();
         conn.execute(
-            "UPDATE paired_users SET pairing_code = NULL, code_expires_at = NULL, paired_at = ?1 WHERE id = ?2",
-            [&now, &id],
+            "DELETE FROM pairing_attempts
+             WHERE (locked_until IS NULL OR locked_until <= ?1) AND last_failure_at <= ?2",
+            [now.to_rfc3339(), (now - self.lockout.max).to_rfc3339()],
         )
         .map_err(|e| Error::Database(e.to_string()))?;
 
-        tracing::info!(sender_id, channel, "sender paired successfully");
-        Ok(true)
+        Ok(swept)
     }
 
     /// Add a sender directly to the allowlist (bypassing pairing flow)
@@ -386,6 +672,19 @@ fn generate_code(length: usize) -> String {
     code
 }
 
+/// Human-readable length of a code TTL, in the largest whole unit
+fn describe_ttl(ttl: chrono::Duration) -> String {
+    let (amount, unit) = if ttl.num_hours() > 0 && ttl.num_minutes() % 60 == 0 {
+        (ttl.num_hours(), "hour")
+    } else if ttl.num_minutes() > 0 && ttl.num_seconds() % 60 == 0 {
+        (ttl.num_minutes(), "minute")
+    } else {
+        (ttl.num_seconds(), "second")
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{amount} {unit}{plural}")
+}
+
 fn parse_datetime(s: &str) -> DateTime<Utc> {
     DateTime::parse_from_rfc3339(s).map_or_else(|_| Utc::now(), |dt| dt.with_timezone(&Utc))
 }
@@ -504,6 +803,126 @@ mod tests {
         assert_eq!(all_users.len(), 3);
     }
 
+    #[test]
+    fn test_rejections_are_distinct() {
+        let manager = setup(DmPolicy::Pairing);
+
+        let (code, expires_at) = manager.issue_code("newuser", "telegram").unwrap();
+        assert!(expires_at > Utc::now() + chrono::Duration::minutes(9));
+
+        assert!(matches!(
+            manager.redeem_code("newuser", "telegram", "WRONG1"),
+            Err(PairingError::Invalid)
+        ));
+        // Codes are bound to the sender they were issued to
+        assert!(matches!(
+            manager.redeem_code("intruder", "telegram", &code),
+            Err(PairingError::Invalid)
+        ));
+
+        manager.redeem_code("newuser", "telegram", &code).unwrap();
+        assert!(manager.is_paired("newuser", "telegram").unwrap());
+        assert!(matches!(
+            manager.redeem_code("newuser", "telegram", &code),
+            Err(PairingError::AlreadyUsed)
+        ));
+    }
+
+    #[test]
+    fn test_expired_codes_are_rejected_and_swept() {
+        let manager = setup(DmPolicy::Pairing).with_code_ttl(chrono::Duration::zero());
+
+        let (code, _) = manager.issue_code("slowuser", "signal").unwrap();
+        assert!(matches!(
+            manager.redeem_code("slowuser", "signal", &code),
+            Err(PairingError::Expired)
+        ));
+        assert!(!manager.is_paired("slowuser", "signal").unwrap());
+
+        assert_eq!(manager.sweep_expired().unwrap(), 1);
+        assert!(matches!(
+            manager.redeem_code("slowuser", "signal", &code),
+            Err(PairingError::Invalid)
+        ));
+    }
+
+    #[test]
+    fn test_code_prompt_reports_configured_ttl() {
+        let manager = setup(DmPolicy::Pairing);
+        assert!(
+            manager
+                .code_prompt("ABC123")
+                .ends_with("Your code: ABC123\n\n(This code expires in 10 minutes)")
+        );
+
+        let manager = manager.with_code_ttl(chrono::Duration::hours(1));
+        assert!(
+            manager
+                .code_prompt("ABC123")
+                .ends_with("expires in 1 hour)")
+        );
+
+        let manager = manager.with_code_ttl(chrono::Duration::seconds(90));
+        assert!(
+            manager
+                .code_prompt("ABC123")
+                .ends_with("expires in 90 seconds)")
+        );
+    }
+
+    #[test]
+    fn test_reissue_revokes_previous_code() {
+        let manager = setup(DmPolicy::Pairing);
+
+        let (first, _) = manager.issue_code("user", "discord").unwrap();
+        let (second, _) = manager.issue_code("user", "discord").unwrap();
+
+        assert_ne!(first, second);
+        assert!(matches!(
+            manager.redeem_code("user", "discord", &first),
+            Err(PairingError::Invalid)
+        ));
+        manager.redeem_code("user", "discord", &second).unwrap();
+    }
+
+    #[test]
+    fn test_repeated_failures_block_sender() {
+        let manager = setup(DmPolicy::Pairing).with_lockout(PairingLockout {
+            max_attempts: 3,
+            ..PairingLockout::default()
+        });
+        let (code, _) = manager.issue_code("attacker", "discord").unwrap();
+
+        for _ in 0..3 {
+            assert!(matches!(
+                manager.redeem_code("attacker", "discord", "WRONG1"),
+                Err(PairingError::Invalid)
+            ));
+        }
+
+        // Even the right code is refused while blocked
+        let Err(PairingError::Locked { until }) = manager.redeem_code("attacker", "discord", &code)
+        else {
+            panic!("expected sender to be blocked");
+        };
+        assert!(until > Utc::now() + chrono::Duration::minutes(14));
+        assert!(!manager.is_paired("attacker", "discord").unwrap());
+
+        // Other senders are unaffected
+        let (other, _) = manager.issue_code("friend", "discord").unwrap();
+        manager.redeem_code("friend", "discord", &other).unwrap();
+    }
+
+    #[test]
+    fn test_block_duration_doubles_up_to_max() {
+        let lockout = PairingLockout::default();
+        assert_eq!(lockout.block_duration(1), chrono::Duration::minutes(15));
+        assert_eq!(lockout.block_duration(2), chrono::Duration::minutes(30));
+        assert_eq!(lockout.block_duration(3), chrono::Duration::minutes(60));
+        assert_eq!(lockout.block_duration(10), chrono::Duration::hours(24));
+        assert_eq!(lockout.block_duration(64), chrono::Duration::hours(24));
+    }
+
     #[test]
     fn test_generate_code_format() {
         let code = generate_code(6);
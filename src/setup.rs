@@ -187,6 +187,7 @@ pub fn run_setup() -> anyhow::Result<()> {
                     .unwrap_or_else(|| "alloy".to_string()),
             ),
             tts_speed: existing.voice.tts_speed.or(Some(1.0)),
+            ..VoiceFileConfig::default()
         }
     } else {
         VoiceFileConfig {
@@ -207,6 +208,8 @@ pub fn run_setup() -> anyhow::Result<()> {
     // 8. Build and write config
     let config_file = BeaconConfigFile {
         persona: Some(persona),
+        default_persona: existing.default_persona,
+        persona_strict: existing.persona_strict,
         llm: LlmFileConfig {
             model: Some(model),
             provider: Some(provider_name),
@@ -218,6 +221,7 @@ pub fn run_setup() -> anyhow::Result<()> {
             port: existing.server.port,
             synapse_url: existing.server.synapse_url,
             cloud_mode: existing.server.cloud_mode,
+            ..Default::default()
         },
         skills: existing.skills,
         mcp_servers,
@@ -304,6 +308,7 @@ fn serialize_config(config: &BeaconConfigFile) -> String {
         || ak.openrouter.is_some()
         || ak.elevenlabs.is_some()
         || ak.deepgram.is_some()
+        || ak.gemini.is_some()
         || ak.discord.is_some()
         || ak.slack.is_some()
         || ak.telegram.is_some()
@@ -315,6 +320,7 @@ fn serialize_config(config: &BeaconConfigFile) -> String {
             ("openrouter", &ak.openrouter),
             ("elevenlabs", &ak.elevenlabs),
             ("deepgram", &ak.deepgram),
+            ("gemini", &ak.gemini),
             ("discord", &ak.discord),
             ("slack", &ak.slack),
             ("telegram", &ak.telegram),
@@ -456,6 +462,7 @@ fn setup_channels(
                 }
                 channels_config.discord = Some(ChannelToggle {
                     enabled: Some(true),
+                    ..Default::default()
                 });
             }
             "Telegram" => {
@@ -468,6 +475,7 @@ fn setup_channels(
                 }
                 channels_config.telegram = Some(ChannelToggle {
                     enabled: Some(true),
+                    ..Default::default()
                 });
             }
             "Slack" => {
@@ -478,6 +486,7 @@ fn setup_channels(
                 }
                 channels_config.slack = Some(ChannelToggle {
                     enabled: Some(true),
+                    ..Default::default()
                 });
             }
             _ => {}
@@ -716,9 +725,11 @@ mod tests {
             channels: ChannelsFileConfig {
                 discord: Some(ChannelToggle {
                     enabled: Some(true),
+                    ..Default::default()
                 }),
                 telegram: Some(ChannelToggle {
                     enabled: Some(false),
+                    ..Default::default()
                 }),
                 ..Default::default()
             },
@@ -784,6 +795,7 @@ mod tests {
                 tts_model: Some("tts-1".to_string()),
                 tts_voice: Some("nova".to_string()),
                 tts_speed: Some(1.0),
+                ..VoiceFileConfig::default()
             },
             api_keys: ApiKeysFileConfig {
                 anthropic: Some("sk-ant-test".to_string()),
@@ -792,6 +804,7 @@ mod tests {
             channels: ChannelsFileConfig {
                 discord: Some(ChannelToggle {
                     enabled: Some(true),
+                    ..Default::default()
                 }),
                 ..Default::default()
             },
@@ -1,8 +1,10 @@
 //! Install automation for skill dependencies
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::Error;
+use crate::config::SkillsConfig;
+use crate::db::SkillRepo;
 
 use super::types::{
     InstallKind, NodeManager, SkillInstallPreferences, SkillInstallResult, SkillInstallSpec,
@@ -73,19 +75,24 @@ pub fn select_install_spec<'a>(
 
 /// Execute an install spec and verify binaries are available
 ///
+/// Git specs are cloned into the configured managed directory and registered
+/// in `skill_repo`.
+///
 /// # Errors
 ///
 /// Returns error if the install command fails to spawn
 pub async fn execute_install(
     spec: &SkillInstallSpec,
-    prefs: &SkillInstallPreferences,
+    config: &SkillsConfig,
+    skill_repo: &SkillRepo,
 ) -> Result<SkillInstallResult, Error> {
     let result = match spec.kind {
         InstallKind::Brew => install_brew(spec).await,
-        InstallKind::Node => install_node(spec, prefs).await,
+        InstallKind::Node => install_node(spec, &config.install_prefs).await,
         InstallKind::Go => install_go(spec).await,
         InstallKind::Uv => install_uv(spec).await,
         InstallKind::Download => install_download(spec).await,
+        InstallKind::Git => install_git(spec, config, skill_repo).await,
     };
 
     let mut result = result?;
@@ -182,6 +189,171 @@ async fn install_uv(spec: &SkillInstallSpec) -> Result<SkillInstallResult, Error
     run_command("uv", &["tool", "install", package]).await
 }
 
+async fn install_git(
+    spec: &SkillInstallSpec,
+    config: &SkillsConfig,
+    skill_repo: &SkillRepo,
+) -> Result<SkillInstallResult, Error> {
+    let url = spec
+        .url
+        .as_deref()
+        .ok_or_else(|| Error::Install("git install requires `url` field".to_string()))?;
+
+    let skill = super::install_from_git(skill_repo, config, url, spec.rev.as_deref()).await?;
+
+    Ok(SkillInstallResult {
+        ok: true,
+        message: format!("installed skill {} from {url}", skill.metadata.name),
+        stdout: String::new(),
+        stderr: String::new(),
+        code: Some(0),
+        warnings: vec![],
+    })
+}
+
+/// Clone or update a git-hosted skill inside `managed_dir`
+///
+/// Fetches only `rev` (or the remote HEAD) at depth 1 into a directory named
+/// after the repository, so re-running moves an existing checkout to the new
+/// ref. The repository must have a `SKILL.md` at its root.
+///
+/// # Errors
+///
+/// Returns `Error::Skill` if the URL or ref could be read as a git option or
+/// `rev` is not a ref name or commit hash, git fails, the target directory
+/// holds something other than a git checkout, or the checkout has no
+/// `SKILL.md`
+pub async fn install_git_skill(
+    url: &str,
+    rev: Option<&str>,
+    managed_dir: &Path,
+) -> Result<PathBuf, Error> {
+    if url.starts_with('-') {
+        return Err(Error::Skill(format!("invalid git URL: {url}")));
+    }
+    if let Some(rev) = rev
+        && !is_valid_git_rev(rev)
+    {
+        return Err(Error::Skill(format!("invalid git ref: {rev}")));
+    }
+
+    let name = git_skill_dir_name(url)
+        .ok_or_else(|| Error::Skill(format!("cannot derive a skill name from {url}")))?;
+    let dest = managed_dir.join(name);
+
+    let fresh = !dest.exists();
+    if !fresh && !dest.join(".git").is_dir() {
+        return Err(Error::Skill(format!(
+            "{} already exists and is not a git checkout",
+            dest.display()
+        )));
+    }
+
+    let result = checkout_git_skill(url, rev, &dest, fresh).await;
+
+    // Leave no half-populated skill behind for discovery to trip over
+    if result.is_err() && fresh {
+        let _ = tokio::fs::remove_dir_all(&dest).await;
+    }
+    result?;
+
+    Ok(dest)
+}
+
+/// Fetch `rev` into `dest` and check it out
+async fn checkout_git_skill(
+    url: &str,
+    rev: Option<&str>,
+    dest: &Path,
+    fresh: bool,
+) -> Result<(), Error> {
+    if fresh {
+        tokio::fs::create_dir_all(dest)
+            .await
+            .map_err(|e| Error::Skill(format!("failed to create {}: {e}", dest.display())))?;
+        run_git(dest, &["init", "-q"]).await?;
+        run_git(dest, &["remote", "add", "--end-of-options", "origin", url]).await?;
+    } else {
+        run_git(
+            dest,
+            &["remote", "set-url", "--end-of-options", "origin", url],
+        )
+        .await?;
+    }
+
+    run_git(
+        dest,
+        &[
+            "fetch",
+            "-q",
+            "--depth",
+            "1",
+            "--end-of-options",
+            "origin",
+            rev.unwrap_or("HEAD"),
+        ],
+    )
+    .await?;
+    run_git(dest, &["checkout", "-q", "--force", "FETCH_HEAD"]).await?;
+
+    if !dest.join("SKILL.md").is_file() {
+        return Err(Error::Skill(format!("{url} has no SKILL.md at its root")));
+    }
+
+    Ok(())
+}
+
+/// Run a git command in `dir`, surfacing failures as `Error::Skill`
+async fn run_git(dir: &Path, args: &[&str]) -> Result<(), Error> {
+    let output = tokio::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await
+        .map_err(|e| Error::Skill(format!("failed to run git: {e}")))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::Skill(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Whether `rev` is a plain ref name (branch, tag) or commit hash
+///
+/// Anything git could parse as an option or a refspec is rejected.
+fn is_valid_git_rev(rev: &str) -> bool {
+    !rev.is_empty()
+        && !rev.starts_with(['-', '/', '.'])
+        && !rev.ends_with(['/', '.'])
+        && !rev.ends_with(".lock")
+        && !rev.contains("..")
+        && !rev.contains("//")
+        && rev
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+}
+
+/// Directory name for a git-hosted skill, from the last path segment of its URL
+fn git_skill_dir_name(url: &str) -> Option<String> {
+    let trimmed = url.trim_end_matches('/');
+    let last = trimmed.rsplit(['/', ':']).next()?;
+    let name = last.strip_suffix(".git").unwrap_or(last);
+
+    let valid = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then(|| name.to_string())
+}
+
 #[allow(clippy::too_many_lines)]
 async fn install_download(spec: &SkillInstallSpec) -> Result<SkillInstallResult, Error> {
     let url = spec
@@ -330,6 +502,7 @@ mod tests {
             package: None,
             module: None,
             url: None,
+            rev: None,
             archive: None,
             strip_components: None,
             target_dir: None,
@@ -346,6 +519,7 @@ mod tests {
             package: Some("prettier".to_string()),
             module: None,
             url: None,
+            rev: None,
             archive: None,
             strip_components: None,
             target_dir: None,
@@ -362,6 +536,7 @@ mod tests {
             package: None,
             module: Some("github.com/example/tool@latest".to_string()),
             url: None,
+            rev: None,
             archive: None,
             strip_components: None,
             target_dir: None,
@@ -378,6 +553,7 @@ mod tests {
             package: None,
             module: None,
             url: Some("https://example.com/tool.tar.gz".to_string()),
+            rev: None,
             archive: Some("tar.gz".to_string()),
             strip_components: Some(1),
             target_dir: None,
@@ -431,4 +607,147 @@ mod tests {
         let selected = select_install_spec(&[], &prefs);
         assert!(selected.is_none());
     }
+
+    #[test]
+    fn git_skill_dir_name_uses_last_segment() {
+        assert_eq!(
+            git_skill_dir_name("https://git.example.com/team/weather-skill.git").as_deref(),
+            Some("weather-skill")
+        );
+        assert_eq!(
+            git_skill_dir_name("git@github.com:team/notes/").as_deref(),
+            Some("notes")
+        );
+        assert_eq!(git_skill_dir_name("file:///srv/skills/..").as_deref(), None);
+        assert_eq!(git_skill_dir_name("").as_deref(), None);
+    }
+
+    #[test]
+    fn git_rev_validation() {
+        for rev in ["main", "v1.2.0", "release/2024-01", "0a1b2c3d", "HEAD"] {
+            assert!(is_valid_git_rev(rev), "{rev} should be accepted");
+        }
+        for rev in [
+            "",
+            "--upload-pack=touch /tmp/pwned",
+            "-q",
+            "main:refs/heads/x",
+            "+main",
+            "a..b",
+            "a b",
+            "/main",
+            "main/",
+            "main.lock",
+            "@{-1}",
+        ] {
+            assert!(!is_valid_git_rev(rev), "{rev} should be rejected");
+        }
+    }
+
+    #[tokio::test]
+    async fn install_git_skill_rejects_option_like_rev() {
+        let tmp = tempfile::tempdir().unwrap();
+        let managed = tmp.path().join("managed");
+
+        let err = install_git_skill(
+            "https://example.com/team/echo-skill.git",
+            Some("--upload-pack=touch /tmp/pwned"),
+            &managed,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::Skill(_)));
+
+        let err = install_git_skill("--upload-pack=x", None, &managed)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Skill(_)));
+        assert!(!managed.exists());
+    }
+
+    /// Run git in `dir`, panicking on failure
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    /// Commit `file` in the repository at `dir`
+    fn git_commit(dir: &Path, file: &str, message: &str) {
+        git(dir, &["add", file]);
+        git(
+            dir,
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "commit",
+                "-qm",
+                message,
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn install_git_skill_pins_and_updates() {
+        if !has_binary("git") {
+            return;
+        }
+
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("echo-skill");
+        std::fs::create_dir(&source).unwrap();
+        git(&source, &["init", "-q"]);
+        std::fs::write(
+            source.join("SKILL.md"),
+            "---\nname: echo\ndescription: v1\n---\n\nv1\n",
+        )
+        .unwrap();
+        git_commit(&source, "SKILL.md", "v1");
+        git(&source, &["tag", "v1"]);
+        std::fs::write(
+            source.join("SKILL.md"),
+            "---\nname: echo\ndescription: v2\n---\n\nv2\n",
+        )
+        .unwrap();
+        git_commit(&source, "SKILL.md", "v2");
+
+        let url = format!("file://{}", source.display());
+        let managed = tmp.path().join("managed");
+
+        let dir = install_git_skill(&url, Some("v1"), &managed).await.unwrap();
+        assert_eq!(dir, managed.join("echo-skill"));
+        let skill = std::fs::read_to_string(dir.join("SKILL.md")).unwrap();
+        assert!(skill.contains("description: v1"));
+
+        // Re-installing without a ref moves the checkout to the remote HEAD
+        install_git_skill(&url, None, &managed).await.unwrap();
+        let skill = std::fs::read_to_string(dir.join("SKILL.md")).unwrap();
+        assert!(skill.contains("description: v2"));
+    }
+
+    #[tokio::test]
+    async fn install_git_skill_rejects_repo_without_skill_md() {
+        if !has_binary("git") {
+            return;
+        }
+
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("not-a-skill");
+        std::fs::create_dir(&source).unwrap();
+        git(&source, &["init", "-q"]);
+        std::fs::write(source.join("README.md"), "hello\n").unwrap();
+        git_commit(&source, "README.md", "init");
+
+        let managed = tmp.path().join("managed");
+        let url = format!("file://{}", source.display());
+        let err = install_git_skill(&url, None, &managed).await.unwrap_err();
+
+        assert!(matches!(err, Error::Skill(_)));
+        assert!(!managed.join("not-a-skill").exists());
+    }
 }
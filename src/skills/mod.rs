@@ -2,14 +2,18 @@
 
 pub mod install;
 mod manifold;
+mod remote;
 mod types;
 
 pub use manifold::ManifoldClient;
+pub use remote::{
+    REMOTE_CACHE_DIR, RemoteManifest, RemoteSkillEntry, RemoteSkillLoader, is_remote_location,
+};
 pub use types::{
-    CoreSkill, CoreSkillMetadata, InstallKind, InstalledSkill, NodeManager, Skill, SkillFilter,
-    SkillInstallPreferences, SkillInstallResult, SkillInstallSpec, SkillLookup, SkillMetadata,
-    SkillPriority, SkillSnapshot, SkillSource, SnapshotEntry, deduplicate_command_name, has_binary,
-    merge_nested_metadata, sanitize_command_name,
+    CoreSkill, CoreSkillMetadata, InstallKind, InstalledSkill, MissingRequirement, NodeManager,
+    Skill, SkillFilter, SkillInstallPreferences, SkillInstallResult, SkillInstallSpec, SkillLookup,
+    SkillMetadata, SkillPriority, SkillSnapshot, SkillSource, SnapshotEntry,
+    deduplicate_command_name, has_binary, merge_nested_metadata, sanitize_command_name,
 };
 
 use std::collections::HashMap;
@@ -269,6 +273,55 @@ impl SkillRegistry {
         Ok(count)
     }
 
+    /// Record skills listed in remote manifests without fetching their bodies
+    ///
+    /// Entry URLs may be relative to the manifest. Skills already discovered
+    /// locally keep precedence by name; unreachable manifests are skipped.
+    pub async fn discover_remote(
+        &mut self,
+        loader: &RemoteSkillLoader,
+        manifests: &[String],
+    ) -> usize {
+        let mut count = 0;
+        for manifest_url in manifests {
+            let manifest = match loader.fetch_manifest(manifest_url).await {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    tracing::warn!(url = %manifest_url, error = %e, "failed to load skill manifest");
+                    continue;
+                }
+            };
+
+            for entry in manifest.skills {
+                let url = match reqwest::Url::parse(manifest_url)
+                    .and_then(|base| base.join(&entry.url))
+                {
+                    Ok(url) => url.to_string(),
+                    Err(e) => {
+                        tracing::warn!(url = %entry.url, error = %e, "invalid remote skill url, skipping");
+                        continue;
+                    }
+                };
+                let id = entry.metadata.name.clone();
+                if self.skills.contains_key(&id) {
+                    continue;
+                }
+                self.skills.insert(
+                    id.clone(),
+                    Skill {
+                        id,
+                        metadata: entry.metadata,
+                        content: String::new(),
+                        source: SkillSource::Local,
+                        location: Some(url),
+                    },
+                );
+                count += 1;
+            }
+        }
+        count
+    }
+
     /// Scan plugin skill directories, tagging discoveries as `SkillSource::Plugin`
     ///
     /// # Errors
@@ -532,43 +585,103 @@ pub enum SlashCommandAction {
         tool_name: String,
         arguments: String,
     },
+    /// No exact match; these command names are close to what was typed
+    Suggest { candidates: Vec<String> },
+}
+
+/// Max edit distance for a command name to be suggested
+const SUGGESTION_MAX_DISTANCE: usize = 2;
+
+/// Max number of suggested command names
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Format suggested command names as a reply, e.g. "Did you mean /weather?"
+#[must_use]
+pub fn format_suggestion(candidates: &[String]) -> String {
+    let commands: Vec<String> = candidates.iter().map(|c| format!("/{c}")).collect();
+    match commands.as_slice() {
+        [] => String::new(),
+        [only] => format!("Did you mean {only}?"),
+        [rest @ .., last] => format!("Did you mean {} or {last}?", rest.join(", ")),
+    }
+}
+
+/// Levenshtein distance between two strings, by character
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Enabled command names within [`SUGGESTION_MAX_DISTANCE`] of `command`,
+/// closest first
+fn suggest_commands(
+    command: &str,
+    skill_repo: &crate::db::SkillRepo,
+    user_id: Option<&str>,
+    channel: Option<&str>,
+) -> Result<Vec<String>> {
+    let command = command.to_lowercase();
+    let mut scored: Vec<(usize, String)> = skill_repo
+        .list_enabled_for_user(user_id, channel)?
+        .into_iter()
+        .filter(|s| s.skill.metadata.user_invocable)
+        .filter_map(|s| s.command_name)
+        .map(|name| (edit_distance(&command, &name.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= SUGGESTION_MAX_DISTANCE)
+        .collect();
+
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    Ok(scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name)
+        .collect())
 }
 
 /// Resolve a slash command from user input
 ///
 /// Parses `/command_name` from the start of input, looks up via `SkillRepo`,
-/// and returns the matching action if found.
+/// and returns the matching action if found. When no command matches exactly,
+/// returns [`SlashCommandAction::Suggest`] with close command names, if any.
 ///
 /// # Errors
 ///
-/// Returns error if database lookup fails
-pub fn resolve_slash_command(
+/// Returns error if database lookup fails or a remote skill body cannot be
+/// fetched
+pub async fn resolve_slash_command(
     input: &str,
     skill_repo: &crate::db::SkillRepo,
     user_id: Option<&str>,
+    channel: Option<&str>,
+    remote: &RemoteSkillLoader,
 ) -> Result<Option<SlashCommandAction>> {
-    let trimmed = input.trim();
-    if !trimmed.starts_with('/') {
+    let Some((command, remaining)) = split_command(input) else {
         return Ok(None);
-    }
-
-    // Extract command name (first word after /)
-    let after_slash = &trimmed[1..];
-    let command_end = after_slash
-        .find(|c: char| c.is_whitespace())
-        .unwrap_or(after_slash.len());
-    let command = &after_slash[..command_end];
-
-    if command.is_empty() {
-        return Ok(None);
-    }
+    };
 
     // Look up in database
-    let skill = skill_repo.get_by_command_name(command, user_id)?;
+    // Scoped overrides for this user and channel decide whether it is enabled
+    let skill = skill_repo.get_by_command_name(command, user_id, channel)?;
 
     match skill {
-        Some(s) if s.enabled && s.skill.metadata.user_invocable => {
-            let remaining = after_slash[command_end..].trim().to_string();
+        Some(mut s) if s.enabled && s.skill.metadata.user_invocable => {
+            // Remote skills are fetched on first use
+            remote.resolve(&mut s).await?;
+
+            let remaining = remaining.to_string();
 
             if let Some(ref tool_name) = s.command_dispatch_tool {
                 Ok(Some(SlashCommandAction::DispatchTool {
@@ -583,10 +696,55 @@ pub fn resolve_slash_command(
                 }))
             }
         }
-        _ => Ok(None),
+        Some(_) => Ok(None),
+        None => {
+            let candidates = suggest_commands(command, skill_repo, user_id, channel)?;
+            if candidates.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(SlashCommandAction::Suggest { candidates }))
+            }
+        }
     }
 }
 
+/// Reply suggesting close commands when `input` is an unknown slash command
+///
+/// For paths that don't dispatch slash commands themselves, such as channel
+/// handlers. Known commands and plain messages yield `None`.
+///
+/// # Errors
+///
+/// Returns error if the skill lookup fails
+pub fn slash_suggestion(
+    input: &str,
+    skill_repo: &crate::db::SkillRepo,
+    user_id: Option<&str>,
+    channel: Option<&str>,
+) -> Result<Option<String>> {
+    let Some((command, _)) = split_command(input) else {
+        return Ok(None);
+    };
+    if skill_repo
+        .get_by_command_name(command, user_id, channel)?
+        .is_some()
+    {
+        return Ok(None);
+    }
+    let candidates = suggest_commands(command, skill_repo, user_id, channel)?;
+    Ok((!candidates.is_empty()).then(|| format_suggestion(&candidates)))
+}
+
+/// Split `/command rest` into the command name and the trimmed rest
+fn split_command(input: &str) -> Option<(&str, &str)> {
+    let after_slash = input.trim().strip_prefix('/')?;
+    let command_end = after_slash
+        .find(char::is_whitespace)
+        .unwrap_or(after_slash.len());
+    let (command, rest) = after_slash.split_at(command_end);
+    (!command.is_empty()).then(|| (command, rest.trim()))
+}
+
 /// Sync discovered skills into the database at startup
 ///
 /// - Bundled skills: upsert content (preserve user's enabled/priority)
@@ -624,6 +782,34 @@ pub fn sync_discovered_skills(
     Ok(synced)
 }
 
+/// Install a skill from a git repository into the managed skills directory
+///
+/// Clones (or updates) the checkout, then registers it through
+/// [`sync_discovered_skills`]. A skill already in the database keeps its
+/// stored content; only the checkout on disk moves to the new ref.
+///
+/// # Errors
+///
+/// Returns `Error::Skill` if the clone fails or the repository has no
+/// `SKILL.md`, or a database error if registration fails
+pub async fn install_from_git(
+    skill_repo: &crate::db::SkillRepo,
+    config: &crate::config::SkillsConfig,
+    url: &str,
+    rev: Option<&str>,
+) -> Result<Skill> {
+    let dir = install::install_git_skill(url, rev, &config.managed_dir).await?;
+    let skill = load_skill_file_with_source(&dir.join("SKILL.md"), SkillSource::Local)?;
+
+    let mut registry = SkillRegistry::new(config.managed_dir.clone());
+    registry.discover_all_roots(config)?;
+    sync_discovered_skills(skill_repo, &registry)?;
+
+    tracing::info!(name = %skill.metadata.name, url, rev, "installed skill from git");
+
+    Ok(skill)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -759,4 +945,97 @@ Body.
         assert_eq!(meta.os, vec!["linux"]);
         assert!(body.contains("Beacon body."));
     }
+
+    #[test]
+    fn edit_distance_counts_single_char_edits() {
+        assert_eq!(edit_distance("weather", "weather"), 0);
+        assert_eq!(edit_distance("wether", "weather"), 1);
+        assert_eq!(edit_distance("waether", "weather"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn format_suggestion_lists_commands() {
+        assert_eq!(
+            format_suggestion(&["weather".to_string()]),
+            "Did you mean /weather?"
+        );
+        assert_eq!(
+            format_suggestion(&["a".to_string(), "b".to_string(), "c".to_string()]),
+            "Did you mean /a, /b or /c?"
+        );
+    }
+
+    #[tokio::test]
+    async fn resolve_slash_command_suggests_close_commands() {
+        let repo = crate::db::SkillRepo::new(crate::db::init_memory().unwrap());
+        for name in ["weather", "summarize"] {
+            let content = format!("---\nname: {name}\ndescription: {name}\n---\n\nBody.\n");
+            let (metadata, body) = parse_frontmatter(&content).unwrap();
+            repo.install(&Skill {
+                id: name.to_string(),
+                metadata,
+                content: body,
+                source: SkillSource::Local,
+                location: None,
+            })
+            .unwrap();
+        }
+        let remote = RemoteSkillLoader::new(std::env::temp_dir());
+
+        // Exact matches still resolve
+        let exact = resolve_slash_command("/weather today", &repo, None, None, &remote)
+            .await
+            .unwrap();
+        assert!(matches!(
+            exact,
+            Some(SlashCommandAction::InjectPrompt { ref remaining, .. }) if remaining == "today"
+        ));
+
+        let typo = resolve_slash_command("/wether today", &repo, None, None, &remote)
+            .await
+            .unwrap();
+        assert!(matches!(
+            typo,
+            Some(SlashCommandAction::Suggest { ref candidates }) if candidates == &["weather"]
+        ));
+
+        let unrelated = resolve_slash_command("/xyz", &repo, None, None, &remote)
+            .await
+            .unwrap();
+        assert!(unrelated.is_none());
+
+        // A channel-scoped disable hides the command on that channel only
+        let weather = repo
+            .get_by_command_name("weather", None, None)
+            .unwrap()
+            .unwrap();
+        repo.set_scope(&weather.id, None, Some("discord"), false)
+            .unwrap();
+        let scoped = resolve_slash_command("/weather", &repo, None, Some("discord"), &remote)
+            .await
+            .unwrap();
+        assert!(scoped.is_none());
+        let elsewhere = resolve_slash_command("/weather", &repo, None, Some("web"), &remote)
+            .await
+            .unwrap();
+        assert!(matches!(
+            elsewhere,
+            Some(SlashCommandAction::InjectPrompt { .. })
+        ));
+
+        // Channel handlers get the reply text for typos only
+        assert_eq!(
+            slash_suggestion("/wether", &repo, None, Some("web")).unwrap(),
+            Some("Did you mean /weather?".to_string())
+        );
+        assert_eq!(
+            slash_suggestion("/weather", &repo, None, Some("web")).unwrap(),
+            None
+        );
+        assert_eq!(
+            slash_suggestion("hello", &repo, None, Some("web")).unwrap(),
+            None
+        );
+    }
 }
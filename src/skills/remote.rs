@@ -0,0 +1,313 @@
+//! Lazily fetched skills hosted over HTTP
+//!
+//! A remote manifest lists skill metadata alongside the URL of each skill's
+//! `SKILL.md`. Discovery downloads only the manifest; a skill body is fetched
+//! the first time the skill is used and cached on disk, then revalidated with
+//! `If-None-Match` on later fetches.
+//!
+//! `SkillSource` is owned by agent-core, so remote skills are stored as local
+//! skills whose `location` is the body URL.
+
+use std::path::{Path, PathBuf};
+
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use super::{InstalledSkill, SkillMetadata};
+use crate::{Error, Result};
+
+/// Directory under the managed skills dir holding fetched bodies
+pub const REMOTE_CACHE_DIR: &str = ".remote";
+
+/// Manifest listing remote skills
+#[derive(Debug, Deserialize)]
+pub struct RemoteManifest {
+    pub skills: Vec<RemoteSkillEntry>,
+}
+
+/// One manifest entry: frontmatter fields plus where to fetch the body
+#[derive(Debug, Deserialize)]
+pub struct RemoteSkillEntry {
+    /// URL of the skill's `SKILL.md`
+    pub url: String,
+    #[serde(flatten)]
+    pub metadata: SkillMetadata,
+}
+
+/// Whether a skill location points at a remote body
+#[must_use]
+pub fn is_remote_location(location: &str) -> bool {
+    location.starts_with("https://") || location.starts_with("http://")
+}
+
+/// Fetches remote skill bodies and manifests through an on-disk cache
+#[derive(Debug, Clone)]
+pub struct RemoteSkillLoader {
+    client: reqwest::Client,
+    cache_dir: PathBuf,
+}
+
+impl RemoteSkillLoader {
+    /// Create a loader caching into `cache_dir`
+    #[must_use]
+    pub fn new(cache_dir: PathBuf) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            cache_dir,
+        }
+    }
+
+    /// Create a loader caching inside the configured managed skills dir
+    #[must_use]
+    pub fn for_config(config: &crate::config::SkillsConfig) -> Self {
+        Self::new(config.managed_dir.join(REMOTE_CACHE_DIR))
+    }
+
+    /// Cache file for a URL
+    #[must_use]
+    pub fn cached_path(&self, url: &str) -> PathBuf {
+        let key = hex::encode(Sha256::digest(url.as_bytes()));
+        self.cache_dir.join(format!("{key}.md"))
+    }
+
+    /// Fetch a URL, revalidating any cached copy with its `ETag`
+    ///
+    /// Falls back to the cached copy when the server is unreachable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Skill` if the fetch fails and nothing is cached
+    pub async fn fetch(&self, url: &str) -> Result<String> {
+        let path = self.cached_path(url);
+        let etag_path = path.with_extension("etag");
+        let cached = tokio::fs::read_to_string(&path).await.ok();
+
+        let mut request = self.client.get(url);
+        if cached.is_some()
+            && let Ok(etag) = tokio::fs::read_to_string(&etag_path).await
+        {
+            request = request.header(IF_NONE_MATCH, etag.trim());
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                return cached.ok_or_else(|| Error::Skill(format!("failed to fetch {url}: {e}")));
+            }
+        };
+
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED
+            && let Some(body) = cached
+        {
+            return Ok(body);
+        }
+        if !status.is_success() {
+            if let Some(body) = cached {
+                tracing::warn!(url, %status, "remote skill fetch failed, using cached copy");
+                return Ok(body);
+            }
+            return Err(Error::Skill(format!(
+                "fetching {url} returned HTTP {status}"
+            )));
+        }
+
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(ToString::to_string);
+        let body = response
+            .text()
+            .await
+            .map_err(|e| Error::Skill(format!("failed to read {url}: {e}")))?;
+
+        if let Err(e) = self.store(&path, &body, etag.as_deref()).await {
+            tracing::warn!(url, error = %e, "failed to cache remote skill");
+        }
+
+        Ok(body)
+    }
+
+    /// Write a fetched body and its `ETag` to the cache
+    async fn store(&self, path: &Path, body: &str, etag: Option<&str>) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.cache_dir).await?;
+        tokio::fs::write(path, body).await?;
+        let etag_path = path.with_extension("etag");
+        match etag {
+            Some(etag) => tokio::fs::write(etag_path, etag).await,
+            None => match tokio::fs::remove_file(etag_path).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Fetch and parse a manifest
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Skill` if the manifest cannot be fetched or parsed
+    pub async fn fetch_manifest(&self, url: &str) -> Result<RemoteManifest> {
+        let body = self.fetch(url).await?;
+        serde_json::from_str(&body)
+            .map_err(|e| Error::Skill(format!("invalid skill manifest at {url}: {e}")))
+    }
+
+    /// Load the body of a remote skill, pointing its location at the cache
+    ///
+    /// Skills with a local location are left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Skill` if the body cannot be fetched
+    pub async fn resolve(&self, skill: &mut InstalledSkill) -> Result<()> {
+        let Some(url) = skill
+            .skill
+            .location
+            .clone()
+            .filter(|l| is_remote_location(l))
+        else {
+            return Ok(());
+        };
+
+        let body = self.fetch(&url).await?;
+        skill.skill.content = super::parse_frontmatter(&body).map_or(body, |(_, content)| content);
+        skill.skill.location = Some(self.cached_location(&url));
+        Ok(())
+    }
+
+    /// Point a remote skill at its cache file for prompt inclusion
+    ///
+    /// Starts a background fetch when the body has not been cached yet, so it
+    /// is on disk by the time the model reads it.
+    pub fn localize(&self, skill: &mut InstalledSkill) {
+        let Some(url) = skill
+            .skill
+            .location
+            .clone()
+            .filter(|l| is_remote_location(l))
+        else {
+            return;
+        };
+
+        if !self.cached_path(&url).exists() {
+            let loader = self.clone();
+            let fetch_url = url.clone();
+            tokio::spawn(async move {
+                if let Err(e) = loader.fetch(&fetch_url).await {
+                    tracing::warn!(url = %fetch_url, error = %e, "failed to prefetch remote skill");
+                }
+            });
+        }
+        skill.skill.location = Some(self.cached_location(&url));
+    }
+
+    fn cached_location(&self, url: &str) -> String {
+        crate::prompt::compact_path(&self.cached_path(url).to_string_lossy())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{Router, http::HeaderMap, routing::get};
+
+    use super::*;
+
+    const BODY: &str = "---\nname: big\ndescription: A large skill\n---\n\nLots of instructions.\n";
+
+    /// Serve `BODY` with an `ETag`, counting full (non-304) responses
+    async fn serve() -> (String, Arc<AtomicUsize>) {
+        let full = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&full);
+        let app = Router::new()
+            .route(
+                "/big/SKILL.md",
+                get(move |headers: HeaderMap| {
+                    let counter = Arc::clone(&counter);
+                    async move {
+                        if headers.get("if-none-match").is_some_and(|v| v == "\"v1\"") {
+                            return (StatusCode::NOT_MODIFIED, [("etag", "\"v1\"")], "");
+                        }
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        (StatusCode::OK, [("etag", "\"v1\"")], BODY)
+                    }
+                }),
+            )
+            .route(
+                "/manifest.json",
+                get(|| async {
+                    r#"{"skills": [{"name": "big", "description": "A large skill", "url": "/big/SKILL.md"}]}"#
+                }),
+            );
+        (crate::test_support::serve(app).await, full)
+    }
+
+    #[tokio::test]
+    async fn fetch_caches_and_revalidates_with_etag() {
+        let (base, full) = serve().await;
+        let dir = tempfile::tempdir().unwrap();
+        let loader = RemoteSkillLoader::new(dir.path().to_path_buf());
+        let url = format!("{base}/big/SKILL.md");
+
+        assert_eq!(loader.fetch(&url).await.unwrap(), BODY);
+        assert_eq!(loader.fetch(&url).await.unwrap(), BODY);
+
+        // Second fetch was answered with 304 from the cached ETag
+        assert_eq!(full.load(Ordering::SeqCst), 1);
+        assert!(loader.cached_path(&url).exists());
+    }
+
+    #[tokio::test]
+    async fn manifest_records_metadata_without_body() {
+        let (base, full) = serve().await;
+        let dir = tempfile::tempdir().unwrap();
+        let loader = RemoteSkillLoader::new(dir.path().to_path_buf());
+
+        let manifest = loader
+            .fetch_manifest(&format!("{base}/manifest.json"))
+            .await
+            .unwrap();
+
+        assert_eq!(manifest.skills.len(), 1);
+        assert_eq!(manifest.skills[0].metadata.name, "big");
+        assert_eq!(manifest.skills[0].url, "/big/SKILL.md");
+        assert_eq!(full.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn registry_resolves_entry_urls_against_manifest() {
+        let (base, full) = serve().await;
+        let dir = tempfile::tempdir().unwrap();
+        let loader = RemoteSkillLoader::new(dir.path().to_path_buf());
+
+        let mut registry = super::super::SkillRegistry::new(dir.path().to_path_buf());
+        let count = registry
+            .discover_remote(&loader, &[format!("{base}/manifest.json")])
+            .await;
+
+        assert_eq!(count, 1);
+        let skill = registry.get("big").unwrap();
+        assert!(skill.content.is_empty());
+        assert_eq!(
+            skill.location.as_deref(),
+            Some(format!("{base}/big/SKILL.md").as_str())
+        );
+        assert_eq!(full.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn remote_locations_are_http_urls() {
+        assert!(is_remote_location(
+            "https://skills.example.com/big/SKILL.md"
+        ));
+        assert!(!is_remote_location(
+            "~/.config/omni/beacon/skills/big/SKILL.md"
+        ));
+    }
+}
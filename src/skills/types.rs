@@ -23,6 +23,8 @@ pub enum InstallKind {
     Go,
     Uv,
     Download,
+    /// Skill sources hosted in a git repository
+    Git,
 }
 
 /// A single install specification for a skill dependency
@@ -46,9 +48,12 @@ pub struct SkillInstallSpec {
     /// Go module path
     #[serde(default)]
     pub module: Option<String>,
-    /// Download URL
+    /// Download or git repository URL
     #[serde(default)]
     pub url: Option<String>,
+    /// Git ref (branch, tag or commit) to pin; defaults to the remote HEAD
+    #[serde(default)]
+    pub rev: Option<String>,
     /// Archive format: "tar.gz", "tar.bz2", "zip"
     #[serde(default)]
     pub archive: Option<String>,
@@ -310,6 +315,91 @@ pub struct InstalledSkill {
     pub skill_env: HashMap<String, String>,
 }
 
+/// A skill requirement the current host does not meet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum MissingRequirement {
+    /// Env var not set (and not provided by the stored API key)
+    Env(String),
+    /// Required binary not on PATH
+    Bin(String),
+    /// None of these binaries are on PATH
+    AnyBin(Vec<String>),
+    /// Current OS is not in this list
+    Os(Vec<String>),
+    /// Config path not enabled (unknown paths are never satisfied)
+    Config(String),
+}
+
+impl std::fmt::Display for MissingRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Env(var) => write!(f, "env var {var} is not set"),
+            Self::Bin(bin) => write!(f, "binary {bin} is not on PATH"),
+            Self::AnyBin(bins) => write!(f, "none of {} are on PATH", bins.join(", ")),
+            Self::Os(os) => write!(
+                f,
+                "{} is not one of {}",
+                std::env::consts::OS,
+                os.join(", ")
+            ),
+            Self::Config(path) => write!(f, "config {path} is not enabled"),
+        }
+    }
+}
+
+impl InstalledSkill {
+    /// Requirements this skill declares that are not met at runtime
+    #[must_use]
+    pub fn missing_requirements(&self, voice_enabled: bool) -> Vec<MissingRequirement> {
+        let meta = &self.skill.metadata;
+        let mut missing: Vec<MissingRequirement> = meta
+            .requires_env
+            .iter()
+            .filter(|var| {
+                !crate::prompt::check_env_requirements_with_config(
+                    std::slice::from_ref(var),
+                    meta.primary_env.as_deref(),
+                    self.api_key.as_deref(),
+                )
+            })
+            .map(|var| MissingRequirement::Env(var.clone()))
+            .collect();
+
+        missing.extend(
+            meta.requires_bins
+                .iter()
+                .filter(|b| !has_binary(b))
+                .map(|b| MissingRequirement::Bin(b.clone())),
+        );
+        if !crate::prompt::check_any_bins_requirement(&meta.requires_any_bins) {
+            missing.push(MissingRequirement::AnyBin(meta.requires_any_bins.clone()));
+        }
+        if !crate::prompt::check_os_requirement(&meta.os) {
+            missing.push(MissingRequirement::Os(meta.os.clone()));
+        }
+        missing.extend(
+            meta.requires_config
+                .iter()
+                .filter(|path| {
+                    !crate::prompt::check_config_requirement(
+                        std::slice::from_ref(path),
+                        voice_enabled,
+                    )
+                })
+                .map(|path| MissingRequirement::Config(path.clone())),
+        );
+
+        missing
+    }
+
+    /// Whether every declared requirement is met
+    #[must_use]
+    pub fn is_satisfied(&self, voice_enabled: bool) -> bool {
+        self.missing_requirements(voice_enabled).is_empty()
+    }
+}
+
 /// Merge nested skill metadata into flat Beacon fields
 ///
 /// Checks `metadata.openclaw`, `metadata.clawdbot`, and `metadata.clawdis`
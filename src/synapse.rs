@@ -0,0 +1,279 @@
+//! Circuit breaker for Synapse calls
+//!
+//! When Synapse is degraded every incoming message would otherwise wait on a
+//! slow failing call, piling load onto the outage. After
+//! `failure_threshold` consecutive failures the breaker opens and calls fail
+//! fast for `cooldown`. It then half-opens: one probe call is let through,
+//! closing the breaker on success and re-opening it on failure.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::{Error, Result};
+
+/// Consecutive failures that open the breaker by default
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// How long the breaker stays open by default before probing
+const DEFAULT_COOLDOWN_SECS: u64 = 30;
+
+/// Reply sent to users while the breaker is open
+pub const UNAVAILABLE_REPLY: &str =
+    "I'm having trouble reaching my brain right now. Please try again in a minute.";
+
+/// Circuit breaker configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the breaker
+    pub failure_threshold: u32,
+    /// How long the breaker stays open before letting a probe through
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cooldown: Duration::from_secs(DEFAULT_COOLDOWN_SECS),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// Load from environment variables
+    ///
+    /// Reads from:
+    /// - `BEACON_SYNAPSE_FAILURE_THRESHOLD`: failures that open the breaker (default: 5)
+    /// - `BEACON_SYNAPSE_COOLDOWN_SECS`: seconds before probing recovery (default: 30)
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            failure_threshold: std::env::var("BEACON_SYNAPSE_FAILURE_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.failure_threshold),
+            cooldown: std::env::var("BEACON_SYNAPSE_COOLDOWN_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map_or(defaults.cooldown, Duration::from_secs),
+        }
+    }
+}
+
+/// Breaker state, as reported by `/ready`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Calls go through
+    Closed,
+    /// Calls fail fast until the cooldown ends
+    Open,
+    /// Cooldown ended; one probe call decides whether to close
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Inner {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probe_in_flight: bool,
+}
+
+/// Circuit breaker shared by everything calling Synapse
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<Inner>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(CircuitBreakerConfig::default())
+    }
+}
+
+impl CircuitBreaker {
+    /// Create a closed breaker
+    #[must_use]
+    pub const fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(Inner {
+                consecutive_failures: 0,
+                opened_at: None,
+                probe_in_flight: false,
+            }),
+        }
+    }
+
+    /// Current state
+    #[must_use]
+    pub fn state(&self) -> BreakerState {
+        let inner = self.lock();
+        match inner.opened_at {
+            None => BreakerState::Closed,
+            Some(at) if at.elapsed() < self.config.cooldown => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+
+    /// Time left until the open breaker lets a probe through
+    #[must_use]
+    pub fn retry_in(&self) -> Option<Duration> {
+        let opened_at = self.lock().opened_at?;
+        self.config.cooldown.checked_sub(opened_at.elapsed())
+    }
+
+    /// Ask to make a call, failing fast while the breaker is open
+    ///
+    /// Report the outcome through the returned permit. A permit dropped
+    /// without an outcome (e.g. a cancelled turn) counts as neither.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Unavailable` while open, or while half-open with a
+    /// probe already in flight
+    pub fn acquire(&self) -> Result<CircuitPermit<'_>> {
+        let mut inner = self.lock();
+        if let Some(opened_at) = inner.opened_at {
+            if opened_at.elapsed() < self.config.cooldown || inner.probe_in_flight {
+                return Err(Error::Unavailable("Synapse circuit breaker is open".into()));
+            }
+            inner.probe_in_flight = true;
+            tracing::info!("Synapse circuit half-open, probing");
+        }
+
+        Ok(CircuitPermit {
+            breaker: self,
+            probe: inner.opened_at.is_some(),
+            resolved: false,
+        })
+    }
+
+    fn record(&self, probe: bool, success: bool) {
+        let mut inner = self.lock();
+        if probe {
+            inner.probe_in_flight = false;
+        }
+        if success {
+            if inner.opened_at.take().is_some() {
+                tracing::info!("Synapse circuit closed");
+            }
+            inner.consecutive_failures = 0;
+            return;
+        }
+
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        let probe_failed = inner.opened_at.is_some();
+        if probe_failed || inner.consecutive_failures >= self.config.failure_threshold {
+            inner.opened_at = Some(Instant::now());
+            tracing::warn!(
+                failures = inner.consecutive_failures,
+                cooldown_secs = self.config.cooldown.as_secs(),
+                "Synapse circuit open"
+            );
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Permission to make one call, returned by [`CircuitBreaker::acquire`]
+#[derive(Debug)]
+pub struct CircuitPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    /// Whether this is the half-open probe
+    probe: bool,
+    resolved: bool,
+}
+
+impl CircuitPermit<'_> {
+    /// The call succeeded
+    pub fn success(mut self) {
+        self.resolved = true;
+        self.breaker.record(self.probe, true);
+    }
+
+    /// The call failed
+    pub fn failure(mut self) {
+        self.resolved = true;
+        self.breaker.record(self.probe, false);
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        // Only the probe holds the half-open slot
+        if self.probe && !self.resolved {
+            self.breaker.lock().probe_in_flight = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(cooldown: Duration) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            cooldown,
+        })
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let breaker = breaker(Duration::from_secs(60));
+
+        breaker.acquire().unwrap().failure();
+        breaker.acquire().unwrap().success();
+        breaker.acquire().unwrap().failure();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+
+        breaker.acquire().unwrap().failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(matches!(breaker.acquire(), Err(Error::Unavailable(_))));
+        assert!(breaker.retry_in().is_some());
+    }
+
+    #[test]
+    fn half_open_lets_one_probe_through() {
+        let breaker = breaker(Duration::ZERO);
+        breaker.acquire().unwrap().failure();
+        breaker.acquire().unwrap().failure();
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        // A failed probe re-opens the breaker
+        let probe = breaker.acquire().unwrap();
+        assert!(breaker.acquire().is_err());
+        probe.failure();
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        // An abandoned probe frees the slot
+        drop(breaker.acquire().unwrap());
+
+        breaker.acquire().unwrap().success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn stale_permits_do_not_free_the_probe_slot() {
+        let breaker = breaker(Duration::ZERO);
+        // Acquired while closed, still running when the breaker opens
+        let stale = breaker.acquire().unwrap();
+        breaker.acquire().unwrap().failure();
+        breaker.acquire().unwrap().failure();
+
+        let _probe = breaker.acquire().unwrap();
+        drop(stale);
+        assert!(breaker.acquire().is_err());
+    }
+}
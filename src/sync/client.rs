@@ -2,10 +2,23 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::db::{DbPool, Memory, MemoryCategory, MemoryRepo};
+use crate::db::{DbPool, Memory, MemoryCategory, MemoryRepo, SyncStateRepo};
 use crate::{Error, Result};
 
-use super::merge::merge_memory;
+use super::crypto::{MemoryCipher, SealedFields, SyncEncryption, generate_salt, is_sealed};
+use super::merge::{MergeStrategy, merge_memory};
+
+/// Starting point of a pull with no stored cursor
+const SYNC_EPOCH: &str = "1970-01-01T00:00:00Z";
+
+/// GraphQL error code the API uses for an expired or unknown cursor
+const INVALID_CURSOR_CODE: &str = "INVALID_CURSOR";
+
+/// Suffix of the cursor key used while encryption is on
+///
+/// Keeps plaintext-era cursors from being reused, so enabling encryption
+/// starts with a full sync that re-uploads everything sealed.
+const ENCRYPTED_CURSOR_SUFFIX: &str = "#e2e";
 
 /// Client for syncing memories with the Beacon cloud API
 #[derive(Clone)]
@@ -14,6 +27,9 @@ pub struct SyncClient {
     device_id: String,
     client: reqwest::Client,
     auth_token: Option<String>,
+    merge_strategy: MergeStrategy,
+    /// Seals memory bodies before upload (None = plaintext)
+    cipher: Option<MemoryCipher>,
 }
 
 /// Memory payload sent to/received from the API
@@ -97,9 +113,55 @@ struct RemoteMemory {
     deleted_at: Option<String>,
 }
 
+/// Response from the `syncSettings` query and `initSyncSettings` mutation
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncSettingsResponse {
+    data: Option<SyncSettingsData>,
+    errors: Option<Vec<GraphQlError>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncSettingsData {
+    sync_settings: Option<SyncSettings>,
+}
+
+/// Per-account sync metadata kept by the API
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncSettings {
+    /// Base64 Argon2 salt for passphrase keys
+    encryption_salt: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct GraphQlError {
     message: String,
+    #[serde(default)]
+    extensions: Option<GraphQlErrorExtensions>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlErrorExtensions {
+    code: Option<String>,
+}
+
+impl GraphQlError {
+    /// Whether the server rejected the `since` cursor
+    fn is_invalid_cursor(&self) -> bool {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.code.as_deref())
+            .is_some_and(|code| code == INVALID_CURSOR_CODE)
+            || self.message.to_ascii_lowercase().contains("invalid cursor")
+    }
+}
+
+/// Result of pulling from a cursor
+enum PullOutcome {
+    Pulled(usize),
+    InvalidCursor,
 }
 
 impl SyncClient {
@@ -111,9 +173,48 @@ impl SyncClient {
             device_id: device_id.to_string(),
             client: reqwest::Client::new(),
             auth_token: None,
+            merge_strategy: MergeStrategy::default(),
+            cipher: None,
         }
     }
 
+    /// Set how conflicting edits are resolved when pulling
+    #[must_use]
+    pub const fn with_merge_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.merge_strategy = strategy;
+        self
+    }
+
+    /// Encrypt memory bodies end to end with `cipher`
+    ///
+    /// Pulled memories that cannot be decrypted abort the pull instead of
+    /// being stored; plaintext left over from before encryption is skipped.
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: MemoryCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Encrypt memory bodies with the key for `encryption`
+    ///
+    /// Passphrase keys are salted per account: the salt is read from the
+    /// account's sync settings, and created there by the first device to
+    /// enable encryption.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the salt cannot be fetched or stored, or the key
+    /// cannot be derived
+    pub async fn with_encryption(self, encryption: &SyncEncryption) -> Result<Self> {
+        let cipher = match encryption {
+            SyncEncryption::Passphrase(passphrase) => {
+                MemoryCipher::from_passphrase(passphrase, &self.account_salt().await?)?
+            }
+            SyncEncryption::DeviceKey => MemoryCipher::from_local_device()?,
+        };
+        Ok(self.with_cipher(cipher))
+    }
+
     /// Set the authentication token for API calls
     #[must_use]
     pub fn with_auth_token(mut self, token: String) -> Self {
@@ -139,27 +240,10 @@ impl SyncClient {
             return Ok(0);
         }
 
-        let payloads: Vec<MemorySyncPayload> = unsynced
+        let payloads = unsynced
             .iter()
-            .map(|m| MemorySyncPayload {
-                gateway_memory_id: m.id.clone(),
-                category: m.category.to_string(),
-                content: m.content.clone(),
-                content_hash: m.content_hash.clone(),
-                tags: m.tags.clone(),
-                pinned: m.pinned,
-                access_count: m.access_count,
-                source_session_id: m.source_session_id.clone(),
-                source_channel: m.source_channel.clone(),
-                origin_device_id: m
-                    .origin_device_id
-                    .clone()
-                    .or_else(|| Some(self.device_id.clone())),
-                created_at: m.created_at.to_rfc3339(),
-                updated_at: m.updated_at.clone(),
-                deleted_at: m.deleted_at.clone(),
-            })
-            .collect();
+            .map(|m| self.to_payload(m))
+            .collect::<Result<Vec<_>>>()?;
 
         let input_json = serde_json::to_string(&payloads)
             .map_err(|e| Error::Database(format!("failed to serialize push payload: {e}")))?;
@@ -197,29 +281,36 @@ impl SyncClient {
         Ok(total)
     }
 
-    /// Pull changes from the cloud API
+    /// Pull all changes from the cloud API
     ///
-    /// Calls the API's `memoriesSince` query, upserts locally with LWW merge,
-    /// and marks pulled memories for re-embedding.
+    /// Calls the API's `memoriesSince` query from the beginning, upserts
+    /// locally using the configured merge strategy, and marks pulled memories
+    /// for re-embedding. The final cursor is stored for [`Self::delta_sync`].
     ///
     /// # Errors
     ///
     /// Returns error if API call or database operation fails
     pub async fn pull_changes(&self, db: &DbPool) -> Result<usize> {
+        match self.pull_since(db, None).await? {
+            PullOutcome::Pulled(count) => Ok(count),
+            PullOutcome::InvalidCursor => Err(Error::Database(
+                "sync API rejected the initial cursor".to_string(),
+            )),
+        }
+    }
+
+    /// Pull memories changed after `since` (or all, when `None`)
+    async fn pull_since(&self, db: &DbPool, since: Option<String>) -> Result<PullOutcome> {
         let repo = MemoryRepo::new(db.clone());
+        let state = SyncStateRepo::new(db.clone());
         let mut total_pulled = 0;
-        let mut cursor = String::new();
+        let mut conflicts = 0_usize;
+        let mut skipped = 0_usize;
+        let mut cursor = since.unwrap_or_else(|| SYNC_EPOCH.to_string());
 
         loop {
-            let since = if cursor.is_empty() {
-                // Use the most recent synced_at as the starting point
-                "1970-01-01T00:00:00Z".to_string()
-            } else {
-                cursor.clone()
-            };
-
             let query = format!(
-                r#"query {{ memoriesSince(since: "{since}", deviceId: "{}") {{ memories {{ id gatewayMemoryId category content contentHash tags pinned accessCount sourceSessionId sourceChannel originDeviceId createdAt updatedAt deletedAt }} cursor hasMore }} }}"#,
+                r#"query {{ memoriesSince(since: "{cursor}", deviceId: "{}") {{ memories {{ id gatewayMemoryId category content contentHash tags pinned accessCount sourceSessionId sourceChannel originDeviceId createdAt updatedAt deletedAt }} cursor hasMore }} }}"#,
                 self.device_id
             );
 
@@ -227,6 +318,9 @@ impl SyncClient {
             let parsed: PullMemoriesResponse = response.json().await?;
 
             if let Some(errors) = parsed.errors {
+                if errors.iter().any(GraphQlError::is_invalid_cursor) {
+                    return Ok(PullOutcome::InvalidCursor);
+                }
                 let msgs: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
                 return Err(Error::Database(format!(
                     "pull sync errors: {}",
@@ -240,12 +334,21 @@ impl SyncClient {
                 .memories_since;
 
             for remote in &result.memories {
-                let memory = remote_to_memory(remote, &self.device_id);
+                let mut memory = remote_to_memory(remote, &self.device_id);
+                if !self.open_pulled(&mut memory)? {
+                    skipped += 1;
+                    continue;
+                }
 
                 // Check if we already have this memory locally
                 if let Some(local) = repo.get_without_access_update(&memory.id)? {
-                    let merged = merge_memory(&local, &memory);
-                    repo.upsert_from_remote(&merged)?;
+                    let outcome = merge_memory(&local, &memory, self.merge_strategy);
+                    repo.upsert_from_remote(&outcome.memory)?;
+                    // Manual conflicts persist, local values standing, until resolved
+                    if self.merge_strategy == MergeStrategy::Manual {
+                        state.replace_conflicts(&local.id, &outcome.conflicts)?;
+                        conflicts += outcome.conflicts.len();
+                    }
                 } else {
                     repo.upsert_from_remote(&memory)?;
                 }
@@ -260,11 +363,26 @@ impl SyncClient {
             }
         }
 
+        // Only a fully drained pull advances the stored cursor
+        state.set_cursor(&self.cursor_key(), &cursor)?;
+
         if total_pulled > 0 {
             tracing::info!(count = total_pulled, "pulled memory changes from cloud");
         }
+        if skipped > 0 {
+            tracing::warn!(
+                count = skipped,
+                "skipped plaintext memories; their devices re-upload them once encryption is on"
+            );
+        }
+        if conflicts > 0 {
+            tracing::warn!(
+                count = conflicts,
+                "memory sync left conflicts for manual resolution"
+            );
+        }
 
-        Ok(total_pulled)
+        Ok(PullOutcome::Pulled(total_pulled))
     }
 
     /// Run a full sync: push local changes, then pull remote changes
@@ -283,6 +401,164 @@ impl SyncClient {
         Ok(())
     }
 
+    /// Run an incremental sync from the cursor of the last successful pull
+    ///
+    /// Pushes unsynced local changes, then pulls only memories changed since
+    /// the stored cursor. Falls back to [`Self::full_sync`] when no cursor is
+    /// stored or the server reports it is no longer valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if push or pull fails
+    pub async fn delta_sync(&self, db: &DbPool) -> Result<()> {
+        let state = SyncStateRepo::new(db.clone());
+        let Some(cursor) = state.cursor(&self.cursor_key())? else {
+            // Replace any plaintext copies on the relay with sealed ones
+            if self.cipher.is_some() {
+                let requeued = MemoryRepo::new(db.clone()).mark_all_unsynced()?;
+                if requeued > 0 {
+                    tracing::info!(count = requeued, "re-uploading memories encrypted");
+                }
+            }
+            return self.full_sync(db).await;
+        };
+
+        let pushed = self.push_changes(db).await?;
+        match self.pull_since(db, Some(cursor)).await? {
+            PullOutcome::Pulled(pulled) => {
+                if pushed > 0 || pulled > 0 {
+                    tracing::info!(pushed, pulled, "memory delta sync complete");
+                }
+                Ok(())
+            }
+            PullOutcome::InvalidCursor => {
+                tracing::warn!("sync cursor rejected by server, running full sync");
+                state.clear_cursor(&self.cursor_key())?;
+                self.full_sync(db).await
+            }
+        }
+    }
+
+    /// Build the upload payload for a memory, sealing it when encryption is on
+    fn to_payload(&self, memory: &Memory) -> Result<MemorySyncPayload> {
+        let mut payload = MemorySyncPayload {
+            gateway_memory_id: memory.id.clone(),
+            category: memory.category.to_string(),
+            content: memory.content.clone(),
+            content_hash: memory.content_hash.clone(),
+            tags: memory.tags.clone(),
+            pinned: memory.pinned,
+            access_count: memory.access_count,
+            source_session_id: memory.source_session_id.clone(),
+            source_channel: memory.source_channel.clone(),
+            origin_device_id: memory
+                .origin_device_id
+                .clone()
+                .or_else(|| Some(self.device_id.clone())),
+            created_at: memory.created_at.to_rfc3339(),
+            updated_at: memory.updated_at.clone(),
+            deleted_at: memory.deleted_at.clone(),
+        };
+
+        if let Some(cipher) = &self.cipher {
+            let fields = SealedFields {
+                content: std::mem::take(&mut payload.content),
+                tags: std::mem::take(&mut payload.tags),
+                source_session_id: payload.source_session_id.take(),
+                source_channel: payload.source_channel.take(),
+            };
+            payload.content = cipher.seal(&memory.id, &fields)?;
+            payload.content_hash = payload.content_hash.map(|h| cipher.blind_hash(&h));
+        }
+
+        Ok(payload)
+    }
+
+    /// Restore sealed fields of a pulled memory
+    ///
+    /// Returns `false` for plaintext left on the relay from before encryption
+    /// was enabled; such entries are skipped rather than trusted. Otherwise
+    /// fails closed: with encryption on, sealed bodies that do not decrypt are
+    /// rejected; with it off, sealed bodies are rejected rather than stored.
+    fn open_pulled(&self, memory: &mut Memory) -> Result<bool> {
+        let Some(cipher) = &self.cipher else {
+            if is_sealed(&memory.content) {
+                return Err(Error::Encryption(format!(
+                    "memory {} is encrypted but sync encryption is not configured",
+                    memory.id
+                )));
+            }
+            return Ok(true);
+        };
+        if !is_sealed(&memory.content) {
+            tracing::debug!(id = %memory.id, "skipping plaintext memory from relay");
+            return Ok(false);
+        }
+
+        let fields = cipher.open(&memory.id, &memory.content)?;
+        memory.content_hash = Some(Memory::compute_content_hash(&fields.content));
+        memory.content = fields.content;
+        memory.tags = fields.tags;
+        memory.source_session_id = fields.source_session_id;
+        memory.source_channel = fields.source_channel;
+        Ok(true)
+    }
+
+    /// Key under which the pull cursor is stored
+    fn cursor_key(&self) -> String {
+        if self.cipher.is_some() {
+            format!("{}{ENCRYPTED_CURSOR_SUFFIX}", self.api_url)
+        } else {
+            self.api_url.clone()
+        }
+    }
+
+    /// Fetch the account's passphrase salt, creating it if none is stored yet
+    async fn account_salt(&self) -> Result<Vec<u8>> {
+        use base64::Engine;
+
+        let stored = self
+            .sync_settings("query { syncSettings { encryptionSalt } }")
+            .await?;
+        let encoded = match stored {
+            Some(salt) => salt,
+            None => {
+                let salt = base64::engine::general_purpose::STANDARD.encode(generate_salt());
+                // The API keeps the first salt written, so racing devices agree
+                let mutation = format!(
+                    r#"mutation {{ syncSettings: initSyncSettings(encryptionSalt: "{salt}") {{ encryptionSalt }} }}"#
+                );
+                self.sync_settings(&mutation).await?.ok_or_else(|| {
+                    Error::Encryption("sync API did not store the encryption salt".to_string())
+                })?
+            }
+        };
+
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| Error::Encryption(format!("sync salt is malformed: {e}")))
+    }
+
+    /// Run a sync settings query or mutation, returning the stored salt
+    async fn sync_settings(&self, query: &str) -> Result<Option<String>> {
+        let response = self.graphql_request(query).await?;
+        let parsed: SyncSettingsResponse = response.json().await?;
+
+        if let Some(errors) = parsed.errors {
+            let msgs: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+            return Err(Error::Database(format!(
+                "sync settings errors: {}",
+                msgs.join(", ")
+            )));
+        }
+
+        Ok(parsed
+            .data
+            .ok_or_else(|| Error::Database("sync settings response missing data".to_string()))?
+            .sync_settings
+            .and_then(|settings| settings.encryption_salt))
+    }
+
     /// Send a GraphQL request to the API
     async fn graphql_request(&self, query: &str) -> Result<reqwest::Response> {
         let url = format!("{}/graphql", self.api_url);
@@ -317,6 +593,7 @@ fn remote_to_memory(remote: &RemoteMemory, _device_id: &str) -> Memory {
         "preference" => MemoryCategory::Preference,
         "fact" => MemoryCategory::Fact,
         "correction" => MemoryCategory::Correction,
+        "ephemeral" => MemoryCategory::Ephemeral,
         _ => MemoryCategory::General,
     };
 
@@ -353,5 +630,244 @@ fn remote_to_memory(remote: &RemoteMemory, _device_id: &str) -> Memory {
         deleted_at: remote.deleted_at.clone(),
         synced_at: None,
         cloud_id: Some(remote.id.clone()),
+        persona_id: None,
+        reinforcement_count: 0,
+        expires_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{Json, Router, routing::post};
+
+    use super::*;
+
+    /// Serve a sync API that only accepts pulls from the epoch
+    async fn serve() -> String {
+        let app = Router::new().route(
+            "/graphql",
+            post(|Json(body): Json<serde_json::Value>| async move {
+                let query = body["query"].as_str().unwrap_or_default();
+                if query.contains(SYNC_EPOCH) {
+                    Json(serde_json::json!({
+                        "data": {"memoriesSince": {"memories": [], "cursor": "c2", "hasMore": false}}
+                    }))
+                } else {
+                    Json(serde_json::json!({
+                        "errors": [{"message": "cursor expired", "extensions": {"code": "INVALID_CURSOR"}}]
+                    }))
+                }
+            }),
+        );
+        crate::test_support::serve(app).await
+    }
+
+    #[tokio::test]
+    async fn delta_sync_falls_back_to_full_sync_on_invalid_cursor() {
+        let api_url = serve().await;
+        let db = crate::db::init_memory().unwrap();
+        let state = SyncStateRepo::new(db.clone());
+        state.set_cursor(&api_url, "stale").unwrap();
+
+        let client = SyncClient::new(&api_url, "gw_test");
+        client.delta_sync(&db).await.unwrap();
+
+        assert_eq!(state.cursor(&api_url).unwrap().as_deref(), Some("c2"));
+    }
+
+    #[test]
+    fn encrypted_payload_round_trips_and_fails_closed() {
+        let cipher = MemoryCipher::from_key(&[3; 32]);
+        let client = SyncClient::new("http://localhost", "gw_test").with_cipher(cipher);
+        let mut memory = Memory::new(
+            "user_1".to_string(),
+            MemoryCategory::Fact,
+            "lives in Lisbon".to_string(),
+        );
+        memory.tags = vec!["home".to_string()];
+
+        // The relay sees only ciphertext and merge metadata
+        let payload = client.to_payload(&memory).unwrap();
+        assert!(is_sealed(&payload.content));
+        assert!(payload.tags.is_empty());
+        assert_ne!(payload.content_hash, memory.content_hash);
+
+        let mut pulled = memory.clone();
+        pulled.content = payload.content.clone();
+        pulled.tags.clear();
+        assert!(client.open_pulled(&mut pulled).unwrap());
+        assert_eq!(pulled.content, "lives in Lisbon");
+        assert_eq!(pulled.tags, vec!["home"]);
+        assert_eq!(pulled.content_hash, memory.content_hash);
+
+        // Legacy plaintext is skipped; undecryptable or unexpected bodies are rejected
+        let mut legacy = memory.clone();
+        legacy.content = "plaintext from the relay".to_string();
+        assert!(!client.open_pulled(&mut legacy).unwrap());
+
+        let mut foreign = memory.clone();
+        foreign.content = MemoryCipher::from_key(&[4; 32])
+            .seal(
+                &memory.id,
+                &SealedFields {
+                    content: "planted".to_string(),
+                    tags: Vec::new(),
+                    source_session_id: None,
+                    source_channel: None,
+                },
+            )
+            .unwrap();
+        assert!(client.open_pulled(&mut foreign).is_err());
+
+        let plain_client = SyncClient::new("http://localhost", "gw_test");
+        let mut sealed = memory;
+        sealed.content = payload.content;
+        assert!(plain_client.open_pulled(&mut sealed).is_err());
+    }
+
+    /// Serve a relay holding one plaintext memory, recording pushed bodies
+    async fn serve_legacy_relay(pushes: Arc<Mutex<Vec<String>>>) -> String {
+        let app = Router::new().route(
+            "/graphql",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let pushes = Arc::clone(&pushes);
+                async move {
+                    let query = body["query"].as_str().unwrap_or_default().to_string();
+                    if query.contains("pushMemories") {
+                        pushes.lock().unwrap().push(query);
+                        return Json(serde_json::json!({
+                            "data": {"pushMemories": {"pushed": 1, "merged": 0}}
+                        }));
+                    }
+                    Json(serde_json::json!({
+                        "data": {"memoriesSince": {
+                            "memories": [{
+                                "id": "cloud_1",
+                                "gatewayMemoryId": "mem_legacy",
+                                "category": "fact",
+                                "content": "uploaded before encryption",
+                                "contentHash": null,
+                                "tags": [],
+                                "pinned": false,
+                                "accessCount": 0,
+                                "sourceSessionId": null,
+                                "sourceChannel": null,
+                                "originDeviceId": "gw_other",
+                                "createdAt": "2024-01-01T00:00:00Z",
+                                "updatedAt": "2024-01-01T00:00:00Z",
+                                "deletedAt": null
+                            }],
+                            "cursor": "c1",
+                            "hasMore": false
+                        }}
+                    }))
+                }
+            }),
+        );
+        crate::test_support::serve(app).await
+    }
+
+    #[tokio::test]
+    async fn enabling_encryption_reuploads_and_skips_legacy_plaintext() {
+        let pushes = Arc::new(Mutex::new(Vec::new()));
+        let api_url = serve_legacy_relay(Arc::clone(&pushes)).await;
+        let db = crate::db::init_memory().unwrap();
+
+        // A memory already uploaded in plaintext, with a plaintext-era cursor
+        let user = crate::db::UserRepo::new(db.clone())
+            .find_or_create("sync_user")
+            .unwrap();
+        let repo = MemoryRepo::new(db.clone());
+        let memory = Memory::new(user.id, MemoryCategory::Fact, "lives in Lisbon".to_string());
+        repo.add(&memory).unwrap();
+        repo.mark_synced(&[memory.id.as_str()]).unwrap();
+        let state = SyncStateRepo::new(db.clone());
+        state.set_cursor(&api_url, "plaintext-era").unwrap();
+
+        let client =
+            SyncClient::new(&api_url, "gw_test").with_cipher(MemoryCipher::from_key(&[3; 32]));
+        client.delta_sync(&db).await.unwrap();
+
+        // The existing memory went back up sealed
+        let pushes = pushes.lock().unwrap();
+        assert_eq!(pushes.len(), 1);
+        assert!(pushes[0].contains(&memory.id));
+        assert!(!pushes[0].contains("Lisbon"));
+
+        // Legacy plaintext was skipped and the encrypted cursor still advanced
+        assert!(
+            repo.get_without_access_update("mem_legacy")
+                .unwrap()
+                .is_none()
+        );
+        let key = format!("{api_url}{ENCRYPTED_CURSOR_SUFFIX}");
+        assert_eq!(state.cursor(&key).unwrap().as_deref(), Some("c1"));
+        assert!(repo.unsynced().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn passphrase_salt_is_created_once_per_account() {
+        let salt: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+        let stored = Arc::clone(&salt);
+        let app = Router::new().route(
+            "/graphql",
+            post(move |Json(body): Json<serde_json::Value>| {
+                let stored = Arc::clone(&stored);
+                async move {
+                    let query = body["query"].as_str().unwrap_or_default();
+                    let mut stored = stored.lock().unwrap();
+                    if let Some(rest) = query.split("encryptionSalt: \"").nth(1)
+                        && stored.is_none()
+                    {
+                        *stored = rest.split('"').next().map(String::from);
+                    }
+                    Json(serde_json::json!({
+                        "data": {"syncSettings": {"encryptionSalt": *stored}}
+                    }))
+                }
+            }),
+        );
+        let api_url = crate::test_support::serve(app).await;
+
+        let encryption = SyncEncryption::Passphrase("correct horse".to_string());
+        let first = SyncClient::new(&api_url, "gw_a")
+            .with_encryption(&encryption)
+            .await
+            .unwrap();
+        let stored_salt = salt.lock().unwrap().clone().unwrap();
+        let second = SyncClient::new(&api_url, "gw_b")
+            .with_encryption(&encryption)
+            .await
+            .unwrap();
+
+        // Both devices derive the same key from the one stored salt
+        assert_eq!(salt.lock().unwrap().as_deref(), Some(stored_salt.as_str()));
+        let memory = Memory::new(
+            "user_1".to_string(),
+            MemoryCategory::Fact,
+            "lives in Lisbon".to_string(),
+        );
+        let mut pulled = memory.clone();
+        pulled.content = first.to_payload(&memory).unwrap().content;
+        assert!(second.open_pulled(&mut pulled).unwrap());
+        assert_eq!(pulled.content, "lives in Lisbon");
+    }
+
+    #[test]
+    fn invalid_cursor_detected_by_code_or_message() {
+        let by_code: GraphQlError = serde_json::from_value(
+            serde_json::json!({"message": "nope", "extensions": {"code": "INVALID_CURSOR"}}),
+        )
+        .unwrap();
+        let by_message: GraphQlError =
+            serde_json::from_value(serde_json::json!({"message": "Invalid cursor"})).unwrap();
+        let other: GraphQlError =
+            serde_json::from_value(serde_json::json!({"message": "unauthorized"})).unwrap();
+
+        assert!(by_code.is_invalid_cursor());
+        assert!(by_message.is_invalid_cursor());
+        assert!(!other.is_invalid_cursor());
     }
 }
@@ -0,0 +1,283 @@
+//! Client-side encryption of synced memories
+//!
+//! When enabled, the personal fields of a memory (content, tags, and where it
+//! was learned) are sealed with XChaCha20-Poly1305 before upload, so the cloud
+//! relay only stores ciphertext plus the metadata it needs to merge. The
+//! memory ID is bound as associated data, so a sealed body cannot be moved to
+//! another memory.
+//!
+//! Keys come from a passphrase (Argon2id with a random per-account salt kept
+//! in the account's sync settings), shared by every device of a user, or from
+//! this device's identity key, which suits single-device backup only.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::security::DeviceIdentity;
+use crate::{Error, Result};
+
+/// Prefix marking a sealed memory body
+pub const SEALED_PREFIX: &str = "beacon-e2e:v1:";
+
+/// Length of the per-account passphrase salt in bytes
+pub const SALT_LEN: usize = 16;
+
+/// Context for deriving the key from the device identity
+const DEVICE_KEY_CONTEXT: &str = "omni.beacon.memory-sync.v1";
+
+/// XChaCha20 nonce length in bytes
+const NONCE_LEN: usize = 24;
+
+/// Where the sync encryption key comes from
+#[derive(Clone, PartialEq, Eq)]
+pub enum SyncEncryption {
+    /// Derive the key from a passphrase shared by the user's devices
+    Passphrase(String),
+    /// Derive the key from this device's identity key
+    DeviceKey,
+}
+
+impl std::fmt::Debug for SyncEncryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passphrase(_) => f.write_str("Passphrase(<redacted>)"),
+            Self::DeviceKey => f.write_str("DeviceKey"),
+        }
+    }
+}
+
+/// Fields of a memory that are sealed before upload
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedFields {
+    pub content: String,
+    pub tags: Vec<String>,
+    pub source_session_id: Option<String>,
+    pub source_channel: Option<String>,
+}
+
+/// Whether a memory body was sealed by [`MemoryCipher`]
+#[must_use]
+pub fn is_sealed(body: &str) -> bool {
+    body.starts_with(SEALED_PREFIX)
+}
+
+/// Generate a fresh passphrase salt for an account
+#[must_use]
+pub fn generate_salt() -> [u8; SALT_LEN] {
+    rand::random()
+}
+
+/// Seals and opens memory bodies with a symmetric key
+#[derive(Clone)]
+pub struct MemoryCipher {
+    cipher: XChaCha20Poly1305,
+    blind_key: [u8; 32],
+}
+
+impl std::fmt::Debug for MemoryCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryCipher").finish_non_exhaustive()
+    }
+}
+
+impl MemoryCipher {
+    /// Create a cipher from a raw 256-bit key
+    #[must_use]
+    pub fn from_key(key: &[u8; 32]) -> Self {
+        let blind_key: [u8; 32] = Sha256::new()
+            .chain_update(b"blind")
+            .chain_update(key)
+            .finalize()
+            .into();
+        Self {
+            cipher: XChaCha20Poly1305::new(key.into()),
+            blind_key,
+        }
+    }
+
+    /// Derive the key from a passphrase and the account's salt with Argon2id
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Encryption` if the passphrase is empty, the salt is
+    /// shorter than [`SALT_LEN`], or derivation fails
+    pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(Error::Encryption("sync passphrase is empty".to_string()));
+        }
+        if salt.len() < SALT_LEN {
+            return Err(Error::Encryption("sync salt is too short".to_string()));
+        }
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| Error::Encryption(format!("key derivation failed: {e}")))?;
+        Ok(Self::from_key(&key))
+    }
+
+    /// Derive the key from a device identity
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the identity has no secret key
+    pub fn from_device(identity: &DeviceIdentity) -> Result<Self> {
+        Ok(Self::from_key(&identity.derive_key(DEVICE_KEY_CONTEXT)?))
+    }
+
+    /// Derive the key from this gateway's stored device identity
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the identity cannot be loaded or created
+    pub fn from_local_device() -> Result<Self> {
+        let identity =
+            DeviceIdentity::load_or_create(&DeviceIdentity::default_path(), "beacon-gateway")?;
+        Self::from_device(&identity)
+    }
+
+    /// Seal a memory's fields, binding them to its ID
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Encryption` if encryption fails
+    pub fn seal(&self, memory_id: &str, fields: &SealedFields) -> Result<String> {
+        use base64::Engine;
+
+        let plaintext = serde_json::to_vec(fields)?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: memory_id.as_bytes(),
+                },
+            )
+            .map_err(|_| Error::Encryption("failed to seal memory".to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(format!(
+            "{SEALED_PREFIX}{}",
+            base64::engine::general_purpose::STANDARD.encode(sealed)
+        ))
+    }
+
+    /// Open a sealed body for the memory it was sealed for
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Encryption` if the body is not sealed, was sealed with
+    /// another key or for another memory, or has been tampered with
+    pub fn open(&self, memory_id: &str, body: &str) -> Result<SealedFields> {
+        use base64::Engine;
+
+        let encoded = body
+            .strip_prefix(SEALED_PREFIX)
+            .ok_or_else(|| Error::Encryption(format!("memory {memory_id} is not encrypted")))?;
+        let sealed = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| Error::Encryption(format!("memory {memory_id} is malformed: {e}")))?;
+        if sealed.len() < NONCE_LEN {
+            return Err(Error::Encryption(format!(
+                "memory {memory_id} is truncated"
+            )));
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: memory_id.as_bytes(),
+                },
+            )
+            .map_err(|_| Error::Encryption(format!("failed to decrypt memory {memory_id}")))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Keyed hash of a content hash
+    ///
+    /// Devices sharing the key produce the same value, so the relay can still
+    /// deduplicate without learning the plaintext hash.
+    #[must_use]
+    pub fn blind_hash(&self, content_hash: &str) -> String {
+        hex::encode(
+            Sha256::new()
+                .chain_update(self.blind_key)
+                .chain_update(content_hash.as_bytes())
+                .finalize(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> SealedFields {
+        SealedFields {
+            content: "allergic to peanuts".to_string(),
+            tags: vec!["health".to_string()],
+            source_session_id: Some("sess_1".to_string()),
+            source_channel: Some("telegram".to_string()),
+        }
+    }
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let cipher = MemoryCipher::from_key(&[7; 32]);
+        let sealed = cipher.seal("mem_1", &fields()).unwrap();
+
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("peanuts"));
+        assert_eq!(cipher.open("mem_1", &sealed).unwrap(), fields());
+    }
+
+    #[test]
+    fn open_fails_closed() {
+        let cipher = MemoryCipher::from_key(&[7; 32]);
+        let sealed = cipher.seal("mem_1", &fields()).unwrap();
+
+        // Wrong key, wrong memory, plaintext, and tampered bodies are rejected
+        assert!(
+            MemoryCipher::from_key(&[8; 32])
+                .open("mem_1", &sealed)
+                .is_err()
+        );
+        assert!(cipher.open("mem_2", &sealed).is_err());
+        assert!(cipher.open("mem_1", "allergic to peanuts").is_err());
+        let mut tampered = sealed;
+        tampered.insert(SEALED_PREFIX.len() + 40, 'A');
+        assert!(cipher.open("mem_1", &tampered).is_err());
+    }
+
+    #[test]
+    fn passphrase_keys_match_across_devices() {
+        let salt = generate_salt();
+        let a = MemoryCipher::from_passphrase("correct horse", &salt).unwrap();
+        let b = MemoryCipher::from_passphrase("correct horse", &salt).unwrap();
+
+        let sealed = a.seal("mem_1", &fields()).unwrap();
+        assert_eq!(b.open("mem_1", &sealed).unwrap(), fields());
+        assert_eq!(a.blind_hash("abc"), b.blind_hash("abc"));
+        assert!(MemoryCipher::from_passphrase("", &salt).is_err());
+        assert!(MemoryCipher::from_passphrase("correct horse", b"short").is_err());
+    }
+
+    #[test]
+    fn passphrase_keys_differ_across_accounts() {
+        let a = MemoryCipher::from_passphrase("correct horse", &generate_salt()).unwrap();
+        let b = MemoryCipher::from_passphrase("correct horse", &generate_salt()).unwrap();
+
+        let sealed = a.seal("mem_1", &fields()).unwrap();
+        assert!(b.open("mem_1", &sealed).is_err());
+        assert_ne!(a.blind_hash("abc"), b.blind_hash("abc"));
+    }
+}
@@ -1,15 +1,99 @@
-//! Last-writer-wins merge logic for memory sync
+//! Conflict resolution for memory sync
+//!
+//! Two copies of a memory are merged field by field according to a
+//! [`MergeStrategy`]. Whatever the strategy, a soft-delete only loses to an
+//! edit made after it, so a stale copy from another device cannot resurrect a
+//! deleted memory.
 
+use std::cmp::Ordering;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+
+use crate::Error;
 use crate::db::Memory;
 
-/// Merge a local memory with a remote memory using LWW semantics
-///
-/// - Fields use last-writer-wins by `updated_at`
-/// - `access_count` uses `max()` to avoid inflation
-/// - Soft-deletes propagate (if either side is deleted, the result is deleted)
+/// How conflicting edits to the same memory are resolved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// The copy with the later `updated_at` wins
+    #[default]
+    LastWriteWins,
+    /// The local copy always wins
+    PreferLocal,
+    /// Last-writer-wins, but tags are unioned and pinning is kept
+    Union,
+    /// Differing fields keep the local value and are reported as conflicts
+    Manual,
+}
+
+impl std::str::FromStr for MergeStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "last_write_wins" | "lww" => Ok(Self::LastWriteWins),
+            "prefer_local" | "local" => Ok(Self::PreferLocal),
+            "union" => Ok(Self::Union),
+            "manual" => Ok(Self::Manual),
+            other => Err(Error::Config(format!(
+                "unknown BEACON_SYNC_MERGE_STRATEGY: {other} (expected `last-write-wins`, `prefer-local`, `union` or `manual`)"
+            ))),
+        }
+    }
+}
+
+/// A field whose local and remote values disagree under [`MergeStrategy::Manual`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergeConflict {
+    pub memory_id: String,
+    pub field: String,
+    pub local: String,
+    pub remote: String,
+}
+
+/// Result of merging two copies of a memory
+#[derive(Debug, Clone)]
 #[must_use]
-pub fn merge_memory(local: &Memory, remote: &Memory) -> Memory {
-    let local_wins = local.updated_at >= remote.updated_at;
+pub struct MergeOutcome {
+    pub memory: Memory,
+    /// Fields left unresolved (only populated by [`MergeStrategy::Manual`])
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Parse a stored timestamp (RFC 3339 or `SQLite` `datetime('now')`)
+fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+                .ok()
+                .map(|t| t.and_utc())
+        })
+}
+
+/// Compare two stored timestamps, falling back to string order if unparseable
+fn compare_timestamps(a: &str, b: &str) -> Ordering {
+    match (parse_timestamp(a), parse_timestamp(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Merge a local memory with a remote memory
+///
+/// - Content fields are resolved by `strategy`
+/// - `access_count` and `reinforcement_count` use `max()` to avoid inflation
+/// - `created_at` keeps the earliest value and `cloud_id` whichever side has one
+/// - A soft-delete stands unless the other copy was edited after it
+pub fn merge_memory(local: &Memory, remote: &Memory, strategy: MergeStrategy) -> MergeOutcome {
+    let local_wins = match strategy {
+        MergeStrategy::PreferLocal | MergeStrategy::Manual => true,
+        MergeStrategy::LastWriteWins | MergeStrategy::Union => {
+            compare_timestamps(&local.updated_at, &remote.updated_at) != Ordering::Less
+        }
+    };
 
     let (winner, loser) = if local_wins {
         (local, remote)
@@ -19,15 +103,32 @@ pub fn merge_memory(local: &Memory, remote: &Memory) -> Memory {
 
     let mut merged = winner.clone();
 
-    // access_count: take the max to avoid inflation from summing
+    if strategy == MergeStrategy::Union {
+        for tag in &loser.tags {
+            if !merged.tags.contains(tag) {
+                merged.tags.push(tag.clone());
+            }
+        }
+        merged.pinned = local.pinned || remote.pinned;
+    }
+    let conflicts = if strategy == MergeStrategy::Manual {
+        field_conflicts(local, remote)
+    } else {
+        Vec::new()
+    };
+
+    // Counters: take the max to avoid inflation from summing
     merged.access_count = std::cmp::max(local.access_count, remote.access_count);
+    merged.reinforcement_count =
+        std::cmp::max(local.reinforcement_count, remote.reinforcement_count);
 
-    // Propagate soft-deletes: if either side has deleted_at, use the earliest
-    merged.deleted_at = match (&local.deleted_at, &remote.deleted_at) {
-        (Some(l), Some(r)) => Some(std::cmp::min(l.clone(), r.clone())),
-        (Some(d), None) | (None, Some(d)) => Some(d.clone()),
-        (None, None) => None,
-    };
+    merged.deleted_at = merge_tombstones(local, remote);
+    if merged.deleted_at.is_some()
+        && compare_timestamps(&loser.updated_at, &merged.updated_at) == Ordering::Greater
+    {
+        // Keep the tombstone's modification time so it propagates onward
+        merged.updated_at.clone_from(&loser.updated_at);
+    }
 
     // Preserve the original created_at (earliest)
     if loser.created_at < winner.created_at {
@@ -39,7 +140,68 @@ pub fn merge_memory(local: &Memory, remote: &Memory) -> Memory {
         merged.cloud_id.clone_from(&loser.cloud_id);
     }
 
-    merged
+    MergeOutcome {
+        memory: merged,
+        conflicts,
+    }
+}
+
+/// Resolve soft-deletes by comparing them against the other copy's edits
+///
+/// The earliest tombstone is kept, unless the live copy was updated after it
+/// (the memory was deliberately edited again on another device).
+fn merge_tombstones(local: &Memory, remote: &Memory) -> Option<String> {
+    match (&local.deleted_at, &remote.deleted_at) {
+        (Some(l), Some(r)) => Some(std::cmp::min_by(l, r, |a, b| compare_timestamps(a, b)).clone()),
+        (Some(deleted), None) => (compare_timestamps(&remote.updated_at, deleted)
+            != Ordering::Greater)
+            .then(|| deleted.clone()),
+        (None, Some(deleted)) => (compare_timestamps(&local.updated_at, deleted)
+            != Ordering::Greater)
+            .then(|| deleted.clone()),
+        (None, None) => None,
+    }
+}
+
+/// Content fields that differ between the two copies
+fn field_conflicts(local: &Memory, remote: &Memory) -> Vec<MergeConflict> {
+    let conflict = |field: &str, local_value: String, remote_value: String| MergeConflict {
+        memory_id: local.id.clone(),
+        field: field.to_string(),
+        local: local_value,
+        remote: remote_value,
+    };
+
+    let mut conflicts = Vec::new();
+    if local.content != remote.content {
+        conflicts.push(conflict(
+            "content",
+            local.content.clone(),
+            remote.content.clone(),
+        ));
+    }
+    if local.category != remote.category {
+        conflicts.push(conflict(
+            "category",
+            local.category.as_str().to_string(),
+            remote.category.as_str().to_string(),
+        ));
+    }
+    if local.tags != remote.tags {
+        conflicts.push(conflict(
+            "tags",
+            local.tags.join(","),
+            remote.tags.join(","),
+        ));
+    }
+    if local.pinned != remote.pinned {
+        conflicts.push(conflict(
+            "pinned",
+            local.pinned.to_string(),
+            remote.pinned.to_string(),
+        ));
+    }
+    conflicts
 }
 
 #[cfg(test)]
@@ -70,6 +232,9 @@ mod tests {
             deleted_at: None,
             synced_at: None,
             cloud_id: None,
+            persona_id: None,
+            reinforcement_count: 0,
+            expires_at: None,
         }
     }
 
@@ -78,7 +243,7 @@ mod tests {
         let local = make_memory("old content", "2025-01-01T00:00:00Z");
         let remote = make_memory("new content", "2025-01-02T00:00:00Z");
 
-        let merged = merge_memory(&local, &remote);
+        let merged = merge_memory(&local, &remote, MergeStrategy::LastWriteWins).memory;
         assert_eq!(merged.content, "new content");
         assert_eq!(merged.updated_at, "2025-01-02T00:00:00Z");
     }
@@ -88,7 +253,7 @@ mod tests {
         let local = make_memory("newer content", "2025-01-03T00:00:00Z");
         let remote = make_memory("older content", "2025-01-01T00:00:00Z");
 
-        let merged = merge_memory(&local, &remote);
+        let merged = merge_memory(&local, &remote, MergeStrategy::LastWriteWins).memory;
         assert_eq!(merged.content, "newer content");
     }
 
@@ -100,7 +265,7 @@ mod tests {
         let mut remote = make_memory("content", "2025-01-02T00:00:00Z");
         remote.access_count = 3;
 
-        let merged = merge_memory(&local, &remote);
+        let merged = merge_memory(&local, &remote, MergeStrategy::LastWriteWins).memory;
         assert_eq!(merged.access_count, 5);
     }
 
@@ -110,7 +275,7 @@ mod tests {
         let mut remote = make_memory("content", "2025-01-02T00:00:00Z");
         remote.deleted_at = Some("2025-01-02T12:00:00Z".to_string());
 
-        let merged = merge_memory(&local, &remote);
+        let merged = merge_memory(&local, &remote, MergeStrategy::LastWriteWins).memory;
         assert!(merged.deleted_at.is_some());
     }
 
@@ -121,7 +286,100 @@ mod tests {
 
         let remote = make_memory("content", "2025-01-02T00:00:00Z");
 
-        let merged = merge_memory(&local, &remote);
+        let merged = merge_memory(&local, &remote, MergeStrategy::LastWriteWins).memory;
         assert_eq!(merged.cloud_id.as_deref(), Some("cloud_123"));
     }
+
+    /// Same memory edited on two devices, remote edit later
+    fn overlapping_edits() -> (Memory, Memory) {
+        let mut local = make_memory("likes tea", "2025-01-01T10:00:00Z");
+        local.tags = vec!["drinks".to_string()];
+        let mut remote = make_memory("likes coffee", "2025-01-01T11:00:00Z");
+        remote.id.clone_from(&local.id);
+        remote.tags = vec!["morning".to_string()];
+        remote.pinned = true;
+        (local, remote)
+    }
+
+    #[test]
+    fn test_strategy_last_write_wins() {
+        let (local, remote) = overlapping_edits();
+        let outcome = merge_memory(&local, &remote, MergeStrategy::LastWriteWins);
+        assert_eq!(outcome.memory.content, "likes coffee");
+        assert_eq!(outcome.memory.tags, vec!["morning"]);
+        assert!(outcome.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_strategy_prefer_local() {
+        let (local, remote) = overlapping_edits();
+        let outcome = merge_memory(&local, &remote, MergeStrategy::PreferLocal);
+        assert_eq!(outcome.memory.content, "likes tea");
+        assert!(!outcome.memory.pinned);
+        assert!(outcome.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_strategy_union() {
+        let (local, remote) = overlapping_edits();
+        let outcome = merge_memory(&local, &remote, MergeStrategy::Union);
+        assert_eq!(outcome.memory.content, "likes coffee");
+        assert_eq!(outcome.memory.tags, vec!["morning", "drinks"]);
+        assert!(outcome.memory.pinned);
+    }
+
+    #[test]
+    fn test_strategy_manual_reports_conflicts() {
+        let (local, remote) = overlapping_edits();
+        let outcome = merge_memory(&local, &remote, MergeStrategy::Manual);
+
+        // Local values are kept until the conflict is resolved
+        assert_eq!(outcome.memory.content, "likes tea");
+        let fields: Vec<&str> = outcome.conflicts.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["content", "tags", "pinned"]);
+        assert_eq!(outcome.conflicts[0].remote, "likes coffee");
+
+        let same = merge_memory(&local, &local.clone(), MergeStrategy::Manual);
+        assert!(same.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_stale_copy_does_not_resurrect_deletion() {
+        // Deleted remotely after the local copy was last edited
+        let local = make_memory("content", "2025-01-03T00:00:00Z");
+        let mut remote = make_memory("content", "2025-01-04 00:00:00");
+        remote.deleted_at = Some("2025-01-04 00:00:00".to_string());
+
+        for strategy in [
+            MergeStrategy::LastWriteWins,
+            MergeStrategy::PreferLocal,
+            MergeStrategy::Union,
+            MergeStrategy::Manual,
+        ] {
+            let merged = merge_memory(&local, &remote, strategy).memory;
+            assert!(merged.deleted_at.is_some(), "{strategy:?} resurrected");
+        }
+    }
+
+    #[test]
+    fn test_edit_after_deletion_restores() {
+        let mut local = make_memory("content", "2025-01-02T00:00:00Z");
+        local.deleted_at = Some("2025-01-02T00:00:00Z".to_string());
+        let remote = make_memory("edited again", "2025-01-05T00:00:00Z");
+
+        let merged = merge_memory(&local, &remote, MergeStrategy::LastWriteWins).memory;
+        assert!(merged.deleted_at.is_none());
+        assert_eq!(merged.content, "edited again");
+    }
+
+    #[test]
+    fn test_strategy_from_str() {
+        let parse = |s: &str| s.parse::<MergeStrategy>().ok();
+        assert_eq!(parse("prefer-local"), Some(MergeStrategy::PreferLocal));
+        assert_eq!(parse("UNION"), Some(MergeStrategy::Union));
+        assert_eq!(parse("manual"), Some(MergeStrategy::Manual));
+        assert_eq!(parse("lww"), Some(MergeStrategy::LastWriteWins));
+        assert_eq!(parse("last-write-wins"), Some(MergeStrategy::LastWriteWins));
+        assert_eq!(parse("newest"), None);
+    }
 }
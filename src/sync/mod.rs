@@ -5,7 +5,9 @@
 //! Gateways push deltas up and pull deltas down
 
 pub mod client;
+pub mod crypto;
 pub mod merge;
 
 pub use client::SyncClient;
-pub use merge::merge_memory;
+pub use crypto::{MemoryCipher, SyncEncryption};
+pub use merge::{MergeConflict, MergeOutcome, MergeStrategy, merge_memory};
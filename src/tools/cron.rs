@@ -7,8 +7,9 @@ use serde::{Deserialize, Serialize};
 
 use agent_core::tools::{ToolKind, ToolProvider};
 
+use super::reminder::{REMIND_ACTION, ReminderCommand, ReminderOrigin, ReminderPayload};
 use crate::Result;
-use crate::integrations::{ScheduleRequest, VortexClient};
+use crate::integrations::{ScheduleRequest, VortexClient, VortexError};
 
 /// Tools for managing scheduled tasks via Vortex
 #[derive(Debug, Clone)]
@@ -75,8 +76,7 @@ impl CronTools {
             timezone: None,
         };
 
-        let schedule = self.vortex.schedule(&request).await?;
-        Ok(schedule.id)
+        self.create(&request).await
     }
 
     /// Schedule a recurring task with additional options
@@ -102,8 +102,41 @@ impl CronTools {
             timezone: params.timezone,
         };
 
-        let schedule = self.vortex.schedule(&request).await?;
-        Ok(schedule.id)
+        self.create(&request).await
+    }
+
+    /// Create a schedule unless an identical one already exists
+    ///
+    /// Reconciles against the existing schedules first, so a retried tool
+    /// call reuses the earlier schedule instead of adding a duplicate.
+    async fn create(&self, request: &ScheduleRequest) -> Result<String> {
+        match self.vortex.list_schedules().await {
+            Ok(existing) => {
+                if let Some(schedule) = existing.into_iter().find(|s| request.matches(s)) {
+                    tracing::debug!(id = %schedule.id, "reusing existing Vortex schedule");
+                    return Ok(schedule.id);
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "failed to list Vortex schedules"),
+        }
+
+        match self.vortex.schedule(request).await {
+            Ok(schedule) => Ok(schedule.id),
+            Err(VortexError::AlreadyExists { id: Some(id) }) => Ok(id),
+            Err(VortexError::AlreadyExists { id: None }) => self
+                .vortex
+                .list_schedules()
+                .await?
+                .into_iter()
+                .find(|s| request.matches(s))
+                .map(|s| s.id)
+                .ok_or_else(|| {
+                    crate::Error::Tool(
+                        "Vortex reported a duplicate schedule it did not list".to_string(),
+                    )
+                }),
+            Err(e) => Err(e.into()),
+        }
     }
 
     /// List all scheduled tasks
@@ -139,7 +172,7 @@ impl CronTools {
     ///
     /// Returns an error if the Vortex API call fails
     pub async fn cancel(&self, schedule_id: &str) -> Result<()> {
-        self.vortex.cancel_schedule(schedule_id).await
+        Ok(self.vortex.delete_schedule(schedule_id).await?)
     }
 
     /// Get details of a specific schedule
@@ -163,6 +196,91 @@ impl CronTools {
             active: schedule.active,
         })
     }
+
+    /// Create a reminder that is delivered back to its originating channel
+    ///
+    /// # Returns
+    ///
+    /// The schedule ID on success
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Vortex API call fails
+    pub async fn create_reminder(
+        &self,
+        cron: &str,
+        payload: &ReminderPayload,
+        timezone: Option<String>,
+    ) -> Result<String> {
+        let payload_json = serde_json::to_value(payload)
+            .map_err(|e| crate::Error::Tool(format!("invalid reminder payload: {e}")))?;
+
+        self.schedule_with_options(ScheduleParams {
+            cron: cron.to_string(),
+            action: REMIND_ACTION.to_string(),
+            payload: payload_json,
+            description: Some(payload.message.clone()),
+            timezone,
+        })
+        .await
+    }
+
+    /// List reminders owned by the requester in `origin`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Vortex API call fails
+    pub async fn list_reminders(&self, origin: &ReminderOrigin) -> Result<Vec<ScheduleInfo>> {
+        let schedules = self.vortex.list_schedules().await?;
+
+        let infos = schedules
+            .into_iter()
+            .filter(|s| s.action == REMIND_ACTION)
+            .filter(|s| {
+                serde_json::from_value::<ReminderPayload>(s.payload.clone())
+                    .is_ok_and(|p| p.is_owned_by(origin))
+            })
+            .map(|s| ScheduleInfo {
+                id: s.id,
+                cron: s.cron,
+                action: s.action,
+                next_run: s.next_run.map(|dt| dt.to_rfc3339()),
+                description: s.description,
+                active: s.active,
+            })
+            .collect();
+
+        Ok(infos)
+    }
+
+    /// Cancel a reminder if it belongs to the requester in `origin`
+    ///
+    /// Returns `false` when the schedule is not a reminder owned by that user
+    /// on that channel, so users cannot cancel each other's schedules by
+    /// guessing IDs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Vortex API call fails
+    pub async fn cancel_reminder(
+        &self,
+        origin: &ReminderOrigin,
+        schedule_id: &str,
+    ) -> Result<bool> {
+        let Ok(schedule) = self.vortex.get_schedule(schedule_id).await else {
+            return Ok(false);
+        };
+
+        let owned = schedule.action == REMIND_ACTION
+            && serde_json::from_value::<ReminderPayload>(schedule.payload)
+                .is_ok_and(|p| p.is_owned_by(origin));
+        if !owned {
+            return Ok(false);
+        }
+
+        self.vortex.delete_schedule(schedule_id).await?;
+        Ok(true)
+    }
 }
 
 /// Parameters for scheduling a task with full options
@@ -216,6 +334,17 @@ impl BuiltinCronTools {
         Self { cron }
     }
 
+    /// Underlying cron tools, used for slash commands that bypass the agent
+    #[must_use]
+    pub const fn cron(&self) -> &CronTools {
+        &self.cron
+    }
+
+    /// Run a reminder slash command for the given origin
+    pub async fn run_command(&self, command: ReminderCommand, origin: &ReminderOrigin) -> String {
+        command.run(&self.cron, origin).await
+    }
+
     /// Return tool definitions for all cron tools
     #[must_use]
     pub fn tool_definitions() -> Vec<synapse_client::ToolDefinition> {
@@ -297,6 +426,111 @@ impl BuiltinCronTools {
         ]
     }
 
+    /// Return agent-core definitions for the reminder tools
+    ///
+    /// Reminder tools are only offered when the executor knows the
+    /// conversation origin, since delivery is routed back to it.
+    #[must_use]
+    pub fn reminder_definitions() -> Vec<agent_core::types::Tool> {
+        vec![
+            agent_core::types::Tool {
+                name: "reminder_create".to_string(),
+                description: "Create a recurring reminder for the current user. It is delivered back to this conversation.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "cron": {
+                            "type": "string",
+                            "description": "Cron expression (e.g., '0 9 * * MON' for 9 AM every Monday)"
+                        },
+                        "message": {
+                            "type": "string",
+                            "description": "Reminder text to deliver"
+                        },
+                        "timezone": {
+                            "type": "string",
+                            "description": "IANA timezone (e.g., 'America/New_York'). Defaults to UTC"
+                        }
+                    },
+                    "required": ["cron", "message"]
+                }),
+            },
+            agent_core::types::Tool {
+                name: "reminder_list".to_string(),
+                description: "List the current user's reminders.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {}
+                }),
+            },
+            agent_core::types::Tool {
+                name: "reminder_cancel".to_string(),
+                description: "Cancel one of the current user's reminders by its ID.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "reminder_id": {
+                            "type": "string",
+                            "description": "ID of the reminder to cancel"
+                        }
+                    },
+                    "required": ["reminder_id"]
+                }),
+            },
+        ]
+    }
+
+    /// Execute a named reminder tool on behalf of `origin`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if arguments are malformed or the Vortex API call fails
+    pub async fn execute_reminder(
+        &self,
+        name: &str,
+        arguments: &str,
+        origin: &ReminderOrigin,
+    ) -> crate::Result<String> {
+        match name {
+            "reminder_create" => {
+                #[derive(serde::Deserialize)]
+                struct CreateArgs {
+                    cron: String,
+                    message: String,
+                    timezone: Option<String>,
+                }
+                let args: CreateArgs = serde_json::from_str(arguments).map_err(|e| {
+                    crate::Error::Tool(format!("reminder_create: invalid arguments: {e}"))
+                })?;
+                let id = self
+                    .cron
+                    .create_reminder(&args.cron, &origin.payload(args.message), args.timezone)
+                    .await?;
+                Ok(serde_json::json!({ "status": "scheduled", "id": id }).to_string())
+            }
+            "reminder_list" => {
+                let reminders = self.cron.list_reminders(origin).await?;
+                Ok(serde_json::json!({ "reminders": reminders }).to_string())
+            }
+            "reminder_cancel" => {
+                #[derive(serde::Deserialize)]
+                struct CancelArgs {
+                    reminder_id: String,
+                }
+                let args: CancelArgs = serde_json::from_str(arguments).map_err(|e| {
+                    crate::Error::Tool(format!("reminder_cancel: invalid arguments: {e}"))
+                })?;
+                let status = if self.cron.cancel_reminder(origin, &args.reminder_id).await? {
+                    "cancelled"
+                } else {
+                    "not_found"
+                };
+                Ok(serde_json::json!({ "status": status, "id": args.reminder_id }).to_string())
+            }
+            _ => Err(crate::Error::Tool(format!("unknown reminder tool: {name}"))),
+        }
+    }
+
     /// Execute a named cron tool
     ///
     /// # Errors
@@ -363,7 +597,7 @@ impl ToolProvider for BuiltinCronTools {
 
     fn kind(&self, name: &str) -> ToolKind {
         match name {
-            "cron_list" | "cron_get" => ToolKind::Read,
+            "cron_list" | "cron_get" | "reminder_list" => ToolKind::Read,
             _ => ToolKind::Mutate,
         }
     }
@@ -400,6 +634,16 @@ mod tests {
         assert!(names.contains(&"cron_get"));
     }
 
+    #[test]
+    fn reminder_tool_definitions() {
+        let defs = BuiltinCronTools::reminder_definitions();
+        let names: Vec<&str> = defs.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["reminder_create", "reminder_list", "reminder_cancel"]
+        );
+    }
+
     #[test]
     fn test_schedule_params_deserialization() {
         let json = r#"{
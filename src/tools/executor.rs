@@ -1,14 +1,15 @@
 //! Tool executor — dispatches tool calls to Synapse MCP or plugin subprocess
 
 use std::sync::Arc;
-use std::time::Duration;
 
 use agent_core::tools::ToolKind;
 use synapse_client::SynapseClient;
 use tokio::sync::Mutex;
 
 use crate::mcp::McpServerManager;
-use crate::plugins::PluginManager;
+use crate::plugins::{PLUGIN_TOOL_TIMEOUT, PluginManager};
+use crate::tools::ToolPolicy;
+use crate::tools::output::{ToolOutputConfig, truncate_output};
 use crate::{Error, Result};
 
 /// Shared plugin manager type
@@ -22,7 +23,7 @@ pub fn classify(name: &str) -> ToolKind {
     match name {
         // Read-only tools
         "Read" | "Glob" | "Grep" | "WebSearch" | "WebFetch" | "ListDir" | "NotebookRead"
-        | "TaskList" | "TaskGet" | "memory_search" | "cron_list" | "cron_get"
+        | "TaskList" | "TaskGet" | "memory_search" | "cron_list" | "cron_get" | "reminder_list"
         | "browser_screenshot" | "browser_extract" => ToolKind::Read,
         // Interactive tools
         "ask_user" | "permission" | "AskUserQuestion" | "location_request" => ToolKind::Interactive,
@@ -34,28 +35,68 @@ pub fn classify(name: &str) -> ToolKind {
     }
 }
 
+/// Map LLM tool names to policy category names
+#[must_use]
+pub fn normalize_tool_name(name: &str) -> String {
+    match name {
+        "Bash" => "shell".to_string(),
+        "Read" | "Glob" | "Grep" | "WebFetch" | "ListDir" => "read_file".to_string(),
+        "Write" | "Edit" => "write_file".to_string(),
+        "WebSearch" => "web_search".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Tool policy enforced for one channel
+struct PolicyScope {
+    policy: Arc<ToolPolicy>,
+    channel: String,
+}
+
+/// Conversation that tool audit events are attributed to
+struct AuditContext {
+    session_id: String,
+    organization_id: String,
+}
+
 /// Executes tool calls via Synapse MCP, plugin subprocess, or direct MCP servers
 pub struct ToolExecutor {
     synapse: Arc<SynapseClient>,
     plugin_manager: SharedPluginManager,
     memory_tools: Option<Arc<crate::tools::BuiltinMemoryTools>>,
     cron_tools: Option<Arc<crate::tools::BuiltinCronTools>>,
+    reminder_origin: Option<crate::tools::ReminderOrigin>,
     exec_tool: Option<Arc<crate::tools::BuiltinExecTool>>,
+    search_tool: Option<Arc<crate::tools::BuiltinSearchTool>>,
+    fetch_tool: Option<Arc<crate::tools::BuiltinFetchTool>>,
+    fetch_cache: Option<Arc<crate::tools::WebFetchCache>>,
     browser_tools: Option<Arc<crate::tools::BuiltinBrowserTools>>,
     mcp_manager: Option<Arc<McpServerManager>>,
+    output_config: ToolOutputConfig,
+    policy: Option<PolicyScope>,
+    session_profile: Option<crate::tools::SessionToolProfile>,
+    audit: Option<AuditContext>,
 }
 
 impl ToolExecutor {
     /// Create a new tool executor
-    pub const fn new(synapse: Arc<SynapseClient>, plugin_manager: SharedPluginManager) -> Self {
+    pub fn new(synapse: Arc<SynapseClient>, plugin_manager: SharedPluginManager) -> Self {
         Self {
             synapse,
             plugin_manager,
             memory_tools: None,
             cron_tools: None,
+            reminder_origin: None,
             exec_tool: None,
+            search_tool: None,
+            fetch_tool: None,
+            fetch_cache: None,
             browser_tools: None,
             mcp_manager: None,
+            output_config: ToolOutputConfig::default(),
+            policy: None,
+            session_profile: None,
+            audit: None,
         }
     }
 
@@ -73,6 +114,13 @@ impl ToolExecutor {
         self
     }
 
+    /// Set the conversation origin, enabling reminder tools alongside cron tools
+    #[must_use]
+    pub fn with_reminder_origin(mut self, origin: crate::tools::ReminderOrigin) -> Self {
+        self.reminder_origin = Some(origin);
+        self
+    }
+
     /// Attach built-in exec tool to this executor
     #[must_use]
     pub fn with_exec_tool(mut self, tool: Arc<crate::tools::BuiltinExecTool>) -> Self {
@@ -80,6 +128,27 @@ impl ToolExecutor {
         self
     }
 
+    /// Attach built-in web search tool, replacing Synapse's `WebSearch`
+    #[must_use]
+    pub fn with_search_tool(mut self, tool: Arc<crate::tools::BuiltinSearchTool>) -> Self {
+        self.search_tool = Some(tool);
+        self
+    }
+
+    /// Attach built-in web fetch tool, replacing Synapse's `WebFetch`
+    #[must_use]
+    pub fn with_fetch_tool(mut self, tool: Arc<crate::tools::BuiltinFetchTool>) -> Self {
+        self.fetch_tool = Some(tool);
+        self
+    }
+
+    /// Share a cache of `WebFetch` results across calls and executors
+    #[must_use]
+    pub fn with_fetch_cache(mut self, cache: Arc<crate::tools::WebFetchCache>) -> Self {
+        self.fetch_cache = Some(cache);
+        self
+    }
+
     /// Attach built-in browser tools to this executor
     #[must_use]
     pub fn with_browser_tools(mut self, tools: Arc<crate::tools::BuiltinBrowserTools>) -> Self {
@@ -94,6 +163,69 @@ impl ToolExecutor {
         self
     }
 
+    /// Set how tool results are truncated or summarized before returning
+    #[must_use]
+    pub fn with_output_config(mut self, config: ToolOutputConfig) -> Self {
+        self.output_config = config;
+        self
+    }
+
+    /// Enforce a channel's tool policy on every listing and call
+    ///
+    /// Denied tools are left out of [`Self::list_tools`] and rejected by
+    /// [`Self::execute`] before reaching any implementation.
+    #[must_use]
+    pub fn with_policy(mut self, policy: Arc<ToolPolicy>, channel: impl Into<String>) -> Self {
+        self.policy = Some(PolicyScope {
+            policy,
+            channel: channel.into(),
+        });
+        self
+    }
+
+    /// Override the channel's tool profile for this session
+    ///
+    /// The session profile takes precedence over the channel and persona
+    /// defaults of the policy set with [`Self::with_policy`].
+    #[must_use]
+    pub fn with_session_profile(
+        mut self,
+        profile: Option<crate::tools::SessionToolProfile>,
+    ) -> Self {
+        self.session_profile = profile;
+        self
+    }
+
+    /// Set the session and organization used for tool audit events
+    #[must_use]
+    pub fn with_audit_context(
+        mut self,
+        session_id: impl Into<String>,
+        organization_id: impl Into<String>,
+    ) -> Self {
+        self.audit = Some(AuditContext {
+            session_id: session_id.into(),
+            organization_id: organization_id.into(),
+        });
+        self
+    }
+
+    /// Whether calling `name` is allowed
+    ///
+    /// A session profile decides alone; otherwise the channel policy applies,
+    /// falling back to the persona default. Without either, everything is allowed.
+    #[must_use]
+    pub fn is_permitted(&self, name: &str) -> bool {
+        if let Some(ref profile) = self.session_profile {
+            return profile.allows(name);
+        }
+        self.policy.as_ref().is_none_or(|scope| {
+            scope
+                .policy
+                .is_allowed(&scope.channel, &normalize_tool_name(name))
+        })
+    }
+
     /// Fetch available tools from both Synapse MCP and loaded plugins
     ///
     /// # Errors
@@ -148,6 +280,13 @@ impl ToolExecutor {
                     .iter()
                     .map(crate::tools::to_synapse_definition),
             );
+            if self.reminder_origin.is_some() {
+                definitions.extend(
+                    crate::tools::BuiltinCronTools::reminder_definitions()
+                        .iter()
+                        .map(crate::tools::to_synapse_definition),
+                );
+            }
         }
 
         if let Some(ref et) = self.exec_tool {
@@ -158,6 +297,18 @@ impl ToolExecutor {
             );
         }
 
+        if let Some(ref st) = self.search_tool {
+            let search = st.definitions();
+            definitions.retain(|d| search.iter().all(|s| s.name != d.function.name));
+            definitions.extend(search.iter().map(crate::tools::to_synapse_definition));
+        }
+
+        if let Some(ref ft) = self.fetch_tool {
+            let fetch = ft.definitions();
+            definitions.retain(|d| fetch.iter().all(|f| f.name != d.function.name));
+            definitions.extend(fetch.iter().map(crate::tools::to_synapse_definition));
+        }
+
         if let Some(ref bt) = self.browser_tools {
             definitions.extend(
                 bt.definitions()
@@ -180,15 +331,143 @@ impl ToolExecutor {
             }
         }
 
+        definitions.retain(|d| self.is_permitted(&d.function.name));
+
         Ok(definitions)
     }
 
     /// Execute a tool call, routing to plugin subprocess or Synapse MCP
     ///
+    /// Calls denied by the tool policy are rejected here, the single entry
+    /// point for every tool, and reported as `beacon.tool.denied` events.
+    /// Successful results are shaped per the output config (optionally
+    /// summarized, then truncated) before being returned.
+    ///
     /// # Errors
     ///
-    /// Returns error if tool execution fails
+    /// Returns `Error::ToolNotPermitted` if the policy denies the tool, or
+    /// error if tool execution fails
     pub async fn execute(&self, name: &str, arguments: &str) -> Result<String> {
+        if !self.is_permitted(name) {
+            return Err(self.deny(name, arguments));
+        }
+
+        let output = self.dispatch(name, arguments).await?;
+        Ok(self.shape_output(name, output).await)
+    }
+
+    /// Record a policy denial and build the error returned to the caller
+    fn deny(&self, name: &str, arguments: &str) -> Error {
+        let channel = self
+            .policy
+            .as_ref()
+            .map_or("", |scope| scope.channel.as_str());
+        tracing::warn!(tool = name, channel, "tool call denied by policy");
+
+        if let Some(ref audit) = self.audit {
+            crate::events::publish(crate::events::build_tool_denied_event(
+                &audit.session_id,
+                name,
+                &audit.organization_id,
+                channel,
+                arguments,
+            ));
+        }
+
+        match self.session_profile {
+            Some(ref profile) => Error::ToolNotPermitted(format!(
+                "{name} is not permitted by the {} session profile",
+                profile.name()
+            )),
+            None => Error::ToolNotPermitted(format!("{name} is not permitted on {channel}")),
+        }
+    }
+
+    /// Summarize and/or truncate a tool result per the output config
+    async fn shape_output(&self, name: &str, output: String) -> String {
+        let output = if self.output_config.should_summarize(&output) {
+            match self.summarize(name, &output).await {
+                Ok(summary) => summary,
+                Err(e) => {
+                    tracing::warn!(tool = name, error = %e, "tool output summarization failed, truncating");
+                    output
+                }
+            }
+        } else {
+            output
+        };
+
+        let original_len = output.len();
+        let shaped = truncate_output(&output, self.output_config.max_chars);
+        if shaped.len() < original_len {
+            tracing::debug!(
+                tool = name,
+                original_len,
+                truncated_len = shaped.len(),
+                "truncated tool output"
+            );
+        }
+        shaped
+    }
+
+    /// Condense a large tool result with the configured summarization model
+    async fn summarize(&self, name: &str, output: &str) -> Result<String> {
+        let Some(model) = self.output_config.summarize_model.clone() else {
+            return Err(Error::Tool("no summarization model configured".to_string()));
+        };
+
+        // Bound what we send to the summarizer itself
+        let input = truncate_output(output, self.output_config.max_chars.saturating_mul(4));
+        let request = synapse_client::ChatRequest {
+            model,
+            messages: vec![
+                synapse_client::Message::system(
+                    "Summarize the following tool output for an AI assistant. Preserve key facts, \
+                     numbers, identifiers, URLs, and error messages. Be concise.",
+                ),
+                synapse_client::Message::user(&format!("Tool: {name}\n\n{input}")),
+            ],
+            stream: false,
+            temperature: None,
+            top_p: None,
+            max_tokens: Some(1024),
+            stop: None,
+            tools: None,
+            tool_choice: None,
+        };
+
+        let response = self
+            .synapse
+            .chat_completion(&request)
+            .await
+            .map_err(|e| Error::Tool(e.to_string()))?;
+
+        response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .filter(|s| !s.trim().is_empty())
+            .map(|summary| format!("[summarized tool output]\n{summary}"))
+            .ok_or_else(|| Error::Tool("empty summary".to_string()))
+    }
+
+    /// Route a tool call, serving repeated `WebFetch` calls from the cache
+    async fn dispatch(&self, name: &str, arguments: &str) -> Result<String> {
+        if name == "WebFetch"
+            && let Some(ref cache) = self.fetch_cache
+            && let Some(key) = fetch_key(arguments)
+        {
+            return cache
+                .get_or_fetch(&key, || self.route(name, arguments))
+                .await;
+        }
+
+        self.route(name, arguments).await
+    }
+
+    /// Route a tool call to its implementation and return the raw result
+    async fn route(&self, name: &str, arguments: &str) -> Result<String> {
         // Route built-in memory tools
         if name.starts_with("memory_")
             && let Some(ref mt) = self.memory_tools
@@ -203,6 +482,14 @@ impl ToolExecutor {
             return ct.execute(name, arguments).await;
         }
 
+        // Route built-in reminder tools (scoped to the conversation origin)
+        if name.starts_with("reminder_")
+            && let Some(ref ct) = self.cron_tools
+            && let Some(ref origin) = self.reminder_origin
+        {
+            return ct.execute_reminder(name, arguments, origin).await;
+        }
+
         // Route built-in exec tool
         if name == "Bash"
             && let Some(ref et) = self.exec_tool
@@ -210,6 +497,20 @@ impl ToolExecutor {
             return et.execute(name, arguments).await;
         }
 
+        // Route built-in web search
+        if name == "WebSearch"
+            && let Some(ref st) = self.search_tool
+        {
+            return st.execute(name, arguments).await;
+        }
+
+        // Route built-in web fetch
+        if name == "WebFetch"
+            && let Some(ref ft) = self.fetch_tool
+        {
+            return ft.execute(name, arguments).await;
+        }
+
         // Route built-in browser tools
         if name.starts_with("browser_")
             && let Some(ref bt) = self.browser_tools
@@ -231,8 +532,8 @@ impl ToolExecutor {
         }
 
         // Plugin tools use `plugin_id::tool_name` format
-        if let Some((plugin_id, tool_name)) = name.split_once("::") {
-            return self.execute_plugin(plugin_id, tool_name, arguments).await;
+        if name.contains("::") {
+            return self.execute_plugin(name, arguments).await;
         }
 
         // Route to Synapse MCP
@@ -244,111 +545,141 @@ impl ToolExecutor {
             .call_tool(name, args)
             .await
             .map_err(|e| Error::Tool(e.to_string()))?;
+        if result.is_error {
+            return Err(Error::Tool(result.text()));
+        }
 
         Ok(result.text())
     }
 
-    /// Execute a plugin tool via subprocess
-    async fn execute_plugin(
-        &self,
-        plugin_id: &str,
-        tool_name: &str,
-        arguments: &str,
-    ) -> Result<String> {
-        let pm = self.plugin_manager.lock().await;
-
-        let plugin = pm
-            .get(plugin_id)
-            .ok_or_else(|| Error::Tool(format!("plugin not found: {plugin_id}")))?;
-
-        if !plugin.enabled {
-            return Err(Error::Tool(format!("plugin disabled: {plugin_id}")));
-        }
-
-        let entry = plugin
-            .manifest
-            .entry
-            .as_deref()
-            .ok_or_else(|| Error::Tool(format!("plugin {plugin_id} has no entry point")))?;
-
-        let entry_path = plugin.path.join(entry);
-        // Release the lock before spawning
-        drop(pm);
-
-        run_plugin_entry(&entry_path, tool_name, arguments).await
+    /// Execute a plugin tool by its scoped `plugin_id::tool_name`
+    async fn execute_plugin(&self, name: &str, arguments: &str) -> Result<String> {
+        // Release the lock before running the tool
+        let invocation = self.plugin_manager.lock().await.invocation(name)?;
+        invocation.call(arguments, PLUGIN_TOOL_TIMEOUT).await
     }
 }
 
-/// Spawn a plugin entry point as a subprocess
+/// Cache key for `WebFetch` arguments
 ///
-/// Detects runtime from file extension:
-/// - `.js` / `.ts` -> `bun`
-/// - `.py` -> `python3`
-/// - anything else -> direct execution
-///
-/// Arguments: `<tool_name> <arguments_json>`
-/// Captures stdout, 30s timeout.
-async fn run_plugin_entry(
-    entry_path: &std::path::Path,
-    tool_name: &str,
-    arguments: &str,
-) -> Result<String> {
-    let ext = entry_path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("");
-
-    // TODO: add "wasm" arm using Extism for sandboxed execution of untrusted plugins
-    let mut cmd = match ext {
-        "js" | "ts" | "mjs" | "mts" => {
-            let mut c = tokio::process::Command::new("bun");
-            c.arg(entry_path);
-            c
-        }
-        "py" => {
-            let mut c = tokio::process::Command::new("python3");
-            c.arg(entry_path);
-            c
-        }
-        _ => tokio::process::Command::new(entry_path),
+/// Covers every argument (a prompt or format changes the result), with the
+/// URL trimmed, null fields dropped and keys in sorted order. `None` when no
+/// URL was given.
+fn fetch_key(arguments: &str) -> Option<String> {
+    let serde_json::Value::Object(args) = serde_json::from_str(arguments).ok()? else {
+        return None;
     };
+    let url = args
+        .get("url")?
+        .as_str()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())?
+        .to_string();
+
+    let mut normalized: std::collections::BTreeMap<String, serde_json::Value> = args
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .collect();
+    normalized.insert("url".to_string(), serde_json::Value::String(url));
+    serde_json::to_string(&normalized).ok()
+}
 
-    cmd.arg(tool_name).arg(arguments);
-    cmd.stdout(std::process::Stdio::piped());
-    cmd.stderr(std::process::Stdio::piped());
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Set working directory to plugin directory
-    if let Some(parent) = entry_path.parent() {
-        cmd.current_dir(parent);
+    #[test]
+    fn test_normalize_tool_name() {
+        assert_eq!(normalize_tool_name("Bash"), "shell");
+        assert_eq!(normalize_tool_name("Read"), "read_file");
+        assert_eq!(normalize_tool_name("Write"), "write_file");
+        assert_eq!(normalize_tool_name("Edit"), "write_file");
+        assert_eq!(normalize_tool_name("WebSearch"), "web_search");
+        assert_eq!(normalize_tool_name("WebFetch"), "read_file");
+        assert_eq!(normalize_tool_name("Glob"), "read_file");
+        assert_eq!(normalize_tool_name("Grep"), "read_file");
+        assert_eq!(normalize_tool_name("ListDir"), "read_file");
+        // Unknown tools pass through
+        assert_eq!(normalize_tool_name("memory_store"), "memory_store");
+        assert_eq!(normalize_tool_name("cron_list"), "cron_list");
     }
 
-    let child = cmd.spawn().map_err(|e| {
-        Error::Tool(format!(
-            "failed to spawn plugin process {}: {e}",
-            entry_path.display()
-        ))
-    })?;
-
-    let output = tokio::time::timeout(Duration::from_secs(30), child.wait_with_output())
-        .await
-        .map_err(|_| Error::Tool("plugin execution timed out (30s)".to_string()))?
-        .map_err(|e| Error::Tool(format!("plugin process error: {e}")))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(Error::Tool(format!(
-            "plugin exited with {}: {stderr}",
-            output.status
-        )));
+    #[tokio::test]
+    async fn denied_tool_never_reaches_implementation() {
+        use std::collections::HashMap;
+
+        use crate::tools::{ToolPolicyConfig, ToolProfile};
+
+        let marker = std::env::temp_dir().join(format!("beacon-denied-{}", uuid::Uuid::new_v4()));
+        let mut channels = HashMap::new();
+        channels.insert("discord".to_string(), ToolProfile::Messaging);
+        let policy = Arc::new(ToolPolicy::new(&ToolPolicyConfig { channels }));
+
+        let synapse = Arc::new(SynapseClient::new("http://127.0.0.1:1").unwrap());
+        let plugins = Arc::new(Mutex::new(PluginManager::new()));
+        let executor = ToolExecutor::new(synapse, plugins)
+            .with_exec_tool(Arc::new(crate::tools::BuiltinExecTool::default()))
+            .with_policy(policy, "discord")
+            .with_audit_context("sess-1", "org-1");
+
+        assert!(!executor.is_permitted("Bash"));
+        assert!(executor.is_permitted("WebSearch"));
+
+        let args = serde_json::json!({ "command": format!("touch {}", marker.display()) });
+        let err = executor
+            .execute("Bash", &args.to_string())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::ToolNotPermitted(_)));
+        assert!(!marker.exists(), "denied command ran");
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    Ok(stdout)
-}
+    #[test]
+    fn session_profile_overrides_channel_policy() {
+        use std::collections::HashMap;
+
+        use crate::tools::{SessionToolProfile, ToolPolicyConfig, ToolProfile};
+
+        let mut channels = HashMap::new();
+        channels.insert("discord".to_string(), ToolProfile::Full);
+        channels.insert("default".to_string(), ToolProfile::Minimal);
+        let policy = Arc::new(ToolPolicy::new(&ToolPolicyConfig { channels }));
+
+        let synapse = Arc::new(SynapseClient::new("http://127.0.0.1:1").unwrap());
+        let plugins = Arc::new(Mutex::new(PluginManager::new()));
+        let executor = |channel: &str, profile: Option<&str>| {
+            ToolExecutor::new(Arc::clone(&synapse), Arc::clone(&plugins))
+                .with_policy(Arc::clone(&policy), channel)
+                .with_session_profile(profile.and_then(SessionToolProfile::parse))
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // Channel default, then persona default
+        assert!(executor("discord", None).is_permitted("Bash"));
+        assert!(!executor("telegram", None).is_permitted("Bash"));
+
+        // Session override wins in both directions
+        let read_only = executor("discord", Some("readonly"));
+        assert!(!read_only.is_permitted("Bash"));
+        assert!(!read_only.is_permitted("Write"));
+        assert!(read_only.is_permitted("Read"));
+        assert!(read_only.is_permitted("WebSearch"));
+        assert!(executor("telegram", Some("full")).is_permitted("Bash"));
+    }
+
+    #[test]
+    fn fetch_key_normalizes_all_arguments() {
+        assert_eq!(
+            fetch_key(r#"{"url": " https://example.com ", "prompt": "a", "format": null}"#),
+            fetch_key(r#"{"prompt": "a", "url": "https://example.com"}"#)
+        );
+        assert_ne!(
+            fetch_key(r#"{"url": "https://example.com", "prompt": "a"}"#),
+            fetch_key(r#"{"url": "https://example.com", "prompt": "b"}"#)
+        );
+        assert_eq!(fetch_key(r#"{"url": ""}"#), None);
+        assert_eq!(fetch_key("not json"), None);
+    }
 
     #[test]
     fn classifies_known_tools() {
@@ -377,6 +708,10 @@ mod tests {
         assert_eq!(classify("cron_get"), ToolKind::Read);
         assert_eq!(classify("cron_schedule"), ToolKind::Mutate);
         assert_eq!(classify("cron_cancel"), ToolKind::Mutate);
+        // Reminder tools
+        assert_eq!(classify("reminder_list"), ToolKind::Read);
+        assert_eq!(classify("reminder_create"), ToolKind::Mutate);
+        assert_eq!(classify("reminder_cancel"), ToolKind::Mutate);
         // Browser tools
         assert_eq!(classify("browser_screenshot"), ToolKind::Read);
         assert_eq!(classify("browser_extract"), ToolKind::Read);
@@ -53,8 +53,8 @@ impl BuiltinMemoryTools {
                         },
                         "category": {
                             "type": "string",
-                            "enum": ["preference", "fact", "correction", "general"],
-                            "description": "Memory category (default: general)"
+                            "enum": ["preference", "fact", "correction", "general", "ephemeral"],
+                            "description": "Memory category (default: general); ephemeral memories expire"
                         }
                     },
                     "required": ["content"]
@@ -8,6 +8,10 @@ pub mod executor;
 pub use agent_core::tools::loop_detection::{LoopDetector, LoopSeverity};
 pub use agent_core::tools::{ToolKind, ToolProvider};
 pub mod memory;
+pub mod output;
+mod profile;
+mod reminder;
+mod search;
 mod sessions;
 mod web;
 
@@ -19,10 +23,15 @@ pub use browser_tools::BuiltinBrowserTools;
 pub use cron::{BuiltinCronTools, CronTools, ScheduleInfo, ScheduleParams};
 pub use exec::BuiltinExecTool;
 pub use memory::BuiltinMemoryTools;
+pub use output::ToolOutputConfig;
+pub use profile::{READ_ONLY_PROFILE, SessionToolProfile};
+pub use reminder::{REMIND_ACTION, ReminderCommand, ReminderOrigin, ReminderPayload};
+pub use search::{BraveSearchProvider, BuiltinSearchTool, SearchError};
 pub use sessions::{MessageInfo, SessionInfo, SessionTools};
 pub use web::{
-    Article, SearchProvider, SearchResult, WebFetchTool, WebResponse, WebSearchTool,
-    extract_article,
+    Article, ArticleExtraction, BuiltinFetchTool, ExtractionStrategy, SearchProvider, SearchResult,
+    WebFetchCache, WebFetchCacheConfig, WebFetchTool, WebResponse, WebSearchTool, extract_article,
+    extract_with_fallback,
 };
 
 /// Convert an agent-core `Tool` to a synapse `ToolDefinition`
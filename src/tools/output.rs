@@ -0,0 +1,169 @@
+//! Shaping of tool results before they are fed back to the LLM
+//!
+//! Large outputs (web fetches, shell commands, page content) are trimmed to a
+//! configurable size, keeping the head and tail of the text so both the start
+//! of a listing and its final lines (often errors or totals) survive. An
+//! optional summarization step condenses very large outputs via the LLM first.
+
+/// Default maximum size of a tool result, in characters
+pub const DEFAULT_MAX_CHARS: usize = 16_000;
+
+/// Share of the retained budget given to the head of the output
+const HEAD_RATIO: f64 = 0.7;
+
+/// Tool output shaping configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolOutputConfig {
+    /// Maximum characters fed back per tool result (0 disables truncation)
+    pub max_chars: usize,
+    /// Summarize results estimated above this many tokens before truncating
+    pub summarize_over_tokens: Option<usize>,
+    /// Model used for summarization (summarization is skipped when unset)
+    pub summarize_model: Option<String>,
+}
+
+impl Default for ToolOutputConfig {
+    fn default() -> Self {
+        Self {
+            max_chars: DEFAULT_MAX_CHARS,
+            summarize_over_tokens: None,
+            summarize_model: None,
+        }
+    }
+}
+
+impl ToolOutputConfig {
+    /// Load tool output configuration from environment variables
+    ///
+    /// Reads from:
+    /// - `BEACON_TOOL_OUTPUT_MAX_CHARS`: max characters per result (default: 16000, 0 disables)
+    /// - `BEACON_TOOL_OUTPUT_SUMMARIZE_TOKENS`: summarize results above this token estimate
+    /// - `BEACON_TOOL_OUTPUT_SUMMARIZE_MODEL`: model used for summarization
+    #[must_use]
+    pub fn from_env() -> Self {
+        let max_chars = std::env::var("BEACON_TOOL_OUTPUT_MAX_CHARS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MAX_CHARS);
+
+        let summarize_over_tokens = std::env::var("BEACON_TOOL_OUTPUT_SUMMARIZE_TOKENS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|n: &usize| *n > 0);
+
+        let summarize_model = std::env::var("BEACON_TOOL_OUTPUT_SUMMARIZE_MODEL")
+            .ok()
+            .filter(|s| !s.is_empty());
+
+        Self {
+            max_chars,
+            summarize_over_tokens,
+            summarize_model,
+        }
+    }
+
+    /// Whether `output` should be summarized before truncation
+    #[must_use]
+    pub fn should_summarize(&self, output: &str) -> bool {
+        self.summarize_model.is_some()
+            && self
+                .summarize_over_tokens
+                .is_some_and(|limit| estimate_tokens(output) > limit)
+    }
+}
+
+/// Rough token estimate (4 chars per token)
+#[must_use]
+pub const fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+/// Truncate `output` to at most `max_chars` characters, keeping head and tail
+///
+/// The omitted middle is replaced by a marker noting how many characters were
+/// dropped. Outputs within the limit (or a limit of 0) are returned unchanged.
+#[must_use]
+pub fn truncate_output(output: &str, max_chars: usize) -> String {
+    let total = output.chars().count();
+    if max_chars == 0 || total <= max_chars {
+        return output.to_string();
+    }
+
+    // The marker length depends on the omitted count, which depends on the
+    // marker length; size it against the worst case (everything omitted)
+    let marker_len = truncation_marker(total).chars().count();
+    let budget = max_chars.saturating_sub(marker_len);
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let head_len = (budget as f64 * HEAD_RATIO) as usize;
+    let tail_len = budget - head_len;
+    let omitted = total - head_len - tail_len;
+
+    let head: String = output.chars().take(head_len).collect();
+    let tail: String = output.chars().skip(total - tail_len).collect();
+
+    format!("{head}{}{tail}", truncation_marker(omitted))
+}
+
+/// Marker inserted in place of omitted output
+fn truncation_marker(omitted: usize) -> String {
+    format!("\n\n[... {omitted} characters truncated ...]\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_output_is_unchanged() {
+        assert_eq!(truncate_output("hello", 100), "hello");
+    }
+
+    #[test]
+    fn zero_limit_disables_truncation() {
+        let long = "x".repeat(50_000);
+        assert_eq!(truncate_output(&long, 0), long);
+    }
+
+    #[test]
+    fn oversized_output_keeps_head_and_tail() {
+        let output = format!(
+            "{}{}{}",
+            "a".repeat(5_000),
+            "b".repeat(10_000),
+            "z".repeat(5_000)
+        );
+        let truncated = truncate_output(&output, 1_000);
+
+        assert!(truncated.chars().count() <= 1_000);
+        assert!(truncated.starts_with('a'));
+        assert!(truncated.ends_with('z'));
+        assert!(truncated.contains("characters truncated ...]"));
+    }
+
+    #[test]
+    fn truncation_respects_char_boundaries() {
+        let output = "é".repeat(2_000);
+        let truncated = truncate_output(&output, 500);
+        assert!(truncated.chars().count() <= 500);
+        assert!(truncated.starts_with('é'));
+    }
+
+    #[test]
+    fn summarize_requires_model_and_threshold() {
+        let big = "word ".repeat(2_000);
+        let mut config = ToolOutputConfig {
+            summarize_over_tokens: Some(100),
+            ..ToolOutputConfig::default()
+        };
+        assert!(!config.should_summarize(&big));
+
+        config.summarize_model = Some("gpt-4o-mini".to_string());
+        assert!(config.should_summarize(&big));
+        assert!(!config.should_summarize("short"));
+    }
+}
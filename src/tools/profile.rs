@@ -0,0 +1,128 @@
+//! Per-session tool profile overrides
+//!
+//! The persona's tool policy maps channels to profiles. A session can
+//! override that with its own profile, such as the strict `readonly` preset
+//! for untrusted DMs. The effective profile for a call is resolved as:
+//!
+//! 1. the session override, when set
+//! 2. the channel's profile from the persona's tool policy
+//! 3. the persona's `default` profile
+
+use std::collections::HashMap;
+
+use agent_core::tools::ToolKind;
+
+use crate::tools::executor::{classify, normalize_tool_name};
+use crate::tools::{ToolPolicy, ToolPolicyConfig, ToolProfile};
+
+/// Name of the read-only preset
+pub const READ_ONLY_PROFILE: &str = "readonly";
+
+/// Policy key consulted for channels without their own profile
+const DEFAULT_CHANNEL: &str = "default";
+
+enum Access {
+    /// Only read-only tools (Read, Glob, Grep, `WebFetch`, `WebSearch`, ...)
+    ReadOnly,
+    /// One of the persona policy presets
+    Preset(ToolPolicy),
+}
+
+/// Tool profile that overrides the channel profile for one session
+pub struct SessionToolProfile {
+    name: String,
+    access: Access,
+}
+
+impl SessionToolProfile {
+    /// The strict read-only preset
+    #[must_use]
+    pub fn read_only() -> Self {
+        Self {
+            name: READ_ONLY_PROFILE.to_string(),
+            access: Access::ReadOnly,
+        }
+    }
+
+    /// Parse a profile name: `readonly` or any persona preset (e.g. `full`)
+    ///
+    /// Returns `None` for unknown names.
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        if matches!(name.as_str(), READ_ONLY_PROFILE | "read_only" | "read-only") {
+            return Some(Self::read_only());
+        }
+
+        let profile: ToolProfile =
+            serde_json::from_value(serde_json::Value::String(name.clone())).ok()?;
+        let config = ToolPolicyConfig {
+            channels: HashMap::from([(DEFAULT_CHANNEL.to_string(), profile)]),
+        };
+        Some(Self {
+            name,
+            access: Access::Preset(ToolPolicy::new(&config)),
+        })
+    }
+
+    /// Canonical profile name, as stored on the session
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether this profile allows calling the tool `name`
+    #[must_use]
+    pub fn allows(&self, name: &str) -> bool {
+        match &self.access {
+            Access::ReadOnly => classify(name) == ToolKind::Read,
+            Access::Preset(policy) => {
+                policy.is_allowed(DEFAULT_CHANNEL, &normalize_tool_name(name))
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for SessionToolProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SessionToolProfile")
+            .field(&self.name)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_allows_only_read_tools() {
+        let profile = SessionToolProfile::parse("readonly").unwrap();
+
+        for tool in ["Read", "Glob", "Grep", "WebFetch", "WebSearch"] {
+            assert!(profile.allows(tool), "{tool} should be allowed");
+        }
+        for tool in ["Bash", "Write", "Edit", "memory_store", "mcp_github_push"] {
+            assert!(!profile.allows(tool), "{tool} should be denied");
+        }
+    }
+
+    #[test]
+    fn parses_presets_and_rejects_unknown_names() {
+        assert_eq!(
+            SessionToolProfile::parse("Read_Only").unwrap().name(),
+            "readonly"
+        );
+
+        let full = SessionToolProfile::parse("full").unwrap();
+        assert_eq!(full.name(), "full");
+        assert!(full.allows("Bash"));
+        assert!(
+            !SessionToolProfile::parse("messaging")
+                .unwrap()
+                .allows("Bash")
+        );
+
+        assert!(SessionToolProfile::parse("superuser").is_none());
+    }
+}
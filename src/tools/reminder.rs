@@ -0,0 +1,391 @@
+//! Reminder commands built on top of Vortex cron schedules
+//!
+//! Reminders are `remind` schedules whose payload records where the reminder
+//! came from (user, channel, channel ID, and thread) so the Vortex callback can
+//! deliver it back to the originating conversation. Users can manage them
+//! directly with `/remind`, `/reminders`, and `/cancel` without going through
+//! the agent.
+
+use serde::{Deserialize, Serialize};
+
+use super::cron::{CronTools, ScheduleInfo};
+
+/// Vortex action used for reminder schedules
+pub const REMIND_ACTION: &str = "remind";
+
+/// Usage text for the `/remind` command
+const REMIND_USAGE: &str = "Usage: /remind <when> <message>\n\
+    <when> is a cron expression (e.g. 0 9 * * MON) or one of: \
+    hourly, daily HH:MM, weekdays HH:MM, weekly <day> HH:MM";
+
+/// Usage text for the `/cancel` command
+const CANCEL_USAGE: &str = "Usage: /cancel <reminder id>";
+
+/// Where a reminder was created, used to route its delivery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReminderOrigin {
+    /// Platform user ID of the requester
+    pub user_id: String,
+    /// Channel name (e.g. `telegram`, `discord`, `slack`)
+    pub channel: String,
+    /// Channel-specific conversation ID to deliver to
+    pub channel_id: String,
+    /// Thread within the conversation, if the reminder was created in one
+    pub thread_id: Option<String>,
+}
+
+impl ReminderOrigin {
+    /// Create a new reminder origin
+    #[must_use]
+    pub fn new(
+        user_id: impl Into<String>,
+        channel: impl Into<String>,
+        channel_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            user_id: user_id.into(),
+            channel: channel.into(),
+            channel_id: channel_id.into(),
+            thread_id: None,
+        }
+    }
+
+    /// Deliver into the given thread
+    #[must_use]
+    pub fn with_thread(mut self, thread_id: Option<String>) -> Self {
+        self.thread_id = thread_id;
+        self
+    }
+
+    /// Build the schedule payload for a reminder with the given message
+    #[must_use]
+    pub fn payload(&self, message: impl Into<String>) -> ReminderPayload {
+        ReminderPayload {
+            user_id: self.user_id.clone(),
+            message: message.into(),
+            channel: self.channel.clone(),
+            channel_id: self.channel_id.clone(),
+            thread_id: self.thread_id.clone(),
+        }
+    }
+}
+
+/// Payload stored with a `remind` schedule and echoed back in the callback
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReminderPayload {
+    /// Platform user ID of the requester
+    pub user_id: String,
+    /// Reminder text to deliver
+    pub message: String,
+    /// Channel name the reminder was created from
+    #[serde(default = "default_channel")]
+    pub channel: String,
+    /// Channel-specific conversation ID to deliver to
+    #[serde(default)]
+    pub channel_id: String,
+    /// Thread within the conversation to deliver into
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+}
+
+/// Reminders created before channel routing was recorded were Telegram-only
+fn default_channel() -> String {
+    "telegram".to_string()
+}
+
+impl ReminderPayload {
+    /// Whether this reminder belongs to the requester in `origin`
+    ///
+    /// Platform user IDs are only unique within a channel, so both the
+    /// channel and the user must match.
+    #[must_use]
+    pub fn is_owned_by(&self, origin: &ReminderOrigin) -> bool {
+        self.channel == origin.channel && self.user_id == origin.user_id
+    }
+}
+
+/// A reminder slash command parsed from a chat message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReminderCommand {
+    /// `/remind <when> <message>`
+    Create {
+        /// Cron expression for the schedule
+        cron: String,
+        /// Reminder text
+        message: String,
+    },
+    /// `/reminders`
+    List,
+    /// `/cancel <id>`
+    Cancel {
+        /// Schedule ID to cancel
+        id: String,
+    },
+    /// A reminder command with malformed arguments; carries usage text
+    Usage(&'static str),
+}
+
+impl ReminderCommand {
+    /// Parse a reminder slash command
+    ///
+    /// Returns `None` if the message is not a reminder command. Telegram-style
+    /// `@botname` suffixes on the command are ignored.
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let trimmed = input.trim();
+        let rest = trimmed.strip_prefix('/')?;
+
+        let (command, args) = rest
+            .split_once(char::is_whitespace)
+            .map_or((rest, ""), |(c, a)| (c, a.trim()));
+        let command = command.split_once('@').map_or(command, |(c, _)| c);
+
+        match command.to_ascii_lowercase().as_str() {
+            "remind" => Some(
+                parse_schedule(args)
+                    .filter(|(_, message)| !message.is_empty())
+                    .map_or(Self::Usage(REMIND_USAGE), |(cron, message)| Self::Create {
+                        cron,
+                        message: message.to_string(),
+                    }),
+            ),
+            "reminders" => Some(Self::List),
+            "cancel" => Some(
+                args.split_whitespace()
+                    .next()
+                    .map_or(Self::Usage(CANCEL_USAGE), |id| Self::Cancel {
+                        id: id.to_string(),
+                    }),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Execute the command against Vortex and return a user-facing reply
+    pub async fn run(self, cron: &CronTools, origin: &ReminderOrigin) -> String {
+        match self {
+            Self::Create {
+                cron: expr,
+                message,
+            } => match cron
+                .create_reminder(&expr, &origin.payload(message), None)
+                .await
+            {
+                Ok(id) => format!("Reminder set ({expr}). ID: {id}"),
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to create reminder");
+                    "Sorry, I couldn't set that reminder.".to_string()
+                }
+            },
+            Self::List => match cron.list_reminders(origin).await {
+                Ok(reminders) if reminders.is_empty() => "You have no reminders.".to_string(),
+                Ok(reminders) => format_reminder_list(&reminders),
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to list reminders");
+                    "Sorry, I couldn't fetch your reminders.".to_string()
+                }
+            },
+            Self::Cancel { id } => match cron.cancel_reminder(origin, &id).await {
+                Ok(true) => format!("Reminder {id} cancelled."),
+                Ok(false) => format!("No reminder with ID {id}."),
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to cancel reminder");
+                    "Sorry, I couldn't cancel that reminder.".to_string()
+                }
+            },
+            Self::Usage(usage) => usage.to_string(),
+        }
+    }
+}
+
+/// Format reminders as one line each
+fn format_reminder_list(reminders: &[ScheduleInfo]) -> String {
+    let mut out = String::from("Your reminders:");
+    for r in reminders {
+        out.push_str("\n- ");
+        out.push_str(&r.id);
+        out.push_str(" (");
+        out.push_str(&r.cron);
+        out.push(')');
+        if let Some(ref desc) = r.description {
+            out.push_str(": ");
+            out.push_str(desc);
+        }
+    }
+    out
+}
+
+/// Split `<when> <message>` into a cron expression and the remaining text
+///
+/// Accepts a raw five-field cron expression or a shorthand
+/// (`hourly`, `daily HH:MM`, `weekdays HH:MM`, `weekly <day> HH:MM`).
+#[must_use]
+pub fn parse_schedule(args: &str) -> Option<(String, &str)> {
+    let tokens: Vec<&str> = args.split_whitespace().collect();
+    let first = tokens.first()?.to_ascii_lowercase();
+
+    let (cron, consumed) = match first.as_str() {
+        "hourly" => ("0 * * * *".to_string(), 1),
+        "daily" => {
+            let (h, m) = parse_time(tokens.get(1)?)?;
+            (format!("{m} {h} * * *"), 2)
+        }
+        "weekdays" => {
+            let (h, m) = parse_time(tokens.get(1)?)?;
+            (format!("{m} {h} * * MON-FRI"), 2)
+        }
+        "weekly" => {
+            let day = parse_day(tokens.get(1)?)?;
+            let (h, m) = parse_time(tokens.get(2)?)?;
+            (format!("{m} {h} * * {day}"), 3)
+        }
+        _ => {
+            let fields = tokens.get(..5)?;
+            if !fields
+                .iter()
+                .enumerate()
+                .all(|(i, f)| is_cron_field(f, i >= 3))
+            {
+                return None;
+            }
+            (fields.join(" "), 5)
+        }
+    };
+
+    Some((cron, remainder_after(args, consumed)))
+}
+
+/// Return the text after the first `n` whitespace-separated tokens
+fn remainder_after(s: &str, n: usize) -> &str {
+    let mut rest = s.trim_start();
+    for _ in 0..n {
+        rest = rest
+            .find(char::is_whitespace)
+            .map_or("", |i| rest[i..].trim_start());
+    }
+    rest.trim_end()
+}
+
+/// Parse `HH:MM` (24-hour) into hour and minute
+fn parse_time(s: &str) -> Option<(u8, u8)> {
+    let (h, m) = s.split_once(':')?;
+    let h: u8 = h.parse().ok()?;
+    let m: u8 = m.parse().ok()?;
+    (h < 24 && m < 60).then_some((h, m))
+}
+
+/// Normalize a weekday name to its three-letter cron form
+fn parse_day(s: &str) -> Option<&'static str> {
+    const DAYS: [&str; 7] = ["MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
+    let upper = s.to_ascii_uppercase();
+    let prefix = upper.get(..3)?;
+    DAYS.into_iter().find(|d| *d == prefix)
+}
+
+/// Check whether a token looks like a single cron field
+///
+/// Names (e.g. `MON`, `JAN`) are only accepted in the month and weekday positions.
+fn is_cron_field(field: &str, allow_names: bool) -> bool {
+    !field.is_empty()
+        && field.split([',', '-', '/']).all(|part| {
+            part == "*"
+                || part == "?"
+                || (!part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+                || (allow_names && part.len() == 3 && part.chars().all(|c| c.is_ascii_alphabetic()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cron_expression_reminder() {
+        let cmd = ReminderCommand::parse("/remind 0 9 * * MON Weekly standup").unwrap();
+        assert_eq!(
+            cmd,
+            ReminderCommand::Create {
+                cron: "0 9 * * MON".to_string(),
+                message: "Weekly standup".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_shorthand_schedules() {
+        assert_eq!(
+            parse_schedule("daily 08:30 Take meds"),
+            Some(("30 8 * * *".to_string(), "Take meds"))
+        );
+        assert_eq!(
+            parse_schedule("weekdays 17:00 Log hours"),
+            Some(("0 17 * * MON-FRI".to_string(), "Log hours"))
+        );
+        assert_eq!(
+            parse_schedule("weekly friday 16:00 Retro"),
+            Some(("0 16 * * FRI".to_string(), "Retro"))
+        );
+        assert_eq!(
+            parse_schedule("hourly Stretch"),
+            Some(("0 * * * *".to_string(), "Stretch"))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_remind() {
+        assert_eq!(
+            ReminderCommand::parse("/remind tomorrow call mom"),
+            Some(ReminderCommand::Usage(REMIND_USAGE))
+        );
+        assert_eq!(
+            ReminderCommand::parse("/remind daily 25:00 nope"),
+            Some(ReminderCommand::Usage(REMIND_USAGE))
+        );
+        assert_eq!(
+            ReminderCommand::parse("/remind 0 9 * * *"),
+            Some(ReminderCommand::Usage(REMIND_USAGE))
+        );
+    }
+
+    #[test]
+    fn parses_list_and_cancel() {
+        assert_eq!(
+            ReminderCommand::parse("/reminders"),
+            Some(ReminderCommand::List)
+        );
+        assert_eq!(
+            ReminderCommand::parse("/cancel@beacon_bot sched_1"),
+            Some(ReminderCommand::Cancel {
+                id: "sched_1".to_string()
+            })
+        );
+        assert_eq!(
+            ReminderCommand::parse("/cancel"),
+            Some(ReminderCommand::Usage(CANCEL_USAGE))
+        );
+    }
+
+    #[test]
+    fn ignores_other_messages() {
+        assert_eq!(ReminderCommand::parse("remind me later"), None);
+        assert_eq!(ReminderCommand::parse("/help"), None);
+    }
+
+    #[test]
+    fn ownership_requires_matching_channel_and_user() {
+        let payload = ReminderOrigin::new("42", "discord", "chan").payload("hi");
+        assert!(payload.is_owned_by(&ReminderOrigin::new("42", "discord", "other-chan")));
+        // The same platform ID on another channel is a different person
+        assert!(!payload.is_owned_by(&ReminderOrigin::new("42", "telegram", "chan")));
+        assert!(!payload.is_owned_by(&ReminderOrigin::new("43", "discord", "chan")));
+    }
+
+    #[test]
+    fn payload_defaults_to_telegram_for_legacy_schedules() {
+        let payload: ReminderPayload =
+            serde_json::from_value(serde_json::json!({ "user_id": "u1", "message": "hi" }))
+                .unwrap();
+        assert_eq!(payload.channel, "telegram");
+        assert!(payload.channel_id.is_empty());
+    }
+}
@@ -0,0 +1,404 @@
+//! Built-in web search tool backed by the Brave Search API
+//!
+//! When selected, the gateway answers `WebSearch` calls itself instead of
+//! forwarding them to Synapse, using the user's own Brave API key. Brave is
+//! one [`SearchProvider`]; the tool accepts any of them.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use agent_core::tools::{ToolKind, ToolProvider};
+use serde::Deserialize;
+
+use crate::tools::{SearchProvider, SearchResult};
+use crate::{Error, Result};
+
+/// Brave Web Search endpoint
+pub const BRAVE_API_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+
+/// Results returned when the caller doesn't ask for a count
+pub const DEFAULT_RESULT_COUNT: usize = 5;
+
+/// Most results Brave returns per request
+pub const MAX_RESULT_COUNT: usize = 20;
+
+/// Brave search failure
+#[derive(Debug, thiserror::Error)]
+pub enum SearchError {
+    /// The API key's rate limit or quota was hit
+    #[error(
+        "rate limited by search provider{}",
+        .retry_after.map(|s| format!(", retry after {s}s")).unwrap_or_default()
+    )]
+    RateLimited { retry_after: Option<u64> },
+
+    /// The API key was rejected
+    #[error("search provider rejected the API key")]
+    Unauthorized,
+
+    /// Any other non-success response
+    #[error("search failed: {0}")]
+    Api(String),
+
+    /// Transport or decoding failure
+    #[error("search request failed: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+impl From<SearchError> for Error {
+    fn from(e: SearchError) -> Self {
+        Self::Tool(e.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+struct BraveResponse {
+    #[serde(default)]
+    web: Option<BraveWeb>,
+}
+
+#[derive(Deserialize)]
+struct BraveWeb {
+    #[serde(default)]
+    results: Vec<BraveResult>,
+}
+
+#[derive(Deserialize)]
+struct BraveResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    description: String,
+}
+
+impl From<BraveResult> for SearchResult {
+    fn from(r: BraveResult) -> Self {
+        Self {
+            title: r.title,
+            url: r.url,
+            snippet: r.description,
+        }
+    }
+}
+
+/// Brave Search API client
+#[derive(Clone)]
+pub struct BraveSearchProvider {
+    client: reqwest::Client,
+    api_key: String,
+    api_url: String,
+}
+
+impl std::fmt::Debug for BraveSearchProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BraveSearchProvider")
+            .field("api_url", &self.api_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BraveSearchProvider {
+    /// Create a provider for an API key
+    #[must_use]
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(15))
+                .build()
+                .unwrap_or_default(),
+            api_key,
+            api_url: BRAVE_API_URL.to_string(),
+        }
+    }
+
+    /// Override the API endpoint
+    #[must_use]
+    pub fn with_api_url(mut self, url: impl Into<String>) -> Self {
+        self.api_url = url.into();
+        self
+    }
+
+    /// Search the web, returning at most `count` results (capped at 20)
+    ///
+    /// # Errors
+    ///
+    /// Returns `SearchError::RateLimited` when Brave throttles the key, and
+    /// other variants for rejected keys, API errors, or transport failures
+    pub async fn search(
+        &self,
+        query: &str,
+        count: usize,
+    ) -> std::result::Result<Vec<SearchResult>, SearchError> {
+        let count = count.clamp(1, MAX_RESULT_COUNT);
+        let response = self
+            .client
+            .get(&self.api_url)
+            .header("X-Subscription-Token", &self.api_key)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .query(&[("q", query), ("count", &count.to_string())])
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse().ok());
+            return Err(SearchError::RateLimited { retry_after });
+        }
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(SearchError::Unauthorized);
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(SearchError::Api(format!("{status}: {body}")));
+        }
+
+        let body: BraveResponse = response.json().await?;
+        Ok(body
+            .web
+            .map(|web| web.results)
+            .unwrap_or_default()
+            .into_iter()
+            .take(count)
+            .map(SearchResult::from)
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl SearchProvider for BraveSearchProvider {
+    async fn search(&self, query: &str, count: usize) -> anyhow::Result<Vec<SearchResult>> {
+        Self::search(self, query, count)
+            .await
+            .map_err(anyhow::Error::from)
+    }
+}
+
+/// Built-in `WebSearch` tool using a configured search provider
+#[derive(Clone)]
+pub struct BuiltinSearchTool {
+    provider: Arc<dyn SearchProvider>,
+}
+
+impl std::fmt::Debug for BuiltinSearchTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BuiltinSearchTool").finish_non_exhaustive()
+    }
+}
+
+impl BuiltinSearchTool {
+    /// Create the tool around a search provider
+    #[must_use]
+    pub fn new(provider: impl SearchProvider + 'static) -> Self {
+        Self {
+            provider: Arc::new(provider),
+        }
+    }
+
+    /// Build the tool from environment variables, if Brave is selected
+    ///
+    /// Reads from:
+    /// - `BEACON_SEARCH_PROVIDER`: `brave` to answer searches locally; any
+    ///   other value keeps Synapse's search (default: `brave` when a key is set)
+    /// - `BRAVE_API_KEY`: Brave Search subscription token
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let api_key = std::env::var("BRAVE_API_KEY")
+            .ok()
+            .filter(|k| !k.is_empty());
+        let provider = std::env::var("BEACON_SEARCH_PROVIDER").ok();
+
+        match (provider.as_deref(), api_key) {
+            (None | Some("brave"), Some(key)) => Some(Self::new(BraveSearchProvider::new(key))),
+            (Some("brave"), None) => {
+                tracing::warn!("BEACON_SEARCH_PROVIDER=brave but BRAVE_API_KEY is not set");
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Execute the `WebSearch` tool from LLM JSON arguments
+    ///
+    /// # Errors
+    ///
+    /// Returns error if arguments are malformed or the search fails
+    pub async fn execute(&self, name: &str, arguments: &str) -> Result<String> {
+        #[derive(Deserialize)]
+        struct SearchArgs {
+            query: Option<String>,
+            #[serde(default)]
+            count: Option<usize>,
+        }
+
+        if name != "WebSearch" {
+            return Err(Error::Tool(format!("unknown search tool: {name}")));
+        }
+
+        let args: SearchArgs = serde_json::from_str(arguments)
+            .map_err(|e| Error::Tool(format!("WebSearch: invalid arguments: {e}")))?;
+        let query = args
+            .query
+            .filter(|q| !q.trim().is_empty())
+            .ok_or_else(|| Error::Tool("WebSearch: `query` parameter is required".to_string()))?;
+
+        let results = self
+            .provider
+            .search(&query, args.count.unwrap_or(DEFAULT_RESULT_COUNT))
+            .await
+            .map_err(|e| Error::Tool(format!("WebSearch: {e}")))?;
+
+        let results: Vec<_> = results
+            .into_iter()
+            .map(|r| {
+                serde_json::json!({
+                    "title": r.title,
+                    "url": r.url,
+                    "snippet": r.snippet,
+                })
+            })
+            .collect();
+        Ok(serde_json::to_string(&results)?)
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolProvider for BuiltinSearchTool {
+    fn definitions(&self) -> Vec<agent_core::types::Tool> {
+        vec![agent_core::types::Tool {
+            name: "WebSearch".to_string(),
+            description:
+                "Search the web and return matching pages with titles, URLs, and snippets."
+                    .to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "The search query"
+                    },
+                    "count": {
+                        "type": "integer",
+                        "description": "Number of results to return (default: 5, max: 20)"
+                    }
+                },
+                "required": ["query"]
+            }),
+        }]
+    }
+
+    async fn execute(&self, name: &str, arguments: &str) -> anyhow::Result<String> {
+        Self::execute(self, name, arguments)
+            .await
+            .map_err(|e| anyhow::anyhow!("{e}"))
+    }
+
+    fn kind(&self, _name: &str) -> ToolKind {
+        ToolKind::Read
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::Router;
+    use axum::extract::Query;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::response::IntoResponse;
+    use axum::routing::get;
+
+    use super::*;
+
+    async fn brave(
+        headers: HeaderMap,
+        Query(params): Query<HashMap<String, String>>,
+    ) -> impl IntoResponse {
+        if headers
+            .get("X-Subscription-Token")
+            .and_then(|v| v.to_str().ok())
+            != Some("key")
+        {
+            return (StatusCode::UNAUTHORIZED, String::new()).into_response();
+        }
+        if params.get("q").map(String::as_str) == Some("busy") {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [("Retry-After", "3")],
+                String::new(),
+            )
+                .into_response();
+        }
+
+        let count: usize = params["count"].parse().unwrap();
+        let results: Vec<_> = (0..10)
+            .map(|i| {
+                serde_json::json!({
+                    "title": format!("Result {i}"),
+                    "url": format!("https://example.com/{i}"),
+                    "description": format!("About {}", params["q"]),
+                })
+            })
+            .take(count)
+            .collect();
+        axum::Json(serde_json::json!({ "web": { "results": results } })).into_response()
+    }
+
+    async fn serve() -> String {
+        let app = Router::new().route("/search", get(brave));
+        format!("{}/search", crate::test_support::serve(app).await)
+    }
+
+    #[tokio::test]
+    async fn search_maps_results_and_honors_count() {
+        let url = serve().await;
+        let tool =
+            BuiltinSearchTool::new(BraveSearchProvider::new("key".to_string()).with_api_url(url));
+
+        let output = tool
+            .execute("WebSearch", r#"{"query": "rust", "count": 3}"#)
+            .await
+            .unwrap();
+        let results: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["title"], "Result 0");
+        assert_eq!(results[0]["url"], "https://example.com/0");
+        assert_eq!(results[0]["snippet"], "About rust");
+    }
+
+    #[tokio::test]
+    async fn brave_serves_as_a_search_provider() {
+        let url = serve().await;
+        let provider: Arc<dyn SearchProvider> =
+            Arc::new(BraveSearchProvider::new("key".to_string()).with_api_url(url));
+
+        let results = provider.search("rust", 2).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].url, "https://example.com/1");
+
+        let err = provider.search("busy", 2).await.unwrap_err();
+        assert!(err.downcast_ref::<SearchError>().is_some());
+    }
+
+    #[tokio::test]
+    async fn rate_limits_and_bad_keys_are_distinct() {
+        let url = serve().await;
+
+        let provider = BraveSearchProvider::new("key".to_string()).with_api_url(&url);
+        let err = provider.search("busy", 5).await.unwrap_err();
+        assert!(matches!(
+            err,
+            SearchError::RateLimited {
+                retry_after: Some(3)
+            }
+        ));
+
+        let provider = BraveSearchProvider::new("wrong".to_string()).with_api_url(&url);
+        let err = provider.search("rust", 5).await.unwrap_err();
+        assert!(matches!(err, SearchError::Unauthorized));
+    }
+}
@@ -0,0 +1,241 @@
+//! Cache for `WebFetch` results
+//!
+//! Agents often fetch the same page several times in one conversation, and
+//! parallel tool calls may request it at once. Results are kept in an LRU
+//! with a TTL, and concurrent fetches with the same arguments share a single
+//! request. One cache is shared by every conversation the gateway serves.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::Result;
+
+/// Default time a fetched page is reused
+pub const DEFAULT_FETCH_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Default number of pages kept
+pub const DEFAULT_FETCH_CACHE_SIZE: usize = 100;
+
+/// Web fetch cache configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebFetchCacheConfig {
+    /// How long a fetched page is reused
+    pub ttl: Duration,
+    /// Maximum cached pages (0 disables caching, not deduplication)
+    pub capacity: usize,
+}
+
+impl Default for WebFetchCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: DEFAULT_FETCH_CACHE_TTL,
+            capacity: DEFAULT_FETCH_CACHE_SIZE,
+        }
+    }
+}
+
+impl WebFetchCacheConfig {
+    /// Load web fetch cache configuration from environment variables
+    ///
+    /// Reads from:
+    /// - `BEACON_WEB_FETCH_CACHE_TTL_SECS`: seconds a page is reused (default: 300)
+    /// - `BEACON_WEB_FETCH_CACHE_SIZE`: pages kept (default: 100, 0 disables)
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            ttl: std::env::var("BEACON_WEB_FETCH_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .map_or(defaults.ttl, Duration::from_secs),
+            capacity: std::env::var("BEACON_WEB_FETCH_CACHE_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.capacity),
+        }
+    }
+}
+
+struct CachedFetch {
+    output: String,
+    fetched_at: Instant,
+}
+
+/// LRU + TTL cache of fetch results keyed by normalized arguments, with
+/// single-flight fetches
+pub struct WebFetchCache {
+    entries: Option<Mutex<LruCache<String, CachedFetch>>>,
+    inflight: Mutex<HashMap<String, Arc<OnceCell<String>>>>,
+    ttl: Duration,
+}
+
+impl WebFetchCache {
+    /// Create a cache
+    #[must_use]
+    pub fn new(config: WebFetchCacheConfig) -> Self {
+        Self {
+            entries: NonZeroUsize::new(config.capacity).map(|n| Mutex::new(LruCache::new(n))),
+            inflight: Mutex::new(HashMap::new()),
+            ttl: config.ttl,
+        }
+    }
+
+    /// Return the cached result for `key`, or run `fetch` to produce it
+    ///
+    /// Callers arriving while a fetch with the same key is running wait for
+    /// it instead of starting their own. Failures are not cached, so `fetch`
+    /// must return tool errors as `Err`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from `fetch`
+    pub async fn get_or_fetch<F, Fut>(&self, key: &str, fetch: F) -> Result<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        if let Some(output) = self.cached(key).await {
+            return Ok(output);
+        }
+
+        let cell = Arc::clone(
+            self.inflight
+                .lock()
+                .await
+                .entry(key.to_string())
+                .or_default(),
+        );
+        let result = cell.get_or_try_init(fetch).await.cloned();
+
+        // Store before clearing the in-flight entry so no caller misses both
+        if let Ok(ref output) = result
+            && let Some(ref entries) = self.entries
+        {
+            entries.lock().await.put(
+                key.to_string(),
+                CachedFetch {
+                    output: output.clone(),
+                    fetched_at: Instant::now(),
+                },
+            );
+        }
+        let mut inflight = self.inflight.lock().await;
+        if inflight.get(key).is_some_and(|c| Arc::ptr_eq(c, &cell)) {
+            inflight.remove(key);
+        }
+
+        result
+    }
+
+    /// Fresh cached result for `key`, evicting it once expired
+    async fn cached(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries.as_ref()?.lock().await;
+        let fresh = entries.get(key)?.fetched_at.elapsed() < self.ttl;
+        if fresh {
+            entries.get(key).map(|e| e.output.clone())
+        } else {
+            entries.pop(key);
+            None
+        }
+    }
+}
+
+impl Default for WebFetchCache {
+    fn default() -> Self {
+        Self::new(WebFetchCacheConfig::default())
+    }
+}
+
+impl std::fmt::Debug for WebFetchCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebFetchCache")
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn reuses_results_within_ttl() {
+        let cache = WebFetchCache::new(WebFetchCacheConfig::default());
+        let calls = &AtomicUsize::new(0);
+        let fetch = || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok("page".to_string())
+        };
+
+        assert_eq!(
+            cache.get_or_fetch("https://a", fetch).await.unwrap(),
+            "page"
+        );
+        assert_eq!(
+            cache.get_or_fetch("https://a", fetch).await.unwrap(),
+            "page"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        cache.get_or_fetch("https://b", fetch).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn expired_and_failed_fetches_are_retried() {
+        let cache = WebFetchCache::new(WebFetchCacheConfig {
+            ttl: Duration::ZERO,
+            ..WebFetchCacheConfig::default()
+        });
+        let calls = &AtomicUsize::new(0);
+
+        for _ in 0..2 {
+            cache
+                .get_or_fetch("https://a", || async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Ok("page".to_string())
+                })
+                .await
+                .unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let failed = cache
+            .get_or_fetch("https://c", || async {
+                Err(crate::Error::Tool("offline".to_string()))
+            })
+            .await;
+        assert!(failed.is_err());
+        assert!(cache.inflight.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn concurrent_fetches_share_one_request() {
+        let cache = WebFetchCache::new(WebFetchCacheConfig::default());
+        let calls = &AtomicUsize::new(0);
+        let fetch = || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok("page".to_string())
+        };
+
+        let (a, b, c) = tokio::join!(
+            cache.get_or_fetch("https://a", fetch),
+            cache.get_or_fetch("https://a", fetch),
+            cache.get_or_fetch("https://a", fetch),
+        );
+
+        assert_eq!(a.unwrap(), "page");
+        assert_eq!(b.unwrap(), "page");
+        assert_eq!(c.unwrap(), "page");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,234 @@
+//! Built-in `WebFetch` tool with article extraction
+//!
+//! When selected, the gateway answers `WebFetch` calls itself instead of
+//! forwarding them to Synapse. HTML pages go through readability with the
+//! text-density fallback (see [`super::extract_with_fallback`]), and the
+//! strategy that produced the body is reported alongside it.
+
+use std::time::Duration;
+
+use agent_core::tools::{ToolKind, ToolProvider};
+use serde::Deserialize;
+
+use super::readability::{ExtractionStrategy, extract_with_fallback, text_density_extract};
+use crate::links::{AddressGuard, DEFAULT_BLOCKED_RANGES};
+use crate::{Error, Result};
+
+/// Maximum redirects followed per fetch
+const MAX_REDIRECTS: usize = 10;
+
+/// Largest response body read, in bytes
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Built-in `WebFetch` tool
+#[derive(Clone)]
+pub struct BuiltinFetchTool {
+    client: reqwest::Client,
+    guard: AddressGuard,
+}
+
+impl std::fmt::Debug for BuiltinFetchTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BuiltinFetchTool").finish_non_exhaustive()
+    }
+}
+
+impl BuiltinFetchTool {
+    /// Create the tool, refusing hosts in `blocked_ranges` (CIDR strings)
+    #[must_use]
+    pub fn new(blocked_ranges: &[String]) -> Self {
+        let guard = AddressGuard::new(blocked_ranges);
+        Self {
+            client: guard
+                .client_builder(MAX_REDIRECTS)
+                .timeout(Duration::from_secs(15))
+                .user_agent("Mozilla/5.0 (compatible; BeaconBot/1.0)")
+                .build()
+                .unwrap_or_default(),
+            guard,
+        }
+    }
+
+    /// Build the tool from environment variables, if selected
+    ///
+    /// Reads `BEACON_FETCH_PROVIDER`: `builtin` to answer fetches locally;
+    /// any other value keeps Synapse's `WebFetch` (default: Synapse).
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let provider = std::env::var("BEACON_FETCH_PROVIDER").ok()?;
+        (provider == "builtin").then(|| {
+            let ranges: Vec<String> = DEFAULT_BLOCKED_RANGES
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            Self::new(&ranges)
+        })
+    }
+
+    /// Execute the `WebFetch` tool from LLM JSON arguments
+    ///
+    /// # Errors
+    ///
+    /// Returns error if arguments are malformed, the URL is blocked, or the
+    /// fetch fails
+    pub async fn execute(&self, name: &str, arguments: &str) -> Result<String> {
+        #[derive(Deserialize)]
+        struct FetchArgs {
+            url: Option<String>,
+        }
+
+        if name != "WebFetch" {
+            return Err(Error::Tool(format!("unknown fetch tool: {name}")));
+        }
+
+        let args: FetchArgs = serde_json::from_str(arguments)
+            .map_err(|e| Error::Tool(format!("WebFetch: invalid arguments: {e}")))?;
+        let url = args
+            .url
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty())
+            .ok_or_else(|| Error::Tool("WebFetch: `url` parameter is required".to_string()))?;
+
+        self.guard
+            .check_url(&url)
+            .await
+            .map_err(|e| Error::WebFetch(e.to_string()))?;
+        let (final_url, is_html, body) = self
+            .fetch(&url)
+            .await
+            .map_err(|e| Error::WebFetch(format!("{url}: {e}")))?;
+
+        if !is_html {
+            return Ok(serde_json::to_string(&serde_json::json!({
+                "url": final_url,
+                "content": body,
+            }))?);
+        }
+
+        let (content, strategy) = match extract_with_fallback(&body, &final_url) {
+            Some(extraction) => (extraction.article.content, Some(extraction.strategy)),
+            None => match text_density_extract(&body) {
+                Some(dense) => (dense, Some(ExtractionStrategy::TextDensity)),
+                None => (String::new(), None),
+            },
+        };
+        tracing::debug!(url = %final_url, ?strategy, chars = content.len(), "fetched article");
+
+        Ok(serde_json::to_string(&serde_json::json!({
+            "url": final_url,
+            "content": content,
+            "strategy": strategy,
+        }))?)
+    }
+
+    /// Fetch a page, returning its final URL, whether it is HTML, and the body
+    async fn fetch(&self, url: &str) -> reqwest::Result<(String, bool, String)> {
+        let mut response = self.client.get(url).send().await?.error_for_status()?;
+        let final_url = response.url().to_string();
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_none_or(|ct| ct.contains("html"));
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            let room = MAX_BODY_BYTES - body.len();
+            body.extend_from_slice(&chunk[..chunk.len().min(room)]);
+            if body.len() >= MAX_BODY_BYTES {
+                break;
+            }
+        }
+        Ok((
+            final_url,
+            is_html,
+            String::from_utf8_lossy(&body).into_owned(),
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolProvider for BuiltinFetchTool {
+    fn definitions(&self) -> Vec<agent_core::types::Tool> {
+        vec![agent_core::types::Tool {
+            name: "WebFetch".to_string(),
+            description: "Fetch a web page and return its main article text.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "The URL to fetch"
+                    }
+                },
+                "required": ["url"]
+            }),
+        }]
+    }
+
+    async fn execute(&self, name: &str, arguments: &str) -> anyhow::Result<String> {
+        Self::execute(self, name, arguments)
+            .await
+            .map_err(|e| anyhow::anyhow!("{e}"))
+    }
+
+    fn kind(&self, _name: &str) -> ToolKind {
+        ToolKind::Read
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::response::Html;
+    use axum::routing::get;
+
+    use super::*;
+
+    async fn page() -> Html<String> {
+        let paragraph = "Rust gives you memory safety without a garbage collector. ";
+        Html(format!(
+            r#"<html><body>
+            <nav><p>Home</p><p>About</p></nav>
+            <div id="app"><div class="post"><p>{}</p><p>{}</p></div></div>
+            </body></html>"#,
+            paragraph.repeat(4),
+            paragraph.repeat(4),
+        ))
+    }
+
+    #[tokio::test]
+    async fn fetch_extracts_the_article_body() {
+        let base = crate::test_support::serve(Router::new().route("/post", get(page))).await;
+        let tool = BuiltinFetchTool::new(&[]);
+
+        let output = tool
+            .execute(
+                "WebFetch",
+                &serde_json::json!({ "url": format!("{base}/post") }).to_string(),
+            )
+            .await
+            .unwrap();
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert!(
+            result["content"]
+                .as_str()
+                .unwrap()
+                .contains("Rust gives you memory safety")
+        );
+        assert!(result["strategy"].is_string());
+    }
+
+    #[tokio::test]
+    async fn fetch_refuses_blocked_hosts() {
+        let tool = BuiltinFetchTool::new(&["127.0.0.0/8".to_string()]);
+        let err = tool
+            .execute("WebFetch", r#"{"url": "http://127.0.0.1:9/"}"#)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::WebFetch(_)));
+
+        assert!(tool.execute("WebFetch", "{}").await.is_err());
+    }
+}
@@ -1,5 +1,16 @@
 //! Web tools for HTTP operations
 
+mod cache;
+mod fetch;
+mod readability;
+
+pub use cache::{WebFetchCache, WebFetchCacheConfig};
+pub use fetch::BuiltinFetchTool;
+pub use readability::{
+    ArticleExtraction, ExtractionStrategy, MIN_ARTICLE_CHARS, extract_with_fallback,
+    text_density_extract,
+};
+
 pub use agent_core::tools::web::fetch::{WebFetchTool, WebResponse};
 pub use agent_core::tools::web::readability::{Article, extract_article};
 pub use agent_core::tools::web::search::{SearchProvider, SearchResult, WebSearchTool};
@@ -0,0 +1,177 @@
+//! Fallback for article extraction on pages readability handles poorly
+//!
+//! Readability scoring works well on classic article markup but can come
+//! back nearly empty on JS-heavy pages whose text sits in generic containers.
+//! When the readability body is too short, the block holding the most
+//! paragraph text is used instead.
+
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+
+use super::{Article, extract_article};
+
+/// Readability bodies shorter than this trigger the fallback
+pub const MIN_ARTICLE_CHARS: usize = 250;
+
+/// Elements that can hold an article body
+const CANDIDATE_BLOCKS: &str = "article, main, section, div, td, body";
+
+/// Children whose text counts toward a block's score
+const TEXT_CHILDREN: &[&str] = &["p", "pre", "blockquote", "li", "h2", "h3", "h4"];
+
+/// Containers that hold navigation rather than content
+const BOILERPLATE: &[&str] = &["nav", "header", "footer", "aside", "form"];
+
+/// Heuristic that produced an article body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionStrategy {
+    /// Readability scoring (the primary path)
+    Readability,
+    /// Block with the most non-link paragraph text
+    TextDensity,
+}
+
+/// Readability [`Article`] with the strategy that produced its body
+#[derive(Debug)]
+pub struct ArticleExtraction {
+    pub article: Article,
+    pub strategy: ExtractionStrategy,
+}
+
+/// Extract the article, replacing a body shorter than [`MIN_ARTICLE_CHARS`]
+/// with the densest text block
+///
+/// Returns None when readability finds no article at all; callers can still
+/// try [`text_density_extract`] on the page.
+#[must_use]
+pub fn extract_with_fallback(html: &str, url: &str) -> Option<ArticleExtraction> {
+    let mut article = extract_article(html, url)?;
+    let (content, strategy) = choose_body(html, &article.content);
+    article.content = content;
+    Some(ArticleExtraction { article, strategy })
+}
+
+/// Pick the article body, falling back to text density when the readability
+/// body is too short
+///
+/// The readability body is kept whenever it is long enough, or when the
+/// fallback finds nothing longer.
+fn choose_body(html: &str, readability: &str) -> (String, ExtractionStrategy) {
+    let primary = readability.trim();
+    if primary.chars().count() >= MIN_ARTICLE_CHARS {
+        return (primary.to_string(), ExtractionStrategy::Readability);
+    }
+
+    match text_density_extract(html) {
+        Some(dense) if dense.chars().count() > primary.chars().count() => {
+            (dense, ExtractionStrategy::TextDensity)
+        }
+        _ => (primary.to_string(), ExtractionStrategy::Readability),
+    }
+}
+
+/// Text of the block with the most paragraph text outside links
+///
+/// Blocks are scored on their direct paragraph-like children only, so the
+/// container actually holding the text wins over its ancestors.
+#[must_use]
+pub fn text_density_extract(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse(CANDIDATE_BLOCKS).ok()?;
+    let links = Selector::parse("a").ok()?;
+
+    document
+        .select(&selector)
+        .filter(|block| !in_boilerplate(*block))
+        .filter_map(|block| {
+            let mut paragraphs = Vec::new();
+            let mut score = 0usize;
+            for child in block.children() {
+                let (text, link_chars) = if let Some(text) = child.value().as_text() {
+                    (normalize_whitespace(text), 0)
+                } else if let Some(el) = ElementRef::wrap(child)
+                    && TEXT_CHILDREN.contains(&el.value().name())
+                {
+                    let link_chars: usize = el
+                        .select(&links)
+                        .map(|a| normalize_whitespace(&a.text().collect::<String>()).len())
+                        .sum();
+                    (
+                        normalize_whitespace(&el.text().collect::<String>()),
+                        link_chars,
+                    )
+                } else {
+                    continue;
+                };
+                if !text.is_empty() {
+                    score += text.len().saturating_sub(link_chars);
+                    paragraphs.push(text);
+                }
+            }
+            (score > 0).then(|| (score, paragraphs.join("\n\n")))
+        })
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, text)| text)
+}
+
+/// Whether the element sits inside navigation or other page chrome
+fn in_boilerplate(element: ElementRef<'_>) -> bool {
+    std::iter::once(*element)
+        .chain(element.ancestors())
+        .filter_map(ElementRef::wrap)
+        .any(|el| BOILERPLATE.contains(&el.value().name()))
+}
+
+/// Collapse runs of whitespace into single spaces
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page() -> String {
+        let paragraph = "Rust gives you memory safety without a garbage collector. ";
+        format!(
+            r#"<html><body>
+            <nav><p>Home</p><p>About</p><p>Contact</p></nav>
+            <div id="app"><div class="post">
+              <p>{}</p><p>{}</p><p>Read <a href="/more">the rest of the story</a>.</p>
+            </div></div>
+            <footer><p>Copyright</p></footer>
+            <script>window.__STATE__ = {{}}</script>
+            </body></html>"#,
+            paragraph.repeat(4),
+            paragraph.repeat(4),
+        )
+    }
+
+    #[test]
+    fn keeps_readability_when_long_enough() {
+        let body = "word ".repeat(100);
+        let (content, strategy) = choose_body(&page(), &body);
+        assert_eq!(strategy, ExtractionStrategy::Readability);
+        assert_eq!(content, body.trim());
+    }
+
+    #[test]
+    fn falls_back_to_densest_block() {
+        let (content, strategy) = choose_body(&page(), "Loading…");
+
+        assert_eq!(strategy, ExtractionStrategy::TextDensity);
+        assert!(content.starts_with("Rust gives you memory safety"));
+        assert!(content.contains("the rest of the story"));
+        assert!(!content.contains("Home"));
+        assert!(!content.contains("__STATE__"));
+    }
+
+    #[test]
+    fn keeps_short_readability_on_empty_pages() {
+        assert_eq!(text_density_extract("<html><body></body></html>"), None);
+        let (content, strategy) = choose_body("<html></html>", "Short");
+        assert_eq!(strategy, ExtractionStrategy::Readability);
+        assert_eq!(content, "Short");
+    }
+}
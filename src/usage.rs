@@ -0,0 +1,268 @@
+//! Local daily usage caps
+//!
+//! Self-hosted gateways often want a simple per-user budget without Aether.
+//! Tokens are tallied per user per UTC day in the local database; once a
+//! user's tally reaches the cap, channel handlers reply with a notice and skip
+//! the agent until the day rolls over. Admins can override the cap per user.
+
+use chrono::{DateTime, Utc};
+
+use crate::db::{DbPool, UsageLimit, UsageRepo};
+
+/// Notice sent when a user has reached their daily cap
+pub const DEFAULT_DAILY_LIMIT_MESSAGE: &str =
+    "You've reached your daily usage limit. It resets at midnight UTC.";
+
+/// Daily usage cap configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageCapConfig {
+    /// Tokens per user per day (`None` = unlimited)
+    pub daily_tokens: Option<u64>,
+    /// Estimated spend per user per day in USD (requires `usd_per_million_tokens`)
+    pub daily_cost_usd: Option<f64>,
+    /// Blended price used to estimate spend from tokens
+    pub usd_per_million_tokens: Option<f64>,
+    /// Reply sent once the cap is reached
+    pub message: String,
+}
+
+impl Default for UsageCapConfig {
+    fn default() -> Self {
+        Self {
+            daily_tokens: None,
+            daily_cost_usd: None,
+            usd_per_million_tokens: None,
+            message: DEFAULT_DAILY_LIMIT_MESSAGE.to_string(),
+        }
+    }
+}
+
+/// Whether a user may start another agent turn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsageDecision {
+    /// Under the cap (or no cap applies)
+    Allowed,
+    /// Cap reached; reply with this notice and skip the agent
+    LimitReached(String),
+}
+
+/// Per-user daily caps backed by the usage ledger
+#[derive(Debug, Clone)]
+pub struct UsageCap {
+    repo: UsageRepo,
+    config: UsageCapConfig,
+}
+
+impl UsageCap {
+    /// Create from configuration
+    #[must_use]
+    pub const fn new(pool: DbPool, config: UsageCapConfig) -> Self {
+        Self {
+            repo: UsageRepo::new(pool),
+            config,
+        }
+    }
+
+    /// Usage ledger (for admin overrides)
+    #[must_use]
+    pub const fn repo(&self) -> &UsageRepo {
+        &self.repo
+    }
+
+    /// Check whether `user_id` may start a turn now
+    #[must_use]
+    pub fn check(&self, user_id: &str) -> UsageDecision {
+        self.check_at(user_id, Utc::now())
+    }
+
+    /// Check whether `user_id` may start a turn at `now`
+    ///
+    /// A per-user override replaces the global caps. Ledger errors fail open.
+    #[must_use]
+    pub fn check_at(&self, user_id: &str, now: DateTime<Utc>) -> UsageDecision {
+        let (token_cap, cost_cap) = match self.repo.get_limit(user_id) {
+            Ok(Some(limit)) => (limit.daily_tokens, None),
+            Ok(None) => (self.config.daily_tokens, self.config.daily_cost_usd),
+            Err(e) => {
+                tracing::warn!(error = %e, "usage limit lookup failed");
+                return UsageDecision::Allowed;
+            }
+        };
+        let cost_cap = cost_cap.zip(self.config.usd_per_million_tokens);
+        if token_cap.is_none() && cost_cap.is_none() {
+            return UsageDecision::Allowed;
+        }
+
+        let used = match self.repo.tokens_on(user_id, &day_key(now)) {
+            Ok(used) => used,
+            Err(e) => {
+                tracing::warn!(error = %e, "usage lookup failed");
+                return UsageDecision::Allowed;
+            }
+        };
+
+        #[allow(clippy::cast_precision_loss)]
+        let over_cost =
+            cost_cap.is_some_and(|(cap, price)| used as f64 * price / 1_000_000.0 >= cap);
+        if token_cap.is_some_and(|cap| used >= cap) || over_cost {
+            tracing::info!(user_id, used, "daily usage cap reached");
+            UsageDecision::LimitReached(self.config.message.clone())
+        } else {
+            UsageDecision::Allowed
+        }
+    }
+
+    /// Add a completed turn's tokens to today's tally
+    pub fn record(&self, user_id: &str, tokens: u64) {
+        self.record_at(user_id, tokens, Utc::now());
+    }
+
+    /// Add tokens to the tally for the day containing `now`
+    pub fn record_at(&self, user_id: &str, tokens: u64, now: DateTime<Utc>) {
+        if tokens == 0 {
+            return;
+        }
+        if let Err(e) = self.repo.add_tokens(user_id, &day_key(now), tokens) {
+            tracing::warn!(error = %e, "failed to record usage");
+        }
+    }
+
+    /// Tokens used by `user_id` so far today (UTC)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn tokens_today(&self, user_id: &str) -> crate::Result<u64> {
+        self.repo.tokens_on(user_id, &day_key(Utc::now()))
+    }
+
+    /// Set or clear (`None`) a user's daily token override
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operation fails
+    pub fn set_override(&self, user_id: &str, limit: Option<UsageLimit>) -> crate::Result<()> {
+        match limit {
+            Some(limit) => self.repo.set_limit(user_id, limit),
+            None => self.repo.delete_limit(user_id).map(|_| ()),
+        }
+    }
+}
+
+/// Tokens to record for a turn: provider-reported usage, or an estimate
+///
+/// Non-streaming responses and some providers report no usage, in which case
+/// prompt and reply are estimated at ~4 characters per token.
+#[must_use]
+pub fn turn_tokens(reported: u64, prompt: &str, reply: &str) -> u64 {
+    if reported > 0 {
+        return reported;
+    }
+    let estimate = crate::tools::output::estimate_tokens(prompt)
+        + crate::tools::output::estimate_tokens(reply);
+    u64::try_from(estimate).unwrap_or(u64::MAX)
+}
+
+/// Ledger key for the UTC day containing `now`
+fn day_key(now: DateTime<Utc>) -> String {
+    now.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::db;
+
+    fn cap(config: UsageCapConfig) -> UsageCap {
+        UsageCap::new(db::init_memory().unwrap(), config)
+    }
+
+    fn token_cap(tokens: u64) -> UsageCapConfig {
+        UsageCapConfig {
+            daily_tokens: Some(tokens),
+            ..UsageCapConfig::default()
+        }
+    }
+
+    #[test]
+    fn under_cap_is_allowed() {
+        let cap = cap(token_cap(1000));
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+
+        assert_eq!(cap.check_at("u1", now), UsageDecision::Allowed);
+        cap.record_at("u1", 999, now);
+        assert_eq!(cap.check_at("u1", now), UsageDecision::Allowed);
+    }
+
+    #[test]
+    fn over_cap_is_rejected_until_the_day_resets() {
+        let cap = cap(token_cap(1000));
+        let late = Utc.with_ymd_and_hms(2026, 3, 1, 23, 59, 59).unwrap();
+        let midnight = Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap();
+
+        cap.record_at("u1", 1000, late);
+        assert_eq!(
+            cap.check_at("u1", late),
+            UsageDecision::LimitReached(DEFAULT_DAILY_LIMIT_MESSAGE.to_string())
+        );
+        // Other users are unaffected
+        assert_eq!(cap.check_at("u2", late), UsageDecision::Allowed);
+        // New UTC day, fresh tally
+        assert_eq!(cap.check_at("u1", midnight), UsageDecision::Allowed);
+    }
+
+    #[test]
+    fn cost_cap_uses_configured_price() {
+        let cap = cap(UsageCapConfig {
+            daily_cost_usd: Some(1.0),
+            usd_per_million_tokens: Some(10.0),
+            ..UsageCapConfig::default()
+        });
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+
+        cap.record_at("u1", 99_999, now);
+        assert_eq!(cap.check_at("u1", now), UsageDecision::Allowed);
+        cap.record_at("u1", 1, now);
+        assert!(matches!(
+            cap.check_at("u1", now),
+            UsageDecision::LimitReached(_)
+        ));
+    }
+
+    #[test]
+    fn override_replaces_global_cap() {
+        let cap = cap(token_cap(100));
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        cap.record_at("vip", 500, now);
+        cap.record_at("trial", 20, now);
+
+        cap.set_override("vip", Some(UsageLimit { daily_tokens: None }))
+            .unwrap();
+        cap.set_override(
+            "trial",
+            Some(UsageLimit {
+                daily_tokens: Some(10),
+            }),
+        )
+        .unwrap();
+        assert_eq!(cap.check_at("vip", now), UsageDecision::Allowed);
+        assert!(matches!(
+            cap.check_at("trial", now),
+            UsageDecision::LimitReached(_)
+        ));
+
+        cap.set_override("vip", None).unwrap();
+        assert!(matches!(
+            cap.check_at("vip", now),
+            UsageDecision::LimitReached(_)
+        ));
+    }
+
+    #[test]
+    fn turn_tokens_falls_back_to_estimate() {
+        assert_eq!(turn_tokens(42, "ignored", "ignored"), 42);
+        assert_eq!(turn_tokens(0, "abcd", "abcdefgh"), 3);
+    }
+}
@@ -149,6 +149,16 @@ impl AudioCapture {
     }
 }
 
+/// Convert f32 samples in [-1.0, 1.0] to 16-bit PCM
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn samples_to_pcm16(samples: &[f32]) -> Vec<i16> {
+    samples
+        .iter()
+        .map(|&sample| (sample * 32767.0).clamp(-32768.0, 32767.0) as i16)
+        .collect()
+}
+
 /// Convert f32 samples to WAV bytes for STT APIs
 ///
 /// # Errors
@@ -167,12 +177,9 @@ pub fn samples_to_wav(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
         let mut writer =
             hound::WavWriter::new(&mut cursor, spec).map_err(|e| Error::Audio(e.to_string()))?;
 
-        for &sample in samples {
-            // Convert f32 [-1.0, 1.0] to i16
-            #[allow(clippy::cast_possible_truncation)]
-            let sample_i16 = (sample * 32767.0).clamp(-32768.0, 32767.0) as i16;
+        for sample in samples_to_pcm16(samples) {
             writer
-                .write_sample(sample_i16)
+                .write_sample(sample)
                 .map_err(|e| Error::Audio(e.to_string()))?;
         }
 
@@ -1,12 +1,18 @@
 //! Voice processing module
 //!
-//! Handles audio capture, wake word detection, and playback.
-//! STT and TTS are routed through Synapse (see `daemon.rs`)
+//! Handles audio capture, wake word detection, speech-to-text,
+//! text-to-speech, and playback.
 
 mod capture;
 mod playback;
+mod stt;
+mod tts;
+mod turn;
 mod wake_word;
 
-pub use capture::{AudioCapture, SAMPLE_RATE, samples_to_wav};
+pub use capture::{AudioCapture, SAMPLE_RATE, samples_to_pcm16, samples_to_wav};
 pub use playback::AudioPlayback;
-pub use wake_word::{DetectorState, WakeWordDetector};
+pub use stt::{PartialTranscript, SpeechToText};
+pub use tts::{SpeechAudio, TextToSpeech, TtsBackend};
+pub use turn::{MAX_QUEUED_TURNS, OverlapPolicy, TurnAdmission, VoiceTurnGuard};
+pub use wake_word::{DetectorState, PORCUPINE_FRAME_LENGTH, WakeWordBackend, WakeWordDetector};
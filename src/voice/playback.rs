@@ -1,6 +1,7 @@
 //! Audio playback to speakers
 
 use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
@@ -16,6 +17,7 @@ pub struct AudioPlayback {
     #[allow(dead_code)]
     device: Device,
     config: StreamConfig,
+    playing: Arc<AtomicBool>,
 }
 
 impl AudioPlayback {
@@ -60,7 +62,17 @@ impl AudioPlayback {
             "audio playback initialized"
         );
 
-        Ok(Self { device, config })
+        Ok(Self {
+            device,
+            config,
+            playing: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Whether audio is currently being played
+    #[must_use]
+    pub fn is_playing(&self) -> bool {
+        self.playing.load(Ordering::Acquire)
     }
 
     /// Play audio samples (f32 format)
@@ -84,12 +96,30 @@ impl AudioPlayback {
         self.play_samples_blocking(samples)
     }
 
+    /// Play audio from WAV bytes, resampling to the playback rate
+    ///
+    /// # Errors
+    ///
+    /// Returns error if decoding or playback fails
+    pub async fn play_wav(&mut self, wav_data: &[u8]) -> Result<()> {
+        let samples = decode_wav(wav_data)?;
+        self.play(samples).await
+    }
+
     /// Play samples in a blocking manner
     fn play_samples_blocking(&self, samples: Vec<f32>) -> Result<()> {
         if samples.is_empty() {
             return Ok(());
         }
 
+        self.playing.store(true, Ordering::Release);
+        let result = self.play_stream(samples);
+        self.playing.store(false, Ordering::Release);
+        result
+    }
+
+    /// Build an output stream and block until `samples` have been played
+    fn play_stream(&self, samples: Vec<f32>) -> Result<()> {
         let host = cpal::default_host();
         let device = host
             .default_output_device()
@@ -164,6 +194,63 @@ impl AudioPlayback {
     }
 }
 
+/// Decode WAV bytes to mono f32 samples at the playback rate
+fn decode_wav(wav_data: &[u8]) -> Result<Vec<f32>> {
+    let mut reader = hound::WavReader::new(Cursor::new(wav_data))
+        .map_err(|e| Error::Audio(format!("WAV decode error: {e}")))?;
+    let spec = reader.spec();
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<std::result::Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            #[allow(clippy::cast_precision_loss)]
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect()
+        }
+    }
+    .map_err(|e| Error::Audio(format!("WAV decode error: {e}")))?;
+
+    // Downmix to mono
+    let channels = usize::from(spec.channels.max(1));
+    #[allow(clippy::cast_precision_loss)]
+    let mono: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok(resample(&mono, spec.sample_rate, PLAYBACK_SAMPLE_RATE))
+}
+
+/// Linearly resample mono audio
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || from_rate == 0 || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = f64::from(from_rate) / f64::from(to_rate);
+    let out_len = (samples.len() as f64 / ratio) as usize;
+    (0..out_len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let idx = pos as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = samples[idx.min(samples.len() - 1)];
+            let b = samples[(idx + 1).min(samples.len() - 1)];
+            (b - a).mul_add(frac, a)
+        })
+        .collect()
+}
+
 /// Decode MP3 bytes to f32 samples
 fn decode_mp3(mp3_data: &[u8]) -> Result<Vec<f32>> {
     let mut decoder = minimp3::Decoder::new(Cursor::new(mp3_data));
@@ -0,0 +1,366 @@
+//! Speech-to-text for the voice loop
+//!
+//! Clips are transcribed via Synapse, or via Whisper directly when a language
+//! hint is configured (Synapse transcription takes no language parameter).
+//!
+//! [`SpeechToText::stream`] transcribes audio while it is still being spoken:
+//! each second of new audio is transcribed on its own and appended to the
+//! segment's text to produce interim results, and a final transcript is
+//! emitted as soon as trailing silence ends the segment, so the caller can act
+//! without waiting for a full utterance buffer. Only audio not yet transcribed
+//! is uploaded, so a segment costs one pass over its audio.
+//!
+//! The Synapse client exposes no realtime transcription session, so streaming
+//! is built on clip transcription rather than a realtime socket.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use synapse_client::SynapseClient;
+use tokio::sync::mpsc;
+
+use super::capture::{SAMPLE_RATE, samples_to_wav};
+use super::wake_word::{ENERGY_THRESHOLD, SILENCE_SAMPLES, calculate_energy};
+use crate::media::providers::WhisperProvider;
+use crate::{Error, Result};
+
+/// Audio chunks buffered ahead of the transcription task
+const AUDIO_CHANNEL_CAPACITY: usize = 64;
+
+/// Transcripts buffered for the receiver before interim results are coalesced
+const TRANSCRIPT_CHANNEL_CAPACITY: usize = 4;
+
+/// New audio between interim transcriptions (in samples)
+const INTERIM_INTERVAL_SAMPLES: usize = SAMPLE_RATE as usize; // 1 second
+
+/// A transcript of the segment being spoken
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialTranscript {
+    /// Transcript of the segment so far
+    pub text: String,
+    /// Whether the segment has ended; interim results may still change
+    pub is_final: bool,
+}
+
+/// Speech-to-text client for the voice loop
+#[derive(Clone)]
+pub struct SpeechToText {
+    synapse: Arc<SynapseClient>,
+    whisper: Option<Arc<WhisperProvider>>,
+    model: String,
+    language: Option<String>,
+}
+
+impl SpeechToText {
+    /// Create a client that transcribes via Synapse with `model`
+    #[must_use]
+    pub const fn new(synapse: Arc<SynapseClient>, model: String) -> Self {
+        Self {
+            synapse,
+            whisper: None,
+            model,
+            language: None,
+        }
+    }
+
+    /// Transcribe via Whisper with a language hint
+    ///
+    /// Ignored when either is missing, since Synapse auto-detects anyway.
+    #[must_use]
+    pub fn with_language(
+        mut self,
+        whisper: Option<Arc<WhisperProvider>>,
+        language: Option<String>,
+    ) -> Self {
+        if whisper.is_some() && language.is_some() {
+            self.whisper = whisper;
+            self.language = language;
+        }
+        self
+    }
+
+    /// Transcribe a WAV clip
+    ///
+    /// # Errors
+    ///
+    /// Returns error if transcription fails
+    pub async fn transcribe(&self, wav: Vec<u8>) -> Result<String> {
+        if let Some(whisper) = &self.whisper {
+            return whisper
+                .transcribe(&wav, "audio/wav", self.language.as_deref())
+                .await;
+        }
+        self.synapse
+            .transcribe(wav.into(), "audio.wav", &self.model)
+            .await
+            .map(|result| result.text)
+            .map_err(|e| Error::Stt(e.to_string()))
+    }
+
+    /// Start a streaming transcription session
+    ///
+    /// Send [`SAMPLE_RATE`] mono chunks to the returned sender and read
+    /// transcripts from the receiver. Each segment of speech yields interim
+    /// results followed by one final result. If the receiver lags, older
+    /// interim results are dropped in favor of newer ones; finals are never
+    /// dropped. Dropping the sender ends the session, finalizing any speech
+    /// still in progress.
+    ///
+    /// Must be called within a Tokio runtime.
+    #[must_use]
+    pub fn stream(&self) -> (mpsc::Sender<Vec<f32>>, mpsc::Receiver<PartialTranscript>) {
+        let (audio_tx, audio_rx) = mpsc::channel(AUDIO_CHANNEL_CAPACITY);
+        let (transcript_tx, transcript_rx) = mpsc::channel(TRANSCRIPT_CHANNEL_CAPACITY);
+        tokio::spawn(self.clone().run_stream(audio_rx, transcript_tx));
+        (audio_tx, transcript_rx)
+    }
+
+    /// Deliver transcripts to the receiver, coalescing interims while it lags
+    ///
+    /// Transcription runs in its own task so a slow upload never holds up
+    /// delivery, and a slow receiver never holds up transcription.
+    async fn run_stream(
+        self,
+        audio_rx: mpsc::Receiver<Vec<f32>>,
+        transcript_tx: mpsc::Sender<PartialTranscript>,
+    ) {
+        let (result_tx, mut result_rx) = mpsc::channel(TRANSCRIPT_CHANNEL_CAPACITY);
+        tokio::spawn(self.transcribe_segments(audio_rx, result_tx));
+
+        let mut pending = TranscriptQueue::default();
+        let mut transcribing = true;
+        loop {
+            tokio::select! {
+                result = result_rx.recv(), if transcribing => match result {
+                    Some(transcript) => pending.push(transcript),
+                    None => transcribing = false,
+                },
+                permit = transcript_tx.reserve(), if !pending.is_empty() => {
+                    let Ok(permit) = permit else {
+                        return;
+                    };
+                    if let Some(transcript) = pending.pop() {
+                        permit.send(transcript);
+                    }
+                }
+                else => break,
+            }
+        }
+    }
+
+    /// Split incoming audio into segments and transcribe each new stretch
+    async fn transcribe_segments(
+        self,
+        mut audio_rx: mpsc::Receiver<Vec<f32>>,
+        result_tx: mpsc::Sender<PartialTranscript>,
+    ) {
+        let mut segment = Segment::default();
+
+        while let Some(chunk) = audio_rx.recv().await {
+            let is_final = match segment.push(&chunk) {
+                SegmentEvent::Pending => continue,
+                SegmentEvent::Interim => false,
+                SegmentEvent::Final => true,
+            };
+            if let Some(transcript) = self.transcribe_delta(&mut segment, is_final).await
+                && result_tx.send(transcript).await.is_err()
+            {
+                return;
+            }
+        }
+
+        // Input closed: finalize whatever is still being spoken
+        if segment.heard_speech
+            && let Some(transcript) = self.transcribe_delta(&mut segment, true).await
+        {
+            let _ = result_tx.send(transcript).await;
+        }
+    }
+
+    /// Transcribe the segment's untranscribed audio and report its text so far
+    async fn transcribe_delta(
+        &self,
+        segment: &mut Segment,
+        is_final: bool,
+    ) -> Option<PartialTranscript> {
+        let delta = std::mem::take(&mut segment.samples);
+        if let Some(text) = self.transcribe_samples(&delta).await {
+            segment.append_text(&text);
+        }
+        let text = if is_final {
+            segment.finish()
+        } else {
+            segment.text.clone()
+        };
+        (!text.is_empty()).then_some(PartialTranscript { text, is_final })
+    }
+
+    async fn transcribe_samples(&self, samples: &[f32]) -> Option<String> {
+        let result = match samples_to_wav(samples, SAMPLE_RATE) {
+            Ok(wav) => self.transcribe(wav).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(text) if !text.trim().is_empty() => Some(text.trim().to_string()),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!(error = %e, "streaming STT failed");
+                None
+            }
+        }
+    }
+}
+
+/// What a chunk did to the open segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SegmentEvent {
+    /// Nothing to transcribe yet
+    Pending,
+    /// Enough new audio for an interim transcript
+    Interim,
+    /// Trailing silence ended the segment
+    Final,
+}
+
+/// The segment currently being spoken
+#[derive(Debug, Default)]
+struct Segment {
+    /// Audio not yet transcribed
+    samples: Vec<f32>,
+    /// Transcript of the audio already sent
+    text: String,
+    heard_speech: bool,
+    silence: usize,
+    since_interim: usize,
+}
+
+impl Segment {
+    fn push(&mut self, chunk: &[f32]) -> SegmentEvent {
+        let is_speech = calculate_energy(chunk) > ENERGY_THRESHOLD;
+        // Skip leading silence
+        if !self.heard_speech && !is_speech {
+            return SegmentEvent::Pending;
+        }
+
+        self.samples.extend_from_slice(chunk);
+        self.since_interim += chunk.len();
+        if is_speech {
+            self.heard_speech = true;
+            self.silence = 0;
+        } else {
+            self.silence += chunk.len();
+        }
+
+        if self.silence >= SILENCE_SAMPLES {
+            SegmentEvent::Final
+        } else if self.since_interim >= INTERIM_INTERVAL_SAMPLES {
+            self.since_interim = 0;
+            SegmentEvent::Interim
+        } else {
+            SegmentEvent::Pending
+        }
+    }
+
+    /// Append the transcript of the latest stretch of audio
+    fn append_text(&mut self, text: &str) {
+        if !self.text.is_empty() {
+            self.text.push(' ');
+        }
+        self.text.push_str(text);
+    }
+
+    /// Take the segment's transcript and start a new segment
+    fn finish(&mut self) -> String {
+        std::mem::take(self).text
+    }
+}
+
+/// Transcripts awaiting delivery; a newer result supersedes queued interims
+#[derive(Debug, Default)]
+struct TranscriptQueue {
+    queue: VecDeque<PartialTranscript>,
+}
+
+impl TranscriptQueue {
+    fn push(&mut self, transcript: PartialTranscript) {
+        self.queue.retain(|t| t.is_final);
+        self.queue.push_back(transcript);
+    }
+
+    fn pop(&mut self) -> Option<PartialTranscript> {
+        self.queue.pop_front()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interim(text: &str) -> PartialTranscript {
+        PartialTranscript {
+            text: text.to_string(),
+            is_final: false,
+        }
+    }
+
+    #[test]
+    fn segment_skips_leading_silence() {
+        let mut segment = Segment::default();
+        assert_eq!(segment.push(&[0.0; 1600]), SegmentEvent::Pending);
+        assert!(segment.samples.is_empty());
+    }
+
+    #[test]
+    fn segment_emits_interims_then_final_on_silence() {
+        let mut segment = Segment::default();
+        let speech = [0.5; 1600];
+        let silence = [0.0; 1600];
+
+        let events: Vec<_> = (0..10).map(|_| segment.push(&speech)).collect();
+        assert_eq!(events[9], SegmentEvent::Interim);
+        assert!(events[..9].iter().all(|e| *e == SegmentEvent::Pending));
+
+        let events: Vec<_> = (0..5).map(|_| segment.push(&silence)).collect();
+        assert_eq!(events[4], SegmentEvent::Final);
+
+        assert_eq!(segment.samples.len(), 15 * 1600);
+        segment.finish();
+        assert!(segment.samples.is_empty());
+        assert!(!segment.heard_speech);
+    }
+
+    #[test]
+    fn segment_accumulates_text_across_stretches() {
+        let mut segment = Segment::default();
+        segment.append_text("turn on");
+        segment.append_text("the lights");
+        assert_eq!(segment.text, "turn on the lights");
+
+        assert_eq!(segment.finish(), "turn on the lights");
+        assert!(segment.text.is_empty());
+    }
+
+    #[test]
+    fn queue_drops_superseded_interims_but_keeps_finals() {
+        let mut queue = TranscriptQueue::default();
+        queue.push(interim("turn"));
+        queue.push(interim("turn on"));
+        queue.push(PartialTranscript {
+            text: "turn on the lights".to_string(),
+            is_final: true,
+        });
+        queue.push(interim("and"));
+        queue.push(interim("and the fan"));
+
+        assert_eq!(
+            queue.pop().map(|t| t.text),
+            Some("turn on the lights".to_string())
+        );
+        assert_eq!(queue.pop(), Some(interim("and the fan")));
+        assert!(queue.is_empty());
+    }
+}
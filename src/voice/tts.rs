@@ -0,0 +1,215 @@
+//! Text-to-speech for the voice loop
+//!
+//! Speech is synthesized via Synapse. When Synapse fails (e.g. unreachable in
+//! setup mode) and an offline fallback is configured, the reply is
+//! synthesized locally instead so voice keeps working.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use synapse_client::SynapseClient;
+
+use super::playback::AudioPlayback;
+use crate::{Error, Result};
+
+/// Piper executable, resolved from `PATH`
+const PIPER_BINARY: &str = "piper";
+
+/// A speech synthesis backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TtsBackend {
+    /// Synapse TTS (MP3 output)
+    Synapse,
+    /// Local synthesis via the piper binary (WAV output)
+    Piper {
+        /// Piper `.onnx` voice model
+        model_path: PathBuf,
+        /// Model config JSON (`None` = piper looks next to the model)
+        config_path: Option<PathBuf>,
+    },
+}
+
+/// Synthesized speech, tagged with its encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpeechAudio {
+    /// MP3 bytes (Synapse)
+    Mp3(Vec<u8>),
+    /// WAV bytes (Piper)
+    Wav(Vec<u8>),
+}
+
+/// Text-to-speech client for the voice loop
+#[derive(Clone)]
+pub struct TextToSpeech {
+    synapse: Arc<SynapseClient>,
+    model: String,
+    voice: String,
+    speed: f64,
+    fallback: Option<TtsBackend>,
+}
+
+impl TextToSpeech {
+    /// Create a client that synthesizes via Synapse
+    #[must_use]
+    pub const fn new(
+        synapse: Arc<SynapseClient>,
+        model: String,
+        voice: String,
+        speed: f64,
+    ) -> Self {
+        Self {
+            synapse,
+            model,
+            voice,
+            speed,
+            fallback: None,
+        }
+    }
+
+    /// Backend to use when Synapse synthesis fails
+    #[must_use]
+    pub fn with_fallback(mut self, fallback: Option<TtsBackend>) -> Self {
+        self.fallback = fallback.filter(|backend| *backend != TtsBackend::Synapse);
+        self
+    }
+
+    /// Synthesize `text`, falling back to the offline backend on error
+    ///
+    /// # Errors
+    ///
+    /// Returns error if Synapse fails and no fallback is configured, or the
+    /// fallback fails too
+    pub async fn synthesize(&self, text: &str) -> Result<SpeechAudio> {
+        let primary = self.synthesize_synapse(text).await;
+        let (Err(e), Some(fallback)) = (&primary, &self.fallback) else {
+            return primary;
+        };
+
+        tracing::warn!(error = %e, "synapse TTS failed, using offline fallback");
+        match fallback {
+            TtsBackend::Synapse => primary,
+            TtsBackend::Piper {
+                model_path,
+                config_path,
+            } => synthesize_piper(model_path, config_path.as_ref(), text)
+                .await
+                .map(SpeechAudio::Wav),
+        }
+    }
+
+    /// Synthesize `text` and play it
+    ///
+    /// # Errors
+    ///
+    /// Returns error if synthesis or playback fails
+    pub async fn speak(&self, playback: &mut AudioPlayback, text: &str) -> Result<()> {
+        tracing::debug!(text, "speaking");
+        match self.synthesize(text).await? {
+            SpeechAudio::Mp3(audio) => playback.play_mp3(&audio).await,
+            SpeechAudio::Wav(audio) => playback.play_wav(&audio).await,
+        }
+    }
+
+    async fn synthesize_synapse(&self, text: &str) -> Result<SpeechAudio> {
+        let request = synapse_client::SpeechRequest {
+            model: self.model.clone(),
+            input: text.to_string(),
+            voice: self.voice.clone(),
+            response_format: None,
+            speed: Some(self.speed),
+        };
+        self.synapse
+            .synthesize(&request)
+            .await
+            .map(|audio| SpeechAudio::Mp3(audio.to_vec()))
+            .map_err(|e| Error::Tts(e.to_string()))
+    }
+}
+
+/// Synthesize `text` to WAV bytes with the piper binary
+async fn synthesize_piper(
+    model_path: &std::path::Path,
+    config_path: Option<&PathBuf>,
+    text: &str,
+) -> Result<Vec<u8>> {
+    use tokio::io::AsyncWriteExt as _;
+
+    let output = tempfile::Builder::new()
+        .prefix("beacon-tts-")
+        .suffix(".wav")
+        .tempfile()
+        .map_err(|e| Error::Tts(format!("failed to create piper output file: {e}")))?;
+
+    let mut child = tokio::process::Command::new(PIPER_BINARY)
+        .args(piper_args(model_path, config_path, output.path()))
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| Error::Tts(format!("failed to run piper: {e}")))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .await
+            .map_err(|e| Error::Tts(format!("failed to send text to piper: {e}")))?;
+    }
+
+    let result = child
+        .wait_with_output()
+        .await
+        .map_err(|e| Error::Tts(format!("piper failed: {e}")))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(Error::Tts(format!(
+            "piper exited with {}: {}",
+            result.status,
+            stderr.trim()
+        )));
+    }
+
+    tokio::fs::read(output.path())
+        .await
+        .map_err(|e| Error::Tts(format!("failed to read piper output: {e}")))
+}
+
+/// Command-line arguments for a piper run
+fn piper_args(
+    model_path: &std::path::Path,
+    config_path: Option<&PathBuf>,
+    output_path: &std::path::Path,
+) -> Vec<std::ffi::OsString> {
+    let mut args = vec!["--model".into(), model_path.as_os_str().to_owned()];
+    if let Some(config_path) = config_path {
+        args.extend(["--config".into(), config_path.as_os_str().to_owned()]);
+    }
+    args.extend(["--output_file".into(), output_path.as_os_str().to_owned()]);
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn piper_args_include_optional_config() {
+        let model = PathBuf::from("/voices/en_US-amy.onnx");
+        let out = PathBuf::from("/tmp/out.wav");
+
+        let args = piper_args(&model, None, &out);
+        assert_eq!(
+            args,
+            [
+                "--model",
+                "/voices/en_US-amy.onnx",
+                "--output_file",
+                "/tmp/out.wav"
+            ]
+        );
+
+        let config = PathBuf::from("/voices/amy.json");
+        let args = piper_args(&model, Some(&config), &out);
+        assert_eq!(&args[2..4], ["--config", "/voices/amy.json"]);
+    }
+}
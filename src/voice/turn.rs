@@ -0,0 +1,119 @@
+//! Single-flight guard for voice turns
+//!
+//! Only one STT → LLM → TTS turn may run at a time so spoken replies never
+//! overlap. Wake-word triggers that arrive while a turn is active are queued
+//! or dropped according to the configured policy.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Maximum number of queued commands under [`OverlapPolicy::Queue`]
+pub const MAX_QUEUED_TURNS: usize = 3;
+
+/// What to do with a trigger that arrives while a turn is active
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Ignore the new trigger
+    #[default]
+    Drop,
+    /// Run the new command after the active turn finishes
+    Queue,
+}
+
+impl OverlapPolicy {
+    /// Parse from string representation
+    #[must_use]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "queue" | "queued" => Self::Queue,
+            _ => Self::Drop,
+        }
+    }
+}
+
+/// Outcome of asking to start a voice turn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TurnAdmission {
+    /// No turn was active; run this command now
+    Start(String),
+    /// A turn is active; the command will be returned by [`VoiceTurnGuard::finish`]
+    Queued,
+    /// A turn is active and the command was discarded
+    Dropped,
+}
+
+#[derive(Debug, Default)]
+struct TurnState {
+    active: bool,
+    queue: VecDeque<String>,
+}
+
+/// Ensures at most one active voice turn
+#[derive(Debug, Default)]
+pub struct VoiceTurnGuard {
+    policy: OverlapPolicy,
+    state: Mutex<TurnState>,
+}
+
+impl VoiceTurnGuard {
+    /// Create a guard with the given overlap policy
+    #[must_use]
+    pub fn new(policy: OverlapPolicy) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(TurnState::default()),
+        }
+    }
+
+    /// Whether a turn is currently running
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.lock().active
+    }
+
+    /// Request a turn for `command`
+    pub fn admit(&self, command: String) -> TurnAdmission {
+        let mut state = self.lock();
+        if !state.active {
+            state.active = true;
+            return TurnAdmission::Start(command);
+        }
+
+        match self.policy {
+            OverlapPolicy::Queue if state.queue.len() < MAX_QUEUED_TURNS => {
+                state.queue.push_back(command);
+                tracing::debug!(queued = state.queue.len(), "voice turn queued");
+                TurnAdmission::Queued
+            }
+            _ => {
+                tracing::debug!("voice turn dropped (another turn is active)");
+                TurnAdmission::Dropped
+            }
+        }
+    }
+
+    /// Finish the active turn, returning the next queued command to run
+    ///
+    /// When a command is returned the guard stays active and the caller must
+    /// call `finish` again once that turn completes.
+    pub fn finish(&self) -> Option<String> {
+        let mut state = self.lock();
+        let next = state.queue.pop_front();
+        state.active = next.is_some();
+        next
+    }
+
+    /// End the active turn and discard anything queued behind it
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.active = false;
+        state.queue.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TurnState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
@@ -1,18 +1,26 @@
 //! Wake word detection
 //!
 //! Detects wake words in audio stream to activate the assistant.
-//! Uses a hybrid approach: local energy detection + cloud verification.
+//! The default backend is hybrid: local energy detection + cloud (STT)
+//! verification. With the `porcupine` feature, a Porcupine keyword model can
+//! spot the wake word fully offline instead.
 
-use crate::Result;
+use std::collections::VecDeque;
+use std::path::Path;
+
+use crate::{Error, Result};
+
+/// Samples per frame expected by Porcupine (at 16kHz)
+pub const PORCUPINE_FRAME_LENGTH: usize = 512;
 
 /// Minimum audio energy threshold to consider speech
-const ENERGY_THRESHOLD: f32 = 0.03;
+pub(super) const ENERGY_THRESHOLD: f32 = 0.03;
 
 /// Minimum duration of speech to trigger (in samples at 16kHz)
 const MIN_SPEECH_SAMPLES: usize = 4800; // 0.3 seconds
 
 /// Silence duration to consider end of utterance (in samples)
-const SILENCE_SAMPLES: usize = 8000; // 0.5 seconds
+pub(super) const SILENCE_SAMPLES: usize = 8000; // 0.5 seconds
 
 /// State of the wake word detector
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,16 +29,67 @@ pub enum DetectorState {
     Idle,
     /// Detected potential speech, accumulating
     Listening,
+    /// Keyword spotted locally; returned once by `process_frame`, after
+    /// which the detector is `Activated`
+    Detected,
     /// Wake word detected, capturing utterance
     Activated,
 }
 
+/// How the wake word is recognized
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WakeWordBackend {
+    /// Transcribe speech segments via STT and match the wake word as text
+    #[default]
+    Stt,
+    /// Spot the keyword offline with a Porcupine `.ppn` model
+    Porcupine,
+}
+
+impl WakeWordBackend {
+    /// Parse from string representation
+    #[must_use]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "porcupine" | "local" => Self::Porcupine,
+            _ => Self::Stt,
+        }
+    }
+}
+
+/// Regroups arbitrary-sized chunks into fixed-size frames
+#[derive(Debug, Default)]
+struct FrameBuffer {
+    pending: VecDeque<i16>,
+}
+
+impl FrameBuffer {
+    fn extend(&mut self, samples: &[i16]) {
+        self.pending.extend(samples);
+    }
+
+    /// Pop the next complete frame, if one is buffered
+    fn next_frame(&mut self) -> Option<Vec<i16>> {
+        (self.pending.len() >= PORCUPINE_FRAME_LENGTH)
+            .then(|| self.pending.drain(..PORCUPINE_FRAME_LENGTH).collect())
+    }
+
+    fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
 /// Detects wake words in audio
 pub struct WakeWordDetector {
     wake_words: Vec<String>,
     state: DetectorState,
     speech_buffer: Vec<f32>,
     silence_counter: usize,
+    backend: WakeWordBackend,
+    frames: FrameBuffer,
+    #[cfg(feature = "porcupine")]
+    porcupine: Option<porcupine::Porcupine>,
 }
 
 impl WakeWordDetector {
@@ -56,9 +115,117 @@ impl WakeWordDetector {
             state: DetectorState::Idle,
             speech_buffer: Vec::new(),
             silence_counter: 0,
+            backend: WakeWordBackend::Stt,
+            frames: FrameBuffer::default(),
+            #[cfg(feature = "porcupine")]
+            porcupine: None,
         })
     }
 
+    /// Create a detector that spots the wake word offline with Porcupine
+    ///
+    /// Reads the Picovoice access key from `PICOVOICE_ACCESS_KEY`.
+    ///
+    /// # Arguments
+    ///
+    /// * `keyword_path` - Path to the `.ppn` keyword model
+    /// * `sensitivity` - Detection sensitivity in [0.0, 1.0]; higher means fewer misses
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the access key is missing or the model cannot be loaded
+    #[cfg(feature = "porcupine")]
+    pub fn with_porcupine(keyword_path: impl AsRef<Path>, sensitivity: f32) -> Result<Self> {
+        let access_key = std::env::var("PICOVOICE_ACCESS_KEY").map_err(|_| {
+            Error::Config("PICOVOICE_ACCESS_KEY required for Porcupine wake word".to_string())
+        })?;
+        let keyword_path = keyword_path.as_ref().to_path_buf();
+        let engine = porcupine::PorcupineBuilder::new_with_keyword_paths(
+            access_key,
+            std::slice::from_ref(&keyword_path),
+        )
+        .sensitivities(&[sensitivity.clamp(0.0, 1.0)])
+        .init()
+        .map_err(|e| Error::Config(format!("failed to load Porcupine model: {e}")))?;
+
+        tracing::debug!(path = %keyword_path.display(), sensitivity, "porcupine wake word initialized");
+
+        let mut detector = Self::new(Vec::new())?;
+        detector.backend = WakeWordBackend::Porcupine;
+        detector.porcupine = Some(engine);
+        Ok(detector)
+    }
+
+    /// Create a detector that spots the wake word offline with Porcupine
+    ///
+    /// # Errors
+    ///
+    /// Always fails: this build does not include the `porcupine` feature
+    #[cfg(not(feature = "porcupine"))]
+    pub fn with_porcupine(keyword_path: impl AsRef<Path>, sensitivity: f32) -> Result<Self> {
+        let _ = (keyword_path.as_ref(), sensitivity);
+        Err(Error::Config(
+            "Porcupine wake word requires building with the `porcupine` feature".to_string(),
+        ))
+    }
+
+    /// Feed 16-bit PCM samples of any length
+    ///
+    /// With the Porcupine backend, samples are regrouped into
+    /// [`PORCUPINE_FRAME_LENGTH`] frames and checked locally; `Detected` is
+    /// returned when the keyword is spotted and the detector is activated.
+    /// With the STT backend this runs speech detection and returns the state.
+    pub fn process_frame(&mut self, samples: &[i16]) -> DetectorState {
+        if self.backend == WakeWordBackend::Stt {
+            let floats: Vec<f32> = samples.iter().map(|&s| f32::from(s) / 32768.0).collect();
+            self.process(&floats);
+            return self.state;
+        }
+
+        if self.state == DetectorState::Activated {
+            return self.state;
+        }
+
+        self.frames.extend(samples);
+        while let Some(frame) = self.frames.next_frame() {
+            if self.spot_keyword(&frame) {
+                tracing::info!("wake word detected (porcupine)");
+                self.frames.clear();
+                self.speech_buffer.clear();
+                self.activate();
+                return DetectorState::Detected;
+            }
+        }
+
+        self.state
+    }
+
+    #[cfg(feature = "porcupine")]
+    fn spot_keyword(&self, frame: &[i16]) -> bool {
+        let Some(engine) = &self.porcupine else {
+            return false;
+        };
+        match engine.process(frame) {
+            Ok(index) => index >= 0,
+            Err(e) => {
+                tracing::warn!(error = %e, "porcupine processing failed");
+                false
+            }
+        }
+    }
+
+    #[cfg(not(feature = "porcupine"))]
+    #[allow(clippy::unused_self)]
+    const fn spot_keyword(&self, _frame: &[i16]) -> bool {
+        false
+    }
+
+    /// Active recognition backend
+    #[must_use]
+    pub const fn backend(&self) -> WakeWordBackend {
+        self.backend
+    }
+
     /// Process audio samples and detect speech activity
     ///
     /// Returns true if speech activity is detected (not wake word yet)
@@ -110,7 +277,7 @@ impl WakeWordDetector {
                     self.reset();
                 }
             }
-            DetectorState::Activated => {
+            DetectorState::Activated | DetectorState::Detected => {
                 // Already activated, accumulating utterance
                 self.speech_buffer.extend_from_slice(samples);
 
@@ -187,6 +354,7 @@ impl WakeWordDetector {
         self.state = DetectorState::Idle;
         self.speech_buffer.clear();
         self.silence_counter = 0;
+        self.frames.clear();
     }
 
     /// Get current state
@@ -210,7 +378,7 @@ impl WakeWordDetector {
 
 /// Calculate RMS energy of audio samples
 #[allow(clippy::cast_precision_loss)]
-fn calculate_energy(samples: &[f32]) -> f32 {
+pub(super) fn calculate_energy(samples: &[f32]) -> f32 {
     if samples.is_empty() {
         return 0.0;
     }
@@ -242,4 +410,40 @@ mod tests {
         assert!(detector.check_wake_word("Hey Orin, what's up?"));
         assert_eq!(detector.state(), DetectorState::Activated);
     }
+
+    #[test]
+    fn test_frame_buffer_regroups_chunks() {
+        let mut frames = FrameBuffer::default();
+
+        // One daemon chunk (1600 samples) yields three full frames
+        frames.extend(&[0; 1600]);
+        let mut count = 0;
+        while frames.next_frame().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 3);
+
+        // The 64-sample remainder carries over into the next chunk
+        frames.extend(&[0; 1600]);
+        let frame = frames.next_frame().unwrap();
+        assert_eq!(frame.len(), PORCUPINE_FRAME_LENGTH);
+        assert_eq!(frames.pending.len(), 1664 - PORCUPINE_FRAME_LENGTH);
+    }
+
+    #[test]
+    fn test_stt_backend_process_frame_detects_speech() {
+        let mut detector = WakeWordDetector::new(vec!["hey orin".to_string()]).unwrap();
+        assert_eq!(detector.backend(), WakeWordBackend::Stt);
+
+        assert_eq!(
+            detector.process_frame(&[16_000; 1600]),
+            DetectorState::Listening
+        );
+    }
+
+    #[cfg(not(feature = "porcupine"))]
+    #[test]
+    fn test_porcupine_requires_feature() {
+        assert!(WakeWordDetector::with_porcupine("hey-orin.ppn", 0.5).is_err());
+    }
 }
@@ -13,9 +13,8 @@ use tower::ServiceExt;
 mod common;
 use common::{create_test_session, create_test_user, setup_test_db};
 
-/// Build a test API router
-fn build_test_router(db: DbPool) -> axum::Router {
-    use axum::Router;
+/// Build the API state shared by test routers
+fn build_test_state(db: DbPool) -> beacon_gateway::api::ApiState {
     use beacon_gateway::db::{MemoryRepo, SessionRepo, SkillRepo, UserRepo};
 
     let tool_policy = Arc::new(ToolPolicy::new(&ToolPolicyConfig::default()));
@@ -28,8 +27,12 @@ fn build_test_router(db: DbPool) -> axum::Router {
     let canvas = Arc::new(Mutex::new(Canvas::new()));
 
     let telegram_group_repo = beacon_gateway::db::TelegramGroupConfigRepo::new(db.clone());
+    let usage_cap = Arc::new(beacon_gateway::UsageCap::new(
+        db.clone(),
+        beacon_gateway::UsageCapConfig::default(),
+    ));
 
-    let state = Arc::new(beacon_gateway::api::ApiState {
+    beacon_gateway::api::ApiState {
         db,
         api_key: Some("test-api-key".to_string()),
         persona_id: "test-persona".to_string(),
@@ -63,15 +66,19 @@ fn build_test_router(db: DbPool) -> axum::Router {
         key_provisioner: None,
         jwt_cache: None,
         local_key_store: None,
-        persona_knowledge: vec![],
+        persona_knowledge: Arc::default(),
         max_context_tokens: 8000,
         knowledge_cache_dir: std::path::PathBuf::from("/tmp/test-knowledge-cache"),
         cloud_mode: false,
+        require_chat_auth: false,
         rate_limiter: None,
         ws_senders: None,
         billing_state: None,
         usage_recorder: None,
         skill_filter: beacon_gateway::skills::SkillFilter::default(),
+        remote_skills: beacon_gateway::skills::RemoteSkillLoader::new(std::path::PathBuf::from(
+            "/tmp/test-remote-skills",
+        )),
         voice_enabled: false,
         skills_config: beacon_gateway::config::SkillsConfig::default(),
         active_persona: Arc::new(RwLock::new(beacon_gateway::api::ActivePersona {
@@ -80,6 +87,8 @@ fn build_test_router(db: DbPool) -> axum::Router {
         })),
         hook_manager: None,
         pairing_manager: None,
+        device_pairing: None,
+        user_api_keys: None,
         attachment_processor: None,
         telegram_dedup: Arc::new(std::sync::Mutex::new(
             beacon_gateway::channels::UpdateDedup::default(),
@@ -92,13 +101,53 @@ fn build_test_router(db: DbPool) -> axum::Router {
         condenser: None,
         reranker: None,
         mcp_manager: None,
-    });
+        outbound_channels: beacon_gateway::channels::OutboundChannels::default(),
+        vortex_webhook_secret: None,
+        vortex_allow_unsigned: false,
+        generic_webhook: None,
+        tool_output: beacon_gateway::tools::ToolOutputConfig::default(),
+        maintenance: Arc::new(beacon_gateway::MaintenanceMode::default()),
+        readiness: Arc::new(beacon_gateway::Readiness::default()),
+        relay_status: None,
+        synapse_breaker: Arc::default(),
+        fetch_cache: Arc::default(),
+        mdns: None,
+        usage_cap,
+        idempotency: beacon_gateway::api::chat::IdempotencyCache::default(),
+    }
+}
+
+/// Build a test API router
+fn build_test_router(db: DbPool) -> axum::Router {
+    use axum::Router;
+
+    let state = Arc::new(build_test_state(db));
 
     Router::new()
         .nest(
             "/api/admin",
             beacon_gateway::api::admin::router(state.clone()),
         )
+        .nest(
+            "/api/chat",
+            beacon_gateway::api::chat::router(state.clone()),
+        )
+        .nest(
+            "/api/knowledge",
+            beacon_gateway::api::knowledge::router(state.clone()),
+        )
+        .nest(
+            "/api/memories",
+            beacon_gateway::api::life_json::router(state.clone()),
+        )
+        .nest(
+            "/api/nodes",
+            beacon_gateway::api::nodes::router(state.node_registry.clone(), state.clone()),
+        )
+        .nest(
+            "/ws",
+            beacon_gateway::api::nodes::ws_router(state.node_registry.clone(), state.clone()),
+        )
         .merge(beacon_gateway::api::health::router())
         .merge(beacon_gateway::api::health::ready_router(state))
 }
@@ -155,6 +204,230 @@ async fn test_ready_endpoint() {
     assert_eq!(json["status"], "ok");
     assert_eq!(json["checks"]["database"]["status"], "ok");
     assert_eq!(json["checks"]["agent"]["status"], "unavailable"); // No agent configured in tests
+    assert!(json["database_pool"]["max_size"].as_u64().unwrap() >= 1);
+    assert!(json["database_pool"]["connections"].is_u64());
+    assert!(json["database_pool"]["idle"].is_u64());
+    assert_eq!(json["synapse_circuit"], "closed");
+}
+
+#[tokio::test]
+async fn test_chat_stream_reports_errors_as_events() {
+    let db = setup_test_db();
+    let app = build_test_router(db);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/chat/stream")
+                .header("content-type", "application/json")
+                .header("Authorization", "Bearer test-api-key")
+                .body(Body::from(
+                    r#"{"message": "hello", "session_id": "web-stream"}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+    // No LLM provider in tests: the turn fails with a terminal error event
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("event:"), "{body}");
+    assert!(body.contains("no_agent"), "{body}");
+}
+
+#[tokio::test]
+async fn test_chat_requires_auth_only_when_configured() {
+    let request = |uri: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"message": "hello", "session_id": "web-1"}"#))
+            .unwrap()
+    };
+
+    // An API key guards admin routes only unless chat auth is opted into
+    let state = Arc::new(build_test_state(setup_test_db()));
+    let response = beacon_gateway::api::chat::router(state)
+        .oneshot(request("/stream"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut state = build_test_state(setup_test_db());
+    state.require_chat_auth = true;
+    let state = Arc::new(state);
+    for uri in ["/", "/stream"] {
+        let response = beacon_gateway::api::chat::router(Arc::clone(&state))
+            .oneshot(request(uri))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn test_device_session_authenticates_chat() {
+    use beacon_gateway::api::pairing::PairingState;
+    use beacon_gateway::security::{DeviceIdentity, DeviceManager, TrustLevel};
+
+    let db = setup_test_db();
+    let devices = DeviceManager::new(db.clone());
+    let device = DeviceIdentity::generate("tablet");
+    devices
+        .register(
+            &device.device_id,
+            &device.public_key,
+            "tablet",
+            None,
+            TrustLevel::Paired,
+        )
+        .unwrap();
+    let (token, _) = devices.create_session(&device.device_id).unwrap();
+    let pending = devices.clone();
+
+    let mut state = build_test_state(db);
+    state.require_chat_auth = true;
+    state.device_pairing = Some(Arc::new(PairingState::new(
+        devices,
+        DeviceIdentity::generate("gateway"),
+    )));
+    let state = Arc::new(state);
+
+    let request = |uri: &str, token: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("Authorization", format!("Bearer {token}"))
+            .body(Body::from(
+                r#"{"message": "hello", "session_id": "web-device"}"#,
+            ))
+            .unwrap()
+    };
+    let chat = || beacon_gateway::api::chat::router(Arc::clone(&state));
+
+    // Sessions count only once an admin approved the pairing
+    let response = chat().oneshot(request("/stream", &token)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    pending.approve(&device.device_id, "api-key").unwrap();
+
+    let response = chat().oneshot(request("/stream", &token)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let unknown = "0".repeat(token.len());
+    let response = chat().oneshot(request("/stream", &unknown)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Paired devices are not admins
+    let response = beacon_gateway::api::admin::router(Arc::clone(&state))
+        .oneshot(
+            Request::builder()
+                .uri("/users")
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+/// Fetch `/ready` for a state using the given readiness tracker
+async fn ready_with(readiness: beacon_gateway::Readiness) -> (StatusCode, serde_json::Value) {
+    let mut state = build_test_state(setup_test_db());
+    state.readiness = Arc::new(readiness);
+    let app = beacon_gateway::api::health::ready_router(Arc::new(state));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/ready")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_ready_fails_when_required_component_missing() {
+    use beacon_gateway::{Readiness, ReadinessComponent, ReadinessConfig};
+
+    // Cloud defaults: the primary channel must have connected
+    let readiness = Readiness::new(&ReadinessConfig::default(), true);
+    readiness.record_channel("telegram", false);
+    let (status, json) = ready_with(readiness).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(json["status"], "degraded");
+    assert_eq!(json["checks"]["channel"]["status"], "fail");
+
+    // Explicitly required components must be present
+    let readiness = Readiness::new(
+        &ReadinessConfig {
+            require: Some(vec![
+                ReadinessComponent::Database,
+                ReadinessComponent::Billing,
+            ]),
+            primary_channel: None,
+        },
+        true,
+    );
+    let (status, json) = ready_with(readiness).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(json["checks"]["billing"]["status"], "unavailable");
+
+    // Not required: a failed channel does not affect readiness
+    let readiness = Readiness::default();
+    readiness.record_channel("telegram", false);
+    let (status, _) = ready_with(readiness).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_personas_lists_embedded() {
+    let db = setup_test_db();
+    let app = build_test_router(db);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/personas")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["active_id"], "test-persona");
+    let personas = json["personas"].as_array().unwrap();
+    for id in ["orin", "microcap"] {
+        let persona = personas
+            .iter()
+            .find(|p| p["id"] == id)
+            .unwrap_or_else(|| panic!("missing embedded persona {id}"));
+        assert_eq!(persona["source"], "embedded");
+        assert_eq!(persona["active"], false);
+    }
 }
 
 #[tokio::test]
@@ -259,3 +532,729 @@ async fn test_admin_session_messages() {
     assert_eq!(json.as_array().unwrap().len(), 1);
     assert_eq!(json[0]["content"], "Hello");
 }
+
+#[tokio::test]
+async fn test_search_messages_endpoint() {
+    let db = setup_test_db();
+
+    let user = create_test_user(&db, "test-external-id");
+    let session = create_test_session(&db, &user.id, "test", "channel-123", "test-persona");
+
+    let session_repo = beacon_gateway::db::SessionRepo::new(db.clone());
+    for content in ["Remind me about the dentist", "Thanks!"] {
+        session_repo
+            .add_message(&session.id, beacon_gateway::db::MessageRole::User, content)
+            .unwrap();
+    }
+
+    let app = build_test_router(db);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(&format!(
+                    "/api/memories/search-messages?user_id={}&q=dentist",
+                    user.id
+                ))
+                .header("Authorization", "Bearer test-api-key")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(json["count"], 1);
+    assert_eq!(
+        json["messages"][0]["content"],
+        "Remind me about the dentist"
+    );
+    assert_eq!(json["messages"][0]["role"], "user");
+}
+
+#[tokio::test]
+async fn test_memories_follow_active_persona() {
+    use axum::Router;
+    use beacon_gateway::db::{MemoryRepo, MemoryScope};
+
+    let db = setup_test_db();
+    let user = create_test_user(&db, "persona-switcher");
+
+    let mut state = build_test_state(db.clone());
+    state.memory_repo = MemoryRepo::new(db).with_scope(MemoryScope::PerUserPersona, "test-persona");
+    let state = Arc::new(state);
+    let app = Router::new().nest(
+        "/api/memories",
+        beacon_gateway::api::life_json::router(state.clone()),
+    );
+
+    let create = |content: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/memories")
+            .header("Authorization", "Bearer test-api-key")
+            .header("Content-Type", "application/json")
+            .body(Body::from(
+                serde_json::json!({ "user_id": user.id, "content": content }).to_string(),
+            ))
+            .unwrap()
+    };
+    let list = || {
+        Request::builder()
+            .uri(&format!("/api/memories?user_id={}", user.id))
+            .header("Authorization", "Bearer test-api-key")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let contents = |body: &[u8]| -> Vec<String> {
+        let json: serde_json::Value = serde_json::from_slice(body).unwrap();
+        json["memories"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["content"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let response = app.clone().oneshot(create("Likes tea")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Switching personas (as the activate endpoint does) starts from a clean slate
+    state.active_persona.write().await.id = "orin".to_string();
+    let response = app.clone().oneshot(list()).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(contents(&body).is_empty());
+
+    let response = app.clone().oneshot(create("Prefers coffee")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Switching back only shows the first persona's memory
+    state.active_persona.write().await.id = "test-persona".to_string();
+    let response = app.oneshot(list()).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(contents(&body), vec!["Likes tea".to_string()]);
+}
+
+#[tokio::test]
+async fn test_admin_maintenance_toggle_reported_by_ready() {
+    let db = setup_test_db();
+    let app = build_test_router(db);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri("/api/admin/maintenance")
+                .header("Authorization", "Bearer test-api-key")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"enabled":true,"message":"Deploying"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["enabled"], true);
+    assert_eq!(json["message"], "Deploying");
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/ready")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // API stays up while in maintenance
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["maintenance"], true);
+}
+
+#[tokio::test]
+async fn test_admin_relay_status_follows_monitor() {
+    let (status_tx, status_rx) = tokio::sync::watch::channel(vec![beacon_gateway::RelayStatus {
+        enabled: true,
+        mode: "ssh".to_string(),
+        url: Some("https://relay.example.com:443".to_string()),
+        connected: true,
+    }]);
+    let mut state = build_test_state(setup_test_db());
+    state.relay_status = Some(status_rx);
+    let app = beacon_gateway::api::admin::router(Arc::new(state));
+
+    let get_status = || {
+        Request::builder()
+            .uri("/relay")
+            .header("Authorization", "Bearer test-api-key")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(get_status()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json[0]["mode"], "ssh");
+    assert_eq!(json[0]["connected"], true);
+
+    // The monitor noticed the relay died
+    status_tx.send_modify(|statuses| {
+        statuses[0].connected = false;
+        statuses[0].url = None;
+    });
+    let response = app.oneshot(get_status()).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json[0]["connected"], false);
+}
+
+#[tokio::test]
+async fn test_nodes_require_auth() {
+    let db = setup_test_db();
+
+    let invoke = r#"{"command": "system.run", "params": {"command": ["id"]}}"#;
+    let invoke_any = r#"{"capability": "shell", "command": "system.run", "params": {}}"#;
+    for (method, uri, body) in [
+        ("GET", "/api/nodes", ""),
+        ("GET", "/api/nodes/registrations", ""),
+        ("POST", "/api/nodes/node-1/invoke", invoke),
+        ("POST", "/api/nodes/invoke-any", invoke_any),
+        ("GET", "/ws/node", ""),
+    ] {
+        let response = build_test_router(db.clone())
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{uri}");
+    }
+
+    let response = build_test_router(db)
+        .oneshot(
+            Request::builder()
+                .uri("/api/nodes")
+                .header("Authorization", "Bearer test-api-key")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_knowledge_preview_requires_embedder() {
+    let db = setup_test_db();
+    let app = build_test_router(db);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/knowledge/preview?q=staking&k=3")
+                .header("Authorization", "Bearer test-api-key")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["error"]["code"], "embedder_unavailable");
+}
+
+#[tokio::test]
+async fn test_admin_usage_limit_override() {
+    let db = setup_test_db();
+    let app = build_test_router(db);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri("/api/admin/users/u1/usage-limit")
+                .header("Authorization", "Bearer test-api-key")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"daily_tokens":500}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["has_override"], true);
+    assert_eq!(json["daily_tokens"], 500);
+    assert_eq!(json["tokens_today"], 0);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri("/api/admin/users/u1/usage-limit")
+                .header("Authorization", "Bearer test-api-key")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/admin/users/u1/usage")
+                .header("Authorization", "Bearer test-api-key")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["has_override"], false);
+}
+
+#[tokio::test]
+async fn test_admin_sync_conflicts_listed_until_resolved() {
+    let db = setup_test_db();
+    beacon_gateway::db::SyncStateRepo::new(db.clone())
+        .replace_conflicts(
+            "m1",
+            &[beacon_gateway::sync::MergeConflict {
+                memory_id: "m1".to_string(),
+                field: "content".to_string(),
+                local: "likes tea".to_string(),
+                remote: "likes coffee".to_string(),
+            }],
+        )
+        .unwrap();
+    let app = build_test_router(db);
+
+    let list = || {
+        Request::builder()
+            .uri("/api/admin/sync/conflicts")
+            .header("Authorization", "Bearer test-api-key")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.clone().oneshot(list()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json[0]["memory_id"], "m1");
+    assert_eq!(json[0]["remote"], "likes coffee");
+
+    let resolve = || {
+        Request::builder()
+            .method("DELETE")
+            .uri("/api/admin/sync/conflicts/m1")
+            .header("Authorization", "Bearer test-api-key")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.clone().oneshot(resolve()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = app.clone().oneshot(resolve()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app.oneshot(list()).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"[]");
+}
+
+/// Channel adapter that records outbound sends
+struct RecordingChannel {
+    sent: Arc<Mutex<Vec<beacon_gateway::channels::OutgoingMessage>>>,
+}
+
+#[async_trait::async_trait]
+impl beacon_gateway::channels::Channel for RecordingChannel {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn connect(&mut self) -> beacon_gateway::Result<()> {
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> beacon_gateway::Result<()> {
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        message: beacon_gateway::channels::OutgoingMessage,
+    ) -> beacon_gateway::Result<()> {
+        self.sent.lock().await.push(message);
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+}
+
+/// Build a webhooks router with a recording Discord adapter and a Vortex secret
+fn build_vortex_router(
+    db: DbPool,
+) -> (
+    axum::Router,
+    Arc<Mutex<Vec<beacon_gateway::channels::OutgoingMessage>>>,
+) {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let mut state = build_test_state(db);
+    state.outbound_channels.insert(Arc::new(RecordingChannel {
+        sent: Arc::clone(&sent),
+    }));
+    state.vortex_webhook_secret = Some("s3cret".to_string());
+
+    let router = axum::Router::new().nest(
+        "/api/webhooks",
+        beacon_gateway::api::webhooks::router(Arc::new(state)),
+    );
+    (router, sent)
+}
+
+fn remind_callback(channel: &str) -> String {
+    serde_json::json!({
+        "schedule_id": "sched_1",
+        "action": "remind",
+        "payload": {
+            "user_id": "u1",
+            "message": "Stand up",
+            "channel": channel,
+            "channel_id": "chan-42",
+            "thread_id": "thread-7"
+        },
+        "fired_at": "2024-01-08T09:00:00Z"
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn test_vortex_remind_delivers_to_stored_channel() {
+    let db = setup_test_db();
+    let (app, sent) = build_vortex_router(db);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/webhooks/vortex")
+                .header("Content-Type", "application/json")
+                .header("X-Vortex-Secret", "s3cret")
+                .body(Body::from(remind_callback("discord")))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let sent = sent.lock().await;
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].channel_id, "chan-42");
+    assert_eq!(sent[0].thread_id.as_deref(), Some("thread-7"));
+    assert_eq!(sent[0].content, "Stand up");
+}
+
+#[tokio::test]
+async fn test_vortex_remind_queues_outbox_when_channel_unavailable() {
+    let db = setup_test_db();
+    let (app, sent) = build_vortex_router(db.clone());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/webhooks/vortex")
+                .header("Content-Type", "application/json")
+                .header("X-Vortex-Secret", "s3cret")
+                .body(Body::from(remind_callback("slack")))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(sent.lock().await.is_empty());
+
+    let pending = beacon_gateway::db::OutboxRepo::new(db)
+        .pending("u1")
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].channel, "slack");
+    assert_eq!(pending[0].content, "Stand up");
+}
+
+#[tokio::test]
+async fn test_outbox_retry_delivers_through_original_channel() {
+    let db = setup_test_db();
+    let outbox = beacon_gateway::db::OutboxRepo::new(db.clone());
+    outbox
+        .enqueue("u1", "discord", "chan-42", Some("thread-7"), "Stand up")
+        .unwrap();
+    outbox
+        .enqueue("u1", "slack", "C1", None, "Still queued")
+        .unwrap();
+
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let mut state = build_test_state(db);
+    state.outbound_channels.insert(Arc::new(RecordingChannel {
+        sent: Arc::clone(&sent),
+    }));
+
+    let delivered = beacon_gateway::api::webhooks::vortex::retry_outbox(&state).await;
+    assert_eq!(delivered, 1);
+
+    let sent = sent.lock().await;
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].channel_id, "chan-42");
+    assert_eq!(sent[0].thread_id.as_deref(), Some("thread-7"));
+    assert_eq!(sent[0].content, "Stand up");
+
+    // Channels without an adapter stay queued for a later pass
+    let pending = outbox.pending("u1").unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].channel, "slack");
+}
+
+#[tokio::test]
+async fn test_vortex_rejects_bad_secret() {
+    let db = setup_test_db();
+    let (app, sent) = build_vortex_router(db);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/webhooks/vortex")
+                .header("Content-Type", "application/json")
+                .header("X-Vortex-Secret", "wrong")
+                .body(Body::from(remind_callback("discord")))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(sent.lock().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_vortex_without_secret_fails_closed() {
+    let db = setup_test_db();
+    let state = Arc::new(build_test_state(db.clone()));
+    let app = axum::Router::new().nest(
+        "/api/webhooks",
+        beacon_gateway::api::webhooks::router(state),
+    );
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/webhooks/vortex")
+                .header("Content-Type", "application/json")
+                .body(Body::from(remind_callback("discord")))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Only an explicit opt-out accepts unsigned callbacks
+    let mut state = build_test_state(db);
+    state.vortex_allow_unsigned = true;
+    let app = axum::Router::new().nest(
+        "/api/webhooks",
+        beacon_gateway::api::webhooks::router(Arc::new(state)),
+    );
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/webhooks/vortex")
+                .header("Content-Type", "application/json")
+                .body(Body::from(remind_callback("ws_push")))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_request_id_echoed_in_error_responses() {
+    let app = axum::Router::new()
+        .route(
+            "/missing",
+            axum::routing::get(|| async {
+                Err::<(), _>(beacon_gateway::Error::NotFound("widget".to_string()))
+            }),
+        )
+        .layer(axum::middleware::from_fn(
+            beacon_gateway::api::request_id::request_id_middleware,
+        ));
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/missing")
+                .header("X-Request-Id", "turn-42")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.headers()["x-request-id"], "turn-42");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["error"]["code"], "not_found");
+    assert_eq!(json["error"]["request_id"], "turn-42");
+
+    // Requests without a usable id get a generated one
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/missing")
+                .header("X-Request-Id", "has spaces")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let generated = response.headers()["x-request-id"].to_str().unwrap();
+    assert_eq!(generated.len(), 36);
+}
+
+#[tokio::test]
+async fn test_local_persona_authoring() {
+    let cache = tempfile::tempdir().unwrap();
+    let mut state = build_test_state(setup_test_db());
+    state.persona_cache_dir = cache.path().to_path_buf();
+    let app = beacon_gateway::api::personas::local_router(Arc::new(state));
+
+    let persona = serde_json::json!({
+        "version": "1.0.0",
+        "identity": { "id": "juniper", "name": "Juniper" },
+        "voice": { "wakeWords": ["hey juniper"], "tts": { "speed": 1.2 } }
+    });
+    let request = |method: &str, uri: &str, body: &serde_json::Value, auth: bool| {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json");
+        if auth {
+            builder = builder.header("Authorization", "Bearer test-api-key");
+        }
+        builder.body(Body::from(body.to_string())).unwrap()
+    };
+
+    // Writes need the admin key
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/api/personas", &persona, false))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/api/personas", &persona, true))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert!(cache.path().join("juniper.json").exists());
+
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/api/personas", &persona, true))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Every validation problem is reported at once
+    let mut invalid = persona.clone();
+    invalid["identity"]["name"] = "".into();
+    invalid["voice"]["tts"]["speed"] = 50.into();
+    let response = app
+        .clone()
+        .oneshot(request("PUT", "/api/personas/juniper", &invalid, true))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["details"].as_array().unwrap().len(), 2);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/personas/local")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["personas"][0]["id"], "juniper");
+    assert_eq!(json["personas"][0]["source"]["type"], "local");
+}
@@ -9,7 +9,8 @@ use beacon_gateway::{
     ToolPolicy, ToolPolicyConfig, ToolProfile,
     channels::{
         BotCommand, Channel, ChannelCapability, ChannelRegistry, IncomingMessage, OutgoingMessage,
-        TelegramChannel, TelegramRateLimiter, UpdateDedup, should_skip_group_message,
+        StreamingConfig, TelegramChannel, TelegramRateLimiter, UpdateDedup,
+        should_skip_group_message,
     },
     config::{ReactionLevel, StreamingMode, TelegramConfig},
     db::{Memory, MemoryCategory, MemoryRepo, MessageRole, SessionRepo, UserRepo},
@@ -94,6 +95,7 @@ async fn test_mock_channel_send() {
         media: vec![],
         edit_target: None,
         voice_note: false,
+        attachments: vec![],
     };
 
     channel.send(message.clone()).await.unwrap();
@@ -103,6 +105,70 @@ async fn test_mock_channel_send() {
     assert_eq!(sent[0].content, "Hello, world!");
 }
 
+#[tokio::test]
+async fn test_default_send_media_posts_attachment_links() {
+    use beacon_gateway::channels::Attachment;
+
+    let mut channel = MockChannel::new("test");
+    channel.connect().await.unwrap();
+
+    let message = OutgoingMessage::text("channel-123".to_string(), "Here it is".to_string())
+        .with_attachments(vec![
+            Attachment::from_url(
+                "https://example.com/a.png".to_string(),
+                "image/png".to_string(),
+                None,
+            ),
+            // Inline data has no link and is dropped
+            Attachment::from_data(vec![1, 2, 3], "image/png".to_string(), None),
+        ]);
+
+    channel.send_media(message).await.unwrap();
+
+    let sent = channel.get_sent_messages().await;
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].content, "Here it is\n\nhttps://example.com/a.png");
+    assert!(sent[0].attachments.is_empty());
+}
+
+#[tokio::test]
+async fn test_maintenance_mode_short_circuits_handler() {
+    use beacon_gateway::MaintenanceMode;
+
+    let channel = MockChannel::new("test");
+    let msg = IncomingMessage {
+        id: "msg-1".to_string(),
+        channel_id: "channel-123".to_string(),
+        sender_id: "user:abc".to_string(),
+        sender_name: "Test User".to_string(),
+        content: "Hello?".to_string(),
+        is_dm: true,
+        reply_to: None,
+        attachments: vec![],
+        thread_id: None,
+        callback_data: None,
+        request_id: beacon_gateway::request_id::generate(),
+    };
+
+    let maintenance = MaintenanceMode::default();
+    assert!(!maintenance.intercept(&channel, &msg).await);
+    assert!(channel.get_sent_messages().await.is_empty());
+
+    // Enabled: reply with the notice and skip the agent
+    maintenance.set_enabled(true);
+    maintenance.set_message(Some("Back soon".to_string()));
+    assert!(maintenance.intercept(&channel, &msg).await);
+    let sent = channel.get_sent_messages().await;
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].content, "Back soon");
+    assert_eq!(sent[0].reply_to.as_deref(), Some("msg-1"));
+
+    // Silent: skip the agent without replying
+    maintenance.set_message(None);
+    assert!(maintenance.intercept(&channel, &msg).await);
+    assert_eq!(channel.get_sent_messages().await.len(), 1);
+}
+
 #[tokio::test]
 async fn test_session_persistence() {
     let db = setup_test_db();
@@ -268,6 +334,7 @@ async fn test_incoming_message_structure() {
         attachments: vec![],
         thread_id: None,
         callback_data: None,
+        request_id: beacon_gateway::request_id::generate(),
     };
 
     assert!(msg.is_dm);
@@ -291,6 +358,46 @@ async fn test_channel_registry() {
     registry.disconnect_all().await;
 }
 
+#[tokio::test]
+async fn test_rate_limited_channel_queues_over_burst() {
+    use beacon_gateway::channels::{RateLimitPolicy, RateLimitedChannel};
+    use std::time::{Duration, Instant};
+
+    let mock = MockChannel::new("mock");
+    let sent = Arc::clone(&mock.sent_messages);
+    let channel = RateLimitedChannel::new(Box::new(mock), RateLimitPolicy::new(20.0, 1));
+
+    let start = Instant::now();
+    for text in ["one", "two"] {
+        channel
+            .send(OutgoingMessage::text("c1".to_string(), text.to_string()))
+            .await
+            .unwrap();
+    }
+
+    // Second send waited for a refill instead of failing
+    assert!(start.elapsed() >= Duration::from_millis(40));
+    assert_eq!(sent.lock().await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_rate_limited_channel_forwards_lifecycle() {
+    use beacon_gateway::channels::{RateLimitPolicy, RateLimitedChannel};
+
+    // The wrapper `ChannelRegistry::register_with_policy` installs
+    let mut channel = RateLimitedChannel::new(
+        Box::new(MockChannel::new("mock")),
+        RateLimitPolicy::discord(),
+    );
+    assert_eq!(channel.name(), "mock");
+
+    channel.connect().await.unwrap();
+    assert!(channel.is_connected());
+
+    channel.disconnect().await.unwrap();
+    assert!(!channel.is_connected());
+}
+
 #[tokio::test]
 async fn test_user_life_json_path() {
     let db = setup_test_db();
@@ -430,6 +537,7 @@ fn incoming_message_has_thread_and_callback() {
         attachments: vec![],
         thread_id: Some("t1".into()),
         callback_data: None,
+        request_id: beacon_gateway::request_id::generate(),
     };
     assert_eq!(msg.thread_id.as_deref(), Some("t1"));
 }
@@ -451,6 +559,7 @@ fn telegram_has_expected_capabilities() {
     assert!(caps.contains(&ChannelCapability::Streaming));
     assert!(caps.contains(&ChannelCapability::Reactions));
     assert!(caps.contains(&ChannelCapability::ForumTopics));
+    assert!(caps.contains(&ChannelCapability::MediaSend));
 }
 
 #[test]
@@ -459,6 +568,7 @@ fn outgoing_message_has_new_fields() {
     assert!(!msg.voice_note);
     assert!(msg.keyboard.is_none());
     assert!(msg.media.is_empty());
+    assert!(msg.attachments.is_empty());
     assert!(msg.edit_target.is_none());
     assert!(msg.thread_id.is_none());
 }
@@ -490,6 +600,27 @@ async fn telegram_rate_limiter_allows_different_chats() {
     assert!(limiter.check("chat_456"));
 }
 
+#[tokio::test]
+async fn telegram_rate_limiter_applies_configured_interval() {
+    use std::time::Duration;
+
+    let config = StreamingConfig {
+        edit_interval_ms: 50,
+        ..StreamingConfig::default()
+    };
+    let limiter = TelegramRateLimiter::from_config(&config);
+    assert_eq!(limiter.interval(), Duration::from_millis(50));
+
+    assert!(limiter.check("chat_123"));
+    assert!(!limiter.check("chat_123"));
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(limiter.check("chat_123"));
+
+    let channel = TelegramChannel::new("test-token".to_string()).with_streaming(config);
+    assert_eq!(channel.streaming(), config);
+}
+
 // --- Dedup tests ---
 
 #[test]
@@ -587,6 +718,7 @@ fn incoming_message_thread_from_polling() {
         attachments: vec![],
         thread_id: Some("42".into()),
         callback_data: None,
+        request_id: beacon_gateway::request_id::generate(),
     };
     assert_eq!(msg.thread_id.as_deref(), Some("42"));
 }
@@ -605,6 +737,7 @@ fn make_group_msg(content: &str) -> IncomingMessage {
         attachments: vec![],
         thread_id: None,
         callback_data: None,
+        request_id: beacon_gateway::request_id::generate(),
     }
 }
 
@@ -2,7 +2,10 @@
 //!
 //! Tests voice components without requiring audio hardware
 
-use beacon_gateway::voice::{DetectorState, SAMPLE_RATE, WakeWordDetector, samples_to_wav};
+use beacon_gateway::voice::{
+    DetectorState, MAX_QUEUED_TURNS, OverlapPolicy, SAMPLE_RATE, TurnAdmission, VoiceTurnGuard,
+    WakeWordDetector, samples_to_wav,
+};
 use std::io::Cursor;
 
 mod common;
@@ -256,3 +259,47 @@ fn test_context_builder_formatting() {
     // Should contain current message
     assert!(prompt.contains("What's the weather?"));
 }
+
+#[test]
+fn test_turn_guard_drops_overlapping_triggers() {
+    let guard = VoiceTurnGuard::new(OverlapPolicy::Drop);
+
+    assert_eq!(
+        guard.admit("what time is it".to_string()),
+        TurnAdmission::Start("what time is it".to_string())
+    );
+    assert!(guard.is_active());
+    assert_eq!(
+        guard.admit("what's the weather".to_string()),
+        TurnAdmission::Dropped
+    );
+
+    assert_eq!(guard.finish(), None);
+    assert!(!guard.is_active());
+    assert!(matches!(
+        guard.admit("again".to_string()),
+        TurnAdmission::Start(_)
+    ));
+}
+
+#[test]
+fn test_turn_guard_queues_overlapping_triggers() {
+    let guard = VoiceTurnGuard::new(OverlapPolicy::Queue);
+
+    assert!(matches!(
+        guard.admit("first".to_string()),
+        TurnAdmission::Start(_)
+    ));
+    for i in 0..MAX_QUEUED_TURNS {
+        assert_eq!(guard.admit(format!("queued {i}")), TurnAdmission::Queued);
+    }
+    assert_eq!(guard.admit("overflow".to_string()), TurnAdmission::Dropped);
+
+    // Queued commands run in order, keeping the guard active until drained
+    assert_eq!(guard.finish().as_deref(), Some("queued 0"));
+    assert!(guard.is_active());
+    assert_eq!(guard.finish().as_deref(), Some("queued 1"));
+    assert_eq!(guard.finish().as_deref(), Some("queued 2"));
+    assert_eq!(guard.finish(), None);
+    assert!(!guard.is_active());
+}